@@ -0,0 +1,319 @@
+// Generated by `cargo xtask ts-types` from the OpenAPI schemas of the `shared` crate.
+// Do not edit by hand.
+
+export interface ApiResponse<T> {
+  data?: T | null;
+  error?: string | null;
+  success: boolean;
+}
+
+export interface EmptyApiResponse {
+  error?: string | null;
+  success: boolean;
+}
+
+export interface HeadpatResponse {
+  message: string;
+}
+
+/** One page of a listing. `total` counts every match, not just `items`. */
+export interface Page<T> {
+  items: T[];
+  limit: number;
+  offset: number;
+  total: number;
+}
+
+export type SortOrder = "asc" | "desc";
+
+export type StaffStatus = "ACTIVE" | "INACTIVE";
+
+/** Working shifts a staff member can be scheduled on. Days off are always allowed. */
+export type ShiftEligibility = "ANY" | "MORNING_ONLY" | "EVENING_ONLY";
+
+export interface Staff {
+  created_at: string;
+  /**
+   * Last day the staff member works, e.g. a notice period's end. They aren't scheduled
+   * after it and are set `Inactive` once it has passed.
+   */
+  effective_until?: string | null;
+  email: string;
+  /**
+   * Seniority / FTE weight used by the scheduler's fairness preferences, 1.0 is the
+   * baseline.
+   */
+  fairness_weight: number;
+  id: string;
+  name: string;
+  position: string;
+  /** Shifts the staff member can be given at all, e.g. because of a medical restriction */
+  shift_eligibility?: ShiftEligibility;
+  status: StaffStatus;
+  updated_at: string;
+  /** Bumped on every update, and sent as the ETag of single-staff responses */
+  version?: number;
+}
+
+/** A staff member as listed among a group's members. */
+export type GroupMember = Staff & Record<string, unknown>;
+
+/** Resolved members of a group sharing a position. */
+export interface PositionBucket {
+  count: number;
+  /** Ordered by name then id, empty with `count_only` */
+  members: Staff[];
+  position: string;
+}
+
+/** Display details of a staff member, for embedding in other services' responses. */
+export interface StaffSummary {
+  name: string;
+  position: string;
+}
+
+export interface StaffGroup {
+  created_at: string;
+  id: string;
+  name: string;
+  parent_group_id?: string | null;
+  updated_at: string;
+  /** Bumped on every update, and sent as the ETag of single-group responses */
+  version?: number;
+}
+
+export interface GroupMembership {
+  group_id: string;
+  staff_id: string;
+  /** First day of the membership, `None` when it applied from the start */
+  valid_from?: string | null;
+}
+
+/**
+ * A date range in which at most `max_off_percent` of a group's staff may have a day off on
+ * any single day, e.g. around public holidays.
+ */
+export interface GroupBlackout {
+  created_at: string;
+  /** Last day of the window, inclusive */
+  end_date: string;
+  group_id: string;
+  id: string;
+  max_off_percent: number;
+  reason?: string | null;
+  /** First day of the window, inclusive */
+  start_date: string;
+}
+
+export type TimeOffStatus = "PENDING" | "APPROVED" | "REJECTED";
+
+/**
+ * Days a staff member asked to have off. Approved requests are days off in every schedule
+ * generated for them.
+ */
+export interface TimeOffRequest {
+  created_at: string;
+  /** Last day off, inclusive */
+  end_date: string;
+  id: string;
+  reason?: string | null;
+  staff_id: string;
+  /** First day off, inclusive */
+  start_date: string;
+  status: TimeOffStatus;
+  updated_at: string;
+}
+
+export type JobStatus = "PENDING" | "PROCESSING" | "COMPLETED" | "FAILED";
+
+/** Ordered as a day runs, with `DayOff` last. */
+export type ShiftType = "MORNING" | "EVENING" | "NIGHT" | "DAY_OFF";
+
+export interface ScheduleJob {
+  created_at: string;
+  /** Jobs that must complete before this one is started. */
+  depends_on: string[];
+  /** Why the job failed, while it is `FAILED`. */
+  error_message?: string | null;
+  id: string;
+  /** Staff needed on EVENING every day, set together with `min_morning`. */
+  min_evening?: number | null;
+  /** Staff needed on MORNING every day, when the job asked for its own coverage. */
+  min_morning?: number | null;
+  period_begin_date: string;
+  /** Consecutive 28-day periods generated from `period_begin_date`, 1 for a single period. */
+  periods: number;
+  /** SHA-256 of the assignment set, set when the job completes. */
+  result_checksum?: string | null;
+  /**
+   * Test run: kept out of calendars, period planning and the failed-job list, can't be
+   * shared, sends no notifications and is purged after `sandbox_retention_hours`.
+   */
+  sandbox: boolean;
+  staff_group_id: string;
+  status: JobStatus;
+  updated_at: string;
+  /** Bumped on every update, e.g. each status change. */
+  version?: number;
+}
+
+export interface ShiftAssignment {
+  date: string;
+  id: string;
+  job_id: string;
+  note?: string | null;
+  shift_type: ShiftType;
+  staff_id: string;
+}
+
+/** Period-level note on a schedule, such as a handover note for the group. */
+export interface ScheduleNote {
+  author: string;
+  /** Markdown, stored and returned as written */
+  body: string;
+  created_at: string;
+  id: string;
+  job_id: string;
+}
+
+/** How evenly a schedule shares out the less popular days. */
+export interface ScheduleMetrics {
+  /** Staff with a Morning:Evening target, and how close they came to it */
+  shift_targets?: Record<string, ShiftTargetDeviation>;
+  /** Saturdays and Sundays off per staff member */
+  weekend_days_off: Record<string, number>;
+  /** The weighting formula the generator applies to weekend days off */
+  weekend_weighting: string;
+}
+
+/** A staff member's working shifts against their Morning:Evening target. */
+export interface ShiftTargetDeviation {
+  /** Actual MORNING share minus the target, 0 without working shifts */
+  deviation: number;
+  evening_shifts: number;
+  morning_shifts: number;
+  /** Share of working shifts meant to be MORNING, 0 to 1 */
+  target_morning_share: number;
+}
+
+/** One 28-day section of a schedule covering several periods. */
+export interface SchedulePeriod {
+  /** Number of assignments dated within the period */
+  assignments: number;
+  /** Metrics over this period alone */
+  metrics: ScheduleMetrics;
+  period_begin_date: string;
+  /** Last day of the period, inclusive */
+  period_end_date: string;
+}
+
+/**
+ * A staff member from the float pool group who was scheduled with a group that couldn't
+ * meet its coverage with its own staff.
+ */
+export interface BorrowedStaff {
+  pool_group_id: string;
+  staff_id: string;
+}
+
+/**
+ * A shift short of a coverage minimum on one day, in a schedule generated after the
+ * minimums couldn't all be met.
+ */
+export interface CoverageShortfall {
+  assigned: number;
+  date: string;
+  /** The position with the minimum, `None` for the group-wide shift coverage */
+  position?: string | null;
+  required: number;
+  shift_type: ShiftType;
+  /** The missing staff are listed in `ScheduleResult::unfilled` as well */
+  unfilled: boolean;
+}
+
+export interface UnfilledShift {
+  date: string;
+  id: string;
+  job_id: string;
+  /** Set when the slot needs a particular position */
+  position?: string | null;
+  shift_type: ShiftType;
+}
+
+/**
+ * A placeholder for one person a shift is missing, to be filled by hand (e.g. with agency
+ * staff). Stored apart from the assignments, since nobody is assigned yet.
+ * The assignments of one staff member or one date.
+ */
+export interface AssignmentGroup {
+  assignments: ShiftAssignment[];
+  /** Set when grouped by date */
+  date?: string | null;
+  /** Set when grouped by staff */
+  staff_id?: string | null;
+}
+
+export interface ScheduleResult {
+  /**
+   * The assignments split by staff member or date, only present with `?group_by=`;
+   * `assignments` is empty then.
+   */
+  assignment_groups?: AssignmentGroup[];
+  assignments: ShiftAssignment[];
+  /**
+   * Staff borrowed from the float pool; their assignments are in `assignments` like
+   * everyone else's.
+   */
+  borrowed_staff?: BorrowedStaff[];
+  /**
+   * SHA-256 (hex) of the assignment set, see `checksum` in the scheduling-service.
+   * `None` for results generated before checksums were recorded.
+   */
+  checksum?: string | null;
+  /** Coverage minimums the schedule falls short of, by date */
+  coverage_shortfalls?: CoverageShortfall[];
+  metrics?: ScheduleMetrics;
+  /** Schedule notes, oldest first. */
+  notes?: ScheduleNote[];
+  period_begin_date: string;
+  /** Per-period sections, oldest first, when the schedule covers more than one period */
+  periods?: SchedulePeriod[];
+  schedule_id: string;
+  /** Layout of this payload, see `RESULT_SCHEMA_VERSION` */
+  schema_version: number;
+  /** Staff details keyed by staff id, only present with `?include=staff`. */
+  staff?: Record<string, StaffSummary> | null;
+  staff_group_id: string;
+  /** One placeholder per missing person, by date, when shortfalls are left unfilled */
+  unfilled?: UnfilledShift[];
+}
+
+export interface StartupCheck {
+  /** What was found, or why the check failed */
+  detail: string;
+  latency_ms: number;
+  name: string;
+  passed: boolean;
+}
+
+export interface StartupReport {
+  /** Number of runs so far, 0 before the first one finished */
+  attempt: number;
+  checked_at?: string | null;
+  checks: StartupCheck[];
+  passed: boolean;
+}
+
+export interface DependencyStatus {
+  /** What answered, or why the dependency counts as down */
+  detail: string;
+  healthy: boolean;
+  latency_ms: number;
+  name: string;
+}
+
+export interface DependencyReport {
+  dependencies: DependencyStatus[];
+  /** True when every dependency answered */
+  ready: boolean;
+}
@@ -0,0 +1,18 @@
+-- Duplicate detection compares names with whitespace and case folded, and emails by trigram
+-- similarity.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_staff_normalized_name ON staff(lower(regexp_replace(btrim(name), '\s+', ' ', 'g')));
+
+CREATE INDEX idx_staff_email_trgm ON staff USING gin (lower(email) gin_trgm_ops);
+
+-- Staff merged into another record. The merged one stays behind as INACTIVE and is no longer
+-- reported as a duplicate.
+CREATE TABLE staff_merges(
+    merged_id uuid CONSTRAINT pk_staff_merges PRIMARY KEY CONSTRAINT fk_sm_merged REFERENCES staff(id) ON DELETE CASCADE,
+    staff_id uuid NOT NULL CONSTRAINT fk_sm_staff REFERENCES staff(id) ON DELETE CASCADE,
+    memberships_moved bigint NOT NULL,
+    merged_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_sm_staff ON staff_merges(staff_id);
@@ -0,0 +1,20 @@
+CREATE TYPE time_off_status AS ENUM(
+    'PENDING',
+    'APPROVED',
+    'REJECTED'
+);
+
+-- Days off staff asked for. Approved ones are forced days off when schedules are generated.
+CREATE TABLE time_off_requests(
+    id uuid CONSTRAINT pk_time_off_requests PRIMARY KEY DEFAULT gen_random_uuid(),
+    staff_id uuid NOT NULL CONSTRAINT fk_tor_staff REFERENCES staff(id) ON DELETE CASCADE,
+    start_date date NOT NULL,
+    end_date date NOT NULL,
+    reason varchar(255),
+    status time_off_status NOT NULL DEFAULT 'PENDING',
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT ck_tor_dates CHECK (end_date >= start_date)
+);
+
+CREATE INDEX idx_tor_staff_dates ON time_off_requests(staff_id, start_date, end_date);
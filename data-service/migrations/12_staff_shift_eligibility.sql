@@ -0,0 +1,9 @@
+CREATE TYPE shift_eligibility AS ENUM(
+    'ANY',
+    'MORNING_ONLY',
+    'EVENING_ONLY'
+);
+
+-- Shifts a staff member can be given at all; the scheduler never assigns the other one.
+ALTER TABLE staff
+    ADD COLUMN shift_eligibility shift_eligibility NOT NULL DEFAULT 'ANY';
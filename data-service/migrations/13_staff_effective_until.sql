@@ -0,0 +1,7 @@
+-- Last working day of staff who are leaving. Once it has passed a background task sets them
+-- INACTIVE; the partial index keeps that scan to the few rows it concerns.
+ALTER TABLE staff
+    ADD COLUMN effective_until date;
+
+CREATE INDEX idx_staff_effective_until ON staff(effective_until)
+WHERE status = 'ACTIVE' AND effective_until IS NOT NULL;
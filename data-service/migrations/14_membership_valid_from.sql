@@ -0,0 +1,3 @@
+-- First day of a membership added ahead of time, e.g. for a confirmed new hire. NULL
+-- memberships apply from the moment they are added.
+ALTER TABLE group_memberships ADD COLUMN valid_from date;
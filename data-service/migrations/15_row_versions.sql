@@ -0,0 +1,20 @@
+-- Row version, bumped by a trigger on every update so no write path can forget it. Unlike
+-- updated_at it changes even when two updates land within the same clock tick, so clients
+-- can use it for If-Match and caches to tell a stale copy apart.
+ALTER TABLE staff ADD COLUMN version integer NOT NULL DEFAULT 1;
+ALTER TABLE staff_groups ADD COLUMN version integer NOT NULL DEFAULT 1;
+
+CREATE FUNCTION bump_version() RETURNS trigger AS $$
+BEGIN
+    NEW.version := OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_staff_version
+    BEFORE UPDATE ON staff
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+
+CREATE TRIGGER trg_staff_groups_version
+    BEFORE UPDATE ON staff_groups
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
@@ -0,0 +1,28 @@
+-- Who changed which staff, group or membership, and how. Written after each change by the
+-- audited repositories, with the record as the API returned it before and after.
+CREATE TYPE audit_entity AS ENUM(
+    'STAFF',
+    'GROUP',
+    'MEMBERSHIP'
+);
+
+CREATE TYPE audit_action AS ENUM(
+    'CREATE',
+    'UPDATE',
+    'DELETE',
+    'MERGE'
+);
+
+CREATE TABLE audit_log(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_audit_log PRIMARY KEY,
+    entity_type audit_entity NOT NULL,
+    entity_id uuid NOT NULL,
+    action audit_action NOT NULL,
+    actor varchar(255) NOT NULL,
+    before jsonb,
+    after jsonb,
+    changed_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_al_entity ON audit_log(entity_type, entity_id, changed_at);
+CREATE INDEX idx_al_changed_at ON audit_log(changed_at);
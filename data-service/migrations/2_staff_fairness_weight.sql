@@ -0,0 +1,4 @@
+-- Seniority / FTE weight used by the scheduler's fairness preferences, 1.0 is the baseline.
+ALTER TABLE staff
+    ADD COLUMN fairness_weight double precision NOT NULL DEFAULT 1.0
+        CONSTRAINT ck_staff_fairness_weight CHECK (fairness_weight > 0);
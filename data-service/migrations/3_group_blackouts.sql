@@ -0,0 +1,14 @@
+-- Date ranges in which only a share of a group's staff may have a day off on any single day.
+CREATE TABLE group_blackouts(
+    id uuid CONSTRAINT pk_group_blackouts PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id uuid NOT NULL CONSTRAINT fk_gb_group REFERENCES staff_groups(id) ON DELETE CASCADE,
+    start_date date NOT NULL,
+    end_date date NOT NULL,
+    max_off_percent smallint NOT NULL
+        CONSTRAINT ck_gb_max_off_percent CHECK (max_off_percent BETWEEN 0 AND 100),
+    reason varchar(255),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT ck_gb_dates CHECK (end_date >= start_date)
+);
+
+CREATE INDEX idx_gb_group_dates ON group_blackouts(group_id, start_date, end_date);
@@ -0,0 +1,11 @@
+-- Group names are unique among siblings, ignoring case. Top-level groups count as siblings
+-- of each other. Existing duplicates get a numeric suffix so the index can be built.
+UPDATE staff_groups g
+SET name = g.name || ' (' || d.rn || ')', updated_at = now()
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY parent_group_id, lower(name) ORDER BY created_at, id) AS rn
+    FROM staff_groups
+) d
+WHERE g.id = d.id AND d.rn > 1;
+
+CREATE UNIQUE INDEX uq_staff_groups_parent_name ON staff_groups(parent_group_id, lower(name)) NULLS NOT DISTINCT;
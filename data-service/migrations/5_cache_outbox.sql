@@ -0,0 +1,8 @@
+-- Cache invalidations recorded in the same transaction as the write they belong to, so a
+-- crash between the commit and the Redis delete can't leave stale lists behind.
+-- The relay applies each entry and then removes it.
+CREATE TABLE cache_outbox(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_cache_outbox PRIMARY KEY,
+    topic varchar(32) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
@@ -0,0 +1,26 @@
+-- External receivers of staff lifecycle events. The secret signs every delivery, so it is
+-- kept as given and never returned by the API.
+CREATE TABLE webhook_subscriptions(
+    id uuid CONSTRAINT pk_webhook_subscriptions PRIMARY KEY DEFAULT gen_random_uuid(),
+    url varchar(2048) NOT NULL,
+    secret varchar(255) NOT NULL,
+    event_types text[] NOT NULL,
+    max_retries smallint NOT NULL DEFAULT 5
+        CONSTRAINT ck_ws_max_retries CHECK (max_retries BETWEEN 0 AND 10),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- One row per event and subscription, written in the same transaction as the staff change.
+-- The dispatcher removes a row once it is delivered or out of retries.
+CREATE TABLE webhook_deliveries(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_webhook_deliveries PRIMARY KEY,
+    subscription_id uuid NOT NULL
+        CONSTRAINT fk_wd_subscription REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    event_type varchar(32) NOT NULL,
+    payload text NOT NULL,
+    attempts smallint NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_wd_next_attempt ON webhook_deliveries(next_attempt_at);
@@ -0,0 +1,78 @@
+-- Every (ancestor, descendant) pair of the group hierarchy, including each group with itself at
+-- depth 0, so resolving a subtree is one indexed lookup instead of a recursive query.
+-- Kept in step with staff_groups by the triggers below; rows of a deleted group go with it.
+CREATE TABLE group_closure(
+    ancestor_id uuid NOT NULL CONSTRAINT fk_gc_ancestor REFERENCES staff_groups(id) ON DELETE CASCADE,
+    descendant_id uuid NOT NULL CONSTRAINT fk_gc_descendant REFERENCES staff_groups(id) ON DELETE CASCADE,
+    depth integer NOT NULL,
+    CONSTRAINT pk_group_closure PRIMARY KEY (ancestor_id, descendant_id)
+);
+
+CREATE INDEX idx_gc_descendant ON group_closure(descendant_id);
+
+-- Nothing used to stop a group being moved under its own subgroup, so existing cycles are cut
+-- where they close instead of looping forever
+WITH RECURSIVE paths AS (
+    SELECT id AS ancestor_id, id AS descendant_id, 0 AS depth
+    FROM staff_groups
+    UNION ALL
+    SELECT p.ancestor_id, g.id, p.depth + 1
+    FROM paths p
+    JOIN staff_groups g ON g.parent_group_id = p.descendant_id
+) CYCLE descendant_id SET is_cycle USING visited
+INSERT INTO group_closure (ancestor_id, descendant_id, depth)
+SELECT DISTINCT ON (ancestor_id, descendant_id) ancestor_id, descendant_id, depth
+FROM paths
+WHERE NOT is_cycle
+ORDER BY ancestor_id, descendant_id, depth;
+
+CREATE FUNCTION group_closure_insert() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO group_closure (ancestor_id, descendant_id, depth)
+    SELECT ancestor_id, NEW.id, depth + 1
+    FROM group_closure
+    WHERE descendant_id = NEW.parent_group_id
+    UNION ALL
+    SELECT NEW.id, NEW.id, 0;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Moves the whole subtree: drops its links to the old ancestors and adds ones to the new.
+-- Also runs when ON DELETE SET NULL detaches the children of a deleted group.
+CREATE FUNCTION group_closure_move() RETURNS trigger AS $$
+BEGIN
+    IF NEW.parent_group_id IS NOT NULL AND EXISTS (
+        SELECT 1 FROM group_closure
+        WHERE ancestor_id = NEW.id AND descendant_id = NEW.parent_group_id
+    ) THEN
+        RAISE EXCEPTION 'group % cannot be moved under its own subgroup', NEW.id
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'ck_staff_groups_no_cycle';
+    END IF;
+
+    DELETE FROM group_closure
+    WHERE descendant_id IN (SELECT descendant_id FROM group_closure WHERE ancestor_id = NEW.id)
+      AND ancestor_id IN (
+          SELECT ancestor_id FROM group_closure
+          WHERE descendant_id = NEW.id AND ancestor_id <> NEW.id
+      );
+
+    INSERT INTO group_closure (ancestor_id, descendant_id, depth)
+    SELECT above.ancestor_id, below.descendant_id, above.depth + below.depth + 1
+    FROM group_closure above
+    CROSS JOIN group_closure below
+    WHERE above.descendant_id = NEW.parent_group_id
+      AND below.ancestor_id = NEW.id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_staff_groups_closure_insert
+    AFTER INSERT ON staff_groups
+    FOR EACH ROW EXECUTE FUNCTION group_closure_insert();
+
+CREATE TRIGGER trg_staff_groups_closure_move
+    AFTER UPDATE OF parent_group_id ON staff_groups
+    FOR EACH ROW
+    WHEN (OLD.parent_group_id IS DISTINCT FROM NEW.parent_group_id)
+    EXECUTE FUNCTION group_closure_move();
@@ -0,0 +1,4 @@
+-- The paginated staff listing sorts by name and filters by position.
+CREATE INDEX idx_staff_name ON staff(name, id);
+
+CREATE INDEX idx_staff_position ON staff(position);
@@ -0,0 +1,21 @@
+-- Groups new staff join automatically based on their position.
+CREATE TABLE onboarding_rules(
+    id uuid CONSTRAINT pk_onboarding_rules PRIMARY KEY DEFAULT gen_random_uuid(),
+    position varchar(255) NOT NULL,
+    group_id uuid NOT NULL CONSTRAINT fk_or_group REFERENCES staff_groups(id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT uq_or_position_group UNIQUE (position, group_id)
+);
+
+-- Which rules were applied to whom, written in the same transaction as the staff member.
+-- Kept when the rule is deleted later, so the group and position are copied over.
+CREATE TABLE onboarding_log(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_onboarding_log PRIMARY KEY,
+    staff_id uuid NOT NULL CONSTRAINT fk_ol_staff REFERENCES staff(id) ON DELETE CASCADE,
+    rule_id uuid CONSTRAINT fk_ol_rule REFERENCES onboarding_rules(id) ON DELETE SET NULL,
+    group_id uuid NOT NULL,
+    position varchar(255) NOT NULL,
+    applied_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_ol_staff ON onboarding_log(staff_id);
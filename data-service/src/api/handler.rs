@@ -1,3 +1,9 @@
+pub mod audit;
+pub mod blackout;
 pub mod group;
+pub mod health;
 pub mod membership;
+pub mod onboarding;
 pub mod staff;
+pub mod time_off;
+pub mod webhook;
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Query, Request, State},
+    http::{Method, header},
+    middleware::Next,
+    response::Response,
+};
+use shared::auth::{AdminAccess, Authenticator};
+use shared::pagination::{Page, PageRequest};
+use shared::responses::ApiResponse;
+
+use crate::{
+    api::state::DataServiceAppState,
+    domain::audit::{AuditEntry, AuditPage, AuditQuery, validate_audit_query, with_actor},
+    error::DataServiceError,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "Audit",
+    operation_id = "list_audit_entries",
+    params(
+        PageRequest,
+        AuditQuery
+    ),
+    responses(
+        (status = 200, description = "Page of matching changes, newest first", body = ApiResponse<Page<AuditEntry>>),
+        (status = 400, description = "Invalid limit or offset, or to before from")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_all(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Query(page): Query<PageRequest>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<ApiResponse<AuditPage>>, DataServiceError> {
+    validate_audit_query(&query, &page)?;
+    let output = state.audit_log.find(query, page).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+/// Makes the caller of a write request the actor the audited repositories record. Requests
+/// whose token doesn't check out pass through as they are; the route's extractor turns them
+/// away.
+pub async fn scope_actor(request: Request, next: Next) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+    let Some(authenticator) = request.extensions().get::<Arc<Authenticator>>().cloned() else {
+        return next.run(request).await;
+    };
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    match authenticator.authenticate(authorization).await {
+        Ok(principal) => with_actor(principal.subject, next.run(request)).await,
+        Err(_) => next.run(request).await,
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use shared::{
+    auth::{ServiceAccess, WriteAccess},
+    responses::{ApiResponse, EmptyApiResponse},
+    types::GroupBlackout,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::state::DataServiceAppState,
+    domain::blackout::{BlackoutQuery, CreateBlackout},
+    error::DataServiceError,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/groups/{group_id}/blackouts",
+    tag = "Blackouts",
+    operation_id = "create_blackout",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID")
+    ),
+    request_body = CreateBlackout,
+    responses(
+        (status = 200, description = "Blackout window created", body = ApiResponse<GroupBlackout>),
+        (status = 400, description = "Invalid date range or percentage"),
+        (status = 404, description = "Group not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn create(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(group_id): Path<Uuid>,
+    Json(body): Json<CreateBlackout>,
+) -> Result<Json<ApiResponse<GroupBlackout>>, DataServiceError> {
+    body.validate()?;
+    let output = state.blackout_repo.create(group_id, body).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/blackouts",
+    tag = "Blackouts",
+    security(("bearer_auth" = []), ("api_key" = [])),
+    operation_id = "list_blackouts",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        BlackoutQuery
+    ),
+    responses(
+        (status = 200, description = "Blackout windows overlapping the range", body = ApiResponse<Vec<GroupBlackout>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_by_group(
+    _: ServiceAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(group_id): Path<Uuid>,
+    Query(query): Query<BlackoutQuery>,
+) -> Result<Json<ApiResponse<Vec<GroupBlackout>>>, DataServiceError> {
+    let output = state.blackout_repo.find_by_group(group_id, query).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/groups/{group_id}/blackouts/{blackout_id}",
+    tag = "Blackouts",
+    operation_id = "delete_blackout",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        ("blackout_id" = Uuid, Path, description = "Blackout window ID")
+    ),
+    responses(
+        (status = 200, description = "Blackout window deleted", body = EmptyApiResponse),
+        (status = 404, description = "Blackout window not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn delete(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path((group_id, blackout_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<()>>, DataServiceError> {
+    state.blackout_repo.delete(group_id, blackout_id).await?;
+
+    Ok(Json(ApiResponse::ok(())))
+}
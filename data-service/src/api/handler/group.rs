@@ -2,9 +2,11 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::HeaderMap,
 };
 use shared::{
+    auth::{ReadAccess, WriteAccess},
     responses::{ApiResponse, EmptyApiResponse},
     types::StaffGroup,
 };
@@ -12,7 +14,11 @@ use uuid::Uuid;
 
 use crate::{
     api::state::DataServiceAppState,
-    domain::group::{CreateGroup, UpdateGroup},
+    domain::{
+        bulk::{BulkDelete, BulkDeleteQuery, BulkDeleteResult},
+        group::{CreateGroup, GroupDepthStats, GroupNode, PatchGroup, UpdateGroup},
+        version::{Versioned, if_match, versioned},
+    },
     error::DataServiceError,
 };
 
@@ -27,6 +33,7 @@ use crate::{
 )]
 #[tracing::instrument(skip(state))]
 pub async fn find_all(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
 ) -> Result<Json<ApiResponse<Vec<StaffGroup>>>, DataServiceError> {
     let output = state.group_repo.find_all().await?;
@@ -43,19 +50,21 @@ pub async fn find_all(
         ("id" = Uuid, Path, description = "Group ID")
     ),
     responses(
-        (status = 200, description = "Group found", body = ApiResponse<StaffGroup>),
+        (status = 200, description = "Group found", body = ApiResponse<StaffGroup>,
+            headers(("ETag" = String, description = "Current version, for If-Match"))),
         (status = 404, description = "Group not found")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn find_by_id(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<StaffGroup>>, DataServiceError> {
+) -> Result<Versioned<StaffGroup>, DataServiceError> {
     let output = state.group_repo.find_by_id(id).await?;
 
     match output {
-        Some(g) => Ok(Json(ApiResponse::ok(g))),
+        Some(g) => Ok(versioned(g.version, g)),
         None => Err(DataServiceError::NotFound("Group not found".to_string())),
     }
 }
@@ -67,11 +76,14 @@ pub async fn find_by_id(
     operation_id = "create_group",
     request_body = CreateGroup,
     responses(
-        (status = 200, description = "Group created", body = ApiResponse<StaffGroup>)
+        (status = 200, description = "Group created", body = ApiResponse<StaffGroup>),
+        (status = 409, description = "Another group under the same parent has this name (case-insensitive)"),
+        (status = 422, description = "The hierarchy would get deeper than the configured maximum")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn create(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Json(group): Json<CreateGroup>,
 ) -> Result<Json<ApiResponse<StaffGroup>>, DataServiceError> {
@@ -87,11 +99,14 @@ pub async fn create(
     operation_id = "batch_create_groups",
     request_body = Vec<CreateGroup>,
     responses(
-        (status = 200, description = "Groups batch created", body = ApiResponse<Vec<StaffGroup>>)
+        (status = 200, description = "Groups batch created", body = ApiResponse<Vec<StaffGroup>>),
+        (status = 409, description = "A name clashes with an existing sibling group or another group in the batch"),
+        (status = 422, description = "The hierarchy would get deeper than the configured maximum")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn batch_create(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Json(groups): Json<Vec<CreateGroup>>,
 ) -> Result<Json<ApiResponse<Vec<StaffGroup>>>, DataServiceError> {
@@ -106,22 +121,69 @@ pub async fn batch_create(
     tag = "Groups",
     operation_id = "update_group",
     params(
-        ("id" = Uuid, Path, description = "Group ID")
+        ("id" = Uuid, Path, description = "Group ID"),
+        ("If-Match" = Option<String>, Header, description = "Only update while the group still has this ETag")
     ),
     request_body = UpdateGroup,
     responses(
-        (status = 200, description = "Group updated", body = ApiResponse<StaffGroup>)
+        (status = 200, description = "Group updated", body = ApiResponse<StaffGroup>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 400, description = "The new parent is the group itself or one of its subgroups"),
+        (status = 409, description = "Another group under the same parent has this name (case-insensitive)"),
+        (status = 412, description = "The group has changed since the If-Match ETag"),
+        (status = 422, description = "The hierarchy would get deeper than the configured maximum")
     )
 )]
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, headers))]
 pub async fn update(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(group): Json<UpdateGroup>,
-) -> Result<Json<ApiResponse<StaffGroup>>, DataServiceError> {
-    let output = state.group_repo.update(id, group).await?;
+) -> Result<Versioned<StaffGroup>, DataServiceError> {
+    let output = state
+        .group_repo
+        .update(id, group, if_match(&headers)?)
+        .await?;
 
-    Ok(Json(ApiResponse::ok(output)))
+    Ok(versioned(output.version, output))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/groups/{id}",
+    tag = "Groups",
+    operation_id = "patch_group",
+    params(
+        ("id" = Uuid, Path, description = "Group ID"),
+        ("If-Match" = Option<String>, Header, description = "Only update while the group still has this ETag")
+    ),
+    request_body(content = PatchGroup, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Group updated", body = ApiResponse<StaffGroup>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 400, description = "`name` set to null, or the new parent is one of the group's subgroups"),
+        (status = 404, description = "Group not found"),
+        (status = 409, description = "Another group under the same parent has this name (case-insensitive)"),
+        (status = 412, description = "The group has changed since the If-Match ETag"),
+        (status = 422, description = "The hierarchy would get deeper than the configured maximum")
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn patch(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(patch): Json<PatchGroup>,
+) -> Result<Versioned<StaffGroup>, DataServiceError> {
+    let output = state
+        .group_repo
+        .update(id, patch.into_update()?, if_match(&headers)?)
+        .await?;
+
+    Ok(versioned(output.version, output))
 }
 
 #[utoipa::path(
@@ -138,6 +200,7 @@ pub async fn update(
 )]
 #[tracing::instrument(skip(state))]
 pub async fn delete(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<()>>, DataServiceError> {
@@ -145,3 +208,94 @@ pub async fn delete(
 
     Ok(Json(ApiResponse::ok(())))
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/groups/bulk-delete",
+    tag = "Groups",
+    operation_id = "bulk_delete_groups",
+    params(BulkDeleteQuery),
+    request_body = BulkDelete,
+    responses(
+        (status = 200, description = "Outcome per id, in request order", body = ApiResponse<Vec<BulkDeleteResult>>),
+        (status = 400, description = "No ids or more than 1000"),
+        (status = 409, description = "A child group left without its parent clashes with a top-level group name")
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(count = body.ids.len()))]
+pub async fn bulk_delete(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Query(query): Query<BulkDeleteQuery>,
+    Json(body): Json<BulkDelete>,
+) -> Result<Json<ApiResponse<Vec<BulkDeleteResult>>>, DataServiceError> {
+    let body = body.validate()?;
+    let output = state.group_repo.bulk_delete(body, query.dry_run).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/depth",
+    tag = "Groups",
+    operation_id = "group_depth_stats",
+    responses(
+        (status = 200, description = "Depth of the deepest group hierarchy and the configured maximum", body = ApiResponse<GroupDepthStats>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn depth_stats(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+) -> Result<Json<ApiResponse<GroupDepthStats>>, DataServiceError> {
+    let output = state.group_repo.depth_stats().await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/tree",
+    tag = "Groups",
+    operation_id = "group_tree",
+    responses(
+        (status = 200, description = "Top-level groups by name, each with its subgroups nested and member counts", body = ApiResponse<Vec<GroupNode>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn tree(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+) -> Result<Json<ApiResponse<Vec<GroupNode>>>, DataServiceError> {
+    let output = state.group_repo.tree(None).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{id}/subtree",
+    tag = "Groups",
+    operation_id = "group_subtree",
+    params(
+        ("id" = Uuid, Path, description = "Group ID")
+    ),
+    responses(
+        (status = 200, description = "The group with its subgroups nested and member counts", body = ApiResponse<GroupNode>),
+        (status = 404, description = "Group not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn subtree(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<GroupNode>>, DataServiceError> {
+    let output = state.group_repo.tree(Some(id)).await?;
+
+    match output.into_iter().next() {
+        Some(node) => Ok(Json(ApiResponse::ok(node))),
+        None => Err(DataServiceError::NotFound("Group not found".to_string())),
+    }
+}
@@ -2,16 +2,23 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use shared::{
+    auth::{ReadAccess, ServiceAccess, WriteAccess},
+    pagination::{Page, PageRequest},
     responses::{ApiResponse, EmptyApiResponse},
-    types::{Staff, StaffGroup},
+    types::{GroupMember, PositionBucket, StaffGroup},
 };
 use uuid::Uuid;
 
 use crate::{
-    api::state::DataServiceAppState, domain::membership::AddMembership, error::DataServiceError,
+    api::state::DataServiceAppState,
+    domain::membership::{
+        AddMembership, MemberPage, MemberQuery, MembershipChanges, MembershipResult,
+        RemoveMembership, SetMembers, validate_member_page,
+    },
+    error::DataServiceError,
 };
 
 #[utoipa::path(
@@ -29,6 +36,7 @@ use crate::{
 )]
 #[tracing::instrument(skip(state))]
 pub async fn add_member(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(group_id): Path<Uuid>,
     Json(body): Json<AddMembership>,
@@ -41,7 +49,7 @@ pub async fn add_member(
 
     state
         .membership_repo
-        .add_staff_to_group(group_id, body.staff_id)
+        .add_staff_to_group(group_id, body.staff_id, body.valid_from)
         .await?;
 
     Ok(Json(ApiResponse::ok(())))
@@ -62,6 +70,7 @@ pub async fn add_member(
 )]
 #[tracing::instrument(skip(state))]
 pub async fn remove_member(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path((group_id, staff_id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<ApiResponse<()>>, DataServiceError> {
@@ -73,24 +82,64 @@ pub async fn remove_member(
     Ok(Json(ApiResponse::ok(())))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/groups/{group_id}/members",
+    tag = "Membership",
+    operation_id = "set_group_members",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID")
+    ),
+    request_body = SetMembers,
+    responses(
+        (status = 200, description = "Member list replaced; the staff added and removed", body = ApiResponse<MembershipChanges>),
+        (status = 400, description = "Unknown staff in the list, nothing changed", body = EmptyApiResponse),
+        (status = 404, description = "Group not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn set_group_members(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(group_id): Path<Uuid>,
+    Json(body): Json<SetMembers>,
+) -> Result<Json<ApiResponse<MembershipChanges>>, DataServiceError> {
+    let output = state
+        .membership_repo
+        .set_group_members(group_id, body.staff_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/groups/{group_id}/members",
     tag = "Membership",
     operation_id = "get_group_members",
     params(
-        ("group_id" = Uuid, Path, description = "Group ID")
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        PageRequest,
+        MemberQuery
     ),
     responses(
-        (status = 200, description = "List group members", body = ApiResponse<Vec<Staff>>)
+        (status = 200, description = "Page of direct group members", body = ApiResponse<Page<GroupMember>>),
+        (status = 400, description = "Invalid limit or offset")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn get_group_members(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(group_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Vec<Staff>>>, DataServiceError> {
-    let output = state.membership_repo.get_group_members(group_id).await?;
+    Query(page): Query<PageRequest>,
+    Query(query): Query<MemberQuery>,
+) -> Result<Json<ApiResponse<MemberPage>>, DataServiceError> {
+    validate_member_page(&page)?;
+    let output = state
+        .membership_repo
+        .get_group_members(group_id, query, page)
+        .await?;
 
     Ok(Json(ApiResponse::ok(output)))
 }
@@ -109,6 +158,7 @@ pub async fn get_group_members(
 )]
 #[tracing::instrument(skip(state))]
 pub async fn get_staff_groups(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(staff_id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<Vec<StaffGroup>>>, DataServiceError> {
@@ -121,20 +171,60 @@ pub async fn get_staff_groups(
     get,
     path = "/api/v1/groups/{group_id}/resolved-members",
     tag = "Membership",
+    security(("bearer_auth" = []), ("api_key" = [])),
     operation_id = "resolve_members",
     params(
-        ("group_id" = Uuid, Path, description = "Group ID")
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        PageRequest,
+        MemberQuery
     ),
     responses(
-        (status = 200, description = "List resolved group members (including sub-groups)", body = ApiResponse<Vec<Staff>>)
+        (status = 200, description = "Page of resolved group members (including sub-groups)", body = ApiResponse<Page<GroupMember>>),
+        (status = 400, description = "Invalid limit or offset")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn resolve_members(
+    _: ServiceAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(group_id): Path<Uuid>,
+    Query(page): Query<PageRequest>,
+    Query(query): Query<MemberQuery>,
+) -> Result<Json<ApiResponse<MemberPage>>, DataServiceError> {
+    validate_member_page(&page)?;
+    let output = state
+        .membership_repo
+        .resolve_members(group_id, query, page)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/members/by-position",
+    tag = "Membership",
+    security(("bearer_auth" = []), ("api_key" = [])),
+    operation_id = "resolve_members_by_position",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        MemberQuery
+    ),
+    responses(
+        (status = 200, description = "Resolved group members (including sub-groups) bucketed by position, with counts", body = ApiResponse<Vec<PositionBucket>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn resolve_members_by_position(
+    _: ServiceAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(group_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Vec<Staff>>>, DataServiceError> {
-    let output = state.membership_repo.resolve_members(group_id).await?;
+    Query(query): Query<MemberQuery>,
+) -> Result<Json<ApiResponse<Vec<PositionBucket>>>, DataServiceError> {
+    let output = state
+        .membership_repo
+        .resolve_members_by_position(group_id, query)
+        .await?;
 
     Ok(Json(ApiResponse::ok(output)))
 }
@@ -146,15 +236,40 @@ pub async fn resolve_members(
     operation_id = "batch_add_members",
     request_body = Vec<AddMembership>,
     responses(
-        (status = 200, description = "Memberships batch added", body = EmptyApiResponse)
+        (status = 200, description = "Per-item outcome, in request order", body = ApiResponse<Vec<MembershipResult>>)
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn batch_add_members(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Json(memberships): Json<Vec<AddMembership>>,
-) -> Result<Json<ApiResponse<()>>, DataServiceError> {
-    state.membership_repo.batch_add_members(memberships).await?;
+) -> Result<Json<ApiResponse<Vec<MembershipResult>>>, DataServiceError> {
+    let report = state.membership_repo.batch_add_members(memberships).await?;
 
-    Ok(Json(ApiResponse::ok(())))
+    Ok(Json(ApiResponse::ok(report)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/memberships/batch",
+    tag = "Membership",
+    operation_id = "batch_remove_members",
+    request_body = Vec<RemoveMembership>,
+    responses(
+        (status = 200, description = "Per-item outcome, in request order", body = ApiResponse<Vec<MembershipResult>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn batch_remove_members(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Json(memberships): Json<Vec<RemoveMembership>>,
+) -> Result<Json<ApiResponse<Vec<MembershipResult>>>, DataServiceError> {
+    let report = state
+        .membership_repo
+        .batch_remove_members(memberships)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(report)))
 }
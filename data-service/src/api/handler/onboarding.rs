@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use shared::auth::{AdminAccess, ReadAccess};
+use shared::responses::{ApiResponse, EmptyApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    api::state::DataServiceAppState,
+    domain::onboarding::{AppliedOnboardingRule, CreateOnboardingRule, OnboardingRule},
+    error::DataServiceError,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/onboarding-rules",
+    tag = "Onboarding",
+    operation_id = "create_onboarding_rule",
+    request_body = CreateOnboardingRule,
+    responses(
+        (status = 200, description = "Rule created; applies to staff created from now on", body = ApiResponse<OnboardingRule>),
+        (status = 400, description = "Empty position"),
+        (status = 404, description = "Group not found"),
+        (status = 409, description = "The position already onboards into the group")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn create(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Json(body): Json<CreateOnboardingRule>,
+) -> Result<Json<ApiResponse<OnboardingRule>>, DataServiceError> {
+    body.validate()?;
+    let output = state.onboarding_repo.create(body).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/onboarding-rules",
+    tag = "Onboarding",
+    operation_id = "list_onboarding_rules",
+    responses(
+        (status = 200, description = "All rules, by position", body = ApiResponse<Vec<OnboardingRule>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_all(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+) -> Result<Json<ApiResponse<Vec<OnboardingRule>>>, DataServiceError> {
+    let output = state.onboarding_repo.find_all().await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/onboarding-rules/{id}",
+    tag = "Onboarding",
+    operation_id = "delete_onboarding_rule",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID")
+    ),
+    responses(
+        (status = 200, description = "Rule deleted; memberships it added are kept", body = EmptyApiResponse),
+        (status = 404, description = "Rule not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn delete(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, DataServiceError> {
+    state.onboarding_repo.delete(id).await?;
+
+    Ok(Json(ApiResponse::ok(())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/{id}/onboarding",
+    tag = "Onboarding",
+    operation_id = "get_staff_onboarding",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID")
+    ),
+    responses(
+        (status = 200, description = "Rules applied when the staff member was created, oldest first", body = ApiResponse<Vec<AppliedOnboardingRule>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_applied(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(staff_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<AppliedOnboardingRule>>>, DataServiceError> {
+    let output = state.onboarding_repo.find_applied(staff_id).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
@@ -2,9 +2,14 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use shared::{
+    auth::{ReadAccess, ServiceAccess, WriteAccess},
+    pagination::{Page, PageRequest},
     responses::{ApiResponse, EmptyApiResponse},
     types::Staff,
 };
@@ -12,7 +17,16 @@ use uuid::Uuid;
 
 use crate::{
     api::state::DataServiceAppState,
-    domain::staff::{CreateStaff, UpdateStaff},
+    domain::{
+        bulk::{BulkDelete, BulkDeleteOutcome, BulkDeleteQuery, BulkDeleteResult},
+        duplicate::{DuplicateQuery, MergeResult, MergeStaff, StaffDuplicate},
+        photo::validate_photo,
+        staff::{
+            CreateStaff, MAX_LOOKUP_IDS, PatchStaff, StaffPage, StaffQuery, UpdateStaff,
+            validate_fairness_weight, validate_staff_query,
+        },
+        version::{Versioned, if_match, versioned},
+    },
     error::DataServiceError,
 };
 
@@ -21,15 +35,24 @@ use crate::{
     path = "/api/v1/staff",
     tag = "Staff",
     operation_id = "list_staff",
+    params(
+        PageRequest,
+        StaffQuery
+    ),
     responses(
-        (status = 200, description = "List all staff", body = ApiResponse<Vec<Staff>>)
+        (status = 200, description = "Page of matching staff, ordered by name", body = ApiResponse<Page<Staff>>),
+        (status = 400, description = "Invalid limit, offset or q")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn find_all(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
-) -> Result<Json<ApiResponse<Vec<Staff>>>, DataServiceError> {
-    let output = state.staff_repo.find_all().await?;
+    Query(page): Query<PageRequest>,
+    Query(query): Query<StaffQuery>,
+) -> Result<Json<ApiResponse<StaffPage>>, DataServiceError> {
+    validate_staff_query(&query, &page)?;
+    let output = state.staff_repo.find_all(query, page).await?;
     Ok(Json(ApiResponse::ok(output)))
 }
 
@@ -42,19 +65,21 @@ pub async fn find_all(
         ("id" = Uuid, Path, description = "Staff ID")
     ),
     responses(
-        (status = 200, description = "Staff found", body = ApiResponse<Staff>),
+        (status = 200, description = "Staff found", body = ApiResponse<Staff>,
+            headers(("ETag" = String, description = "Current version, for If-Match"))),
         (status = 404, description = "Staff not found")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn find_by_id(
+    _: ReadAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Staff>>, DataServiceError> {
+) -> Result<Versioned<Staff>, DataServiceError> {
     let output = state.staff_repo.find_by_id(id).await?;
 
     match output {
-        Some(s) => Ok(Json(ApiResponse::ok(s))),
+        Some(s) => Ok(versioned(s.version, s)),
         None => Err(DataServiceError::NotFound("Staff not found".to_string())),
     }
 }
@@ -66,14 +91,18 @@ pub async fn find_by_id(
     operation_id = "create_staff",
     request_body = CreateStaff,
     responses(
-        (status = 200, description = "Staff created", body = ApiResponse<Staff>)
+        (status = 200, description = "Staff created", body = ApiResponse<Staff>),
+        (status = 400, description = "Invalid fairness_weight"),
+        (status = 409, description = "Email already exists")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn create(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Json(staff): Json<CreateStaff>,
 ) -> Result<Json<ApiResponse<Staff>>, DataServiceError> {
+    validate_fairness_weight(staff.fairness_weight)?;
     let output = state.staff_repo.create(staff).await?;
 
     Ok(Json(ApiResponse::ok(output)))
@@ -86,41 +115,132 @@ pub async fn create(
     operation_id = "batch_create_staff",
     request_body = Vec<CreateStaff>,
     responses(
-        (status = 200, description = "Staff batch created", body = ApiResponse<Vec<Staff>>)
+        (status = 200, description = "Staff batch created", body = ApiResponse<Vec<Staff>>),
+        (status = 409, description = "An email already exists or repeats within the batch")
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn batch_create(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Json(staffs): Json<Vec<CreateStaff>>,
 ) -> Result<Json<ApiResponse<Vec<Staff>>>, DataServiceError> {
+    for staff in &staffs {
+        validate_fairness_weight(staff.fairness_weight)?;
+    }
     let output = state.staff_repo.batch_create(staffs).await?;
 
     Ok(Json(ApiResponse::ok(output)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/staff/lookup",
+    tag = "Staff",
+    security(("bearer_auth" = []), ("api_key" = [])),
+    operation_id = "lookup_staff",
+    request_body(content = Vec<Uuid>, description = "Staff IDs (max 1000)"),
+    responses(
+        (status = 200, description = "Matching staff; unknown IDs are omitted", body = ApiResponse<Vec<Staff>>),
+        (status = 400, description = "Too many IDs")
+    )
+)]
+#[tracing::instrument(skip(state, ids), fields(count = ids.len()))]
+pub async fn lookup(
+    _: ServiceAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Json(mut ids): Json<Vec<Uuid>>,
+) -> Result<Json<ApiResponse<Vec<Staff>>>, DataServiceError> {
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() > MAX_LOOKUP_IDS {
+        return Err(DataServiceError::BadRequest(format!(
+            "At most {MAX_LOOKUP_IDS} ids can be looked up at once"
+        )));
+    }
+
+    let output = if ids.is_empty() {
+        Vec::new()
+    } else {
+        state.staff_repo.find_by_ids(ids).await?
+    };
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
 #[utoipa::path(
     put,
     path = "/api/v1/staff/{id}",
     tag = "Staff",
     operation_id = "update_staff",
     params(
-        ("id" = Uuid, Path, description = "Staff ID")
+        ("id" = Uuid, Path, description = "Staff ID"),
+        ("If-Match" = Option<String>, Header, description = "Only update while the staff member still has this ETag")
     ),
     request_body = UpdateStaff,
     responses(
-        (status = 200, description = "Staff updated", body = ApiResponse<Staff>)
+        (status = 200, description = "Staff updated", body = ApiResponse<Staff>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 409, description = "Email already used by other staff"),
+        (status = 412, description = "The staff member has changed since the If-Match ETag")
     )
 )]
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, headers))]
 pub async fn update(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(staff): Json<UpdateStaff>,
-) -> Result<Json<ApiResponse<Staff>>, DataServiceError> {
-    let output = state.staff_repo.update(id, staff).await?;
+) -> Result<Versioned<Staff>, DataServiceError> {
+    if let Some(weight) = staff.fairness_weight {
+        validate_fairness_weight(weight)?;
+    }
+    let output = state
+        .staff_repo
+        .update(id, staff, if_match(&headers)?)
+        .await?;
 
-    Ok(Json(ApiResponse::ok(output)))
+    Ok(versioned(output.version, output))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/staff/{id}",
+    tag = "Staff",
+    operation_id = "patch_staff",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID"),
+        ("If-Match" = Option<String>, Header, description = "Only update while the staff member still has this ETag")
+    ),
+    request_body(content = PatchStaff, content_type = "application/merge-patch+json"),
+    responses(
+        (status = 200, description = "Staff updated", body = ApiResponse<Staff>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 400, description = "A field set to null or an invalid fairness_weight"),
+        (status = 404, description = "Staff not found"),
+        (status = 409, description = "Email already used by other staff"),
+        (status = 412, description = "The staff member has changed since the If-Match ETag")
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn patch(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(patch): Json<PatchStaff>,
+) -> Result<Versioned<Staff>, DataServiceError> {
+    let staff = patch.into_update()?;
+    if let Some(weight) = staff.fairness_weight {
+        validate_fairness_weight(weight)?;
+    }
+    let output = state
+        .staff_repo
+        .update(id, staff, if_match(&headers)?)
+        .await?;
+
+    Ok(versioned(output.version, output))
 }
 
 #[utoipa::path(
@@ -137,6 +257,7 @@ pub async fn update(
 )]
 #[tracing::instrument(skip(state))]
 pub async fn deactivate(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<()>>, DataServiceError> {
@@ -159,10 +280,204 @@ pub async fn deactivate(
 )]
 #[tracing::instrument(skip(state))]
 pub async fn delete(
+    _: WriteAccess,
     State(state): State<Arc<DataServiceAppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<()>>, DataServiceError> {
     state.staff_repo.delete(id).await?;
 
+    if let Err(e) = state.photo_storage.delete(id).await {
+        tracing::warn!("Failed to delete photo of removed staff {id}: {e}");
+    }
+
+    Ok(Json(ApiResponse::ok(())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/staff/bulk-delete",
+    tag = "Staff",
+    operation_id = "bulk_delete_staff",
+    params(BulkDeleteQuery),
+    request_body = BulkDelete,
+    responses(
+        (status = 200, description = "Outcome per id, in request order", body = ApiResponse<Vec<BulkDeleteResult>>),
+        (status = 400, description = "No ids or more than 1000")
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(count = body.ids.len()))]
+pub async fn bulk_delete(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Query(query): Query<BulkDeleteQuery>,
+    Json(body): Json<BulkDelete>,
+) -> Result<Json<ApiResponse<Vec<BulkDeleteResult>>>, DataServiceError> {
+    let body = body.validate()?;
+    let output = state.staff_repo.bulk_delete(body, query.dry_run).await?;
+
+    for result in &output {
+        if result.outcome == BulkDeleteOutcome::Deleted
+            && let Err(e) = state.photo_storage.delete(result.id).await
+        {
+            tracing::warn!("Failed to delete photo of removed staff {}: {e}", result.id);
+        }
+    }
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/duplicates",
+    tag = "Staff",
+    operation_id = "find_staff_duplicates",
+    params(DuplicateQuery),
+    responses(
+        (status = 200, description = "Likely duplicate pairs, same names first", body = ApiResponse<Vec<StaffDuplicate>>),
+        (status = 400, description = "Invalid min_similarity or limit")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_duplicates(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Query(query): Query<DuplicateQuery>,
+) -> Result<Json<ApiResponse<Vec<StaffDuplicate>>>, DataServiceError> {
+    query.validate()?;
+    let output = state.staff_repo.find_duplicates(query).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/staff/{id}/merge",
+    tag = "Staff",
+    operation_id = "merge_staff",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID to keep")
+    ),
+    request_body = MergeStaff,
+    responses(
+        (status = 200, description = "Duplicate merged and deactivated", body = ApiResponse<MergeResult>),
+        (status = 400, description = "Merging a record into itself"),
+        (status = 404, description = "Staff not found"),
+        (status = 409, description = "One of the records was already merged")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn merge(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<MergeStaff>,
+) -> Result<Json<ApiResponse<MergeResult>>, DataServiceError> {
+    if body.duplicate_id == id {
+        return Err(DataServiceError::BadRequest(
+            "A staff member can't be merged into itself".to_string(),
+        ));
+    }
+    let output = state.staff_repo.merge(id, body.duplicate_id).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/staff/{id}/photo",
+    tag = "Staff",
+    operation_id = "put_staff_photo",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID")
+    ),
+    request_body(content = Vec<u8>, description = "JPEG, PNG or WebP image, max 1 MiB", content_type = "image/*"),
+    responses(
+        (status = 200, description = "Photo stored", body = EmptyApiResponse),
+        (status = 400, description = "Empty, oversized or unsupported image"),
+        (status = 404, description = "Staff not found")
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(size = body.len()))]
+pub async fn put_photo(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+    body: Bytes,
+) -> Result<Json<ApiResponse<()>>, DataServiceError> {
+    validate_photo(&body)?;
+
+    if state.staff_repo.find_by_id(id).await?.is_none() {
+        return Err(DataServiceError::NotFound("Staff not found".to_string()));
+    }
+
+    state.photo_storage.put(id, body).await?;
+
     Ok(Json(ApiResponse::ok(())))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/{id}/photo",
+    tag = "Staff",
+    operation_id = "get_staff_photo",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID")
+    ),
+    responses(
+        (status = 200, description = "Staff photo", body = Vec<u8>, content_type = "image/*"),
+        (status = 304, description = "Photo unchanged since the given ETag"),
+        (status = 404, description = "Staff has no photo")
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn get_photo(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, DataServiceError> {
+    let photo = state
+        .photo_storage
+        .get(id)
+        .await?
+        .ok_or_else(|| DataServiceError::NotFound("Photo not found".to_string()))?;
+
+    let etag = photo.etag.map(|tag| format!("\"{tag}\""));
+    let cache_headers = [
+        (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+        (
+            header::LAST_MODIFIED,
+            photo
+                .last_modified
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        ),
+    ];
+
+    let not_modified = etag.as_deref().is_some_and(|etag| {
+        headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.split(',').any(|v| v.trim() == etag))
+    });
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            cache_headers,
+            [(header::ETAG, etag.unwrap_or_default())],
+        )
+            .into_response());
+    }
+
+    let mut response = (
+        cache_headers,
+        [(header::CONTENT_TYPE, photo.content_type)],
+        photo.bytes,
+    )
+        .into_response();
+    if let Some(etag) = etag.and_then(|etag| etag.parse().ok()) {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use shared::{
+    auth::{ReadAccess, ServiceAccess, WriteAccess},
+    responses::{ApiResponse, EmptyApiResponse},
+    types::TimeOffRequest,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::state::DataServiceAppState,
+    domain::time_off::{CreateTimeOff, TimeOffQuery, TimeOffWindow, UpdateTimeOff},
+    error::DataServiceError,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/staff/{id}/time-off",
+    tag = "Time Off",
+    operation_id = "create_time_off",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID")
+    ),
+    request_body = CreateTimeOff,
+    responses(
+        (status = 200, description = "Request created as PENDING", body = ApiResponse<TimeOffRequest>),
+        (status = 400, description = "Invalid date range"),
+        (status = 404, description = "Staff not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn create(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(staff_id): Path<Uuid>,
+    Json(body): Json<CreateTimeOff>,
+) -> Result<Json<ApiResponse<TimeOffRequest>>, DataServiceError> {
+    body.validate()?;
+    let output = state.time_off_repo.create(staff_id, body).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/{id}/time-off",
+    tag = "Time Off",
+    operation_id = "list_time_off",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID"),
+        TimeOffQuery
+    ),
+    responses(
+        (status = 200, description = "Requests of the staff member, by start date", body = ApiResponse<Vec<TimeOffRequest>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_by_staff(
+    _: ReadAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(staff_id): Path<Uuid>,
+    Query(query): Query<TimeOffQuery>,
+) -> Result<Json<ApiResponse<Vec<TimeOffRequest>>>, DataServiceError> {
+    let output = state.time_off_repo.find_by_staff(staff_id, query).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/staff/{id}/time-off/{request_id}",
+    tag = "Time Off",
+    operation_id = "update_time_off",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID"),
+        ("request_id" = Uuid, Path, description = "Time off request ID")
+    ),
+    request_body = UpdateTimeOff,
+    responses(
+        (status = 200, description = "Request updated", body = ApiResponse<TimeOffRequest>),
+        (status = 400, description = "Invalid date range"),
+        (status = 404, description = "Time off request not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn update(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path((staff_id, request_id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<UpdateTimeOff>,
+) -> Result<Json<ApiResponse<TimeOffRequest>>, DataServiceError> {
+    body.validate()?;
+    let output = state
+        .time_off_repo
+        .update(staff_id, request_id, body)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/staff/{id}/time-off/{request_id}",
+    tag = "Time Off",
+    operation_id = "delete_time_off",
+    params(
+        ("id" = Uuid, Path, description = "Staff ID"),
+        ("request_id" = Uuid, Path, description = "Time off request ID")
+    ),
+    responses(
+        (status = 200, description = "Request deleted", body = EmptyApiResponse),
+        (status = 404, description = "Time off request not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn delete(
+    _: WriteAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path((staff_id, request_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<()>>, DataServiceError> {
+    state.time_off_repo.delete(staff_id, request_id).await?;
+
+    Ok(Json(ApiResponse::ok(())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/resolved-members/time-off",
+    tag = "Time Off",
+    security(("bearer_auth" = []), ("api_key" = [])),
+    operation_id = "list_resolved_time_off",
+    params(
+        ("group_id" = Uuid, Path, description = "Group ID"),
+        TimeOffWindow
+    ),
+    responses(
+        (status = 200, description = "Approved time off of the group's resolved members overlapping the window", body = ApiResponse<Vec<TimeOffRequest>>),
+        (status = 400, description = "to before from")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_resolved(
+    _: ServiceAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(group_id): Path<Uuid>,
+    Query(window): Query<TimeOffWindow>,
+) -> Result<Json<ApiResponse<Vec<TimeOffRequest>>>, DataServiceError> {
+    window.validate()?;
+    let output = state
+        .time_off_repo
+        .find_approved_for_group(group_id, window.from, window.to)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
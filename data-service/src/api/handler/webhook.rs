@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use shared::auth::AdminAccess;
+use shared::responses::{ApiResponse, EmptyApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    api::state::DataServiceAppState,
+    domain::webhook::{CreateWebhook, WebhookSubscription},
+    error::DataServiceError,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "Webhooks",
+    operation_id = "create_webhook",
+    request_body = CreateWebhook,
+    responses(
+        (status = 200, description = "Subscription created; the secret is not returned", body = ApiResponse<WebhookSubscription>),
+        (status = 400, description = "Invalid URL, secret, event types or retry count")
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(url = %body.url))]
+pub async fn create(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Json(body): Json<CreateWebhook>,
+) -> Result<Json<ApiResponse<WebhookSubscription>>, DataServiceError> {
+    body.validate()?;
+    let output = state.webhook_repo.create(body).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "Webhooks",
+    operation_id = "list_webhooks",
+    responses(
+        (status = 200, description = "All subscriptions, oldest first", body = ApiResponse<Vec<WebhookSubscription>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn find_all(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+) -> Result<Json<ApiResponse<Vec<WebhookSubscription>>>, DataServiceError> {
+    let output = state.webhook_repo.find_all().await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{id}",
+    tag = "Webhooks",
+    operation_id = "delete_webhook",
+    params(
+        ("id" = Uuid, Path, description = "Subscription ID")
+    ),
+    responses(
+        (status = 200, description = "Subscription and its pending deliveries deleted", body = EmptyApiResponse),
+        (status = 404, description = "Subscription not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn delete(
+    _: AdminAccess,
+    State(state): State<Arc<DataServiceAppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, DataServiceError> {
+    state.webhook_repo.delete(id).await?;
+
+    Ok(Json(ApiResponse::ok(())))
+}
@@ -1,11 +1,24 @@
 use std::sync::Arc;
 
+use shared::health::DependencyChecks;
+use shared::startup::StartupSelfCheck;
+
 use crate::domain::{
-    group::GroupRepository, membership::MembershipRepository, staff::StaffRepository,
+    audit::AuditLog, blackout::BlackoutRepository, group::GroupRepository,
+    membership::MembershipRepository, onboarding::OnboardingRepository, photo::PhotoStorage,
+    staff::StaffRepository, time_off::TimeOffRepository, webhook::WebhookRepository,
 };
 
 pub struct DataServiceAppState {
     pub staff_repo: Arc<dyn StaffRepository>,
     pub group_repo: Arc<dyn GroupRepository>,
     pub membership_repo: Arc<dyn MembershipRepository>,
+    pub blackout_repo: Arc<dyn BlackoutRepository>,
+    pub photo_storage: Arc<dyn PhotoStorage>,
+    pub webhook_repo: Arc<dyn WebhookRepository>,
+    pub onboarding_repo: Arc<dyn OnboardingRepository>,
+    pub time_off_repo: Arc<dyn TimeOffRepository>,
+    pub audit_log: Arc<dyn AuditLog>,
+    pub startup: Arc<StartupSelfCheck>,
+    pub dependencies: Arc<DependencyChecks>,
 }
@@ -1,3 +1,13 @@
+pub mod audit;
+pub mod blackout;
+pub mod bulk;
+pub mod duplicate;
 pub mod group;
 pub mod membership;
+pub mod onboarding;
+pub mod patch;
+pub mod photo;
 pub mod staff;
+pub mod time_off;
+pub mod version;
+pub mod webhook;
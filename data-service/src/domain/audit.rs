@@ -0,0 +1,149 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::pagination::{Page, PageRequest};
+use sqlx::{FromRow, Type};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// Recorded as the actor of changes made outside a request, e.g. the staff expiry job.
+pub const SYSTEM_ACTOR: &str = "system";
+
+tokio::task_local! {
+    static ACTOR: String;
+}
+
+/// Run `f` with `actor` as the one audited for the changes it makes.
+pub async fn with_actor<F: Future>(actor: String, f: F) -> F::Output {
+    ACTOR.scope(actor, f).await
+}
+
+/// The token subject of the request being served, or `system` outside one.
+pub fn current_actor() -> String {
+    ACTOR
+        .try_with(String::clone)
+        .unwrap_or_else(|_| SYSTEM_ACTOR.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "audit_entity", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditEntity {
+    Staff,
+    Group,
+    /// Keyed by the group; `staff_id` is in the recorded JSON
+    Membership,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "audit_action", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    Create,
+    /// Including deactivation, by hand or once `effective_until` has passed
+    Update,
+    Delete,
+    /// A duplicate folded into another staff record; `after` holds the merge result
+    Merge,
+}
+
+/// One recorded change. `before` is empty for a create, `after` for a delete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub entity_type: AuditEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    /// Token subject of the caller, `anonymous` with authentication off, or `system`
+    pub actor: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewAuditEntry {
+    pub entity_type: AuditEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    pub actor: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl NewAuditEntry {
+    /// An entry for the current actor, with the records serialized as they are returned by
+    /// the API.
+    pub fn new(
+        entity_type: AuditEntity,
+        entity_id: Uuid,
+        action: AuditAction,
+        before: Option<&impl Serialize>,
+        after: Option<&impl Serialize>,
+    ) -> Self {
+        Self {
+            entity_type,
+            entity_id,
+            action,
+            actor: current_actor(),
+            before: before.and_then(|value| serde_json::to_value(value).ok()),
+            after: after.and_then(|value| serde_json::to_value(value).ok()),
+        }
+    }
+}
+
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 100;
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 1000;
+
+/// Filters of the audit log; all given ones must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub entity_type: Option<AuditEntity>,
+    pub entity_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub actor: Option<String>,
+    /// Only changes at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only changes before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Page size of the audit log: 100 unless asked otherwise, at most 1000.
+pub fn audit_page_limit(page: &PageRequest) -> i64 {
+    page.limit_or(DEFAULT_AUDIT_PAGE_SIZE)
+}
+
+pub fn validate_audit_query(
+    query: &AuditQuery,
+    page: &PageRequest,
+) -> Result<(), DataServiceError> {
+    page.validate(MAX_AUDIT_PAGE_SIZE)
+        .map_err(DataServiceError::BadRequest)?;
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && to < from
+    {
+        return Err(DataServiceError::BadRequest(
+            "to must not be before from".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// One page of the audit log, newest first.
+pub type AuditPage = Page<AuditEntry>;
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entries: Vec<NewAuditEntry>) -> Result<(), DataServiceError>;
+    async fn find(
+        &self,
+        query: AuditQuery,
+        page: PageRequest,
+    ) -> Result<AuditPage, DataServiceError>;
+}
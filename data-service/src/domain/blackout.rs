@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::types::GroupBlackout;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBlackout {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Share of the group's staff that may be off on each day of the window, 0-100
+    pub max_off_percent: i16,
+    pub reason: Option<String>,
+}
+
+impl CreateBlackout {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        if self.end_date < self.start_date {
+            return Err(DataServiceError::BadRequest(
+                "end_date must not be before start_date".to_string(),
+            ));
+        }
+        if !(0..=100).contains(&self.max_off_percent) {
+            return Err(DataServiceError::BadRequest(
+                "max_off_percent must be between 0 and 100".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BlackoutQuery {
+    /// Only windows ending on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only windows starting on or before this date
+    pub to: Option<NaiveDate>,
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait BlackoutRepository: Send + Sync {
+    async fn create(
+        &self,
+        group_id: Uuid,
+        blackout: CreateBlackout,
+    ) -> Result<GroupBlackout, DataServiceError>;
+    /// Windows of the group overlapping the query range, ordered by start date.
+    async fn find_by_group(
+        &self,
+        group_id: Uuid,
+        query: BlackoutQuery,
+    ) -> Result<Vec<GroupBlackout>, DataServiceError>;
+    async fn delete(&self, group_id: Uuid, blackout_id: Uuid) -> Result<(), DataServiceError>;
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// Upper bound on ids per bulk delete, same as a staff lookup.
+pub const MAX_BULK_DELETE_IDS: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BulkDelete {
+    pub ids: Vec<Uuid>,
+    /// Delete records that still have memberships or child groups too. Memberships are
+    /// removed with them; child groups become top-level groups.
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+impl BulkDelete {
+    /// Drop repeated ids, keeping the first occurrence, and check the batch size.
+    pub fn validate(mut self) -> Result<Self, DataServiceError> {
+        let mut seen = std::collections::HashSet::new();
+        self.ids.retain(|id| seen.insert(*id));
+        if self.ids.is_empty() || self.ids.len() > MAX_BULK_DELETE_IDS {
+            return Err(DataServiceError::BadRequest(format!(
+                "ids must hold between 1 and {MAX_BULK_DELETE_IDS} ids"
+            )));
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BulkDeleteQuery {
+    /// Report what would happen without deleting anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BulkDeleteOutcome {
+    Deleted,
+    /// Dry run only: the record would be deleted
+    WouldDelete,
+    /// Kept because of `blocked_by`; send `cascade: true` to delete anyway
+    Blocked,
+    NotFound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockerKind {
+    /// Group memberships of the staff member, or direct members of the group
+    Memberships,
+    ChildGroups,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Blocker {
+    pub kind: BlockerKind,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct BulkDeleteResult {
+    pub id: Uuid,
+    pub outcome: BulkDeleteOutcome,
+    /// Everything still referencing the record, reported even when `cascade` deletes it
+    pub blocked_by: Vec<Blocker>,
+}
+
+impl BulkDeleteResult {
+    /// Outcome of one id from what references it, before anything is deleted.
+    pub fn assess(
+        id: Uuid,
+        found: bool,
+        blocked_by: Vec<Blocker>,
+        cascade: bool,
+        dry_run: bool,
+    ) -> Self {
+        let blocked_by: Vec<Blocker> = blocked_by.into_iter().filter(|b| b.count > 0).collect();
+        let outcome = match (found, blocked_by.is_empty() || cascade, dry_run) {
+            (false, _, _) => BulkDeleteOutcome::NotFound,
+            (true, false, _) => BulkDeleteOutcome::Blocked,
+            (true, true, true) => BulkDeleteOutcome::WouldDelete,
+            (true, true, false) => BulkDeleteOutcome::Deleted,
+        };
+        Self {
+            id,
+            outcome,
+            blocked_by,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use shared::types::Staff;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// Email similarity from which two records are reported when `min_similarity` is omitted.
+pub const DEFAULT_EMAIL_SIMILARITY: f32 = 0.8;
+pub const DEFAULT_DUPLICATE_LIMIT: i64 = 100;
+pub const MAX_DUPLICATE_LIMIT: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DuplicateQuery {
+    /// Trigram similarity of the emails, between 0 and 1, from which a pair is reported;
+    /// 0.8 by default
+    pub min_similarity: Option<f32>,
+    /// Pairs to return, 100 by default and at most 1000
+    pub limit: Option<i64>,
+}
+
+impl DuplicateQuery {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        if self.min_similarity.is_some_and(|s| !(s > 0.0 && s <= 1.0)) {
+            return Err(DataServiceError::BadRequest(
+                "min_similarity must be greater than 0 and at most 1".to_string(),
+            ));
+        }
+        if self
+            .limit
+            .is_some_and(|l| !(1..=MAX_DUPLICATE_LIMIT).contains(&l))
+        {
+            return Err(DataServiceError::BadRequest(format!(
+                "limit must be between 1 and {MAX_DUPLICATE_LIMIT}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn min_similarity(&self) -> f32 {
+        self.min_similarity.unwrap_or(DEFAULT_EMAIL_SIMILARITY)
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_DUPLICATE_LIMIT)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicateReason {
+    /// Same name once case and whitespace are ignored
+    SameName,
+    /// Emails at least `min_similarity` alike
+    SimilarEmail,
+}
+
+/// Two records that likely describe the same person, the older one first.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StaffDuplicate {
+    pub staff: Staff,
+    pub duplicate: Staff,
+    pub reasons: Vec<DuplicateReason>,
+    /// Trigram similarity of the two emails, 1 when identical apart from case
+    pub email_similarity: f32,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeStaff {
+    /// Record merged into the one in the path; it is deactivated and loses its memberships
+    pub duplicate_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MergeResult {
+    pub staff: Staff,
+    /// The merged record, now `INACTIVE`
+    pub merged: Staff,
+    /// Groups the kept record joined; groups both were in are not counted
+    pub memberships_moved: u64,
+}
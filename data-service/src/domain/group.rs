@@ -1,11 +1,17 @@
 use async_trait::async_trait;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use shared::types::StaffGroup;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::domain::bulk::{BulkDelete, BulkDeleteResult};
+use crate::domain::patch::Patch;
 use crate::error::DataServiceError;
 
+/// Levels a hierarchy may have, counting the top-level group as 1, unless `MAX_GROUP_DEPTH`
+/// says otherwise.
+pub const DEFAULT_MAX_GROUP_DEPTH: i32 = 6;
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateGroup {
     pub name: String,
@@ -15,10 +21,54 @@ pub struct CreateGroup {
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateGroup {
     pub name: Option<String>,
+    /// `Some(None)` clears the parent. In a PUT body `null` reads as "not provided", so
+    /// clearing goes through PATCH.
     #[schema(nullable)]
     pub parent_group_id: Option<Option<Uuid>>,
 }
 
+/// Merge-patch body for `PATCH /api/v1/groups/{id}`. `parent_group_id: null` makes the
+/// group top-level.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchGroup {
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Uuid>, nullable)]
+    pub parent_group_id: Patch<Uuid>,
+}
+
+impl PatchGroup {
+    pub fn into_update(self) -> Result<UpdateGroup, DataServiceError> {
+        Ok(UpdateGroup {
+            name: self.name.required("name")?,
+            parent_group_id: self.parent_group_id.nullable(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupDepthStats {
+    /// Levels of the deepest hierarchy today; 0 without groups
+    pub max_depth: i32,
+    /// Most levels a create or move may produce
+    pub limit: i32,
+}
+
+/// A group and everything below it, as returned by `GET /api/v1/groups/tree`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupNode {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_group_id: Option<Uuid>,
+    /// Staff directly in this group today, memberships starting later left out
+    pub member_count: i64,
+    /// By name
+    #[schema(no_recursion)]
+    pub children: Vec<GroupNode>,
+}
+
 #[cfg_attr(feature = "test-support", mockall::automock)]
 #[async_trait]
 pub trait GroupRepository: Send + Sync {
@@ -29,6 +79,25 @@ pub trait GroupRepository: Send + Sync {
         &self,
         groups: Vec<CreateGroup>,
     ) -> Result<Vec<StaffGroup>, DataServiceError>;
-    async fn update(&self, id: Uuid, group: UpdateGroup) -> Result<StaffGroup, DataServiceError>;
+    /// With `expected_version` set, the update only applies while the group is still at that
+    /// version.
+    async fn update(
+        &self,
+        id: Uuid,
+        group: UpdateGroup,
+        expected_version: Option<i32>,
+    ) -> Result<StaffGroup, DataServiceError>;
     async fn delete(&self, id: Uuid) -> Result<(), DataServiceError>;
+    /// One result per requested id, in request order. Nothing is deleted on a dry run.
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError>;
+    async fn depth_stats(&self) -> Result<GroupDepthStats, DataServiceError>;
+    /// Top-level groups with their subgroups nested, or just `root` and its subgroups. Empty
+    /// when `root` doesn't exist.
+    async fn tree(&self, root: Option<Uuid>) -> Result<Vec<GroupNode>, DataServiceError>;
+    /// The given groups and every group above them, without duplicates.
+    async fn lineage(&self, ids: &[Uuid]) -> Result<Vec<Uuid>, DataServiceError>;
 }
@@ -1,7 +1,9 @@
 use async_trait::async_trait;
-use serde::Deserialize;
-use shared::types::{Staff, StaffGroup};
-use utoipa::ToSchema;
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize};
+use shared::pagination::{Page, PageRequest};
+use shared::types::{GroupMember, PositionBucket, StaffGroup, StaffStatus};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::error::DataServiceError;
@@ -10,6 +12,98 @@ use crate::error::DataServiceError;
 pub struct AddMembership {
     pub staff_id: Uuid,
     pub group_id: Uuid,
+    /// First day in the group, for a membership added ahead of time. Until then member
+    /// listings only show it with `include_future`.
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RemoveMembership {
+    pub staff_id: Uuid,
+    pub group_id: Uuid,
+}
+
+/// The complete list of a group's direct members.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetMembers {
+    pub staff_ids: Vec<Uuid>,
+}
+
+/// What replacing a group's member list changed, each in id order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MembershipChanges {
+    pub added: Vec<Uuid>,
+    pub removed: Vec<Uuid>,
+}
+
+pub const DEFAULT_MEMBER_PAGE_SIZE: i64 = 500;
+pub const MAX_MEMBER_PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MemberQuery {
+    /// Only return `total`, with no items
+    #[serde(default)]
+    pub count_only: bool,
+    /// Only members with this status (`active` / `inactive`)
+    #[serde(default, deserialize_with = "deserialize_status")]
+    #[param(value_type = Option<StaffStatus>)]
+    pub status: Option<StaffStatus>,
+    /// Also list memberships that start after today
+    #[serde(default)]
+    pub include_future: bool,
+}
+
+/// Query strings are usually lowercase (`?status=active`), the JSON enum is uppercase.
+pub fn deserialize_status<'de, D>(deserializer: D) -> Result<Option<StaffStatus>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match value.to_ascii_uppercase().as_str() {
+        "ACTIVE" => Ok(Some(StaffStatus::Active)),
+        "INACTIVE" => Ok(Some(StaffStatus::Inactive)),
+        _ => Err(serde::de::Error::unknown_variant(
+            &value,
+            &["active", "inactive"],
+        )),
+    }
+}
+
+/// Page size of a member listing: 500 unless asked otherwise, at most 1000.
+pub fn member_page_limit(page: &PageRequest) -> i64 {
+    page.limit_or(DEFAULT_MEMBER_PAGE_SIZE)
+}
+
+pub fn validate_member_page(page: &PageRequest) -> Result<(), DataServiceError> {
+    page.validate(MAX_MEMBER_PAGE_SIZE)
+        .map_err(DataServiceError::BadRequest)
+}
+
+/// One page of members, ordered by name then id so paging is stable.
+pub type MemberPage = Page<GroupMember>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MembershipOutcome {
+    Inserted,
+    /// Already a member, or listed earlier in the same batch
+    Duplicate,
+    UnknownStaff,
+    UnknownGroup,
+    Removed,
+    /// Nothing to remove, including a pair listed earlier in the same batch
+    NotMember,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MembershipResult {
+    pub staff_id: Uuid,
+    pub group_id: Uuid,
+    pub outcome: MembershipOutcome,
 }
 
 #[cfg_attr(feature = "test-support", mockall::automock)]
@@ -19,17 +113,47 @@ pub trait MembershipRepository: Send + Sync {
         &self,
         group_id: Uuid,
         staff_id: Uuid,
+        valid_from: Option<NaiveDate>,
     ) -> Result<(), DataServiceError>;
     async fn remove_staff_from_group(
         &self,
         group_id: Uuid,
         staff_id: Uuid,
     ) -> Result<(), DataServiceError>;
-    async fn get_group_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError>;
+    async fn get_group_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError>;
     async fn get_staff_groups(&self, staff_id: Uuid) -> Result<Vec<StaffGroup>, DataServiceError>;
-    async fn resolve_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError>;
+    async fn resolve_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError>;
+    /// Every resolved member of the group, bucketed by position in position order. Not
+    /// paged: a bucket always holds all of its members.
+    async fn resolve_members_by_position(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+    ) -> Result<Vec<PositionBucket>, DataServiceError>;
     async fn batch_add_members(
         &self,
         memberships: Vec<AddMembership>,
-    ) -> Result<(), DataServiceError>;
+    ) -> Result<Vec<MembershipResult>, DataServiceError>;
+    async fn batch_remove_members(
+        &self,
+        memberships: Vec<RemoveMembership>,
+    ) -> Result<Vec<MembershipResult>, DataServiceError>;
+    /// Make `staff_ids` the group's direct members in one transaction. Members who stay keep
+    /// their `valid_from`; new ones start right away. Fails as a whole on an unknown group or
+    /// staff id.
+    async fn set_group_members(
+        &self,
+        group_id: Uuid,
+        staff_ids: Vec<Uuid>,
+    ) -> Result<MembershipChanges, DataServiceError>;
 }
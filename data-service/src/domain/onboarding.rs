@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// New staff whose position matches exactly are added to the group when they are created.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOnboardingRule {
+    pub position: String,
+    pub group_id: Uuid,
+}
+
+impl CreateOnboardingRule {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        if self.position.trim().is_empty() {
+            return Err(DataServiceError::BadRequest(
+                "position must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OnboardingRule {
+    pub id: Uuid,
+    pub position: String,
+    pub group_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A rule applied when a staff member was created.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AppliedOnboardingRule {
+    /// `None` once the rule has been deleted
+    pub rule_id: Option<Uuid>,
+    pub group_id: Uuid,
+    /// Position the staff member had when the rule matched
+    pub position: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait OnboardingRepository: Send + Sync {
+    async fn create(&self, rule: CreateOnboardingRule) -> Result<OnboardingRule, DataServiceError>;
+    /// Ordered by position, then creation.
+    async fn find_all(&self) -> Result<Vec<OnboardingRule>, DataServiceError>;
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError>;
+    /// Rules applied to the staff member, oldest first.
+    async fn find_applied(
+        &self,
+        staff_id: Uuid,
+    ) -> Result<Vec<AppliedOnboardingRule>, DataServiceError>;
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::error::DataServiceError;
+
+/// One member of an RFC 7396 merge patch. Fields using it need `#[serde(default)]` so a
+/// left-out member stays `Absent` instead of reading as `null`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Self::Value(value),
+            None => Self::Null,
+        })
+    }
+}
+
+impl<T> Patch<T> {
+    /// For a column that can't be cleared: the new value, if any. `null` is a 400.
+    pub fn required(self, field: &str) -> Result<Option<T>, DataServiceError> {
+        match self {
+            Self::Absent => Ok(None),
+            Self::Null => Err(DataServiceError::BadRequest(format!(
+                "{field} cannot be null"
+            ))),
+            Self::Value(value) => Ok(Some(value)),
+        }
+    }
+
+    /// For a nullable column: `None` leaves it as is, `Some(None)` clears it.
+    pub fn nullable(self) -> Option<Option<T>> {
+        match self {
+            Self::Absent => None,
+            Self::Null => Some(None),
+            Self::Value(value) => Some(Some(value)),
+        }
+    }
+}
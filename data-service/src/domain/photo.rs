@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// Photos are shown as small roster avatars, so anything larger is almost certainly
+/// an unresized camera original.
+pub const MAX_PHOTO_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct StaffPhoto {
+    pub content_type: &'static str,
+    pub bytes: Bytes,
+    pub etag: Option<String>,
+    pub last_modified: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait PhotoStorage: Send + Sync {
+    async fn put(&self, staff_id: Uuid, bytes: Bytes) -> Result<(), DataServiceError>;
+    async fn get(&self, staff_id: Uuid) -> Result<Option<StaffPhoto>, DataServiceError>;
+    async fn delete(&self, staff_id: Uuid) -> Result<(), DataServiceError>;
+}
+
+/// Detect the image type from its magic bytes. The declared `Content-Type` is not trusted.
+pub fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+pub fn validate_photo(bytes: &[u8]) -> Result<&'static str, DataServiceError> {
+    if bytes.is_empty() {
+        return Err(DataServiceError::BadRequest("Photo is empty".to_string()));
+    }
+    if bytes.len() > MAX_PHOTO_BYTES {
+        return Err(DataServiceError::BadRequest(format!(
+            "Photo must be at most {MAX_PHOTO_BYTES} bytes"
+        )));
+    }
+    sniff_image_type(bytes).ok_or_else(|| {
+        DataServiceError::BadRequest("Photo must be a JPEG, PNG or WebP image".to_string())
+    })
+}
@@ -1,16 +1,42 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::Deserialize;
-use shared::types::{Staff, StaffStatus};
-use utoipa::ToSchema;
+use shared::pagination::{Page, PageRequest};
+use shared::types::{ShiftEligibility, Staff, StaffStatus};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::domain::bulk::{BulkDelete, BulkDeleteResult};
+use crate::domain::duplicate::{DuplicateQuery, MergeResult, StaffDuplicate};
+use crate::domain::membership::deserialize_status;
+use crate::domain::patch::Patch;
 use crate::error::DataServiceError;
 
+/// Upper bound on `fairness_weight`. The scheduler divides by it, so it is kept within a
+/// range where one person can't absorb every weekend preference.
+pub const MAX_FAIRNESS_WEIGHT: f64 = 10.0;
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateStaff {
     pub name: String,
     pub email: String,
     pub position: String,
+    /// Seniority / FTE weight for scheduling fairness, defaults to 1.0
+    #[serde(default = "default_fairness_weight")]
+    pub fairness_weight: f64,
+    /// Shifts the staff member can work, `ANY` by default
+    #[serde(default)]
+    pub shift_eligibility: ShiftEligibility,
+    /// Last working day, for someone who is already known to be leaving
+    pub effective_until: Option<NaiveDate>,
+}
+
+fn default_fairness_weight() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -19,16 +45,167 @@ pub struct UpdateStaff {
     pub email: Option<String>,
     pub position: Option<String>,
     pub status: Option<StaffStatus>,
+    pub fairness_weight: Option<f64>,
+    pub shift_eligibility: Option<ShiftEligibility>,
+    /// `Some(None)` withdraws a termination. In a PUT body `null` reads as "not provided", so
+    /// withdrawing goes through PATCH.
+    #[schema(nullable)]
+    pub effective_until: Option<Option<NaiveDate>>,
+}
+
+/// Merge-patch body for `PATCH /api/v1/staff/{id}`. Only `effective_until` can be `null`,
+/// which withdraws a termination.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PatchStaff {
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub email: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub position: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<StaffStatus>)]
+    pub status: Patch<StaffStatus>,
+    #[serde(default)]
+    #[schema(value_type = Option<f64>)]
+    pub fairness_weight: Patch<f64>,
+    #[serde(default)]
+    #[schema(value_type = Option<ShiftEligibility>)]
+    pub shift_eligibility: Patch<ShiftEligibility>,
+    #[serde(default)]
+    #[schema(value_type = Option<NaiveDate>, nullable)]
+    pub effective_until: Patch<NaiveDate>,
+}
+
+impl PatchStaff {
+    pub fn into_update(self) -> Result<UpdateStaff, DataServiceError> {
+        Ok(UpdateStaff {
+            name: self.name.required("name")?,
+            email: self.email.required("email")?,
+            position: self.position.required("position")?,
+            status: self.status.required("status")?,
+            fairness_weight: self.fairness_weight.required("fairness_weight")?,
+            shift_eligibility: self.shift_eligibility.required("shift_eligibility")?,
+            effective_until: self.effective_until.nullable(),
+        })
+    }
+}
+
+pub fn validate_fairness_weight(weight: f64) -> Result<(), DataServiceError> {
+    if weight > 0.0 && weight <= MAX_FAIRNESS_WEIGHT {
+        Ok(())
+    } else {
+        Err(DataServiceError::BadRequest(format!(
+            "fairness_weight must be greater than 0 and at most {MAX_FAIRNESS_WEIGHT}"
+        )))
+    }
+}
+
+/// Upper bound on ids per lookup request, keeping the `ANY($1)` array reasonable.
+pub const MAX_LOOKUP_IDS: usize = 1000;
+
+pub const DEFAULT_STAFF_PAGE_SIZE: i64 = 100;
+pub const MAX_STAFF_PAGE_SIZE: i64 = 1000;
+
+/// Longest `q` accepted, in characters.
+pub const MAX_STAFF_SEARCH_CHARS: usize = 100;
+
+/// Filters of the staff listing; all given ones must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StaffQuery {
+    /// Only staff with this status (`active` / `inactive`)
+    #[serde(default, deserialize_with = "deserialize_status")]
+    #[param(value_type = Option<StaffStatus>)]
+    pub status: Option<StaffStatus>,
+    /// Only staff with exactly this position
+    pub position: Option<String>,
+    /// Case-insensitive substring of the name or email
+    pub q: Option<String>,
+}
+
+/// Page size of the staff listing: 100 unless asked otherwise, at most 1000.
+pub fn staff_page_limit(page: &PageRequest) -> i64 {
+    page.limit_or(DEFAULT_STAFF_PAGE_SIZE)
+}
+
+pub fn validate_staff_query(
+    query: &StaffQuery,
+    page: &PageRequest,
+) -> Result<(), DataServiceError> {
+    page.validate(MAX_STAFF_PAGE_SIZE)
+        .map_err(DataServiceError::BadRequest)?;
+    if let Some(q) = &query.q
+        && (q.trim().is_empty() || q.chars().count() > MAX_STAFF_SEARCH_CHARS)
+    {
+        return Err(DataServiceError::BadRequest(format!(
+            "q must be between 1 and {MAX_STAFF_SEARCH_CHARS} characters"
+        )));
+    }
+    Ok(())
 }
 
+/// One page of staff, ordered by name then id so paging is stable.
+pub type StaffPage = Page<Staff>;
+
 #[cfg_attr(feature = "test-support", mockall::automock)]
 #[async_trait]
 pub trait StaffRepository: Send + Sync {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Staff>, DataServiceError>;
-    async fn find_all(&self) -> Result<Vec<Staff>, DataServiceError>;
+    async fn find_all(
+        &self,
+        query: StaffQuery,
+        page: PageRequest,
+    ) -> Result<StaffPage, DataServiceError>;
+    /// Staff matching any of `ids`; unknown ids are skipped.
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, DataServiceError>;
     async fn create(&self, staff: CreateStaff) -> Result<Staff, DataServiceError>;
     async fn batch_create(&self, staffs: Vec<CreateStaff>) -> Result<Vec<Staff>, DataServiceError>;
-    async fn update(&self, id: Uuid, staff: UpdateStaff) -> Result<Staff, DataServiceError>;
+    /// With `expected_version` set, the update only applies while the staff member is still
+    /// at that version.
+    async fn update(
+        &self,
+        id: Uuid,
+        staff: UpdateStaff,
+        expected_version: Option<i32>,
+    ) -> Result<Staff, DataServiceError>;
     async fn deactivate(&self, id: Uuid) -> Result<(), DataServiceError>;
+    /// Set active staff whose `effective_until` is before `today` to inactive. Returns the
+    /// staff it changed.
+    async fn deactivate_expired(&self, today: NaiveDate) -> Result<Vec<Staff>, DataServiceError>;
     async fn delete(&self, id: Uuid) -> Result<(), DataServiceError>;
+    /// One result per requested id, in request order. Nothing is deleted on a dry run.
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError>;
+    /// Likely duplicate pairs, same names first, then by email similarity. Records merged
+    /// into another one are left out.
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<StaffDuplicate>, DataServiceError>;
+    /// Move `duplicate_id`'s memberships to `id` and deactivate it.
+    async fn merge(&self, id: Uuid, duplicate_id: Uuid) -> Result<MergeResult, DataServiceError>;
+}
+
+/// Deactivate staff past their `effective_until`, on `interval`. "Past" is by the calendar in
+/// `timezone`. Safe to run on every replica: a row is only flipped once.
+pub async fn run_staff_expiry(repo: Arc<dyn StaffRepository>, timezone: Tz, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match repo
+            .deactivate_expired(shared::time::today_in(timezone))
+            .await
+        {
+            Ok(expired) if expired.is_empty() => {}
+            Ok(expired) => tracing::info!(count = expired.len(), "Deactivated departed staff"),
+            Err(e) => tracing::warn!("Staff expiry failed: {e}"),
+        }
+    }
 }
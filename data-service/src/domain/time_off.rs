@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::types::{TimeOffRequest, TimeOffStatus};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+/// Longest time off a single request can cover, in days.
+pub const MAX_TIME_OFF_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTimeOff {
+    pub start_date: NaiveDate,
+    /// Inclusive, the same as `start_date` for a single day
+    pub end_date: NaiveDate,
+    pub reason: Option<String>,
+}
+
+impl CreateTimeOff {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        validate_dates(self.start_date, self.end_date)
+    }
+}
+
+/// Changes to a request, e.g. `{ "status": "APPROVED" }` to approve it.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateTimeOff {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub reason: Option<String>,
+    pub status: Option<TimeOffStatus>,
+}
+
+impl UpdateTimeOff {
+    /// Only checks the range when both ends are given; the database rejects an inverted range
+    /// made from one new date and one stored one.
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        match (self.start_date, self.end_date) {
+            (Some(start), Some(end)) => validate_dates(start, end),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn validate_dates(start_date: NaiveDate, end_date: NaiveDate) -> Result<(), DataServiceError> {
+    if end_date < start_date {
+        return Err(DataServiceError::BadRequest(
+            "end_date must not be before start_date".to_string(),
+        ));
+    }
+    if (end_date - start_date).num_days() >= MAX_TIME_OFF_DAYS {
+        return Err(DataServiceError::BadRequest(format!(
+            "Time off can cover at most {MAX_TIME_OFF_DAYS} days"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeOffQuery {
+    /// Only requests with this status
+    pub status: Option<TimeOffStatus>,
+    /// Only requests ending on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only requests starting on or before this date
+    pub to: Option<NaiveDate>,
+}
+
+/// Days the scheduler asks about, usually the periods it is generating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeOffWindow {
+    pub from: NaiveDate,
+    /// Inclusive
+    pub to: NaiveDate,
+}
+
+impl TimeOffWindow {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        if self.to < self.from {
+            return Err(DataServiceError::BadRequest(
+                "to must not be before from".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait TimeOffRepository: Send + Sync {
+    async fn create(
+        &self,
+        staff_id: Uuid,
+        request: CreateTimeOff,
+    ) -> Result<TimeOffRequest, DataServiceError>;
+    /// Requests of the staff member matching the query, ordered by start date.
+    async fn find_by_staff(
+        &self,
+        staff_id: Uuid,
+        query: TimeOffQuery,
+    ) -> Result<Vec<TimeOffRequest>, DataServiceError>;
+    async fn update(
+        &self,
+        staff_id: Uuid,
+        request_id: Uuid,
+        update: UpdateTimeOff,
+    ) -> Result<TimeOffRequest, DataServiceError>;
+    async fn delete(&self, staff_id: Uuid, request_id: Uuid) -> Result<(), DataServiceError>;
+    /// Approved requests of the group's resolved members overlapping `from..=to`, ordered by
+    /// staff and start date.
+    async fn find_approved_for_group(
+        &self,
+        group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TimeOffRequest>, DataServiceError>;
+}
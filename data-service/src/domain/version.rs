@@ -0,0 +1,48 @@
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderName, header},
+};
+use serde::Serialize;
+use shared::responses::ApiResponse;
+
+use crate::error::DataServiceError;
+
+/// A single-row response with the row's version as its ETag.
+pub type Versioned<T> = ([(HeaderName, String); 1], Json<ApiResponse<T>>);
+
+/// `body` with a strong ETag for `version`, which changes with every update.
+pub fn versioned<T: Serialize>(version: i32, body: T) -> Versioned<T> {
+    (
+        [(header::ETAG, format!("\"{version}\""))],
+        Json(ApiResponse::ok(body)),
+    )
+}
+
+/// The version an update was made against, from its `If-Match` header. `None` when the
+/// header is absent or `*`, so the update goes ahead whatever the current version.
+pub fn if_match(headers: &HeaderMap) -> Result<Option<i32>, DataServiceError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let invalid = || DataServiceError::BadRequest("If-Match must be a single ETag".to_string());
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .and_then(|v| v.parse().ok())
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+/// The error for an update that matched no row: the row is gone, or `If-Match` named a
+/// version it has moved on from.
+pub fn missed_update(what: &str, exists: bool) -> DataServiceError {
+    if exists {
+        DataServiceError::PreconditionFailed(format!("{what} has changed since the given ETag"))
+    } else {
+        DataServiceError::NotFound(format!("{what} not found"))
+    }
+}
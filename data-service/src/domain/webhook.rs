@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::Staff;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::DataServiceError;
+
+pub const DEFAULT_MAX_RETRIES: i16 = 5;
+pub const MAX_RETRIES_LIMIT: i16 = 10;
+const MIN_SECRET_CHARS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StaffEventType {
+    #[serde(rename = "staff.created")]
+    Created,
+    #[serde(rename = "staff.updated")]
+    Updated,
+    #[serde(rename = "staff.deactivated")]
+    Deactivated,
+    #[serde(rename = "staff.deleted")]
+    Deleted,
+}
+
+impl StaffEventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "staff.created",
+            Self::Updated => "staff.updated",
+            Self::Deactivated => "staff.deactivated",
+            Self::Deleted => "staff.deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::Created,
+            Self::Updated,
+            Self::Deactivated,
+            Self::Deleted,
+        ]
+        .into_iter()
+        .find(|event| event.as_str() == value)
+    }
+}
+
+/// Body POSTed to subscribers. `staff` is the record after the change, or the last state
+/// before it for `staff.deleted`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StaffEvent {
+    pub event: StaffEventType,
+    pub staff: Staff,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhook {
+    /// http(s) URL the events are POSTed to
+    pub url: String,
+    /// Key for the `X-Webhook-Signature` HMAC-SHA256 of each body, at least 16 characters
+    pub secret: String,
+    pub event_types: Vec<StaffEventType>,
+    /// Attempts after the first failed one before a delivery is dropped, 0-10 (default 5)
+    pub max_retries: Option<i16>,
+}
+
+impl CreateWebhook {
+    pub fn validate(&self) -> Result<(), DataServiceError> {
+        let scheme_ok = self
+            .url
+            .split_once("://")
+            .is_some_and(|(scheme, rest)| matches!(scheme, "http" | "https") && !rest.is_empty());
+        if !scheme_ok {
+            return Err(DataServiceError::BadRequest(
+                "url must be an http or https URL".to_string(),
+            ));
+        }
+        if self.secret.chars().count() < MIN_SECRET_CHARS {
+            return Err(DataServiceError::BadRequest(format!(
+                "secret must be at least {MIN_SECRET_CHARS} characters"
+            )));
+        }
+        if self.event_types.is_empty() {
+            return Err(DataServiceError::BadRequest(
+                "event_types must not be empty".to_string(),
+            ));
+        }
+        if !(0..=MAX_RETRIES_LIMIT).contains(&self.max_retries()) {
+            return Err(DataServiceError::BadRequest(format!(
+                "max_retries must be between 0 and {MAX_RETRIES_LIMIT}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn max_retries(&self) -> i16 {
+        self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+    }
+}
+
+/// A subscription as returned by the API; the secret is write-only.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<StaffEventType>,
+    pub max_retries: i16,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create(&self, webhook: CreateWebhook)
+    -> Result<WebhookSubscription, DataServiceError>;
+    async fn find_all(&self) -> Result<Vec<WebhookSubscription>, DataServiceError>;
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError>;
+}
@@ -2,6 +2,7 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::response::Response;
 use shared::responses::ApiResponse;
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 
 // Data Service Error
@@ -16,6 +17,12 @@ pub enum DataServiceError {
     #[error("Bad Request: {0}")]
     BadRequest(String),
 
+    #[error("Unprocessable: {0}")]
+    Unprocessable(String),
+
+    #[error("Precondition Failed: {0}")]
+    PreconditionFailed(String),
+
     #[error("Internal Server Error: {0}")]
     Internal(String),
 
@@ -23,12 +30,31 @@ pub enum DataServiceError {
     Database(#[from] sqlx::Error),
 }
 
+impl DataServiceError {
+    /// Maps a constraint violation by its SQLSTATE class and constraint name, which stay put
+    /// when the server's message wording or locale changes. `map` gets both and returns the
+    /// error to report, or `None` to keep it a database error.
+    pub fn from_violation(
+        e: sqlx::Error,
+        map: impl FnOnce(ErrorKind, Option<&str>) -> Option<Self>,
+    ) -> Self {
+        if let sqlx::Error::Database(db) = &e
+            && let Some(mapped) = map(db.kind(), db.constraint())
+        {
+            return mapped;
+        }
+        e.into()
+    }
+}
+
 impl IntoResponse for DataServiceError {
     fn into_response(self) -> Response {
         let (status, message) = match &self {
             Self::NotFound(message) => (StatusCode::NOT_FOUND, message.clone()),
             Self::Conflict(message) => (StatusCode::CONFLICT, message.clone()),
             Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message.clone()),
+            Self::Unprocessable(message) => (StatusCode::UNPROCESSABLE_ENTITY, message.clone()),
+            Self::PreconditionFailed(message) => (StatusCode::PRECONDITION_FAILED, message.clone()),
             Self::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message.clone()),
             Self::Database(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -1,4 +1,15 @@
+pub mod audit;
+pub mod blackout;
 pub mod cache;
 pub mod group;
+pub mod health;
 pub mod membership;
+pub mod onboarding;
+pub mod outbox;
+pub mod photo;
 pub mod staff;
+pub mod time_off;
+pub mod webhook;
+
+/// Migrations embedded at build time: run on boot, then compared by the startup self-check.
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
@@ -0,0 +1,638 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde_json::json;
+use shared::pagination::PageRequest;
+use shared::types::{PositionBucket, Staff, StaffGroup};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::audit::{
+    AuditAction, AuditEntity, AuditEntry, AuditLog, AuditPage, AuditQuery, NewAuditEntry,
+    audit_page_limit,
+};
+use crate::domain::bulk::{BulkDelete, BulkDeleteOutcome, BulkDeleteResult};
+use crate::domain::duplicate::{DuplicateQuery, MergeResult, StaffDuplicate};
+use crate::domain::group::{CreateGroup, GroupDepthStats, GroupNode, GroupRepository, UpdateGroup};
+use crate::domain::membership::{
+    AddMembership, MemberPage, MemberQuery, MembershipChanges, MembershipOutcome,
+    MembershipRepository, MembershipResult, RemoveMembership,
+};
+use crate::domain::staff::{CreateStaff, StaffPage, StaffQuery, StaffRepository, UpdateStaff};
+use crate::error::DataServiceError;
+
+pub struct PgAuditLog {
+    pool: PgPool,
+}
+
+impl PgAuditLog {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLog for PgAuditLog {
+    #[tracing::instrument(skip(self, entries), fields(count = entries.len()))]
+    async fn record(&self, entries: Vec<NewAuditEntry>) -> Result<(), DataServiceError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let entity_types: Vec<AuditEntity> = entries.iter().map(|e| e.entity_type).collect();
+        let entity_ids: Vec<Uuid> = entries.iter().map(|e| e.entity_id).collect();
+        let actions: Vec<AuditAction> = entries.iter().map(|e| e.action).collect();
+        let actors: Vec<String> = entries.iter().map(|e| e.actor.clone()).collect();
+        let befores: Vec<Option<serde_json::Value>> =
+            entries.iter().map(|e| e.before.clone()).collect();
+        let afters: Vec<Option<serde_json::Value>> =
+            entries.iter().map(|e| e.after.clone()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (entity_type, entity_id, action, actor, before, after)
+            SELECT * FROM UNNEST($1::audit_entity[], $2::uuid[], $3::audit_action[], $4::text[], $5::jsonb[], $6::jsonb[])
+            "#,
+        )
+        .bind(&entity_types)
+        .bind(&entity_ids)
+        .bind(&actions)
+        .bind(&actors)
+        .bind(&befores)
+        .bind(&afters)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find(
+        &self,
+        query: AuditQuery,
+        page: PageRequest,
+    ) -> Result<AuditPage, DataServiceError> {
+        let limit = audit_page_limit(&page);
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM audit_log
+            WHERE ($1::audit_entity IS NULL OR entity_type = $1)
+                AND ($2::uuid IS NULL OR entity_id = $2)
+                AND ($3::audit_action IS NULL OR action = $3)
+                AND ($4::text IS NULL OR actor = $4)
+                AND ($5::timestamptz IS NULL OR changed_at >= $5)
+                AND ($6::timestamptz IS NULL OR changed_at < $6)
+            "#,
+            query.entity_type as Option<AuditEntity>,
+            query.entity_id,
+            query.action as Option<AuditAction>,
+            query.actor,
+            query.from,
+            query.to
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let items = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT id, entity_type AS "entity_type: _", entity_id, action AS "action: _", actor, before, after, changed_at
+            FROM audit_log
+            WHERE ($1::audit_entity IS NULL OR entity_type = $1)
+                AND ($2::uuid IS NULL OR entity_id = $2)
+                AND ($3::audit_action IS NULL OR action = $3)
+                AND ($4::text IS NULL OR actor = $4)
+                AND ($5::timestamptz IS NULL OR changed_at >= $5)
+                AND ($6::timestamptz IS NULL OR changed_at < $6)
+            ORDER BY changed_at DESC, id DESC
+            LIMIT $7 OFFSET $8
+            "#,
+            query.entity_type as Option<AuditEntity>,
+            query.entity_id,
+            query.action as Option<AuditAction>,
+            query.actor,
+            query.from,
+            query.to,
+            limit,
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(AuditPage {
+            items,
+            total,
+            limit,
+            offset: page.offset(),
+        })
+    }
+}
+
+/// The change already happened, so a failed write to the log is reported rather than
+/// returned.
+async fn record(log: &dyn AuditLog, entries: Vec<NewAuditEntry>) {
+    if entries.is_empty() {
+        return;
+    }
+    if let Err(e) = log.record(entries).await {
+        tracing::error!("Failed to write the audit log: {e}");
+    }
+}
+
+fn deleted_ids(results: &[BulkDeleteResult]) -> impl Iterator<Item = Uuid> + '_ {
+    results
+        .iter()
+        .filter(|result| result.outcome == BulkDeleteOutcome::Deleted)
+        .map(|result| result.id)
+}
+
+/// Records every staff write in the audit log, with the record before and after.
+pub struct AuditedStaffRepository {
+    inner: Arc<dyn StaffRepository>,
+    log: Arc<dyn AuditLog>,
+}
+
+impl AuditedStaffRepository {
+    pub fn new(inner: Arc<dyn StaffRepository>, log: Arc<dyn AuditLog>) -> Self {
+        Self { inner, log }
+    }
+
+    async fn record_update(&self, before: Option<Staff>, after: &Staff) {
+        let entry = NewAuditEntry::new(
+            AuditEntity::Staff,
+            after.id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+    }
+}
+
+#[async_trait]
+impl StaffRepository for AuditedStaffRepository {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Staff>, DataServiceError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_all(
+        &self,
+        query: StaffQuery,
+        page: PageRequest,
+    ) -> Result<StaffPage, DataServiceError> {
+        self.inner.find_all(query, page).await
+    }
+
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, DataServiceError> {
+        self.inner.find_by_ids(ids).await
+    }
+
+    async fn create(&self, staff: CreateStaff) -> Result<Staff, DataServiceError> {
+        let output = self.inner.create(staff).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Staff,
+            output.id,
+            AuditAction::Create,
+            None::<&Staff>,
+            Some(&output),
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(output)
+    }
+
+    async fn batch_create(&self, staffs: Vec<CreateStaff>) -> Result<Vec<Staff>, DataServiceError> {
+        let output = self.inner.batch_create(staffs).await?;
+        let entries = output
+            .iter()
+            .map(|staff| {
+                NewAuditEntry::new(
+                    AuditEntity::Staff,
+                    staff.id,
+                    AuditAction::Create,
+                    None::<&Staff>,
+                    Some(staff),
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        staff: UpdateStaff,
+        expected_version: Option<i32>,
+    ) -> Result<Staff, DataServiceError> {
+        let before = self.inner.find_by_id(id).await?;
+        let output = self.inner.update(id, staff, expected_version).await?;
+        self.record_update(before, &output).await;
+
+        Ok(output)
+    }
+
+    async fn deactivate(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let before = self.inner.find_by_id(id).await?;
+        self.inner.deactivate(id).await?;
+        if let Some(after) = self.inner.find_by_id(id).await? {
+            self.record_update(before, &after).await;
+        }
+
+        Ok(())
+    }
+
+    async fn deactivate_expired(&self, today: NaiveDate) -> Result<Vec<Staff>, DataServiceError> {
+        let output = self.inner.deactivate_expired(today).await?;
+        let entries = output
+            .iter()
+            .map(|staff| {
+                // Only the status changed; the rest of the record is the same before
+                let before = json!({ "status": "ACTIVE" });
+                NewAuditEntry::new(
+                    AuditEntity::Staff,
+                    staff.id,
+                    AuditAction::Update,
+                    Some(&before),
+                    Some(staff),
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let before = self.inner.find_by_id(id).await?;
+        self.inner.delete(id).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Staff,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None::<&Staff>,
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(())
+    }
+
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        if dry_run {
+            return self.inner.bulk_delete(request, dry_run).await;
+        }
+        let before: HashMap<Uuid, Staff> = self
+            .inner
+            .find_by_ids(request.ids.clone())
+            .await?
+            .into_iter()
+            .map(|staff| (staff.id, staff))
+            .collect();
+        let output = self.inner.bulk_delete(request, dry_run).await?;
+        let entries = deleted_ids(&output)
+            .map(|id| {
+                NewAuditEntry::new(
+                    AuditEntity::Staff,
+                    id,
+                    AuditAction::Delete,
+                    before.get(&id),
+                    None::<&Staff>,
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<StaffDuplicate>, DataServiceError> {
+        self.inner.find_duplicates(query).await
+    }
+
+    async fn merge(&self, id: Uuid, duplicate_id: Uuid) -> Result<MergeResult, DataServiceError> {
+        let before = self.inner.find_by_id(duplicate_id).await?;
+        let output = self.inner.merge(id, duplicate_id).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Staff,
+            duplicate_id,
+            AuditAction::Merge,
+            before.as_ref(),
+            Some(&output),
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(output)
+    }
+}
+
+/// Records every group write in the audit log, with the record before and after. Members
+/// a deleted group takes with it are not recorded one by one.
+pub struct AuditedGroupRepository {
+    inner: Arc<dyn GroupRepository>,
+    log: Arc<dyn AuditLog>,
+}
+
+impl AuditedGroupRepository {
+    pub fn new(inner: Arc<dyn GroupRepository>, log: Arc<dyn AuditLog>) -> Self {
+        Self { inner, log }
+    }
+}
+
+#[async_trait]
+impl GroupRepository for AuditedGroupRepository {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<StaffGroup>, DataServiceError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<StaffGroup>, DataServiceError> {
+        self.inner.find_all().await
+    }
+
+    async fn create(&self, group: CreateGroup) -> Result<StaffGroup, DataServiceError> {
+        let output = self.inner.create(group).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Group,
+            output.id,
+            AuditAction::Create,
+            None::<&StaffGroup>,
+            Some(&output),
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(output)
+    }
+
+    async fn batch_create(
+        &self,
+        groups: Vec<CreateGroup>,
+    ) -> Result<Vec<StaffGroup>, DataServiceError> {
+        let output = self.inner.batch_create(groups).await?;
+        let entries = output
+            .iter()
+            .map(|group| {
+                NewAuditEntry::new(
+                    AuditEntity::Group,
+                    group.id,
+                    AuditAction::Create,
+                    None::<&StaffGroup>,
+                    Some(group),
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        group: UpdateGroup,
+        expected_version: Option<i32>,
+    ) -> Result<StaffGroup, DataServiceError> {
+        let before = self.inner.find_by_id(id).await?;
+        let output = self.inner.update(id, group, expected_version).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Group,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(&output),
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(output)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let before = self.inner.find_by_id(id).await?;
+        self.inner.delete(id).await?;
+        let entry = NewAuditEntry::new(
+            AuditEntity::Group,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None::<&StaffGroup>,
+        );
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(())
+    }
+
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        if dry_run {
+            return self.inner.bulk_delete(request, dry_run).await;
+        }
+        let before: HashMap<Uuid, StaffGroup> = self
+            .inner
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|group| request.ids.contains(&group.id))
+            .map(|group| (group.id, group))
+            .collect();
+        let output = self.inner.bulk_delete(request, dry_run).await?;
+        let entries = deleted_ids(&output)
+            .map(|id| {
+                NewAuditEntry::new(
+                    AuditEntity::Group,
+                    id,
+                    AuditAction::Delete,
+                    before.get(&id),
+                    None::<&StaffGroup>,
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn depth_stats(&self) -> Result<GroupDepthStats, DataServiceError> {
+        self.inner.depth_stats().await
+    }
+
+    async fn tree(&self, root: Option<Uuid>) -> Result<Vec<GroupNode>, DataServiceError> {
+        self.inner.tree(root).await
+    }
+
+    async fn lineage(&self, ids: &[Uuid]) -> Result<Vec<Uuid>, DataServiceError> {
+        self.inner.lineage(ids).await
+    }
+}
+
+/// Records every membership added or removed in the audit log, under the group.
+pub struct AuditedMembershipRepository {
+    inner: Arc<dyn MembershipRepository>,
+    log: Arc<dyn AuditLog>,
+}
+
+impl AuditedMembershipRepository {
+    pub fn new(inner: Arc<dyn MembershipRepository>, log: Arc<dyn AuditLog>) -> Self {
+        Self { inner, log }
+    }
+}
+
+fn membership_entry(
+    group_id: Uuid,
+    staff_id: Uuid,
+    valid_from: Option<NaiveDate>,
+    action: AuditAction,
+) -> NewAuditEntry {
+    let membership = json!({
+        "group_id": group_id,
+        "staff_id": staff_id,
+        "valid_from": valid_from,
+    });
+    let (before, after) = match action {
+        AuditAction::Delete => (Some(&membership), None),
+        _ => (None, Some(&membership)),
+    };
+    NewAuditEntry::new(AuditEntity::Membership, group_id, action, before, after)
+}
+
+#[async_trait]
+impl MembershipRepository for AuditedMembershipRepository {
+    async fn add_staff_to_group(
+        &self,
+        group_id: Uuid,
+        staff_id: Uuid,
+        valid_from: Option<NaiveDate>,
+    ) -> Result<(), DataServiceError> {
+        self.inner
+            .add_staff_to_group(group_id, staff_id, valid_from)
+            .await?;
+        let entry = membership_entry(group_id, staff_id, valid_from, AuditAction::Create);
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(())
+    }
+
+    async fn remove_staff_from_group(
+        &self,
+        group_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<(), DataServiceError> {
+        self.inner
+            .remove_staff_from_group(group_id, staff_id)
+            .await?;
+        let entry = membership_entry(group_id, staff_id, None, AuditAction::Delete);
+        record(self.log.as_ref(), vec![entry]).await;
+
+        Ok(())
+    }
+
+    async fn get_group_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        self.inner.get_group_members(group_id, query, page).await
+    }
+
+    async fn get_staff_groups(&self, staff_id: Uuid) -> Result<Vec<StaffGroup>, DataServiceError> {
+        self.inner.get_staff_groups(staff_id).await
+    }
+
+    async fn resolve_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        self.inner.resolve_members(group_id, query, page).await
+    }
+
+    async fn resolve_members_by_position(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+    ) -> Result<Vec<PositionBucket>, DataServiceError> {
+        self.inner
+            .resolve_members_by_position(group_id, query)
+            .await
+    }
+
+    async fn batch_add_members(
+        &self,
+        memberships: Vec<AddMembership>,
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
+        // Reversed so a pair listed twice keeps its first start date, the one inserted
+        let valid_from: HashMap<(Uuid, Uuid), Option<NaiveDate>> = memberships
+            .iter()
+            .rev()
+            .map(|m| ((m.group_id, m.staff_id), m.valid_from))
+            .collect();
+        let output = self.inner.batch_add_members(memberships).await?;
+        let entries = output
+            .iter()
+            .filter(|result| result.outcome == MembershipOutcome::Inserted)
+            .map(|result| {
+                let valid_from = valid_from
+                    .get(&(result.group_id, result.staff_id))
+                    .copied()
+                    .flatten();
+                membership_entry(
+                    result.group_id,
+                    result.staff_id,
+                    valid_from,
+                    AuditAction::Create,
+                )
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+    async fn batch_remove_members(
+        &self,
+        memberships: Vec<RemoveMembership>,
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
+        let output = self.inner.batch_remove_members(memberships).await?;
+        let entries = output
+            .iter()
+            .filter(|result| result.outcome == MembershipOutcome::Removed)
+            .map(|result| {
+                membership_entry(result.group_id, result.staff_id, None, AuditAction::Delete)
+            })
+            .collect();
+        record(self.log.as_ref(), entries).await;
+
+        Ok(output)
+    }
+
+    async fn set_group_members(
+        &self,
+        group_id: Uuid,
+        staff_ids: Vec<Uuid>,
+    ) -> Result<MembershipChanges, DataServiceError> {
+        let output = self.inner.set_group_members(group_id, staff_ids).await?;
+        let added = output
+            .added
+            .iter()
+            .map(|&staff_id| membership_entry(group_id, staff_id, None, AuditAction::Create));
+        let removed = output
+            .removed
+            .iter()
+            .map(|&staff_id| membership_entry(group_id, staff_id, None, AuditAction::Delete));
+        record(self.log.as_ref(), added.chain(removed).collect()).await;
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use shared::types::GroupBlackout;
+use sqlx::{PgPool, error::ErrorKind};
+use uuid::Uuid;
+
+use crate::{
+    domain::blackout::{BlackoutQuery, BlackoutRepository, CreateBlackout},
+    error::DataServiceError,
+};
+
+pub struct PgBlackoutRepository {
+    pool: PgPool,
+}
+
+impl PgBlackoutRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BlackoutRepository for PgBlackoutRepository {
+    #[tracing::instrument(skip(self))]
+    async fn create(
+        &self,
+        group_id: Uuid,
+        blackout: CreateBlackout,
+    ) -> Result<GroupBlackout, DataServiceError> {
+        let output = sqlx::query_as!(
+            GroupBlackout,
+            r#"
+            INSERT INTO group_blackouts (group_id, start_date, end_date, max_off_percent, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, group_id, start_date, end_date, max_off_percent, reason, created_at
+            "#,
+            group_id,
+            blackout.start_date,
+            blackout.end_date,
+            blackout.max_off_percent,
+            blackout.reason
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        output.map_err(|e| {
+            DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+                (ErrorKind::ForeignKeyViolation, Some("fk_gb_group")) => {
+                    Some(DataServiceError::NotFound("Group not found".to_string()))
+                }
+                _ => None,
+            })
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_by_group(
+        &self,
+        group_id: Uuid,
+        query: BlackoutQuery,
+    ) -> Result<Vec<GroupBlackout>, DataServiceError> {
+        let output = sqlx::query_as!(
+            GroupBlackout,
+            r#"
+            SELECT id, group_id, start_date, end_date, max_off_percent, reason, created_at
+            FROM group_blackouts
+            WHERE group_id = $1
+              AND ($2::date IS NULL OR end_date >= $2)
+              AND ($3::date IS NULL OR start_date <= $3)
+            ORDER BY start_date, id
+            "#,
+            group_id,
+            query.from,
+            query.to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, group_id: Uuid, blackout_id: Uuid) -> Result<(), DataServiceError> {
+        let output = sqlx::query!(
+            r#"
+            DELETE FROM group_blackouts
+            WHERE id = $1 AND group_id = $2
+            "#,
+            blackout_id,
+            group_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if output.rows_affected() == 0 {
+            return Err(DataServiceError::NotFound("Blackout not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
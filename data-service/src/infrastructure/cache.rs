@@ -1,4 +1,9 @@
 pub mod client;
 pub mod group;
 pub mod membership;
+pub mod relay;
 pub mod staff;
+
+/// Seconds an id lookup that found nothing stays cached, unless overridden. Kept short so
+/// a record created right after a failed lookup shows up quickly.
+pub const DEFAULT_NEGATIVE_TTL: u64 = 30;
@@ -2,6 +2,31 @@ use redis::AsyncCommands;
 use redis::aio::ConnectionManager;
 use serde::{Serialize, de::DeserializeOwned};
 
+/// What a key caches, for the metrics: `staff` for `data-service:staff:id:{id}`.
+fn cache_name(key: &str) -> &str {
+    key.split(':').nth(1).unwrap_or(key)
+}
+
+/// `cache_name` of a batch of keys, `mixed` when they span several.
+fn batch_name<'a>(keys: &[&'a str]) -> &'a str {
+    match keys.split_first() {
+        Some((first, rest)) => {
+            let name = cache_name(first);
+            if rest.iter().all(|key| cache_name(key) == name) {
+                name
+            } else {
+                "mixed"
+            }
+        }
+        None => "none",
+    }
+}
+
+/// Note how a cache operation ended on its span.
+fn record_outcome(outcome: &str) {
+    tracing::Span::current().record("cache.outcome", outcome);
+}
+
 #[derive(Clone)]
 pub struct RedisCache {
     conn: ConnectionManager,
@@ -15,95 +40,187 @@ impl RedisCache {
         Ok(Self { conn })
     }
 
+    #[tracing::instrument(
+        name = "cache.get",
+        skip_all,
+        fields(cache.name = cache_name(key), cache.outcome = tracing::field::Empty)
+    )]
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
         let mut conn = self.conn.clone();
         let output: Result<Option<String>, _> = conn.get(key).await;
 
-        match output {
+        let value = match output {
             Ok(Some(json)) => match serde_json::from_str(&json) {
                 Ok(value) => {
                     tracing::info!("Cache hit: {key}");
+                    record_outcome("hit");
                     Some(value)
                 }
                 Err(e) => {
                     tracing::warn!("Cache deserialize error for {key}: {e}");
+                    record_outcome("error");
                     None
                 }
             },
             Ok(None) => {
                 tracing::info!("Cache miss: {key}");
+                record_outcome("miss");
                 None
             }
             Err(e) => {
                 tracing::warn!("Cache get error for {key}: {e}");
+                record_outcome("error");
                 None
             }
-        }
+        };
+        let hit = u64::from(value.is_some());
+        shared::metrics::record_cache_lookups(cache_name(key), hit, 1 - hit);
+        value
     }
 
+    #[tracing::instrument(
+        name = "cache.set",
+        skip_all,
+        fields(cache.name = cache_name(key), cache.outcome = tracing::field::Empty)
+    )]
     pub async fn set<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: u64) {
         let mut conn = self.conn.clone();
 
         match serde_json::to_string(value) {
             Ok(json) => {
                 let output: Result<(), _> = conn.set_ex(key, &json, ttl_seconds).await;
-                if let Err(e) = output {
-                    tracing::warn!("Cache set error for {key}: {e}");
+                match output {
+                    Ok(()) => record_outcome("ok"),
+                    Err(e) => {
+                        tracing::warn!("Cache set error for {key}: {e}");
+                        record_outcome("error");
+                    }
                 }
             }
             Err(e) => {
                 tracing::warn!("Cache serialize error for {key}: {e}");
+                record_outcome("error");
             }
         }
     }
 
     pub async fn delete(&self, keys: &[&str]) {
+        if let Err(e) = self.try_delete(keys).await {
+            tracing::warn!("Cache delete error for {keys:?}: {e}");
+        }
+    }
+
+    /// `delete` for callers that need to know whether it went through.
+    #[tracing::instrument(
+        name = "cache.delete",
+        skip_all,
+        fields(
+            cache.name = batch_name(keys),
+            cache.keys = keys.len(),
+            cache.outcome = tracing::field::Empty
+        )
+    )]
+    pub async fn try_delete(&self, keys: &[&str]) -> Result<(), redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.clone();
+        let output = conn.del(keys).await;
+        record_outcome(if output.is_ok() { "ok" } else { "error" });
+        output
+    }
+
+    /// Round trip to the server, for the startup self-check.
+    pub async fn ping(&self) -> Result<(), redis::RedisError> {
+        let mut conn = self.conn.clone();
+        redis::cmd("PING").query_async::<String>(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Increment each counter, creating missing ones at 1, without expiry.
+    #[tracing::instrument(
+        name = "cache.incr",
+        skip_all,
+        fields(
+            cache.name = batch_name(keys),
+            cache.keys = keys.len(),
+            cache.outcome = tracing::field::Empty
+        )
+    )]
+    pub async fn incr(&self, keys: &[&str]) {
         if keys.is_empty() {
             return;
         }
         let mut conn = self.conn.clone();
-        let output: Result<(), _> = conn.del(keys).await;
-        if let Err(e) = output {
-            tracing::warn!("Cache delete error for {keys:?}: {e}");
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.incr(*key, 1).ignore();
+        }
+        let output: Result<(), _> = pipe.query_async(&mut conn).await;
+        match output {
+            Ok(()) => record_outcome("ok"),
+            Err(e) => {
+                tracing::warn!("Cache incr error for {keys:?}: {e}");
+                record_outcome("error");
+            }
         }
     }
 
     pub async fn delete_by_pattern(&self, pattern: &str) {
+        if let Err(e) = self.try_delete_by_pattern(pattern).await {
+            tracing::warn!("Cache pattern delete error for {pattern}: {e}");
+        }
+    }
+
+    /// `delete_by_pattern` for callers that need to know whether it went through.
+    ///
+    /// The span counts the SCAN round trips and matched keys, which is where a pattern
+    /// delete over a large keyspace spends its time.
+    #[tracing::instrument(
+        name = "cache.delete_by_pattern",
+        skip_all,
+        fields(
+            cache.name = cache_name(pattern),
+            cache.scans = tracing::field::Empty,
+            cache.keys = tracing::field::Empty,
+            cache.outcome = tracing::field::Empty
+        )
+    )]
+    pub async fn try_delete_by_pattern(&self, pattern: &str) -> Result<(), redis::RedisError> {
+        let output = self.scan_and_delete(pattern).await;
+        record_outcome(if output.is_ok() { "ok" } else { "error" });
+        output
+    }
+
+    async fn scan_and_delete(&self, pattern: &str) -> Result<(), redis::RedisError> {
+        let span = tracing::Span::current();
         let mut conn = self.conn.clone();
         let mut cursor: u64 = 0;
+        let mut scans: u64 = 0;
         let mut keys_to_delete: Vec<String> = Vec::new();
 
         loop {
-            let output: Result<(u64, Vec<String>), _> = redis::cmd("SCAN")
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
                 .arg(cursor)
                 .arg("MATCH")
                 .arg(pattern)
                 .arg("COUNT")
                 .arg(100)
                 .query_async(&mut conn)
-                .await;
-
-            match output {
-                Ok((next_cursor, keys)) => {
-                    keys_to_delete.extend(keys);
-                    cursor = next_cursor;
-                    if cursor == 0 {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Cache scan error for pattern {pattern}: {e}");
-
-                    return;
-                }
+                .await?;
+            scans += 1;
+            span.record("cache.scans", scans);
+            keys_to_delete.extend(keys);
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
             }
         }
 
-        if !keys_to_delete.is_empty() {
-            let output: Result<(), _> = conn.del(&keys_to_delete).await;
-            if let Err(e) = output {
-                tracing::warn!("cache pattern delete error for {pattern}: {e}");
-            }
+        span.record("cache.keys", keys_to_delete.len());
+        if keys_to_delete.is_empty() {
+            return Ok(());
         }
+        conn.del(&keys_to_delete).await
     }
 }
@@ -4,11 +4,13 @@ use async_trait::async_trait;
 use shared::types::StaffGroup;
 use uuid::Uuid;
 
-use super::client::RedisCache;
-use crate::domain::group::{CreateGroup, GroupRepository, UpdateGroup};
+use super::membership::{bump_resolved_versions, forget_group};
+use super::{DEFAULT_NEGATIVE_TTL, client::RedisCache};
+use crate::domain::bulk::{BulkDelete, BulkDeleteOutcome, BulkDeleteResult};
+use crate::domain::group::{CreateGroup, GroupDepthStats, GroupNode, GroupRepository, UpdateGroup};
 use crate::error::DataServiceError;
 
-const KEY_ALL: &str = "data-service:groups:all";
+pub const KEY_ALL: &str = "data-service:groups:all";
 const TTL_ALL: u64 = 300;
 const TTL_BY_ID: u64 = 600;
 
@@ -19,31 +21,38 @@ fn key_by_id(id: Uuid) -> String {
 pub struct CachedGroupRepository {
     inner: Arc<dyn GroupRepository>,
     cache: RedisCache,
+    negative_ttl: u64,
 }
 
 impl CachedGroupRepository {
     pub fn new(inner: Arc<dyn GroupRepository>, cache: RedisCache) -> Self {
-        Self { inner, cache }
+        Self {
+            inner,
+            cache,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+        }
     }
 
-    async fn invalidate_lists(&self) {
-        self.cache.delete(&[KEY_ALL]).await;
+    /// How long a lookup of an unknown id stays cached, in seconds. 0 disables it.
+    pub fn with_negative_ttl(mut self, seconds: u64) -> Self {
+        self.negative_ttl = seconds;
+        self
     }
 
-    async fn invalidate_with_membership(&self, id: Uuid) {
-        self.cache.delete(&[KEY_ALL, &key_by_id(id)]).await;
-        self.cache
-            .delete_by_pattern("data-service:membership:staff:*:groups")
-            .await;
-        self.cache
-            .delete_by_pattern("data-service:membership:group:*:resolved")
-            .await;
+    /// Drops the lists and any cached miss for the new ids, which a lookup racing the insert
+    /// may have stored
+    async fn invalidate_created(&self, ids: impl Iterator<Item = Uuid>) {
+        let mut keys: Vec<String> = ids.map(key_by_id).collect();
+        keys.push(KEY_ALL.to_string());
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.cache.delete(&keys).await;
     }
 
-    async fn invalidate_all(&self, id: Uuid) {
+    /// Drops the group and the per-staff group lists, which embed it
+    async fn invalidate_with_membership(&self, id: Uuid) {
         self.cache.delete(&[KEY_ALL, &key_by_id(id)]).await;
         self.cache
-            .delete_by_pattern("data-service:membership:*")
+            .delete_by_pattern("data-service:membership:staff:*:groups")
             .await;
     }
 }
@@ -66,14 +75,18 @@ impl GroupRepository for CachedGroupRepository {
             return Ok(cached);
         }
         let output = self.inner.find_by_id(id).await?;
-        self.cache.set(&key, &output, TTL_BY_ID).await;
+        match &output {
+            Some(_) => self.cache.set(&key, &output, TTL_BY_ID).await,
+            None if self.negative_ttl > 0 => self.cache.set(&key, &output, self.negative_ttl).await,
+            None => {}
+        }
 
         Ok(output)
     }
 
     async fn create(&self, group: CreateGroup) -> Result<StaffGroup, DataServiceError> {
         let output = self.inner.create(group).await?;
-        self.invalidate_lists().await;
+        self.invalidate_created(std::iter::once(output.id)).await;
 
         Ok(output)
     }
@@ -83,21 +96,80 @@ impl GroupRepository for CachedGroupRepository {
         groups: Vec<CreateGroup>,
     ) -> Result<Vec<StaffGroup>, DataServiceError> {
         let output = self.inner.batch_create(groups).await?;
-        self.invalidate_lists().await;
+        self.invalidate_created(output.iter().map(|created| created.id))
+            .await;
 
         Ok(output)
     }
 
-    async fn update(&self, id: Uuid, group: UpdateGroup) -> Result<StaffGroup, DataServiceError> {
-        let output = self.inner.update(id, group).await?;
+    async fn update(
+        &self,
+        id: Uuid,
+        group: UpdateGroup,
+        expected_version: Option<i32>,
+    ) -> Result<StaffGroup, DataServiceError> {
+        // A move changes the resolved members of the old ancestors as well as the new ones
+        let moves = group.parent_group_id.is_some();
+        let before = if moves {
+            Some(self.inner.lineage(&[id]).await)
+        } else {
+            None
+        };
+        let output = self.inner.update(id, group, expected_version).await?;
         self.invalidate_with_membership(id).await;
+        if let Some(before) = before {
+            let after = self.inner.lineage(&[id]).await;
+            let lineage = before.and_then(|mut ids| {
+                ids.extend(after?);
+                Ok(ids)
+            });
+            bump_resolved_versions(&self.cache, lineage).await;
+        }
         Ok(output)
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let lineage = self.inner.lineage(&[id]).await;
         self.inner.delete(id).await?;
-        self.invalidate_all(id).await;
+        self.invalidate_with_membership(id).await;
+        bump_resolved_versions(&self.cache, lineage).await;
+        forget_group(&self.cache, id).await;
 
         Ok(())
     }
+
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        let output = self.inner.bulk_delete(request, dry_run).await?;
+        if output
+            .iter()
+            .any(|result| result.outcome == BulkDeleteOutcome::Deleted)
+        {
+            // Children of deleted groups lost their parent, so every cached group may be stale
+            self.cache.delete(&[KEY_ALL]).await;
+            self.cache
+                .delete_by_pattern("data-service:groups:id:*")
+                .await;
+            self.cache
+                .delete_by_pattern("data-service:membership:*")
+                .await;
+        }
+
+        Ok(output)
+    }
+
+    async fn depth_stats(&self) -> Result<GroupDepthStats, DataServiceError> {
+        self.inner.depth_stats().await
+    }
+
+    async fn lineage(&self, ids: &[Uuid]) -> Result<Vec<Uuid>, DataServiceError> {
+        self.inner.lineage(ids).await
+    }
+
+    async fn tree(&self, root: Option<Uuid>) -> Result<Vec<GroupNode>, DataServiceError> {
+        self.inner.tree(root).await
+    }
 }
@@ -1,55 +1,192 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use shared::types::{Staff, StaffGroup};
+use chrono::NaiveDate;
+use shared::pagination::PageRequest;
+use shared::types::{PositionBucket, StaffGroup, StaffStatus};
 use uuid::Uuid;
 
 use super::client::RedisCache;
-use crate::domain::membership::{AddMembership, MembershipRepository};
+use crate::domain::group::GroupRepository;
+use crate::domain::membership::{
+    AddMembership, MemberPage, MemberQuery, MembershipChanges, MembershipOutcome,
+    MembershipRepository, MembershipResult, RemoveMembership, member_page_limit,
+};
 use crate::error::DataServiceError;
 
 const TTL: u64 = 300;
+/// Every cached member page and group-of-staff list; the resolved version counters live
+/// outside it.
+pub const KEY_LISTS: &str = "data-service:membership:*";
 
-fn key_group_members(group_id: Uuid) -> String {
-    format!("data-service:membership:group:{group_id}:members")
+fn key_group_members(group_id: Uuid, query: &MemberQuery, page: &PageRequest) -> String {
+    format!(
+        "data-service:membership:group:{group_id}:members:{}",
+        query_suffix(query, page)
+    )
 }
 
-fn key_staff_groups(staff_id: Uuid) -> String {
+pub(super) fn key_staff_groups(staff_id: Uuid) -> String {
     format!("data-service:membership:staff:{staff_id}:groups")
 }
 
-fn key_resolved(group_id: Uuid) -> String {
-    format!("data-service:membership:group:{group_id}:resolved")
+fn key_resolved(group_id: Uuid, version: u64, query: &MemberQuery, page: &PageRequest) -> String {
+    format!(
+        "data-service:membership:group:{group_id}:resolved:v{version}:{}",
+        query_suffix(query, page)
+    )
+}
+
+/// Under the resolved prefix, so it is versioned and dropped together with the pages.
+fn key_resolved_by_position(group_id: Uuid, version: u64, query: &MemberQuery) -> String {
+    format!(
+        "data-service:membership:group:{group_id}:resolved:v{version}:by-position:{}:{}",
+        status_suffix(query),
+        match (query.count_only, query.include_future) {
+            (true, false) => "count",
+            (true, true) => "count+future",
+            (false, false) => "all",
+            (false, true) => "all+future",
+        }
+    )
+}
+
+/// Bumped whenever the members of the group's subtree change, which orphans every resolved
+/// entry stored under the old version. Lives outside `data-service:membership:` so the
+/// pattern deletes there never reset a counter back to a version that is still cached.
+fn key_resolved_version(group_id: Uuid) -> String {
+    format!("data-service:resolved-version:group:{group_id}")
+}
+
+/// Move the resolved members of every group in `lineage` to a new version. When the
+/// hierarchy couldn't be read, drop all resolved entries instead.
+pub(super) async fn bump_resolved_versions(
+    cache: &RedisCache,
+    lineage: Result<Vec<Uuid>, DataServiceError>,
+) {
+    match lineage {
+        Ok(ids) => {
+            let keys: Vec<String> = ids.into_iter().map(key_resolved_version).collect();
+            let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+            cache.incr(&keys).await;
+        }
+        Err(e) => {
+            tracing::warn!("Reading group lineage failed, dropping all resolved members: {e}");
+            cache
+                .delete_by_pattern("data-service:membership:group:*:resolved:*")
+                .await;
+        }
+    }
+}
+
+/// Drop the member pages of `group_ids` and move the resolved members of every group above
+/// them to a new version.
+pub(super) async fn invalidate_group_members(
+    cache: &RedisCache,
+    groups: &dyn GroupRepository,
+    group_ids: &[Uuid],
+) {
+    if group_ids.is_empty() {
+        return;
+    }
+    for group_id in group_ids {
+        cache
+            .delete_by_pattern(&format!(
+                "data-service:membership:group:{group_id}:members:*"
+            ))
+            .await;
+    }
+    bump_resolved_versions(cache, groups.lineage(group_ids).await).await;
+}
+
+/// Forget a deleted group's cached lists and its version counter.
+pub(super) async fn forget_group(cache: &RedisCache, group_id: Uuid) {
+    cache
+        .delete_by_pattern(&format!("data-service:membership:group:{group_id}:*"))
+        .await;
+    cache.delete(&[&key_resolved_version(group_id)]).await;
+}
+
+fn status_suffix(query: &MemberQuery) -> &'static str {
+    match query.status {
+        Some(StaffStatus::Active) => "active",
+        Some(StaffStatus::Inactive) => "inactive",
+        None => "any",
+    }
+}
+
+fn query_suffix(query: &MemberQuery, page: &PageRequest) -> String {
+    let status = status_suffix(query);
+    let status = if query.include_future {
+        format!("{status}+future")
+    } else {
+        status.to_string()
+    };
+    if query.count_only {
+        format!("{status}:count")
+    } else {
+        format!("{status}:{}:{}", member_page_limit(page), page.offset())
+    }
 }
 
 pub struct CachedMembershipRepository {
     inner: Arc<dyn MembershipRepository>,
+    /// Finds the groups above a changed one, whose resolved members change with it
+    groups: Arc<dyn GroupRepository>,
     cache: RedisCache,
 }
 
 impl CachedMembershipRepository {
-    pub fn new(inner: Arc<dyn MembershipRepository>, cache: RedisCache) -> Self {
-        Self { inner, cache }
+    pub fn new(
+        inner: Arc<dyn MembershipRepository>,
+        groups: Arc<dyn GroupRepository>,
+        cache: RedisCache,
+    ) -> Self {
+        Self {
+            inner,
+            groups,
+            cache,
+        }
     }
 
-    async fn invalidate_membership(&self, group_id: Uuid, staff_id: Uuid) {
-        self.cache
-            .delete(&[&key_group_members(group_id), &key_staff_groups(staff_id)])
-            .await;
-        self.cache
-            .delete_by_pattern("data-service:membership:group:*:resolved")
-            .await;
+    async fn invalidate_memberships(&self, group_ids: &[Uuid], staff_ids: &[Uuid]) {
+        let keys: Vec<String> = staff_ids.iter().copied().map(key_staff_groups).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.cache.delete(&keys).await;
+        invalidate_group_members(&self.cache, self.groups.as_ref(), group_ids).await;
+    }
+
+    /// Invalidate the groups and staff of the batch items that ended in `changed`.
+    async fn invalidate_changed(&self, report: &[MembershipResult], changed: MembershipOutcome) {
+        let mut group_ids = Vec::new();
+        let mut staff_ids = Vec::new();
+        for r in report.iter().filter(|r| r.outcome == changed) {
+            if !group_ids.contains(&r.group_id) {
+                group_ids.push(r.group_id);
+            }
+            if !staff_ids.contains(&r.staff_id) {
+                staff_ids.push(r.staff_id);
+            }
+        }
+        if !group_ids.is_empty() {
+            self.invalidate_memberships(&group_ids, &staff_ids).await;
+        }
     }
 }
 
 #[async_trait]
 impl MembershipRepository for CachedMembershipRepository {
-    async fn get_group_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError> {
-        let key = key_group_members(group_id);
-        if let Some(cached) = self.cache.get::<Vec<Staff>>(&key).await {
+    async fn get_group_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        let key = key_group_members(group_id, &query, &page);
+        if let Some(cached) = self.cache.get::<MemberPage>(&key).await {
             return Ok(cached);
         }
-        let output = self.inner.get_group_members(group_id).await?;
+        let output = self.inner.get_group_members(group_id, query, page).await?;
         self.cache.set(&key, &output, TTL).await;
 
         Ok(output)
@@ -66,12 +203,45 @@ impl MembershipRepository for CachedMembershipRepository {
         Ok(output)
     }
 
-    async fn resolve_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError> {
-        let key = key_resolved(group_id);
-        if let Some(cached) = self.cache.get::<Vec<Staff>>(&key).await {
+    async fn resolve_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        let version = self
+            .cache
+            .get::<u64>(&key_resolved_version(group_id))
+            .await
+            .unwrap_or(0);
+        let key = key_resolved(group_id, version, &query, &page);
+        if let Some(cached) = self.cache.get::<MemberPage>(&key).await {
             return Ok(cached);
         }
-        let output = self.inner.resolve_members(group_id).await?;
+        let output = self.inner.resolve_members(group_id, query, page).await?;
+        self.cache.set(&key, &output, TTL).await;
+
+        Ok(output)
+    }
+
+    async fn resolve_members_by_position(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+    ) -> Result<Vec<PositionBucket>, DataServiceError> {
+        let version = self
+            .cache
+            .get::<u64>(&key_resolved_version(group_id))
+            .await
+            .unwrap_or(0);
+        let key = key_resolved_by_position(group_id, version, &query);
+        if let Some(cached) = self.cache.get::<Vec<PositionBucket>>(&key).await {
+            return Ok(cached);
+        }
+        let output = self
+            .inner
+            .resolve_members_by_position(group_id, query)
+            .await?;
         self.cache.set(&key, &output, TTL).await;
 
         Ok(output)
@@ -81,9 +251,12 @@ impl MembershipRepository for CachedMembershipRepository {
         &self,
         group_id: Uuid,
         staff_id: Uuid,
+        valid_from: Option<NaiveDate>,
     ) -> Result<(), DataServiceError> {
-        self.inner.add_staff_to_group(group_id, staff_id).await?;
-        self.invalidate_membership(group_id, staff_id).await;
+        self.inner
+            .add_staff_to_group(group_id, staff_id, valid_from)
+            .await?;
+        self.invalidate_memberships(&[group_id], &[staff_id]).await;
 
         Ok(())
     }
@@ -96,7 +269,7 @@ impl MembershipRepository for CachedMembershipRepository {
         self.inner
             .remove_staff_from_group(group_id, staff_id)
             .await?;
-        self.invalidate_membership(group_id, staff_id).await;
+        self.invalidate_memberships(&[group_id], &[staff_id]).await;
 
         Ok(())
     }
@@ -104,12 +277,41 @@ impl MembershipRepository for CachedMembershipRepository {
     async fn batch_add_members(
         &self,
         memberships: Vec<AddMembership>,
-    ) -> Result<(), DataServiceError> {
-        self.inner.batch_add_members(memberships).await?;
-        self.cache
-            .delete_by_pattern("data-service:membership:*")
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
+        let report = self.inner.batch_add_members(memberships).await?;
+        self.invalidate_changed(&report, MembershipOutcome::Inserted)
             .await;
 
-        Ok(())
+        Ok(report)
+    }
+
+    async fn batch_remove_members(
+        &self,
+        memberships: Vec<RemoveMembership>,
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
+        let report = self.inner.batch_remove_members(memberships).await?;
+        self.invalidate_changed(&report, MembershipOutcome::Removed)
+            .await;
+
+        Ok(report)
+    }
+
+    async fn set_group_members(
+        &self,
+        group_id: Uuid,
+        staff_ids: Vec<Uuid>,
+    ) -> Result<MembershipChanges, DataServiceError> {
+        let changes = self.inner.set_group_members(group_id, staff_ids).await?;
+        let changed: Vec<Uuid> = changes
+            .added
+            .iter()
+            .chain(&changes.removed)
+            .copied()
+            .collect();
+        if !changed.is_empty() {
+            self.invalidate_memberships(&[group_id], &changed).await;
+        }
+
+        Ok(changes)
     }
 }
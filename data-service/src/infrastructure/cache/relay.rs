@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use super::client::RedisCache;
+use super::{group, membership, staff};
+use crate::error::DataServiceError;
+use crate::infrastructure::outbox::{
+    GROUP_LISTS, MEMBERSHIP_LISTS, OutboxEntry, PgCacheOutbox, STAFF_LISTS,
+};
+
+const BATCH_SIZE: i64 = 100;
+
+/// Applies the invalidations in the cache outbox. The cached repositories still invalidate
+/// right after a write; this catches the ones lost to a crash or a Redis error in between.
+pub struct OutboxRelay {
+    outbox: PgCacheOutbox,
+    cache: RedisCache,
+}
+
+impl OutboxRelay {
+    pub fn new(outbox: PgCacheOutbox, cache: RedisCache) -> Self {
+        Self { outbox, cache }
+    }
+
+    /// Apply one batch of entries and remove the ones Redis accepted. Returns how many.
+    pub async fn drain(&self) -> Result<usize, DataServiceError> {
+        let mut applied = Vec::new();
+        for OutboxEntry { id, topic } in self.outbox.pending(BATCH_SIZE).await? {
+            let output = match topic.as_str() {
+                STAFF_LISTS => self.cache.try_delete_by_pattern(staff::KEY_LISTS).await,
+                GROUP_LISTS => self.cache.try_delete(&[group::KEY_ALL]).await,
+                MEMBERSHIP_LISTS => {
+                    self.cache
+                        .try_delete_by_pattern(membership::KEY_LISTS)
+                        .await
+                }
+                other => {
+                    tracing::warn!(id, topic = other, "Dropping unknown cache outbox topic");
+                    Ok(())
+                }
+            };
+            match output {
+                Ok(()) => applied.push(id),
+                // Left in the outbox for the next round
+                Err(e) => tracing::warn!(id, %topic, "Cache outbox invalidation failed: {e}"),
+            }
+        }
+
+        if !applied.is_empty() {
+            self.outbox.remove(&applied).await?;
+        }
+        Ok(applied.len())
+    }
+
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.drain().await {
+                tracing::warn!("Cache outbox relay failed: {e}");
+            }
+        }
+    }
+}
@@ -1,98 +1,258 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use shared::types::Staff;
+use chrono::NaiveDate;
+use shared::pagination::PageRequest;
+use shared::types::{Staff, StaffStatus};
 use uuid::Uuid;
 
-use super::client::RedisCache;
-use crate::domain::staff::{CreateStaff, StaffRepository, UpdateStaff};
+use super::membership::{self, invalidate_group_members, key_staff_groups};
+use super::{DEFAULT_NEGATIVE_TTL, client::RedisCache};
+use crate::domain::bulk::{BulkDelete, BulkDeleteOutcome, BulkDeleteResult};
+use crate::domain::duplicate::{DuplicateQuery, MergeResult, StaffDuplicate};
+use crate::domain::group::GroupRepository;
+use crate::domain::membership::MembershipRepository;
+use crate::domain::staff::{
+    CreateStaff, StaffPage, StaffQuery, StaffRepository, UpdateStaff, staff_page_limit,
+};
 use crate::error::DataServiceError;
 
-const KEY_ALL: &str = "data-service:staff:all";
-const TTL_ALL: u64 = 300;
+/// Matches every cached page of the staff listing.
+pub const KEY_LISTS: &str = "data-service:staff:list:*";
+const TTL_LIST: u64 = 300;
 const TTL_BY_ID: u64 = 600;
 
 fn key_by_id(id: Uuid) -> String {
     format!("data-service:staff:id:{id}")
 }
 
+/// The position goes last: it is free text and may contain the separator.
+fn key_list(query: &StaffQuery, page: &PageRequest) -> String {
+    let status = match query.status {
+        Some(StaffStatus::Active) => "active",
+        Some(StaffStatus::Inactive) => "inactive",
+        None => "any",
+    };
+    let position = match &query.position {
+        Some(position) => format!("={position}"),
+        None => "any".to_string(),
+    };
+    format!(
+        "data-service:staff:list:{status}:{}:{}:{position}",
+        staff_page_limit(page),
+        page.offset()
+    )
+}
+
 pub struct CachedStaffRepository {
     inner: Arc<dyn StaffRepository>,
+    /// Finds the groups a changed staff member is in, whose member lists show them
+    memberships: Arc<dyn MembershipRepository>,
+    /// Finds the groups above those, whose resolved members include them too
+    groups: Arc<dyn GroupRepository>,
     cache: RedisCache,
+    negative_ttl: u64,
 }
 
 impl CachedStaffRepository {
-    pub fn new(inner: Arc<dyn StaffRepository>, cache: RedisCache) -> Self {
-        Self { inner, cache }
+    pub fn new(
+        inner: Arc<dyn StaffRepository>,
+        memberships: Arc<dyn MembershipRepository>,
+        groups: Arc<dyn GroupRepository>,
+        cache: RedisCache,
+    ) -> Self {
+        Self {
+            inner,
+            memberships,
+            groups,
+            cache,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+        }
     }
 
-    async fn invalidate_lists(&self) {
-        self.cache.delete(&[KEY_ALL]).await;
+    /// How long a lookup of an unknown id stays cached, in seconds. 0 disables it.
+    pub fn with_negative_ttl(mut self, seconds: u64) -> Self {
+        self.negative_ttl = seconds;
+        self
     }
 
-    async fn invalidate_all(&self, id: Uuid) {
-        self.cache.delete(&[KEY_ALL, &key_by_id(id)]).await;
-        self.cache
-            .delete_by_pattern("data-service:membership:*")
-            .await;
+    /// Drops the lists and any cached miss for the new ids, which a lookup racing the insert
+    /// may have stored
+    async fn invalidate_created(&self, ids: impl Iterator<Item = Uuid>) {
+        let keys: Vec<String> = ids.map(key_by_id).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.cache.delete(&keys).await;
+        self.cache.delete_by_pattern(KEY_LISTS).await;
+    }
+
+    /// The groups the staff members are directly in. Read before a write that removes their
+    /// memberships.
+    async fn groups_of(&self, ids: &[Uuid]) -> Result<Vec<Uuid>, DataServiceError> {
+        let mut group_ids = Vec::new();
+        for id in ids {
+            for group in self.memberships.get_staff_groups(*id).await? {
+                if !group_ids.contains(&group.id) {
+                    group_ids.push(group.id);
+                }
+            }
+        }
+        Ok(group_ids)
+    }
+
+    /// Drops the staff members' entries and the member lists of `groups`, which show them.
+    /// When their groups couldn't be read, every membership list goes instead.
+    async fn invalidate_all(&self, ids: &[Uuid], groups: Result<Vec<Uuid>, DataServiceError>) {
+        let keys: Vec<String> = ids
+            .iter()
+            .flat_map(|id| [key_by_id(*id), key_staff_groups(*id)])
+            .collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.cache.delete(&keys).await;
+        self.cache.delete_by_pattern(KEY_LISTS).await;
+        match groups {
+            Ok(group_ids) => {
+                invalidate_group_members(&self.cache, self.groups.as_ref(), &group_ids).await;
+            }
+            Err(e) => {
+                tracing::warn!("Reading staff groups failed, dropping all membership lists: {e}");
+                self.cache.delete_by_pattern(membership::KEY_LISTS).await;
+            }
+        }
     }
 }
 
 #[async_trait]
 impl StaffRepository for CachedStaffRepository {
-    async fn find_all(&self) -> Result<Vec<Staff>, DataServiceError> {
-        if let Some(cached) = self.cache.get::<Vec<Staff>>(KEY_ALL).await {
+    async fn find_all(
+        &self,
+        query: StaffQuery,
+        page: PageRequest,
+    ) -> Result<StaffPage, DataServiceError> {
+        // Searches are too varied to be worth caching
+        if query.q.is_some() {
+            return self.inner.find_all(query, page).await;
+        }
+        let key = key_list(&query, &page);
+        if let Some(cached) = self.cache.get::<StaffPage>(&key).await {
             return Ok(cached);
         }
-        let output = self.inner.find_all().await?;
-        self.cache.set(KEY_ALL, &output, TTL_ALL).await;
+        let output = self.inner.find_all(query, page).await?;
+        self.cache.set(&key, &output, TTL_LIST).await;
 
         Ok(output)
     }
 
+    // Arbitrary id sets make poor cache keys; go straight to the database
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, DataServiceError> {
+        self.inner.find_by_ids(ids).await
+    }
+
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Staff>, DataServiceError> {
         let key = key_by_id(id);
         if let Some(cached) = self.cache.get::<Option<Staff>>(&key).await {
             return Ok(cached);
         }
         let output = self.inner.find_by_id(id).await?;
-        self.cache.set(&key, &output, TTL_BY_ID).await;
+        match &output {
+            Some(_) => self.cache.set(&key, &output, TTL_BY_ID).await,
+            None if self.negative_ttl > 0 => self.cache.set(&key, &output, self.negative_ttl).await,
+            None => {}
+        }
 
         Ok(output)
     }
 
     async fn create(&self, staff: CreateStaff) -> Result<Staff, DataServiceError> {
         let output = self.inner.create(staff).await?;
-        self.invalidate_lists().await;
+        self.invalidate_created(std::iter::once(output.id)).await;
 
         Ok(output)
     }
 
     async fn batch_create(&self, staffs: Vec<CreateStaff>) -> Result<Vec<Staff>, DataServiceError> {
         let output = self.inner.batch_create(staffs).await?;
-        self.invalidate_lists().await;
+        self.invalidate_created(output.iter().map(|created| created.id))
+            .await;
 
         Ok(output)
     }
 
-    async fn update(&self, id: Uuid, staff: UpdateStaff) -> Result<Staff, DataServiceError> {
-        let output = self.inner.update(id, staff).await?;
-        self.invalidate_all(id).await;
+    async fn update(
+        &self,
+        id: Uuid,
+        staff: UpdateStaff,
+        expected_version: Option<i32>,
+    ) -> Result<Staff, DataServiceError> {
+        let output = self.inner.update(id, staff, expected_version).await?;
+        self.invalidate_all(&[id], self.groups_of(&[id]).await)
+            .await;
 
         Ok(output)
     }
 
     async fn deactivate(&self, id: Uuid) -> Result<(), DataServiceError> {
         self.inner.deactivate(id).await?;
-        self.invalidate_all(id).await;
+        self.invalidate_all(&[id], self.groups_of(&[id]).await)
+            .await;
 
         Ok(())
     }
 
+    async fn deactivate_expired(&self, today: NaiveDate) -> Result<Vec<Staff>, DataServiceError> {
+        let output = self.inner.deactivate_expired(today).await?;
+        if !output.is_empty() {
+            let ids: Vec<Uuid> = output.iter().map(|staff| staff.id).collect();
+            self.invalidate_all(&ids, self.groups_of(&ids).await).await;
+        }
+
+        Ok(output)
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        // Deleting drops the memberships, so their groups are read first
+        let groups = self.groups_of(&[id]).await;
         self.inner.delete(id).await?;
-        self.invalidate_all(id).await;
+        self.invalidate_all(&[id], groups).await;
 
         Ok(())
     }
+
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        let groups = if dry_run {
+            Ok(Vec::new())
+        } else {
+            self.groups_of(&request.ids).await
+        };
+        let output = self.inner.bulk_delete(request, dry_run).await?;
+        let deleted: Vec<Uuid> = output
+            .iter()
+            .filter(|result| result.outcome == BulkDeleteOutcome::Deleted)
+            .map(|result| result.id)
+            .collect();
+        if !deleted.is_empty() {
+            self.invalidate_all(&deleted, groups).await;
+        }
+
+        Ok(output)
+    }
+
+    // Similarity scans are occasional admin work; not worth caching
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<StaffDuplicate>, DataServiceError> {
+        self.inner.find_duplicates(query).await
+    }
+
+    async fn merge(&self, id: Uuid, duplicate_id: Uuid) -> Result<MergeResult, DataServiceError> {
+        let groups = self.groups_of(&[id, duplicate_id]).await;
+        let output = self.inner.merge(id, duplicate_id).await?;
+        self.invalidate_all(&[id, duplicate_id], groups).await;
+
+        Ok(output)
+    }
 }
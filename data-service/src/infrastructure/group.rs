@@ -1,20 +1,110 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Tz;
 use shared::types::StaffGroup;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool, error::ErrorKind};
 use uuid::Uuid;
 
 use crate::{
-    domain::group::{CreateGroup, GroupRepository, UpdateGroup},
+    domain::{
+        bulk::{Blocker, BlockerKind, BulkDelete, BulkDeleteOutcome, BulkDeleteResult},
+        group::{
+            CreateGroup, DEFAULT_MAX_GROUP_DEPTH, GroupDepthStats, GroupNode, GroupRepository,
+            UpdateGroup,
+        },
+        version::missed_update,
+    },
     error::DataServiceError,
+    infrastructure::outbox,
 };
 
+/// A clash on the per-parent name index is a 409, an unknown parent a 400.
+fn map_group_violation(e: sqlx::Error) -> DataServiceError {
+    DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+        (ErrorKind::UniqueViolation, Some("uq_staff_groups_parent_name")) => {
+            Some(DataServiceError::Conflict(
+                "A group with this name already exists under the same parent".to_string(),
+            ))
+        }
+        (ErrorKind::ForeignKeyViolation, Some("fk_staff_groups_parent")) => Some(
+            DataServiceError::BadRequest("Parent group not found".to_string()),
+        ),
+        (ErrorKind::CheckViolation, Some("ck_staff_groups_no_cycle")) => {
+            Some(DataServiceError::BadRequest(
+                "A group can't be moved under its own subgroup".to_string(),
+            ))
+        }
+        _ => None,
+    })
+}
+
 pub struct PgGroupRepository {
     pool: PgPool,
+    max_depth: i32,
+    /// Decides which memberships have started, for member counts
+    timezone: Tz,
 }
 
 impl PgGroupRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            max_depth: DEFAULT_MAX_GROUP_DEPTH,
+            timezone: Tz::UTC,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: i32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Fails with the deepest chain through `ids` when it has more than `max_depth` levels.
+    /// Runs after the write, inside its transaction, so moved subtrees are measured in place.
+    async fn check_depth(
+        &self,
+        conn: &mut PgConnection,
+        ids: &[Uuid],
+    ) -> Result<(), DataServiceError> {
+        let chain = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id, 1 AS depth, ARRAY[name]::varchar[] AS chain, id = ANY($1) AS touched
+                FROM staff_groups
+                WHERE parent_group_id IS NULL
+                UNION ALL
+                SELECT g.id, t.depth + 1, t.chain || g.name, t.touched OR g.id = ANY($1)
+                FROM staff_groups g
+                JOIN tree t ON g.parent_group_id = t.id
+                WHERE t.depth <= $2
+            )
+            SELECT chain AS "chain!"
+            FROM tree
+            WHERE touched AND depth > $2
+            ORDER BY depth DESC
+            LIMIT 1
+            "#,
+            ids,
+            self.max_depth
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        match chain {
+            Some(chain) => Err(DataServiceError::Unprocessable(format!(
+                "Group hierarchy would exceed {} levels: {}",
+                self.max_depth,
+                chain.join(" > ")
+            ))),
+            None => Ok(()),
+        }
     }
 }
 
@@ -25,7 +115,7 @@ impl GroupRepository for PgGroupRepository {
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
-            SELECT id, name, parent_group_id, created_at, updated_at
+            SELECT id, name, parent_group_id, created_at, updated_at, version
             FROM staff_groups
             WHERE id = $1
             "#,
@@ -42,7 +132,7 @@ impl GroupRepository for PgGroupRepository {
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
-            SELECT id, name, parent_group_id, created_at, updated_at
+            SELECT id, name, parent_group_id, created_at, updated_at, version
             FROM staff_groups
             "#
         )
@@ -54,18 +144,26 @@ impl GroupRepository for PgGroupRepository {
 
     #[tracing::instrument(skip(self))]
     async fn create(&self, group: CreateGroup) -> Result<StaffGroup, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
             INSERT INTO staff_groups (name, parent_group_id)
             VALUES ($1, $2)
-            RETURNING id, name, parent_group_id, created_at, updated_at
+            RETURNING id, name, parent_group_id, created_at, updated_at, version
             "#,
             group.name,
             group.parent_group_id
         )
-        .fetch_one(&self.pool)
-        .await?;
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_group_violation)?;
+
+        if output.parent_group_id.is_some() {
+            self.check_depth(&mut tx, &[output.id]).await?;
+        }
+        tx.commit().await?;
 
         Ok(output)
     }
@@ -78,42 +176,76 @@ impl GroupRepository for PgGroupRepository {
         let names: Vec<String> = groups.iter().map(|g| g.name.clone()).collect();
         let parent_ids: Vec<Option<Uuid>> = groups.iter().map(|g| g.parent_group_id).collect();
 
+        let mut tx = self.pool.begin().await?;
+
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
             INSERT INTO staff_groups (name, parent_group_id)
             SELECT * FROM UNNEST($1::varchar[], $2::uuid[])
-            RETURNING id, name, parent_group_id, created_at, updated_at
+            RETURNING id, name, parent_group_id, created_at, updated_at, version
             "#,
             &names,
             &parent_ids as _,
         )
-        .fetch_all(&self.pool)
-        .await?;
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_group_violation)?;
+
+        let ids: Vec<Uuid> = output.iter().map(|group| group.id).collect();
+        self.check_depth(&mut tx, &ids).await?;
+        outbox::enqueue(&mut tx, outbox::GROUP_LISTS).await?;
+        tx.commit().await?;
 
         Ok(output)
     }
 
     #[tracing::instrument(skip(self))]
-    async fn update(&self, id: Uuid, group: UpdateGroup) -> Result<StaffGroup, DataServiceError> {
+    async fn update(
+        &self,
+        id: Uuid,
+        group: UpdateGroup,
+        expected_version: Option<i32>,
+    ) -> Result<StaffGroup, DataServiceError> {
+        let moves = matches!(group.parent_group_id, Some(Some(_)));
+        let mut tx = self.pool.begin().await?;
+
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
             UPDATE staff_groups
             SET name = COALESCE($2, name),
-                parent_group_id = COALESCE($3, parent_group_id),
+                parent_group_id = CASE WHEN $3 THEN $4 ELSE parent_group_id END,
                 updated_at = now()
-            WHERE id = $1
-            RETURNING id, name, parent_group_id, created_at, updated_at
+            WHERE id = $1 AND ($5::int IS NULL OR version = $5)
+            RETURNING id, name, parent_group_id, created_at, updated_at, version
             "#,
             id,
             group.name,
-            group.parent_group_id as _,
+            group.parent_group_id.is_some(),
+            group.parent_group_id.flatten() as _,
+            expected_version,
         )
-        .fetch_optional(&self.pool)
-        .await?;
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_group_violation)?;
+        let Some(output) = output else {
+            let exists = expected_version.is_some()
+                && sqlx::query_scalar!(
+                    r#"SELECT EXISTS(SELECT 1 FROM staff_groups WHERE id = $1) AS "exists!""#,
+                    id
+                )
+                .fetch_one(&mut *tx)
+                .await?;
+            return Err(missed_update("Group", exists));
+        };
+
+        if moves {
+            self.check_depth(&mut tx, &[id]).await?;
+        }
+        tx.commit().await?;
 
-        output.ok_or_else(|| DataServiceError::NotFound("Group not found".to_string()))
+        Ok(output)
     }
 
     #[tracing::instrument(skip(self))]
@@ -134,4 +266,159 @@ impl GroupRepository for PgGroupRepository {
 
         Ok(())
     }
+
+    #[tracing::instrument(skip(self, request), fields(count = request.ids.len()))]
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Children that are deleted in the same request don't count against their parent
+        let rows = sqlx::query!(
+            r#"
+            SELECT i.id AS "id!",
+                   g.id IS NOT NULL AS "found!",
+                   (SELECT COUNT(*) FROM group_memberships gm WHERE gm.group_id = i.id) AS "memberships!",
+                   (SELECT COUNT(*) FROM staff_groups c
+                    WHERE c.parent_group_id = i.id AND c.id <> ALL($1)) AS "child_groups!"
+            FROM UNNEST($1::uuid[]) WITH ORDINALITY AS i(id, position)
+            LEFT JOIN staff_groups g ON g.id = i.id
+            ORDER BY i.position
+            "#,
+            &request.ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let results: Vec<BulkDeleteResult> = rows
+            .into_iter()
+            .map(|row| {
+                let blockers = vec![
+                    Blocker {
+                        kind: BlockerKind::Memberships,
+                        count: row.memberships,
+                    },
+                    Blocker {
+                        kind: BlockerKind::ChildGroups,
+                        count: row.child_groups,
+                    },
+                ];
+                BulkDeleteResult::assess(row.id, row.found, blockers, request.cascade, dry_run)
+            })
+            .collect();
+        if dry_run {
+            return Ok(results);
+        }
+
+        let ids: Vec<Uuid> = results
+            .iter()
+            .filter(|result| result.outcome == BulkDeleteOutcome::Deleted)
+            .map(|result| result.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(results);
+        }
+        sqlx::query!(
+            r#"
+            DELETE FROM staff_groups
+            WHERE id = ANY($1)
+            "#,
+            &ids
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(map_group_violation)?;
+
+        outbox::enqueue(&mut tx, outbox::GROUP_LISTS).await?;
+        tx.commit().await?;
+
+        Ok(results)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn depth_stats(&self) -> Result<GroupDepthStats, DataServiceError> {
+        let max_depth = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(MAX(depth) + 1, 0) AS "max_depth!"
+            FROM group_closure
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(GroupDepthStats {
+            max_depth,
+            limit: self.max_depth,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn tree(&self, root: Option<Uuid>) -> Result<Vec<GroupNode>, DataServiceError> {
+        let today = Utc::now().with_timezone(&self.timezone).date_naive();
+        let rows = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id, name, parent_group_id, 0 AS depth
+                FROM staff_groups
+                WHERE CASE WHEN $1::uuid IS NULL THEN parent_group_id IS NULL ELSE id = $1 END
+                UNION ALL
+                SELECT g.id, g.name, g.parent_group_id, t.depth + 1
+                FROM staff_groups g
+                JOIN tree t ON g.parent_group_id = t.id
+            )
+            SELECT t.id AS "id!", t.name AS "name!", t.parent_group_id, t.depth AS "depth!",
+                (
+                    SELECT COUNT(*)
+                    FROM group_memberships gm
+                    WHERE gm.group_id = t.id AND (gm.valid_from IS NULL OR gm.valid_from <= $2)
+                ) AS "member_count!"
+            FROM tree t
+            ORDER BY t.depth, lower(t.name), t.id
+            "#,
+            root,
+            today
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Deepest first, so a group's subgroups are all built by the time it is reached
+        let mut subgroups: HashMap<Uuid, Vec<GroupNode>> = HashMap::new();
+        let mut roots = Vec::new();
+        for row in rows.into_iter().rev() {
+            let mut children = subgroups.remove(&row.id).unwrap_or_default();
+            children.reverse();
+            let node = GroupNode {
+                id: row.id,
+                name: row.name,
+                parent_group_id: row.parent_group_id,
+                member_count: row.member_count,
+                children,
+            };
+            match row.parent_group_id.filter(|_| row.depth > 0) {
+                Some(parent) => subgroups.entry(parent).or_default().push(node),
+                None => roots.push(node),
+            }
+        }
+        roots.reverse();
+
+        Ok(roots)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn lineage(&self, ids: &[Uuid]) -> Result<Vec<Uuid>, DataServiceError> {
+        let output = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT ancestor_id
+            FROM group_closure
+            WHERE descendant_id = ANY($1)
+            "#,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
 }
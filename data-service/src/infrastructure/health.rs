@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use shared::health::DependencyChecks;
+use shared::startup::{StartupSelfCheck, check_database, check_migrations};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::photo::PhotoStorage;
+use crate::infrastructure::{MIGRATOR, cache::client::RedisCache};
+
+/// Boot checks: the database and its schema, Redis, the settings read from the environment
+/// and the photo store they point at.
+pub fn startup_self_check(
+    pool: PgPool,
+    cache: RedisCache,
+    photo_storage: Arc<dyn PhotoStorage>,
+    max_group_depth: i32,
+) -> StartupSelfCheck {
+    let migrations_pool = pool.clone();
+    let config_result = if max_group_depth >= 1 {
+        Ok(format!("max group depth {max_group_depth}"))
+    } else {
+        Err(format!(
+            "MAX_GROUP_DEPTH must be at least 1, got {max_group_depth}"
+        ))
+    };
+
+    StartupSelfCheck::new()
+        .with_check("database", move || {
+            let pool = pool.clone();
+            async move { check_database(&pool).await }
+        })
+        .with_check("migrations", move || {
+            let pool = migrations_pool.clone();
+            async move { check_migrations(&pool, &MIGRATOR).await }
+        })
+        .with_check("redis", move || {
+            let cache = cache.clone();
+            async move {
+                cache.ping().await.map_err(|e| e.to_string())?;
+                Ok("PING answered".to_string())
+            }
+        })
+        .with_check("config", move || std::future::ready(config_result.clone()))
+        .with_check("photo-storage", move || {
+            let photo_storage = photo_storage.clone();
+            // A lookup for an id no photo is stored under: only the backend answering matters
+            async move {
+                photo_storage
+                    .get(Uuid::nil())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok("reachable".to_string())
+            }
+        })
+}
+
+/// Pinged on every `/readyz`: the database and Redis.
+pub fn dependency_checks(pool: PgPool, cache: RedisCache) -> DependencyChecks {
+    DependencyChecks::new()
+        .with_check("postgres", move || {
+            let pool = pool.clone();
+            async move { check_database(&pool).await }
+        })
+        .with_check("redis", move || {
+            let cache = cache.clone();
+            async move {
+                cache.ping().await.map_err(|e| e.to_string())?;
+                Ok("PING answered".to_string())
+            }
+        })
+}
@@ -1,20 +1,73 @@
+use std::collections::HashSet;
+
 use async_trait::async_trait;
-use shared::types::{Staff, StaffGroup};
-use sqlx::PgPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use shared::pagination::PageRequest;
+use shared::types::{
+    GroupMember, PositionBucket, ShiftEligibility, Staff, StaffGroup, StaffStatus,
+};
+use sqlx::{PgPool, error::ErrorKind};
 use uuid::Uuid;
 
 use crate::{
-    domain::membership::{AddMembership, MembershipRepository},
+    domain::membership::{
+        AddMembership, MemberPage, MemberQuery, MembershipChanges, MembershipOutcome,
+        MembershipRepository, MembershipResult, RemoveMembership, member_page_limit,
+    },
     error::DataServiceError,
 };
 
 pub struct PgMembershipRepository {
     pool: PgPool,
+    /// Decides on which day a future membership starts
+    timezone: Tz,
 }
 
 impl PgMembershipRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, timezone: Tz) -> Self {
+        Self { pool, timezone }
+    }
+
+    fn today(&self) -> NaiveDate {
+        Utc::now().with_timezone(&self.timezone).date_naive()
+    }
+}
+
+/// A member listing row: the staff columns and the start of their membership.
+struct MemberRow {
+    id: Uuid,
+    name: String,
+    email: String,
+    position: String,
+    fairness_weight: f64,
+    shift_eligibility: ShiftEligibility,
+    status: StaffStatus,
+    effective_until: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    version: i32,
+    valid_from: Option<NaiveDate>,
+}
+
+impl From<MemberRow> for GroupMember {
+    fn from(row: MemberRow) -> Self {
+        Self {
+            staff: Staff {
+                id: row.id,
+                name: row.name,
+                email: row.email,
+                position: row.position,
+                fairness_weight: row.fairness_weight,
+                shift_eligibility: row.shift_eligibility,
+                status: row.status,
+                effective_until: row.effective_until,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                version: row.version,
+            },
+            valid_from: row.valid_from,
+        }
     }
 }
 
@@ -25,35 +78,35 @@ impl MembershipRepository for PgMembershipRepository {
         &self,
         group_id: Uuid,
         staff_id: Uuid,
+        valid_from: Option<NaiveDate>,
     ) -> Result<(), DataServiceError> {
         let output = sqlx::query!(
             r#"
-            INSERT INTO group_memberships (group_id, staff_id) VALUES ($1, $2)
+            INSERT INTO group_memberships (group_id, staff_id, valid_from) VALUES ($1, $2, $3)
             "#,
             group_id,
-            staff_id
+            staff_id,
+            valid_from
         )
         .execute(&self.pool)
         .await;
 
-        match output {
-            Ok(_) => Ok(()),
-            Err(sqlx::Error::Database(e)) => {
-                let msg = e.message();
-                if msg.contains("fk_gm_staff") {
-                    Err(DataServiceError::NotFound("Staff not found".to_string()))
-                } else if msg.contains("fk_gm_group") {
-                    Err(DataServiceError::NotFound("Group not found".to_string()))
-                } else if msg.contains("duplicate") || msg.contains("already exists") {
-                    Err(DataServiceError::BadRequest(
-                        "Staff already in group".to_string(),
-                    ))
-                } else {
-                    Err(sqlx::Error::Database(e).into())
+        output.map_err(|e| {
+            DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+                (ErrorKind::ForeignKeyViolation, Some("fk_gm_staff")) => {
+                    Some(DataServiceError::NotFound("Staff not found".to_string()))
                 }
-            }
-            Err(e) => Err(e.into()),
-        }
+                (ErrorKind::ForeignKeyViolation, Some("fk_gm_group")) => {
+                    Some(DataServiceError::NotFound("Group not found".to_string()))
+                }
+                (ErrorKind::UniqueViolation, _) => Some(DataServiceError::BadRequest(
+                    "Staff already in group".to_string(),
+                )),
+                _ => None,
+            })
+        })?;
+
+        Ok(())
     }
 
     #[tracing::instrument(skip(self))]
@@ -83,21 +136,64 @@ impl MembershipRepository for PgMembershipRepository {
     }
 
     #[tracing::instrument(skip(self))]
-    async fn get_group_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError> {
-        let output = sqlx::query_as!(
-            Staff,
+    async fn get_group_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        let limit = member_page_limit(&page);
+        let today = self.today();
+        let total = sqlx::query_scalar!(
             r#"
-            SELECT s.id, s.name, s.email, s.position, s.status as "status: _", s.created_at, s.updated_at
-            FROM staff s
-            JOIN group_memberships gm ON s.id = gm.staff_id
-            WHERE gm.group_id = $1
+            SELECT COUNT(*) AS "count!"
+            FROM group_memberships gm
+            JOIN staff s ON s.id = gm.staff_id
+            WHERE gm.group_id = $1 AND ($2::staff_status IS NULL OR s.status = $2)
+              AND ($3 OR gm.valid_from IS NULL OR gm.valid_from <= $4)
             "#,
-            group_id
+            group_id,
+            query.status.clone() as Option<StaffStatus>,
+            query.include_future,
+            today
         )
-        .fetch_all(&self.pool)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(output)
+        let items = if query.count_only {
+            Vec::new()
+        } else {
+            sqlx::query_as!(
+                MemberRow,
+                r#"
+                SELECT s.id, s.name, s.email, s.position, s.fairness_weight, s.shift_eligibility as "shift_eligibility: _", s.status as "status: _", s.effective_until, s.created_at, s.updated_at, s.version, gm.valid_from
+                FROM staff s
+                JOIN group_memberships gm ON s.id = gm.staff_id
+                WHERE gm.group_id = $1 AND ($4::staff_status IS NULL OR s.status = $4)
+                  AND ($5 OR gm.valid_from IS NULL OR gm.valid_from <= $6)
+                ORDER BY s.name, s.id
+                LIMIT $2 OFFSET $3
+                "#,
+                group_id,
+                limit,
+                page.offset(),
+                query.status.clone() as Option<StaffStatus>,
+                query.include_future,
+                today
+            )
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(GroupMember::from)
+            .collect()
+        };
+
+        Ok(MemberPage {
+            items,
+            total,
+            limit,
+            offset: page.offset(),
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -105,7 +201,7 @@ impl MembershipRepository for PgMembershipRepository {
         let output = sqlx::query_as!(
             StaffGroup,
             r#"
-            SELECT sg.id, sg.name, sg.parent_group_id, sg.created_at, sg.updated_at
+            SELECT sg.id, sg.name, sg.parent_group_id, sg.created_at, sg.updated_at, sg.version
             FROM staff_groups sg
             JOIN group_memberships gm ON sg.id = gm.group_id
             WHERE gm.staff_id = $1
@@ -119,26 +215,140 @@ impl MembershipRepository for PgMembershipRepository {
     }
 
     #[tracing::instrument(skip(self))]
-    async fn resolve_members(&self, group_id: Uuid) -> Result<Vec<Staff>, DataServiceError> {
-        let output = sqlx::query_as!(
-            Staff,
+    async fn resolve_members(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+        page: PageRequest,
+    ) -> Result<MemberPage, DataServiceError> {
+        let limit = member_page_limit(&page);
+        let today = self.today();
+        let total = sqlx::query_scalar!(
             r#"
-            WITH RECURSIVE group_tree AS (
-                SELECT id FROM staff_groups WHERE id = $1
-                UNION ALL
-                SELECT sg.id FROM staff_groups sg
-                JOIN group_tree gt ON sg.parent_group_id = gt.id
-            )
-            SELECT DISTINCT s.id, s.name, s.email, s.position, s.status as "status: _", s.created_at, s.updated_at
+            SELECT COUNT(DISTINCT s.id) AS "count!"
             FROM staff s
             JOIN group_memberships gm ON s.id = gm.staff_id
-            JOIN group_tree gt ON gm.group_id = gt.id
+            JOIN group_closure gc ON gm.group_id = gc.descendant_id
+            WHERE gc.ancestor_id = $1 AND ($2::staff_status IS NULL OR s.status = $2)
+              AND ($3 OR gm.valid_from IS NULL OR gm.valid_from <= $4)
             "#,
-            group_id
+            group_id,
+            query.status.clone() as Option<StaffStatus>,
+            query.include_future,
+            today
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        // A membership without a start date makes the member current whatever the others say
+        let items = if query.count_only {
+            Vec::new()
+        } else {
+            sqlx::query_as!(
+                MemberRow,
+                r#"
+                SELECT s.id, s.name, s.email, s.position, s.fairness_weight, s.shift_eligibility as "shift_eligibility: _", s.status as "status: _", s.effective_until, s.created_at, s.updated_at, s.version,
+                    CASE WHEN bool_or(gm.valid_from IS NULL) THEN NULL ELSE MIN(gm.valid_from) END AS valid_from
+                FROM staff s
+                JOIN group_memberships gm ON s.id = gm.staff_id
+                JOIN group_closure gc ON gm.group_id = gc.descendant_id
+                WHERE gc.ancestor_id = $1 AND ($4::staff_status IS NULL OR s.status = $4)
+                  AND ($5 OR gm.valid_from IS NULL OR gm.valid_from <= $6)
+                GROUP BY s.id
+                ORDER BY s.name, s.id
+                LIMIT $2 OFFSET $3
+                "#,
+                group_id,
+                limit,
+                page.offset(),
+                query.status.clone() as Option<StaffStatus>,
+                query.include_future,
+                today
+            )
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(GroupMember::from)
+            .collect()
+        };
+
+        Ok(MemberPage {
+            items,
+            total,
+            limit,
+            offset: page.offset(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn resolve_members_by_position(
+        &self,
+        group_id: Uuid,
+        query: MemberQuery,
+    ) -> Result<Vec<PositionBucket>, DataServiceError> {
+        // The window count keeps the counts right when the member rows are skipped
+        let rows = sqlx::query!(
+            r#"
+            WITH members AS (
+                SELECT DISTINCT s.id, s.name, s.email, s.position, s.fairness_weight, s.shift_eligibility, s.status, s.effective_until, s.created_at, s.updated_at, s.version
+                FROM staff s
+                JOIN group_memberships gm ON s.id = gm.staff_id
+                JOIN group_closure gc ON gm.group_id = gc.descendant_id
+                WHERE gc.ancestor_id = $1 AND ($2::staff_status IS NULL OR s.status = $2)
+                  AND ($4 OR gm.valid_from IS NULL OR gm.valid_from <= $5)
+            ),
+            counted AS (
+                SELECT m.*,
+                    COUNT(*) OVER (PARTITION BY m.position) AS position_count,
+                    ROW_NUMBER() OVER (PARTITION BY m.position ORDER BY m.name, m.id) AS position_rank
+                FROM members m
+            )
+            SELECT id AS "id!", name AS "name!", email AS "email!", position AS "position!",
+                fairness_weight AS "fairness_weight!",
+                shift_eligibility AS "shift_eligibility!: ShiftEligibility", status AS "status!: StaffStatus",
+                effective_until, created_at AS "created_at!", updated_at AS "updated_at!",
+                version AS "version!", position_count AS "position_count!"
+            FROM counted
+            WHERE NOT $3 OR position_rank = 1
+            ORDER BY position, name, id
+            "#,
+            group_id,
+            query.status.clone() as Option<StaffStatus>,
+            query.count_only,
+            query.include_future,
+            self.today()
         )
         .fetch_all(&self.pool)
         .await?;
 
+        let mut output: Vec<PositionBucket> = Vec::new();
+        for row in rows {
+            if output.last().is_none_or(|b| b.position != row.position) {
+                output.push(PositionBucket {
+                    position: row.position.clone(),
+                    count: row.position_count,
+                    members: Vec::new(),
+                });
+            }
+            if !query.count_only
+                && let Some(bucket) = output.last_mut()
+            {
+                bucket.members.push(Staff {
+                    id: row.id,
+                    name: row.name,
+                    email: row.email,
+                    position: row.position,
+                    fairness_weight: row.fairness_weight,
+                    shift_eligibility: row.shift_eligibility,
+                    status: row.status,
+                    effective_until: row.effective_until,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                    version: row.version,
+                });
+            }
+        }
+
         Ok(output)
     }
 
@@ -146,22 +356,194 @@ impl MembershipRepository for PgMembershipRepository {
     async fn batch_add_members(
         &self,
         memberships: Vec<AddMembership>,
-    ) -> Result<(), DataServiceError> {
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
         let staff_ids: Vec<Uuid> = memberships.iter().map(|m| m.staff_id).collect();
         let group_ids: Vec<Uuid> = memberships.iter().map(|m| m.group_id).collect();
 
-        sqlx::query!(
+        let mut tx = self.pool.begin().await?;
+
+        // FOR SHARE keeps the referenced rows from being deleted before the insert
+        let known_staff: HashSet<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM staff WHERE id = ANY($1) FOR SHARE",
+            &staff_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let known_groups: HashSet<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM staff_groups WHERE id = ANY($1) FOR SHARE",
+            &group_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let valid: Vec<&AddMembership> = memberships
+            .iter()
+            .filter(|m| known_staff.contains(&m.staff_id) && known_groups.contains(&m.group_id))
+            .collect();
+        let valid_staff: Vec<Uuid> = valid.iter().map(|m| m.staff_id).collect();
+        let valid_groups: Vec<Uuid> = valid.iter().map(|m| m.group_id).collect();
+        let valid_from: Vec<Option<NaiveDate>> = valid.iter().map(|m| m.valid_from).collect();
+
+        let mut inserted: HashSet<(Uuid, Uuid)> = sqlx::query!(
             r#"
-            INSERT INTO group_memberships (staff_id, group_id)
-            SELECT * FROM UNNEST($1::uuid[], $2::uuid[])
+            INSERT INTO group_memberships (staff_id, group_id, valid_from)
+            SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::date[])
             ON CONFLICT DO NOTHING
+            RETURNING staff_id AS "staff_id!", group_id AS "group_id!"
+            "#,
+            &valid_staff,
+            &valid_groups,
+            &valid_from as &[Option<NaiveDate>]
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|row| (row.staff_id, row.group_id))
+        .collect();
+
+        tx.commit().await?;
+
+        let report = memberships
+            .into_iter()
+            .map(|m| {
+                let outcome = if !known_staff.contains(&m.staff_id) {
+                    MembershipOutcome::UnknownStaff
+                } else if !known_groups.contains(&m.group_id) {
+                    MembershipOutcome::UnknownGroup
+                } else if inserted.remove(&(m.staff_id, m.group_id)) {
+                    // Removed so a repeated pair later in the batch reports as duplicate
+                    MembershipOutcome::Inserted
+                } else {
+                    MembershipOutcome::Duplicate
+                };
+                MembershipResult {
+                    staff_id: m.staff_id,
+                    group_id: m.group_id,
+                    outcome,
+                }
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn batch_remove_members(
+        &self,
+        memberships: Vec<RemoveMembership>,
+    ) -> Result<Vec<MembershipResult>, DataServiceError> {
+        let staff_ids: Vec<Uuid> = memberships.iter().map(|m| m.staff_id).collect();
+        let group_ids: Vec<Uuid> = memberships.iter().map(|m| m.group_id).collect();
+
+        let mut removed: HashSet<(Uuid, Uuid)> = sqlx::query!(
+            r#"
+            DELETE FROM group_memberships gm
+            USING UNNEST($1::uuid[], $2::uuid[]) AS r(staff_id, group_id)
+            WHERE gm.staff_id = r.staff_id AND gm.group_id = r.group_id
+            RETURNING gm.staff_id, gm.group_id
             "#,
             &staff_ids,
             &group_ids
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.staff_id, row.group_id))
+        .collect();
+
+        let report = memberships
+            .into_iter()
+            .map(|m| {
+                let outcome = if removed.remove(&(m.staff_id, m.group_id)) {
+                    MembershipOutcome::Removed
+                } else {
+                    MembershipOutcome::NotMember
+                };
+                MembershipResult {
+                    staff_id: m.staff_id,
+                    group_id: m.group_id,
+                    outcome,
+                }
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_group_members(
+        &self,
+        group_id: Uuid,
+        staff_ids: Vec<Uuid>,
+    ) -> Result<MembershipChanges, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Two replacements of the same group run one after the other, not interleaved
+        sqlx::query_scalar!(
+            "SELECT id FROM staff_groups WHERE id = $1 FOR UPDATE",
+            group_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DataServiceError::NotFound("Group not found".to_string()))?;
+
+        let known_staff: HashSet<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM staff WHERE id = ANY($1) FOR SHARE",
+            &staff_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let mut unknown: Vec<Uuid> = staff_ids
+            .iter()
+            .filter(|id| !known_staff.contains(id))
+            .copied()
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort();
+            unknown.dedup();
+            let unknown: Vec<String> = unknown.iter().map(Uuid::to_string).collect();
+            return Err(DataServiceError::BadRequest(format!(
+                "Unknown staff: {}",
+                unknown.join(", ")
+            )));
+        }
+
+        let mut removed = sqlx::query_scalar!(
+            r#"
+            DELETE FROM group_memberships
+            WHERE group_id = $1 AND NOT (staff_id = ANY($2))
+            RETURNING staff_id AS "staff_id!"
+            "#,
+            group_id,
+            &staff_ids
+        )
+        .fetch_all(&mut *tx)
         .await?;
 
-        Ok(())
+        let mut added = sqlx::query_scalar!(
+            r#"
+            INSERT INTO group_memberships (group_id, staff_id)
+            SELECT $1, * FROM UNNEST($2::uuid[])
+            ON CONFLICT DO NOTHING
+            RETURNING staff_id AS "staff_id!"
+            "#,
+            group_id,
+            &staff_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        added.sort();
+        removed.sort();
+        Ok(MembershipChanges { added, removed })
     }
 }
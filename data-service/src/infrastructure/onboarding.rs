@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use shared::types::Staff;
+use sqlx::{PgConnection, PgPool, error::ErrorKind};
+use uuid::Uuid;
+
+use crate::{
+    domain::onboarding::{
+        AppliedOnboardingRule, CreateOnboardingRule, OnboardingRepository, OnboardingRule,
+    },
+    error::DataServiceError,
+    infrastructure::outbox,
+};
+
+/// Add new staff to the groups their position's rules name and log each rule applied,
+/// inside the caller's transaction. Returns how many rules were applied.
+pub async fn apply_onboarding_rules(
+    conn: &mut PgConnection,
+    staff: &[Staff],
+) -> Result<u64, DataServiceError> {
+    let ids: Vec<Uuid> = staff.iter().map(|s| s.id).collect();
+    let positions: Vec<String> = staff.iter().map(|s| s.position.clone()).collect();
+
+    let applied = sqlx::query!(
+        r#"
+        WITH matched AS (
+            SELECT s.id AS staff_id, r.id AS rule_id, r.group_id, s.position
+            FROM UNNEST($1::uuid[], $2::varchar[]) AS s(id, position)
+            JOIN onboarding_rules r ON r.position = s.position
+        ), added AS (
+            INSERT INTO group_memberships (staff_id, group_id)
+            SELECT staff_id, group_id FROM matched
+            ON CONFLICT DO NOTHING
+        )
+        INSERT INTO onboarding_log (staff_id, rule_id, group_id, position)
+        SELECT staff_id, rule_id, group_id, position FROM matched
+        "#,
+        &ids,
+        &positions
+    )
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    if applied > 0 {
+        outbox::enqueue(conn, outbox::MEMBERSHIP_LISTS).await?;
+        tracing::info!(applied, staff = staff.len(), "Applied onboarding rules");
+    }
+    Ok(applied)
+}
+
+pub struct PgOnboardingRepository {
+    pool: PgPool,
+}
+
+impl PgOnboardingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OnboardingRepository for PgOnboardingRepository {
+    #[tracing::instrument(skip(self))]
+    async fn create(&self, rule: CreateOnboardingRule) -> Result<OnboardingRule, DataServiceError> {
+        let output = sqlx::query_as!(
+            OnboardingRule,
+            r#"
+            INSERT INTO onboarding_rules (position, group_id)
+            VALUES ($1, $2)
+            RETURNING id, position, group_id, created_at
+            "#,
+            rule.position.trim(),
+            rule.group_id
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        output.map_err(|e| {
+            DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+                (ErrorKind::ForeignKeyViolation, Some("fk_or_group")) => {
+                    Some(DataServiceError::NotFound("Group not found".to_string()))
+                }
+                (ErrorKind::UniqueViolation, Some("uq_or_position_group")) => {
+                    Some(DataServiceError::Conflict(
+                        "Position already onboards into this group".to_string(),
+                    ))
+                }
+                _ => None,
+            })
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_all(&self) -> Result<Vec<OnboardingRule>, DataServiceError> {
+        let output = sqlx::query_as!(
+            OnboardingRule,
+            r#"
+            SELECT id, position, group_id, created_at
+            FROM onboarding_rules
+            ORDER BY position, created_at, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let output = sqlx::query!(
+            r#"
+            DELETE FROM onboarding_rules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if output.rows_affected() == 0 {
+            return Err(DataServiceError::NotFound(
+                "Onboarding rule not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_applied(
+        &self,
+        staff_id: Uuid,
+    ) -> Result<Vec<AppliedOnboardingRule>, DataServiceError> {
+        let output = sqlx::query_as!(
+            AppliedOnboardingRule,
+            r#"
+            SELECT rule_id, group_id, position, applied_at
+            FROM onboarding_log
+            WHERE staff_id = $1
+            ORDER BY id
+            "#,
+            staff_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+}
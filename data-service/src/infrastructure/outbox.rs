@@ -0,0 +1,73 @@
+use sqlx::{PgConnection, PgPool};
+
+use crate::error::DataServiceError;
+
+/// The cached staff list is out of date.
+pub const STAFF_LISTS: &str = "staff_lists";
+/// The cached group list is out of date.
+pub const GROUP_LISTS: &str = "group_lists";
+/// Cached member and group-of-staff lists are out of date. Only written for memberships
+/// added outside the membership repository, so the relay is what drops them.
+pub const MEMBERSHIP_LISTS: &str = "membership_lists";
+
+/// Record that `topic` needs invalidating, inside the caller's transaction.
+pub async fn enqueue(conn: &mut PgConnection, topic: &str) -> Result<(), DataServiceError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO cache_outbox (topic)
+        VALUES ($1)
+        "#,
+        topic
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub struct OutboxEntry {
+    pub id: i64,
+    pub topic: String,
+}
+
+pub struct PgCacheOutbox {
+    pool: PgPool,
+}
+
+impl PgCacheOutbox {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Oldest entries first.
+    pub async fn pending(&self, limit: i64) -> Result<Vec<OutboxEntry>, DataServiceError> {
+        let output = sqlx::query_as!(
+            OutboxEntry,
+            r#"
+            SELECT id, topic
+            FROM cache_outbox
+            ORDER BY id
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    pub async fn remove(&self, ids: &[i64]) -> Result<(), DataServiceError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM cache_outbox
+            WHERE id = ANY($1)
+            "#,
+            ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
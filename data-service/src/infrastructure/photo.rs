@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use object_store::{
+    ObjectStore, ObjectStoreExt, aws::AmazonS3Builder, local::LocalFileSystem, memory::InMemory,
+    path::Path,
+};
+use uuid::Uuid;
+
+use crate::domain::photo::{PhotoStorage, StaffPhoto, sniff_image_type};
+use crate::error::DataServiceError;
+
+/// Photo storage on any `object_store` backend (local filesystem, S3, in-memory).
+pub struct ObjectPhotoStorage {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectPhotoStorage {
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    /// Store photos under `root`, creating the directory if needed.
+    pub fn filesystem(root: &str) -> Result<Self, DataServiceError> {
+        std::fs::create_dir_all(root)
+            .map_err(|e| DataServiceError::Internal(format!("Photo directory {root}: {e}")))?;
+        let store = LocalFileSystem::new_with_prefix(root).map_err(storage_error)?;
+        Ok(Self::new(Arc::new(store)))
+    }
+
+    /// S3 bucket; credentials and region come from the standard `AWS_*` variables.
+    pub fn s3(bucket: &str) -> Result<Self, DataServiceError> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(storage_error)?;
+        Ok(Self::new(Arc::new(store)))
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(InMemory::new()))
+    }
+
+    fn location(staff_id: Uuid) -> Path {
+        Path::from(format!("staff-photos/{staff_id}"))
+    }
+}
+
+#[async_trait]
+impl PhotoStorage for ObjectPhotoStorage {
+    async fn put(&self, staff_id: Uuid, bytes: Bytes) -> Result<(), DataServiceError> {
+        self.store
+            .put(&Self::location(staff_id), bytes.into())
+            .await
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, staff_id: Uuid) -> Result<Option<StaffPhoto>, DataServiceError> {
+        let result = match self.store.get(&Self::location(staff_id)).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(storage_error(e)),
+        };
+        let meta = result.meta.clone();
+        let bytes = result.bytes().await.map_err(storage_error)?;
+
+        // Only validated images are ever written, so sniffing again recovers the type
+        // without relying on backend metadata support
+        let content_type = sniff_image_type(&bytes).unwrap_or("application/octet-stream");
+
+        Ok(Some(StaffPhoto {
+            content_type,
+            bytes,
+            etag: meta.e_tag,
+            last_modified: meta.last_modified,
+        }))
+    }
+
+    async fn delete(&self, staff_id: Uuid) -> Result<(), DataServiceError> {
+        match self.store.delete(&Self::location(staff_id)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+}
+
+fn storage_error(e: object_store::Error) -> DataServiceError {
+    DataServiceError::Internal(format!("Photo storage error: {e}"))
+}
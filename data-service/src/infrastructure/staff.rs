@@ -1,13 +1,46 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use shared::types::Staff;
-use sqlx::PgPool;
+use chrono::NaiveDate;
+use shared::pagination::PageRequest;
+use shared::types::{ShiftEligibility, Staff, StaffStatus};
+use sqlx::{PgPool, error::ErrorKind};
 use uuid::Uuid;
 
 use crate::{
-    domain::staff::{CreateStaff, StaffRepository, UpdateStaff},
+    domain::{
+        bulk::{Blocker, BlockerKind, BulkDelete, BulkDeleteOutcome, BulkDeleteResult},
+        duplicate::{DuplicateQuery, DuplicateReason, MergeResult, StaffDuplicate},
+        staff::{
+            CreateStaff, StaffPage, StaffQuery, StaffRepository, UpdateStaff, staff_page_limit,
+        },
+        version::missed_update,
+        webhook::StaffEventType,
+    },
     error::DataServiceError,
+    infrastructure::{onboarding::apply_onboarding_rules, outbox, webhook::enqueue_staff_events},
 };
 
+/// `ILIKE` pattern matching `q` anywhere, with its own wildcards taken literally.
+fn contains_pattern(q: &str) -> String {
+    let escaped = q
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+/// A second staff member with the same email is a 409, whichever statement wrote it.
+fn map_email_conflict(e: sqlx::Error) -> DataServiceError {
+    DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+        (ErrorKind::UniqueViolation, Some("uq_staff_email")) => Some(DataServiceError::Conflict(
+            "Email already exists".to_string(),
+        )),
+        _ => None,
+    })
+}
+
 pub struct PgStaffRepository {
     pool: PgPool,
 }
@@ -25,7 +58,7 @@ impl StaffRepository for PgStaffRepository {
         let output = sqlx::query_as!(
             Staff,
             r#"
-            SELECT id, name, email, position, status AS "status: _", created_at, updated_at
+            SELECT id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             FROM staff
             WHERE id = $1
         "#,
@@ -37,14 +70,17 @@ impl StaffRepository for PgStaffRepository {
         Ok(output)
     }
 
-    #[tracing::instrument(skip(self))]
-    async fn find_all(&self) -> Result<Vec<Staff>, DataServiceError> {
+    #[tracing::instrument(skip(self, ids), fields(count = ids.len()))]
+    async fn find_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, DataServiceError> {
         let output = sqlx::query_as!(
             Staff,
             r#"
-            SELECT id, name, email, position, status AS "status: _", created_at, updated_at
+            SELECT id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             FROM staff
-            "#
+            WHERE id = ANY($1)
+            ORDER BY name, id
+        "#,
+            &ids
         )
         .fetch_all(&self.pool)
         .await?;
@@ -52,21 +88,87 @@ impl StaffRepository for PgStaffRepository {
         Ok(output)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn find_all(
+        &self,
+        query: StaffQuery,
+        page: PageRequest,
+    ) -> Result<StaffPage, DataServiceError> {
+        let limit = staff_page_limit(&page);
+        let pattern = query.q.as_deref().map(contains_pattern);
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM staff
+            WHERE ($1::staff_status IS NULL OR status = $1)
+                AND ($2::text IS NULL OR position = $2)
+                AND ($3::text IS NULL OR name ILIKE $3 OR email ILIKE $3)
+            "#,
+            query.status.clone() as Option<StaffStatus>,
+            query.position,
+            pattern
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let items = sqlx::query_as!(
+            Staff,
+            r#"
+            SELECT id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            FROM staff
+            WHERE ($1::staff_status IS NULL OR status = $1)
+                AND ($2::text IS NULL OR position = $2)
+                AND ($3::text IS NULL OR name ILIKE $3 OR email ILIKE $3)
+            ORDER BY name, id
+            LIMIT $4 OFFSET $5
+            "#,
+            query.status as Option<StaffStatus>,
+            query.position,
+            pattern,
+            limit,
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(StaffPage {
+            items,
+            total,
+            limit,
+            offset: page.offset(),
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     async fn create(&self, staff: CreateStaff) -> Result<Staff, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
         let output = sqlx::query_as!(
             Staff,
             r#"
-            INSERT INTO staff (name, email, position)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, email, position, status AS "status: _", created_at, updated_at
+            INSERT INTO staff (name, email, position, fairness_weight, shift_eligibility, effective_until)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             "#,
             staff.name,
             staff.email,
-            staff.position
+            staff.position,
+            staff.fairness_weight,
+            staff.shift_eligibility as _,
+            staff.effective_until
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_email_conflict)?;
+
+        apply_onboarding_rules(&mut tx, std::slice::from_ref(&output)).await?;
+        enqueue_staff_events(
+            &mut tx,
+            StaffEventType::Created,
+            std::slice::from_ref(&output),
         )
-        .fetch_one(&self.pool)
         .await?;
+        tx.commit().await?;
 
         Ok(output)
     }
@@ -76,26 +178,49 @@ impl StaffRepository for PgStaffRepository {
         let names: Vec<String> = staffs.iter().map(|s| s.name.clone()).collect();
         let emails: Vec<String> = staffs.iter().map(|s| s.email.clone()).collect();
         let positions: Vec<String> = staffs.iter().map(|s| s.position.clone()).collect();
+        let weights: Vec<f64> = staffs.iter().map(|s| s.fairness_weight).collect();
+        let eligibilities: Vec<ShiftEligibility> =
+            staffs.iter().map(|s| s.shift_eligibility).collect();
+        let effective_until: Vec<Option<NaiveDate>> =
+            staffs.iter().map(|s| s.effective_until).collect();
+
+        let mut tx = self.pool.begin().await?;
 
         let output = sqlx::query_as!(
             Staff,
             r#"
-                INSERT INTO staff(name, email, position)
-                SELECT * FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[])
-                RETURNING id, name, email, position, status AS "status: _", created_at, updated_at
+                INSERT INTO staff(name, email, position, fairness_weight, shift_eligibility, effective_until)
+                SELECT * FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::float8[], $5::shift_eligibility[], $6::date[])
+                RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             "#,
             &names,
             &emails,
-            &positions
+            &positions,
+            &weights,
+            &eligibilities as &[ShiftEligibility],
+            &effective_until as &[Option<NaiveDate>]
         )
-        .fetch_all(&self.pool)
-        .await?;
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_email_conflict)?;
+
+        outbox::enqueue(&mut tx, outbox::STAFF_LISTS).await?;
+        apply_onboarding_rules(&mut tx, &output).await?;
+        enqueue_staff_events(&mut tx, StaffEventType::Created, &output).await?;
+        tx.commit().await?;
 
         Ok(output)
     }
 
     #[tracing::instrument(skip(self))]
-    async fn update(&self, id: Uuid, staff: UpdateStaff) -> Result<Staff, DataServiceError> {
+    async fn update(
+        &self,
+        id: Uuid,
+        staff: UpdateStaff,
+        expected_version: Option<i32>,
+    ) -> Result<Staff, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
         let output = sqlx::query_as!(
             Staff,
             r#"
@@ -104,58 +229,370 @@ impl StaffRepository for PgStaffRepository {
                 email = COALESCE($3,email),
                 position = COALESCE($4, position),
                 status = COALESCE($5, status),
+                fairness_weight = COALESCE($6, fairness_weight),
+                shift_eligibility = COALESCE($7, shift_eligibility),
+                effective_until = CASE WHEN $8 THEN $9 ELSE effective_until END,
                 updated_at = now()
-            WHERE id = $1
-            RETURNING id, name, email, position, status AS "status: _", created_at, updated_at
+            WHERE id = $1 AND ($10::int IS NULL OR version = $10)
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             "#,
             id,
             staff.name,
             staff.email,
             staff.position,
             staff.status as _,
+            staff.fairness_weight,
+            staff.shift_eligibility as _,
+            staff.effective_until.is_some(),
+            staff.effective_until.flatten(),
+            expected_version,
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_email_conflict)?;
+        let Some(output) = output else {
+            let exists = expected_version.is_some()
+                && sqlx::query_scalar!(
+                    r#"SELECT EXISTS(SELECT 1 FROM staff WHERE id = $1) AS "exists!""#,
+                    id
+                )
+                .fetch_one(&mut *tx)
+                .await?;
+            return Err(missed_update("Staff", exists));
+        };
+
+        enqueue_staff_events(
+            &mut tx,
+            StaffEventType::Updated,
+            std::slice::from_ref(&output),
         )
-        .fetch_optional(&self.pool)
         .await?;
+        tx.commit().await?;
 
-        output.ok_or_else(|| DataServiceError::NotFound("Staff not found".to_string()))
+        Ok(output)
     }
 
     #[tracing::instrument(skip(self))]
     async fn deactivate(&self, id: Uuid) -> Result<(), DataServiceError> {
-        let output = sqlx::query!(
+        let mut tx = self.pool.begin().await?;
+
+        let output = sqlx::query_as!(
+            Staff,
             r#"
             UPDATE staff
             SET status = 'INACTIVE', updated_at = now()
             WHERE id = $1
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             "#,
             id
         )
-        .execute(&self.pool)
-        .await?;
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DataServiceError::NotFound("Staff not found".to_string()))?;
 
-        if output.rows_affected() == 0 {
-            return Err(DataServiceError::NotFound("Staff not found".to_string()));
-        }
+        enqueue_staff_events(&mut tx, StaffEventType::Deactivated, &[output]).await?;
+        tx.commit().await?;
 
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn deactivate_expired(&self, today: NaiveDate) -> Result<Vec<Staff>, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let output = sqlx::query_as!(
+            Staff,
+            r#"
+            UPDATE staff
+            SET status = 'INACTIVE', updated_at = now()
+            WHERE status = 'ACTIVE' AND effective_until < $1
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            "#,
+            today
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        enqueue_staff_events(&mut tx, StaffEventType::Deactivated, &output).await?;
+        tx.commit().await?;
+
+        Ok(output)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
-        let output = sqlx::query!(
+        let mut tx = self.pool.begin().await?;
+
+        let output = sqlx::query_as!(
+            Staff,
             r#"
             DELETE FROM staff
             WHERE id = $1
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
             "#,
             id
         )
-        .execute(&self.pool)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DataServiceError::NotFound("Staff not found".to_string()))?;
+
+        enqueue_staff_events(&mut tx, StaffEventType::Deleted, &[output]).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, request), fields(count = request.ids.len()))]
+    async fn bulk_delete(
+        &self,
+        request: BulkDelete,
+        dry_run: bool,
+    ) -> Result<Vec<BulkDeleteResult>, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT i.id AS "id!",
+                   s.id IS NOT NULL AS "found!",
+                   (SELECT COUNT(*) FROM group_memberships gm WHERE gm.staff_id = i.id) AS "memberships!"
+            FROM UNNEST($1::uuid[]) WITH ORDINALITY AS i(id, position)
+            LEFT JOIN staff s ON s.id = i.id
+            ORDER BY i.position
+            "#,
+            &request.ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let results: Vec<BulkDeleteResult> = rows
+            .into_iter()
+            .map(|row| {
+                let blockers = vec![Blocker {
+                    kind: BlockerKind::Memberships,
+                    count: row.memberships,
+                }];
+                BulkDeleteResult::assess(row.id, row.found, blockers, request.cascade, dry_run)
+            })
+            .collect();
+        if dry_run {
+            return Ok(results);
+        }
+
+        let ids: Vec<Uuid> = results
+            .iter()
+            .filter(|result| result.outcome == BulkDeleteOutcome::Deleted)
+            .map(|result| result.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(results);
+        }
+        let deleted = sqlx::query_as!(
+            Staff,
+            r#"
+            DELETE FROM staff
+            WHERE id = ANY($1)
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            "#,
+            &ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        outbox::enqueue(&mut tx, outbox::STAFF_LISTS).await?;
+        enqueue_staff_events(&mut tx, StaffEventType::Deleted, &deleted).await?;
+        tx.commit().await?;
+
+        Ok(results)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_duplicates(
+        &self,
+        query: DuplicateQuery,
+    ) -> Result<Vec<StaffDuplicate>, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        // `%` compares against this threshold, which lets it use the trigram index
+        sqlx::query!(
+            r#"
+            SELECT set_config('pg_trgm.similarity_threshold', $1::real::text, true)
+            "#,
+            query.min_similarity()
+        )
+        .fetch_one(&mut *tx)
         .await?;
 
-        if output.rows_affected() == 0 {
+        let pairs = sqlx::query!(
+            r#"
+            WITH candidates AS (
+                SELECT a.id AS staff_id, b.id AS duplicate_id
+                FROM staff a
+                JOIN staff b
+                    ON lower(regexp_replace(btrim(b.name), '\s+', ' ', 'g'))
+                        = lower(regexp_replace(btrim(a.name), '\s+', ' ', 'g'))
+                    AND b.id <> a.id
+                UNION
+                SELECT a.id, b.id
+                FROM staff a
+                JOIN staff b ON lower(b.email) % lower(a.email) AND b.id <> a.id
+            )
+            SELECT a.id AS "staff_id!",
+                   b.id AS "duplicate_id!",
+                   lower(regexp_replace(btrim(a.name), '\s+', ' ', 'g'))
+                       = lower(regexp_replace(btrim(b.name), '\s+', ' ', 'g')) AS "same_name!",
+                   similarity(lower(a.email), lower(b.email)) AS "email_similarity!"
+            FROM candidates c
+            JOIN staff a ON a.id = c.staff_id
+            JOIN staff b ON b.id = c.duplicate_id
+            WHERE (a.created_at, a.id) < (b.created_at, b.id)
+                AND NOT EXISTS (
+                    SELECT 1 FROM staff_merges m WHERE m.merged_id IN (a.id, b.id)
+                )
+            ORDER BY 3 DESC, 4 DESC, a.created_at, a.id, b.created_at, b.id
+            LIMIT $1
+            "#,
+            query.limit()
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let ids: Vec<Uuid> = pairs
+            .iter()
+            .flat_map(|pair| [pair.staff_id, pair.duplicate_id])
+            .collect();
+        let staff: HashMap<Uuid, Staff> = sqlx::query_as!(
+            Staff,
+            r#"
+            SELECT id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            FROM staff
+            WHERE id = ANY($1)
+            "#,
+            &ids
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s))
+        .collect();
+        tx.commit().await?;
+
+        let min_similarity = query.min_similarity();
+        let output = pairs
+            .into_iter()
+            .filter_map(|pair| {
+                let mut reasons = Vec::new();
+                if pair.same_name {
+                    reasons.push(DuplicateReason::SameName);
+                }
+                if pair.email_similarity >= min_similarity {
+                    reasons.push(DuplicateReason::SimilarEmail);
+                }
+                Some(StaffDuplicate {
+                    staff: staff.get(&pair.staff_id)?.clone(),
+                    duplicate: staff.get(&pair.duplicate_id)?.clone(),
+                    reasons,
+                    email_similarity: pair.email_similarity,
+                })
+            })
+            .collect();
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn merge(&self, id: Uuid, duplicate_id: Uuid) -> Result<MergeResult, DataServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked = sqlx::query!(
+            r#"
+            SELECT s.id, EXISTS (SELECT 1 FROM staff_merges m WHERE m.merged_id = s.id) AS "merged!"
+            FROM staff s
+            WHERE s.id = ANY($1)
+            ORDER BY s.id
+            FOR UPDATE
+            "#,
+            &[id, duplicate_id]
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if locked.len() < 2 {
             return Err(DataServiceError::NotFound("Staff not found".to_string()));
         }
+        if let Some(merged) = locked.iter().find(|row| row.merged) {
+            return Err(DataServiceError::Conflict(format!(
+                "Staff {} was already merged into another record",
+                merged.id
+            )));
+        }
 
-        Ok(())
+        let memberships_moved = sqlx::query!(
+            r#"
+            WITH moved AS (
+                DELETE FROM group_memberships
+                WHERE staff_id = $2
+                RETURNING group_id
+            )
+            INSERT INTO group_memberships (staff_id, group_id)
+            SELECT $1, group_id FROM moved
+            ON CONFLICT DO NOTHING
+            "#,
+            id,
+            duplicate_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let merged = sqlx::query_as!(
+            Staff,
+            r#"
+            UPDATE staff
+            SET status = 'INACTIVE', updated_at = now()
+            WHERE id = $1
+            RETURNING id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            "#,
+            duplicate_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO staff_merges (merged_id, staff_id, memberships_moved)
+            VALUES ($1, $2, $3)
+            "#,
+            duplicate_id,
+            id,
+            memberships_moved as i64
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let staff = sqlx::query_as!(
+            Staff,
+            r#"
+            SELECT id, name, email, position, fairness_weight, shift_eligibility AS "shift_eligibility: _", status AS "status: _", effective_until, created_at, updated_at, version
+            FROM staff
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        outbox::enqueue(&mut tx, outbox::MEMBERSHIP_LISTS).await?;
+        enqueue_staff_events(
+            &mut tx,
+            StaffEventType::Deactivated,
+            std::slice::from_ref(&merged),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(MergeResult {
+            staff,
+            merged,
+            memberships_moved,
+        })
     }
 }
@@ -0,0 +1,179 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use shared::types::{TimeOffRequest, TimeOffStatus};
+use sqlx::{PgPool, error::ErrorKind};
+use uuid::Uuid;
+
+use crate::{
+    domain::time_off::{CreateTimeOff, TimeOffQuery, TimeOffRepository, UpdateTimeOff},
+    error::DataServiceError,
+};
+
+pub struct PgTimeOffRepository {
+    pool: PgPool,
+}
+
+impl PgTimeOffRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TimeOffRepository for PgTimeOffRepository {
+    #[tracing::instrument(skip(self))]
+    async fn create(
+        &self,
+        staff_id: Uuid,
+        request: CreateTimeOff,
+    ) -> Result<TimeOffRequest, DataServiceError> {
+        let output = sqlx::query_as!(
+            TimeOffRequest,
+            r#"
+            INSERT INTO time_off_requests (staff_id, start_date, end_date, reason)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, staff_id, start_date, end_date, reason, status AS "status: _", created_at, updated_at
+            "#,
+            staff_id,
+            request.start_date,
+            request.end_date,
+            request.reason
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        output.map_err(|e| {
+            DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+                (ErrorKind::ForeignKeyViolation, Some("fk_tor_staff")) => {
+                    Some(DataServiceError::NotFound("Staff not found".to_string()))
+                }
+                _ => None,
+            })
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_by_staff(
+        &self,
+        staff_id: Uuid,
+        query: TimeOffQuery,
+    ) -> Result<Vec<TimeOffRequest>, DataServiceError> {
+        let output = sqlx::query_as!(
+            TimeOffRequest,
+            r#"
+            SELECT id, staff_id, start_date, end_date, reason, status AS "status: _", created_at, updated_at
+            FROM time_off_requests
+            WHERE staff_id = $1
+              AND ($2::time_off_status IS NULL OR status = $2)
+              AND ($3::date IS NULL OR end_date >= $3)
+              AND ($4::date IS NULL OR start_date <= $4)
+            ORDER BY start_date, id
+            "#,
+            staff_id,
+            query.status as Option<TimeOffStatus>,
+            query.from,
+            query.to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update(
+        &self,
+        staff_id: Uuid,
+        request_id: Uuid,
+        update: UpdateTimeOff,
+    ) -> Result<TimeOffRequest, DataServiceError> {
+        let output = sqlx::query_as!(
+            TimeOffRequest,
+            r#"
+            UPDATE time_off_requests
+            SET start_date = COALESCE($3, start_date),
+                end_date = COALESCE($4, end_date),
+                reason = COALESCE($5, reason),
+                status = COALESCE($6, status),
+                updated_at = now()
+            WHERE id = $1 AND staff_id = $2
+            RETURNING id, staff_id, start_date, end_date, reason, status AS "status: _", created_at, updated_at
+            "#,
+            request_id,
+            staff_id,
+            update.start_date,
+            update.end_date,
+            update.reason,
+            update.status as Option<TimeOffStatus>
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            DataServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+                (ErrorKind::CheckViolation, Some("ck_tor_dates")) => Some(
+                    DataServiceError::BadRequest(
+                        "end_date must not be before start_date".to_string(),
+                    ),
+                ),
+                _ => None,
+            })
+        })?;
+
+        output.ok_or_else(|| DataServiceError::NotFound("Time off request not found".to_string()))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, staff_id: Uuid, request_id: Uuid) -> Result<(), DataServiceError> {
+        let output = sqlx::query!(
+            r#"
+            DELETE FROM time_off_requests
+            WHERE id = $1 AND staff_id = $2
+            "#,
+            request_id,
+            staff_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if output.rows_affected() == 0 {
+            return Err(DataServiceError::NotFound(
+                "Time off request not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_approved_for_group(
+        &self,
+        group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TimeOffRequest>, DataServiceError> {
+        let output = sqlx::query_as!(
+            TimeOffRequest,
+            r#"
+            SELECT t.id, t.staff_id, t.start_date, t.end_date, t.reason, t.status AS "status: _", t.created_at, t.updated_at
+            FROM time_off_requests t
+            WHERE t.status = 'APPROVED'
+              AND t.end_date >= $2
+              AND t.start_date <= $3
+              AND t.staff_id IN (
+                  SELECT gm.staff_id
+                  FROM group_memberships gm
+                  JOIN group_closure gc ON gm.group_id = gc.descendant_id
+                  WHERE gc.ancestor_id = $1
+              )
+            ORDER BY t.staff_id, t.start_date, t.id
+            "#,
+            group_id,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,314 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, header};
+use sha2::Sha256;
+use shared::types::Staff;
+use sqlx::{PgConnection, PgPool};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    domain::webhook::{
+        CreateWebhook, StaffEvent, StaffEventType, WebhookRepository, WebhookSubscription,
+    },
+    error::DataServiceError,
+};
+
+const BATCH_SIZE: i64 = 50;
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a claimed delivery stays hidden from other dispatchers while it is being sent.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+const FIRST_RETRY_DELAY_SECS: f64 = 30.0;
+const MAX_RETRY_DELAY_SECS: f64 = 3600.0;
+
+/// Queue a delivery of `event` for every staff member to each subscription that wants it,
+/// inside the caller's transaction.
+pub async fn enqueue_staff_events(
+    conn: &mut PgConnection,
+    event: StaffEventType,
+    staff: &[Staff],
+) -> Result<(), DataServiceError> {
+    let occurred_at = Utc::now();
+    let payloads = staff
+        .iter()
+        .map(|staff| {
+            serde_json::to_string(&StaffEvent {
+                event,
+                staff: staff.clone(),
+                occurred_at,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| DataServiceError::Internal(format!("Failed to encode staff event: {e}")))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (subscription_id, event_type, payload)
+        SELECT s.id, $1::text, p.payload
+        FROM webhook_subscriptions s
+        CROSS JOIN UNNEST($2::text[]) AS p(payload)
+        WHERE $1 = ANY(s.event_types)
+        "#,
+        event.as_str(),
+        &payloads
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+struct SubscriptionRow {
+    id: Uuid,
+    url: String,
+    event_types: Vec<String>,
+    max_retries: i16,
+    created_at: DateTime<Utc>,
+}
+
+impl From<SubscriptionRow> for WebhookSubscription {
+    fn from(row: SubscriptionRow) -> Self {
+        Self {
+            id: row.id,
+            url: row.url,
+            event_types: row
+                .event_types
+                .iter()
+                .filter_map(|event| StaffEventType::parse(event))
+                .collect(),
+            max_retries: row.max_retries,
+            created_at: row.created_at,
+        }
+    }
+}
+
+pub struct PgWebhookRepository {
+    pool: PgPool,
+}
+
+impl PgWebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PgWebhookRepository {
+    #[tracing::instrument(skip(self, webhook), fields(url = %webhook.url))]
+    async fn create(
+        &self,
+        webhook: CreateWebhook,
+    ) -> Result<WebhookSubscription, DataServiceError> {
+        let max_retries = webhook.max_retries();
+        let mut event_types: Vec<String> = Vec::new();
+        for event in &webhook.event_types {
+            if !event_types.iter().any(|known| known == event.as_str()) {
+                event_types.push(event.as_str().to_string());
+            }
+        }
+
+        let output = sqlx::query_as!(
+            SubscriptionRow,
+            r#"
+            INSERT INTO webhook_subscriptions (url, secret, event_types, max_retries)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, url, event_types, max_retries, created_at
+            "#,
+            webhook.url,
+            webhook.secret,
+            &event_types,
+            max_retries
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(output.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_all(&self) -> Result<Vec<WebhookSubscription>, DataServiceError> {
+        let output = sqlx::query_as!(
+            SubscriptionRow,
+            r#"
+            SELECT id, url, event_types, max_retries, created_at
+            FROM webhook_subscriptions
+            ORDER BY created_at, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output.into_iter().map(Into::into).collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<(), DataServiceError> {
+        let output = sqlx::query!(
+            r#"
+            DELETE FROM webhook_subscriptions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if output.rows_affected() == 0 {
+            return Err(DataServiceError::NotFound("Webhook not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+struct DueDelivery {
+    id: i64,
+    event_type: String,
+    payload: String,
+    attempts: i16,
+    url: String,
+    secret: String,
+    max_retries: i16,
+}
+
+/// Sends queued deliveries, retrying failed ones with exponential backoff until the
+/// subscription's `max_retries` is used up.
+pub struct WebhookDispatcher {
+    pool: PgPool,
+    client: Client,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: PgPool) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+        Ok(Self { pool, client })
+    }
+
+    /// Claim the deliveries that are due and send them concurrently. Returns how many were
+    /// attempted.
+    pub async fn drain(&self) -> Result<usize, DataServiceError> {
+        let due = sqlx::query_as!(
+            DueDelivery,
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM webhook_deliveries
+                WHERE next_attempt_at <= now()
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = now() + make_interval(secs => $2)
+            FROM due, webhook_subscriptions s
+            WHERE d.id = due.id AND s.id = d.subscription_id
+            RETURNING d.id, d.event_type, d.payload, d.attempts, s.url, s.secret, s.max_retries
+            "#,
+            BATCH_SIZE,
+            CLAIM_LEASE.as_secs_f64()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let count = due.len();
+        let mut sends = JoinSet::new();
+        for delivery in due {
+            let pool = self.pool.clone();
+            let client = self.client.clone();
+            sends.spawn(async move {
+                if let Err(e) = deliver(&pool, &client, delivery).await {
+                    tracing::warn!("Recording webhook delivery outcome failed: {e}");
+                }
+            });
+        }
+        sends.join_all().await;
+
+        Ok(count)
+    }
+
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.drain().await {
+                tracing::warn!("Webhook dispatch failed: {e}");
+            }
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(delivery_id = delivery.id, event = %delivery.event_type))]
+async fn deliver(
+    pool: &PgPool,
+    client: &Client,
+    delivery: DueDelivery,
+) -> Result<(), DataServiceError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(delivery.secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(delivery.payload.as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    let outcome = client
+        .post(&delivery.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Event", &delivery.event_type)
+        .header("X-Webhook-Delivery", delivery.id.to_string())
+        .header("X-Webhook-Signature", format!("sha256={signature}"))
+        .body(delivery.payload)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))
+        .and_then(|res| {
+            if res.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("receiver returned {}", res.status()))
+            }
+        });
+
+    match outcome {
+        Ok(()) => remove(pool, delivery.id).await,
+        Err(e) if delivery.attempts >= delivery.max_retries => {
+            tracing::warn!(url = %delivery.url, "Dropping webhook delivery after retries: {e}");
+            remove(pool, delivery.id).await
+        }
+        Err(e) => {
+            tracing::info!(url = %delivery.url, "Webhook delivery failed, will retry: {e}");
+            let delay = (FIRST_RETRY_DELAY_SECS * 2f64.powi(delivery.attempts.into()))
+                .min(MAX_RETRY_DELAY_SECS);
+            sqlx::query!(
+                r#"
+                UPDATE webhook_deliveries
+                SET attempts = attempts + 1,
+                    next_attempt_at = now() + make_interval(secs => $2)
+                WHERE id = $1
+                "#,
+                delivery.id,
+                delay
+            )
+            .execute(pool)
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+async fn remove(pool: &PgPool, id: i64) -> Result<(), DataServiceError> {
+    sqlx::query!(
+        r#"
+        DELETE FROM webhook_deliveries
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
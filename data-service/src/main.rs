@@ -1,57 +1,122 @@
 use axum::{
-    Router,
-    routing::{delete, get, patch, post},
+    Extension, Router,
+    routing::{delete, get, patch, post, put},
 };
+use chrono_tz::Tz;
 use data_service::{
     api::{
-        handler::{group, membership, staff},
+        handler::{
+            audit, blackout, group, health, membership, onboarding, staff, time_off, webhook,
+        },
         state::DataServiceAppState,
     },
+    domain::{
+        group::DEFAULT_MAX_GROUP_DEPTH,
+        staff::{StaffRepository, run_staff_expiry},
+    },
     infrastructure::{
+        MIGRATOR,
+        audit::{
+            AuditedGroupRepository, AuditedMembershipRepository, AuditedStaffRepository, PgAuditLog,
+        },
+        blackout::PgBlackoutRepository,
         cache::{
-            client::RedisCache, group::CachedGroupRepository,
-            membership::CachedMembershipRepository, staff::CachedStaffRepository,
+            DEFAULT_NEGATIVE_TTL, client::RedisCache, group::CachedGroupRepository,
+            membership::CachedMembershipRepository, relay::OutboxRelay,
+            staff::CachedStaffRepository,
         },
         group::PgGroupRepository,
+        health::{dependency_checks, startup_self_check},
         membership::PgMembershipRepository,
+        onboarding::PgOnboardingRepository,
+        outbox::PgCacheOutbox,
+        photo::ObjectPhotoStorage,
         staff::PgStaffRepository,
+        time_off::PgTimeOffRepository,
+        webhook::{PgWebhookRepository, WebhookDispatcher},
     },
 };
+use shared::auth::{AuthConfig, Authenticator, SecuritySchemes};
+use shared::startup::STARTUP_RETRY_INTERVAL;
 use sqlx::postgres::PgPoolOptions;
-use std::{env, sync::Arc};
+use std::{env, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+const STAFF_EXPIRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
         staff::find_all,
         staff::create,
         staff::batch_create,
+        staff::lookup,
         staff::find_by_id,
         staff::update,
+        staff::patch,
         staff::deactivate,
         staff::delete,
+        staff::bulk_delete,
+        staff::find_duplicates,
+        staff::merge,
+        staff::put_photo,
+        staff::get_photo,
         group::find_all,
         group::create,
         group::batch_create,
         group::find_by_id,
         group::update,
+        group::patch,
         group::delete,
+        group::bulk_delete,
+        group::depth_stats,
+        group::tree,
+        group::subtree,
         membership::add_member,
+        membership::set_group_members,
         membership::remove_member,
         membership::get_group_members,
         membership::get_staff_groups,
         membership::resolve_members,
+        membership::resolve_members_by_position,
         membership::batch_add_members,
+        membership::batch_remove_members,
+        blackout::create,
+        blackout::find_by_group,
+        blackout::delete,
+        webhook::create,
+        webhook::find_all,
+        webhook::delete,
+        onboarding::create,
+        onboarding::find_all,
+        onboarding::delete,
+        onboarding::find_applied,
+        time_off::create,
+        time_off::find_by_staff,
+        time_off::update,
+        time_off::delete,
+        time_off::find_resolved,
+        audit::find_all,
+        health::liveness,
+        health::readiness,
+        health::startup,
     ),
+    modifiers(&SecuritySchemes),
+    security(("bearer_auth" = [])),
     tags(
         (name = "Staff", description = "Staff management"),
         (name = "Groups", description = "Staff group management"),
         (name = "Membership", description = "Group membership management"),
+        (name = "Blackouts", description = "Group day-off blackout windows"),
+        (name = "Webhooks", description = "Staff lifecycle event subscriptions"),
+        (name = "Onboarding", description = "Groups new staff join by position"),
+        (name = "Time Off", description = "Staff time-off requests"),
+        (name = "Audit", description = "Who changed staff, groups and memberships"),
+        (name = "Health", description = "Liveness, readiness and the startup self-check"),
     )
 )]
 struct ApiDoc;
@@ -59,6 +124,7 @@ struct ApiDoc;
 #[tokio::main]
 async fn main() {
     let _guard = shared::telemetry::init_telemetry("data-service");
+    let metrics = shared::metrics::init_metrics();
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let port = env::var("SERVER_PORT").unwrap_or_else(|_| "8080".to_string());
@@ -69,7 +135,7 @@ async fn main() {
         .await
         .expect("Failed to establish connection into Postgres");
 
-    sqlx::migrate!()
+    MIGRATOR
         .run(&pool)
         .await
         .expect("Failed to run database migrations");
@@ -78,20 +144,110 @@ async fn main() {
     let cache = RedisCache::new(&redis_url)
         .await
         .expect("Failed to connect to Redis");
+    let max_group_depth = env::var("MAX_GROUP_DEPTH")
+        .ok()
+        .map(|depth| depth.parse().expect("MAX_GROUP_DEPTH must be a number"))
+        .unwrap_or(DEFAULT_MAX_GROUP_DEPTH);
+    // Decides when a staff member's last working day has passed and future memberships start
+    let timezone: Tz = env::var("TIMEZONE")
+        .map(|tz| tz.parse().expect("TIMEZONE must be an IANA timezone name"))
+        .unwrap_or(Tz::UTC);
+    let negative_ttl = env::var("CACHE_NEGATIVE_TTL_SECS")
+        .ok()
+        .map(|ttl| {
+            ttl.parse()
+                .expect("CACHE_NEGATIVE_TTL_SECS must be a number")
+        })
+        .unwrap_or(DEFAULT_NEGATIVE_TTL);
+
+    let photo_storage = match env::var("PHOTO_STORAGE").as_deref() {
+        Ok("s3") => {
+            let bucket = env::var("PHOTO_S3_BUCKET").expect("PHOTO_S3_BUCKET must be set");
+            ObjectPhotoStorage::s3(&bucket)
+        }
+        _ => {
+            let root = env::var("PHOTO_STORAGE_PATH").unwrap_or_else(|_| "photos".to_string());
+            ObjectPhotoStorage::filesystem(&root)
+        }
+    }
+    .expect("Failed to initialize photo storage");
+    let photo_storage = Arc::new(photo_storage);
+
+    let startup = Arc::new(startup_self_check(
+        pool.clone(),
+        cache.clone(),
+        photo_storage.clone(),
+        max_group_depth,
+    ));
+    startup.spawn(STARTUP_RETRY_INTERVAL);
+    let dependencies = Arc::new(dependency_checks(pool.clone(), cache.clone()));
+
+    tokio::spawn(
+        OutboxRelay::new(PgCacheOutbox::new(pool.clone()), cache.clone())
+            .run(Duration::from_secs(5)),
+    );
+    tokio::spawn(
+        WebhookDispatcher::new(pool.clone())
+            .expect("Failed to initialize webhook dispatcher")
+            .run(Duration::from_secs(5)),
+    );
+
+    let authenticator = Arc::new(Authenticator::new(AuthConfig::from_env()));
+    if !authenticator.is_enabled() {
+        tracing::warn!("AUTH_JWKS_URL and AUTH_JWT_SECRET not set, authentication is disabled");
+    }
+
+    let audit_log = Arc::new(PgAuditLog::new(pool.clone()));
+    let staff_repo: Arc<dyn StaffRepository> = Arc::new(AuditedStaffRepository::new(
+        Arc::new(
+            CachedStaffRepository::new(
+                Arc::new(PgStaffRepository::new(pool.clone())),
+                Arc::new(PgMembershipRepository::new(pool.clone(), timezone)),
+                Arc::new(PgGroupRepository::new(pool.clone())),
+                cache.clone(),
+            )
+            .with_negative_ttl(negative_ttl),
+        ),
+        audit_log.clone(),
+    ));
+    tokio::spawn(run_staff_expiry(
+        staff_repo.clone(),
+        timezone,
+        STAFF_EXPIRY_INTERVAL,
+    ));
 
     let state = Arc::new(DataServiceAppState {
-        staff_repo: Arc::new(CachedStaffRepository::new(
-            Arc::new(PgStaffRepository::new(pool.clone())),
-            cache.clone(),
-        )),
-        group_repo: Arc::new(CachedGroupRepository::new(
-            Arc::new(PgGroupRepository::new(pool.clone())),
-            cache.clone(),
+        staff_repo,
+        group_repo: Arc::new(AuditedGroupRepository::new(
+            Arc::new(
+                CachedGroupRepository::new(
+                    Arc::new(
+                        PgGroupRepository::new(pool.clone())
+                            .with_max_depth(max_group_depth)
+                            .with_timezone(timezone),
+                    ),
+                    cache.clone(),
+                )
+                .with_negative_ttl(negative_ttl),
+            ),
+            audit_log.clone(),
         )),
-        membership_repo: Arc::new(CachedMembershipRepository::new(
-            Arc::new(PgMembershipRepository::new(pool.clone())),
-            cache,
+        membership_repo: Arc::new(AuditedMembershipRepository::new(
+            Arc::new(CachedMembershipRepository::new(
+                Arc::new(PgMembershipRepository::new(pool.clone(), timezone)),
+                Arc::new(PgGroupRepository::new(pool.clone())),
+                cache,
+            )),
+            audit_log.clone(),
         )),
+        blackout_repo: Arc::new(PgBlackoutRepository::new(pool.clone())),
+        photo_storage,
+        webhook_repo: Arc::new(PgWebhookRepository::new(pool.clone())),
+        onboarding_repo: Arc::new(PgOnboardingRepository::new(pool.clone())),
+        time_off_repo: Arc::new(PgTimeOffRepository::new(pool)),
+        audit_log,
+        startup,
+        dependencies,
     });
 
     let app = Router::new()
@@ -103,33 +259,56 @@ async fn main() {
                 })
             }),
         )
+        .route("/healthz", get(health::liveness))
+        .route("/readyz", get(health::readiness))
+        .route("/healthz/startup", get(health::startup))
+        .route(
+            "/metrics",
+            get(move || std::future::ready(shared::metrics::render(&metrics))),
+        )
         // Staff routes
         .route("/api/v1/staff", get(staff::find_all).post(staff::create))
         .route("/api/v1/staff/batch", post(staff::batch_create))
+        .route("/api/v1/staff/lookup", post(staff::lookup))
+        .route("/api/v1/staff/bulk-delete", post(staff::bulk_delete))
+        .route("/api/v1/staff/duplicates", get(staff::find_duplicates))
         .route(
             "/api/v1/staff/{id}",
             get(staff::find_by_id)
                 .put(staff::update)
+                .patch(staff::patch)
                 .delete(staff::delete),
         )
         .route("/api/v1/staff/{id}/deactivate", patch(staff::deactivate))
+        .route("/api/v1/staff/{id}/merge", post(staff::merge))
+        .route(
+            "/api/v1/staff/{id}/photo",
+            get(staff::get_photo).put(staff::put_photo),
+        )
         // Group routes
         .route("/api/v1/groups", get(group::find_all).post(group::create))
         .route("/api/v1/groups/batch", post(group::batch_create))
+        .route("/api/v1/groups/bulk-delete", post(group::bulk_delete))
+        .route("/api/v1/groups/depth", get(group::depth_stats))
+        .route("/api/v1/groups/tree", get(group::tree))
+        .route("/api/v1/groups/{id}/subtree", get(group::subtree))
         .route(
             "/api/v1/groups/{id}",
             get(group::find_by_id)
                 .put(group::update)
+                .patch(group::patch)
                 .delete(group::delete),
         )
         // Membership routes
         .route(
             "/api/v1/memberships/batch",
-            post(membership::batch_add_members),
+            post(membership::batch_add_members).delete(membership::batch_remove_members),
         )
         .route(
             "/api/v1/groups/{group_id}/members",
-            get(membership::get_group_members).post(membership::add_member),
+            get(membership::get_group_members)
+                .post(membership::add_member)
+                .put(membership::set_group_members),
         )
         .route(
             "/api/v1/groups/{group_id}/members/{staff_id}",
@@ -139,16 +318,65 @@ async fn main() {
             "/api/v1/groups/{group_id}/resolved-members",
             get(membership::resolve_members),
         )
+        .route(
+            "/api/v1/groups/{group_id}/members/by-position",
+            get(membership::resolve_members_by_position),
+        )
+        // Blackout routes
+        .route(
+            "/api/v1/groups/{group_id}/blackouts",
+            get(blackout::find_by_group).post(blackout::create),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/blackouts/{blackout_id}",
+            delete(blackout::delete),
+        )
+        // Webhook routes
+        .route(
+            "/api/v1/webhooks",
+            get(webhook::find_all).post(webhook::create),
+        )
+        .route("/api/v1/webhooks/{id}", delete(webhook::delete))
+        // Onboarding routes
+        .route(
+            "/api/v1/onboarding-rules",
+            get(onboarding::find_all).post(onboarding::create),
+        )
+        .route("/api/v1/onboarding-rules/{id}", delete(onboarding::delete))
+        .route(
+            "/api/v1/staff/{id}/onboarding",
+            get(onboarding::find_applied),
+        )
+        // Time off routes
+        .route(
+            "/api/v1/staff/{id}/time-off",
+            get(time_off::find_by_staff).post(time_off::create),
+        )
+        .route(
+            "/api/v1/staff/{id}/time-off/{request_id}",
+            put(time_off::update).delete(time_off::delete),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/resolved-members/time-off",
+            get(time_off::find_resolved),
+        )
         // Staff's groups (optional)
         .route(
             "/api/v1/staff/{id}/groups",
             get(membership::get_staff_groups),
         )
+        // Audit routes
+        .route("/api/v1/audit", get(audit::find_all))
         // Swagger UI
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        // Inside the Extension layer, which hands it the authenticator
+        .layer(axum::middleware::from_fn(audit::scope_actor))
+        .layer(Extension(authenticator))
+        .layer(axum::middleware::from_fn(shared::metrics::track_requests))
         // tracing log (turn request into info level)
         .layer(
             TraceLayer::new_for_http()
+                .make_span_with(shared::telemetry::make_request_span)
                 .on_request(DefaultOnRequest::new().level(Level::INFO))
                 .on_response(
                     DefaultOnResponse::new()
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     http::{Request, StatusCode},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
 };
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use http_body_util::BodyExt;
 use serde_json::json;
 use tower::ServiceExt;
@@ -14,49 +14,167 @@ use uuid::Uuid;
 
 use data_service::{
     api::{
-        handler::{group, membership, staff},
+        handler::{
+            audit, blackout, group, health, membership, onboarding, staff, time_off, webhook,
+        },
         state::DataServiceAppState,
     },
     domain::{
-        group::MockGroupRepository, membership::MockMembershipRepository,
-        staff::MockStaffRepository,
+        audit::{AuditAction, AuditEntity, AuditEntry, AuditPage, AuditQuery, MockAuditLog},
+        blackout::MockBlackoutRepository,
+        bulk::{Blocker, BlockerKind, BulkDeleteResult},
+        duplicate::{DuplicateReason, MergeResult, StaffDuplicate},
+        group::{GroupDepthStats, GroupNode, MockGroupRepository},
+        membership::{
+            MemberPage, MemberQuery, MembershipChanges, MembershipOutcome, MembershipResult,
+            MockMembershipRepository, member_page_limit,
+        },
+        onboarding::{MockOnboardingRepository, OnboardingRule},
+        staff::{MAX_LOOKUP_IDS, MockStaffRepository, StaffPage, StaffQuery},
+        time_off::MockTimeOffRepository,
+        webhook::{MockWebhookRepository, WebhookSubscription},
     },
     error::DataServiceError,
+    infrastructure::{audit::AuditedStaffRepository, photo::ObjectPhotoStorage},
+};
+use shared::auth::{AuthConfig, Authenticator};
+use shared::health::DependencyChecks;
+use shared::pagination::PageRequest;
+use shared::startup::StartupSelfCheck;
+use shared::types::{
+    GroupBlackout, GroupMember, PositionBucket, ShiftEligibility, Staff, StaffGroup, StaffStatus,
+    TimeOffRequest, TimeOffStatus,
 };
-use shared::types::{Staff, StaffGroup, StaffStatus};
 
 fn build_test_app(
     mock_staff: MockStaffRepository,
     mock_group: MockGroupRepository,
     mock_membership: MockMembershipRepository,
 ) -> Router {
-    let state = Arc::new(DataServiceAppState {
+    test_router(DataServiceAppState {
         staff_repo: Arc::new(mock_staff),
         group_repo: Arc::new(mock_group),
         membership_repo: Arc::new(mock_membership),
-    });
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+fn build_blackout_test_app(mock_blackout: MockBlackoutRepository) -> Router {
+    test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(mock_blackout),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+fn build_webhook_test_app(mock_webhook: MockWebhookRepository) -> Router {
+    test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(mock_webhook),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+fn build_onboarding_test_app(mock_onboarding: MockOnboardingRepository) -> Router {
+    test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(mock_onboarding),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+fn build_time_off_test_app(mock_time_off: MockTimeOffRepository) -> Router {
+    test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(mock_time_off),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+fn test_router(state: DataServiceAppState) -> Router {
+    test_router_with_auth(state, Authenticator::disabled())
+}
 
+fn test_router_with_auth(state: DataServiceAppState, authenticator: Authenticator) -> Router {
     Router::new()
+        .route("/healthz", get(health::liveness))
+        .route("/readyz", get(health::readiness))
+        .route("/healthz/startup", get(health::startup))
         .route("/api/v1/staff", get(staff::find_all).post(staff::create))
         .route("/api/v1/staff/batch", post(staff::batch_create))
+        .route("/api/v1/staff/lookup", post(staff::lookup))
+        .route("/api/v1/staff/bulk-delete", post(staff::bulk_delete))
+        .route("/api/v1/staff/duplicates", get(staff::find_duplicates))
         .route(
             "/api/v1/staff/{id}",
             get(staff::find_by_id)
                 .put(staff::update)
+                .patch(staff::patch)
                 .delete(staff::delete),
         )
         .route("/api/v1/staff/{id}/deactivate", patch(staff::deactivate))
+        .route("/api/v1/staff/{id}/merge", post(staff::merge))
+        .route(
+            "/api/v1/staff/{id}/photo",
+            get(staff::get_photo).put(staff::put_photo),
+        )
         .route("/api/v1/groups", get(group::find_all).post(group::create))
         .route("/api/v1/groups/batch", post(group::batch_create))
+        .route("/api/v1/groups/bulk-delete", post(group::bulk_delete))
+        .route("/api/v1/groups/depth", get(group::depth_stats))
+        .route("/api/v1/groups/tree", get(group::tree))
+        .route("/api/v1/groups/{id}/subtree", get(group::subtree))
         .route(
             "/api/v1/groups/{id}",
             get(group::find_by_id)
                 .put(group::update)
+                .patch(group::patch)
                 .delete(group::delete),
         )
         .route(
             "/api/v1/groups/{group_id}/members",
-            get(membership::get_group_members).post(membership::add_member),
+            get(membership::get_group_members)
+                .post(membership::add_member)
+                .put(membership::set_group_members),
         )
         .route(
             "/api/v1/groups/{group_id}/members/{staff_id}",
@@ -64,17 +182,58 @@ fn build_test_app(
         )
         .route(
             "/api/v1/memberships/batch",
-            post(membership::batch_add_members),
+            post(membership::batch_add_members).delete(membership::batch_remove_members),
         )
         .route(
             "/api/v1/groups/{group_id}/resolved-members",
             get(membership::resolve_members),
         )
+        .route(
+            "/api/v1/groups/{group_id}/members/by-position",
+            get(membership::resolve_members_by_position),
+        )
         .route(
             "/api/v1/staff/{id}/groups",
             get(membership::get_staff_groups),
         )
-        .with_state(state)
+        .route(
+            "/api/v1/groups/{group_id}/blackouts",
+            get(blackout::find_by_group).post(blackout::create),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/blackouts/{blackout_id}",
+            delete(blackout::delete),
+        )
+        .route(
+            "/api/v1/webhooks",
+            get(webhook::find_all).post(webhook::create),
+        )
+        .route("/api/v1/webhooks/{id}", delete(webhook::delete))
+        .route(
+            "/api/v1/onboarding-rules",
+            get(onboarding::find_all).post(onboarding::create),
+        )
+        .route("/api/v1/onboarding-rules/{id}", delete(onboarding::delete))
+        .route(
+            "/api/v1/staff/{id}/time-off",
+            get(time_off::find_by_staff).post(time_off::create),
+        )
+        .route(
+            "/api/v1/staff/{id}/time-off/{request_id}",
+            put(time_off::update).delete(time_off::delete),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/resolved-members/time-off",
+            get(time_off::find_resolved),
+        )
+        .route(
+            "/api/v1/staff/{id}/onboarding",
+            get(onboarding::find_applied),
+        )
+        .route("/api/v1/audit", get(audit::find_all))
+        .layer(axum::middleware::from_fn(audit::scope_actor))
+        .layer(Extension(Arc::new(authenticator)))
+        .with_state(Arc::new(state))
 }
 
 fn make_staff(id: Uuid) -> Staff {
@@ -84,9 +243,44 @@ fn make_staff(id: Uuid) -> Staff {
         name: "Alice".to_string(),
         email: format!("alice-{id}@example.com"),
         position: "Nurse".to_string(),
+        fairness_weight: 1.0,
+        shift_eligibility: ShiftEligibility::Any,
         status: StaffStatus::Active,
+        effective_until: None,
         created_at: now,
         updated_at: now,
+        version: 1,
+    }
+}
+
+/// Page the way the Postgres repository does, so handlers see realistic envelopes.
+fn make_page(staff: Vec<Staff>, query: MemberQuery, page: PageRequest) -> MemberPage {
+    let limit = member_page_limit(&page);
+    let staff: Vec<Staff> = staff
+        .into_iter()
+        .filter(|s| {
+            query
+                .status
+                .as_ref()
+                .is_none_or(|status| s.status == *status)
+        })
+        .collect();
+    let total = staff.len() as i64;
+    let items = if query.count_only {
+        Vec::new()
+    } else {
+        staff
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(limit as usize)
+            .map(GroupMember::from)
+            .collect()
+    };
+    MemberPage {
+        items,
+        total,
+        limit,
+        offset: page.offset(),
     }
 }
 
@@ -98,6 +292,7 @@ fn make_group(id: Uuid) -> StaffGroup {
         parent_group_id: None,
         created_at: now,
         updated_at: now,
+        version: 1,
     }
 }
 
@@ -143,6 +338,36 @@ async fn create_staff_returns_ok() {
     assert_eq!(json["data"]["name"], "Alice");
 }
 
+#[tokio::test]
+async fn create_staff_invalid_fairness_weight_returns_400() {
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!({
+        "name": "Alice",
+        "email": "alice@example.com",
+        "position": "Nurse",
+        "fairness_weight": 0.0
+    });
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/staff")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn find_all_staff_returns_list() {
     let mut mock_staff = MockStaffRepository::new();
@@ -150,7 +375,15 @@ async fn find_all_staff_returns_list() {
 
     mock_staff
         .expect_find_all()
-        .returning(move || Ok(staff.clone()));
+        .withf(|query, page| *query == StaffQuery::default() && *page == PageRequest::default())
+        .returning(move |_, _| {
+            Ok(StaffPage {
+                items: staff.clone(),
+                total: 2,
+                limit: 100,
+                offset: 0,
+            })
+        });
 
     let app = build_test_app(
         mock_staff,
@@ -173,7 +406,77 @@ async fn find_all_staff_returns_list() {
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert!(json["success"].as_bool().unwrap());
-    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["items"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["total"], 2);
+}
+
+#[tokio::test]
+async fn find_all_staff_passes_filters_and_page() {
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff
+        .expect_find_all()
+        .withf(|query, page| {
+            query.status == Some(StaffStatus::Inactive)
+                && query.position.as_deref() == Some("Nurse")
+                && query.q.as_deref() == Some("ann")
+                && page.limit == Some(25)
+                && page.offset == Some(50)
+        })
+        .returning(|_, _| {
+            Ok(StaffPage {
+                items: vec![],
+                total: 60,
+                limit: 25,
+                offset: 50,
+            })
+        });
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/staff?status=inactive&position=Nurse&q=ann&limit=25&offset=50")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["total"], 60);
+    assert_eq!(json["data"]["offset"], 50);
+}
+
+#[tokio::test]
+async fn find_all_staff_rejects_bad_page_or_search() {
+    for uri in [
+        "/api/v1/staff?limit=5000",
+        "/api/v1/staff?offset=-1",
+        "/api/v1/staff?q=%20%20",
+    ] {
+        let mut mock_staff = MockStaffRepository::new();
+        mock_staff.expect_find_all().never();
+        let app = build_test_app(
+            mock_staff,
+            MockGroupRepository::new(),
+            MockMembershipRepository::new(),
+        );
+
+        let res = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
 }
 
 #[tokio::test]
@@ -239,6 +542,189 @@ async fn create_group_returns_ok() {
     assert_eq!(json["data"]["name"], "Ward A");
 }
 
+#[tokio::test]
+async fn create_group_duplicate_name_returns_409() {
+    let mut mock_group = MockGroupRepository::new();
+    mock_group.expect_create().returning(|_| {
+        Err(DataServiceError::Conflict(
+            "A group with this name already exists under the same parent".into(),
+        ))
+    });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!({
+        "name": "ward a",
+        "parent_group_id": Uuid::new_v4()
+    });
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/groups")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn create_group_too_deep_returns_422() {
+    let mut mock_group = MockGroupRepository::new();
+    mock_group.expect_create().returning(|_| {
+        Err(DataServiceError::Unprocessable(
+            "Group hierarchy would exceed 6 levels: A > B > C > D > E > F > G".into(),
+        ))
+    });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!({
+        "name": "G",
+        "parent_group_id": Uuid::new_v4()
+    });
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/groups")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["error"].as_str().unwrap().ends_with("E > F > G"));
+}
+
+#[tokio::test]
+async fn group_depth_stats_is_not_read_as_an_id() {
+    let mut mock_group = MockGroupRepository::new();
+    mock_group.expect_depth_stats().returning(|| {
+        Ok(GroupDepthStats {
+            max_depth: 4,
+            limit: 6,
+        })
+    });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/groups/depth")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["max_depth"], 4);
+}
+
+#[tokio::test]
+async fn group_tree_returns_nested_groups_with_member_counts() {
+    let (hospital, surgery) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut mock_group = MockGroupRepository::new();
+    mock_group
+        .expect_tree()
+        .withf(|root| root.is_none())
+        .returning(move |_| {
+            Ok(vec![GroupNode {
+                id: hospital,
+                name: "Hospital".to_string(),
+                parent_group_id: None,
+                member_count: 2,
+                children: vec![GroupNode {
+                    id: surgery,
+                    name: "Surgery".to_string(),
+                    parent_group_id: Some(hospital),
+                    member_count: 5,
+                    children: vec![],
+                }],
+            }])
+        });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/groups/tree")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["member_count"], 2);
+    assert_eq!(json["data"][0]["children"][0]["name"], "Surgery");
+    assert_eq!(json["data"][0]["children"][0]["member_count"], 5);
+}
+
+#[tokio::test]
+async fn group_subtree_of_unknown_group_returns_404() {
+    let id = Uuid::new_v4();
+    let mut mock_group = MockGroupRepository::new();
+    mock_group
+        .expect_tree()
+        .withf(move |root| *root == Some(id))
+        .returning(|_| Ok(vec![]));
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/groups/{id}/subtree"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn resolve_members_returns_nested() {
     let mut mock_membership = MockMembershipRepository::new();
@@ -246,7 +732,7 @@ async fn resolve_members_returns_nested() {
 
     mock_membership
         .expect_resolve_members()
-        .returning(move |_| Ok(staff.clone()));
+        .returning(move |_, query, page| Ok(make_page(staff.clone(), query, page)));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -270,7 +756,8 @@ async fn resolve_members_returns_nested() {
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert!(json["success"].as_bool().unwrap());
-    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"]["items"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"]["total"], 1);
 }
 
 // -- Staff update / delete / deactivate tests --
@@ -284,7 +771,7 @@ async fn update_staff_returns_updated() {
 
     mock_staff
         .expect_update()
-        .returning(move |_, _| Ok(updated.clone()));
+        .returning(move |_, _, _| Ok(updated.clone()));
 
     let app = build_test_app(
         mock_staff,
@@ -318,7 +805,7 @@ async fn update_staff_not_found_returns_404() {
     let mut mock_staff = MockStaffRepository::new();
     mock_staff
         .expect_update()
-        .returning(|_, _| Err(DataServiceError::NotFound("Staff not found".into())));
+        .returning(|_, _, _| Err(DataServiceError::NotFound("Staff not found".into())));
 
     let app = build_test_app(
         mock_staff,
@@ -342,6 +829,57 @@ async fn update_staff_not_found_returns_404() {
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn update_staff_checks_the_if_match_version() {
+    let mut mock_staff = MockStaffRepository::new();
+    let staff_id = Uuid::new_v4();
+    mock_staff
+        .expect_update()
+        .withf(|_, _, expected| *expected == Some(3))
+        .times(1)
+        .returning(|id, _, _| {
+            Ok(Staff {
+                version: 4,
+                ..make_staff(id)
+            })
+        });
+    mock_staff
+        .expect_update()
+        .withf(|_, _, expected| *expected == Some(2))
+        .times(1)
+        .returning(|_, _, _| {
+            Err(DataServiceError::PreconditionFailed(
+                "Staff has changed since the given ETag".into(),
+            ))
+        });
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+    let put = |if_match: &str| {
+        Request::builder()
+            .method("PUT")
+            .uri(format!("/api/v1/staff/{staff_id}"))
+            .header("content-type", "application/json")
+            .header("if-match", if_match)
+            .body(Body::from(r#"{"name":"Alice Updated"}"#))
+            .unwrap()
+    };
+
+    let res = app.clone().oneshot(put("\"3\"")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["etag"], "\"4\"");
+
+    let res = app.clone().oneshot(put("\"2\"")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+    // Not an ETag this service hands out, so it can't match anything
+    let res = app.oneshot(put("W/\"3\"")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn delete_staff_returns_ok() {
     let mut mock_staff = MockStaffRepository::new();
@@ -454,13 +992,11 @@ async fn create_staff_duplicate_email_returns_409() {
 }
 
 #[tokio::test]
-async fn batch_create_staff_returns_list() {
+async fn update_staff_duplicate_email_returns_409() {
     let mut mock_staff = MockStaffRepository::new();
-    let staff = vec![make_staff(Uuid::new_v4()), make_staff(Uuid::new_v4())];
-
     mock_staff
-        .expect_batch_create()
-        .returning(move |_| Ok(staff.clone()));
+        .expect_update()
+        .returning(|_, _, _| Err(DataServiceError::Conflict("Email already exists".into())));
 
     let app = build_test_app(
         mock_staff,
@@ -468,15 +1004,82 @@ async fn batch_create_staff_returns_list() {
         MockMembershipRepository::new(),
     );
 
-    let body = json!([
-        { "name": "A", "email": "a@example.com", "position": "Staff" },
-        { "name": "B", "email": "b@example.com", "position": "Staff" }
-    ]);
-
+    let staff_id = Uuid::new_v4();
+    let body = json!({ "email": "taken@example.com" });
     let res = app
         .oneshot(
             Request::builder()
-                .method("POST")
+                .method("PUT")
+                .uri(format!("/api/v1/staff/{staff_id}"))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn batch_create_staff_duplicate_email_returns_409() {
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff
+        .expect_batch_create()
+        .returning(|_| Err(DataServiceError::Conflict("Email already exists".into())));
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!([
+        { "name": "A", "email": "same@example.com", "position": "Staff" },
+        { "name": "B", "email": "same@example.com", "position": "Staff" }
+    ]);
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/staff/batch")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"], "Email already exists");
+}
+
+#[tokio::test]
+async fn batch_create_staff_returns_list() {
+    let mut mock_staff = MockStaffRepository::new();
+    let staff = vec![make_staff(Uuid::new_v4()), make_staff(Uuid::new_v4())];
+
+    mock_staff
+        .expect_batch_create()
+        .returning(move |_| Ok(staff.clone()));
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!([
+        { "name": "A", "email": "a@example.com", "position": "Staff" },
+        { "name": "B", "email": "b@example.com", "position": "Staff" }
+    ]);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
                 .uri("/api/v1/staff/batch")
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_vec(&body).unwrap()))
@@ -504,7 +1107,7 @@ async fn update_group_returns_updated() {
 
     mock_group
         .expect_update()
-        .returning(move |_, _| Ok(updated.clone()));
+        .returning(move |_, _, _| Ok(updated.clone()));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -533,6 +1136,161 @@ async fn update_group_returns_updated() {
     assert_eq!(json["data"]["name"], "Ward B");
 }
 
+#[tokio::test]
+async fn patch_group_with_null_parent_clears_it() {
+    let mut mock_group = MockGroupRepository::new();
+    let group_id = Uuid::new_v4();
+    let updated = make_group(group_id);
+
+    mock_group
+        .expect_update()
+        .withf(|_, group, _| group.name.is_none() && group.parent_group_id == Some(None))
+        .returning(move |_, _, _| Ok(updated.clone()));
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        mock_group,
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!({ "parent_group_id": null });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/groups/{group_id}"))
+                .header("content-type", "application/merge-patch+json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn patch_staff_with_null_name_returns_400() {
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff.expect_update().never();
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let staff_id = Uuid::new_v4();
+    let body = json!({ "name": null, "position": "Doctor" });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/staff/{staff_id}"))
+                .header("content-type", "application/merge-patch+json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn patch_staff_sets_shift_eligibility() {
+    let mut mock_staff = MockStaffRepository::new();
+    let staff_id = Uuid::new_v4();
+    let mut updated = make_staff(staff_id);
+    updated.shift_eligibility = ShiftEligibility::MorningOnly;
+
+    mock_staff
+        .expect_update()
+        .withf(|_, staff, _| {
+            staff.shift_eligibility == Some(ShiftEligibility::MorningOnly) && staff.name.is_none()
+        })
+        .returning(move |_, _, _| Ok(updated.clone()));
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!({ "shift_eligibility": "MORNING_ONLY" });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/staff/{staff_id}"))
+                .header("content-type", "application/merge-patch+json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["shift_eligibility"], "MORNING_ONLY");
+}
+
+#[tokio::test]
+async fn patch_staff_sets_and_withdraws_a_termination_date() {
+    let mut mock_staff = MockStaffRepository::new();
+    let staff_id = Uuid::new_v4();
+    let last_day = NaiveDate::from_ymd_opt(2026, 11, 30).unwrap();
+
+    mock_staff
+        .expect_update()
+        .withf(move |_, staff, _| staff.effective_until == Some(Some(last_day)))
+        .times(1)
+        .returning(move |id, _, _| {
+            Ok(Staff {
+                effective_until: Some(last_day),
+                ..make_staff(id)
+            })
+        });
+    mock_staff
+        .expect_update()
+        .withf(|_, staff, _| staff.effective_until == Some(None))
+        .times(1)
+        .returning(|id, _, _| Ok(make_staff(id)));
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let patch = |body: serde_json::Value| {
+        Request::builder()
+            .method("PATCH")
+            .uri(format!("/api/v1/staff/{staff_id}"))
+            .header("content-type", "application/merge-patch+json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(patch(json!({ "effective_until": "2026-11-30" })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["effective_until"], "2026-11-30");
+
+    let res = app
+        .oneshot(patch(json!({ "effective_until": null })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 async fn delete_group_returns_ok() {
     let mut mock_group = MockGroupRepository::new();
@@ -629,7 +1387,8 @@ async fn add_member_returns_ok() {
     let mut mock_membership = MockMembershipRepository::new();
     mock_membership
         .expect_add_staff_to_group()
-        .returning(|_, _| Ok(()));
+        .withf(|_, _, valid_from| valid_from.is_none())
+        .returning(|_, _, _| Ok(()));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -692,7 +1451,7 @@ async fn get_group_members_returns_list() {
 
     mock_membership
         .expect_get_group_members()
-        .returning(move |_| Ok(staff.clone()));
+        .returning(move |_, query, page| Ok(make_page(staff.clone(), query, page)));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -716,17 +1475,22 @@ async fn get_group_members_returns_list() {
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert!(json["success"].as_bool().unwrap());
-    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["items"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["limit"], 500);
+    assert_eq!(json["data"]["offset"], 0);
 }
 
 #[tokio::test]
-async fn get_staff_groups_returns_list() {
+async fn get_group_members_passes_paging_and_count_only() {
     let mut mock_membership = MockMembershipRepository::new();
-    let groups = vec![make_group(Uuid::new_v4())];
+    let staff = vec![make_staff(Uuid::new_v4()), make_staff(Uuid::new_v4())];
 
     mock_membership
-        .expect_get_staff_groups()
-        .returning(move |_| Ok(groups.clone()));
+        .expect_get_group_members()
+        .withf(|_, query, page| {
+            query.count_only && page.limit == Some(10) && page.offset == Some(20)
+        })
+        .returning(move |_, query, page| Ok(make_page(staff.clone(), query, page)));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -734,11 +1498,13 @@ async fn get_staff_groups_returns_list() {
         mock_membership,
     );
 
-    let staff_id = Uuid::new_v4();
+    let group_id = Uuid::new_v4();
     let res = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/staff/{staff_id}/groups"))
+                .uri(format!(
+                    "/api/v1/groups/{group_id}/members?limit=10&offset=20&count_only=true"
+                ))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -749,16 +1515,22 @@ async fn get_staff_groups_returns_list() {
 
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    assert!(json["success"].as_bool().unwrap());
-    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"]["total"], 2);
+    assert!(json["data"]["items"].as_array().unwrap().is_empty());
 }
 
 #[tokio::test]
-async fn batch_add_members_returns_ok() {
+async fn resolve_members_filters_by_status() {
     let mut mock_membership = MockMembershipRepository::new();
+    let active = make_staff(Uuid::new_v4());
+    let mut inactive = make_staff(Uuid::new_v4());
+    inactive.status = StaffStatus::Inactive;
+    let staff = vec![active.clone(), inactive];
+
     mock_membership
-        .expect_batch_add_members()
-        .returning(|_| Ok(()));
+        .expect_resolve_members()
+        .withf(|_, query, _| query.status == Some(StaffStatus::Active))
+        .returning(move |_, query, page| Ok(make_page(staff.clone(), query, page)));
 
     let app = build_test_app(
         MockStaffRepository::new(),
@@ -766,22 +1538,1389 @@ async fn batch_add_members_returns_ok() {
         mock_membership,
     );
 
-    let body = json!([
-        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() },
-        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() }
-    ]);
+    let group_id = Uuid::new_v4();
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/groups/{group_id}/resolved-members?status=active"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["total"], 1);
+    assert_eq!(json["data"]["items"][0]["id"], active.id.to_string());
+}
+
+#[tokio::test]
+async fn future_members_are_listed_with_their_start_date_on_request() {
+    let mut mock_membership = MockMembershipRepository::new();
+    let start = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    let new_hire = make_staff(Uuid::new_v4());
+    let new_hire_id = new_hire.id;
+
+    mock_membership
+        .expect_add_staff_to_group()
+        .withf(move |_, staff_id, valid_from| {
+            *staff_id == new_hire_id && *valid_from == Some(start)
+        })
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    mock_membership
+        .expect_resolve_members()
+        .withf(|_, query, _| query.include_future)
+        .returning(move |_, _, page| {
+            Ok(MemberPage {
+                items: vec![GroupMember {
+                    staff: new_hire.clone(),
+                    valid_from: Some(start),
+                }],
+                total: 1,
+                limit: member_page_limit(&page),
+                offset: page.offset(),
+            })
+        });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
 
+    let group_id = Uuid::new_v4();
+    let body = json!({ "staff_id": new_hire_id, "group_id": group_id, "valid_from": "2026-03-02" });
     let res = app
+        .clone()
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/v1/memberships/batch")
+                .uri(format!("/api/v1/groups/{group_id}/members"))
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_vec(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/groups/{group_id}/resolved-members?include_future=true"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let member = &json["data"]["items"][0];
+    assert_eq!(member["id"], new_hire_id.to_string());
+    assert_eq!(member["valid_from"], "2026-03-02");
+}
+
+#[tokio::test]
+async fn resolve_members_by_position_returns_buckets() {
+    let mut mock_membership = MockMembershipRepository::new();
+    let nurse = make_staff(Uuid::new_v4());
+    let mut doctor = make_staff(Uuid::new_v4());
+    doctor.position = "Doctor".to_string();
+    let buckets = vec![
+        PositionBucket {
+            position: "Doctor".to_string(),
+            count: 1,
+            members: vec![doctor],
+        },
+        PositionBucket {
+            position: "Nurse".to_string(),
+            count: 1,
+            members: vec![nurse.clone()],
+        },
+    ];
+
+    mock_membership
+        .expect_resolve_members_by_position()
+        .withf(|_, query| query.status == Some(StaffStatus::Active) && !query.count_only)
+        .returning(move |_, _| Ok(buckets.clone()));
 
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
+
+    let group_id = Uuid::new_v4();
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/groups/{group_id}/members/by-position?status=active"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["position"], "Doctor");
+    assert_eq!(json["data"][1]["count"], 1);
+    assert_eq!(json["data"][1]["members"][0]["id"], nurse.id.to_string());
+}
+
+#[tokio::test]
+async fn resolve_members_rejects_oversized_limit() {
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/groups/{}/resolved-members?limit=5000",
+                    Uuid::new_v4()
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_staff_groups_returns_list() {
+    let mut mock_membership = MockMembershipRepository::new();
+    let groups = vec![make_group(Uuid::new_v4())];
+
+    mock_membership
+        .expect_get_staff_groups()
+        .returning(move |_| Ok(groups.clone()));
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
+
+    let staff_id = Uuid::new_v4();
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/staff/{staff_id}/groups"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["success"].as_bool().unwrap());
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn batch_add_members_returns_per_item_report() {
+    let mut mock_membership = MockMembershipRepository::new();
+    mock_membership
+        .expect_batch_add_members()
+        .returning(|memberships| {
+            let outcomes = [MembershipOutcome::Inserted, MembershipOutcome::UnknownStaff];
+            Ok(memberships
+                .into_iter()
+                .zip(outcomes)
+                .map(|(m, outcome)| MembershipResult {
+                    staff_id: m.staff_id,
+                    group_id: m.group_id,
+                    outcome,
+                })
+                .collect())
+        });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
+
+    let body = json!([
+        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() },
+        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() }
+    ]);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/memberships/batch")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let report = json["data"].as_array().unwrap();
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0]["outcome"], "INSERTED");
+    assert_eq!(report[1]["outcome"], "UNKNOWN_STAFF");
+}
+
+#[tokio::test]
+async fn batch_remove_members_reports_pairs_that_were_not_members() {
+    let mut mock_membership = MockMembershipRepository::new();
+    mock_membership
+        .expect_batch_remove_members()
+        .returning(|memberships| {
+            let outcomes = [MembershipOutcome::Removed, MembershipOutcome::NotMember];
+            Ok(memberships
+                .into_iter()
+                .zip(outcomes)
+                .map(|(m, outcome)| MembershipResult {
+                    staff_id: m.staff_id,
+                    group_id: m.group_id,
+                    outcome,
+                })
+                .collect())
+        });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
+
+    let body = json!([
+        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() },
+        { "staff_id": Uuid::new_v4(), "group_id": Uuid::new_v4() }
+    ]);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/v1/memberships/batch")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let report = json["data"].as_array().unwrap();
+    assert_eq!(report[0]["outcome"], "REMOVED");
+    assert_eq!(report[1]["outcome"], "NOT_MEMBER");
+}
+
+#[tokio::test]
+async fn set_group_members_returns_the_changes() {
+    let group_id = Uuid::new_v4();
+    let (kept, added, removed) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut mock_membership = MockMembershipRepository::new();
+    mock_membership
+        .expect_set_group_members()
+        .withf(move |id, staff_ids| *id == group_id && *staff_ids == [kept, added])
+        .returning(move |_, _| {
+            Ok(MembershipChanges {
+                added: vec![added],
+                removed: vec![removed],
+            })
+        });
+
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        mock_membership,
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/groups/{group_id}/members"))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&json!({ "staff_ids": [kept, added] })).unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["added"], json!([added]));
+    assert_eq!(json["data"]["removed"], json!([removed]));
+}
+
+#[tokio::test]
+async fn staff_photo_round_trip_with_etag() {
+    let mut staff = MockStaffRepository::new();
+    let id = Uuid::new_v4();
+    let s = make_staff(id);
+    staff
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(s.clone())));
+
+    let app = build_test_app(
+        staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+    let png = b"\x89PNG\r\n\x1a\n fake image data".to_vec();
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/staff/{id}/photo"))
+                .header("content-type", "image/png")
+                .body(Body::from(png.clone()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/staff/{id}/photo"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "image/png");
+    assert!(res.headers().contains_key("cache-control"));
+    let etag = res.headers()["etag"].clone();
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(body.as_ref(), png.as_slice());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/staff/{id}/photo"))
+                .header("if-none-match", etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn put_staff_photo_rejects_non_image() {
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/staff/{}/photo", Uuid::new_v4()))
+                .header("content-type", "image/png")
+                .body(Body::from("<svg onload=alert(1)>"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn lookup_staff_dedups_ids_and_returns_matches() {
+    let mut mock_staff = MockStaffRepository::new();
+    let known = Uuid::new_v4();
+    let unknown = Uuid::new_v4();
+    let staff = make_staff(known);
+
+    mock_staff
+        .expect_find_by_ids()
+        .withf(|ids| ids.len() == 2)
+        .times(1)
+        .returning(move |_| Ok(vec![staff.clone()]));
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let body = json!([known, unknown, known]);
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/staff/lookup")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let data = json["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], known.to_string());
+}
+
+#[tokio::test]
+async fn lookup_staff_rejects_oversized_batches_without_a_query() {
+    // No expectations: an empty or oversized batch must not reach the database
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+    let lookup = |ids: Vec<Uuid>| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/staff/lookup")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&ids).unwrap()))
+                .unwrap(),
+        )
+    };
+
+    let res = lookup(vec![]).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"], json!([]));
+
+    let ids = (0..=MAX_LOOKUP_IDS).map(|_| Uuid::new_v4()).collect();
+    let res = lookup(ids).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_blackout_returns_window() {
+    let group_id = Uuid::new_v4();
+    let mut mock_blackout = MockBlackoutRepository::new();
+    mock_blackout
+        .expect_create()
+        .withf(move |id, b| *id == group_id && b.max_off_percent == 20)
+        .times(1)
+        .returning(|group_id, b| {
+            Ok(GroupBlackout {
+                id: Uuid::new_v4(),
+                group_id,
+                start_date: b.start_date,
+                end_date: b.end_date,
+                max_off_percent: b.max_off_percent,
+                reason: b.reason,
+                created_at: Utc::now(),
+            })
+        });
+
+    let app = build_blackout_test_app(mock_blackout);
+    let body = json!({
+        "start_date": "2026-12-20",
+        "end_date": "2027-01-02",
+        "max_off_percent": 20,
+        "reason": "Holiday season"
+    });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/groups/{group_id}/blackouts"))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["end_date"], "2027-01-02");
+}
+
+#[tokio::test]
+async fn create_blackout_with_inverted_dates_returns_400() {
+    let mut mock_blackout = MockBlackoutRepository::new();
+    mock_blackout.expect_create().never();
+
+    let app = build_blackout_test_app(mock_blackout);
+    let body = json!({
+        "start_date": "2027-01-02",
+        "end_date": "2026-12-20",
+        "max_off_percent": 20
+    });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/groups/{}/blackouts", Uuid::new_v4()))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_webhook_hides_secret() {
+    let mut mock_webhook = MockWebhookRepository::new();
+    mock_webhook
+        .expect_create()
+        .withf(|webhook| webhook.secret == "0123456789abcdef" && webhook.max_retries() == 5)
+        .returning(|webhook| {
+            Ok(WebhookSubscription {
+                id: Uuid::new_v4(),
+                url: webhook.url,
+                event_types: webhook.event_types,
+                max_retries: 5,
+                created_at: Utc::now(),
+            })
+        });
+
+    let app = build_webhook_test_app(mock_webhook);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/webhooks")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "url": "https://badges.example.com/hooks/staff",
+                        "secret": "0123456789abcdef",
+                        "event_types": ["staff.created", "staff.deactivated"]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["event_types"][1], "staff.deactivated");
+    assert!(json["data"].get("secret").is_none());
+}
+
+#[tokio::test]
+async fn create_webhook_rejects_short_secret() {
+    let app = build_webhook_test_app(MockWebhookRepository::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/webhooks")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "url": "https://badges.example.com/hooks/staff",
+                        "secret": "short",
+                        "event_types": ["staff.created"]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn create_onboarding_rule_trims_position() {
+    let group_id = Uuid::new_v4();
+    let mut mock_onboarding = MockOnboardingRepository::new();
+    mock_onboarding
+        .expect_create()
+        .withf(move |rule| rule.position == " Nurse " && rule.group_id == group_id)
+        .returning(|rule| {
+            Ok(OnboardingRule {
+                id: Uuid::new_v4(),
+                position: rule.position.trim().to_string(),
+                group_id: rule.group_id,
+                created_at: Utc::now(),
+            })
+        });
+
+    let app = build_onboarding_test_app(mock_onboarding);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/onboarding-rules")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "position": " Nurse ", "group_id": group_id }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["position"], "Nurse");
+    assert_eq!(json["data"]["group_id"], group_id.to_string());
+}
+
+#[tokio::test]
+async fn create_onboarding_rule_rejects_blank_position_and_reports_conflicts() {
+    let mut mock_onboarding = MockOnboardingRepository::new();
+    mock_onboarding.expect_create().returning(|_| {
+        Err(DataServiceError::Conflict(
+            "Position already onboards into this group".to_string(),
+        ))
+    });
+    let app = build_onboarding_test_app(mock_onboarding);
+
+    let request = |position: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/onboarding-rules")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "position": position, "group_id": Uuid::new_v4() }).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app.clone().oneshot(request("  ")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app.oneshot(request("Nurse")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+fn make_time_off(staff_id: Uuid, status: TimeOffStatus) -> TimeOffRequest {
+    let now = Utc::now();
+    TimeOffRequest {
+        id: Uuid::new_v4(),
+        staff_id,
+        start_date: chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap(),
+        end_date: chrono::NaiveDate::from_ymd_opt(2026, 3, 11).unwrap(),
+        reason: Some("Wedding".to_string()),
+        status,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[tokio::test]
+async fn create_time_off_validates_dates_and_starts_pending() {
+    let mut mock_time_off = MockTimeOffRepository::new();
+    let staff_id = Uuid::new_v4();
+
+    mock_time_off
+        .expect_create()
+        .withf(move |id, request| *id == staff_id && request.reason.as_deref() == Some("Wedding"))
+        .times(1)
+        .returning(|id, _| Ok(make_time_off(id, TimeOffStatus::Pending)));
+
+    let app = build_time_off_test_app(mock_time_off);
+    let create = |start: &str, end: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/staff/{staff_id}/time-off"))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "start_date": start, "end_date": end, "reason": "Wedding" }).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(create("2026-03-11", "2026-03-09"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(create("2026-03-09", "2026-03-11"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["status"], "PENDING");
+}
+
+#[tokio::test]
+async fn resolved_time_off_needs_a_window() {
+    let mut mock_time_off = MockTimeOffRepository::new();
+    let group_id = Uuid::new_v4();
+    let staff_id = Uuid::new_v4();
+
+    mock_time_off
+        .expect_find_approved_for_group()
+        .withf(move |id, from, to| {
+            *id == group_id && from.to_string() == "2026-03-02" && to.to_string() == "2026-03-29"
+        })
+        .times(1)
+        .returning(move |_, _, _| Ok(vec![make_time_off(staff_id, TimeOffStatus::Approved)]));
+
+    let app = build_time_off_test_app(mock_time_off);
+    let get = |query: &str| {
+        Request::builder()
+            .uri(format!(
+                "/api/v1/groups/{group_id}/resolved-members/time-off{query}"
+            ))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app.clone().oneshot(get("")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = app
+        .clone()
+        .oneshot(get("?from=2026-03-29&to=2026-03-02"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(get("?from=2026-03-02&to=2026-03-29"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["staff_id"], staff_id.to_string());
+    assert_eq!(json["data"][0]["status"], "APPROVED");
+}
+
+#[tokio::test]
+async fn bulk_delete_staff_dry_run_reports_blockers() {
+    let mut mock_staff = MockStaffRepository::new();
+    let blocked = Uuid::new_v4();
+    let missing = Uuid::new_v4();
+
+    mock_staff
+        .expect_bulk_delete()
+        .withf(move |request, dry_run| {
+            *dry_run && !request.cascade && request.ids == [blocked, missing]
+        })
+        .returning(move |request, dry_run| {
+            Ok(vec![
+                BulkDeleteResult::assess(
+                    request.ids[0],
+                    true,
+                    vec![Blocker {
+                        kind: BlockerKind::Memberships,
+                        count: 2,
+                    }],
+                    request.cascade,
+                    dry_run,
+                ),
+                BulkDeleteResult::assess(request.ids[1], false, vec![], request.cascade, dry_run),
+            ])
+        });
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/staff/bulk-delete?dry_run=true")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "ids": [blocked, missing, blocked] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["outcome"], "BLOCKED");
+    assert_eq!(json["data"][0]["blocked_by"][0]["kind"], "MEMBERSHIPS");
+    assert_eq!(json["data"][0]["blocked_by"][0]["count"], 2);
+    assert_eq!(json["data"][1]["outcome"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn find_staff_duplicates_validates_and_passes_query() {
+    let mut mock_staff = MockStaffRepository::new();
+    let (kept, duplicate) = (make_staff(Uuid::new_v4()), make_staff(Uuid::new_v4()));
+
+    mock_staff
+        .expect_find_duplicates()
+        .withf(|query| query.min_similarity() == 0.6 && query.limit() == 100)
+        .times(1)
+        .returning(move |_| {
+            Ok(vec![StaffDuplicate {
+                staff: kept.clone(),
+                duplicate: duplicate.clone(),
+                reasons: vec![DuplicateReason::SameName],
+                email_similarity: 0.25,
+            }])
+        });
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    for uri in [
+        "/api/v1/staff/duplicates?min_similarity=0",
+        "/api/v1/staff/duplicates?min_similarity=1.5",
+        "/api/v1/staff/duplicates?limit=1001",
+    ] {
+        let res = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/staff/duplicates?min_similarity=0.6")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["reasons"], json!(["SAME_NAME"]));
+    assert_eq!(json["data"][0]["email_similarity"], 0.25);
+}
+
+#[tokio::test]
+async fn merge_staff_rejects_itself_and_returns_report() {
+    let mut mock_staff = MockStaffRepository::new();
+    let kept = Uuid::new_v4();
+    let duplicate = Uuid::new_v4();
+
+    mock_staff
+        .expect_merge()
+        .withf(move |id, duplicate_id| *id == kept && *duplicate_id == duplicate)
+        .times(1)
+        .returning(|id, duplicate_id| {
+            Ok(MergeResult {
+                staff: make_staff(id),
+                merged: Staff {
+                    status: StaffStatus::Inactive,
+                    ..make_staff(duplicate_id)
+                },
+                memberships_moved: 2,
+            })
+        });
+
+    let app = build_test_app(
+        mock_staff,
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let merge = |duplicate_id: Uuid| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/staff/{kept}/merge"))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "duplicate_id": duplicate_id }).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app.clone().oneshot(merge(kept)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app.oneshot(merge(duplicate)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["merged"]["status"], "INACTIVE");
+    assert_eq!(json["data"]["memberships_moved"], 2);
+}
+
+#[tokio::test]
+async fn bulk_delete_groups_rejects_empty_ids() {
+    let app = build_test_app(
+        MockStaffRepository::new(),
+        MockGroupRepository::new(),
+        MockMembershipRepository::new(),
+    );
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/groups/bulk-delete")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "ids": [] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn startup_self_check_reports_failed_checks() {
+    let startup = Arc::new(
+        StartupSelfCheck::new()
+            .with_check("database", || async { Ok("PostgreSQL 17.2".to_string()) })
+            .with_check("redis", || async {
+                Err("Connection refused (os error 111)".to_string())
+            }),
+    );
+    startup.run().await;
+
+    let app = test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup,
+        dependencies: Arc::new(DependencyChecks::new()),
+    });
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/healthz/startup")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["passed"], false);
+    assert_eq!(json["data"]["checks"][0]["passed"], true);
+    assert_eq!(json["data"]["checks"][1]["name"], "redis");
+    assert_eq!(
+        json["data"]["checks"][1]["detail"],
+        "Connection refused (os error 111)"
+    );
+}
+
+#[tokio::test]
+async fn readiness_reports_each_dependency() {
+    let app = test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(MockAuditLog::new()),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(
+            DependencyChecks::new()
+                .with_check("postgres", || async { Ok("PostgreSQL 17.2".to_string()) })
+                .with_check("redis", || async {
+                    Err("Connection refused (os error 111)".to_string())
+                }),
+        ),
+    });
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/readyz")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["ready"], false);
+    assert_eq!(json["data"]["dependencies"][0]["healthy"], true);
+    assert_eq!(json["data"]["dependencies"][1]["name"], "redis");
+    assert_eq!(json["data"]["dependencies"][1]["healthy"], false);
+
+    // Liveness doesn't depend on them
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/healthz")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+fn token(secret: &str, roles: &[&str], expires_in: i64) -> String {
+    let claims = json!({
+        "sub": "user-1",
+        "roles": roles,
+        "iss": "https://id.example.com",
+        "exp": Utc::now().timestamp() + expires_in,
+    });
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn routes_require_a_token_with_the_right_role() {
+    let staff_id = Uuid::new_v4();
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff
+        .expect_find_by_id()
+        .returning(|id| Ok(Some(make_staff(id))));
+    let app = test_router_with_auth(
+        DataServiceAppState {
+            staff_repo: Arc::new(mock_staff),
+            group_repo: Arc::new(MockGroupRepository::new()),
+            membership_repo: Arc::new(MockMembershipRepository::new()),
+            blackout_repo: Arc::new(MockBlackoutRepository::new()),
+            photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+            webhook_repo: Arc::new(MockWebhookRepository::new()),
+            onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+            time_off_repo: Arc::new(MockTimeOffRepository::new()),
+            audit_log: Arc::new(MockAuditLog::new()),
+            startup: Arc::new(StartupSelfCheck::new()),
+            dependencies: Arc::new(DependencyChecks::new()),
+        },
+        Authenticator::new(AuthConfig {
+            secret: Some("test-secret".to_string()),
+            issuer: Some("https://id.example.com".to_string()),
+            ..AuthConfig::default()
+        }),
+    );
+    let call = |method: &str, uri: String, token: Option<String>| {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let staff_uri = format!("/api/v1/staff/{staff_id}");
+
+    let res = call("GET", staff_uri.clone(), None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(res.headers()["www-authenticate"], "Bearer");
+
+    let expired = token("test-secret", &["admin"], -3600);
+    let forged = token("other-secret", &["admin"], 3600);
+    for bad in [expired, forged] {
+        let res = call("GET", staff_uri.clone(), Some(bad)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let viewer = token("test-secret", &["viewer"], 3600);
+    let res = call("GET", staff_uri.clone(), Some(viewer.clone()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = call("DELETE", staff_uri.clone(), Some(viewer))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    // Schedulers change data but not the webhook configuration
+    let scheduler = token("test-secret", &["scheduler"], 3600);
+    let res = call("GET", "/api/v1/webhooks".to_string(), Some(scheduler))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    // Probes stay open
+    let res = call("GET", "/healthz".to_string(), None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn internal_routes_accept_a_service_api_key() {
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff
+        .expect_find_by_id()
+        .returning(|id| Ok(Some(make_staff(id))));
+    let mut mock_membership = MockMembershipRepository::new();
+    mock_membership
+        .expect_resolve_members()
+        .returning(|_, query, page| Ok(make_page(vec![], query, page)));
+    let app = test_router_with_auth(
+        DataServiceAppState {
+            staff_repo: Arc::new(mock_staff),
+            group_repo: Arc::new(MockGroupRepository::new()),
+            membership_repo: Arc::new(mock_membership),
+            blackout_repo: Arc::new(MockBlackoutRepository::new()),
+            photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+            webhook_repo: Arc::new(MockWebhookRepository::new()),
+            onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+            time_off_repo: Arc::new(MockTimeOffRepository::new()),
+            audit_log: Arc::new(MockAuditLog::new()),
+            startup: Arc::new(StartupSelfCheck::new()),
+            dependencies: Arc::new(DependencyChecks::new()),
+        },
+        Authenticator::new(AuthConfig {
+            api_keys: vec!["old-key".to_string(), "new-key".to_string()],
+            ..AuthConfig::default()
+        }),
+    );
+    let call = |uri: String, api_key: Option<&str>| {
+        let mut request = Request::builder().uri(uri);
+        if let Some(api_key) = api_key {
+            request = request.header("X-Api-Key", api_key);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let members_uri = format!("/api/v1/groups/{}/resolved-members", Uuid::new_v4());
+
+    let res = call(members_uri.clone(), None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = call(members_uri.clone(), Some("wrong-key")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    for key in ["old-key", "new-key"] {
+        let res = call(members_uri.clone(), Some(key)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    // Routes for people keep following the token settings, here none
+    let res = call(format!("/api/v1/staff/{}", Uuid::new_v4()), None)
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn audit_log_lists_changes_matching_the_filters() {
+    let entity_id = Uuid::new_v4();
+    let mut mock_audit = MockAuditLog::new();
+    mock_audit
+        .expect_find()
+        .withf(move |query, page| {
+            *query
+                == AuditQuery {
+                    entity_type: Some(AuditEntity::Staff),
+                    entity_id: Some(entity_id),
+                    actor: Some("user-1".to_string()),
+                    ..AuditQuery::default()
+                }
+                && page.limit == Some(10)
+        })
+        .returning(move |_, _| {
+            Ok(AuditPage {
+                items: vec![AuditEntry {
+                    id: 7,
+                    entity_type: AuditEntity::Staff,
+                    entity_id,
+                    action: AuditAction::Delete,
+                    actor: "user-1".to_string(),
+                    before: Some(json!({ "name": "Alice" })),
+                    after: None,
+                    changed_at: Utc::now(),
+                }],
+                total: 1,
+                limit: 10,
+                offset: 0,
+            })
+        });
+    let app = test_router(DataServiceAppState {
+        staff_repo: Arc::new(MockStaffRepository::new()),
+        group_repo: Arc::new(MockGroupRepository::new()),
+        membership_repo: Arc::new(MockMembershipRepository::new()),
+        blackout_repo: Arc::new(MockBlackoutRepository::new()),
+        photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+        webhook_repo: Arc::new(MockWebhookRepository::new()),
+        onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+        time_off_repo: Arc::new(MockTimeOffRepository::new()),
+        audit_log: Arc::new(mock_audit),
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    });
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/audit?entity_type=STAFF&entity_id={entity_id}&actor=user-1&limit=10"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["items"][0]["action"], "DELETE");
+    assert_eq!(json["data"]["items"][0]["before"]["name"], "Alice");
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/audit?from=2026-03-02T00:00:00Z&to=2026-03-01T00:00:00Z")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn staff_update_is_audited_under_the_callers_token_subject() {
+    let staff_id = Uuid::new_v4();
+    let mut mock_staff = MockStaffRepository::new();
+    mock_staff
+        .expect_find_by_id()
+        .returning(|id| Ok(Some(make_staff(id))));
+    mock_staff.expect_update().returning(|id, _, _| {
+        let mut updated = make_staff(id);
+        updated.name = "Alice Updated".to_string();
+        Ok(updated)
+    });
+    let mut mock_audit = MockAuditLog::new();
+    mock_audit
+        .expect_record()
+        .withf(move |entries| {
+            let [entry] = entries.as_slice() else {
+                return false;
+            };
+            entry.entity_type == AuditEntity::Staff
+                && entry.entity_id == staff_id
+                && entry.action == AuditAction::Update
+                && entry.actor == "user-1"
+                && entry.before.as_ref().is_some_and(|b| b["name"] == "Alice")
+                && entry
+                    .after
+                    .as_ref()
+                    .is_some_and(|a| a["name"] == "Alice Updated")
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+    let app = test_router_with_auth(
+        DataServiceAppState {
+            staff_repo: Arc::new(AuditedStaffRepository::new(
+                Arc::new(mock_staff),
+                Arc::new(mock_audit),
+            )),
+            group_repo: Arc::new(MockGroupRepository::new()),
+            membership_repo: Arc::new(MockMembershipRepository::new()),
+            blackout_repo: Arc::new(MockBlackoutRepository::new()),
+            photo_storage: Arc::new(ObjectPhotoStorage::in_memory()),
+            webhook_repo: Arc::new(MockWebhookRepository::new()),
+            onboarding_repo: Arc::new(MockOnboardingRepository::new()),
+            time_off_repo: Arc::new(MockTimeOffRepository::new()),
+            audit_log: Arc::new(MockAuditLog::new()),
+            startup: Arc::new(StartupSelfCheck::new()),
+            dependencies: Arc::new(DependencyChecks::new()),
+        },
+        Authenticator::new(AuthConfig {
+            secret: Some("test-secret".to_string()),
+            issuer: Some("https://id.example.com".to_string()),
+            ..AuthConfig::default()
+        }),
+    );
+
+    let body = json!({ "name": "Alice Updated" });
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/v1/staff/{staff_id}"))
+                .header("content-type", "application/json")
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", token("test-secret", &["scheduler"], 3600)),
+                )
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
     assert_eq!(res.status(), StatusCode::OK);
 }
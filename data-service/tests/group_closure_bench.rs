@@ -0,0 +1,168 @@
+//! Member resolution on deep group trees: the recursive parent walk used before
+//! `group_closure` against the closure-table join.
+//!
+//! Needs a Postgres server the test can create databases on:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://user@localhost/postgres \
+//!     cargo test -p data-service --test group_closure_bench -- --ignored --nocapture
+//! ```
+
+use serde_json::Value;
+use sqlx::PgPool;
+
+const TREES: i32 = 50;
+const DEPTH: i32 = 64;
+/// Leaf groups next to the group that continues the chain on every level.
+const SIBLINGS: i32 = 3;
+const MEMBERS_PER_GROUP: i32 = 5;
+const STAFF_POOL: i32 = 5000;
+
+/// 50 chains of 64 levels with 3 leaf siblings per level: 12,800 groups, 64,000 memberships.
+async fn seed(pool: &PgPool) {
+    sqlx::query(
+        r#"
+        INSERT INTO staff (id, name, email, position)
+        SELECT md5('staff' || n)::uuid, 'Staff ' || n, 'staff' || n || '@example.com', 'Nurse'
+        FROM generate_series(1, $1) AS n
+        "#,
+    )
+    .bind(STAFF_POOL)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    // Level by level, so the closure trigger always finds the parent's rows
+    for level in 0..DEPTH {
+        sqlx::query(
+            r#"
+            INSERT INTO staff_groups (id, name, parent_group_id)
+            SELECT md5('t' || t || 'l' || $1::int || 's' || s)::uuid,
+                   'Tree ' || t || ' level ' || $1::int || ' #' || s,
+                   CASE WHEN $1::int > 0 THEN md5('t' || t || 'l' || ($1::int - 1) || 's0')::uuid END
+            FROM generate_series(1, $2) AS t,
+                 generate_series(0, $3) AS s
+            "#,
+        )
+        .bind(level)
+        .bind(TREES)
+        .bind(SIBLINGS)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO group_memberships (staff_id, group_id)
+        SELECT md5('staff' || (1 + (g.n * $1 + m) % $2))::uuid, g.id
+        FROM (SELECT id, row_number() OVER (ORDER BY id) AS n FROM staff_groups) AS g,
+             generate_series(0, $1 - 1) AS m
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(MEMBERS_PER_GROUP)
+    .bind(STAFF_POOL)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    sqlx::query("ANALYZE").execute(pool).await.unwrap();
+}
+
+struct Plan {
+    execution_ms: f64,
+    indexes: Vec<String>,
+}
+
+async fn explain(pool: &PgPool, sql: &str) -> Plan {
+    let (plan,): (Value,) =
+        sqlx::query_as(&format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {sql}"))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+
+    fn collect(node: &Value, indexes: &mut Vec<String>) {
+        if let Some(name) = node["Index Name"].as_str() {
+            indexes.push(name.to_string());
+        }
+        for child in node["Plans"].as_array().into_iter().flatten() {
+            collect(child, indexes);
+        }
+    }
+
+    let mut indexes = Vec::new();
+    collect(&plan[0]["Plan"], &mut indexes);
+    Plan {
+        execution_ms: plan[0]["Execution Time"].as_f64().unwrap(),
+        indexes,
+    }
+}
+
+/// Best of a few runs, so the first (cold cache) run doesn't dominate.
+async fn best_of(pool: &PgPool, sql: &str, runs: usize) -> Plan {
+    let mut best = explain(pool, sql).await;
+    for _ in 1..runs {
+        let plan = explain(pool, sql).await;
+        if plan.execution_ms < best.execution_ms {
+            best = plan;
+        }
+    }
+    best
+}
+
+fn recursive_sql(group: &str) -> String {
+    format!(
+        "WITH RECURSIVE group_tree AS ( \
+             SELECT id FROM staff_groups WHERE id = {group} \
+             UNION ALL \
+             SELECT sg.id FROM staff_groups sg JOIN group_tree gt ON sg.parent_group_id = gt.id) \
+         SELECT DISTINCT s.id, s.name FROM staff s \
+         JOIN group_memberships gm ON s.id = gm.staff_id \
+         JOIN group_tree gt ON gm.group_id = gt.id \
+         ORDER BY s.name, s.id"
+    )
+}
+
+fn closure_sql(group: &str) -> String {
+    format!(
+        "SELECT DISTINCT s.id, s.name FROM staff s \
+         JOIN group_memberships gm ON s.id = gm.staff_id \
+         JOIN group_closure gc ON gm.group_id = gc.descendant_id \
+         WHERE gc.ancestor_id = {group} \
+         ORDER BY s.name, s.id"
+    )
+}
+
+#[sqlx::test]
+#[ignore = "seeds 12,800 groups; needs DATABASE_URL"]
+async fn closure_table_resolves_deep_trees_faster(pool: PgPool) {
+    seed(&pool).await;
+
+    println!(
+        "{:<16} {:>15} {:>12}",
+        "subtree levels", "recursive (ms)", "closure (ms)"
+    );
+    for levels in [8, 32, DEPTH] {
+        let group = format!("md5('t7l{}s0')::uuid", DEPTH - levels);
+        let (recursive, closure) = (recursive_sql(&group), closure_sql(&group));
+
+        let expected: Vec<(uuid::Uuid, String)> =
+            sqlx::query_as(&recursive).fetch_all(&pool).await.unwrap();
+        let actual: Vec<(uuid::Uuid, String)> =
+            sqlx::query_as(&closure).fetch_all(&pool).await.unwrap();
+        assert_eq!(actual, expected, "closure join resolved different members");
+
+        let old_plan = best_of(&pool, &recursive, 5).await;
+        let new_plan = best_of(&pool, &closure, 5).await;
+        println!(
+            "{levels:<16} {:>15.3} {:>12.3}   {:?} -> {:?}",
+            old_plan.execution_ms, new_plan.execution_ms, old_plan.indexes, new_plan.indexes
+        );
+        assert!(
+            new_plan.indexes.iter().any(|i| i == "pk_group_closure"),
+            "closure join should use pk_group_closure, used {:?}",
+            new_plan.indexes
+        );
+    }
+}
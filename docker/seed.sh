@@ -4,6 +4,8 @@ set -e
 DATA_SERVICE_URL="${DATA_SERVICE_URL:-http://data-service:8080}"
 MAX_RETRIES=6
 RETRY_INTERVAL=10
+# Needs the scheduler role when the data-service requires authentication
+AUTH_HEADER="Authorization: Bearer ${SEED_TOKEN:-}"
 
 # Wait for data-service
 echo "[seed] Waiting for data-service..."
@@ -19,21 +21,21 @@ done
 echo "[seed] data-service is ready."
 
 # Check if already seeded
-existing=$(curl -sf "${DATA_SERVICE_URL}/api/v1/staff" | grep -c '"id"' || true)
-if [ "$existing" -gt 0 ]; then
+existing=$(curl -sf -H "${AUTH_HEADER}" "${DATA_SERVICE_URL}/api/v1/staff?limit=1" | grep -o '"total":[0-9]*' | cut -d: -f2 || true)
+if [ "${existing:-0}" -gt 0 ]; then
   echo "[seed] Data already exists (${existing} staff found). Skipping."
   exit 0
 fi
 
 # Import staff
 echo "[seed] Importing staff..."
-curl -sf -X POST -H "Content-Type: application/json" \
+curl -sf -X POST -H "Content-Type: application/json" -H "${AUTH_HEADER}" \
   -d @/data/staff.json "${DATA_SERVICE_URL}/api/v1/staff/batch" > /dev/null
 echo "[seed] Staff imported!"
 
 # Import groups
 echo "[seed] Importing groups..."
-curl -sf -X POST -H "Content-Type: application/json" \
+curl -sf -X POST -H "Content-Type: application/json" -H "${AUTH_HEADER}" \
   -d @/data/groups.json "${DATA_SERVICE_URL}/api/v1/groups/batch" > /dev/null
 echo "[seed] Groups imported!"
 
@@ -0,0 +1,30 @@
+-- Compact storage: one row per staff member and job instead of one per day. `shifts` has a
+-- character per day from the job's period_begin_date: 'M'orning, 'E'vening, 'O'ff, or '-'
+-- for no assignment. `notes` runs parallel to it and is NULL when no day has a note.
+CREATE TABLE packed_assignments(
+    job_id uuid NOT NULL CONSTRAINT fk_pa_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    staff_id uuid NOT NULL,
+    shifts text NOT NULL,
+    notes varchar(280)[],
+    CONSTRAINT pk_packed_assignments PRIMARY KEY (job_id, staff_id)
+);
+
+-- Packed rows expanded back to one per day. Ids are derived from job, staff and day, so they
+-- are stable across reads and survive unpacking a job for editing.
+CREATE VIEW unpacked_assignments AS
+SELECT md5(pa.job_id::text || pa.staff_id::text || d.n)::uuid AS id,
+    pa.job_id,
+    pa.staff_id,
+    sj.period_begin_date + (d.n::int - 1) AS date,
+    (CASE d.code WHEN 'M' THEN 'MORNING' WHEN 'E' THEN 'EVENING' ELSE 'DAY_OFF' END)::shift_type AS shift_type,
+    pa.notes[d.n::int] AS note
+FROM packed_assignments pa
+JOIN schedule_jobs sj ON sj.id = pa.job_id
+CROSS JOIN LATERAL unnest(string_to_array(pa.shifts, NULL)) WITH ORDINALITY AS d(code, n)
+WHERE d.code <> '-';
+
+-- Every assignment, whichever way its job is stored. Reads go through here.
+CREATE VIEW all_shift_assignments AS
+SELECT id, job_id, staff_id, date, shift_type, note FROM shift_assignments
+UNION ALL
+SELECT id, job_id, staff_id, date, shift_type, note FROM unpacked_assignments;
@@ -0,0 +1,5 @@
+-- A job can cover several consecutive 28-day periods, generated one after the other with
+-- each period continuing where the previous one ended.
+ALTER TABLE schedule_jobs
+    ADD COLUMN periods smallint NOT NULL DEFAULT 1
+    CONSTRAINT ck_jobs_periods CHECK (periods BETWEEN 1 AND 13);
@@ -0,0 +1,10 @@
+-- Staff a job needs on each shift every day, when it asked for something other than the
+-- configured [shift_coverage]. Both are set or neither is.
+ALTER TABLE schedule_jobs
+    ADD COLUMN min_morning smallint,
+    ADD COLUMN min_evening smallint,
+    ADD CONSTRAINT ck_jobs_coverage CHECK (
+        (min_morning IS NULL) = (min_evening IS NULL)
+        AND min_morning >= 0
+        AND min_evening >= 0
+    );
@@ -0,0 +1,17 @@
+-- URL a job's outcome is POSTed to once it completes or fails, set on submission
+ALTER TABLE schedule_jobs
+    ADD COLUMN callback_url text;
+
+-- Callbacks waiting to be sent, or retried. Rows are removed once delivered or given up on.
+CREATE TABLE callback_deliveries(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_callback_deliveries PRIMARY KEY,
+    job_id uuid NOT NULL CONSTRAINT fk_cd_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    url text NOT NULL,
+    -- Exact body sent, so every retry carries the same signature
+    payload text NOT NULL,
+    attempts smallint NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_cd_next_attempt ON callback_deliveries(next_attempt_at);
@@ -0,0 +1,12 @@
+-- Staff confirming they've seen their published roster. One row per staff member and job;
+-- acknowledging again keeps the first time.
+CREATE TABLE schedule_acknowledgments(
+    job_id uuid NOT NULL CONSTRAINT fk_sack_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    staff_id uuid NOT NULL,
+    acknowledged_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT pk_schedule_acknowledgments PRIMARY KEY (job_id, staff_id)
+);
+
+-- When the last reminder about missing acknowledgments went out, so replicas don't repeat it.
+ALTER TABLE schedule_jobs
+    ADD COLUMN acknowledgment_reminded_at timestamptz;
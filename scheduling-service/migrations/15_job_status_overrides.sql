@@ -0,0 +1,2 @@
+-- Status forced by an admin, with the reason in the event detail
+ALTER TYPE job_event_kind ADD VALUE 'OVERRIDDEN';
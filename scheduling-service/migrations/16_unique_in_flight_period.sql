@@ -0,0 +1,4 @@
+-- At most one queued or running job per group and period start. A finished job can still be
+-- followed by a forced resubmission, which then takes its place in calendars.
+CREATE UNIQUE INDEX uq_jobs_in_flight_period ON schedule_jobs(staff_group_id, period_begin_date)
+WHERE status IN ('PENDING', 'PROCESSING') AND NOT sandbox;
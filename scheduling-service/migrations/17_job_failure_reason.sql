@@ -0,0 +1,2 @@
+-- Why a job failed, kept until it is retried or its status overridden.
+ALTER TABLE schedule_jobs ADD COLUMN error_message text;
@@ -0,0 +1,19 @@
+ALTER TYPE shift_type ADD VALUE 'NIGHT' BEFORE 'DAY_OFF';
+
+-- Packed rows use 'N' for NIGHT
+CREATE OR REPLACE VIEW unpacked_assignments AS
+SELECT md5(pa.job_id::text || pa.staff_id::text || d.n)::uuid AS id,
+    pa.job_id,
+    pa.staff_id,
+    sj.period_begin_date + (d.n::int - 1) AS date,
+    (CASE d.code
+        WHEN 'M' THEN 'MORNING'
+        WHEN 'E' THEN 'EVENING'
+        WHEN 'N' THEN 'NIGHT'
+        ELSE 'DAY_OFF'
+    END)::shift_type AS shift_type,
+    pa.notes[d.n::int] AS note
+FROM packed_assignments pa
+JOIN schedule_jobs sj ON sj.id = pa.job_id
+CROSS JOIN LATERAL unnest(string_to_array(pa.shifts, NULL)) WITH ORDINALITY AS d(code, n)
+WHERE d.code <> '-';
@@ -0,0 +1,14 @@
+-- Job version, bumped by a trigger on every update. Claiming a pending job compares it
+-- instead of updated_at, which two writes in the same clock tick can leave unchanged.
+ALTER TABLE schedule_jobs ADD COLUMN version integer NOT NULL DEFAULT 1;
+
+CREATE FUNCTION bump_version() RETURNS trigger AS $$
+BEGIN
+    NEW.version := OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_schedule_jobs_version
+    BEFORE UPDATE ON schedule_jobs
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
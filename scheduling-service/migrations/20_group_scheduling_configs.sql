@@ -0,0 +1,37 @@
+-- Rule settings a staff group uses instead of the ones in scheduling.toml. A NULL column keeps
+-- the configured value; the coverage pair is set or unset together, as on schedule_jobs.
+CREATE TYPE day_off_pairing AS ENUM(
+    'off',
+    'prefer',
+    'require'
+);
+
+CREATE TYPE week_alignment AS ENUM(
+    'period',
+    'iso'
+);
+
+CREATE TABLE group_scheduling_configs(
+    staff_group_id uuid NOT NULL CONSTRAINT pk_group_scheduling_configs PRIMARY KEY,
+    min_day_off_per_week smallint,
+    max_day_off_per_week smallint,
+    no_morning_after_evening boolean,
+    night_shift boolean,
+    no_morning_after_night boolean,
+    max_daily_shift_diff smallint,
+    day_off_pairing day_off_pairing,
+    week_alignment week_alignment,
+    min_morning smallint,
+    min_evening smallint,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT ck_gsc_day_off CHECK (
+        min_day_off_per_week BETWEEN 0 AND 7
+        AND max_day_off_per_week BETWEEN 0 AND 7
+    ),
+    CONSTRAINT ck_gsc_shift_diff CHECK (max_daily_shift_diff >= 0),
+    CONSTRAINT ck_gsc_coverage CHECK (
+        (min_morning IS NULL) = (min_evening IS NULL)
+        AND min_morning >= 0
+        AND min_evening >= 0
+    )
+);
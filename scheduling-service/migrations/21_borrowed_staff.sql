@@ -0,0 +1,7 @@
+-- Float pool staff a job scheduled with its group, saved with the job's assignments.
+CREATE TABLE borrowed_staff(
+    job_id uuid NOT NULL CONSTRAINT fk_bs_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    staff_id uuid NOT NULL,
+    pool_group_id uuid NOT NULL,
+    CONSTRAINT pk_borrowed_staff PRIMARY KEY (job_id, staff_id)
+);
@@ -0,0 +1,14 @@
+-- Days and shifts a job's schedule falls short of its coverage minimums, saved with the
+-- job's assignments when coverage_shortfall lets it complete anyway.
+CREATE TABLE coverage_shortfalls(
+    id bigint GENERATED ALWAYS AS IDENTITY CONSTRAINT pk_coverage_shortfalls PRIMARY KEY,
+    job_id uuid NOT NULL CONSTRAINT fk_cs_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    date date NOT NULL,
+    shift_type shift_type NOT NULL,
+    position text,
+    required int NOT NULL,
+    assigned int NOT NULL,
+    unfilled boolean NOT NULL
+);
+
+CREATE INDEX idx_cs_job ON coverage_shortfalls(job_id);
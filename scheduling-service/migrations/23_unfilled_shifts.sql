@@ -0,0 +1,11 @@
+-- Placeholders for the staff a job's schedule is missing, one row per person, saved with the
+-- job's assignments when coverage_shortfall = "unfilled".
+CREATE TABLE unfilled_shifts(
+    id uuid CONSTRAINT pk_unfilled_shifts PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id uuid NOT NULL CONSTRAINT fk_us_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    date date NOT NULL,
+    shift_type shift_type NOT NULL,
+    position text
+);
+
+CREATE INDEX idx_us_job ON unfilled_shifts(job_id);
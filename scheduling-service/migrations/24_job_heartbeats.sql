@@ -0,0 +1,11 @@
+-- The replica running a PROCESSING job and when it last said it was alive. Recovery only
+-- resets jobs whose heartbeat stopped, never ones another replica is still running.
+ALTER TABLE schedule_jobs ADD COLUMN worker_id text;
+ALTER TABLE schedule_jobs ADD COLUMN heartbeat_at timestamptz;
+
+-- When each singleton task last started, so it runs once per interval across all replicas
+-- rather than once per replica.
+CREATE TABLE task_runs(
+    name text CONSTRAINT pk_task_runs PRIMARY KEY,
+    last_run_at timestamptz NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE schedule_jobs
+    ADD COLUMN result_checksum varchar(64);
@@ -0,0 +1,15 @@
+-- Reporting reads assignments by job or by staff, always within a date range.
+-- The composite indexes cover both the lookup and the range, and replace the
+-- single-column ones they start with.
+CREATE INDEX idx_sa_job_date ON shift_assignments(job_id, date);
+
+CREATE INDEX idx_sa_staff_date ON shift_assignments(staff_id, date);
+
+DROP INDEX idx_sa_job;
+
+DROP INDEX idx_sa_staff;
+
+-- Group lookups (calendar feed, overlap check) filter on the period as well.
+CREATE INDEX idx_jobs_group_period ON schedule_jobs(staff_group_id, period_begin_date);
+
+DROP INDEX idx_jobs_group;
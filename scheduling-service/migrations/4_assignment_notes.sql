@@ -0,0 +1,3 @@
+-- Free-text note per assignment, e.g. "covering for Bob" or "training day".
+ALTER TABLE shift_assignments
+    ADD COLUMN note varchar(280);
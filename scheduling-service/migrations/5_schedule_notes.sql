@@ -0,0 +1,10 @@
+-- Period-level annotations on a schedule, e.g. handover notes for the group.
+CREATE TABLE schedule_notes(
+    id uuid CONSTRAINT pk_schedule_notes PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id uuid NOT NULL CONSTRAINT fk_sn_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    author varchar(100) NOT NULL,
+    body text NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_sn_job_created ON schedule_notes(job_id, created_at);
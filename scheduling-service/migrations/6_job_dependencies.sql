@@ -0,0 +1,5 @@
+-- Jobs that must complete before a job is started, e.g. the previous period of the group.
+ALTER TABLE schedule_jobs
+    ADD COLUMN depends_on uuid[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_jobs_depends_on ON schedule_jobs USING gin(depends_on);
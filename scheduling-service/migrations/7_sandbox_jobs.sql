@@ -0,0 +1,6 @@
+-- Test submissions from integrators: kept out of calendars and period planning, and purged
+-- once they are older than the configured retention.
+ALTER TABLE schedule_jobs
+    ADD COLUMN sandbox boolean NOT NULL DEFAULT false;
+
+CREATE INDEX idx_jobs_sandbox_created ON schedule_jobs(created_at) WHERE sandbox;
@@ -0,0 +1,20 @@
+-- Sign-off of completed schedules by the group's designated approvers before publishing.
+ALTER TABLE schedule_jobs
+    ADD COLUMN published_at timestamptz;
+
+CREATE TYPE approval_decision AS ENUM(
+    'APPROVED',
+    'REJECTED'
+);
+
+-- One decision per approver and job; deciding again replaces the earlier one.
+-- `checksum` is the job's result checksum at the time, so edits made afterwards are noticed.
+CREATE TABLE schedule_approvals(
+    job_id uuid NOT NULL CONSTRAINT fk_sap_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    approver_id uuid NOT NULL,
+    decision approval_decision NOT NULL,
+    comment text,
+    checksum varchar(64),
+    decided_at timestamptz NOT NULL DEFAULT now(),
+    CONSTRAINT pk_schedule_approvals PRIMARY KEY (job_id, approver_id)
+);
@@ -0,0 +1,24 @@
+-- Lifecycle of a job as it happened, so slow or failed runs can be explained without the logs.
+CREATE TYPE job_event_kind AS ENUM(
+    'CREATED',
+    'PICKED_UP',
+    'DATA_FETCHED',
+    'GENERATED',
+    'SAVED',
+    'COMPLETED',
+    'FAILED',
+    'RETRIED',
+    'RECOVERED'
+);
+
+CREATE TABLE job_events(
+    id uuid CONSTRAINT pk_job_events PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id uuid NOT NULL CONSTRAINT fk_je_job REFERENCES schedule_jobs(id) ON DELETE CASCADE,
+    kind job_event_kind NOT NULL,
+    detail text,
+    -- How long the step took, for steps that are timed
+    duration_ms bigint,
+    occurred_at timestamptz NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_je_job_occurred ON job_events(job_id, occurred_at);
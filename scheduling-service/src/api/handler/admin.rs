@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use shared::auth::AdminAccess;
+use shared::responses::{ApiResponse, EmptyApiResponse};
+use shared::types::ScheduleJob;
+use uuid::Uuid;
+
+use crate::{
+    api::state::SchedulingAppState,
+    domain::{
+        consistency::{ConsistencyCheckQuery, ConsistencyReport},
+        group_config::{GroupRuleOverrides, GroupSchedulingConfig},
+        health::ProbeReport,
+        history::HistoryImport,
+        job::StatusOverride,
+        packed::{PackAssignmentsQuery, PackReport},
+        queue::JobQueue,
+    },
+    error::SchedulingServiceError,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/probe/{dependency}",
+    tag = "Admin",
+    operation_id = "probe_dependency",
+    params(("dependency" = String, Path, description = "Dependency name from the health config, e.g. data-service")),
+    responses(
+        (status = 200, description = "Result of one direct call to the dependency's health endpoint", body = ApiResponse<ProbeReport>),
+        (status = 404, description = "No dependency with this name is configured", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn probe_dependency(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(dependency): Path<String>,
+) -> Result<Json<ApiResponse<ProbeReport>>, SchedulingServiceError> {
+    let report = state
+        .health_checker
+        .probe(&dependency)
+        .await
+        .ok_or_else(|| {
+            SchedulingServiceError::NotFound(format!("Unknown dependency '{dependency}'"))
+        })?;
+
+    Ok(Json(ApiResponse::ok(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/queue",
+    tag = "Admin",
+    operation_id = "get_job_queue",
+    responses(
+        (status = 200, description = "Pending jobs with their wait, processing jobs with their run time and worker", body = ApiResponse<JobQueue>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn job_queue(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+) -> Result<Json<ApiResponse<JobQueue>>, SchedulingServiceError> {
+    let output = state.scheduling_service.job_queue().await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/groups/{staff_group_id}/history",
+    tag = "Admin",
+    operation_id = "import_history",
+    params(("staff_group_id" = Uuid, Path, description = "Group the roster belongs to")),
+    request_body(
+        content = String,
+        content_type = "text/csv",
+        description = "Header `staff_id,date,shift_type`, then one row per staff member and past date"
+    ),
+    responses(
+        (status = 201, description = "Roster stored as completed jobs, one per period", body = ApiResponse<HistoryImport>),
+        (status = 400, description = "Malformed rows, or a period that already has a job", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, csv))]
+pub async fn import_history(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(staff_group_id): Path<Uuid>,
+    csv: String,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .import_history(staff_group_id, &csv)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::ok(output))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/assignments/pack",
+    tag = "Admin",
+    operation_id = "pack_assignments",
+    params(PackAssignmentsQuery),
+    responses(
+        (status = 200, description = "One batch of completed jobs moved to packed storage", body = ApiResponse<PackReport>),
+        (status = 400, description = "limit out of range", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn pack_assignments(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Query(query): Query<PackAssignmentsQuery>,
+) -> Result<Json<ApiResponse<PackReport>>, SchedulingServiceError> {
+    let output = state.scheduling_service.pack_assignments(query).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/consistency/staff-references",
+    tag = "Admin",
+    operation_id = "check_staff_references",
+    params(ConsistencyCheckQuery),
+    responses(
+        (status = 200, description = "Staff ids in stored assignments that the data-service no longer knows", body = ApiResponse<ConsistencyReport>),
+        (status = 502, description = "The data-service lookup failed", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn check_staff_references(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Query(query): Query<ConsistencyCheckQuery>,
+) -> Result<Json<ApiResponse<ConsistencyReport>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .check_staff_references(query)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/schedules/{schedule_id}/status",
+    tag = "Admin",
+    operation_id = "override_job_status",
+    params(("schedule_id" = Uuid, Path, description = "Job whose status is forced")),
+    request_body = StatusOverride,
+    responses(
+        (status = 200, description = "Status changed and recorded in the job timeline", body = ApiResponse<ScheduleJob>),
+        (status = 400, description = "Missing reason, a transition that isn't allowed, or the job changed meanwhile", body = EmptyApiResponse),
+        (status = 404, description = "Job not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, principal, request), fields(subject = %principal.subject))]
+pub async fn override_job_status(
+    AdminAccess(principal): AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(request): Json<StatusOverride>,
+) -> Result<Json<ApiResponse<ScheduleJob>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .override_status(schedule_id, &principal.subject, request)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/groups/{staff_group_id}/scheduling-config",
+    tag = "Admin",
+    operation_id = "get_group_scheduling_config",
+    params(("staff_group_id" = Uuid, Path, description = "Group whose rule overrides are read")),
+    responses(
+        (status = 200, description = "Rule settings the group uses instead of scheduling.toml", body = ApiResponse<GroupSchedulingConfig>),
+        (status = 404, description = "The group has no overrides", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_group_config(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(staff_group_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<GroupSchedulingConfig>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .get_group_config(staff_group_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/groups/{staff_group_id}/scheduling-config",
+    tag = "Admin",
+    operation_id = "put_group_scheduling_config",
+    params(("staff_group_id" = Uuid, Path, description = "Group whose rule overrides are replaced")),
+    request_body = GroupRuleOverrides,
+    responses(
+        (status = 200, description = "Overrides stored; the group's next jobs use them", body = ApiResponse<GroupSchedulingConfig>),
+        (status = 400, description = "Merged with scheduling.toml, the rules can't all be met", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn put_group_config(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(staff_group_id): Path<Uuid>,
+    Json(overrides): Json<GroupRuleOverrides>,
+) -> Result<Json<ApiResponse<GroupSchedulingConfig>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .set_group_config(staff_group_id, overrides)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/groups/{staff_group_id}/scheduling-config",
+    tag = "Admin",
+    operation_id = "delete_group_scheduling_config",
+    params(("staff_group_id" = Uuid, Path, description = "Group put back on scheduling.toml")),
+    responses(
+        (status = 200, description = "Overrides removed", body = EmptyApiResponse),
+        (status = 404, description = "The group has no overrides", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn delete_group_config(
+    _: AdminAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(staff_group_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, SchedulingServiceError> {
+    state
+        .scheduling_service
+        .delete_group_config(staff_group_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(())))
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use shared::{
+    health::DependencyReport,
+    responses::{ApiResponse, EmptyApiResponse},
+    startup::StartupReport,
+};
+
+use crate::{api::state::SchedulingAppState, domain::health::ReadinessReport};
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "Health",
+    security(()),
+    operation_id = "liveness",
+    responses(
+        (status = 200, description = "The process is up and serving requests", body = EmptyApiResponse)
+    )
+)]
+pub async fn liveness() -> Json<EmptyApiResponse> {
+    Json(EmptyApiResponse {
+        success: true,
+        error: None,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "Health",
+    security(()),
+    operation_id = "dependency_readiness",
+    responses(
+        (status = 200, description = "Postgres and the data-service answered just now", body = ApiResponse<DependencyReport>),
+        (status = 503, description = "A dependency is down or the startup self-check hasn't passed", body = ApiResponse<DependencyReport>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn dependency_readiness(
+    State(state): State<Arc<SchedulingAppState>>,
+) -> impl IntoResponse {
+    let report = state.dependencies.check().await;
+    let error = if !state.startup.passed() {
+        "Startup self-check has not passed"
+    } else if !report.ready {
+        "Dependency unavailable"
+    } else {
+        return (StatusCode::OK, Json(ApiResponse::ok(report)));
+    };
+
+    let response = ApiResponse {
+        success: false,
+        data: Some(report),
+        error: Some(error.to_string()),
+    };
+    (StatusCode::SERVICE_UNAVAILABLE, Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "Health",
+    security(()),
+    operation_id = "readiness",
+    responses(
+        (status = 200, description = "All required dependencies are healthy", body = ApiResponse<ReadinessReport>),
+        (status = 503, description = "A required dependency is unhealthy or the startup self-check hasn't passed", body = ApiResponse<ReadinessReport>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn readiness(State(state): State<Arc<SchedulingAppState>>) -> impl IntoResponse {
+    let report = state.health_checker.readiness().await;
+    let error = if !state.startup.passed() {
+        "Startup self-check has not passed"
+    } else if !report.ready {
+        "Required dependency unavailable"
+    } else {
+        return (StatusCode::OK, Json(ApiResponse::ok(report)));
+    };
+
+    let response = ApiResponse {
+        success: false,
+        data: Some(report),
+        error: Some(error.to_string()),
+    };
+    (StatusCode::SERVICE_UNAVAILABLE, Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/healthz/startup",
+    tag = "Health",
+    security(()),
+    operation_id = "startup_self_check",
+    responses(
+        (status = 200, description = "Every startup check passed", body = ApiResponse<StartupReport>),
+        (status = 503, description = "A startup check failed or hasn't run yet; failing checks are retried", body = ApiResponse<StartupReport>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn startup(State(state): State<Arc<SchedulingAppState>>) -> impl IntoResponse {
+    let report = state.startup.report();
+    if report.passed {
+        return (StatusCode::OK, Json(ApiResponse::ok(report)));
+    }
+
+    let response = ApiResponse {
+        success: false,
+        data: Some(report),
+        error: Some("Startup self-check has not passed".to_string()),
+    };
+    (StatusCode::SERVICE_UNAVAILABLE, Json(response))
+}
@@ -2,22 +2,71 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
 use chrono::NaiveDate;
+use futures_util::{Stream, stream};
 use serde::Deserialize;
-use shared::responses::ApiResponse;
-use utoipa::ToSchema;
+use shared::{
+    auth::{ReadAccess, WriteAccess},
+    pagination::{Page, PageRequest, SortOrder},
+    responses::{ApiResponse, EmptyApiResponse},
+};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::{api::state::SchedulingAppState, error::SchedulingServiceError};
+use crate::{
+    api::state::SchedulingAppState,
+    domain::{
+        acknowledgment::{AcknowledgeRequest, AcknowledgmentStatus, OutstandingAcknowledgments},
+        approval::{ApprovalDecision, ApprovalRequest, ApprovalStatus, approver_of},
+        assignment_order::{AssignmentGrouping, AssignmentOrder, group_assignments},
+        coverage::ShiftCoverage,
+        job::{
+            BulkAssignmentReport, BulkAssignmentUpdate, CreateScheduleNote, JobOptions, JobPage,
+            JobQuery, UpdateAssignment,
+        },
+        locale::Locale,
+        period::NextPeriod,
+        preflight::SubmittedJob,
+        result_version,
+        share::{CreateShareLinkRequest, ShareLink},
+        simulate::{SimulationReport, SimulationRequest},
+        staff_schedule::{StaffAssignments, StaffAssignmentsQuery, StaffSchedule},
+        stats::{JobStats, JobStatsQuery},
+        status_updates::JobStatusUpdate,
+        timeline::JobTimeline,
+    },
+    error::SchedulingServiceError,
+};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateScheduleRequest {
     pub staff_group_id: Uuid,
     pub period_begin_date: NaiveDate,
+    /// Jobs that must complete before this one starts, e.g. the previous period's
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Run the full pipeline as a test: the job stays out of calendars, period planning and
+    /// the failed-job list, can't be shared and is purged after a while
+    #[serde(default)]
+    pub sandbox: bool,
+    /// Consecutive 28-day periods to generate as one schedule, 1 by default and at most 13
+    pub periods: Option<i16>,
+    /// Staff needed on each shift every day, instead of the configured `[shift_coverage]`
+    pub coverage: Option<ShiftCoverage>,
+    /// http(s) URL the outcome is POSTed to, as a signed `JobCallback`, once the job
+    /// completes or fails
+    pub callback_url: Option<String>,
+    /// Submit even when the group already has a non-failed job starting on this date, e.g.
+    /// to regenerate a completed schedule
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[utoipa::path(
@@ -27,20 +76,40 @@ pub struct CreateScheduleRequest {
     operation_id = "submit_schedule",
     request_body = CreateScheduleRequest,
     responses(
-        (status = 202, description = "Schedule job submitted", body = ApiResponse<shared::types::ScheduleJob>)
+        (status = 202, description = "Schedule job submitted, with any pre-flight warnings", body = ApiResponse<SubmittedJob>),
+        (status = 200, description = "The group's existing job for the period, with `duplicate_submissions = \"return_existing\"`", body = ApiResponse<SubmittedJob>),
+        (status = 400, description = "Invalid request, or a `callback_url` while callbacks are disabled", body = EmptyApiResponse),
+        (status = 409, description = "The group already has a job for the period, or one is queued or running even with `force`", body = EmptyApiResponse)
     )
 )]
 #[tracing::instrument(skip(state))]
 pub async fn submit_schedule(
+    _: WriteAccess,
     State(state): State<Arc<SchedulingAppState>>,
     Json(req): Json<CreateScheduleRequest>,
 ) -> Result<impl IntoResponse, SchedulingServiceError> {
     let job = state
         .scheduling_service
-        .submit_schedule(req.staff_group_id, req.period_begin_date)
+        .submit_schedule(
+            req.staff_group_id,
+            req.period_begin_date,
+            req.depends_on,
+            JobOptions {
+                sandbox: req.sandbox,
+                periods: req.periods.unwrap_or(1),
+                coverage: req.coverage,
+                callback_url: req.callback_url,
+                force: req.force,
+            },
+        )
         .await?;
 
-    Ok((StatusCode::ACCEPTED, Json(ApiResponse::ok(job))))
+    let status = if job.existing {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    };
+    Ok((status, Json(ApiResponse::ok(job))))
 }
 
 #[utoipa::path(
@@ -49,20 +118,553 @@ pub async fn submit_schedule(
     tag = "Schedules",
     operation_id = "get_schedule_status",
     params(
-        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the status last seen")
     ),
     responses(
-        (status = 200, description = "Schedule job status", body = ApiResponse<shared::types::ScheduleJob>)
+        (status = 200, description = "Schedule job status", body = ApiResponse<shared::types::ScheduleJob>,
+            headers(("ETag" = String, description = "The job's version"))),
+        (status = 304, description = "The job hasn't changed since the given ETag")
     )
 )]
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, headers))]
 pub async fn get_status(
+    _: ReadAccess,
     State(state): State<Arc<SchedulingAppState>>,
     Path(schedule_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<shared::types::ScheduleJob>>, SchedulingServiceError> {
+    headers: HeaderMap,
+) -> Result<Response, SchedulingServiceError> {
     let job = state.scheduling_service.get_status(schedule_id).await?;
 
-    Ok(Json(ApiResponse::ok(job)))
+    // Pollers send back the ETag and get a bodiless 304 until the job moves on
+    let etag = format!("\"{}\"", job.version);
+    let unchanged = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|v| v.trim() == etag));
+    if unchanged {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+    Ok(([(header::ETAG, etag)], Json(ApiResponse::ok(job))).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/timeline",
+    tag = "Schedules",
+    operation_id = "get_schedule_timeline",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 200, description = "Lifecycle events of the job, oldest first", body = ApiResponse<JobTimeline>),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_timeline(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<JobTimeline>>, SchedulingServiceError> {
+    let timeline = state.scheduling_service.get_timeline(schedule_id).await?;
+
+    Ok(Json(ApiResponse::ok(timeline)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/events",
+    tag = "Schedules",
+    operation_id = "stream_schedule_events",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent `status` events, the current status first; the stream ends after COMPLETED or FAILED", body = JobStatusUpdate, content_type = "text/event-stream"),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn stream_events(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, SchedulingServiceError> {
+    let watch = state.scheduling_service.watch_status(schedule_id).await?;
+
+    let events = stream::unfold(watch, |mut watch| async move {
+        let update = watch.next().await?;
+        Some((Event::default().event("status").json_data(update), watch))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/schedules/{schedule_id}/assignments/{assignment_id}",
+    tag = "Schedules",
+    operation_id = "update_assignment",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        ("assignment_id" = Uuid, Path, description = "Shift assignment ID")
+    ),
+    request_body = UpdateAssignment,
+    responses(
+        (status = 200, description = "Updated assignment", body = ApiResponse<shared::types::ShiftAssignment>),
+        (status = 400, description = "Schedule not completed or already published, empty update or note too long"),
+        (status = 404, description = "Schedule or assignment not found")
+    )
+)]
+#[tracing::instrument(skip(state, req))]
+pub async fn update_assignment(
+    _: WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path((schedule_id, assignment_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateAssignment>,
+) -> Result<Json<ApiResponse<shared::types::ShiftAssignment>>, SchedulingServiceError> {
+    let assignment = state
+        .scheduling_service
+        .update_assignment(schedule_id, assignment_id, req)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(assignment)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/schedules/{schedule_id}/assignments",
+    tag = "Schedules",
+    operation_id = "update_assignments",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = BulkAssignmentUpdate,
+    responses(
+        (status = 200, description = "All changes saved, with the rules the edited schedule breaks", body = ApiResponse<BulkAssignmentReport>),
+        (status = 400, description = "Schedule not completed or already published, no changes, too many, or one assignment changed twice", body = EmptyApiResponse),
+        (status = 404, description = "Schedule or one of the assignments not found, nothing saved", body = EmptyApiResponse),
+        (status = 409, description = "The schedule's assignments changed meanwhile, nothing saved", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, req))]
+pub async fn update_assignments(
+    _: WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<BulkAssignmentUpdate>,
+) -> Result<Json<ApiResponse<BulkAssignmentReport>>, SchedulingServiceError> {
+    let report = state
+        .scheduling_service
+        .update_assignments(schedule_id, req)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(report)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/notes",
+    tag = "Schedules",
+    operation_id = "add_schedule_note",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = CreateScheduleNote,
+    responses(
+        (status = 201, description = "Note added", body = ApiResponse<shared::types::ScheduleNote>),
+        (status = 400, description = "Missing or too long body, or a caller subject too long to sign with"),
+        (status = 404, description = "Schedule not found")
+    )
+)]
+#[tracing::instrument(skip(state, principal, req), fields(subject = %principal.subject))]
+pub async fn add_note(
+    WriteAccess(principal): WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<CreateScheduleNote>,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let note = state
+        .scheduling_service
+        .add_note(schedule_id, &principal.subject, req)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::ok(note))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/approvals",
+    tag = "Schedules",
+    operation_id = "get_schedule_approvals",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 200, description = "Sign-off progress and every approver's latest decision", body = ApiResponse<ApprovalStatus>),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_approvals(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ApprovalStatus>>, SchedulingServiceError> {
+    let status = state
+        .scheduling_service
+        .get_approval_status(schedule_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/approvals/approve",
+    tag = "Schedules",
+    operation_id = "approve_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = ApprovalRequest,
+    responses(
+        (status = 200, description = "Approval recorded", body = ApiResponse<ApprovalStatus>),
+        (status = 400, description = "Not a completed draft or already published", body = EmptyApiResponse),
+        (status = 403, description = "The caller isn't an approver of the group", body = EmptyApiResponse),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, principal, req), fields(subject = %principal.subject))]
+pub async fn approve_schedule(
+    WriteAccess(principal): WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<ApprovalRequest>,
+) -> Result<Json<ApiResponse<ApprovalStatus>>, SchedulingServiceError> {
+    let status = state
+        .scheduling_service
+        .decide_approval(
+            schedule_id,
+            approver_of(&principal)?,
+            ApprovalDecision::Approved,
+            req,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/approvals/reject",
+    tag = "Schedules",
+    operation_id = "reject_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = ApprovalRequest,
+    responses(
+        (status = 200, description = "Rejection recorded; publishing is blocked until the approver approves", body = ApiResponse<ApprovalStatus>),
+        (status = 400, description = "Missing comment, not a completed draft or already published", body = EmptyApiResponse),
+        (status = 403, description = "The caller isn't an approver of the group", body = EmptyApiResponse),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, principal, req), fields(subject = %principal.subject))]
+pub async fn reject_schedule(
+    WriteAccess(principal): WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<ApprovalRequest>,
+) -> Result<Json<ApiResponse<ApprovalStatus>>, SchedulingServiceError> {
+    let status = state
+        .scheduling_service
+        .decide_approval(
+            schedule_id,
+            approver_of(&principal)?,
+            ApprovalDecision::Rejected,
+            req,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/publish",
+    tag = "Schedules",
+    operation_id = "publish_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 200, description = "Schedule published", body = ApiResponse<ApprovalStatus>),
+        (status = 400, description = "Not a completed draft, missing approvals or already published", body = EmptyApiResponse),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn publish_schedule(
+    _: WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ApprovalStatus>>, SchedulingServiceError> {
+    let status = state.scheduling_service.publish(schedule_id).await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/acknowledgments",
+    tag = "Schedules",
+    operation_id = "acknowledge_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = AcknowledgeRequest,
+    responses(
+        (status = 200, description = "Acknowledgment recorded; acknowledging again keeps the first time", body = ApiResponse<AcknowledgmentStatus>),
+        (status = 400, description = "Schedule not published, the staff member has no shifts in it, or no staff_id and the token's subject isn't a staff id", body = EmptyApiResponse),
+        (status = 403, description = "staff_id names someone else and the caller isn't a scheduler", body = EmptyApiResponse),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, principal, req), fields(subject = %principal.subject))]
+pub async fn acknowledge_schedule(
+    ReadAccess(principal): ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<AcknowledgeRequest>,
+) -> Result<Json<ApiResponse<AcknowledgmentStatus>>, SchedulingServiceError> {
+    let staff_id = req.staff_id_for(&principal)?;
+    let status = state
+        .scheduling_service
+        .acknowledge(schedule_id, staff_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/acknowledgments",
+    tag = "Schedules",
+    operation_id = "get_schedule_acknowledgments",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 200, description = "Who on the roster has acknowledged the published schedule", body = ApiResponse<AcknowledgmentStatus>),
+        (status = 400, description = "Schedule not published", body = EmptyApiResponse),
+        (status = 404, description = "Schedule not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_acknowledgments(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<AcknowledgmentStatus>>, SchedulingServiceError> {
+    let status = state
+        .scheduling_service
+        .get_acknowledgments(schedule_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(status)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/acknowledgments",
+    tag = "Schedules",
+    operation_id = "get_outstanding_acknowledgments",
+    params(
+        ("group_id" = Uuid, Path, description = "Staff group ID")
+    ),
+    responses(
+        (status = 200, description = "The group's published schedules, for periods that haven't ended, that some staff haven't acknowledged; newest first", body = ApiResponse<Vec<OutstandingAcknowledgments>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_outstanding_acknowledgments(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(group_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<OutstandingAcknowledgments>>>, SchedulingServiceError> {
+    let outstanding = state
+        .scheduling_service
+        .get_outstanding_acknowledgments(group_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(outstanding)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/simulate",
+    tag = "Schedules",
+    operation_id = "simulate_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    request_body = SimulationRequest,
+    responses(
+        (status = 200, description = "Stored schedule compared with a regenerated one, nothing is saved", body = ApiResponse<SimulationReport>),
+        (status = 400, description = "Schedule not completed or invalid rule overrides"),
+        (status = 404, description = "Schedule not found")
+    )
+)]
+#[tracing::instrument(skip(state, req))]
+pub async fn simulate(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Json(req): Json<SimulationRequest>,
+) -> Result<Json<ApiResponse<SimulationReport>>, SchedulingServiceError> {
+    let report = state.scheduling_service.simulate(schedule_id, req).await?;
+
+    Ok(Json(ApiResponse::ok(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules",
+    tag = "Schedules",
+    operation_id = "list_schedules",
+    params(
+        PageRequest,
+        JobQuery
+    ),
+    responses(
+        (status = 200, description = "Page of matching schedule jobs, oldest first", body = ApiResponse<Page<shared::types::ScheduleJob>>),
+        (status = 400, description = "Invalid limit, offset or date range", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn list_schedules(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Query(page): Query<PageRequest>,
+    Query(query): Query<JobQuery>,
+) -> Result<Json<ApiResponse<JobPage>>, SchedulingServiceError> {
+    let output = state.scheduling_service.list_jobs(query, page).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/stats",
+    tag = "Schedules",
+    operation_id = "get_schedule_stats",
+    params(JobStatsQuery),
+    responses(
+        (status = 200, description = "Job counts per status, mean processing time and failure rate over the window", body = ApiResponse<JobStats>),
+        (status = 400, description = "window_hours out of range", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_stats(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Query(query): Query<JobStatsQuery>,
+) -> Result<Json<ApiResponse<JobStats>>, SchedulingServiceError> {
+    let output = state.scheduling_service.job_stats(query).await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FailedJobsQuery {
+    /// `asc` (oldest first, the default) or `desc` by creation time
+    #[serde(default)]
+    #[param(inline)]
+    pub order: SortOrder,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/failed",
+    tag = "Schedules",
+    operation_id = "list_failed_schedules",
+    params(FailedJobsQuery),
+    responses(
+        (status = 200, description = "Failed schedule jobs, oldest first unless `order=desc`", body = ApiResponse<Vec<shared::types::ScheduleJob>>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn list_failed(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Query(query): Query<FailedJobsQuery>,
+) -> Result<Json<ApiResponse<Vec<shared::types::ScheduleJob>>>, SchedulingServiceError> {
+    let jobs = state
+        .scheduling_service
+        .list_failed_jobs(query.order)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(jobs)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/retry",
+    tag = "Schedules",
+    operation_id = "retry_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID")
+    ),
+    responses(
+        (status = 202, description = "Job reset to PENDING and queued again", body = ApiResponse<shared::types::ScheduleJob>),
+        (status = 400, description = "Job is not in FAILED status"),
+        (status = 404, description = "Schedule job not found")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn retry_schedule(
+    _: WriteAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let job = state.scheduling_service.retry_job(schedule_id).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(ApiResponse::ok(job))))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultQuery {
+    /// Comma-separated extras to embed; `staff` adds name and position per staff id
+    pub include: Option<String>,
+    /// Result layout to return; takes precedence over a versioned `Accept` header
+    pub version: Option<u32>,
+    /// Order of the assignments, `staff` (by id) unless given
+    #[serde(default)]
+    pub sort: AssignmentOrder,
+    /// Split the assignments into `assignment_groups` by `staff` or `date`
+    pub group_by: Option<AssignmentGrouping>,
+}
+
+impl ResultQuery {
+    fn includes_staff(&self) -> Result<bool, SchedulingServiceError> {
+        let mut staff = false;
+        for part in self.include.iter().flat_map(|s| s.split(',')) {
+            match part.trim() {
+                "staff" => staff = true,
+                "" => {}
+                other => {
+                    return Err(SchedulingServiceError::BadRequest(format!(
+                        "Unknown include '{other}', expected 'staff'"
+                    )));
+                }
+            }
+        }
+        Ok(staff)
+    }
 }
 
 #[utoipa::path(
@@ -70,19 +672,305 @@ pub async fn get_status(
     path = "/api/v1/schedules/{schedule_id}/result",
     tag = "Schedules",
     operation_id = "get_schedule_result",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        ResultQuery,
+        ("Accept" = Option<String>, Header, description = "`application/vnd.shift-scheduler.result+json; version=N` pins the result layout")
+    ),
+    responses(
+        (status = 200, description = "Schedule result with shift assignments", body = ApiResponse<shared::types::ScheduleResult>),
+        (status = 400, description = "Unknown include or unsupported `version`", body = EmptyApiResponse),
+        (status = 406, description = "No result version in `Accept` is supported", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn get_result(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Query(query): Query<ResultQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let include_staff = query.includes_staff()?;
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let version = result_version::negotiate(query.version, accept)?;
+    let mut output = state.scheduling_service.get_result(schedule_id).await?;
+    if include_staff {
+        state.scheduling_service.hydrate_staff(&mut output).await?;
+    }
+    state
+        .scheduling_service
+        .order_assignments(&mut output, query.sort)
+        .await?;
+    if let Some(grouping) = query.group_by {
+        output.assignment_groups =
+            group_assignments(std::mem::take(&mut output.assignments), grouping);
+    }
+    // Only one layout exists so far; converting to an older one goes here
+    output.schema_version = version;
+
+    Ok(([(header::VARY, "accept")], Json(ApiResponse::ok(output))))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PayrollExportQuery {
+    /// Order of the rows, `staff` (by id) unless given
+    #[serde(default)]
+    pub sort: AssignmentOrder,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/payroll-export",
+    tag = "Reports",
+    operation_id = "get_payroll_export",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        PayrollExportQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Language of shift and day names (en, vi)")
+    ),
+    responses(
+        (status = 200, description = "Per-staff shift-hour records as CSV", body = String, content_type = "text/csv"),
+        (status = 400, description = "Job is not completed", body = EmptyApiResponse),
+        (status = 404, description = "Schedule job not found", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn get_payroll_export(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Query(query): Query<PayrollExportQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let csv = state
+        .scheduling_service
+        .payroll_export(schedule_id, requested_locale(&headers), query.sort)
+        .await?;
+    let disposition = format!("attachment; filename=\"payroll-{schedule_id}.csv\"");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        csv,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/staff/{staff_id}",
+    tag = "Schedules",
+    operation_id = "get_staff_schedule",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        ("staff_id" = Uuid, Path, description = "Staff member ID")
+    ),
+    responses(
+        (status = 200, description = "The staff member's assignments in the schedule", body = ApiResponse<StaffSchedule>),
+        (status = 400, description = "Job is not completed", body = EmptyApiResponse),
+        (status = 404, description = "Schedule job not found, or the staff member has no assignments in it", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_staff_schedule(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path((schedule_id, staff_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<StaffSchedule>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .staff_schedule(schedule_id, staff_id)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/staff/{staff_id}/assignments",
+    tag = "Schedules",
+    operation_id = "get_staff_assignments",
+    params(
+        ("staff_id" = Uuid, Path, description = "Staff member ID"),
+        StaffAssignmentsQuery
+    ),
+    responses(
+        (status = 200, description = "The staff member's assignments across published schedules", body = ApiResponse<StaffAssignments>),
+        (status = 400, description = "from after to, or a range over 366 days", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_staff_assignments(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(staff_id): Path<Uuid>,
+    Query(query): Query<StaffAssignmentsQuery>,
+) -> Result<Json<ApiResponse<StaffAssignments>>, SchedulingServiceError> {
+    let output = state
+        .scheduling_service
+        .staff_assignments(staff_id, query)
+        .await?;
+
+    Ok(Json(ApiResponse::ok(output)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultCalendarQuery {
+    /// Only this staff member's shifts, for a personal subscription
+    pub staff_id: Option<Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{schedule_id}/result.ics",
+    tag = "Reports",
+    operation_id = "get_result_calendar",
+    params(
+        ("schedule_id" = Uuid, Path, description = "Schedule job ID"),
+        ResultCalendarQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Language of event titles (en, vi)")
+    ),
+    responses(
+        (status = 200, description = "iCalendar feed of the schedule's working shifts", body = String, content_type = "text/calendar"),
+        (status = 400, description = "Job is not completed", body = EmptyApiResponse),
+        (status = 404, description = "Schedule job not found, or the staff member has no shifts in it", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn get_result_calendar(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(schedule_id): Path<Uuid>,
+    Query(query): Query<ResultCalendarQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let ics = state
+        .scheduling_service
+        .result_calendar(schedule_id, query.staff_id, requested_locale(&headers))
+        .await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ics,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/calendar.ics",
+    tag = "Reports",
+    operation_id = "get_group_calendar",
+    params(
+        ("group_id" = Uuid, Path, description = "Staff group ID"),
+        ("Accept-Language" = Option<String>, Header, description = "Language of event titles (en, vi)")
+    ),
+    responses(
+        (status = 200, description = "iCalendar feed of the group's published shifts", body = String, content_type = "text/calendar")
+    )
+)]
+#[tracing::instrument(skip(state, headers))]
+pub async fn get_group_calendar(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(group_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let ics = state
+        .scheduling_service
+        .group_calendar(group_id, requested_locale(&headers))
+        .await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ics,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/next-period",
+    tag = "Schedules",
+    operation_id = "get_next_period",
+    params(
+        ("group_id" = Uuid, Path, description = "Staff group ID")
+    ),
+    responses(
+        (status = 200, description = "Next Monday whose period is free of the group's jobs", body = ApiResponse<NextPeriod>)
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn get_next_period(
+    _: ReadAccess,
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(group_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<NextPeriod>>, SchedulingServiceError> {
+    let period = state.scheduling_service.next_period(group_id).await?;
+
+    Ok(Json(ApiResponse::ok(period)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules/{schedule_id}/share",
+    tag = "Sharing",
+    operation_id = "create_share_link",
     params(
         ("schedule_id" = Uuid, Path, description = "Schedule job ID")
     ),
+    request_body = CreateShareLinkRequest,
     responses(
-        (status = 200, description = "Schedule result with shift assignments", body = ApiResponse<shared::types::ScheduleResult>)
+        (status = 201, description = "Signed, expiring share link", body = ApiResponse<ShareLink>),
+        (status = 400, description = "Job is not completed and published, or expiry out of range", body = EmptyApiResponse),
+        (status = 404, description = "Schedule job not found", body = EmptyApiResponse)
     )
 )]
 #[tracing::instrument(skip(state))]
-pub async fn get_result(
+pub async fn create_share_link(
+    _: WriteAccess,
     State(state): State<Arc<SchedulingAppState>>,
     Path(schedule_id): Path<Uuid>,
+    Json(req): Json<CreateShareLinkRequest>,
+) -> Result<impl IntoResponse, SchedulingServiceError> {
+    let link = state
+        .scheduling_service
+        .create_share_link(schedule_id, req.expires_in_hours)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::ok(link))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shared/{token}",
+    tag = "Sharing",
+    security(()),
+    operation_id = "get_shared_schedule",
+    params(
+        ("token" = String, Path, description = "Share token; the only credential needed to read the result")
+    ),
+    responses(
+        (status = 200, description = "Read-only schedule result", body = ApiResponse<shared::types::ScheduleResult>),
+        (status = 404, description = "Share link is invalid or has expired", body = EmptyApiResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_shared_schedule(
+    State(state): State<Arc<SchedulingAppState>>,
+    Path(token): Path<String>,
 ) -> Result<Json<ApiResponse<shared::types::ScheduleResult>>, SchedulingServiceError> {
-    let output = state.scheduling_service.get_result(schedule_id).await?;
+    let output = state.scheduling_service.get_shared_result(&token).await?;
 
     Ok(Json(ApiResponse::ok(output)))
 }
+
+/// Locale explicitly asked for via `Accept-Language`, if it names a supported one.
+fn requested_locale(headers: &HeaderMap) -> Option<Locale> {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::negotiate)
+}
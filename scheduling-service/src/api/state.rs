@@ -1,7 +1,13 @@
 use std::sync::Arc;
 
-use crate::domain::service::SchedulingService;
+use shared::health::DependencyChecks;
+use shared::startup::StartupSelfCheck;
+
+use crate::domain::{health::HealthChecker, service::SchedulingService};
 
 pub struct SchedulingAppState {
     pub scheduling_service: Arc<SchedulingService>,
+    pub health_checker: Arc<HealthChecker>,
+    pub startup: Arc<StartupSelfCheck>,
+    pub dependencies: Arc<DependencyChecks>,
 }
@@ -1,5 +1,34 @@
+pub mod acknowledgment;
+pub mod approval;
+pub mod assignment_order;
+pub mod blackout;
+pub mod calendar;
+pub mod callback;
+pub mod checksum;
 pub mod client;
+pub mod consistency;
+pub mod coverage;
+pub mod export;
+pub mod float_pool;
+pub mod group_config;
+pub mod health;
+pub mod history;
 pub mod job;
 pub mod job_state;
+pub mod locale;
+pub mod lock;
+pub mod notify;
+pub mod packed;
+pub mod period;
+pub mod preflight;
+pub mod queue;
+pub mod result_version;
 pub mod scheduler;
 pub mod service;
+pub mod share;
+pub mod simulate;
+pub mod staff_schedule;
+pub mod stats;
+pub mod status_updates;
+pub mod targets;
+pub mod timeline;
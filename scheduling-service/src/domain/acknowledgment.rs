@@ -0,0 +1,185 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use shared::auth::{Principal, Role};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::SchedulingServiceError;
+
+/// Reminders about published schedules some staff haven't acknowledged yet. They go to the
+/// `[notifications]` channels.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AcknowledgmentConfig {
+    /// Hours after publishing before the first reminder, and between reminders; 0 sends none
+    pub reminder_interval_hours: u32,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct AcknowledgeRequest {
+    /// Staff member acknowledging, the caller when omitted. Only schedulers may name someone
+    /// else.
+    pub staff_id: Option<Uuid>,
+}
+
+impl AcknowledgeRequest {
+    /// Who `principal` acknowledges for: themselves, or anyone when they are a scheduler.
+    pub fn staff_id_for(&self, principal: &Principal) -> Result<Uuid, SchedulingServiceError> {
+        let own = principal.staff_id();
+        match self.staff_id {
+            Some(staff_id) if own == Some(staff_id) || principal.has(Role::Scheduler) => {
+                Ok(staff_id)
+            }
+            Some(staff_id) => Err(SchedulingServiceError::Forbidden(format!(
+                "Only schedulers can acknowledge for staff {staff_id}"
+            ))),
+            None => own.ok_or_else(|| {
+                SchedulingServiceError::BadRequest(
+                    "staff_id is required when the token's subject isn't a staff id".to_string(),
+                )
+            }),
+        }
+    }
+}
+
+/// A staff member on a published roster and when they acknowledged it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct StaffAcknowledgment {
+    pub staff_id: Uuid,
+    /// `None` while outstanding
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AcknowledgmentStatus {
+    pub schedule_id: Uuid,
+    pub published_at: DateTime<Utc>,
+    /// Staff with at least one assignment in the schedule
+    pub roster_size: usize,
+    pub acknowledged: usize,
+    /// Staff yet to acknowledge, by id
+    pub outstanding: Vec<Uuid>,
+    /// Every staff member on the roster, by id
+    pub staff: Vec<StaffAcknowledgment>,
+}
+
+impl AcknowledgmentStatus {
+    pub fn new(
+        schedule_id: Uuid,
+        published_at: DateTime<Utc>,
+        staff: Vec<StaffAcknowledgment>,
+    ) -> Self {
+        let outstanding: Vec<Uuid> = staff
+            .iter()
+            .filter(|s| s.acknowledged_at.is_none())
+            .map(|s| s.staff_id)
+            .collect();
+        Self {
+            schedule_id,
+            published_at,
+            roster_size: staff.len(),
+            acknowledged: staff.len() - outstanding.len(),
+            outstanding,
+            staff,
+        }
+    }
+}
+
+/// A published schedule, for a period that hasn't ended, with staff yet to acknowledge it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OutstandingAcknowledgments {
+    pub schedule_id: Uuid,
+    pub staff_group_id: Uuid,
+    pub period_begin_date: NaiveDate,
+    pub published_at: DateTime<Utc>,
+    pub roster_size: i64,
+    /// Staff yet to acknowledge, by id
+    pub outstanding: Vec<Uuid>,
+    pub last_reminded_at: Option<DateTime<Utc>>,
+}
+
+impl OutstandingAcknowledgments {
+    /// Whether a reminder is due at `now`: `interval_hours` after publishing, then after
+    /// each reminder.
+    pub fn reminder_due(&self, interval_hours: u32, now: DateTime<Utc>) -> bool {
+        interval_hours > 0
+            && self.last_reminded_at.unwrap_or(self.published_at)
+                + chrono::TimeDelta::hours(i64::from(interval_hours))
+                <= now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn status_splits_acknowledged_from_outstanding() {
+        let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let status = AcknowledgmentStatus::new(
+            Uuid::nil(),
+            Utc::now(),
+            vec![
+                StaffAcknowledgment {
+                    staff_id: alice,
+                    acknowledged_at: Some(Utc::now()),
+                },
+                StaffAcknowledgment {
+                    staff_id: bob,
+                    acknowledged_at: None,
+                },
+            ],
+        );
+
+        assert_eq!(status.roster_size, 2);
+        assert_eq!(status.acknowledged, 1);
+        assert_eq!(status.outstanding, vec![bob]);
+    }
+
+    #[test]
+    fn reminders_repeat_after_the_interval() {
+        let now = Utc::now();
+        let mut outstanding = OutstandingAcknowledgments {
+            schedule_id: Uuid::nil(),
+            staff_group_id: Uuid::nil(),
+            period_begin_date: now.date_naive(),
+            published_at: now - TimeDelta::hours(30),
+            roster_size: 3,
+            outstanding: vec![Uuid::nil()],
+            last_reminded_at: None,
+        };
+        assert!(outstanding.reminder_due(24, now));
+        assert!(!outstanding.reminder_due(0, now));
+
+        outstanding.last_reminded_at = Some(now - TimeDelta::hours(6));
+        assert!(!outstanding.reminder_due(24, now));
+    }
+
+    #[test]
+    fn only_schedulers_acknowledge_for_someone_else() {
+        let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let principal = |subject: String, role| Principal {
+            subject,
+            roles: vec![role],
+        };
+        let viewer = principal(alice.to_string(), Role::Viewer);
+        let request = |staff_id| AcknowledgeRequest { staff_id };
+
+        assert_eq!(request(None).staff_id_for(&viewer).unwrap(), alice);
+        assert_eq!(request(Some(alice)).staff_id_for(&viewer).unwrap(), alice);
+        assert!(matches!(
+            request(Some(bob)).staff_id_for(&viewer),
+            Err(SchedulingServiceError::Forbidden(_))
+        ));
+
+        let scheduler = principal("planner@example.com".to_string(), Role::Scheduler);
+        assert_eq!(request(Some(bob)).staff_id_for(&scheduler).unwrap(), bob);
+        assert!(matches!(
+            request(None).staff_id_for(&scheduler),
+            Err(SchedulingServiceError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::auth::Principal;
+use shared::types::ScheduleJob;
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::SchedulingServiceError;
+
+/// Longest approval comment accepted, in characters.
+pub const MAX_APPROVAL_COMMENT_CHARS: usize = 2_000;
+
+/// Groups whose completed schedules need sign-off before they can be published. Groups
+/// without an entry can publish straight away.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ApprovalConfig {
+    pub groups: HashMap<Uuid, GroupApprovers>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupApprovers {
+    /// Staff allowed to approve or reject, typically the group's leads
+    pub approvers: Vec<Uuid>,
+    /// Approvals needed to publish, every approver when omitted
+    pub required: Option<usize>,
+}
+
+impl GroupApprovers {
+    pub fn required(&self) -> usize {
+        let approvers = self.approvers.len();
+        self.required.unwrap_or(approvers).min(approvers)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "approval_decision", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApprovalDecision {
+    Approved,
+    Rejected,
+}
+
+/// The approver is the caller, whose token's `sub` must be their staff id.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ApprovalRequest {
+    /// Optional when approving, required when rejecting
+    pub comment: Option<String>,
+}
+
+/// The staff member deciding: always the caller, never someone named in the request.
+pub fn approver_of(principal: &Principal) -> Result<Uuid, SchedulingServiceError> {
+    principal.staff_id().ok_or_else(|| {
+        SchedulingServiceError::Forbidden(
+            "Approving needs a token whose subject is the approver's staff id".to_string(),
+        )
+    })
+}
+
+/// One approver's latest decision on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScheduleApproval {
+    pub job_id: Uuid,
+    pub approver_id: Uuid,
+    pub decision: ApprovalDecision,
+    pub comment: Option<String>,
+    /// Result checksum of the schedule when the decision was made
+    pub checksum: Option<String>,
+    pub decided_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApprovalStatus {
+    pub schedule_id: Uuid,
+    /// Current result checksum; decisions made against another one predate an edit and
+    /// don't count
+    pub checksum: Option<String>,
+    pub approvers: Vec<Uuid>,
+    pub required: usize,
+    pub approved: usize,
+    /// Any current rejection blocks publishing until that approver approves
+    pub rejected: usize,
+    pub can_publish: bool,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Latest decision per approver, newest first
+    pub decisions: Vec<ScheduleApproval>,
+}
+
+impl ApprovalStatus {
+    /// Only decisions by current approvers on the schedule as it is now are counted.
+    pub fn new(
+        job: &ScheduleJob,
+        approvers: Option<&GroupApprovers>,
+        decisions: Vec<ScheduleApproval>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let (approver_ids, required) = approvers
+            .map(|a| (a.approvers.clone(), a.required()))
+            .unwrap_or_default();
+        let count = |decision| {
+            decisions
+                .iter()
+                .filter(|d| {
+                    d.decision == decision
+                        && d.checksum == job.result_checksum
+                        && approver_ids.contains(&d.approver_id)
+                })
+                .count()
+        };
+        let (approved, rejected) = (
+            count(ApprovalDecision::Approved),
+            count(ApprovalDecision::Rejected),
+        );
+
+        Self {
+            schedule_id: job.id,
+            checksum: job.result_checksum.clone(),
+            approvers: approver_ids,
+            required,
+            approved,
+            rejected,
+            can_publish: published_at.is_none() && rejected == 0 && approved >= required,
+            published_at,
+            decisions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::types::JobStatus;
+
+    fn job(checksum: &str) -> ScheduleJob {
+        ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: chrono::NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            status: JobStatus::Completed,
+            result_checksum: Some(checksum.to_string()),
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        }
+    }
+
+    fn decision(approver_id: Uuid, decision: ApprovalDecision, checksum: &str) -> ScheduleApproval {
+        ScheduleApproval {
+            job_id: Uuid::nil(),
+            approver_id,
+            decision,
+            comment: None,
+            checksum: Some(checksum.to_string()),
+            decided_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn required_defaults_to_every_approver_and_is_capped() {
+        let approvers = |required| GroupApprovers {
+            approvers: vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()],
+            required,
+        };
+        assert_eq!(approvers(None).required(), 3);
+        assert_eq!(approvers(Some(2)).required(), 2);
+        assert_eq!(approvers(Some(5)).required(), 3);
+    }
+
+    #[test]
+    fn publishing_needs_enough_current_approvals_and_no_rejection() {
+        let leads: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let approvers = GroupApprovers {
+            approvers: leads.clone(),
+            required: Some(2),
+        };
+        let job = job("abc");
+        let status =
+            |decisions| ApprovalStatus::new(&job, Some(&approvers), decisions, None).can_publish;
+
+        assert!(!status(vec![decision(
+            leads[0],
+            ApprovalDecision::Approved,
+            "abc"
+        )]));
+        assert!(status(vec![
+            decision(leads[0], ApprovalDecision::Approved, "abc"),
+            decision(leads[1], ApprovalDecision::Approved, "abc"),
+        ]));
+        assert!(!status(vec![
+            decision(leads[0], ApprovalDecision::Approved, "abc"),
+            decision(leads[1], ApprovalDecision::Approved, "abc"),
+            decision(leads[2], ApprovalDecision::Rejected, "abc"),
+        ]));
+        // Approved before an edit, or by someone who is no longer an approver
+        assert!(!status(vec![
+            decision(leads[0], ApprovalDecision::Approved, "abc"),
+            decision(leads[1], ApprovalDecision::Approved, "old"),
+            decision(Uuid::new_v4(), ApprovalDecision::Approved, "abc"),
+        ]));
+        // A stale rejection no longer blocks
+        assert!(status(vec![
+            decision(leads[0], ApprovalDecision::Approved, "abc"),
+            decision(leads[1], ApprovalDecision::Approved, "abc"),
+            decision(leads[2], ApprovalDecision::Rejected, "old"),
+        ]));
+    }
+
+    #[test]
+    fn groups_without_approvers_can_publish_once() {
+        let job = job("abc");
+        assert!(ApprovalStatus::new(&job, None, vec![], None).can_publish);
+        assert!(!ApprovalStatus::new(&job, None, vec![], Some(Utc::now())).can_publish);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::types::{AssignmentGroup, ShiftAssignment};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How a result's assignments are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentOrder {
+    /// By staff id, then date, as stored
+    #[default]
+    Staff,
+    /// By staff name, then date; staff with the same name stay apart by id
+    StaffName,
+    /// By date, then shift (MORNING first, DAY_OFF last), then staff id
+    Date,
+}
+
+/// What a result's assignments are grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentGrouping {
+    Staff,
+    Date,
+}
+
+/// Sort `assignments` by `order`. `names` is only read for `StaffName`; staff missing from it
+/// sort after the named ones.
+pub fn sort_assignments(
+    assignments: &mut [ShiftAssignment],
+    order: AssignmentOrder,
+    names: &HashMap<Uuid, String>,
+) {
+    match order {
+        AssignmentOrder::Staff => assignments.sort_by_key(|a| (a.staff_id, a.date)),
+        AssignmentOrder::StaffName => assignments.sort_by(|a, b| {
+            let name = |id| names.get(&id).map_or((true, ""), |n| (false, n.as_str()));
+            (name(a.staff_id), a.staff_id, a.date).cmp(&(name(b.staff_id), b.staff_id, b.date))
+        }),
+        AssignmentOrder::Date => {
+            assignments.sort_by(|a, b| {
+                (a.date, &a.shift_type, a.staff_id).cmp(&(b.date, &b.shift_type, b.staff_id))
+            });
+        }
+    }
+}
+
+/// Split sorted assignments into groups, keeping the order within each. Staff groups come in
+/// the order their first assignment appears, so they follow the sort; date groups always run
+/// by date.
+pub fn group_assignments(
+    assignments: Vec<ShiftAssignment>,
+    grouping: AssignmentGrouping,
+) -> Vec<AssignmentGroup> {
+    let mut groups: Vec<AssignmentGroup> = Vec::new();
+    let mut index: HashMap<(Option<Uuid>, Option<NaiveDate>), usize> = HashMap::new();
+    for assignment in assignments {
+        let key = match grouping {
+            AssignmentGrouping::Staff => (Some(assignment.staff_id), None),
+            AssignmentGrouping::Date => (None, Some(assignment.date)),
+        };
+        let i = *index.entry(key).or_insert_with(|| {
+            groups.push(AssignmentGroup {
+                staff_id: key.0,
+                date: key.1,
+                assignments: vec![],
+            });
+            groups.len() - 1
+        });
+        groups[i].assignments.push(assignment);
+    }
+    if grouping == AssignmentGrouping::Date {
+        groups.sort_by_key(|g| g.date);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+    use shared::types::ShiftType;
+
+    fn assignment(staff_id: Uuid, day: u32, shift_type: ShiftType) -> ShiftAssignment {
+        ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id: Uuid::nil(),
+            staff_id,
+            date: NaiveDate::from_ymd_opt(2026, 3, day).unwrap(),
+            shift_type,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn staff_name_order_groups_each_persons_shifts_by_date() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let names = HashMap::from([(alice, "Alice".to_string()), (bob, "Bob".to_string())]);
+        let mut assignments = vec![
+            assignment(bob, 2, ShiftType::Morning),
+            assignment(alice, 3, ShiftType::Evening),
+            assignment(bob, 1, ShiftType::Evening),
+            assignment(alice, 1, ShiftType::Morning),
+        ];
+
+        sort_assignments(&mut assignments, AssignmentOrder::StaffName, &names);
+        let order: Vec<_> = assignments
+            .iter()
+            .map(|a| (a.staff_id, a.date.day0() + 1))
+            .collect();
+        assert_eq!(order, [(alice, 1), (alice, 3), (bob, 1), (bob, 2)]);
+
+        let groups = group_assignments(assignments, AssignmentGrouping::Staff);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].staff_id, Some(alice));
+        assert_eq!(groups[1].assignments.len(), 2);
+    }
+
+    #[test]
+    fn date_groups_run_by_date_with_morning_first() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut assignments = vec![
+            assignment(alice, 2, ShiftType::DayOff),
+            assignment(bob, 1, ShiftType::Evening),
+            assignment(alice, 1, ShiftType::Morning),
+            assignment(bob, 2, ShiftType::Morning),
+        ];
+
+        sort_assignments(&mut assignments, AssignmentOrder::Date, &HashMap::new());
+        let groups = group_assignments(assignments, AssignmentGrouping::Date);
+        let layout: Vec<Vec<Uuid>> = groups
+            .iter()
+            .map(|g| g.assignments.iter().map(|a| a.staff_id).collect())
+            .collect();
+        assert_eq!(layout, [vec![alice, bob], vec![bob, alice]]);
+        assert!(groups.iter().all(|g| g.staff_id.is_none()));
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::{Duration, NaiveDate};
+use shared::types::{GroupBlackout, ShiftType};
+
+use crate::domain::scheduler::{AssignmentContext, SchedulingRule};
+
+/// Caps DAY_OFF assignments on the dates of the group's blackout windows at `max_off_percent`
+/// of the staff being scheduled, rounded down.
+pub struct BlackoutRule {
+    pub windows: Vec<GroupBlackout>,
+    pub min_day_off_per_week: u8,
+}
+
+impl BlackoutRule {
+    /// Most days off allowed on `date`, `None` outside every window. Overlapping windows take
+    /// the strictest cap.
+    pub fn day_off_cap(&self, date: NaiveDate, staff: usize) -> Option<usize> {
+        self.windows
+            .iter()
+            .filter(|window| window.contains(date))
+            .map(|window| staff * window.max_off_percent.clamp(0, 100) as usize / 100)
+            .min()
+    }
+}
+
+impl SchedulingRule for BlackoutRule {
+    fn name(&self) -> &str {
+        "blackout"
+    }
+
+    fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
+        let staff = ctx.day_tally.staff;
+        let cap_today = self.day_off_cap(ctx.date, staff);
+        if *candidate == ShiftType::DayOff {
+            return cap_today.is_none_or(|cap| ctx.day_off_count < cap);
+        }
+
+        // Working today moves the week's outstanding days off to later days, which may be
+        // capped too. Take the day off now if they wouldn't fit.
+        let later_days =
+            (1..=ctx.days_remaining_in_week as i64).map(|days| ctx.date + Duration::days(days));
+        let later_caps: Vec<_> = later_days
+            .map(|date| self.day_off_cap(date, staff))
+            .collect();
+        if cap_today.is_none() && later_caps.iter().all(Option::is_none) {
+            return true;
+        }
+
+        let owes_day_off = ctx.day_offs_this_week < self.min_day_off_per_week;
+        let debt = ctx.day_tally.day_off_debt;
+        let owing_left_today = ctx
+            .day_tally
+            .unassigned_owing
+            .saturating_sub(usize::from(owes_day_off));
+        let today = match cap_today {
+            Some(cap) => cap.saturating_sub(ctx.day_off_count).min(owing_left_today),
+            None => owing_left_today,
+        };
+        let later: usize = later_caps.iter().map(|cap| cap.unwrap_or(staff)).sum();
+        debt <= today + later
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::LazyLock;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::coverage::PositionTally;
+
+    static NO_POSITIONS: LazyLock<HashMap<String, PositionTally>> = LazyLock::new(HashMap::new);
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 12, day).unwrap()
+    }
+
+    fn rule(start: u32, end: u32, max_off_percent: i16) -> BlackoutRule {
+        BlackoutRule {
+            windows: vec![GroupBlackout {
+                id: Uuid::new_v4(),
+                group_id: Uuid::new_v4(),
+                start_date: date(start),
+                end_date: date(end),
+                max_off_percent,
+                reason: None,
+                created_at: Utc::now(),
+            }],
+            min_day_off_per_week: 1,
+        }
+    }
+
+    fn context<'a>(
+        date: NaiveDate,
+        day_off_count: usize,
+        days_remaining_in_week: u8,
+        day_tally: &'a PositionTally,
+    ) -> AssignmentContext<'a> {
+        AssignmentContext {
+            date,
+            previous_shift: None,
+            days_off_in_a_row: 0,
+            day_offs_this_week: 0,
+            days_remaining_in_week,
+            morning_count: 0,
+            evening_count: 0,
+            night_count: 0,
+            day_off_count,
+            position: "Nurse",
+            position_tally: &NO_POSITIONS,
+            day_tally,
+        }
+    }
+
+    fn tally(staff: usize, unassigned: usize, day_off_debt: usize) -> PositionTally {
+        PositionTally {
+            staff,
+            unassigned,
+            day_off_debt,
+            unassigned_owing: unassigned.min(day_off_debt),
+            ..PositionTally::default()
+        }
+    }
+
+    #[test]
+    fn day_off_cap_rounds_down_and_ignores_other_dates() {
+        let rule = rule(24, 26, 25);
+
+        assert_eq!(rule.day_off_cap(date(24), 10), Some(2));
+        assert_eq!(rule.day_off_cap(date(26), 3), Some(0));
+        assert_eq!(rule.day_off_cap(date(27), 10), None);
+    }
+
+    #[test]
+    fn day_off_is_rejected_once_the_cap_is_reached() {
+        let rule = rule(24, 26, 20);
+        let tally = tally(10, 5, 0);
+
+        assert!(rule.is_valid(&context(date(24), 1, 3, &tally), &ShiftType::DayOff));
+        assert!(!rule.is_valid(&context(date(24), 2, 3, &tally), &ShiftType::DayOff));
+        assert!(rule.is_valid(&context(date(23), 9, 3, &tally), &ShiftType::DayOff));
+    }
+
+    #[test]
+    fn working_is_rejected_when_later_capped_days_cannot_absorb_days_off() {
+        // The whole week from Monday 21st is capped at 1 day off per day
+        let rule = rule(21, 27, 10);
+        let tally = tally(10, 10, 10);
+
+        assert!(!rule.is_valid(&context(date(21), 0, 6, &tally), &ShiftType::Morning));
+        assert!(rule.is_valid(&context(date(21), 0, 6, &tally), &ShiftType::DayOff));
+        // Far enough from the window, nothing changes
+        assert!(rule.is_valid(&context(date(14), 0, 6, &tally), &ShiftType::Morning));
+    }
+}
@@ -0,0 +1,284 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use shared::types::ShiftAssignment;
+
+use crate::domain::export::ShiftTimes;
+use crate::domain::locale::Locale;
+
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Date window (relative to today) covered by subscribed calendar feeds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub horizon_past_days: u32,
+    pub horizon_future_days: u32,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            horizon_past_days: 7,
+            horizon_future_days: 56,
+        }
+    }
+}
+
+impl CalendarConfig {
+    pub fn horizon(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        (
+            today - chrono::Days::new(self.horizon_past_days.into()),
+            today + chrono::Days::new(self.horizon_future_days.into()),
+        )
+    }
+}
+
+pub struct CalendarEntry<'a> {
+    pub assignment: &'a ShiftAssignment,
+    pub staff_name: Option<&'a str>,
+}
+
+/// Render working-shift assignments as an RFC 5545 calendar.
+///
+/// Shift times are configured as wall-clock times in `timezone` and emitted in UTC, so
+/// clients show the right hours across DST changes without needing VTIMEZONE blocks.
+/// Day offs produce no events.
+pub fn render_calendar(
+    name: &str,
+    entries: &[CalendarEntry<'_>],
+    timezone: Tz,
+    shift_times: &ShiftTimes,
+    locale: Locale,
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut output = String::new();
+    let dtstamp = format_utc(generated_at);
+
+    push_line(&mut output, "BEGIN:VCALENDAR");
+    push_line(&mut output, "VERSION:2.0");
+    push_line(
+        &mut output,
+        "PRODID:-//shift-scheduler//scheduling-service//EN",
+    );
+    push_line(&mut output, "CALSCALE:GREGORIAN");
+    push_line(&mut output, "METHOD:PUBLISH");
+    push_line(&mut output, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    push_line(&mut output, &format!("X-WR-TIMEZONE:{}", timezone.name()));
+
+    for entry in entries {
+        let assignment = entry.assignment;
+        let Some(window) = shift_times.window(&assignment.shift_type) else {
+            continue;
+        };
+
+        let start = to_utc(timezone, assignment.date, window.start);
+        let end = start + window.duration();
+        let who = entry
+            .staff_name
+            .map(str::to_string)
+            .unwrap_or_else(|| assignment.staff_id.to_string());
+        let summary = locale.shift_event_title(&who, &assignment.shift_type);
+
+        push_line(&mut output, "BEGIN:VEVENT");
+        push_line(
+            &mut output,
+            &format!("UID:{}@shift-scheduler", assignment.id),
+        );
+        push_line(&mut output, &format!("DTSTAMP:{dtstamp}"));
+        push_line(&mut output, &format!("DTSTART:{}", format_utc(start)));
+        push_line(&mut output, &format!("DTEND:{}", format_utc(end)));
+        push_line(&mut output, &format!("SUMMARY:{}", escape_text(&summary)));
+        if let Some(note) = &assignment.note {
+            push_line(&mut output, &format!("DESCRIPTION:{}", escape_text(note)));
+        }
+        push_line(&mut output, "END:VEVENT");
+    }
+
+    push_line(&mut output, "END:VCALENDAR");
+    output
+}
+
+/// Local wall-clock time to UTC. Ambiguous times (DST fall-back) take the earlier instant,
+/// times inside a spring-forward gap are pushed an hour later, past the gap.
+fn to_utc(timezone: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(local + TimeDelta::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folding it at 75 octets as RFC 5545 requires.
+fn push_line(output: &mut String, line: &str) {
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            octets = 1;
+        }
+        output.push(ch);
+        octets += ch.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::types::ShiftType;
+    use uuid::Uuid;
+
+    fn make_assignment(date: NaiveDate, shift_type: ShiftType) -> ShiftAssignment {
+        ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id: Uuid::new_v4(),
+            staff_id: Uuid::new_v4(),
+            date,
+            shift_type,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn calendar_converts_shift_times_to_utc_and_skips_day_off() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let morning = make_assignment(date, ShiftType::Morning);
+        let day_off = make_assignment(date, ShiftType::DayOff);
+        let entries = [
+            CalendarEntry {
+                assignment: &morning,
+                staff_name: Some("Alice"),
+            },
+            CalendarEntry {
+                assignment: &day_off,
+                staff_name: None,
+            },
+        ];
+
+        let ics = render_calendar(
+            "Ward A",
+            &entries,
+            chrono_tz::Asia::Ho_Chi_Minh,
+            &ShiftTimes::default(),
+            Locale::En,
+            Utc::now(),
+        );
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        // 06:00 in Ho Chi Minh (UTC+7) is 23:00 UTC the previous day
+        assert!(ics.contains("DTSTART:20260215T230000Z\r\n"));
+        assert!(ics.contains("DTEND:20260216T070000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Alice - Morning shift\r\n"));
+    }
+
+    #[test]
+    fn assignment_note_becomes_event_description() {
+        let mut assignment = make_assignment(
+            NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            ShiftType::Evening,
+        );
+        assignment.note = Some("Cover front desk, then ward B".to_string());
+        let entries = [CalendarEntry {
+            assignment: &assignment,
+            staff_name: None,
+        }];
+
+        let ics = render_calendar(
+            "Ward A",
+            &entries,
+            chrono_tz::UTC,
+            &ShiftTimes::default(),
+            Locale::En,
+            Utc::now(),
+        );
+
+        assert!(ics.contains("DESCRIPTION:Cover front desk\\, then ward B\r\n"));
+    }
+
+    #[test]
+    fn calendar_respects_dst_offsets() {
+        // US/Eastern switches to daylight time on 2026-03-08
+        let before = make_assignment(
+            NaiveDate::from_ymd_opt(2026, 3, 7).unwrap(),
+            ShiftType::Morning,
+        );
+        let after = make_assignment(
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap(),
+            ShiftType::Morning,
+        );
+        let entries = [
+            CalendarEntry {
+                assignment: &before,
+                staff_name: None,
+            },
+            CalendarEntry {
+                assignment: &after,
+                staff_name: None,
+            },
+        ];
+
+        let ics = render_calendar(
+            "Ward A",
+            &entries,
+            chrono_tz::US::Eastern,
+            &ShiftTimes::default(),
+            Locale::En,
+            Utc::now(),
+        );
+
+        assert!(ics.contains("DTSTART:20260307T110000Z\r\n"));
+        assert!(ics.contains("DTSTART:20260309T100000Z\r\n"));
+    }
+
+    #[test]
+    fn calendar_titles_follow_locale() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let evening = make_assignment(date, ShiftType::Evening);
+        let entries = [CalendarEntry {
+            assignment: &evening,
+            staff_name: Some("Lan"),
+        }];
+
+        let ics = render_calendar(
+            "Khoa A",
+            &entries,
+            chrono_tz::Asia::Ho_Chi_Minh,
+            &ShiftTimes::default(),
+            Locale::Vi,
+            Utc::now(),
+        );
+
+        assert!(ics.contains("SUMMARY:Lan - Ca tối\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let mut output = String::new();
+        push_line(&mut output, &"x".repeat(100));
+
+        let lines: Vec<_> = output.split("\r\n").collect();
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "x".repeat(25)));
+    }
+}
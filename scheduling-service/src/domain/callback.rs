@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::{JobStatus, ScheduleResult};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::SchedulingServiceError;
+
+/// Longest `callback_url` accepted, in characters.
+pub const MAX_CALLBACK_URL_CHARS: usize = 2_048;
+
+/// Body POSTed to a job's `callback_url` once it completes or fails.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobCallback {
+    pub job_id: Uuid,
+    /// `COMPLETED` or `FAILED`
+    pub status: JobStatus,
+    /// The schedule as the result endpoint returns it, when the job completed
+    pub result: Option<ScheduleResult>,
+    /// Why the job failed
+    pub error: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+pub fn validate_callback_url(url: &str) -> Result<(), SchedulingServiceError> {
+    let scheme_ok = url
+        .split_once("://")
+        .is_some_and(|(scheme, rest)| matches!(scheme, "http" | "https") && !rest.is_empty());
+    if !scheme_ok {
+        return Err(SchedulingServiceError::BadRequest(
+            "callback_url must be an http or https URL".to_string(),
+        ));
+    }
+    if url.chars().count() > MAX_CALLBACK_URL_CHARS {
+        return Err(SchedulingServiceError::BadRequest(format!(
+            "callback_url must be at most {MAX_CALLBACK_URL_CHARS} characters"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callback_urls_must_be_http() {
+        assert!(validate_callback_url("https://example.com/hooks/schedule").is_ok());
+        assert!(validate_callback_url("http://10.0.0.5:8080/done").is_ok());
+        for url in ["ftp://example.com", "example.com/hook", "https://"] {
+            assert!(validate_callback_url(url).is_err(), "{url} was accepted");
+        }
+        let long = format!("https://example.com/{}", "a".repeat(MAX_CALLBACK_URL_CHARS));
+        assert!(validate_callback_url(&long).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use shared::types::ShiftType;
+use uuid::Uuid;
+
+/// Content hash of an assignment set.
+///
+/// Canonical form: one `{staff_id},{date},{SHIFT_TYPE}\n` line per assignment, sorted by
+/// staff id then date, hashed with SHA-256 and hex encoded. Row ids are not part of the
+/// hash, so downstream systems can recompute it from the result payload alone.
+pub fn assignments_checksum<'a>(
+    assignments: impl IntoIterator<Item = (Uuid, NaiveDate, &'a ShiftType)>,
+) -> String {
+    let mut lines: Vec<_> = assignments.into_iter().collect();
+    lines.sort_by_key(|(staff_id, date, _)| (*staff_id, *date));
+
+    let mut hasher = Sha256::new();
+    for (staff_id, date, shift_type) in lines {
+        hasher.update(format!("{staff_id},{date},{}\n", shift_type.as_str()));
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, day).unwrap()
+    }
+
+    #[test]
+    fn checksum_ignores_input_order() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let forward = [
+            (a, date(16), &ShiftType::Morning),
+            (b, date(16), &ShiftType::Evening),
+            (a, date(17), &ShiftType::DayOff),
+        ];
+        let mut reversed = forward;
+        reversed.reverse();
+
+        assert_eq!(
+            assignments_checksum(forward),
+            assignments_checksum(reversed)
+        );
+    }
+
+    #[test]
+    fn checksum_detects_changed_shift() {
+        let a = Uuid::new_v4();
+        let original = assignments_checksum([(a, date(16), &ShiftType::Morning)]);
+        let tampered = assignments_checksum([(a, date(16), &ShiftType::Evening)]);
+
+        assert_ne!(original, tampered);
+        assert_eq!(original.len(), 64);
+    }
+}
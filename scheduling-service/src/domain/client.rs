@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use shared::types::Staff;
+use chrono::NaiveDate;
+use shared::types::{GroupBlackout, GroupMember, Staff, TimeOffRequest};
 use uuid::Uuid;
 
 use crate::error::SchedulingServiceError;
@@ -7,8 +10,44 @@ use crate::error::SchedulingServiceError;
 #[cfg_attr(feature = "test-support", mockall::automock)]
 #[async_trait]
 pub trait DataServiceClient: Send + Sync {
+    /// All members of the group and its sub-groups, optionally only active ones. Members
+    /// whose membership starts later are included, with its start date.
     async fn get_resolved_members(
         &self,
         staff_group_id: Uuid,
-    ) -> Result<Vec<Staff>, SchedulingServiceError>;
+        active_only: bool,
+    ) -> Result<Vec<GroupMember>, SchedulingServiceError>;
+
+    /// Number of members `get_resolved_members` would return, without transferring them.
+    async fn count_resolved_members(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<i64, SchedulingServiceError>;
+
+    /// Resolved members per position, counted by the data-service in one query.
+    async fn count_members_by_position(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<HashMap<String, i64>, SchedulingServiceError>;
+
+    /// Staff with the given ids; ids unknown to the data-service are left out.
+    async fn get_staff_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, SchedulingServiceError>;
+
+    /// Day-off blackout windows of the group overlapping `from..=to`.
+    async fn get_blackouts(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<GroupBlackout>, SchedulingServiceError>;
+
+    /// Approved time off of the group's resolved members overlapping `from..=to`.
+    async fn get_time_off(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TimeOffRequest>, SchedulingServiceError>;
 }
@@ -0,0 +1,124 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Author of the notes the check leaves on affected schedules.
+pub const CONSISTENCY_NOTE_AUTHOR: &str = "consistency-check";
+/// Staff ids spelled out in one note; the rest are counted.
+const MAX_NOTED_STAFF: usize = 50;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConsistencyCheckQuery {
+    /// Leave a note on every schedule with orphaned assignments
+    #[serde(default)]
+    pub annotate: bool,
+}
+
+/// A staff id found in stored assignments, with the jobs it appears in.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct StaffReferences {
+    pub staff_id: Uuid,
+    pub job_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct OrphanedStaff {
+    pub staff_id: Uuid,
+    /// Jobs with assignments of this staff id, in id order
+    pub job_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ConsistencyReport {
+    /// Distinct staff ids referenced by stored assignments
+    pub checked_staff: usize,
+    /// Referenced staff ids the data-service doesn't know
+    pub orphaned: Vec<OrphanedStaff>,
+    /// Jobs that got a note, 0 without `annotate`. Jobs already noted for the same staff are
+    /// left alone
+    pub annotated_jobs: usize,
+}
+
+/// The references whose staff id isn't in `known`.
+pub fn find_orphans(references: Vec<StaffReferences>, known: &HashSet<Uuid>) -> Vec<OrphanedStaff> {
+    references
+        .into_iter()
+        .filter(|r| !known.contains(&r.staff_id))
+        .map(|r| OrphanedStaff {
+            staff_id: r.staff_id,
+            job_ids: r.job_ids,
+        })
+        .collect()
+}
+
+/// The orphaned staff ids of each affected job, both in id order.
+pub fn orphans_by_job(orphaned: &[OrphanedStaff]) -> BTreeMap<Uuid, Vec<Uuid>> {
+    let mut by_job: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for orphan in orphaned {
+        for job_id in &orphan.job_ids {
+            by_job.entry(*job_id).or_default().push(orphan.staff_id);
+        }
+    }
+    for staff_ids in by_job.values_mut() {
+        staff_ids.sort_unstable();
+    }
+    by_job
+}
+
+/// Body of the note left on a job whose assignments reference `staff_ids`.
+pub fn orphan_note(staff_ids: &[Uuid]) -> String {
+    let mut listed: Vec<String> = staff_ids
+        .iter()
+        .take(MAX_NOTED_STAFF)
+        .map(Uuid::to_string)
+        .collect();
+    if staff_ids.len() > MAX_NOTED_STAFF {
+        listed.push(format!("and {} more", staff_ids.len() - MAX_NOTED_STAFF));
+    }
+    format!(
+        "Assignments reference staff that no longer exist in the data-service: {}",
+        listed.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orphans_are_grouped_per_job() {
+        let (kept, gone_a, gone_b) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+        let (job_1, job_2) = (Uuid::from_u128(10), Uuid::from_u128(11));
+        let references = vec![
+            StaffReferences {
+                staff_id: kept,
+                job_ids: vec![job_1, job_2],
+            },
+            StaffReferences {
+                staff_id: gone_b,
+                job_ids: vec![job_1],
+            },
+            StaffReferences {
+                staff_id: gone_a,
+                job_ids: vec![job_1, job_2],
+            },
+        ];
+
+        let orphaned = find_orphans(references, &HashSet::from([kept]));
+        assert_eq!(orphaned.len(), 2);
+
+        let by_job = orphans_by_job(&orphaned);
+        assert_eq!(by_job[&job_1], vec![gone_a, gone_b]);
+        assert_eq!(by_job[&job_2], vec![gone_a]);
+    }
+
+    #[test]
+    fn long_notes_count_the_rest() {
+        let staff_ids: Vec<Uuid> = (0..60).map(Uuid::from_u128).collect();
+        assert!(orphan_note(&staff_ids).ends_with("and 10 more"));
+    }
+}
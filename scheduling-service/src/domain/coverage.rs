@@ -0,0 +1,615 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use shared::types::{CoverageShortfall, ShiftType};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::job::{NewShiftAssignment, NewUnfilledShift};
+use crate::domain::scheduler::{AssignmentContext, RosterMember, SchedulingConfig, SchedulingRule};
+
+/// Staff needed per position on each shift, set per group under
+/// `[position_coverage.groups."<group id>"]`. Groups without an entry have no position limits.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PositionCoverageConfig {
+    pub groups: HashMap<Uuid, ShiftPositionLimits>,
+}
+
+/// Limits keyed by the staff `position`, matched exactly.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShiftPositionLimits {
+    pub morning: HashMap<String, PositionLimit>,
+    pub evening: HashMap<String, PositionLimit>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PositionLimit {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+/// Most staff a job can require on one shift per day.
+pub const MAX_SHIFT_COVERAGE: usize = i16::MAX as usize;
+
+/// Staff needed on each shift every day, counting the whole group whatever their position.
+/// Set under `[shift_coverage]`; a schedule request can ask for its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ShiftCoverage {
+    pub morning: usize,
+    pub evening: usize,
+}
+
+impl ShiftCoverage {
+    /// The coverage stored with a job, `None` when it uses the configured one.
+    pub fn from_job(min_morning: Option<i16>, min_evening: Option<i16>) -> Option<Self> {
+        Some(Self {
+            morning: usize::try_from(min_morning?).ok()?,
+            evening: usize::try_from(min_evening?).ok()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.morning + self.evening == 0
+    }
+}
+
+/// What a job does when its roster can't meet the coverage minimums, `coverage_shortfall` in
+/// `scheduling.toml`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShortfallBehavior {
+    /// The job fails
+    #[default]
+    Fail,
+    /// Generate as close to the minimums as the roster allows and report the gaps
+    Relax,
+    /// Like `relax`, and leave a placeholder for every missing person
+    Unfilled,
+}
+
+/// Whether the group has any daily minimums to meet, group-wide or per position.
+pub fn has_coverage_minimums(config: &SchedulingConfig, staff_group_id: Uuid) -> bool {
+    !config.shift_coverage.is_empty()
+        || config
+            .position_coverage
+            .groups
+            .get(&staff_group_id)
+            .is_some_and(|limits| {
+                limits
+                    .morning
+                    .values()
+                    .chain(limits.evening.values())
+                    .any(|limit| limit.min > 0)
+            })
+}
+
+/// Each day and shift of `assignments` short of the group's minimums: the group-wide
+/// coverage first, then the positions in name order.
+pub fn coverage_shortfalls(
+    assignments: &[NewShiftAssignment],
+    roster: &[RosterMember],
+    config: &SchedulingConfig,
+    staff_group_id: Uuid,
+    unfilled: bool,
+) -> Vec<CoverageShortfall> {
+    let position_of: HashMap<Uuid, &str> = roster
+        .iter()
+        .map(|m| (m.staff_id, m.position.as_str()))
+        .collect();
+    let mut days: BTreeMap<NaiveDate, HashMap<Option<&str>, (usize, usize)>> = BTreeMap::new();
+    for a in assignments {
+        let day = days.entry(a.date).or_default();
+        for key in [None, position_of.get(&a.staff_id).copied()] {
+            let (morning, evening) = day.entry(key).or_default();
+            match a.shift_type {
+                ShiftType::Morning => *morning += 1,
+                ShiftType::Evening => *evening += 1,
+                ShiftType::Night | ShiftType::DayOff => {}
+            }
+        }
+    }
+
+    let mut minimums: Vec<(Option<&str>, ShiftType, usize)> = vec![
+        (None, ShiftType::Morning, config.shift_coverage.morning),
+        (None, ShiftType::Evening, config.shift_coverage.evening),
+    ];
+    if let Some(limits) = config.position_coverage.groups.get(&staff_group_id) {
+        let mut positions: Vec<(Option<&str>, ShiftType, usize)> = limits
+            .morning
+            .iter()
+            .map(|(p, l)| (Some(p.as_str()), ShiftType::Morning, l.min))
+            .chain(
+                limits
+                    .evening
+                    .iter()
+                    .map(|(p, l)| (Some(p.as_str()), ShiftType::Evening, l.min)),
+            )
+            .collect();
+        positions.sort_by_key(|(position, shift, _)| (*position, *shift == ShiftType::Evening));
+        minimums.extend(positions);
+    }
+
+    let mut shortfalls = Vec::new();
+    for (&date, counts) in &days {
+        for (position, shift_type, required) in &minimums {
+            let (morning, evening) = counts.get(position).copied().unwrap_or_default();
+            let assigned = match shift_type {
+                ShiftType::Morning => morning,
+                _ => evening,
+            };
+            if assigned < *required {
+                shortfalls.push(CoverageShortfall {
+                    date,
+                    shift_type: shift_type.clone(),
+                    position: position.map(str::to_string),
+                    required: *required as u32,
+                    assigned: assigned as u32,
+                    unfilled,
+                });
+            }
+        }
+    }
+    shortfalls
+}
+
+/// A placeholder for each person missing from the shortfalls left unfilled, by date, MORNING
+/// first. Whoever fills a position's slot also counts towards the group-wide coverage, so
+/// only the part of a group-wide gap beyond the position slots gets slots of its own.
+pub fn unfilled_shifts(shortfalls: &[CoverageShortfall]) -> Vec<NewUnfilledShift> {
+    let mut gaps: BTreeMap<(NaiveDate, bool), (ShiftType, usize, Vec<&str>)> = BTreeMap::new();
+    for s in shortfalls.iter().filter(|s| s.unfilled) {
+        let (_, group_gap, positions) = gaps
+            .entry((s.date, s.shift_type == ShiftType::Evening))
+            .or_insert_with(|| (s.shift_type.clone(), 0, Vec::new()));
+        let missing = s.required.saturating_sub(s.assigned) as usize;
+        match &s.position {
+            None => *group_gap = missing,
+            Some(position) => positions.extend(std::iter::repeat_n(position.as_str(), missing)),
+        }
+    }
+
+    gaps.into_iter()
+        .flat_map(|((date, _), (shift_type, group_gap, positions))| {
+            let generic = group_gap.saturating_sub(positions.len());
+            positions
+                .into_iter()
+                .map(Some)
+                .chain(std::iter::repeat_n(None, generic))
+                .map(move |position| NewUnfilledShift {
+                    date,
+                    shift_type: shift_type.clone(),
+                    position: position.map(str::to_string),
+                })
+        })
+        .collect()
+}
+
+/// One position's assignments so far on the day being generated.
+#[derive(Debug, Clone, Default)]
+pub struct PositionTally {
+    pub morning: usize,
+    pub evening: usize,
+    /// Staff in the position without a shift yet today, including the one being assigned
+    pub unassigned: usize,
+    /// Everyone in the position, assigned or not
+    pub staff: usize,
+    /// Days off the position's staff still need this week to reach `min_day_off_per_week`
+    pub day_off_debt: usize,
+    /// Unassigned staff, including the one being assigned, who still need one of those days off
+    pub unassigned_owing: usize,
+}
+
+pub struct PositionCoverageRule {
+    pub limits: ShiftPositionLimits,
+    pub min_day_off_per_week: u8,
+    /// Only aim for the minimums, see `ShortfallBehavior`; maximums still hold
+    pub relaxed: bool,
+}
+
+impl PositionCoverageRule {
+    fn lookup(&self, ctx: &AssignmentContext) -> (PositionLimit, PositionLimit, PositionTally) {
+        let limit = |limits: &HashMap<String, PositionLimit>| {
+            limits.get(ctx.position).copied().unwrap_or_default()
+        };
+        let tally = ctx
+            .position_tally
+            .get(ctx.position)
+            .cloned()
+            .unwrap_or_default();
+        (
+            limit(&self.limits.morning),
+            limit(&self.limits.evening),
+            tally,
+        )
+    }
+}
+
+impl SchedulingRule for PositionCoverageRule {
+    fn name(&self) -> &str {
+        "position_coverage"
+    }
+
+    fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
+        let (morning, evening, tally) = self.lookup(ctx);
+        keeps_minimums(
+            morning,
+            evening,
+            &tally,
+            ctx,
+            candidate,
+            self.min_day_off_per_week,
+            self.relaxed,
+        )
+    }
+
+    fn preferred_shift(&self, ctx: &AssignmentContext) -> Option<ShiftType> {
+        let (morning, evening, tally) = self.lookup(ctx);
+        missing_shift(morning, evening, &tally)
+    }
+}
+
+/// Daily headcount per shift across the group, on top of any position limits.
+pub struct ShiftCoverageRule {
+    pub coverage: ShiftCoverage,
+    pub min_day_off_per_week: u8,
+    /// Only aim for the minimums, see `ShortfallBehavior`
+    pub relaxed: bool,
+}
+
+impl ShiftCoverageRule {
+    fn limits(&self) -> (PositionLimit, PositionLimit) {
+        let min = |min| PositionLimit { min, max: None };
+        (min(self.coverage.morning), min(self.coverage.evening))
+    }
+}
+
+impl SchedulingRule for ShiftCoverageRule {
+    fn name(&self) -> &str {
+        "shift_coverage"
+    }
+
+    fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
+        let (morning, evening) = self.limits();
+        keeps_minimums(
+            morning,
+            evening,
+            ctx.day_tally,
+            ctx,
+            candidate,
+            self.min_day_off_per_week,
+            self.relaxed,
+        )
+    }
+
+    fn preferred_shift(&self, ctx: &AssignmentContext) -> Option<ShiftType> {
+        let (morning, evening) = self.limits();
+        missing_shift(morning, evening, ctx.day_tally)
+    }
+}
+
+/// Whether the staff counted in `tally` can still meet today's minimums, and the days off
+/// they owe this week, after the one being assigned takes `candidate`. `relaxed` only
+/// checks the maximums.
+fn keeps_minimums(
+    morning: PositionLimit,
+    evening: PositionLimit,
+    tally: &PositionTally,
+    ctx: &AssignmentContext,
+    candidate: &ShiftType,
+    min_day_off_per_week: u8,
+    relaxed: bool,
+) -> bool {
+    let full = |limit: PositionLimit, count: usize| limit.max.is_some_and(|max| count >= max);
+    match candidate {
+        ShiftType::Morning if full(morning, tally.morning) => return false,
+        ShiftType::Evening if full(evening, tally.evening) => return false,
+        _ if relaxed => return true,
+        _ => {}
+    }
+    let missing_morning = morning.min.saturating_sub(tally.morning);
+    let missing_evening = evening.min.saturating_sub(tally.evening);
+    let missing_after = match candidate {
+        ShiftType::Morning => missing_evening + missing_morning.saturating_sub(1),
+        ShiftType::Evening => missing_morning + missing_evening.saturating_sub(1),
+        ShiftType::Night | ShiftType::DayOff => missing_morning + missing_evening,
+    };
+
+    // Staff are assigned one at a time, so the people left after this one must still be
+    // enough for today's open minimums
+    let left_today = tally.unassigned.saturating_sub(1);
+    if missing_after > left_today {
+        return false;
+    }
+    if morning.min + evening.min == 0 {
+        return true;
+    }
+
+    // Every later day can only give as many days off as there are staff beyond the minimums,
+    // so the days off still owed this week have to fit in what's left. Without this everyone
+    // would reach their forced days off on the last day together.
+    let owes_day_off = ctx.day_offs_this_week < min_day_off_per_week;
+    let debt_after = if *candidate == ShiftType::DayOff && owes_day_off {
+        tally.day_off_debt.saturating_sub(1)
+    } else {
+        tally.day_off_debt
+    };
+    let owing_left_today = tally
+        .unassigned_owing
+        .saturating_sub(usize::from(owes_day_off));
+    let spare_per_day = tally.staff.saturating_sub(morning.min + evening.min);
+    let spare_today = (left_today - missing_after).min(owing_left_today);
+    debt_after <= spare_today + ctx.days_remaining_in_week as usize * spare_per_day
+}
+
+/// The shift furthest from its minimum today, evening on a tie.
+fn missing_shift(
+    morning: PositionLimit,
+    evening: PositionLimit,
+    tally: &PositionTally,
+) -> Option<ShiftType> {
+    let missing_morning = morning.min.saturating_sub(tally.morning);
+    let missing_evening = evening.min.saturating_sub(tally.evening);
+    match (missing_morning, missing_evening) {
+        (0, 0) => None,
+        (m, e) if e >= m => Some(ShiftType::Evening),
+        _ => Some(ShiftType::Morning),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn rule() -> PositionCoverageRule {
+        PositionCoverageRule {
+            limits: ShiftPositionLimits {
+                morning: HashMap::new(),
+                evening: HashMap::from([
+                    ("Doctor".to_string(), PositionLimit { min: 1, max: None }),
+                    (
+                        "Nurse".to_string(),
+                        PositionLimit {
+                            min: 2,
+                            max: Some(3),
+                        },
+                    ),
+                ]),
+            },
+            min_day_off_per_week: 1,
+            relaxed: false,
+        }
+    }
+
+    fn context<'a>(
+        position: &'a str,
+        position_tally: &'a HashMap<String, PositionTally>,
+        day_tally: &'a PositionTally,
+    ) -> AssignmentContext<'a> {
+        AssignmentContext {
+            date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            previous_shift: None,
+            days_off_in_a_row: 0,
+            day_offs_this_week: 0,
+            days_remaining_in_week: 6,
+            morning_count: 0,
+            evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position,
+            position_tally,
+            day_tally,
+        }
+    }
+
+    #[test]
+    fn limits_parse_per_group_and_shift() {
+        let config: PositionCoverageConfig = toml::from_str(
+            r#"
+            [groups."00000000-0000-0000-0000-000000000000".evening]
+            Doctor = { min = 1 }
+            Nurse = { min = 2, max = 4 }
+            "#,
+        )
+        .unwrap();
+
+        let limits = &config.groups[&Uuid::nil()];
+        assert!(limits.morning.is_empty());
+        assert_eq!(
+            limits.evening["Doctor"],
+            PositionLimit { min: 1, max: None }
+        );
+        assert_eq!(
+            limits.evening["Nurse"],
+            PositionLimit {
+                min: 2,
+                max: Some(4)
+            }
+        );
+    }
+
+    #[test]
+    fn last_staff_of_a_position_must_cover_open_minimum() {
+        let tally = HashMap::from([(
+            "Nurse".to_string(),
+            PositionTally {
+                morning: 1,
+                evening: 1,
+                unassigned: 1,
+                ..PositionTally::default()
+            },
+        )]);
+        let day = PositionTally::default();
+        let ctx = context("Nurse", &tally, &day);
+
+        assert!(rule().is_valid(&ctx, &ShiftType::Evening));
+        assert!(!rule().is_valid(&ctx, &ShiftType::Morning));
+        assert!(!rule().is_valid(&ctx, &ShiftType::DayOff));
+    }
+
+    #[test]
+    fn free_choice_while_enough_staff_remain() {
+        let tally = HashMap::from([(
+            "Doctor".to_string(),
+            PositionTally {
+                unassigned: 2,
+                ..PositionTally::default()
+            },
+        )]);
+        let day = PositionTally::default();
+        let ctx = context("Doctor", &tally, &day);
+
+        assert!(rule().is_valid(&ctx, &ShiftType::DayOff));
+        assert!(rule().is_valid(&ctx, &ShiftType::Morning));
+    }
+
+    #[test]
+    fn maximum_blocks_further_shifts_of_the_position() {
+        let tally = HashMap::from([(
+            "Nurse".to_string(),
+            PositionTally {
+                evening: 3,
+                unassigned: 4,
+                ..PositionTally::default()
+            },
+        )]);
+        let day = PositionTally::default();
+        let ctx = context("Nurse", &tally, &day);
+
+        assert!(!rule().is_valid(&ctx, &ShiftType::Evening));
+        assert!(rule().is_valid(&ctx, &ShiftType::Morning));
+        // Positions without limits are unaffected
+        assert!(rule().is_valid(&context("Cashier", &tally, &day), &ShiftType::Evening));
+    }
+
+    #[test]
+    fn shift_coverage_counts_the_whole_roster() {
+        let rule = ShiftCoverageRule {
+            coverage: ShiftCoverage {
+                morning: 2,
+                evening: 1,
+            },
+            min_day_off_per_week: 1,
+            relaxed: false,
+        };
+        let positions = HashMap::new();
+        // Two left, one MORNING and the EVENING still open
+        let day = PositionTally {
+            morning: 1,
+            unassigned: 2,
+            staff: 5,
+            ..PositionTally::default()
+        };
+        let ctx = context("Porter", &positions, &day);
+
+        assert!(rule.is_valid(&ctx, &ShiftType::Morning));
+        assert!(rule.is_valid(&ctx, &ShiftType::Evening));
+        assert!(!rule.is_valid(&ctx, &ShiftType::DayOff));
+        assert_eq!(rule.preferred_shift(&ctx), Some(ShiftType::Evening));
+
+        let covered = PositionTally {
+            morning: 2,
+            evening: 1,
+            unassigned: 2,
+            staff: 5,
+            ..PositionTally::default()
+        };
+        let ctx = context("Porter", &positions, &covered);
+        assert!(rule.is_valid(&ctx, &ShiftType::DayOff));
+        assert_eq!(rule.preferred_shift(&ctx), None);
+    }
+
+    #[test]
+    fn relaxed_rule_only_keeps_the_maximums() {
+        let relaxed = PositionCoverageRule {
+            relaxed: true,
+            ..rule()
+        };
+        let tally = HashMap::from([(
+            "Nurse".to_string(),
+            PositionTally {
+                evening: 3,
+                unassigned: 1,
+                ..PositionTally::default()
+            },
+        )]);
+        let day = PositionTally::default();
+        let ctx = context("Doctor", &tally, &day);
+        assert!(!rule().is_valid(&ctx, &ShiftType::DayOff));
+        assert!(relaxed.is_valid(&ctx, &ShiftType::DayOff));
+        assert_eq!(relaxed.preferred_shift(&ctx), Some(ShiftType::Evening));
+
+        let ctx = context("Nurse", &tally, &day);
+        assert!(!relaxed.is_valid(&ctx, &ShiftType::Evening));
+    }
+
+    #[test]
+    fn shortfalls_leave_one_placeholder_per_missing_person() {
+        let group = Uuid::from_u128(1);
+        let config = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 1,
+                evening: 3,
+            },
+            position_coverage: PositionCoverageConfig {
+                groups: HashMap::from([(group, rule().limits)]),
+            },
+            ..SchedulingConfig::default()
+        };
+        let roster = crate::testing::RosterBuilder::new()
+            .with_staff("Nurse", 1)
+            .with_staff("Porter", 1)
+            .build();
+        let day = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let assignments = [(0, ShiftType::Morning), (1, ShiftType::Evening)]
+            .into_iter()
+            .map(|(i, shift_type)| NewShiftAssignment {
+                staff_id: roster[i].staff_id,
+                date: day,
+                shift_type,
+            })
+            .collect::<Vec<_>>();
+
+        let shortfalls = coverage_shortfalls(&assignments, &roster, &config, group, true);
+        assert_eq!(
+            shortfalls
+                .iter()
+                .map(|s| (s.position.as_deref(), s.required, s.assigned))
+                .collect::<Vec<_>>(),
+            vec![(None, 3, 1), (Some("Doctor"), 1, 0), (Some("Nurse"), 2, 0)]
+        );
+
+        // The Doctor and both Nurses also make up the group-wide EVENING gap of two
+        let unfilled = unfilled_shifts(&shortfalls);
+        assert_eq!(
+            unfilled
+                .iter()
+                .map(|u| u.position.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("Doctor"), Some("Nurse"), Some("Nurse")]
+        );
+        assert!(unfilled.iter().all(|u| u.shift_type == ShiftType::Evening));
+
+        let reported = coverage_shortfalls(&assignments, &roster, &config, group, false);
+        assert!(unfilled_shifts(&reported).is_empty());
+    }
+
+    #[test]
+    fn shift_coverage_is_read_from_a_job() {
+        assert_eq!(
+            ShiftCoverage::from_job(Some(3), Some(2)),
+            Some(ShiftCoverage {
+                morning: 3,
+                evening: 2
+            })
+        );
+        assert_eq!(ShiftCoverage::from_job(None, None), None);
+        assert_eq!(ShiftCoverage::from_job(Some(-1), Some(2)), None);
+    }
+}
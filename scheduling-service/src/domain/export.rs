@@ -0,0 +1,456 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeDelta};
+use serde::Deserialize;
+use shared::types::{ScheduleResult, ShiftAssignment, ShiftType, UnfilledShift};
+use uuid::Uuid;
+
+use crate::domain::locale::Locale;
+
+// region: Shift times
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShiftWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl ShiftWindow {
+    /// Shift length; a window whose end is before its start runs past midnight.
+    pub fn duration(&self) -> TimeDelta {
+        let length = self.end - self.start;
+        if length < TimeDelta::zero() {
+            length + TimeDelta::days(1)
+        } else {
+            length
+        }
+    }
+}
+
+/// Wall-clock times of the working shifts, in the configured timezone.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ShiftTimes {
+    pub morning: ShiftWindow,
+    pub evening: ShiftWindow,
+    pub night: ShiftWindow,
+}
+
+impl Default for ShiftTimes {
+    fn default() -> Self {
+        let at = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        Self {
+            morning: ShiftWindow {
+                start: at(6),
+                end: at(14),
+            },
+            evening: ShiftWindow {
+                start: at(14),
+                end: at(22),
+            },
+            night: ShiftWindow {
+                start: at(22),
+                end: at(6),
+            },
+        }
+    }
+}
+
+impl ShiftTimes {
+    pub fn window(&self, shift_type: &ShiftType) -> Option<&ShiftWindow> {
+        match shift_type {
+            ShiftType::Morning => Some(&self.morning),
+            ShiftType::Evening => Some(&self.evening),
+            ShiftType::Night => Some(&self.night),
+            ShiftType::DayOff => None,
+        }
+    }
+}
+
+// endregion: Shift times
+
+// region: Payroll export
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayrollField {
+    ScheduleId,
+    StaffGroupId,
+    /// `UNFILLED` on placeholder rows
+    StaffId,
+    Date,
+    /// Localized day name, e.g. "Monday"
+    Weekday,
+    ShiftType,
+    /// Localized shift name, e.g. "Morning"
+    ShiftName,
+    StartTime,
+    EndTime,
+    Hours,
+    /// Free-text assignment note, empty when unset; the wanted position on placeholder rows
+    Note,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayrollColumn {
+    pub header: String,
+    pub field: PayrollField,
+}
+
+/// Column mapping for the payroll CSV, so each deployment can match its finance system.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PayrollExportConfig {
+    pub delimiter: char,
+    pub include_day_off: bool,
+    /// Append a row per unfilled shift placeholder, so payroll sees the gaps to staff.
+    pub include_unfilled: bool,
+    pub columns: Vec<PayrollColumn>,
+}
+
+impl Default for PayrollExportConfig {
+    fn default() -> Self {
+        let column = |header: &str, field| PayrollColumn {
+            header: header.to_string(),
+            field,
+        };
+        Self {
+            delimiter: ',',
+            include_day_off: false,
+            include_unfilled: false,
+            columns: vec![
+                column("staff_id", PayrollField::StaffId),
+                column("date", PayrollField::Date),
+                column("shift_type", PayrollField::ShiftType),
+                column("start_time", PayrollField::StartTime),
+                column("end_time", PayrollField::EndTime),
+                column("hours", PayrollField::Hours),
+            ],
+        }
+    }
+}
+
+/// Placeholder rows have no one to pay yet, so `StaffId` reads this instead.
+pub const UNFILLED_STAFF_ID: &str = "UNFILLED";
+
+/// What a CSV row is about: a staff member's shift, or a shift still waiting for one.
+enum PayrollRow<'a> {
+    Assignment(&'a ShiftAssignment),
+    Unfilled(&'a UnfilledShift),
+}
+
+impl PayrollRow<'_> {
+    fn staff_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Assignment(a) => Some(a.staff_id),
+            Self::Unfilled(_) => None,
+        }
+    }
+
+    fn date(&self) -> NaiveDate {
+        match self {
+            Self::Assignment(a) => a.date,
+            Self::Unfilled(u) => u.date,
+        }
+    }
+
+    fn shift_type(&self) -> &ShiftType {
+        match self {
+            Self::Assignment(a) => &a.shift_type,
+            Self::Unfilled(u) => &u.shift_type,
+        }
+    }
+
+    fn note(&self) -> Option<&str> {
+        match self {
+            Self::Assignment(a) => a.note.as_deref(),
+            Self::Unfilled(u) => u.position.as_deref(),
+        }
+    }
+}
+
+/// Render one CSV row per shift assignment (day offs skipped unless configured), then the
+/// unfilled placeholders if configured.
+pub fn render_payroll_csv(
+    result: &ScheduleResult,
+    shift_times: &ShiftTimes,
+    config: &PayrollExportConfig,
+    locale: Locale,
+) -> String {
+    let mut output = String::new();
+
+    let headers: Vec<&str> = config.columns.iter().map(|c| c.header.as_str()).collect();
+    push_row(&mut output, &headers, config.delimiter);
+
+    let unfilled: &[UnfilledShift] = if config.include_unfilled {
+        &result.unfilled
+    } else {
+        &[]
+    };
+    let rows = result
+        .assignments
+        .iter()
+        .map(PayrollRow::Assignment)
+        .chain(unfilled.iter().map(PayrollRow::Unfilled));
+
+    for row in rows {
+        let window = shift_times.window(row.shift_type());
+        if window.is_none() && !config.include_day_off {
+            continue;
+        }
+
+        let values: Vec<String> = config
+            .columns
+            .iter()
+            .map(|c| field_value(c.field, result, &row, window, locale))
+            .collect();
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        push_row(&mut output, &values, config.delimiter);
+    }
+
+    output
+}
+
+fn field_value(
+    field: PayrollField,
+    result: &ScheduleResult,
+    row: &PayrollRow,
+    window: Option<&ShiftWindow>,
+    locale: Locale,
+) -> String {
+    match field {
+        PayrollField::ScheduleId => result.schedule_id.to_string(),
+        PayrollField::StaffGroupId => result.staff_group_id.to_string(),
+        PayrollField::StaffId => row
+            .staff_id()
+            .map_or_else(|| UNFILLED_STAFF_ID.to_string(), |id| id.to_string()),
+        PayrollField::Date => format_date(row.date()),
+        PayrollField::Weekday => locale.weekday_name(row.date().weekday()).to_string(),
+        PayrollField::ShiftType => row.shift_type().as_str().to_string(),
+        PayrollField::ShiftName => locale.shift_name(row.shift_type()).to_string(),
+        PayrollField::StartTime => window
+            .map(|w| w.start.format("%H:%M").to_string())
+            .unwrap_or_default(),
+        PayrollField::EndTime => window
+            .map(|w| w.end.format("%H:%M").to_string())
+            .unwrap_or_default(),
+        PayrollField::Hours => {
+            let minutes = window.map_or(0, |w| w.duration().num_minutes());
+            format!("{:.2}", minutes as f64 / 60.0)
+        }
+        PayrollField::Note => row.note().map(neutralize_formula).unwrap_or_default(),
+    }
+}
+
+/// Spreadsheets run cells starting with these as formulas; notes are user input.
+fn neutralize_formula(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn push_row(output: &mut String, values: &[&str], delimiter: char) {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            output.push(delimiter);
+        }
+        if value.contains([delimiter, '"', '\n', '\r']) {
+            output.push('"');
+            output.push_str(&value.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(value);
+        }
+    }
+    output.push_str("\r\n");
+}
+
+// endregion: Payroll export
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::types::ScheduleMetrics;
+
+    fn make_result(shifts: &[ShiftType]) -> ScheduleResult {
+        let schedule_id = Uuid::new_v4();
+        let staff_id = Uuid::new_v4();
+        let begin = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        ScheduleResult {
+            schema_version: shared::types::RESULT_SCHEMA_VERSION,
+            schedule_id,
+            period_begin_date: begin,
+            staff_group_id: Uuid::new_v4(),
+            checksum: None,
+            assignments: shifts
+                .iter()
+                .enumerate()
+                .map(|(i, shift_type)| ShiftAssignment {
+                    id: Uuid::new_v4(),
+                    job_id: schedule_id,
+                    staff_id,
+                    date: begin + TimeDelta::days(i as i64),
+                    shift_type: shift_type.clone(),
+                    note: None,
+                })
+                .collect(),
+            notes: vec![],
+            metrics: ScheduleMetrics::default(),
+            periods: vec![],
+            borrowed_staff: vec![],
+            coverage_shortfalls: vec![],
+            unfilled: vec![],
+            assignment_groups: vec![],
+            staff: None,
+        }
+    }
+
+    #[test]
+    fn overnight_window_duration_wraps_midnight() {
+        let window = ShiftWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        };
+        assert_eq!(window.duration(), TimeDelta::hours(8));
+    }
+
+    #[test]
+    fn payroll_csv_skips_day_off_by_default() {
+        let result = make_result(&[ShiftType::Morning, ShiftType::DayOff, ShiftType::Evening]);
+        let csv = render_payroll_csv(
+            &result,
+            &ShiftTimes::default(),
+            &PayrollExportConfig::default(),
+            Locale::En,
+        );
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "staff_id,date,shift_type,start_time,end_time,hours"
+        );
+        let staff_id = result.assignments[0].staff_id;
+        assert_eq!(
+            lines[1],
+            format!("{staff_id},2026-02-16,MORNING,06:00,14:00,8.00")
+        );
+        assert_eq!(
+            lines[2],
+            format!("{staff_id},2026-02-18,EVENING,14:00,22:00,8.00")
+        );
+    }
+
+    #[test]
+    fn payroll_csv_uses_configured_columns_and_quotes_values() {
+        let result = make_result(&[ShiftType::DayOff]);
+        let config = PayrollExportConfig {
+            delimiter: ';',
+            include_day_off: true,
+            include_unfilled: false,
+            columns: vec![
+                PayrollColumn {
+                    header: "Work; Date".to_string(),
+                    field: PayrollField::Date,
+                },
+                PayrollColumn {
+                    header: "Hours".to_string(),
+                    field: PayrollField::Hours,
+                },
+            ],
+        };
+        let csv = render_payroll_csv(&result, &ShiftTimes::default(), &config, Locale::En);
+
+        assert_eq!(csv, "\"Work; Date\";Hours\r\n2026-02-16;0.00\r\n");
+    }
+
+    #[test]
+    fn payroll_csv_localizes_weekday_and_shift_name() {
+        let result = make_result(&[ShiftType::Morning]);
+        let config = PayrollExportConfig {
+            columns: vec![
+                PayrollColumn {
+                    header: "Thứ".to_string(),
+                    field: PayrollField::Weekday,
+                },
+                PayrollColumn {
+                    header: "Ca".to_string(),
+                    field: PayrollField::ShiftName,
+                },
+            ],
+            ..PayrollExportConfig::default()
+        };
+        let csv = render_payroll_csv(&result, &ShiftTimes::default(), &config, Locale::Vi);
+
+        assert_eq!(csv, "Thứ,Ca\r\nThứ Hai,Ca sáng\r\n");
+    }
+
+    #[test]
+    fn payroll_csv_includes_notes_without_formula_injection() {
+        let mut result = make_result(&[ShiftType::Morning, ShiftType::Evening]);
+        result.assignments[0].note = Some("training day, room 2".to_string());
+        result.assignments[1].note = Some("=HYPERLINK(\"x\")".to_string());
+        let config = PayrollExportConfig {
+            columns: vec![PayrollColumn {
+                header: "note".to_string(),
+                field: PayrollField::Note,
+            }],
+            ..PayrollExportConfig::default()
+        };
+        let csv = render_payroll_csv(&result, &ShiftTimes::default(), &config, Locale::En);
+
+        assert_eq!(
+            csv,
+            "note\r\n\"training day, room 2\"\r\n\"'=HYPERLINK(\"\"x\"\")\"\r\n"
+        );
+    }
+
+    #[test]
+    fn payroll_csv_appends_unfilled_placeholders_when_configured() {
+        let mut result = make_result(&[ShiftType::Morning]);
+        result.unfilled = vec![UnfilledShift {
+            id: Uuid::new_v4(),
+            job_id: result.schedule_id,
+            date: NaiveDate::from_ymd_opt(2026, 2, 17).unwrap(),
+            shift_type: ShiftType::Night,
+            position: Some("Nurse".to_string()),
+        }];
+        let config = PayrollExportConfig {
+            columns: vec![
+                PayrollColumn {
+                    header: "staff_id".to_string(),
+                    field: PayrollField::StaffId,
+                },
+                PayrollColumn {
+                    header: "date".to_string(),
+                    field: PayrollField::Date,
+                },
+                PayrollColumn {
+                    header: "hours".to_string(),
+                    field: PayrollField::Hours,
+                },
+                PayrollColumn {
+                    header: "note".to_string(),
+                    field: PayrollField::Note,
+                },
+            ],
+            ..PayrollExportConfig::default()
+        };
+
+        let csv = render_payroll_csv(&result, &ShiftTimes::default(), &config, Locale::En);
+        assert_eq!(csv.lines().count(), 2);
+
+        let config = PayrollExportConfig {
+            include_unfilled: true,
+            ..config
+        };
+        let csv = render_payroll_csv(&result, &ShiftTimes::default(), &config, Locale::En);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], "UNFILLED,2026-02-17,8.00,Nurse");
+    }
+}
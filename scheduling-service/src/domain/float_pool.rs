@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::types::{ShiftAssignment, ShiftType};
+use uuid::Uuid;
+
+use crate::domain::coverage::has_coverage_minimums;
+use crate::domain::job::NewShiftAssignment;
+use crate::domain::scheduler::{RosterMember, SchedulingConfig, SchedulingRule, gen_horizon};
+
+/// `[float_pool]` in `scheduling.toml`: a staff group whose members are lent to other groups
+/// when those can't meet their coverage minimums with their own staff.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FloatPoolConfig {
+    pub staff_group_id: Option<Uuid>,
+}
+
+/// The pool a group falls back on when its own staff can't be scheduled. Only groups with
+/// coverage minimums borrow, and the pool doesn't borrow from itself.
+pub fn borrowing_pool(config: &SchedulingConfig, staff_group_id: Uuid) -> Option<Uuid> {
+    let has_minimums = has_coverage_minimums(config, staff_group_id);
+    config
+        .float_pool
+        .staff_group_id
+        .filter(|&pool| pool != staff_group_id && has_minimums)
+}
+
+/// Pool members who could be lent to a group with `roster`, in the order to borrow them:
+/// those with the fewest days already worked elsewhere first. Members already in the group
+/// are left out, and days a member works in another schedule become days off here.
+pub fn lendable(
+    pool: Vec<RosterMember>,
+    roster: &[RosterMember],
+    worked_elsewhere: &HashMap<Uuid, Vec<ShiftAssignment>>,
+) -> Vec<RosterMember> {
+    let in_group: HashSet<Uuid> = roster.iter().map(|m| m.staff_id).collect();
+    let mut lendable: Vec<(usize, RosterMember)> = pool
+        .into_iter()
+        .filter(|m| !in_group.contains(&m.staff_id))
+        .map(|mut member| {
+            let busy: Vec<NaiveDate> = worked_elsewhere
+                .get(&member.staff_id)
+                .into_iter()
+                .flatten()
+                .filter(|a| a.shift_type != ShiftType::DayOff)
+                .map(|a| a.date)
+                .collect();
+            member.time_off.extend(&busy);
+            (busy.len(), member)
+        })
+        .collect();
+    lendable.sort_by_key(|(busy, m)| (*busy, m.staff_id));
+    lendable.into_iter().map(|(_, m)| m).collect()
+}
+
+/// Generate with `roster` plus as few of `pool` as it takes, adding them in order; `roster`
+/// alone is expected to have failed already. Returns the assignments and the borrowed staff,
+/// or `None` when even the whole pool isn't enough.
+pub fn gen_borrowing(
+    roster: &[RosterMember],
+    pool: &[RosterMember],
+    period_begin_date: NaiveDate,
+    periods: usize,
+    rules: &[Box<dyn SchedulingRule>],
+    config: &SchedulingConfig,
+) -> Option<(Vec<NewShiftAssignment>, Vec<Uuid>)> {
+    let mut candidates = roster.to_vec();
+    for member in pool {
+        candidates.push(member.clone());
+        match gen_horizon(&candidates, period_begin_date, periods, rules, config) {
+            Ok(assignments) => {
+                let borrowed = candidates[roster.len()..]
+                    .iter()
+                    .map(|m| m.staff_id)
+                    .collect();
+                return Some((assignments, borrowed));
+            }
+            Err(e) => tracing::debug!(
+                borrowed = candidates.len() - roster.len(),
+                error = %e,
+                "Still short with the borrowed staff"
+            ),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::coverage::ShiftCoverage;
+    use crate::testing::{RosterBuilder, SchedulingConfigBuilder};
+
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+    }
+
+    #[test]
+    fn borrows_only_as_many_as_coverage_needs() {
+        let config = SchedulingConfigBuilder::new()
+            .with_shift_coverage(2, 2)
+            .build()
+            .unwrap();
+        let rules = config.build_rules_for_group(Uuid::nil(), vec![]);
+        let staff = RosterBuilder::new()
+            .with_staff("Nurse", 3)
+            .with_staff("Float", 4)
+            .build();
+        let (roster, pool) = staff.split_at(3);
+        assert!(gen_horizon(roster, monday(), 1, &rules, &config).is_err());
+
+        let (assignments, borrowed) =
+            gen_borrowing(roster, pool, monday(), 1, &rules, &config).unwrap();
+
+        assert!(!borrowed.is_empty());
+        assert_eq!(
+            borrowed,
+            pool[..borrowed.len()]
+                .iter()
+                .map(|m| m.staff_id)
+                .collect::<Vec<_>>()
+        );
+        let one_fewer = [roster, &pool[..borrowed.len() - 1]].concat();
+        assert!(gen_horizon(&one_fewer, monday(), 1, &rules, &config).is_err());
+        assert!(assignments.iter().any(|a| a.staff_id == borrowed[0]));
+    }
+
+    #[test]
+    fn only_groups_with_coverage_minimums_borrow() {
+        let pool = Uuid::from_u128(7);
+        let mut config = SchedulingConfig {
+            float_pool: FloatPoolConfig {
+                staff_group_id: Some(pool),
+            },
+            ..SchedulingConfig::default()
+        };
+        assert_eq!(borrowing_pool(&config, Uuid::from_u128(1)), None);
+
+        config.shift_coverage = ShiftCoverage {
+            morning: 1,
+            evening: 1,
+        };
+        assert_eq!(borrowing_pool(&config, Uuid::from_u128(1)), Some(pool));
+        assert_eq!(borrowing_pool(&config, pool), None);
+    }
+
+    #[test]
+    fn pool_staff_busy_elsewhere_go_last_and_keep_those_days_free() {
+        let staff = RosterBuilder::new()
+            .with_staff("Nurse", 1)
+            .with_staff("Float", 2)
+            .build();
+        let (roster, pool) = staff.split_at(1);
+        let (busy, free) = (pool[0].staff_id, pool[1].staff_id);
+        let worked = ShiftAssignment {
+            id: Uuid::from_u128(99),
+            job_id: Uuid::from_u128(98),
+            staff_id: busy,
+            date: monday(),
+            shift_type: ShiftType::Morning,
+            note: None,
+        };
+        let worked_elsewhere = HashMap::from([(busy, vec![worked])]);
+
+        let mut in_pool = pool.to_vec();
+        in_pool.push(roster[0].clone());
+        let lendable = lendable(in_pool, roster, &worked_elsewhere);
+
+        assert_eq!(
+            lendable.iter().map(|m| m.staff_id).collect::<Vec<_>>(),
+            vec![free, busy]
+        );
+        assert!(lendable[1].time_off.contains(&monday()));
+        assert!(lendable[0].time_off.is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::coverage::{MAX_SHIFT_COVERAGE, ShiftCoverage};
+use crate::domain::scheduler::{DayOffPairing, SchedulingConfig, WeekAlignment};
+
+/// Rule settings a staff group uses instead of the ones in `scheduling.toml`, for wards that
+/// work differently from the rest. Omitted fields keep the configured value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupRuleOverrides {
+    pub min_day_off_per_week: Option<u8>,
+    pub max_day_off_per_week: Option<u8>,
+    pub no_morning_after_evening: Option<bool>,
+    pub night_shift: Option<bool>,
+    pub no_morning_after_night: Option<bool>,
+    pub max_daily_shift_diff: Option<u8>,
+    pub day_off_pairing: Option<DayOffPairing>,
+    pub week_alignment: Option<WeekAlignment>,
+    /// Staff needed on each shift every day; coverage given on a schedule request still wins
+    pub shift_coverage: Option<ShiftCoverage>,
+}
+
+impl GroupRuleOverrides {
+    /// `config` with the group's settings merged over it. Fails when the result is a rule set
+    /// no schedule could satisfy, which a later change to `scheduling.toml` can also cause.
+    pub fn apply(&self, config: &SchedulingConfig) -> Result<SchedulingConfig, String> {
+        if self
+            .shift_coverage
+            .is_some_and(|c| c.morning.max(c.evening) > MAX_SHIFT_COVERAGE)
+        {
+            return Err(format!(
+                "shift_coverage must be at most {MAX_SHIFT_COVERAGE} staff per shift"
+            ));
+        }
+
+        let config = SchedulingConfig {
+            min_day_off_per_week: self
+                .min_day_off_per_week
+                .unwrap_or(config.min_day_off_per_week),
+            max_day_off_per_week: self
+                .max_day_off_per_week
+                .unwrap_or(config.max_day_off_per_week),
+            no_morning_after_evening: self
+                .no_morning_after_evening
+                .unwrap_or(config.no_morning_after_evening),
+            night_shift: self.night_shift.unwrap_or(config.night_shift),
+            no_morning_after_night: self
+                .no_morning_after_night
+                .unwrap_or(config.no_morning_after_night),
+            max_daily_shift_diff: self
+                .max_daily_shift_diff
+                .unwrap_or(config.max_daily_shift_diff),
+            day_off_pairing: self.day_off_pairing.unwrap_or(config.day_off_pairing),
+            week_alignment: self.week_alignment.unwrap_or(config.week_alignment),
+            shift_coverage: self.shift_coverage.unwrap_or(config.shift_coverage),
+            ..config.clone()
+        };
+
+        config.validate_rules()?;
+        Ok(config)
+    }
+}
+
+/// The overrides stored for a group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GroupSchedulingConfig {
+    pub staff_group_id: Uuid,
+    pub overrides: GroupRuleOverrides,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_only_the_fields_they_set() {
+        let global = SchedulingConfig::default();
+        let overrides = GroupRuleOverrides {
+            max_day_off_per_week: Some(3),
+            night_shift: Some(true),
+            ..GroupRuleOverrides::default()
+        };
+
+        let merged = overrides.apply(&global).unwrap();
+        assert_eq!(merged.max_day_off_per_week, 3);
+        assert!(merged.night_shift);
+        assert_eq!(merged.min_day_off_per_week, global.min_day_off_per_week);
+        assert_eq!(merged.max_daily_shift_diff, global.max_daily_shift_diff);
+        assert_eq!(merged.timezone, global.timezone);
+    }
+
+    #[test]
+    fn overrides_are_checked_against_the_global_values_they_keep() {
+        let global = SchedulingConfig {
+            min_day_off_per_week: 2,
+            ..SchedulingConfig::default()
+        };
+        let overrides = GroupRuleOverrides {
+            max_day_off_per_week: Some(1),
+            ..GroupRuleOverrides::default()
+        };
+
+        assert!(overrides.apply(&global).is_err());
+        assert!(
+            overrides
+                .apply(&SchedulingConfig::default())
+                .is_ok_and(|merged| merged.max_day_off_per_week == 1)
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const DATA_SERVICE_DEPENDENCY: &str = "data-service";
+
+/// Which downstream endpoints are probed for readiness.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Health endpoint path on the data-service, appended to `DATA_SERVICE_URL`
+    pub data_service_path: String,
+    /// Further dependencies, probed alongside the data-service
+    pub dependencies: Vec<HealthDependency>,
+    /// Seconds between background checks, 0 disables them and `/ready` probes live
+    pub interval_secs: u64,
+    /// Each tick is randomly moved up to this many seconds either way
+    pub jitter_secs: u64,
+    /// Consecutive failed probes before a dependency counts as down
+    pub failure_threshold: u32,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            data_service_path: "/headpat".to_string(),
+            dependencies: Vec::new(),
+            interval_secs: 30,
+            jitter_secs: 5,
+            failure_threshold: 3,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// The data-service followed by the configured extra dependencies.
+    pub fn resolve(&self, data_service_url: &str) -> Vec<HealthDependency> {
+        let data_service = HealthDependency {
+            name: DATA_SERVICE_DEPENDENCY.to_string(),
+            url: format!(
+                "{}{}",
+                data_service_url.trim_end_matches('/'),
+                self.data_service_path
+            ),
+            required: true,
+        };
+
+        std::iter::once(data_service)
+            .chain(self.dependencies.iter().cloned())
+            .collect()
+    }
+
+    /// Delay before the next background check, `None` when they are disabled.
+    pub fn next_interval(&self) -> Option<Duration> {
+        if self.interval_secs == 0 {
+            return None;
+        }
+
+        let jitter_ms = self.jitter_secs.min(self.interval_secs) * 1000;
+        let base_ms = self.interval_secs * 1000;
+        let delay_ms = rand::random_range(base_ms - jitter_ms..=base_ms + jitter_ms);
+        Some(Duration::from_millis(delay_ms.max(1000)))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthDependency {
+    pub name: String,
+    /// Full URL of the health endpoint
+    pub url: String,
+    /// Optional dependencies are reported but don't affect readiness
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// Outcome of a single direct request to a dependency's health endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProbeReport {
+    pub name: String,
+    pub target: String,
+    pub required: bool,
+    pub healthy: bool,
+    /// HTTP status, absent when no response was received
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// Failed checks in a row, counted by the readiness checks only
+    pub consecutive_failures: u32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    /// False when any required dependency reached the failure threshold
+    pub ready: bool,
+    pub dependencies: Vec<ProbeReport>,
+}
+
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait HealthProber: Send + Sync {
+    /// Call the dependency's health endpoint once. Never fails: connection errors are
+    /// reported in the result.
+    async fn probe(&self, dependency: &HealthDependency) -> ProbeReport;
+}
+
+/// Tracks dependency health on its own failure counters. A single failed probe only marks a
+/// dependency down once `failure_threshold` checks in a row have failed, so one slow health
+/// response doesn't flip readiness.
+pub struct HealthChecker {
+    prober: Arc<dyn HealthProber>,
+    dependencies: Vec<HealthDependency>,
+    failure_threshold: u32,
+    failures: Mutex<HashMap<String, u32>>,
+    latest: Mutex<Option<ReadinessReport>>,
+}
+
+impl HealthChecker {
+    pub fn new(prober: Arc<dyn HealthProber>, dependencies: Vec<HealthDependency>) -> Self {
+        Self {
+            prober,
+            dependencies,
+            failure_threshold: 1,
+            failures: Mutex::new(HashMap::new()),
+            latest: Mutex::new(None),
+        }
+    }
+
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Re-check dependencies on the configured interval until the process exits.
+    pub fn spawn_monitor(self: &Arc<Self>, config: &HealthConfig) {
+        let checker = self.clone();
+        let config = config.clone();
+
+        tokio::spawn(async move {
+            while let Some(delay) = config.next_interval() {
+                let report = checker.check().await;
+                if !report.ready {
+                    tracing::warn!(?report, "Required dependency is down");
+                }
+                tokio::time::sleep(delay).await;
+            }
+        });
+    }
+
+    /// Probe one dependency by name, `None` if it isn't configured.
+    pub async fn probe(&self, name: &str) -> Option<ProbeReport> {
+        let dependency = self.dependencies.iter().find(|d| d.name == name)?;
+        Some(self.prober.probe(dependency).await)
+    }
+
+    /// Latest background check result, or a live check when the monitor hasn't run yet.
+    pub async fn readiness(&self) -> ReadinessReport {
+        let latest = self.latest.lock().unwrap().clone();
+        match latest {
+            Some(report) => report,
+            None => self.check().await,
+        }
+    }
+
+    /// Probe every dependency concurrently; each keeps its own status in the report.
+    pub async fn check(&self) -> ReadinessReport {
+        let handles: Vec<_> = self
+            .dependencies
+            .iter()
+            .cloned()
+            .map(|dependency| {
+                let prober = self.prober.clone();
+                tokio::spawn(async move { prober.probe(&dependency).await })
+            })
+            .collect();
+
+        let mut reports = Vec::with_capacity(handles.len());
+        for (handle, dependency) in handles.into_iter().zip(&self.dependencies) {
+            let report = handle.await.unwrap_or_else(|e| ProbeReport {
+                name: dependency.name.clone(),
+                target: dependency.url.clone(),
+                required: dependency.required,
+                healthy: false,
+                status: None,
+                latency_ms: 0,
+                error: Some(format!("probe task failed: {e}")),
+                consecutive_failures: 0,
+            });
+            reports.push(report);
+        }
+
+        {
+            let mut failures = self.failures.lock().unwrap();
+            for report in &mut reports {
+                let count = failures.entry(report.name.clone()).or_default();
+                *count = if report.healthy { 0 } else { *count + 1 };
+                report.consecutive_failures = *count;
+                shared::metrics::set_dependency_health_failing(
+                    &report.name,
+                    *count >= self.failure_threshold,
+                );
+            }
+        }
+
+        let report = ReadinessReport {
+            ready: reports
+                .iter()
+                .all(|r| !r.required || r.consecutive_failures < self.failure_threshold),
+            dependencies: reports,
+        };
+        *self.latest.lock().unwrap() = Some(report.clone());
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(name: &str, required: bool) -> HealthDependency {
+        HealthDependency {
+            name: name.to_string(),
+            url: format!("http://{name}/health"),
+            required,
+        }
+    }
+
+    fn checker_with_unhealthy(
+        unhealthy: &'static str,
+        deps: Vec<HealthDependency>,
+    ) -> HealthChecker {
+        let mut prober = MockHealthProber::new();
+        prober.expect_probe().returning(move |dep| ProbeReport {
+            name: dep.name.clone(),
+            target: dep.url.clone(),
+            required: dep.required,
+            healthy: dep.name != unhealthy,
+            status: Some(if dep.name == unhealthy { 503 } else { 200 }),
+            latency_ms: 1,
+            error: None,
+            consecutive_failures: 0,
+        });
+        HealthChecker::new(Arc::new(prober), deps)
+    }
+
+    #[test]
+    fn resolve_puts_data_service_first_with_configured_path() {
+        let config = HealthConfig {
+            data_service_path: "/healthz".to_string(),
+            dependencies: vec![dependency("auth-service", false)],
+            ..HealthConfig::default()
+        };
+
+        let deps = config.resolve("http://data-service:8080/");
+
+        assert_eq!(deps.len(), 2);
+        assert_eq!(deps[0].name, DATA_SERVICE_DEPENDENCY);
+        assert_eq!(deps[0].url, "http://data-service:8080/healthz");
+        assert!(deps[0].required);
+        assert_eq!(deps[1].name, "auth-service");
+    }
+
+    #[tokio::test]
+    async fn optional_dependency_failure_keeps_service_ready() {
+        let checker = checker_with_unhealthy(
+            "auth-service",
+            vec![
+                dependency("data-service", true),
+                dependency("auth-service", false),
+            ],
+        );
+
+        let report = checker.readiness().await;
+        assert!(report.ready);
+        assert!(report.dependencies[0].healthy);
+        assert!(!report.dependencies[1].healthy);
+
+        let checker = checker_with_unhealthy(
+            "data-service",
+            vec![
+                dependency("data-service", true),
+                dependency("auth-service", false),
+            ],
+        );
+        assert!(!checker.readiness().await.ready);
+    }
+
+    #[tokio::test]
+    async fn dependency_is_down_only_after_threshold_failures_in_a_row() {
+        let checker =
+            checker_with_unhealthy("data-service", vec![dependency("data-service", true)])
+                .with_failure_threshold(3);
+
+        assert!(checker.check().await.ready);
+        assert!(checker.check().await.ready);
+
+        let report = checker.check().await;
+        assert!(!report.ready);
+        assert_eq!(report.dependencies[0].consecutive_failures, 3);
+    }
+
+    #[test]
+    fn next_interval_stays_within_jitter() {
+        let config = HealthConfig {
+            interval_secs: 10,
+            jitter_secs: 2,
+            ..HealthConfig::default()
+        };
+
+        for _ in 0..100 {
+            let delay = config.next_interval().unwrap();
+            assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(12));
+        }
+
+        let disabled = HealthConfig {
+            interval_secs: 0,
+            ..HealthConfig::default()
+        };
+        assert_eq!(disabled.next_interval(), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use serde::Serialize;
+use shared::types::{ScheduleJob, ShiftType};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    domain::{checksum::assignments_checksum, job::NewShiftAssignment},
+    error::SchedulingServiceError,
+};
+
+/// Header line of a roster history CSV.
+pub const HISTORY_CSV_HEADER: &str = "staff_id,date,shift_type";
+
+/// Most rows accepted in one import, about two years of a 100-person ward.
+pub const MAX_HISTORY_ROWS: usize = 100_000;
+
+/// One Monday-to-Sunday period of an imported roster, stored as a completed job.
+pub struct ImportedPeriod {
+    pub period_begin_date: NaiveDate,
+    pub assignments: Vec<NewShiftAssignment>,
+    pub checksum: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistoryImport {
+    /// Rows read from the CSV
+    pub rows: usize,
+    /// One completed job per imported period, oldest first
+    pub jobs: Vec<ScheduleJob>,
+}
+
+/// Parse a `staff_id,date,shift_type` roster and split it into Monday-based periods.
+///
+/// Every row must be dated before `today`, and a staff member can appear only once per
+/// date. Problems are reported together, by line number, so a file can be fixed in one go.
+pub fn parse_history_csv(
+    csv: &str,
+    today: NaiveDate,
+) -> Result<(usize, Vec<ImportedPeriod>), SchedulingServiceError> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    match lines.next() {
+        Some((_, header)) if header.trim().eq_ignore_ascii_case(HISTORY_CSV_HEADER) => {}
+        _ => {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "CSV must start with the header '{HISTORY_CSV_HEADER}'"
+            )));
+        }
+    }
+
+    let mut periods: BTreeMap<NaiveDate, Vec<NewShiftAssignment>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut rows = 0;
+    for (index, line) in lines {
+        rows += 1;
+        if rows > MAX_HISTORY_ROWS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "At most {MAX_HISTORY_ROWS} rows can be imported at once"
+            )));
+        }
+        match parse_row(line, today) {
+            Ok(assignment) if !seen.insert((assignment.staff_id, assignment.date)) => {
+                errors.push(format!(
+                    "line {}: {} already has a shift on {}",
+                    index + 1,
+                    assignment.staff_id,
+                    assignment.date
+                ));
+            }
+            Ok(assignment) => {
+                let monday = assignment.date
+                    - TimeDelta::days(assignment.date.weekday().num_days_from_monday() as i64);
+                periods.entry(monday).or_default().push(assignment);
+            }
+            Err(e) => errors.push(format!("line {}: {e}", index + 1)),
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.truncate(20);
+        return Err(SchedulingServiceError::BadRequest(errors.join("; ")));
+    }
+    if periods.is_empty() {
+        return Err(SchedulingServiceError::BadRequest(
+            "CSV has no rows".to_string(),
+        ));
+    }
+
+    let periods = periods
+        .into_iter()
+        .map(|(period_begin_date, assignments)| ImportedPeriod {
+            period_begin_date,
+            checksum: assignments_checksum(
+                assignments
+                    .iter()
+                    .map(|a| (a.staff_id, a.date, &a.shift_type)),
+            ),
+            assignments,
+        })
+        .collect();
+    Ok((rows, periods))
+}
+
+fn parse_row(line: &str, today: NaiveDate) -> Result<NewShiftAssignment, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [staff_id, date, shift_type] = fields[..] else {
+        return Err(format!("expected 3 fields, found {}", fields.len()));
+    };
+
+    let staff_id =
+        Uuid::parse_str(staff_id).map_err(|_| format!("invalid staff_id '{staff_id}'"))?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{date}', expected YYYY-MM-DD"))?;
+    if date >= today {
+        return Err(format!("{date} is not in the past"));
+    }
+    let shift_type = match shift_type.to_ascii_uppercase().as_str() {
+        "MORNING" => ShiftType::Morning,
+        "EVENING" => ShiftType::Evening,
+        "NIGHT" => ShiftType::Night,
+        "DAY_OFF" => ShiftType::DayOff,
+        _ => {
+            return Err(format!(
+                "invalid shift_type '{shift_type}', expected MORNING, EVENING, NIGHT or DAY_OFF"
+            ));
+        }
+    };
+
+    Ok(NewShiftAssignment {
+        staff_id,
+        date,
+        shift_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+    }
+
+    #[test]
+    fn rows_are_grouped_into_monday_periods() {
+        let a = Uuid::new_v4();
+        let csv = format!(
+            "staff_id,date,shift_type\n\
+             {a},2026-02-15,MORNING\n\
+             {a},2026-02-16,evening\n\
+             \n\
+             {a},2026-02-22,DAY_OFF\n"
+        );
+
+        let (rows, periods) = parse_history_csv(&csv, today()).unwrap();
+
+        assert_eq!(rows, 3);
+        let mondays: Vec<_> = periods.iter().map(|p| p.period_begin_date).collect();
+        assert_eq!(
+            mondays,
+            [
+                NaiveDate::from_ymd_opt(2026, 2, 9).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            ]
+        );
+        assert_eq!(periods[1].assignments.len(), 2);
+        assert_eq!(periods[1].assignments[0].shift_type, ShiftType::Evening);
+    }
+
+    #[test]
+    fn problems_are_reported_by_line() {
+        let a = Uuid::new_v4();
+        let csv = format!(
+            "staff_id,date,shift_type\n\
+             {a},2026-02-16,MORNING\n\
+             {a},2026-02-16,EVENING\n\
+             {a},2026-03-02,MORNING\n\
+             nobody,2026-02-17,MORNING\n\
+             {a},2026-02-18,AFTERNOON\n"
+        );
+
+        let Err(SchedulingServiceError::BadRequest(message)) = parse_history_csv(&csv, today())
+        else {
+            panic!("expected a bad request");
+        };
+
+        assert!(message.contains("line 3: "), "{message}");
+        assert!(
+            message.contains("line 4: 2026-03-02 is not in the past"),
+            "{message}"
+        );
+        assert!(message.contains("line 5: invalid staff_id"), "{message}");
+        assert!(message.contains("line 6: invalid shift_type"), "{message}");
+    }
+
+    #[test]
+    fn header_is_required() {
+        assert!(parse_history_csv("a,b,c\n", today()).is_err());
+        assert!(parse_history_csv("staff_id,date,shift_type\n", today()).is_err());
+    }
+}
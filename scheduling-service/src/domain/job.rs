@@ -1,9 +1,30 @@
 use async_trait::async_trait;
-use chrono::NaiveDate;
-use shared::types::{JobStatus, ScheduleJob, ShiftAssignment, ShiftType};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use shared::{
+    pagination::{Page, PageRequest},
+    types::{
+        BorrowedStaff, CoverageShortfall, JobStatus, ScheduleJob, ScheduleNote, ShiftAssignment,
+        ShiftType, UnfilledShift,
+    },
+};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::error::SchedulingServiceError;
+use crate::{
+    domain::{
+        acknowledgment::{OutstandingAcknowledgments, StaffAcknowledgment},
+        approval::{ApprovalDecision, ScheduleApproval},
+        consistency::StaffReferences,
+        coverage::ShiftCoverage,
+        group_config::{GroupRuleOverrides, GroupSchedulingConfig},
+        history::ImportedPeriod,
+        simulate::RuleViolation,
+        stats::JobStatusCounts,
+        timeline::{JobEventKind, JobTimelineEvent},
+    },
+    error::SchedulingServiceError,
+};
 
 pub struct NewShiftAssignment {
     pub staff_id: Uuid,
@@ -11,6 +32,137 @@ pub struct NewShiftAssignment {
     pub shift_type: ShiftType,
 }
 
+/// A placeholder for a missing person, before it is saved. See `UnfilledShift`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewUnfilledShift {
+    pub date: NaiveDate,
+    pub shift_type: ShiftType,
+    pub position: Option<String>,
+}
+
+/// Per-staff totals over a group's recent completed schedules.
+pub struct StaffShiftHistory {
+    pub staff_id: Uuid,
+    pub weekend_days_off: i64,
+    pub evening_shifts: i64,
+}
+
+/// Longest assignment note accepted, in characters (matches the column width).
+pub const MAX_NOTE_CHARS: usize = 280;
+
+/// Manual change to one generated assignment. Omitted fields are left as they are.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAssignment {
+    pub shift_type: Option<ShiftType>,
+    /// Free-text note, an empty string clears it
+    pub note: Option<String>,
+}
+
+/// Most changes accepted in one bulk assignment update.
+pub const MAX_ASSIGNMENT_CHANGES: usize = 500;
+
+/// New shift for one assignment in a bulk update.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AssignmentChange {
+    pub assignment_id: Uuid,
+    pub shift_type: ShiftType,
+}
+
+/// Several assignment changes, saved together or not at all.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkAssignmentUpdate {
+    pub changes: Vec<AssignmentChange>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkAssignmentReport {
+    /// The changed assignments as saved, by date
+    pub assignments: Vec<ShiftAssignment>,
+    /// Rules the edited schedule breaks, checked against the group's rules
+    pub violations: Vec<RuleViolation>,
+    /// How many of `violations` the schedule didn't break before the changes
+    pub introduced_violations: usize,
+}
+
+/// Longest schedule note author name accepted, in characters (matches the column width).
+pub const MAX_AUTHOR_CHARS: usize = 100;
+
+/// Longest schedule note body accepted, in characters.
+pub const MAX_NOTE_BODY_CHARS: usize = 10_000;
+
+/// The author is the caller, taken from their token.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateScheduleNote {
+    /// Markdown
+    pub body: String,
+}
+
+/// How a submitted job should run, besides its group, period and dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobOptions {
+    pub sandbox: bool,
+    /// Consecutive periods to generate
+    pub periods: i16,
+    /// Instead of the configured `[shift_coverage]`
+    pub coverage: Option<ShiftCoverage>,
+    /// Where the outcome is POSTed once the job completes or fails
+    pub callback_url: Option<String>,
+    /// Submit even when the group already has a job for the period
+    pub force: bool,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            sandbox: false,
+            periods: 1,
+            coverage: None,
+            callback_url: None,
+            force: false,
+        }
+    }
+}
+
+/// Longest status override reason accepted, in characters.
+pub const MAX_OVERRIDE_REASON_CHARS: usize = 500;
+
+/// Break-glass change of a job's status, for when it got stuck outside the normal flow.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StatusOverride {
+    /// `PENDING` re-runs the job from scratch, `COMPLETED` needs a saved result
+    pub status: JobStatus,
+    /// Why the normal flow couldn't be used, kept in the job timeline
+    pub reason: String,
+}
+
+pub const DEFAULT_JOB_PAGE_SIZE: i64 = 50;
+pub const MAX_JOB_PAGE_SIZE: i64 = 500;
+
+/// Filters of the job listing; all given ones must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobQuery {
+    /// Only jobs in this status
+    #[param(value_type = Option<JobStatus>)]
+    pub status: Option<JobStatus>,
+    pub staff_group_id: Option<Uuid>,
+    /// Only periods starting on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only periods starting on or before this date
+    pub to: Option<NaiveDate>,
+    /// Include sandbox jobs, left out by default
+    #[serde(default)]
+    pub include_sandbox: bool,
+}
+
+/// Page size of the job listing: 50 unless asked otherwise, at most 500.
+pub fn job_page_limit(page: &PageRequest) -> i64 {
+    page.limit_or(DEFAULT_JOB_PAGE_SIZE)
+}
+
+/// One page of jobs, oldest first so paging is stable while new jobs come in.
+pub type JobPage = Page<ScheduleJob>;
+
 #[cfg_attr(feature = "test-support", mockall::automock)]
 #[async_trait]
 pub trait JobRepository: Send + Sync {
@@ -18,18 +170,60 @@ pub trait JobRepository: Send + Sync {
         &self,
         staff_group_id: Uuid,
         period_begin_date: NaiveDate,
+        depends_on: Vec<Uuid>,
+        options: JobOptions,
     ) -> Result<ScheduleJob, SchedulingServiceError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<ScheduleJob>, SchedulingServiceError>;
+    /// Newest non-failed, non-sandbox job of the group starting on `period_begin_date`.
+    async fn find_duplicate_job(
+        &self,
+        staff_group_id: Uuid,
+        period_begin_date: NaiveDate,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError>;
+    /// Set the status of a `Processing` job `worker_id` is running. A `Conflict` when recovery
+    /// has handed the job to another worker since.
     async fn update_status(
         &self,
         id: Uuid,
         status: JobStatus,
+        worker_id: Option<String>,
     ) -> Result<(), SchedulingServiceError>;
+    /// Set the job `Failed`, keeping `error_message` as the reason. Like `update_status`, only
+    /// while `worker_id` still runs the job.
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error_message: String,
+        worker_id: Option<String>,
+    ) -> Result<(), SchedulingServiceError>;
+    /// Persist the generated assignments together with their checksum, the float pool staff
+    /// they include and the coverage they fall short of, with a placeholder for every person
+    /// missing from the shortfalls left unfilled, atomically. Replaces the borrowed staff,
+    /// shortfalls and placeholders of an earlier run. Like `update_status`, only while
+    /// `worker_id` still runs the job.
     async fn save_assignments(
         &self,
         job_id: Uuid,
         assignments: Vec<NewShiftAssignment>,
+        checksum: String,
+        borrowed: Vec<BorrowedStaff>,
+        shortfalls: Vec<CoverageShortfall>,
+        worker_id: Option<String>,
     ) -> Result<(), SchedulingServiceError>;
+    async fn get_borrowed_staff(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<BorrowedStaff>, SchedulingServiceError>;
+    /// By date, the group-wide shortfalls before the positions', MORNING first.
+    async fn get_coverage_shortfalls(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<CoverageShortfall>, SchedulingServiceError>;
+    /// By date, MORNING first, the placeholders asking for a position first.
+    async fn get_unfilled_shifts(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<UnfilledShift>, SchedulingServiceError>;
     async fn get_assignments(
         &self,
         job_id: Uuid,
@@ -38,5 +232,247 @@ pub trait JobRepository: Send + Sync {
         &self,
         status: JobStatus,
     ) -> Result<Vec<ScheduleJob>, SchedulingServiceError>;
+    /// How many jobs are in each status; statuses without jobs are left out.
+    async fn count_by_status(&self) -> Result<Vec<(JobStatus, i64)>, SchedulingServiceError>;
+    /// Status counts and mean processing time of the jobs created since `since`.
+    async fn job_stats(
+        &self,
+        since: DateTime<Utc>,
+        staff_group_id: Option<Uuid>,
+        include_sandbox: bool,
+    ) -> Result<JobStatusCounts, SchedulingServiceError>;
+    /// Jobs matching `query`, ordered by creation time then id.
+    async fn find_paginated(
+        &self,
+        query: JobQuery,
+        page: PageRequest,
+    ) -> Result<JobPage, SchedulingServiceError>;
+    /// Set one assignment's shift and note, and recompute the job's checksum from what is
+    /// stored, in one transaction holding the job's lock. Returns `None` when the assignment
+    /// doesn't belong to the job.
+    async fn update_assignment(
+        &self,
+        job_id: Uuid,
+        assignment_id: Uuid,
+        shift_type: ShiftType,
+        note: Option<String>,
+    ) -> Result<Option<ShiftAssignment>, SchedulingServiceError>;
+    /// Set the shift of each `(assignment_id, shift_type)` and recompute the job's checksum
+    /// like `update_assignment`. Returns `None`, saving nothing, when any of the assignments
+    /// doesn't belong to the job.
+    async fn update_assignments(
+        &self,
+        job_id: Uuid,
+        changes: Vec<(Uuid, ShiftType)>,
+    ) -> Result<Option<Vec<ShiftAssignment>>, SchedulingServiceError>;
+    /// Non-failed, non-sandbox jobs of the group whose period starts within `from..=to`.
+    async fn find_overlapping_jobs(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError>;
+    /// `Pending` jobs with dependencies that have all completed, oldest first. With
+    /// `dependency` set, only the jobs waiting on that job.
+    async fn find_ready_dependents(
+        &self,
+        dependency: Option<Uuid>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError>;
+    /// Take a `Pending` job for starting, provided it is still at `seen_version` (its
+    /// `version` when read). Returns `None` when someone else claimed it first.
+    async fn claim_pending_job(
+        &self,
+        job_id: Uuid,
+        seen_version: i32,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError>;
+    /// Move a `Pending` job to `Processing` under `worker_id`, provided it is still at
+    /// `seen_version`, and start its heartbeat. Returns `false` when another worker started it
+    /// first, or it changed since it was read.
+    async fn start_pending_job(
+        &self,
+        job_id: Uuid,
+        seen_version: i32,
+        worker_id: Option<String>,
+    ) -> Result<bool, SchedulingServiceError>;
+    /// Tell other replicas `worker_id` is still running this `Processing` job. Does nothing
+    /// once the job was handed to another worker.
+    async fn heartbeat(
+        &self,
+        job_id: Uuid,
+        worker_id: Option<String>,
+    ) -> Result<(), SchedulingServiceError>;
+    /// Move the `Processing` jobs nobody runs anymore back to `Pending` and drop anything
+    /// they had saved, atomically: those whose heartbeat is older than `stale_before`, has
+    /// never been sent, or whose worker is `worker_id`. Only startup passes `worker_id`, to
+    /// take back what a previous run of this replica left behind.
+    async fn reset_abandoned_jobs(
+        &self,
+        stale_before: DateTime<Utc>,
+        worker_id: Option<String>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError>;
     async fn delete_assignments(&self, job_id: Uuid) -> Result<(), SchedulingServiceError>;
+    /// Move a `Failed` job back to `Pending` and drop anything it had saved, atomically.
+    /// Returns `None` when the job doesn't exist or isn't `Failed` (e.g. a concurrent retry won).
+    async fn reset_failed_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError>;
+    /// Move the job from `from` to `to`, dropping anything it had saved when `to` is
+    /// `Pending`, atomically. Returns `None` when the job doesn't exist or is no longer in
+    /// `from`.
+    async fn override_status(
+        &self,
+        job_id: Uuid,
+        from: JobStatus,
+        to: JobStatus,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError>;
+    async fn add_note(
+        &self,
+        job_id: Uuid,
+        author: String,
+        body: String,
+    ) -> Result<ScheduleNote, SchedulingServiceError>;
+    /// Notes of the job, oldest first.
+    async fn get_notes(&self, job_id: Uuid) -> Result<Vec<ScheduleNote>, SchedulingServiceError>;
+    /// Working-shift assignments of the group's published schedules dated within `from..=to`.
+    /// When a period was generated more than once only the latest published job counts.
+    /// Sandbox jobs are left out.
+    async fn get_group_assignments(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ShiftAssignment>, SchedulingServiceError>;
+    /// A staff member's assignments, days off included, dated within `from..=to` across the
+    /// completed schedules of every group, with the same latest-job and sandbox rules as
+    /// `get_group_assignments`. Drafts count too unless `published_only`.
+    async fn get_staff_assignments(
+        &self,
+        staff_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        published_only: bool,
+    ) -> Result<Vec<ShiftAssignment>, SchedulingServiceError>;
+    /// Delete sandbox jobs created before `cutoff`, with their assignments and notes, unless
+    /// they are still processing. Returns how many were deleted.
+    async fn purge_sandbox_jobs(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, SchedulingServiceError>;
+    /// Weekend days off and evening shifts per staff member over the group's last `periods`
+    /// completed schedules dated before `before`, one job per period like
+    /// `get_group_assignments`. Sandbox jobs are left out.
+    async fn get_shift_history(
+        &self,
+        staff_group_id: Uuid,
+        before: NaiveDate,
+        periods: i64,
+    ) -> Result<Vec<StaffShiftHistory>, SchedulingServiceError>;
+    /// Store each period as a `Completed` job with its assignments, all or nothing.
+    async fn import_completed_jobs(
+        &self,
+        staff_group_id: Uuid,
+        periods: Vec<ImportedPeriod>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError>;
+    /// Record an approver's decision on a job, replacing their earlier one.
+    async fn record_approval(
+        &self,
+        job_id: Uuid,
+        approver_id: Uuid,
+        decision: ApprovalDecision,
+        comment: Option<String>,
+        checksum: Option<String>,
+    ) -> Result<(), SchedulingServiceError>;
+    /// Latest decision per approver, newest first.
+    async fn get_approvals(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<ScheduleApproval>, SchedulingServiceError>;
+    async fn get_published_at(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, SchedulingServiceError>;
+    /// Mark the job published. Returns `None` when it already was.
+    async fn publish_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, SchedulingServiceError>;
+    /// Record that a staff member has seen the job's roster. Acknowledging again keeps the
+    /// first time, which is returned.
+    async fn record_acknowledgment(
+        &self,
+        job_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<DateTime<Utc>, SchedulingServiceError>;
+    /// Every staff member with an assignment in the job and when they acknowledged it, by id.
+    async fn get_acknowledgments(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<StaffAcknowledgment>, SchedulingServiceError>;
+    /// Published jobs of the group, or of every group, whose periods haven't ended and that
+    /// some staff haven't acknowledged, newest period first.
+    async fn find_outstanding_acknowledgments(
+        &self,
+        staff_group_id: Option<Uuid>,
+    ) -> Result<Vec<OutstandingAcknowledgments>, SchedulingServiceError>;
+    /// Note a reminder about the job's missing acknowledgments, unless one was noted since
+    /// `last_reminded_at`. Returns whether this call noted it, so only one replica sends it.
+    async fn mark_acknowledgment_reminder(
+        &self,
+        job_id: Uuid,
+        last_reminded_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, SchedulingServiceError>;
+    /// Append a step to the job's timeline.
+    async fn record_job_event(
+        &self,
+        job_id: Uuid,
+        kind: JobEventKind,
+        detail: Option<String>,
+        duration_ms: Option<i64>,
+    ) -> Result<(), SchedulingServiceError>;
+    /// The job's timeline, oldest first.
+    async fn get_job_events(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<JobTimelineEvent>, SchedulingServiceError>;
+    /// Ids of `Completed` jobs that still have per-day assignment rows, after `after` in id
+    /// order.
+    async fn find_unpacked_jobs(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, SchedulingServiceError>;
+    /// Replace the job's per-day assignment rows with packed ones, atomically. Returns how
+    /// many rows were replaced, or `None` when they can't be packed.
+    async fn pack_job(&self, job_id: Uuid) -> Result<Option<u64>, SchedulingServiceError>;
+    /// Every staff id in stored assignments, row or packed, with the jobs it appears in.
+    async fn find_staff_references(&self) -> Result<Vec<StaffReferences>, SchedulingServiceError>;
+    /// Rule overrides stored for the group, if any.
+    async fn get_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<Option<GroupSchedulingConfig>, SchedulingServiceError>;
+    /// Store the group's overrides, replacing any it had.
+    async fn upsert_group_config(
+        &self,
+        staff_group_id: Uuid,
+        overrides: GroupRuleOverrides,
+    ) -> Result<GroupSchedulingConfig, SchedulingServiceError>;
+    /// Returns whether the group had overrides.
+    async fn delete_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<bool, SchedulingServiceError>;
+    /// The URL given on submission to POST the job's outcome to, if any.
+    async fn get_callback_url(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<String>, SchedulingServiceError>;
+    /// Queue `payload` for delivery to `url`; the `CallbackDispatcher` sends it.
+    async fn enqueue_callback(
+        &self,
+        job_id: Uuid,
+        url: String,
+        payload: String,
+    ) -> Result<(), SchedulingServiceError>;
 }
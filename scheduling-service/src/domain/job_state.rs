@@ -2,6 +2,8 @@ use chrono::NaiveDate;
 use shared::types::{JobStatus, ScheduleJob};
 use uuid::Uuid;
 
+use crate::domain::coverage::ShiftCoverage;
+
 /// wrapper for a job in `Pending` status.
 /// consuming `start_processing` into to `ProcessingJob`.
 pub struct PendingJob {
@@ -65,6 +67,15 @@ impl ProcessingJob {
         self.inner.period_begin_date
     }
 
+    pub fn periods(&self) -> i16 {
+        self.inner.periods
+    }
+
+    /// Coverage the job asked for, `None` for the configured one.
+    pub fn shift_coverage(&self) -> Option<ShiftCoverage> {
+        ShiftCoverage::from_job(self.inner.min_morning, self.inner.min_evening)
+    }
+
     pub fn complete(mut self) -> (CompletedJob, Uuid, JobStatus) {
         let id = self.inner.id;
         self.inner.status = JobStatus::Completed;
@@ -101,8 +112,16 @@ mod tests {
             staff_group_id: Uuid::new_v4(),
             period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
             status,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 1,
         }
     }
 
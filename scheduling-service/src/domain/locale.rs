@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Weekday};
+use serde::Deserialize;
+use shared::types::ShiftType;
+use uuid::Uuid;
+
+/// Languages human-readable output (CSV, ICS) can be rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Vi,
+}
+
+impl Locale {
+    /// Match a BCP 47 tag on its primary language subtag (`vi-VN` -> `Vi`).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::En),
+            "vi" => Some(Self::Vi),
+            _ => None,
+        }
+    }
+
+    /// Pick the supported locale with the highest quality from an `Accept-Language` value.
+    /// Returns `None` for `*` or when nothing matches, so the caller's fallback applies.
+    pub fn negotiate(accept_language: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+
+        for item in accept_language.split(',') {
+            let mut parts = item.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+
+        best.map(|(locale, _)| locale)
+    }
+
+    pub fn shift_name(self, shift_type: &ShiftType) -> &'static str {
+        match (self, shift_type) {
+            (Self::En, ShiftType::Morning) => "Morning",
+            (Self::En, ShiftType::Evening) => "Evening",
+            (Self::En, ShiftType::Night) => "Night",
+            (Self::En, ShiftType::DayOff) => "Day off",
+            (Self::Vi, ShiftType::Morning) => "Ca sáng",
+            (Self::Vi, ShiftType::Evening) => "Ca tối",
+            (Self::Vi, ShiftType::Night) => "Ca đêm",
+            (Self::Vi, ShiftType::DayOff) => "Nghỉ",
+        }
+    }
+
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Self::En => [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ],
+            Self::Vi => [
+                "Thứ Hai",
+                "Thứ Ba",
+                "Thứ Tư",
+                "Thứ Năm",
+                "Thứ Sáu",
+                "Thứ Bảy",
+                "Chủ Nhật",
+            ],
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Title of a calendar event for one staff member's shift.
+    pub fn shift_event_title(self, who: &str, shift_type: &ShiftType) -> String {
+        match self {
+            Self::En => format!("{who} - {} shift", self.shift_name(shift_type)),
+            Self::Vi => format!("{who} - {}", self.shift_name(shift_type)),
+        }
+    }
+
+    pub fn group_calendar_name(self, staff_group_id: Uuid) -> String {
+        match self {
+            Self::En => format!("Group {staff_group_id} shifts"),
+            Self::Vi => format!("Lịch ca nhóm {staff_group_id}"),
+        }
+    }
+
+    pub fn schedule_calendar_name(self, period_begin_date: NaiveDate) -> String {
+        match self {
+            Self::En => format!("Shifts from {period_begin_date}"),
+            Self::Vi => format!("Lịch ca từ {period_begin_date}"),
+        }
+    }
+
+    pub fn staff_calendar_name(self, staff_name: &str, period_begin_date: NaiveDate) -> String {
+        match self {
+            Self::En => format!("{staff_name}'s shifts from {period_begin_date}"),
+            Self::Vi => format!("Lịch ca của {staff_name} từ {period_begin_date}"),
+        }
+    }
+}
+
+/// Default output language, optionally overridden per staff group.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalizationConfig {
+    pub default_locale: Locale,
+    pub groups: HashMap<Uuid, Locale>,
+}
+
+impl LocalizationConfig {
+    /// An explicitly requested locale wins over the group's, which wins over the default.
+    pub fn resolve(&self, requested: Option<Locale>, staff_group_id: Uuid) -> Locale {
+        requested
+            .or_else(|| self.groups.get(&staff_group_id).copied())
+            .unwrap_or(self.default_locale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_picks_highest_quality_supported_language() {
+        assert_eq!(
+            Locale::negotiate("fr-FR, vi;q=0.8, en;q=0.5"),
+            Some(Locale::Vi)
+        );
+        assert_eq!(Locale::negotiate("en-US,vi-VN;q=0.9"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("vi;q=0, en;q=0.1"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("de, *;q=0.5"), None);
+    }
+
+    #[test]
+    fn resolve_prefers_request_then_group_then_default() {
+        let group_id = Uuid::new_v4();
+        let config = LocalizationConfig {
+            default_locale: Locale::En,
+            groups: HashMap::from([(group_id, Locale::Vi)]),
+        };
+
+        assert_eq!(config.resolve(Some(Locale::En), group_id), Locale::En);
+        assert_eq!(config.resolve(None, group_id), Locale::Vi);
+        assert_eq!(config.resolve(None, Uuid::new_v4()), Locale::En);
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::error::SchedulingServiceError;
+
+/// Lock name for recovery of jobs left `Processing` by a replica that stopped.
+pub const RECOVER_STALE_JOBS: &str = "recover_stale_jobs";
+
+/// Lock name for the periodic purge of expired sandbox jobs.
+pub const PURGE_SANDBOX_JOBS: &str = "purge_sandbox_jobs";
+
+/// Held while a singleton task runs; dropping it releases the lock.
+pub trait TaskLockGuard: Send {}
+
+/// Cluster-wide mutual exclusion for tasks that every replica would otherwise run at once.
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait TaskLock: Send + Sync {
+    /// Take the named lock without waiting. `None` means another replica holds it.
+    async fn try_acquire(
+        &self,
+        name: &str,
+    ) -> Result<Option<Box<dyn TaskLockGuard>>, SchedulingServiceError>;
+
+    /// Like `try_acquire`, for a task that should run once per `interval` across all replicas
+    /// rather than once per replica. Also `None` when any replica started it less than
+    /// `interval` ago; taking the lock counts as a run.
+    async fn try_acquire_due(
+        &self,
+        name: &str,
+        interval: Duration,
+    ) -> Result<Option<Box<dyn TaskLockGuard>>, SchedulingServiceError>;
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::JobStatus;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// A job reaching a terminal state, or a reminder about its published roster.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobEvent {
+    pub job_id: Uuid,
+    pub staff_group_id: Uuid,
+    pub period_begin_date: NaiveDate,
+    pub status: JobStatus,
+    pub error: Option<String>,
+    /// Set on acknowledgment reminders: staff who haven't acknowledged the published roster
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unacknowledged: Option<Vec<Uuid>>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl JobEvent {
+    /// One-line human readable description, used by chat and email channels.
+    pub fn summary(&self) -> String {
+        let subject = format!(
+            "Schedule {} for group {} (period from {})",
+            self.job_id, self.staff_group_id, self.period_begin_date
+        );
+        if let Some(staff) = &self.unacknowledged {
+            return format!(
+                "{subject} is published but {} staff haven't acknowledged it",
+                staff.len()
+            );
+        }
+        match (&self.status, &self.error) {
+            (JobStatus::Completed, _) => format!("{subject} completed"),
+            (_, Some(error)) => format!("{subject} failed: {error}"),
+            (status, None) => format!("{subject} is {status:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct NotifyError(pub String);
+
+/// A delivery channel for job events. Implementations shouldn't retry: a failed
+/// notification is logged and dropped.
+#[cfg_attr(feature = "test-support", mockall::automock)]
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> &'static str;
+    async fn send(&self, event: &JobEvent) -> Result<(), NotifyError>;
+}
+
+/// Channels a deployment delivers job events to, in `[[notifications.channels]]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub channels: Vec<ChannelConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChannelConfig {
+    /// POSTs the event as JSON
+    Webhook { url: String },
+    /// Incoming webhook; the URL is a secret, so only the env var holding it is configured
+    Slack { webhook_url_env: String },
+    /// SMTP with STARTTLS; credentials come from `SMTP_USERNAME` / `SMTP_PASSWORD`
+    Email {
+        smtp_host: String,
+        #[serde(default)]
+        smtp_port: Option<u16>,
+        from: String,
+        to: Vec<String>,
+    },
+}
+
+/// Fan an event out to every configured channel concurrently and wait for all of them.
+pub async fn dispatch(notifiers: &[Arc<dyn Notifier>], event: JobEvent) {
+    let mut sends = JoinSet::new();
+    for notifier in notifiers {
+        let notifier = Arc::clone(notifier);
+        let event = event.clone();
+        sends.spawn(async move {
+            if let Err(e) = notifier.send(&event).await {
+                tracing::warn!(
+                    channel = notifier.channel(),
+                    job_id = %event.job_id,
+                    "Notification failed: {e}"
+                );
+            }
+        });
+    }
+    sends.join_all().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_selected_by_type() {
+        let config: NotificationConfig = toml::from_str(
+            r#"
+            channels = [
+                { type = "webhook", url = "https://example.com/hook" },
+                { type = "slack", webhook_url_env = "SLACK_WEBHOOK_URL" },
+                { type = "email", smtp_host = "smtp.example.com", from = "a@example.com", to = ["b@example.com"] },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(config.channels[0], ChannelConfig::Webhook { .. }));
+        assert!(matches!(config.channels[1], ChannelConfig::Slack { .. }));
+        assert!(matches!(
+            config.channels[2],
+            ChannelConfig::Email {
+                smtp_port: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn summary_includes_failure_reason() {
+        let event = JobEvent {
+            job_id: Uuid::nil(),
+            staff_group_id: Uuid::nil(),
+            period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            status: JobStatus::Failed,
+            error: Some("Data service unavailable".to_string()),
+            unacknowledged: None,
+            occurred_at: Utc::now(),
+        };
+
+        assert!(
+            event
+                .summary()
+                .ends_with("failed: Data service unavailable")
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use shared::types::ShiftType;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Jobs packed per backfill call when `limit` is omitted.
+pub const DEFAULT_PACK_LIMIT: i64 = 100;
+/// Most jobs packed per backfill call.
+pub const MAX_PACK_LIMIT: i64 = 1_000;
+
+/// Marks a day of the period the staff member has no assignment on.
+const NO_SHIFT: char = '-';
+
+/// How generated schedules are stored. Reads understand both layouts whatever is set here,
+/// so the mode can be switched at any time.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Store new schedules as one packed row per staff member instead of one row per staff
+    /// member and day
+    pub compact_assignments: bool,
+}
+
+/// One staff member's shifts in a job, a character per day from the period start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedShifts {
+    pub staff_id: Uuid,
+    /// `M`orning, `E`vening, `N`ight, `O`ff, or `-` for no assignment
+    pub shifts: String,
+    /// Note per day, parallel to `shifts`; `None` when no day has one
+    pub notes: Option<Vec<Option<String>>>,
+}
+
+fn shift_code(shift: &ShiftType) -> char {
+    match shift {
+        ShiftType::Morning => 'M',
+        ShiftType::Evening => 'E',
+        ShiftType::Night => 'N',
+        ShiftType::DayOff => 'O',
+    }
+}
+
+/// Pack a job's assignments per staff member, ordered by staff id. Returns `None` when they
+/// can't be packed: a date before the period start, or two assignments for the same staff
+/// member and date.
+pub fn pack_shifts(
+    period_begin_date: NaiveDate,
+    assignments: impl IntoIterator<Item = (Uuid, NaiveDate, ShiftType, Option<String>)>,
+) -> Option<Vec<PackedShifts>> {
+    let mut by_staff: BTreeMap<Uuid, Vec<(usize, ShiftType, Option<String>)>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for (staff_id, date, shift_type, note) in assignments {
+        let day = usize::try_from((date - period_begin_date).num_days()).ok()?;
+        if !seen.insert((staff_id, day)) {
+            return None;
+        }
+        by_staff
+            .entry(staff_id)
+            .or_default()
+            .push((day, shift_type, note));
+    }
+
+    let packed = by_staff
+        .into_iter()
+        .map(|(staff_id, days)| {
+            let len = days.iter().map(|(day, _, _)| day + 1).max().unwrap_or(0);
+            let mut shifts = vec![NO_SHIFT; len];
+            let mut notes = vec![None; len];
+            for (day, shift_type, note) in days {
+                shifts[day] = shift_code(&shift_type);
+                notes[day] = note;
+            }
+            PackedShifts {
+                staff_id,
+                shifts: shifts.into_iter().collect(),
+                notes: notes.iter().any(Option::is_some).then_some(notes),
+            }
+        })
+        .collect();
+    Some(packed)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PackAssignmentsQuery {
+    /// Resume after this job id, the `next_after` of the previous call
+    pub after: Option<Uuid>,
+    /// Jobs to look at, 100 by default and at most 1000
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct PackReport {
+    /// Completed jobs still stored as rows that were looked at
+    pub scanned: usize,
+    pub packed_jobs: usize,
+    /// Per-day rows replaced by packed ones
+    pub packed_rows: u64,
+    /// Jobs left as rows because their assignments can't be packed
+    pub skipped: Vec<Uuid>,
+    /// Pass as `after` to continue; absent once every job was looked at
+    pub next_after: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn packs_one_string_per_staff_member() {
+        let (alice, bob) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let packed = pack_shifts(
+            date(2),
+            vec![
+                (bob, date(3), ShiftType::Evening, None),
+                (alice, date(2), ShiftType::Morning, None),
+                (
+                    alice,
+                    date(4),
+                    ShiftType::DayOff,
+                    Some("training".to_string()),
+                ),
+                (bob, date(2), ShiftType::Morning, None),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            packed,
+            vec![
+                PackedShifts {
+                    staff_id: alice,
+                    shifts: "M-O".to_string(),
+                    notes: Some(vec![None, None, Some("training".to_string())]),
+                },
+                PackedShifts {
+                    staff_id: bob,
+                    shifts: "ME".to_string(),
+                    notes: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_what_a_string_cannot_hold() {
+        let staff = Uuid::from_u128(1);
+        let before_period = vec![(staff, date(1), ShiftType::Morning, None)];
+        assert_eq!(pack_shifts(date(2), before_period), None);
+
+        let same_day = vec![
+            (staff, date(2), ShiftType::Morning, None),
+            (staff, date(2), ShiftType::Evening, None),
+        ];
+        assert_eq!(pack_shifts(date(2), same_day), None);
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{NaiveDate, TimeDelta};
+use serde::Serialize;
+use shared::time::monday_on_or_after;
+use shared::types::ScheduleJob;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::scheduler::PERIOD_DAYS;
+
+/// Suggested start of a group's next schedule.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NextPeriod {
+    pub staff_group_id: Uuid,
+    pub period_begin_date: NaiveDate,
+    /// Last day of the period, inclusive
+    pub period_end_date: NaiveDate,
+    /// Timezone "today" was taken in
+    pub timezone: String,
+}
+
+/// Most periods one job can cover, a year's worth.
+pub const MAX_HORIZON_PERIODS: i16 = 13;
+
+/// Days covered by `periods` consecutive periods.
+pub fn horizon_days(periods: i16) -> i64 {
+    PERIOD_DAYS as i64 * i64::from(periods.max(1))
+}
+
+/// Last day covered by the job, inclusive.
+pub fn horizon_end(job: &ScheduleJob) -> NaiveDate {
+    job.period_begin_date + TimeDelta::days(horizon_days(job.periods) - 1)
+}
+
+/// Whether the job shares a day with `periods` periods starting at `begin`.
+pub fn overlaps(job: &ScheduleJob, begin: NaiveDate, periods: i16) -> bool {
+    job.period_begin_date < begin + TimeDelta::days(horizon_days(periods))
+        && horizon_end(job) >= begin
+}
+
+/// Earliest period start a job overlapping `begin` can have, for `find_overlapping_jobs`.
+pub fn earliest_overlapping_begin(begin: NaiveDate) -> NaiveDate {
+    begin - TimeDelta::days(horizon_days(MAX_HORIZON_PERIODS) - 1)
+}
+
+/// First Monday on or after `today` whose whole period is clear of `jobs`, so submitting it
+/// raises no overlap warning. `jobs` must be ordered by `period_begin_date`.
+pub fn next_unscheduled_monday(today: NaiveDate, jobs: &[ScheduleJob]) -> NaiveDate {
+    let mut candidate = monday_on_or_after(today);
+    for job in jobs {
+        if overlaps(job, candidate, 1) {
+            candidate = monday_on_or_after(horizon_end(job) + TimeDelta::days(1));
+        }
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use shared::types::JobStatus;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn job(period_begin_date: NaiveDate) -> ScheduleJob {
+        ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::nil(),
+            period_begin_date,
+            status: JobStatus::Completed,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn without_jobs_suggests_today_or_the_following_monday() {
+        // Monday
+        assert_eq!(next_unscheduled_monday(date(3, 2), &[]), date(3, 2));
+        // Sunday at the end of February rolls into March
+        assert_eq!(next_unscheduled_monday(date(3, 1), &[]), date(3, 2));
+    }
+
+    #[test]
+    fn skips_back_to_back_periods_and_partial_overlaps() {
+        let jobs = [job(date(2, 16)), job(date(3, 16)), job(date(4, 20))];
+
+        // 02-16 covers until 03-15, 03-16 until 04-12; 04-13 would overlap 04-20
+        assert_eq!(next_unscheduled_monday(date(2, 18), &jobs), date(5, 18));
+    }
+
+    #[test]
+    fn gap_of_a_full_period_is_used() {
+        let jobs = [job(date(2, 16)), job(date(4, 13))];
+
+        assert_eq!(next_unscheduled_monday(date(2, 18), &jobs), date(3, 16));
+    }
+
+    #[test]
+    fn multi_period_jobs_block_their_whole_horizon() {
+        let quarter = ScheduleJob {
+            periods: 3,
+            ..job(date(2, 16))
+        };
+        assert_eq!(horizon_end(&quarter), date(5, 10));
+        assert!(overlaps(&quarter, date(5, 4), 1));
+        assert!(!overlaps(&quarter, date(5, 11), 1));
+        assert!(overlaps(&job(date(5, 4)), date(2, 16), 3));
+        assert!(!overlaps(&job(date(5, 11)), date(2, 16), 3));
+
+        assert_eq!(
+            next_unscheduled_monday(date(2, 18), &[quarter]),
+            date(5, 11)
+        );
+    }
+}
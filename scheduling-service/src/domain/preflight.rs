@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use shared::types::ScheduleJob;
+use utoipa::ToSchema;
+
+use std::collections::HashMap;
+
+use crate::domain::coverage::{PositionLimit, ShiftPositionLimits};
+use crate::domain::scheduler::{DAYS_PER_WEEK, SchedulingConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WarningCode {
+    EmptyGroup,
+    InsufficientStaff,
+    InsufficientPositionStaff,
+    OverlappingSchedule,
+    PreflightUnavailable,
+}
+
+/// Something likely to make the submitted job fail or produce a poor schedule. The job is
+/// still accepted.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubmissionWarning {
+    pub code: WarningCode,
+    pub message: String,
+}
+
+impl SubmissionWarning {
+    fn new(code: WarningCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// What a submission gets when the group already has a non-failed job starting on the same
+/// date. A submission with `force` set always creates a job.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// 409 naming the existing job
+    #[default]
+    Reject,
+    /// The existing job, with 200 instead of 202, so retried submissions are harmless
+    ReturnExisting,
+}
+
+/// The accepted job plus any pre-flight warnings, returned with 202.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SubmittedJob {
+    #[serde(flatten)]
+    pub job: ScheduleJob,
+    pub warnings: Vec<SubmissionWarning>,
+    /// The group's earlier job for the period was returned and nothing was submitted
+    pub existing: bool,
+}
+
+/// Fewest active staff that can cover the daily `shift_coverage`, and at least one morning
+/// and one evening shift, every day while each person still gets the minimum weekly days off.
+pub fn min_staff_for_coverage(config: &SchedulingConfig) -> usize {
+    let coverage = config.shift_coverage;
+    let shifts_per_day = coverage.morning.max(1) + coverage.evening.max(1);
+    min_staff_for_shifts(config, shifts_per_day).max(shifts_per_day)
+}
+
+/// Fewest staff that can fill `shifts_per_day` shifts every day with the weekly days off.
+pub fn min_staff_for_shifts(config: &SchedulingConfig, shifts_per_day: usize) -> usize {
+    let working_days = DAYS_PER_WEEK.saturating_sub(config.min_day_off_per_week as usize);
+    if working_days == 0 {
+        return usize::MAX;
+    }
+    (shifts_per_day * DAYS_PER_WEEK).div_ceil(working_days)
+}
+
+/// Positions with fewer active staff than their morning plus evening minimums need, given
+/// the weekly days off. `counts` holds active staff per position.
+pub fn position_warnings(
+    config: &SchedulingConfig,
+    limits: &ShiftPositionLimits,
+    counts: &HashMap<String, i64>,
+) -> Vec<SubmissionWarning> {
+    let mut positions: Vec<&String> = limits.morning.keys().chain(limits.evening.keys()).collect();
+    positions.sort();
+    positions.dedup();
+
+    positions
+        .into_iter()
+        .filter_map(|position| {
+            let min =
+                |shift: &HashMap<String, PositionLimit>| shift.get(position).map_or(0, |l| l.min);
+            let per_day = min(&limits.morning) + min(&limits.evening);
+            if per_day == 0 {
+                return None;
+            }
+            let needed = min_staff_for_shifts(config, per_day);
+            let count = counts.get(position).copied().unwrap_or(0).max(0) as usize;
+            (count < needed).then(|| {
+                SubmissionWarning::new(
+                    WarningCode::InsufficientPositionStaff,
+                    format!(
+                        "Group has {count} active {position} staff, at least {needed} are needed \
+                         for the position minimums"
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// `active_staff` is `None` when the member count couldn't be fetched.
+pub fn preflight_warnings(
+    config: &SchedulingConfig,
+    active_staff: Option<usize>,
+    overlapping: &[ScheduleJob],
+) -> Vec<SubmissionWarning> {
+    let mut warnings = Vec::new();
+
+    match active_staff {
+        None => warnings.push(SubmissionWarning::new(
+            WarningCode::PreflightUnavailable,
+            "Could not check group membership, the data-service is unavailable",
+        )),
+        Some(0) => warnings.push(SubmissionWarning::new(
+            WarningCode::EmptyGroup,
+            "Group has no active staff, the schedule will be empty",
+        )),
+        Some(count) => {
+            let needed = min_staff_for_coverage(config);
+            if count < needed {
+                warnings.push(SubmissionWarning::new(
+                    WarningCode::InsufficientStaff,
+                    format!(
+                        "Group has {count} active staff, at least {needed} are needed to cover \
+                         the daily shift minimums"
+                    ),
+                ));
+            }
+        }
+    }
+
+    for job in overlapping {
+        warnings.push(SubmissionWarning::new(
+            WarningCode::OverlappingSchedule,
+            format!(
+                "Overlaps schedule {} starting {} ({:?})",
+                job.id, job.period_begin_date, job.status
+            ),
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::coverage::ShiftCoverage;
+    use chrono::{NaiveDate, Utc};
+    use shared::types::JobStatus;
+    use uuid::Uuid;
+
+    #[test]
+    fn coverage_needs_enough_staff_for_days_off() {
+        let with_min_off = |min_day_off_per_week| SchedulingConfig {
+            min_day_off_per_week,
+            ..SchedulingConfig::default()
+        };
+
+        assert_eq!(min_staff_for_coverage(&with_min_off(1)), 3);
+        assert_eq!(min_staff_for_coverage(&with_min_off(0)), 2);
+        assert_eq!(min_staff_for_coverage(&with_min_off(7)), usize::MAX);
+
+        let covered = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 3,
+                evening: 2,
+            },
+            ..with_min_off(2)
+        };
+        // 5 shifts a day over 5 working days each
+        assert_eq!(min_staff_for_coverage(&covered), 7);
+    }
+
+    #[test]
+    fn warnings_cover_staffing_and_overlaps() {
+        let config = SchedulingConfig::default();
+        let overlapping = ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            status: JobStatus::Completed,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        };
+
+        let codes = |warnings: Vec<SubmissionWarning>| -> Vec<WarningCode> {
+            warnings.into_iter().map(|w| w.code).collect()
+        };
+
+        assert!(preflight_warnings(&config, Some(10), &[]).is_empty());
+        assert_eq!(
+            codes(preflight_warnings(&config, Some(0), &[])),
+            [WarningCode::EmptyGroup]
+        );
+        assert_eq!(
+            codes(preflight_warnings(&config, Some(2), &[overlapping])),
+            [
+                WarningCode::InsufficientStaff,
+                WarningCode::OverlappingSchedule
+            ]
+        );
+        assert_eq!(
+            codes(preflight_warnings(&config, None, &[])),
+            [WarningCode::PreflightUnavailable]
+        );
+    }
+    #[test]
+    fn position_minimums_need_enough_staff_per_position() {
+        let config = SchedulingConfig::default();
+        let limit = |min| PositionLimit { min, max: None };
+        let limits = ShiftPositionLimits {
+            morning: HashMap::from([("Nurse".to_string(), limit(2))]),
+            evening: HashMap::from([
+                ("Nurse".to_string(), limit(1)),
+                ("Doctor".to_string(), limit(1)),
+            ]),
+        };
+
+        // 3 nurses a day over 6 working days a week: 21 shifts need 4 nurses
+        let counts = HashMap::from([("Nurse".to_string(), 4), ("Doctor".to_string(), 1)]);
+        let warnings = position_warnings(&config, &limits, &counts);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::InsufficientPositionStaff);
+        assert!(warnings[0].message.contains("1 active Doctor"));
+
+        let counts = HashMap::from([("Nurse".to_string(), 3), ("Doctor".to_string(), 2)]);
+        let warnings = position_warnings(&config, &limits, &counts);
+        assert!(warnings[0].message.contains("3 active Nurse"));
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use shared::types::ScheduleJob;
+use utoipa::ToSchema;
+
+use crate::domain::timeline::{JobEventKind, JobTimelineEvent};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueuedJob {
+    #[serde(flatten)]
+    pub job: ScheduleJob,
+    /// Time since submission
+    pub waiting_ms: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RunningJob {
+    #[serde(flatten)]
+    pub job: ScheduleJob,
+    /// Time since a worker last picked the job up
+    pub elapsed_ms: i64,
+    /// Replica that picked it up; missing for jobs picked up before workers recorded it
+    pub worker_id: Option<String>,
+}
+
+/// What the job queue holds right now. Failed jobs are not retried on their own, so nothing
+/// waits for a retry; they stay failed until `POST /api/v1/schedules/{id}/retry`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobQueue {
+    /// Oldest first, including jobs still waiting on their dependencies
+    pub pending: Vec<QueuedJob>,
+    /// Longest running first
+    pub processing: Vec<RunningJob>,
+}
+
+impl JobQueue {
+    /// `processing` pairs each job with its timeline, oldest event first. A job without a
+    /// pick-up event counts from its last update.
+    pub fn new(
+        pending: Vec<ScheduleJob>,
+        processing: Vec<(ScheduleJob, Vec<JobTimelineEvent>)>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let pending = pending
+            .into_iter()
+            .map(|job| QueuedJob {
+                waiting_ms: (now - job.created_at).num_milliseconds().max(0),
+                job,
+            })
+            .collect();
+        let mut processing: Vec<RunningJob> = processing
+            .into_iter()
+            .map(|(job, events)| {
+                let picked_up = events
+                    .into_iter()
+                    .rev()
+                    .find(|e| e.kind == JobEventKind::PickedUp);
+                let since = picked_up.as_ref().map_or(job.updated_at, |e| e.occurred_at);
+                RunningJob {
+                    elapsed_ms: (now - since).num_milliseconds().max(0),
+                    worker_id: picked_up.and_then(|e| e.detail),
+                    job,
+                }
+            })
+            .collect();
+        processing.sort_by_key(|r| std::cmp::Reverse(r.elapsed_ms));
+        Self {
+            pending,
+            processing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use shared::types::JobStatus;
+    use uuid::Uuid;
+
+    fn job(status: JobStatus, created_at: DateTime<Utc>) -> ScheduleJob {
+        ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            status,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at,
+            updated_at: created_at,
+            version: 1,
+        }
+    }
+
+    fn picked_up(detail: Option<&str>, occurred_at: DateTime<Utc>) -> JobTimelineEvent {
+        JobTimelineEvent {
+            kind: JobEventKind::PickedUp,
+            detail: detail.map(str::to_string),
+            duration_ms: None,
+            occurred_at,
+        }
+    }
+
+    #[test]
+    fn running_jobs_count_from_their_latest_pick_up() {
+        let now = Utc::now();
+        let minutes_ago = |m| now - TimeDelta::minutes(m);
+        let retried = job(JobStatus::Processing, minutes_ago(30));
+        let recent = job(JobStatus::Processing, minutes_ago(5));
+        let queue = JobQueue::new(
+            vec![job(JobStatus::Pending, minutes_ago(2))],
+            vec![
+                (recent, vec![]),
+                (
+                    retried,
+                    vec![
+                        picked_up(Some("worker-a"), minutes_ago(29)),
+                        picked_up(Some("worker-b"), minutes_ago(10)),
+                    ],
+                ),
+            ],
+            now,
+        );
+
+        assert_eq!(queue.pending[0].waiting_ms, 120_000);
+        let running: Vec<_> = queue
+            .processing
+            .iter()
+            .map(|r| (r.elapsed_ms, r.worker_id.as_deref()))
+            .collect();
+        assert_eq!(running, [(600_000, Some("worker-b")), (300_000, None)]);
+    }
+}
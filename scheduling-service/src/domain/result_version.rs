@@ -0,0 +1,125 @@
+use shared::types::RESULT_SCHEMA_VERSION;
+
+use crate::error::SchedulingServiceError;
+
+/// Vendor media type a client can put in `Accept` to pin the result layout, e.g.
+/// `application/vnd.shift-scheduler.result+json; version=1`.
+pub const RESULT_MEDIA_TYPE: &str = "application/vnd.shift-scheduler.result+json";
+
+/// Result layouts that can still be requested, oldest first. When the payload changes,
+/// `RESULT_SCHEMA_VERSION` moves on and the old number stays here, with the result handler
+/// converting to it, for as long as consumers may pin it.
+pub const SUPPORTED_RESULT_VERSIONS: &[u32] = &[RESULT_SCHEMA_VERSION];
+
+/// Layout requested with `?version=`, else through an `Accept` header, else the current one.
+/// An unknown `?version=` is a 400; an `Accept` header naming only unknown versions is a 406.
+pub fn negotiate(query: Option<u32>, accept: Option<&str>) -> Result<u32, SchedulingServiceError> {
+    if let Some(version) = query {
+        return if SUPPORTED_RESULT_VERSIONS.contains(&version) {
+            Ok(version)
+        } else {
+            Err(SchedulingServiceError::BadRequest(unsupported(version)))
+        };
+    }
+
+    let mut best: Option<(u32, f32)> = None;
+    let mut pinned = None;
+    let mut plain_json_ok = false;
+    for item in accept.into_iter().flat_map(|a| a.split(',')) {
+        let mut parts = item.split(';').map(str::trim);
+        let media_type = parts.next().unwrap_or_default();
+        let mut version = None;
+        let mut quality = 1.0;
+        for param in parts {
+            if let Some(v) = param.strip_prefix("version=") {
+                version = v.trim_matches('"').parse::<u32>().ok().or(Some(0));
+            } else if let Some(q) = param.strip_prefix("q=") {
+                quality = q.parse::<f32>().unwrap_or(1.0);
+            }
+        }
+        if quality <= 0.0 {
+            continue;
+        }
+
+        if media_type.eq_ignore_ascii_case(RESULT_MEDIA_TYPE) {
+            let version = version.unwrap_or(RESULT_SCHEMA_VERSION);
+            pinned.get_or_insert(version);
+            if SUPPORTED_RESULT_VERSIONS.contains(&version) && best.is_none_or(|(_, q)| quality > q)
+            {
+                best = Some((version, quality));
+            }
+        } else if ["application/json", "application/*", "*/*"]
+            .iter()
+            .any(|t| media_type.eq_ignore_ascii_case(t))
+        {
+            plain_json_ok = true;
+        }
+    }
+
+    match (best, pinned) {
+        (Some((version, _)), _) => Ok(version),
+        (None, Some(version)) if !plain_json_ok => {
+            Err(SchedulingServiceError::NotAcceptable(unsupported(version)))
+        }
+        _ => Ok(RESULT_SCHEMA_VERSION),
+    }
+}
+
+fn unsupported(version: u32) -> String {
+    let supported: Vec<String> = SUPPORTED_RESULT_VERSIONS
+        .iter()
+        .map(u32::to_string)
+        .collect();
+    format!(
+        "Result version {version} is not supported, expected one of: {}",
+        supported.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_wins_over_accept_and_defaults_to_current() {
+        let accept = Some("application/vnd.shift-scheduler.result+json; version=7");
+        assert_eq!(negotiate(Some(1), accept).unwrap(), 1);
+        assert_eq!(negotiate(None, None).unwrap(), RESULT_SCHEMA_VERSION);
+        assert_eq!(
+            negotiate(None, Some("application/json")).unwrap(),
+            RESULT_SCHEMA_VERSION
+        );
+        assert!(matches!(
+            negotiate(Some(99), None),
+            Err(SchedulingServiceError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn accept_header_pins_a_supported_version_or_is_not_acceptable() {
+        assert_eq!(
+            negotiate(
+                None,
+                Some("application/vnd.shift-scheduler.result+json;version=1")
+            )
+            .unwrap(),
+            1
+        );
+        assert!(matches!(
+            negotiate(
+                None,
+                Some("application/vnd.shift-scheduler.result+json; version=99")
+            ),
+            Err(SchedulingServiceError::NotAcceptable(_))
+        ));
+        // Plain JSON is an acceptable fallback when the pinned version is gone
+        assert_eq!(
+            negotiate(
+                None,
+                Some("application/vnd.shift-scheduler.result+json; version=99, application/json;q=0.5")
+            )
+            .unwrap(),
+            RESULT_SCHEMA_VERSION
+        );
+    }
+}
@@ -1,16 +1,38 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 
-use chrono::{Duration, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use chrono_tz::Tz;
-use serde::Deserialize;
-use shared::types::ShiftType;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use shared::types::{
+    GroupBlackout, GroupMember, ShiftEligibility, ShiftType, TimeOffRequest, TimeOffStatus,
+};
+use sqlx::Type;
 use thiserror::Error;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::domain::acknowledgment::AcknowledgmentConfig;
+use crate::domain::approval::ApprovalConfig;
+use crate::domain::blackout::BlackoutRule;
+use crate::domain::calendar::CalendarConfig;
+use crate::domain::coverage::{
+    PositionCoverageConfig, PositionCoverageRule, PositionTally, ShiftCoverage, ShiftCoverageRule,
+    ShortfallBehavior,
+};
+use crate::domain::export::{PayrollExportConfig, ShiftTimes};
+use crate::domain::float_pool::FloatPoolConfig;
+use crate::domain::health::HealthConfig;
 use crate::domain::job::NewShiftAssignment;
+use crate::domain::locale::LocalizationConfig;
+use crate::domain::notify::NotificationConfig;
+use crate::domain::packed::StorageConfig;
+use crate::domain::preflight::{DuplicatePolicy, min_staff_for_shifts};
+use crate::domain::targets::ShiftTargetConfig;
 
-const PERIOD_DAYS: usize = 28;
-const DAYS_PER_WEEK: usize = 7;
+pub const PERIOD_DAYS: usize = 28;
+pub const DAYS_PER_WEEK: usize = 7;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
@@ -19,7 +41,34 @@ pub struct SchedulingConfig {
     pub min_day_off_per_week: u8,
     pub max_day_off_per_week: u8,
     pub no_morning_after_evening: bool,
+    /// Schedule a third working shift, NIGHT, next to MORNING and EVENING
+    pub night_shift: bool,
+    /// Only applies with `night_shift` on
+    pub no_morning_after_night: bool,
     pub max_daily_shift_diff: u8,
+    pub day_off_pairing: DayOffPairing,
+    pub week_alignment: WeekAlignment,
+    pub shift_ordering: ShiftOrdering,
+    pub job_timeout_secs: u64,
+    /// Jobs one replica generates at once; the rest stay `Pending` until a slot frees up
+    pub max_concurrent_jobs: usize,
+    pub sandbox_retention_hours: u64,
+    pub duplicate_submissions: DuplicatePolicy,
+    pub fairness: FairnessConfig,
+    pub position_coverage: PositionCoverageConfig,
+    pub shift_coverage: ShiftCoverage,
+    pub coverage_shortfall: ShortfallBehavior,
+    pub shift_targets: ShiftTargetConfig,
+    pub shift_times: ShiftTimes,
+    pub payroll_export: PayrollExportConfig,
+    pub calendar: CalendarConfig,
+    pub localization: LocalizationConfig,
+    pub health: HealthConfig,
+    pub notifications: NotificationConfig,
+    pub approvals: ApprovalConfig,
+    pub acknowledgments: AcknowledgmentConfig,
+    pub storage: StorageConfig,
+    pub float_pool: FloatPoolConfig,
 }
 
 impl Default for SchedulingConfig {
@@ -29,7 +78,31 @@ impl Default for SchedulingConfig {
             min_day_off_per_week: 1,
             max_day_off_per_week: 2,
             no_morning_after_evening: true,
+            night_shift: false,
+            no_morning_after_night: true,
             max_daily_shift_diff: 1,
+            day_off_pairing: DayOffPairing::default(),
+            week_alignment: WeekAlignment::default(),
+            shift_ordering: ShiftOrdering::default(),
+            job_timeout_secs: 300,
+            max_concurrent_jobs: 4,
+            sandbox_retention_hours: 24,
+            duplicate_submissions: DuplicatePolicy::default(),
+            fairness: FairnessConfig::default(),
+            position_coverage: PositionCoverageConfig::default(),
+            shift_coverage: ShiftCoverage::default(),
+            coverage_shortfall: ShortfallBehavior::default(),
+            shift_targets: ShiftTargetConfig::default(),
+            shift_times: ShiftTimes::default(),
+            payroll_export: PayrollExportConfig::default(),
+            calendar: CalendarConfig::default(),
+            localization: LocalizationConfig::default(),
+            health: HealthConfig::default(),
+            notifications: NotificationConfig::default(),
+            approvals: ApprovalConfig::default(),
+            acknowledgments: AcknowledgmentConfig::default(),
+            storage: StorageConfig::default(),
+            float_pool: FloatPoolConfig::default(),
         }
     }
 }
@@ -55,27 +128,150 @@ impl SchedulingConfig {
             Tz::UTC
         })
     }
+
+    /// Day-off limits that no schedule could satisfy.
+    pub fn validate_rules(&self) -> Result<(), String> {
+        if self.max_day_off_per_week as usize > DAYS_PER_WEEK {
+            return Err(format!(
+                "max_day_off_per_week must be at most {DAYS_PER_WEEK}"
+            ));
+        }
+        if self.min_day_off_per_week > self.max_day_off_per_week {
+            return Err("min_day_off_per_week can't exceed max_day_off_per_week".to_string());
+        }
+        if self.day_off_pairing == DayOffPairing::Require && self.max_day_off_per_week < 2 {
+            return Err(
+                "day_off_pairing = \"require\" needs max_day_off_per_week of at least 2"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Everything `load` accepts but the service can't work with as intended, including a
+    /// timezone that `timezone()` would quietly replace with UTC.
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_rules()?;
+        if self.max_concurrent_jobs == 0 {
+            return Err("max_concurrent_jobs must be at least 1".to_string());
+        }
+        self.timezone
+            .parse::<Tz>()
+            .map_err(|_| format!("unknown timezone {:?}", self.timezone))?;
+        Ok(())
+    }
+
+    /// This config with the shift coverage a job asked for, if any, in place of `[shift_coverage]`.
+    pub fn with_shift_coverage(&self, coverage: Option<ShiftCoverage>) -> Self {
+        Self {
+            shift_coverage: coverage.unwrap_or(self.shift_coverage),
+            ..self.clone()
+        }
+    }
+
+    /// Processing budget for a single job, enforced by the watchdog in `SchedulingService`.
+    pub fn job_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.job_timeout_secs)
+    }
+
+    /// How long sandbox jobs are kept before the purge deletes them.
+    pub fn sandbox_retention(&self) -> chrono::TimeDelta {
+        chrono::TimeDelta::hours(self.sandbox_retention_hours.try_into().unwrap_or(i64::MAX))
+    }
+}
+
+/// What the weekly day-off rules count as a week.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "week_alignment", rename_all = "lowercase")]
+pub enum WeekAlignment {
+    /// Blocks of 7 days counted from the period start
+    #[default]
+    Period,
+    /// ISO calendar weeks (Monday to Sunday) of the assignment dates; a period that doesn't
+    /// start on a Monday then has a partial week at each end
+    Iso,
+}
+
+impl WeekAlignment {
+    /// Position of `date` within its week, 0 being the first day.
+    pub fn day_in_week(self, date: NaiveDate, period_begin_date: NaiveDate) -> usize {
+        match self {
+            Self::Period => (date - period_begin_date)
+                .num_days()
+                .rem_euclid(DAYS_PER_WEEK as i64) as usize,
+            Self::Iso => date.weekday().num_days_from_monday() as usize,
+        }
+    }
+
+    /// First day of the week `date` belongs to (which may be before the period start).
+    pub fn week_start(self, date: NaiveDate, period_begin_date: NaiveDate) -> NaiveDate {
+        date - Duration::days(self.day_in_week(date, period_begin_date) as i64)
+    }
+}
+
+/// Whether a staff member's days off come in runs of two or more instead of scattered single
+/// days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "day_off_pairing", rename_all = "lowercase")]
+pub enum DayOffPairing {
+    /// Days off fall wherever the other rules put them
+    #[default]
+    Off,
+    /// After a single day off, another one is tried first where the rules allow it
+    Prefer,
+    /// A single day off is always followed by a second one
+    Require,
 }
 
 #[derive(Debug, Error)]
 pub enum SchedulingError {
     #[error("No valid shift found for staff {staff_id} on day {day}")]
     NoValidShift { staff_id: Uuid, day: usize },
+    #[error(
+        "Covering {morning} MORNING and {evening} EVENING staff every day takes at least {needed} \
+         staff with {min_day_off_per_week} days off a week, the roster has {staff}"
+    )]
+    CoverageUnreachable {
+        morning: usize,
+        evening: usize,
+        min_day_off_per_week: u8,
+        needed: usize,
+        staff: usize,
+    },
 }
 
 // region: Trait-based scheduling rules
 
-pub struct AssignmentContext {
+pub struct AssignmentContext<'a> {
+    pub date: NaiveDate,
     pub previous_shift: Option<ShiftType>,
+    /// DAY_OFFs in a row up to yesterday; the generator reports any longer run as 2
+    pub days_off_in_a_row: u8,
     pub day_offs_this_week: u8,
     pub days_remaining_in_week: u8,
     pub morning_count: usize,
     pub evening_count: usize,
+    pub night_count: usize,
+    /// DAY_OFF assignments so far today
+    pub day_off_count: usize,
+    /// Position of the staff member being assigned
+    pub position: &'a str,
+    /// Today's assignments so far, per position
+    pub position_tally: &'a HashMap<String, PositionTally>,
+    /// Today's assignments so far across the whole roster
+    pub day_tally: &'a PositionTally,
 }
 
 pub trait SchedulingRule: Send + Sync {
     fn name(&self) -> &str;
     fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool;
+
+    /// Shift the generator should try first, for rules that need particular shifts filled.
+    fn preferred_shift(&self, _ctx: &AssignmentContext) -> Option<ShiftType> {
+        None
+    }
 }
 
 pub struct NoMorningAfterEveningRule;
@@ -93,6 +289,21 @@ impl SchedulingRule for NoMorningAfterEveningRule {
     }
 }
 
+pub struct NoMorningAfterNightRule;
+
+impl SchedulingRule for NoMorningAfterNightRule {
+    fn name(&self) -> &str {
+        "no_morning_after_night"
+    }
+
+    fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
+        !matches!(
+            (ctx.previous_shift.as_ref(), candidate),
+            (Some(ShiftType::Night), ShiftType::Morning)
+        )
+    }
+}
+
 pub struct MaxDayOffRule {
     pub max: u8,
 }
@@ -127,8 +338,37 @@ impl SchedulingRule for MinDayOffRule {
     }
 }
 
+/// No working shift right after a single day off, so days off come at least in pairs. A
+/// day off that would start a run is only allowed when the week has room for the second one,
+/// or it is the week's last day and the pair carries into the next week.
+pub struct DayOffPairingRule {
+    pub max_day_off_per_week: u8,
+}
+
+impl SchedulingRule for DayOffPairingRule {
+    fn name(&self) -> &str {
+        "day_off_pairing"
+    }
+
+    fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
+        if *candidate != ShiftType::DayOff {
+            return ctx.days_off_in_a_row != 1;
+        }
+        ctx.days_off_in_a_row > 0
+            || ctx.days_remaining_in_week == 0
+            || ctx.day_offs_this_week + 2 <= self.max_day_off_per_week
+    }
+
+    fn preferred_shift(&self, ctx: &AssignmentContext) -> Option<ShiftType> {
+        (ctx.days_off_in_a_row == 1).then_some(ShiftType::DayOff)
+    }
+}
+
+/// Keeps the daily headcounts of the working shifts within `max_diff` of each other.
 pub struct DailyBalanceRule {
     pub max_diff: u8,
+    /// Balance NIGHT against the other two as well
+    pub include_night: bool,
 }
 
 impl SchedulingRule for DailyBalanceRule {
@@ -137,12 +377,20 @@ impl SchedulingRule for DailyBalanceRule {
     }
 
     fn is_valid(&self, ctx: &AssignmentContext, candidate: &ShiftType) -> bool {
-        let (m, e) = match candidate {
-            ShiftType::Morning => (ctx.morning_count + 1, ctx.evening_count),
-            ShiftType::Evening => (ctx.morning_count, ctx.evening_count + 1),
+        let (mut m, mut e, mut n) = (ctx.morning_count, ctx.evening_count, ctx.night_count);
+        match candidate {
+            ShiftType::Morning => m += 1,
+            ShiftType::Evening => e += 1,
+            ShiftType::Night => n += 1,
             ShiftType::DayOff => return true,
+        }
+        let counts: &[usize] = if self.include_night {
+            &[m, e, n]
+        } else {
+            &[m, e]
         };
-        m.abs_diff(e) <= self.max_diff as usize
+        let spread = counts.iter().max().unwrap_or(&0) - counts.iter().min().unwrap_or(&0);
+        spread <= self.max_diff as usize
     }
 }
 
@@ -152,6 +400,9 @@ impl SchedulingConfig {
         if self.no_morning_after_evening {
             rules.push(Box::new(NoMorningAfterEveningRule));
         }
+        if self.night_shift && self.no_morning_after_night {
+            rules.push(Box::new(NoMorningAfterNightRule));
+        }
         rules.push(Box::new(MaxDayOffRule {
             max: self.max_day_off_per_week,
         }));
@@ -160,89 +411,809 @@ impl SchedulingConfig {
         }));
         rules.push(Box::new(DailyBalanceRule {
             max_diff: self.max_daily_shift_diff,
+            include_night: self.night_shift,
         }));
+        if self.day_off_pairing == DayOffPairing::Require {
+            rules.push(Box::new(DayOffPairingRule {
+                max_day_off_per_week: self.max_day_off_per_week,
+            }));
+        }
+        rules
+    }
+
+    /// `build_rules` plus the position limits configured for the group, if any, a cap on days
+    /// off for each of the group's blackout windows in the period, and the daily shift
+    /// coverage. Coverage comes last so position minimums get to pick their shift first.
+    pub fn build_rules_for_group(
+        &self,
+        staff_group_id: Uuid,
+        blackouts: Vec<GroupBlackout>,
+    ) -> Vec<Box<dyn SchedulingRule>> {
+        self.group_rules(staff_group_id, blackouts, false)
+    }
+
+    /// `build_rules_for_group` with coverage minimums the generator aims for but may fall
+    /// short of, for `coverage_shortfall = "relax"` or `"unfilled"`. Position maximums hold.
+    pub fn build_relaxed_rules_for_group(
+        &self,
+        staff_group_id: Uuid,
+        blackouts: Vec<GroupBlackout>,
+    ) -> Vec<Box<dyn SchedulingRule>> {
+        self.group_rules(staff_group_id, blackouts, true)
+    }
+
+    fn group_rules(
+        &self,
+        staff_group_id: Uuid,
+        blackouts: Vec<GroupBlackout>,
+        relaxed: bool,
+    ) -> Vec<Box<dyn SchedulingRule>> {
+        let mut rules = self.build_rules();
+        if let Some(limits) = self.position_coverage.groups.get(&staff_group_id) {
+            rules.push(Box::new(PositionCoverageRule {
+                limits: limits.clone(),
+                min_day_off_per_week: self.min_day_off_per_week,
+                relaxed,
+            }));
+        }
+        if !blackouts.is_empty() {
+            rules.push(Box::new(BlackoutRule {
+                windows: blackouts,
+                min_day_off_per_week: self.min_day_off_per_week,
+            }));
+        }
+        if !self.shift_coverage.is_empty() {
+            rules.push(Box::new(ShiftCoverageRule {
+                coverage: self.shift_coverage,
+                min_day_off_per_week: self.min_day_off_per_week,
+                relaxed,
+            }));
+        }
         rules
     }
 }
 
 // endregion: Trait-based scheduling rules
 
+// region: Shift option ordering
+
+/// Which working shift each staff member is offered first, day by day. Offering the morning
+/// first to everyone makes the roster order decide who ends up on evenings, which repeats in
+/// visible patterns; the other strategies break that up. Rules are checked the same either
+/// way, and a period an ordering can't fill is generated again with `fixed`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(tag = "strategy", rename_all = "lowercase")]
+pub enum ShiftOrdering {
+    /// Morning before evening every day
+    #[default]
+    Fixed,
+    /// Morning first on even days of the period, evening first on odd days
+    Alternating,
+    /// Morning or evening first at random per staff member and day, reproducible from `seed`
+    Seeded { seed: u64 },
+    /// Like `seeded`, with the morning offered first `morning_weight` (0 to 1) of the time
+    Weighted { seed: u64, morning_weight: f64 },
+}
+
+impl ShiftOrdering {
+    fn orderer(self, period_begin_date: NaiveDate) -> ShiftOrderer {
+        // Mixing in the period keeps consecutive periods from repeating the same draws
+        let seed = match self {
+            Self::Seeded { seed } | Self::Weighted { seed, .. } => {
+                seed ^ period_begin_date.num_days_from_ce() as u64
+            }
+            Self::Fixed | Self::Alternating => 0,
+        };
+        ShiftOrderer {
+            ordering: self,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+struct ShiftOrderer {
+    ordering: ShiftOrdering,
+    rng: StdRng,
+}
+
+impl ShiftOrderer {
+    /// Called once per staff member and day, in generation order, so a seed always gives
+    /// the same schedule for the same roster.
+    fn working_shifts(&mut self, day: usize) -> [ShiftType; 2] {
+        let morning_first = match self.ordering {
+            ShiftOrdering::Fixed => true,
+            ShiftOrdering::Alternating => day.is_multiple_of(2),
+            ShiftOrdering::Seeded { .. } => self.rng.random_bool(0.5),
+            ShiftOrdering::Weighted { morning_weight, .. } => {
+                let weight = if morning_weight.is_nan() {
+                    0.5
+                } else {
+                    morning_weight.clamp(0.0, 1.0)
+                };
+                self.rng.random_bool(weight)
+            }
+        };
+        if morning_first {
+            [ShiftType::Morning, ShiftType::Evening]
+        } else {
+            [ShiftType::Evening, ShiftType::Morning]
+        }
+    }
+}
+
+// endregion: Shift option ordering
+
+// region: Soft fairness preferences
+
+/// Preferences that only change the order shifts are tried in. Unlike the rules above they
+/// never make a schedule invalid, so they hold "where policy allows".
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FairnessConfig {
+    /// Share of the group offered a day off first on each Saturday and Sunday, 0 disables it
+    pub weekend_exemption_share: f64,
+    /// Completed schedules of the group whose weekend days off and evening shifts carry over
+    /// into the next one, 0 only counts the period being generated
+    pub history_periods: u32,
+}
+
+impl Default for FairnessConfig {
+    fn default() -> Self {
+        Self {
+            weekend_exemption_share: 0.25,
+            history_periods: 2,
+        }
+    }
+}
+
+impl FairnessConfig {
+    /// Staff offered the weekend day off first: the `weekend_exemption_share` of the roster
+    /// (rounded down) with the lowest `weekend_days_off / fairness_weight`, so a weight of 2
+    /// earns about twice the weekend days off of a weight of 1.
+    pub fn weekend_preferences(
+        &self,
+        roster: &[RosterMember],
+        weekend_days_off: &[u32],
+    ) -> Vec<bool> {
+        let slots = (roster.len() as f64 * self.weekend_exemption_share.clamp(0.0, 1.0)) as usize;
+        let score = |i: usize| weekend_days_off[i] as f64 / roster[i].fairness_weight;
+
+        let mut order: Vec<usize> = (0..roster.len()).collect();
+        order.sort_by(|&a, &b| {
+            score(a)
+                .total_cmp(&score(b))
+                .then(
+                    roster[b]
+                        .fairness_weight
+                        .total_cmp(&roster[a].fairness_weight),
+                )
+                .then(a.cmp(&b))
+        });
+
+        let mut preferred = vec![false; roster.len()];
+        for &i in order.iter().take(slots) {
+            preferred[i] = true;
+        }
+        preferred
+    }
+
+    /// Staff offered the evening shift ahead of the morning one: those with fewer evenings in
+    /// their history than the roster average. Without history nobody is.
+    pub fn evening_preferences(&self, roster: &[RosterMember]) -> Vec<bool> {
+        if roster.is_empty() {
+            return Vec::new();
+        }
+        let total: u32 = roster.iter().map(|m| m.history.evening_shifts).sum();
+        let average = total as f64 / roster.len() as f64;
+        roster
+            .iter()
+            .map(|m| (m.history.evening_shifts as f64) < average)
+            .collect()
+    }
+
+    /// Human readable form of the weighting, returned with schedule results.
+    pub fn describe(&self) -> String {
+        let history = if self.history_periods > 0 {
+            format!(
+                ". Weekend days off and evening shifts from the group's last {} completed \
+                 schedules count too, and staff with fewer past evenings than average are \
+                 offered the evening shift first",
+                self.history_periods
+            )
+        } else {
+            String::new()
+        };
+        if self.weekend_exemption_share <= 0.0 {
+            return format!(
+                "Weekend preferences are off: weekend days off follow the day-off rules only{history}"
+            );
+        }
+        format!(
+            "On each Saturday and Sunday, floor({share} x staff) members with the lowest \
+             weekend_days_off / fairness_weight are offered the day off first (ties go to the \
+             higher fairness_weight), as long as max_day_off_per_week allows it{history}",
+            share = self.weekend_exemption_share
+        )
+    }
+}
+
+/// Totals from a staff member's recent completed schedules in the group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShiftHistory {
+    pub weekend_days_off: u32,
+    pub evening_shifts: u32,
+}
+
+/// Attach `history` to the roster. Members it doesn't cover, e.g. new to the group, get the
+/// average of those it does, so they neither jump the queue nor start behind everyone else.
+pub fn apply_history(roster: &mut [RosterMember], history: &HashMap<Uuid, ShiftHistory>) {
+    let known: Vec<&ShiftHistory> = roster
+        .iter()
+        .filter_map(|m| history.get(&m.staff_id))
+        .collect();
+    if known.is_empty() {
+        return;
+    }
+    let average = |field: fn(&ShiftHistory) -> u32| {
+        let total: u32 = known.iter().map(|h| field(h)).sum();
+        (total as f64 / known.len() as f64).round() as u32
+    };
+    let fallback = ShiftHistory {
+        weekend_days_off: average(|h| h.weekend_days_off),
+        evening_shifts: average(|h| h.evening_shifts),
+    };
+
+    for member in roster {
+        member.history = history.get(&member.staff_id).copied().unwrap_or(fallback);
+    }
+}
+
+/// A staff member as seen by the generator.
+#[derive(Debug, Clone)]
+pub struct RosterMember {
+    pub staff_id: Uuid,
+    pub position: String,
+    /// Seniority / FTE weight, 1.0 is the baseline
+    pub fairness_weight: f64,
+    /// Working shifts the member can be given at all
+    pub shift_eligibility: ShiftEligibility,
+    pub history: ShiftHistory,
+    /// Shift worked the day before the period, so rest rules hold across the boundary
+    pub last_shift: Option<ShiftType>,
+    /// Approved time off, a day off on each of these dates whatever the rules say
+    pub time_off: HashSet<NaiveDate>,
+    /// First day in the group, for a membership added ahead of time; the member gets no
+    /// assignments before it
+    pub starts_on: Option<NaiveDate>,
+    /// Last working day; the member gets no assignments after it
+    pub effective_until: Option<NaiveDate>,
+}
+
+impl RosterMember {
+    /// Whether `date` is before the member joins or after they have left.
+    pub fn off_roster(&self, date: NaiveDate) -> bool {
+        self.starts_on.is_some_and(|first| date < first)
+            || self.effective_until.is_some_and(|last| date > last)
+    }
+}
+
+impl From<&GroupMember> for RosterMember {
+    fn from(member: &GroupMember) -> Self {
+        let staff = &member.staff;
+        Self {
+            staff_id: staff.id,
+            position: staff.position.clone(),
+            // Guard the division in `weekend_preferences` against bad upstream data
+            fairness_weight: if staff.fairness_weight > 0.0 {
+                staff.fairness_weight
+            } else {
+                1.0
+            },
+            shift_eligibility: staff.shift_eligibility,
+            history: ShiftHistory::default(),
+            last_shift: None,
+            time_off: HashSet::new(),
+            starts_on: member.valid_from,
+            effective_until: staff.effective_until,
+        }
+    }
+}
+
+/// Mark the approved days of `time_off` within `from..=to` on the members they belong to.
+pub fn apply_time_off(
+    roster: &mut [RosterMember],
+    time_off: &[TimeOffRequest],
+    from: NaiveDate,
+    to: NaiveDate,
+) {
+    let index: HashMap<Uuid, usize> = roster
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.staff_id, i))
+        .collect();
+    for request in time_off {
+        // The data-service only sends approved requests; re-check in case that changes
+        if request.status != TimeOffStatus::Approved {
+            continue;
+        }
+        if let Some(&i) = index.get(&request.staff_id) {
+            roster[i].time_off.extend(request.days_within(from, to));
+        }
+    }
+}
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+// endregion: Soft fairness preferences
+
 // region: Main algo
 
-#[tracing::instrument(skip(staff_ids, rules))]
+/// `rules` decide which shifts are valid; `config` supplies the week alignment, the
+/// fairness preferences and the shift ordering.
+#[tracing::instrument(skip(roster, rules, config))]
 pub fn gen_schedule(
-    staff_ids: &[Uuid],
+    roster: &[RosterMember],
     period_begin_date: NaiveDate,
     rules: &[Box<dyn SchedulingRule>],
+    config: &SchedulingConfig,
 ) -> Result<Vec<NewShiftAssignment>, SchedulingError> {
-    tracing::debug!(
-        staff_count = staff_ids.len(),
-        "Starting schedule generation"
-    );
+    tracing::debug!(staff_count = roster.len(), "Starting schedule generation");
+
+    let coverage = config.shift_coverage;
+    if !coverage.is_empty() {
+        let needed = min_staff_for_shifts(config, coverage.morning + coverage.evening);
+        if roster.len() < needed {
+            return Err(SchedulingError::CoverageUnreachable {
+                morning: coverage.morning,
+                evening: coverage.evening,
+                min_day_off_per_week: config.min_day_off_per_week,
+                needed,
+                staff: roster.len(),
+            });
+        }
+    }
 
-    let shift_options = [ShiftType::Morning, ShiftType::Evening, ShiftType::DayOff];
-    let mut assignments: Vec<NewShiftAssignment> = Vec::new();
+    let ordering = config.shift_ordering;
+    match gen_schedule_ordered(roster, period_begin_date, rules, config, ordering) {
+        // The greedy pass never backtracks, so a shuffled ordering can paint itself into a
+        // corner the fixed one gets through
+        Err(e) if ordering != ShiftOrdering::Fixed => {
+            tracing::warn!(?ordering, error = %e, "Shift ordering dead-ended, retrying with fixed");
+            gen_schedule_ordered(
+                roster,
+                period_begin_date,
+                rules,
+                config,
+                ShiftOrdering::Fixed,
+            )
+        }
+        result => result,
+    }
+}
+
+/// Generate `periods` consecutive periods from `period_begin_date` as one schedule. Each
+/// period carries on from the previous one: its last shifts feed the rest rules, and its
+/// weekend days off and evenings count towards fairness in the next.
+pub fn gen_horizon(
+    roster: &[RosterMember],
+    period_begin_date: NaiveDate,
+    periods: usize,
+    rules: &[Box<dyn SchedulingRule>],
+    config: &SchedulingConfig,
+) -> Result<Vec<NewShiftAssignment>, SchedulingError> {
+    let mut roster = roster.to_vec();
+    let index: HashMap<Uuid, usize> = roster
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.staff_id, i))
+        .collect();
+    let mut assignments = Vec::with_capacity(roster.len() * PERIOD_DAYS * periods);
+
+    for period in 0..periods {
+        let offset = period * PERIOD_DAYS;
+        let begin = period_begin_date + Duration::days(offset as i64);
+        let generated = gen_schedule(&roster, begin, rules, config).map_err(|e| match e {
+            SchedulingError::NoValidShift { staff_id, day } => SchedulingError::NoValidShift {
+                staff_id,
+                day: day + offset,
+            },
+            e => e,
+        })?;
+
+        // Generated day by day, so the last shift seen per member is the period's last
+        for a in &generated {
+            let member = &mut roster[index[&a.staff_id]];
+            match a.shift_type {
+                ShiftType::DayOff if is_weekend(a.date) => member.history.weekend_days_off += 1,
+                ShiftType::Evening => member.history.evening_shifts += 1,
+                _ => {}
+            }
+            member.last_shift = Some(a.shift_type.clone());
+        }
+        assignments.extend(generated);
+    }
+
+    Ok(assignments)
+}
+
+/// Pending staff whose `AssignmentContext` only differs in the shared daily counts: same
+/// position, previous shift (a lone day off apart from a longer run) and days off this week.
+/// Rules see the same context for all of them, so a preferred shift is worked out once per
+/// group rather than once per person.
+#[derive(Clone, Copy)]
+struct ContextGroup {
+    position: usize,
+    previous_shift: usize,
+    day_offs: u8,
+}
+
+impl ContextGroup {
+    /// No shift yet, MORNING, EVENING, NIGHT, then a single DAY_OFF and a run of them
+    const SHIFT_STATES: usize = 6;
+    const DAY_OFF_STATES: usize = DAYS_PER_WEEK + 1;
+
+    fn new(
+        position: usize,
+        previous_shift: &Option<ShiftType>,
+        days_off_in_a_row: u8,
+        day_offs: u8,
+    ) -> Self {
+        let previous_shift = match previous_shift {
+            None => 0,
+            Some(ShiftType::Morning) => 1,
+            Some(ShiftType::Evening) => 2,
+            Some(ShiftType::Night) => 3,
+            Some(ShiftType::DayOff) if days_off_in_a_row <= 1 => 4,
+            Some(ShiftType::DayOff) => 5,
+        };
+        Self {
+            position,
+            previous_shift,
+            day_offs,
+        }
+    }
+
+    fn count(positions: usize) -> usize {
+        positions * Self::SHIFT_STATES * Self::DAY_OFF_STATES
+    }
+
+    fn index(self) -> usize {
+        (self.position * Self::SHIFT_STATES + self.previous_shift) * Self::DAY_OFF_STATES
+            + self.day_offs as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        Self {
+            position: index / (Self::SHIFT_STATES * Self::DAY_OFF_STATES),
+            previous_shift: index / Self::DAY_OFF_STATES % Self::SHIFT_STATES,
+            day_offs: (index % Self::DAY_OFF_STATES) as u8,
+        }
+    }
+
+    fn previous_shift(self) -> Option<ShiftType> {
+        match self.previous_shift {
+            1 => Some(ShiftType::Morning),
+            2 => Some(ShiftType::Evening),
+            3 => Some(ShiftType::Night),
+            4 | 5 => Some(ShiftType::DayOff),
+            _ => None,
+        }
+    }
+
+    fn days_off_in_a_row(self) -> u8 {
+        match self.previous_shift {
+            4 => 1,
+            5 => 2,
+            _ => 0,
+        }
+    }
+}
+
+fn gen_schedule_ordered(
+    roster: &[RosterMember],
+    period_begin_date: NaiveDate,
+    rules: &[Box<dyn SchedulingRule>],
+    config: &SchedulingConfig,
+    ordering: ShiftOrdering,
+) -> Result<Vec<NewShiftAssignment>, SchedulingError> {
+    let mut orderer = ordering.orderer(period_begin_date);
+    let mut assignments: Vec<NewShiftAssignment> = Vec::with_capacity(roster.len() * PERIOD_DAYS);
 
     // per staff track both fields
-    let mut previous_shifts: Vec<Option<ShiftType>> = vec![None; staff_ids.len()];
-    let mut weekly_day_offs: Vec<u8> = vec![0; staff_ids.len()];
+    let mut previous_shifts: Vec<Option<ShiftType>> =
+        roster.iter().map(|m| m.last_shift.clone()).collect();
+    // A day off before the period counts as a finished pair, the days before it are unknown
+    let mut days_off_in_a_row: Vec<u8> = roster
+        .iter()
+        .map(|m| {
+            if m.last_shift == Some(ShiftType::DayOff) {
+                2
+            } else {
+                0
+            }
+        })
+        .collect();
+    let mut weekly_day_offs: Vec<u8> = vec![0; roster.len()];
+    let mut weekend_days_off: Vec<u32> =
+        roster.iter().map(|m| m.history.weekend_days_off).collect();
+    let prefers_evening = config.fairness.evening_preferences(roster);
+    let targets = config.shift_targets.for_roster(roster);
+    // Working shifts per staff member this period, (morning, evening), to steer towards targets
+    let mut worked: Vec<(u32, u32)> = vec![(0, 0); roster.len()];
+    let no_day_off_preference = vec![false; roster.len()];
+
+    // Positions are numbered once, and the tallies reset in place each day
+    let mut positions: Vec<&str> = Vec::new();
+    let position_of: Vec<usize> = roster
+        .iter()
+        .map(|member| {
+            positions
+                .iter()
+                .position(|p| *p == member.position)
+                .unwrap_or_else(|| {
+                    positions.push(&member.position);
+                    positions.len() - 1
+                })
+        })
+        .collect();
+    let mut position_tally: HashMap<String, PositionTally> = positions
+        .iter()
+        .map(|p| (p.to_string(), PositionTally::default()))
+        .collect();
+
+    let mut resting: Vec<usize> = Vec::with_capacity(roster.len());
+    let mut pending: VecDeque<usize> = VecDeque::with_capacity(roster.len());
+    let mut group_sizes: Vec<usize> = vec![0; ContextGroup::count(positions.len())];
+    let mut present_groups: Vec<usize> = Vec::new();
+    let mut group_preference: Vec<Option<bool>> = vec![None; group_sizes.len()];
 
     for day in 0..PERIOD_DAYS {
         let date = period_begin_date + Duration::days(day as i64);
-        let day_in_week = day % DAYS_PER_WEEK;
-        let days_remaining_in_week = (DAYS_PER_WEEK - 1 - day_in_week) as u8;
+        let day_in_week = config.week_alignment.day_in_week(date, period_begin_date);
+        // A partial week at the end of the period has to meet the minimum in fewer days
+        let days_remaining_in_week =
+            (DAYS_PER_WEEK - 1 - day_in_week).min(PERIOD_DAYS - 1 - day) as u8;
+        let weekend = is_weekend(date);
 
-        // Weekly counter reset on Monday
+        // Weekly counter reset at the start of each week
         if day_in_week == 0 {
             weekly_day_offs.fill(0);
         }
 
+        let weekend_preference;
+        let prefers_day_off = if weekend {
+            weekend_preference = config
+                .fairness
+                .weekend_preferences(roster, &weekend_days_off);
+            &weekend_preference
+        } else {
+            &no_day_off_preference
+        };
+
         // track daily shift count for balance constraint
         let mut morning_count: usize = 0;
         let mut evening_count: usize = 0;
+        let mut night_count: usize = 0;
+        let mut day_off_count: usize = 0;
+        position_tally
+            .values_mut()
+            .for_each(|tally| *tally = PositionTally::default());
+        let mut day_tally = PositionTally::default();
+        // Staff on time off, or who have left, aren't available today, so the coverage rules
+        // don't count them
+        let on_time_off =
+            |i: usize| roster[i].time_off.contains(&date) || roster[i].off_roster(date);
+        for (i, member) in roster.iter().enumerate() {
+            if on_time_off(i) {
+                continue;
+            }
+            let owed = config
+                .min_day_off_per_week
+                .saturating_sub(weekly_day_offs[i]);
+            for tally in [
+                position_tally.get_mut(member.position.as_str()).unwrap(),
+                &mut day_tally,
+            ] {
+                tally.staff += 1;
+                tally.unassigned += 1;
+                tally.day_off_debt += owed as usize;
+                tally.unassigned_owing += usize::from(owed > 0);
+            }
+        }
 
-        for (i, staff_id) in staff_ids.iter().enumerate() {
-            let mut assigned = false;
-
-            for shift in &shift_options {
-                let ctx = AssignmentContext {
-                    previous_shift: previous_shifts[i].clone(),
-                    day_offs_this_week: weekly_day_offs[i],
-                    days_remaining_in_week,
-                    morning_count,
-                    evening_count,
-                };
-
-                let ok = rules.iter().all(|rule| rule.is_valid(&ctx, shift));
+        // Staff on time off or forced to a day off by the weekly minimum go first, so the
+        // position counts others see only include staff who can still work. Nobody's need
+        // changes until they are assigned, so they can be picked out up front.
+        let must_rest = |i: usize| {
+            on_time_off(i)
+                || weekly_day_offs[i] + days_remaining_in_week < config.min_day_off_per_week
+        };
+        resting.clear();
+        pending.clear();
+        for i in 0..roster.len() {
+            if must_rest(i) {
+                resting.push(i);
+            } else {
+                let group = ContextGroup::new(
+                    position_of[i],
+                    &previous_shifts[i],
+                    days_off_in_a_row[i],
+                    weekly_day_offs[i],
+                );
+                if group_sizes[group.index()] == 0 {
+                    present_groups.push(group.index());
+                }
+                group_sizes[group.index()] += 1;
+                pending.push_back(i);
+            }
+        }
 
-                if ok {
-                    if *shift == ShiftType::DayOff {
-                        weekly_day_offs[i] += 1;
-                    }
-                    if *shift == ShiftType::Morning {
-                        morning_count += 1;
+        let mut resting_left = resting.iter().copied();
+        loop {
+            let context = |group: ContextGroup| AssignmentContext {
+                date,
+                previous_shift: group.previous_shift(),
+                days_off_in_a_row: group.days_off_in_a_row(),
+                day_offs_this_week: group.day_offs,
+                days_remaining_in_week,
+                morning_count,
+                evening_count,
+                night_count,
+                day_off_count,
+                position: positions[group.position],
+                position_tally: &position_tally,
+                day_tally: &day_tally,
+            };
+            let group_of = |i: usize| {
+                ContextGroup::new(
+                    position_of[i],
+                    &previous_shifts[i],
+                    days_off_in_a_row[i],
+                    weekly_day_offs[i],
+                )
+            };
+
+            let i = match resting_left.next() {
+                Some(i) => i,
+                None if pending.is_empty() => break,
+                None => {
+                    // Then those who can take a shift a rule wants filled, then staff without
+                    // such a shift, who may unblock the rest (e.g. a MORNING that lets the
+                    // balance rule allow another EVENING). Without position limits nobody has
+                    // a preferred shift and the order is the roster's.
+                    let (mut any_fillable, mut any_unpreferred) = (false, false);
+                    for &index in &present_groups {
+                        let ctx = context(ContextGroup::from_index(index));
+                        let preference = rules
+                            .iter()
+                            .find_map(|rule| rule.preferred_shift(&ctx))
+                            .map(|shift| rules.iter().all(|rule| rule.is_valid(&ctx, &shift)));
+                        any_fillable |= preference == Some(true);
+                        any_unpreferred |= preference.is_none();
+                        group_preference[index] = preference;
                     }
-                    if *shift == ShiftType::Evening {
-                        evening_count += 1;
+                    let wanted = if any_fillable {
+                        Some(Some(true))
+                    } else if any_unpreferred {
+                        Some(None)
+                    } else {
+                        None
+                    };
+                    let next = wanted
+                        .and_then(|wanted| {
+                            pending
+                                .iter()
+                                .position(|&i| group_preference[group_of(i).index()] == wanted)
+                        })
+                        .unwrap_or(0);
+                    let i = pending.remove(next).unwrap();
+
+                    let index = group_of(i).index();
+                    group_sizes[index] -= 1;
+                    if group_sizes[index] == 0 {
+                        present_groups.retain(|&g| g != index);
                     }
-
-                    previous_shifts[i] = Some(shift.clone());
-                    assignments.push(NewShiftAssignment {
-                        staff_id: *staff_id,
-                        date,
-                        shift_type: shift.clone(),
-                    });
-                    assigned = true;
-                    break;
+                    i
                 }
+            };
+            let member = &roster[i];
+            let ctx = context(group_of(i));
+            let ordered = orderer.working_shifts(day);
+            // A shift target replaces the ordering and the evening preference for its staff
+            let target = targets[i].and_then(|t| t.working_shifts(worked[i].0, worked[i].1));
+            let mut shift_options = if prefers_evening[i] && target.is_none() && !prefers_day_off[i]
+            {
+                vec![ShiftType::Evening, ShiftType::Morning]
+            } else {
+                target.clone().unwrap_or(ordered).to_vec()
+            };
+            // NIGHT is offered after the day's other working shifts; the balance rule makes
+            // sure it gets its share
+            if config.night_shift {
+                shift_options.push(ShiftType::Night);
+            }
+            if prefers_day_off[i] {
+                shift_options.insert(0, ShiftType::DayOff);
+            } else {
+                shift_options.push(ShiftType::DayOff);
+            }
+            if config.day_off_pairing == DayOffPairing::Prefer && days_off_in_a_row[i] == 1 {
+                shift_options.sort_by_key(|shift| *shift != ShiftType::DayOff);
+            }
+            // A shift a rule asks for goes first, ahead of the fairness order
+            if let Some(preferred) = rules.iter().find_map(|rule| rule.preferred_shift(&ctx)) {
+                shift_options.sort_by_key(|shift| *shift != preferred);
             }
 
-            if !assigned {
+            let shift = if on_time_off(i) {
+                Some(ShiftType::DayOff)
+            } else {
+                // Eligibility is a hard limit, whatever a rule or preference asks for
+                shift_options.into_iter().find(|shift| {
+                    member.shift_eligibility.allows(shift)
+                        && rules.iter().all(|rule| rule.is_valid(&ctx, shift))
+                })
+            };
+            let Some(shift) = shift else {
                 return Err(SchedulingError::NoValidShift {
-                    staff_id: *staff_id,
+                    staff_id: member.staff_id,
                     day,
                 });
+            };
+
+            let owes_day_off = weekly_day_offs[i] < config.min_day_off_per_week;
+            // Staff on time off were left out of today's tallies
+            if !on_time_off(i) {
+                for tally in [
+                    position_tally.get_mut(member.position.as_str()).unwrap(),
+                    &mut day_tally,
+                ] {
+                    tally.unassigned -= 1;
+                    tally.unassigned_owing -= usize::from(owes_day_off);
+                    match shift {
+                        ShiftType::DayOff if owes_day_off => tally.day_off_debt -= 1,
+                        ShiftType::DayOff => {}
+                        ShiftType::Morning => tally.morning += 1,
+                        ShiftType::Evening => tally.evening += 1,
+                        ShiftType::Night => {}
+                    }
+                }
             }
+            match shift {
+                ShiftType::DayOff => {
+                    weekly_day_offs[i] += 1;
+                    day_off_count += 1;
+                    if weekend {
+                        weekend_days_off[i] += 1;
+                    }
+                }
+                ShiftType::Morning => {
+                    morning_count += 1;
+                    worked[i].0 += 1;
+                }
+                ShiftType::Evening => {
+                    evening_count += 1;
+                    worked[i].1 += 1;
+                }
+                ShiftType::Night => night_count += 1,
+            }
+
+            days_off_in_a_row[i] = if shift == ShiftType::DayOff {
+                days_off_in_a_row[i].saturating_add(1)
+            } else {
+                0
+            };
+            previous_shifts[i] = Some(shift.clone());
+            if member.off_roster(date) {
+                continue;
+            }
+            assignments.push(NewShiftAssignment {
+                staff_id: member.staff_id,
+                date,
+                shift_type: shift,
+            });
         }
     }
 
@@ -259,6 +1230,7 @@ pub fn gen_schedule(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::targets::ShiftTarget;
 
     fn default_config() -> SchedulingConfig {
         SchedulingConfig::default()
@@ -268,17 +1240,32 @@ mod tests {
         NaiveDate::from_ymd_opt(2026, 2, 16).unwrap()
     }
 
+    #[test]
+    fn bundled_config_file_parses() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/scheduling.toml");
+        let config = SchedulingConfig::load(path).unwrap();
+        assert_eq!(config.timezone, "Asia/Ho_Chi_Minh");
+        assert_eq!(config.payroll_export.columns.len(), 6);
+    }
+
     // Rule tests
 
     #[test]
     fn no_morning_after_evening_rule_blocks() {
         let rule = NoMorningAfterEveningRule;
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: Some(ShiftType::Evening),
+            days_off_in_a_row: 0,
             day_offs_this_week: 0,
             days_remaining_in_week: 6,
             morning_count: 0,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(!rule.is_valid(&ctx, &ShiftType::Morning));
     }
@@ -287,11 +1274,18 @@ mod tests {
     fn no_morning_after_evening_rule_allows_evening_after_evening() {
         let rule = NoMorningAfterEveningRule;
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: Some(ShiftType::Evening),
+            days_off_in_a_row: 0,
             day_offs_this_week: 0,
             days_remaining_in_week: 6,
             morning_count: 0,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(rule.is_valid(&ctx, &ShiftType::Evening));
     }
@@ -300,11 +1294,18 @@ mod tests {
     fn max_day_off_rule_blocks_excess() {
         let rule = MaxDayOffRule { max: 2 };
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: None,
+            days_off_in_a_row: 0,
             day_offs_this_week: 2,
             days_remaining_in_week: 4,
             morning_count: 0,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(!rule.is_valid(&ctx, &ShiftType::DayOff));
     }
@@ -313,11 +1314,18 @@ mod tests {
     fn max_day_off_rule_allows_under_max() {
         let rule = MaxDayOffRule { max: 2 };
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: None,
+            days_off_in_a_row: 0,
             day_offs_this_week: 1,
             days_remaining_in_week: 4,
             morning_count: 0,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(rule.is_valid(&ctx, &ShiftType::DayOff));
     }
@@ -327,38 +1335,65 @@ mod tests {
         let rule = MinDayOffRule { min: 1 };
         // 0 day offs, 0 days remaining -> working shift must be rejected
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: None,
+            days_off_in_a_row: 0,
             day_offs_this_week: 0,
             days_remaining_in_week: 0,
             morning_count: 0,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(!rule.is_valid(&ctx, &ShiftType::Morning));
     }
 
     #[test]
     fn daily_balance_rule_rejects_imbalance() {
-        let rule = DailyBalanceRule { max_diff: 1 };
+        let rule = DailyBalanceRule {
+            max_diff: 1,
+            include_night: false,
+        };
         // 3 morning, 1 evening -> adding morning would make diff 3
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: None,
+            days_off_in_a_row: 0,
             day_offs_this_week: 0,
             days_remaining_in_week: 6,
             morning_count: 3,
             evening_count: 1,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(!rule.is_valid(&ctx, &ShiftType::Morning));
     }
 
     #[test]
     fn daily_balance_rule_day_off_always_passes() {
-        let rule = DailyBalanceRule { max_diff: 1 };
+        let rule = DailyBalanceRule {
+            max_diff: 1,
+            include_night: false,
+        };
         let ctx = AssignmentContext {
+            date: monday(),
             previous_shift: None,
+            days_off_in_a_row: 0,
             day_offs_this_week: 0,
             days_remaining_in_week: 6,
             morning_count: 10,
             evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
         };
         assert!(rule.is_valid(&ctx, &ShiftType::DayOff));
     }
@@ -380,8 +1415,99 @@ mod tests {
         assert!(!rules.iter().any(|r| r.name() == "no_morning_after_evening"));
     }
 
+    #[test]
+    fn daily_balance_rule_counts_night_when_enabled() {
+        let ctx = AssignmentContext {
+            date: monday(),
+            previous_shift: None,
+            days_off_in_a_row: 0,
+            day_offs_this_week: 0,
+            days_remaining_in_week: 6,
+            morning_count: 1,
+            evening_count: 1,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
+        };
+        let two_shifts = DailyBalanceRule {
+            max_diff: 1,
+            include_night: false,
+        };
+        let three_shifts = DailyBalanceRule {
+            max_diff: 1,
+            include_night: true,
+        };
+        assert!(two_shifts.is_valid(&ctx, &ShiftType::Morning));
+        assert!(!three_shifts.is_valid(&ctx, &ShiftType::Morning));
+        assert!(three_shifts.is_valid(&ctx, &ShiftType::Night));
+    }
+
+    #[test]
+    fn no_morning_after_night_rule_needs_the_night_shift() {
+        let rule_names = |config: SchedulingConfig| -> Vec<String> {
+            config
+                .build_rules()
+                .iter()
+                .map(|r| r.name().to_string())
+                .collect()
+        };
+        assert!(!rule_names(default_config()).contains(&"no_morning_after_night".to_string()));
+        assert!(
+            rule_names(SchedulingConfig {
+                night_shift: true,
+                ..default_config()
+            })
+            .contains(&"no_morning_after_night".to_string())
+        );
+
+        let ctx = AssignmentContext {
+            date: monday(),
+            previous_shift: Some(ShiftType::Night),
+            days_off_in_a_row: 0,
+            day_offs_this_week: 0,
+            days_remaining_in_week: 6,
+            morning_count: 0,
+            evening_count: 0,
+            night_count: 0,
+            day_off_count: 0,
+            position: "Nurse",
+            position_tally: &HashMap::new(),
+            day_tally: &PositionTally::default(),
+        };
+        assert!(!NoMorningAfterNightRule.is_valid(&ctx, &ShiftType::Morning));
+        assert!(NoMorningAfterNightRule.is_valid(&ctx, &ShiftType::Evening));
+    }
+
     // gen_schedule tests
 
+    fn roster(staff_ids: &[Uuid]) -> Vec<RosterMember> {
+        staff_ids
+            .iter()
+            .map(|&staff_id| RosterMember {
+                staff_id,
+                position: "Nurse".to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                history: ShiftHistory::default(),
+                last_shift: None,
+                time_off: HashSet::new(),
+                starts_on: None,
+                effective_until: None,
+            })
+            .collect()
+    }
+
+    fn weekend_days_off(assignments: &[NewShiftAssignment], staff_id: Uuid) -> usize {
+        assignments
+            .iter()
+            .filter(|a| {
+                a.staff_id == staff_id && a.shift_type == ShiftType::DayOff && is_weekend(a.date)
+            })
+            .count()
+    }
+
     fn validate_schedule(
         assignments: &[NewShiftAssignment],
         staff_ids: &[Uuid],
@@ -452,7 +1578,7 @@ mod tests {
         let staff_ids = vec![Uuid::new_v4()];
         let config = default_config();
         let rules = config.build_rules();
-        let assignments = gen_schedule(&staff_ids, monday(), &rules).unwrap();
+        let assignments = gen_schedule(&roster(&staff_ids), monday(), &rules, &config).unwrap();
         validate_schedule(&assignments, &staff_ids, &config);
     }
 
@@ -461,16 +1587,103 @@ mod tests {
         let staff_ids: Vec<_> = (0..4).map(|_| Uuid::new_v4()).collect();
         let config = default_config();
         let rules = config.build_rules();
-        let assignments = gen_schedule(&staff_ids, monday(), &rules).unwrap();
+        let assignments = gen_schedule(&roster(&staff_ids), monday(), &rules, &config).unwrap();
         assert_eq!(assignments.len(), 4 * PERIOD_DAYS);
         validate_schedule(&assignments, &staff_ids, &config);
     }
 
+    #[test]
+    fn gen_schedule_rotates_through_three_shifts_with_nights_on() {
+        let staff_ids: Vec<_> = (0..6).map(|_| Uuid::new_v4()).collect();
+        let config = SchedulingConfig {
+            night_shift: true,
+            ..default_config()
+        };
+        let rules = config.build_rules();
+        let assignments = gen_schedule(&roster(&staff_ids), monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+
+        for day in 0..PERIOD_DAYS {
+            let date = monday() + Duration::days(day as i64);
+            let count = |shift: ShiftType| {
+                assignments
+                    .iter()
+                    .filter(|a| a.date == date && a.shift_type == shift)
+                    .count()
+            };
+            let counts = [
+                count(ShiftType::Morning),
+                count(ShiftType::Evening),
+                count(ShiftType::Night),
+            ];
+            assert!(
+                counts.iter().max().unwrap() - counts.iter().min().unwrap() <= 1,
+                "Day {date} is unbalanced: {counts:?}"
+            );
+        }
+        let nights = assignments
+            .iter()
+            .filter(|a| a.shift_type == ShiftType::Night)
+            .count();
+        assert!(nights >= PERIOD_DAYS, "only {nights} NIGHT shifts");
+        for &sid in &staff_ids {
+            let shifts: Vec<_> = assignments.iter().filter(|a| a.staff_id == sid).collect();
+            assert!(
+                shifts
+                    .windows(2)
+                    .all(|w| !(w[0].shift_type == ShiftType::Night
+                        && w[1].shift_type == ShiftType::Morning))
+            );
+        }
+    }
+
+    #[test]
+    fn gen_horizon_continues_each_period_from_the_last() {
+        let staff_ids: Vec<_> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let config = default_config();
+        let rules = config.build_rules();
+        let assignments = gen_horizon(&roster(&staff_ids), monday(), 3, &rules, &config).unwrap();
+        assert_eq!(assignments.len(), 4 * PERIOD_DAYS * 3);
+
+        for period in assignments.chunks(4 * PERIOD_DAYS) {
+            validate_schedule(period, &staff_ids, &config);
+        }
+        // Rest rules hold across period boundaries too
+        for &sid in &staff_ids {
+            let shifts: Vec<_> = assignments.iter().filter(|a| a.staff_id == sid).collect();
+            assert_eq!(shifts.len(), PERIOD_DAYS * 3);
+            for w in shifts.windows(2) {
+                assert_eq!(w[1].date, w[0].date + Duration::days(1));
+                assert!(
+                    !(w[0].shift_type == ShiftType::Evening
+                        && w[1].shift_type == ShiftType::Morning),
+                    "Staff {sid} has morning after evening on {}",
+                    w[1].date
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn last_shift_carries_into_the_first_day() {
+        let staff_ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let config = default_config();
+        let rules = config.build_rules();
+        let mut members = roster(&staff_ids);
+        members[0].last_shift = Some(ShiftType::Evening);
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        let first = assignments
+            .iter()
+            .find(|a| a.staff_id == staff_ids[0] && a.date == monday())
+            .unwrap();
+        assert_ne!(first.shift_type, ShiftType::Morning);
+    }
+
     #[test]
     fn gen_schedule_empty_staff() {
         let config = default_config();
         let rules = config.build_rules();
-        let output = gen_schedule(&[], monday(), &rules).unwrap();
+        let output = gen_schedule(&[], monday(), &rules, &config).unwrap();
         assert!(output.is_empty());
     }
 
@@ -483,7 +1696,7 @@ mod tests {
             ..default_config()
         };
         let rules = config.build_rules();
-        let assignments = gen_schedule(&staff_ids, monday(), &rules).unwrap();
+        let assignments = gen_schedule(&roster(&staff_ids), monday(), &rules, &config).unwrap();
         validate_schedule(&assignments, &staff_ids, &config);
     }
 
@@ -492,8 +1705,603 @@ mod tests {
         let staff_ids: Vec<_> = (0..20).map(|_| Uuid::new_v4()).collect();
         let config = default_config();
         let rules = config.build_rules();
-        let assignments = gen_schedule(&staff_ids, monday(), &rules).unwrap();
+        let assignments = gen_schedule(&roster(&staff_ids), monday(), &rules, &config).unwrap();
         assert_eq!(assignments.len(), 20 * PERIOD_DAYS);
         validate_schedule(&assignments, &staff_ids, &config);
     }
+
+    #[test]
+    fn weekend_preferences_favour_lowest_weighted_count() {
+        let config = FairnessConfig {
+            weekend_exemption_share: 0.5,
+            ..FairnessConfig::default()
+        };
+        let mut members = roster(&[
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        ]);
+        members[3].fairness_weight = 2.0;
+
+        // Nobody has had a weekend off yet: the heavier weight wins the tie
+        assert_eq!(
+            config.weekend_preferences(&members, &[0, 0, 0, 0]),
+            [true, false, false, true]
+        );
+        // 2 days at weight 2 scores the same as 1 day at weight 1
+        assert_eq!(
+            config.weekend_preferences(&members, &[1, 1, 0, 2]),
+            [false, false, true, true]
+        );
+        assert_eq!(
+            FairnessConfig {
+                weekend_exemption_share: 0.0,
+                ..FairnessConfig::default()
+            }
+            .weekend_preferences(&members, &[0, 0, 0, 0]),
+            [false; 4]
+        );
+    }
+
+    #[test]
+    fn gen_schedule_gives_heavier_weight_more_weekend_days_off() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[7].fairness_weight = 2.0;
+        let config = default_config();
+        let rules = config.build_rules();
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+
+        let senior = weekend_days_off(&assignments, staff_ids[7]);
+        for &sid in &staff_ids[..7] {
+            assert!(
+                senior > weekend_days_off(&assignments, sid),
+                "senior has {senior} weekend days off, staff {sid} has {}",
+                weekend_days_off(&assignments, sid)
+            );
+        }
+    }
+
+    #[test]
+    fn shift_ordering_strategies_keep_schedules_valid() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let members = roster(&staff_ids);
+        let config = default_config();
+        let generate = |ordering| {
+            let assignments =
+                gen_schedule_ordered(&members, monday(), &config.build_rules(), &config, ordering)
+                    .unwrap();
+            validate_schedule(&assignments, &staff_ids, &config);
+            assignments
+                .into_iter()
+                .map(|a| (a.staff_id, a.date, a.shift_type))
+                .collect::<Vec<_>>()
+        };
+
+        let fixed = generate(ShiftOrdering::Fixed);
+        let alternating = generate(ShiftOrdering::Alternating);
+        let seeded = generate(ShiftOrdering::Seeded { seed: 3 });
+        let weighted = generate(ShiftOrdering::Weighted {
+            seed: 3,
+            morning_weight: 0.8,
+        });
+
+        assert_ne!(fixed, alternating);
+        assert_ne!(fixed, seeded);
+        assert_ne!(seeded, weighted);
+        // Same seed, same schedule
+        assert!(seeded == generate(ShiftOrdering::Seeded { seed: 3 }));
+        assert!(seeded != generate(ShiftOrdering::Seeded { seed: 4 }));
+    }
+
+    #[test]
+    fn shift_ordering_that_dead_ends_falls_back_to_fixed() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let members = roster(&staff_ids);
+        // Seed 7 runs out of valid shifts on this roster and period
+        let seeded = ShiftOrdering::Seeded { seed: 7 };
+        let config = SchedulingConfig {
+            shift_ordering: seeded,
+            ..default_config()
+        };
+        let rules = config.build_rules();
+        assert!(gen_schedule_ordered(&members, monday(), &rules, &config, seeded).is_err());
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+        let fixed = gen_schedule_ordered(&members, monday(), &rules, &config, ShiftOrdering::Fixed)
+            .unwrap();
+        assert!(
+            assignments
+                .iter()
+                .zip(&fixed)
+                .all(|(a, f)| a.shift_type == f.shift_type)
+        );
+    }
+
+    #[test]
+    fn shift_ordering_reads_from_toml() {
+        let config: SchedulingConfig = toml::from_str(
+            r#"
+            [shift_ordering]
+            strategy = "weighted"
+            seed = 42
+            morning_weight = 0.7
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.shift_ordering,
+            ShiftOrdering::Weighted {
+                seed: 42,
+                morning_weight: 0.7
+            }
+        );
+        assert_eq!(
+            SchedulingConfig::default().shift_ordering,
+            ShiftOrdering::Fixed
+        );
+    }
+
+    #[test]
+    fn gen_schedule_gives_weekend_relief_after_a_heavy_history() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        // Staff 0 worked every weekend of the last schedules, the rest had plenty off
+        let history: HashMap<_, _> = staff_ids
+            .iter()
+            .enumerate()
+            .map(|(i, &sid)| {
+                let weekend_days_off = if i == 0 { 0 } else { 6 };
+                (
+                    sid,
+                    ShiftHistory {
+                        weekend_days_off,
+                        evening_shifts: 10,
+                    },
+                )
+            })
+            .collect();
+        apply_history(&mut members, &history);
+        let config = default_config();
+        let rules = config.build_rules();
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+
+        let relieved = weekend_days_off(&assignments, staff_ids[0]);
+        for &sid in &staff_ids[1..] {
+            assert!(
+                relieved > weekend_days_off(&assignments, sid),
+                "staff with no past weekends off has {relieved}, staff {sid} has {}",
+                weekend_days_off(&assignments, sid)
+            );
+        }
+    }
+
+    #[test]
+    fn history_fills_in_newcomers_and_steers_evenings() {
+        let staff_ids: Vec<_> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        let history = HashMap::from([
+            (
+                staff_ids[0],
+                ShiftHistory {
+                    weekend_days_off: 2,
+                    evening_shifts: 12,
+                },
+            ),
+            (
+                staff_ids[1],
+                ShiftHistory {
+                    weekend_days_off: 4,
+                    evening_shifts: 2,
+                },
+            ),
+            (
+                staff_ids[2],
+                ShiftHistory {
+                    weekend_days_off: 3,
+                    evening_shifts: 7,
+                },
+            ),
+        ]);
+
+        apply_history(&mut members, &history);
+
+        assert_eq!(
+            members[3].history,
+            ShiftHistory {
+                weekend_days_off: 3,
+                evening_shifts: 7,
+            }
+        );
+        let config = FairnessConfig::default();
+        assert_eq!(
+            config.evening_preferences(&members),
+            [false, true, false, false]
+        );
+        // No history at all leaves the order alone
+        assert_eq!(config.evening_preferences(&roster(&staff_ids)), [false; 4]);
+    }
+
+    #[test]
+    fn gen_schedule_meets_position_minimums_per_shift() {
+        use crate::domain::coverage::{PositionLimit, ShiftPositionLimits};
+
+        let group_id = Uuid::new_v4();
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        // Doctors at the end of the roster, so covering them needs the day reordered
+        for member in &mut members[5..] {
+            member.position = "Doctor".to_string();
+        }
+        let limit = |min, max| PositionLimit { min, max };
+        let mut config = default_config();
+        config.position_coverage.groups.insert(
+            group_id,
+            ShiftPositionLimits {
+                morning: HashMap::from([("Nurse".to_string(), limit(1, Some(3)))]),
+                evening: HashMap::from([("Doctor".to_string(), limit(1, None))]),
+            },
+        );
+        let rules = config.build_rules_for_group(group_id, vec![]);
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+
+        let position = |staff_id| {
+            &members
+                .iter()
+                .find(|m| m.staff_id == staff_id)
+                .unwrap()
+                .position
+        };
+        for day in 0..PERIOD_DAYS {
+            let date = monday() + Duration::days(day as i64);
+            let count = |pos: &str, shift: ShiftType| {
+                assignments
+                    .iter()
+                    .filter(|a| {
+                        a.date == date && a.shift_type == shift && position(a.staff_id) == pos
+                    })
+                    .count()
+            };
+            assert!(
+                count("Doctor", ShiftType::Evening) >= 1,
+                "no evening doctor on {date}"
+            );
+            assert!(
+                (1..=3).contains(&count("Nurse", ShiftType::Morning)),
+                "morning nurses on {date}"
+            );
+        }
+        assert_eq!(config.build_rules().len() + 1, rules.len());
+    }
+
+    #[test]
+    fn gen_schedule_caps_days_off_inside_blackout_window() {
+        let group_id = Uuid::new_v4();
+        let staff_ids: Vec<_> = (0..10).map(|_| Uuid::new_v4()).collect();
+        let members = roster(&staff_ids);
+        let config = default_config();
+        let blackout = GroupBlackout {
+            id: Uuid::new_v4(),
+            group_id,
+            start_date: monday() + Duration::days(3),
+            end_date: monday() + Duration::days(9),
+            max_off_percent: 20,
+            reason: Some("Stocktake".to_string()),
+            created_at: chrono::Utc::now(),
+        };
+        let rules = config.build_rules_for_group(group_id, vec![blackout.clone()]);
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+
+        for day in 0..PERIOD_DAYS {
+            let date = monday() + Duration::days(day as i64);
+            let days_off = assignments
+                .iter()
+                .filter(|a| a.date == date && a.shift_type == ShiftType::DayOff)
+                .count();
+            if blackout.contains(date) {
+                assert!(days_off <= 2, "{days_off} days off on {date}");
+            }
+        }
+    }
+
+    #[test]
+    fn gen_schedule_gives_approved_time_off_as_days_off() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        let request = |staff_id, status, start: i64, end: i64| TimeOffRequest {
+            id: Uuid::new_v4(),
+            staff_id,
+            start_date: monday() + Duration::days(start),
+            end_date: monday() + Duration::days(end),
+            reason: None,
+            status,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let period_end = monday() + Duration::days(PERIOD_DAYS as i64 - 1);
+        apply_time_off(
+            &mut members,
+            &[
+                request(staff_ids[0], TimeOffStatus::Approved, 7, 13),
+                // Ends past the period, only its first day counts
+                request(staff_ids[0], TimeOffStatus::Approved, 27, 40),
+                request(staff_ids[1], TimeOffStatus::Pending, 0, 27),
+            ],
+            monday(),
+            period_end,
+        );
+        assert_eq!(members[0].time_off.len(), 8);
+        assert!(members[1].time_off.is_empty());
+
+        // Enough coverage that the rules alone would never give a whole week off
+        let config = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 2,
+                evening: 1,
+            },
+            ..default_config()
+        };
+        let rules = config.build_rules_for_group(Uuid::new_v4(), vec![]);
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+
+        let days_off: Vec<i64> = assignments
+            .iter()
+            .filter(|a| a.staff_id == staff_ids[0] && a.shift_type == ShiftType::DayOff)
+            .map(|a| (a.date - monday()).num_days())
+            .collect();
+        for day in (7..=13).chain([27]) {
+            assert!(days_off.contains(&day), "day {day} should be off");
+        }
+    }
+
+    #[test]
+    fn gen_schedule_stops_assigning_staff_after_their_last_day() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[0].effective_until = Some(monday() + Duration::days(9));
+        let config = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 2,
+                evening: 1,
+            },
+            ..default_config()
+        };
+        let rules = config.build_rules_for_group(Uuid::new_v4(), vec![]);
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+
+        let leaver_days: Vec<i64> = assignments
+            .iter()
+            .filter(|a| a.staff_id == staff_ids[0])
+            .map(|a| (a.date - monday()).num_days())
+            .collect();
+        assert_eq!(leaver_days, (0..=9).collect::<Vec<_>>());
+        // The rest still cover every day without the leaver
+        for day in 10..PERIOD_DAYS as i64 {
+            let date = monday() + Duration::days(day);
+            let mornings = assignments
+                .iter()
+                .filter(|a| a.date == date && a.shift_type == ShiftType::Morning)
+                .count();
+            assert!(mornings >= 2, "day {day} has {mornings} mornings");
+        }
+    }
+
+    #[test]
+    fn gen_schedule_starts_assigning_new_hires_on_their_first_day() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[0].starts_on = Some(monday() + Duration::days(14));
+        let config = default_config();
+        let rules = config.build_rules_for_group(Uuid::new_v4(), vec![]);
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+
+        let new_hire_days: Vec<i64> = assignments
+            .iter()
+            .filter(|a| a.staff_id == staff_ids[0])
+            .map(|a| (a.date - monday()).num_days())
+            .collect();
+        assert_eq!(new_hire_days, (14..PERIOD_DAYS as i64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn gen_schedule_steers_towards_shift_targets() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[0].position = "Nurse".to_string();
+        let config = SchedulingConfig {
+            shift_targets: ShiftTargetConfig {
+                positions: HashMap::from([(
+                    "Nurse".to_string(),
+                    ShiftTarget {
+                        morning: 0,
+                        evening: 1,
+                    },
+                )]),
+                staff: HashMap::from([(
+                    staff_ids[1],
+                    ShiftTarget {
+                        morning: 2,
+                        evening: 1,
+                    },
+                )]),
+            },
+            // Targets are soft, a tight balance rule would overrule them here
+            max_daily_shift_diff: 8,
+            ..default_config()
+        };
+        let rules = config.build_rules();
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+        let shifts = |staff_id: Uuid, shift: ShiftType| {
+            assignments
+                .iter()
+                .filter(|a| a.staff_id == staff_id && a.shift_type == shift)
+                .count()
+        };
+        assert_eq!(shifts(staff_ids[0], ShiftType::Morning), 0);
+        // No MORNING after EVENING keeps 2:1 from being exact
+        let morning = shifts(staff_ids[1], ShiftType::Morning) as f64;
+        let share = morning / (morning + shifts(staff_ids[1], ShiftType::Evening) as f64);
+        assert!((share - 2.0 / 3.0).abs() < 0.1, "morning share {share}");
+    }
+
+    #[test]
+    fn gen_schedule_never_gives_staff_a_shift_they_are_not_eligible_for() {
+        let staff_ids: Vec<_> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[0].shift_eligibility = ShiftEligibility::MorningOnly;
+        members[1].shift_eligibility = ShiftEligibility::EveningOnly;
+        let config = default_config();
+        let rules = config.build_rules();
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+        let worked = |staff_id: Uuid, shift: ShiftType| {
+            assignments
+                .iter()
+                .any(|a| a.staff_id == staff_id && a.shift_type == shift)
+        };
+        assert!(!worked(staff_ids[0], ShiftType::Evening));
+        assert!(worked(staff_ids[0], ShiftType::Morning));
+        assert!(!worked(staff_ids[1], ShiftType::Morning));
+        assert!(worked(staff_ids[1], ShiftType::Evening));
+    }
+
+    #[test]
+    fn gen_schedule_pairs_days_off() {
+        let staff_ids: Vec<_> = (0..10).map(|_| Uuid::new_v4()).collect();
+        let members = roster(&staff_ids);
+        let lone_days_off = |pairing| {
+            let config = SchedulingConfig {
+                day_off_pairing: pairing,
+                ..default_config()
+            };
+            let rules = config.build_rules();
+            let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+            validate_schedule(&assignments, &staff_ids, &config);
+            let config = SchedulingConfig {
+                day_off_pairing: DayOffPairing::Require,
+                ..config
+            };
+            crate::domain::simulate::find_violations(
+                assignments
+                    .iter()
+                    .map(|a| (a.staff_id, a.date, &a.shift_type)),
+                monday(),
+                &config,
+            )
+            .iter()
+            .filter(|v| v.rule == "day_off_pairing")
+            .count()
+        };
+
+        let scattered = lone_days_off(DayOffPairing::Off);
+        assert!(scattered > 0);
+        assert!(lone_days_off(DayOffPairing::Prefer) < scattered);
+        assert_eq!(lone_days_off(DayOffPairing::Require), 0);
+    }
+
+    #[test]
+    fn gen_schedule_meets_daily_shift_coverage() {
+        let staff_ids: Vec<_> = (0..10).map(|_| Uuid::new_v4()).collect();
+        let mut members = roster(&staff_ids);
+        members[0].position = "Porter".to_string();
+        let config = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 3,
+                evening: 4,
+            },
+            ..default_config()
+        };
+        let rules = config.build_rules_for_group(Uuid::new_v4(), vec![]);
+
+        let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+        validate_schedule(&assignments, &staff_ids, &config);
+        for day in 0..PERIOD_DAYS {
+            let date = monday() + Duration::days(day as i64);
+            let count = |shift: ShiftType| {
+                assignments
+                    .iter()
+                    .filter(|a| a.date == date && a.shift_type == shift)
+                    .count()
+            };
+            assert!(
+                count(ShiftType::Morning) >= 3,
+                "short on mornings on {date}"
+            );
+            assert!(
+                count(ShiftType::Evening) >= 4,
+                "short on evenings on {date}"
+            );
+        }
+
+        // 70 shifts a week need 12 staff working 6 days each
+        let config = SchedulingConfig {
+            shift_coverage: ShiftCoverage {
+                morning: 5,
+                evening: 5,
+            },
+            ..default_config()
+        };
+        let rules = config.build_rules();
+        let Err(error) = gen_schedule(&members, monday(), &rules, &config) else {
+            panic!("coverage for 12 generated with 10 staff");
+        };
+        assert!(matches!(
+            error,
+            SchedulingError::CoverageUnreachable {
+                needed: 12,
+                staff: 10,
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("at least 12 staff"));
+    }
+
+    #[test]
+    fn week_alignment_positions() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+        let next_monday = NaiveDate::from_ymd_opt(2026, 2, 23).unwrap();
+
+        assert_eq!(WeekAlignment::Period.day_in_week(next_monday, wednesday), 5);
+        assert_eq!(WeekAlignment::Iso.day_in_week(next_monday, wednesday), 0);
+        assert_eq!(
+            WeekAlignment::Iso.week_start(wednesday, wednesday),
+            NaiveDate::from_ymd_opt(2026, 2, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn gen_schedule_iso_weeks_for_period_not_starting_monday() {
+        let staff_ids: Vec<_> = (0..6).map(|_| Uuid::new_v4()).collect();
+        let config = SchedulingConfig {
+            week_alignment: WeekAlignment::Iso,
+            ..default_config()
+        };
+        let rules = config.build_rules();
+        let wednesday = NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+
+        let assignments = gen_schedule(&roster(&staff_ids), wednesday, &rules, &config).unwrap();
+
+        // Includes the partial ISO weeks at both ends (Wed-Sun and Mon-Tue)
+        let violations = crate::domain::simulate::find_violations(
+            assignments
+                .iter()
+                .map(|a| (a.staff_id, a.date, &a.shift_type)),
+            wednesday,
+            &config,
+        );
+        assert!(violations.is_empty(), "{violations:?}");
+    }
 }
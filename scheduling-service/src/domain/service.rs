@@ -1,23 +1,91 @@
-use chrono::{Datelike, NaiveDate};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use tokio_util::task::TaskTracker;
 use tracing::Instrument;
 use uuid::Uuid;
 
-use shared::types::{JobStatus, ScheduleJob, ScheduleResult, StaffStatus};
-
+use shared::pagination::{PageRequest, SortOrder};
+use shared::types::{
+    BorrowedStaff, JobStatus, ScheduleJob, ScheduleMetrics, ScheduleNote, SchedulePeriod,
+    ScheduleResult, ShiftAssignment, ShiftType, StaffStatus, StaffSummary,
+};
+
+use crate::domain::acknowledgment::{AcknowledgmentStatus, OutstandingAcknowledgments};
+use crate::domain::approval::{
+    ApprovalDecision, ApprovalRequest, ApprovalStatus, MAX_APPROVAL_COMMENT_CHARS,
+};
+use crate::domain::assignment_order::{AssignmentOrder, sort_assignments};
+use crate::domain::calendar::{CalendarEntry, render_calendar};
+use crate::domain::callback::{JobCallback, validate_callback_url};
+use crate::domain::checksum::assignments_checksum;
 use crate::domain::client::DataServiceClient;
-use crate::domain::job::JobRepository;
+use crate::domain::consistency::{
+    CONSISTENCY_NOTE_AUTHOR, ConsistencyCheckQuery, ConsistencyReport, find_orphans, orphan_note,
+    orphans_by_job,
+};
+use crate::domain::coverage::{
+    MAX_SHIFT_COVERAGE, ShiftCoverage, ShortfallBehavior, coverage_shortfalls,
+    has_coverage_minimums,
+};
+use crate::domain::export::render_payroll_csv;
+use crate::domain::float_pool::{borrowing_pool, gen_borrowing, lendable};
+use crate::domain::group_config::{GroupRuleOverrides, GroupSchedulingConfig};
+use crate::domain::history::{HistoryImport, parse_history_csv};
+use crate::domain::job::{
+    BulkAssignmentReport, BulkAssignmentUpdate, CreateScheduleNote, JobOptions, JobPage, JobQuery,
+    JobRepository, MAX_ASSIGNMENT_CHANGES, MAX_AUTHOR_CHARS, MAX_JOB_PAGE_SIZE,
+    MAX_NOTE_BODY_CHARS, MAX_NOTE_CHARS, MAX_OVERRIDE_REASON_CHARS, StatusOverride,
+    UpdateAssignment,
+};
 use crate::domain::job_state::PendingJob;
-use crate::domain::scheduler::{SchedulingConfig, SchedulingRule, gen_schedule};
+use crate::domain::locale::Locale;
+use crate::domain::lock::{PURGE_SANDBOX_JOBS, RECOVER_STALE_JOBS, TaskLock};
+use crate::domain::notify::{JobEvent, Notifier, dispatch};
+use crate::domain::packed::{DEFAULT_PACK_LIMIT, MAX_PACK_LIMIT, PackAssignmentsQuery, PackReport};
+use crate::domain::period::{
+    MAX_HORIZON_PERIODS, NextPeriod, earliest_overlapping_begin, horizon_days,
+    next_unscheduled_monday, overlaps,
+};
+use crate::domain::preflight::{
+    DuplicatePolicy, SubmissionWarning, SubmittedJob, position_warnings, preflight_warnings,
+};
+use crate::domain::queue::JobQueue;
+use crate::domain::scheduler::{
+    PERIOD_DAYS, RosterMember, SchedulingConfig, SchedulingError, ShiftHistory, apply_history,
+    apply_time_off, gen_horizon, is_weekend,
+};
+use crate::domain::share::{
+    DEFAULT_SHARE_LINK_TTL_HOURS, MAX_SHARE_LINK_TTL_HOURS, ShareLink, ShareLinkSigner,
+};
+use crate::domain::simulate::{
+    Coverage, ScenarioReport, SimulationReport, SimulationRequest, coverage, find_violations,
+};
+use crate::domain::staff_schedule::{
+    MAX_STAFF_ASSIGNMENT_DAYS, StaffAssignments, StaffAssignmentsQuery, StaffSchedule,
+};
+use crate::domain::stats::{
+    DEFAULT_STATS_WINDOW_HOURS, JobStats, JobStatsQuery, MAX_STATS_WINDOW_HOURS,
+};
+use crate::domain::status_updates::{JobStatusUpdate, JobStatusUpdates, StatusWatch};
+use crate::domain::timeline::{JobEventKind, JobTimeline};
 use crate::error::SchedulingServiceError;
 
 pub struct SchedulingService {
     job_repo: Arc<dyn JobRepository>,
     data_client: Arc<dyn DataServiceClient>,
     config: SchedulingConfig,
-    rules: Arc<Vec<Box<dyn SchedulingRule>>>,
     task_tracker: TaskTracker,
+    share_signer: Option<Arc<ShareLinkSigner>>,
+    task_lock: Option<Arc<dyn TaskLock>>,
+    notifiers: Arc<Vec<Arc<dyn Notifier>>>,
+    callbacks: bool,
+    status_updates: JobStatusUpdates,
+    worker_id: Option<Arc<str>>,
+    job_slots: Arc<Semaphore>,
 }
 
 impl SchedulingService {
@@ -26,16 +94,53 @@ impl SchedulingService {
         data_client: Arc<dyn DataServiceClient>,
         config: SchedulingConfig,
     ) -> Self {
-        let rules = Arc::new(config.build_rules());
+        let job_slots = Arc::new(Semaphore::new(config.max_concurrent_jobs.max(1)));
         Self {
             job_repo,
             data_client,
             config,
-            rules,
             task_tracker: TaskTracker::new(),
+            share_signer: None,
+            task_lock: None,
+            notifiers: Arc::new(Vec::new()),
+            callbacks: false,
+            status_updates: JobStatusUpdates::default(),
+            worker_id: None,
+            job_slots,
         }
     }
 
+    /// Enable public share links signed with the given signer.
+    pub fn with_share_links(mut self, signer: ShareLinkSigner) -> Self {
+        self.share_signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Run singleton maintenance tasks on one replica at a time.
+    pub fn with_task_lock(mut self, lock: Arc<dyn TaskLock>) -> Self {
+        self.task_lock = Some(lock);
+        self
+    }
+
+    /// Deliver an event to these channels whenever a job completes or fails.
+    pub fn with_notifiers(mut self, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        self.notifiers = Arc::new(notifiers);
+        self
+    }
+
+    /// Accept `callback_url` on submission and queue each job's outcome for it. Something
+    /// has to send the queue, see `CallbackDispatcher`.
+    pub fn with_callbacks(mut self) -> Self {
+        self.callbacks = true;
+        self
+    }
+
+    /// Name this replica in the timeline of the jobs it picks up.
+    pub fn with_worker_id(mut self, worker_id: impl Into<String>) -> Self {
+        self.worker_id = Some(worker_id.into().into());
+        self
+    }
+
     pub fn task_tracker(&self) -> &TaskTracker {
         &self.task_tracker
     }
@@ -45,13 +150,39 @@ impl SchedulingService {
         &self,
         staff_group_id: Uuid,
         period_begin_date: NaiveDate,
-    ) -> Result<ScheduleJob, SchedulingServiceError> {
+        mut depends_on: Vec<Uuid>,
+        options: JobOptions,
+    ) -> Result<SubmittedJob, SchedulingServiceError> {
         if period_begin_date.weekday() != chrono::Weekday::Mon {
             return Err(SchedulingServiceError::BadRequest(
                 "period_begin_date must be a Monday".to_string(),
             ));
         }
 
+        if !(1..=MAX_HORIZON_PERIODS).contains(&options.periods) {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "periods must be between 1 and {MAX_HORIZON_PERIODS}"
+            )));
+        }
+
+        if options
+            .coverage
+            .is_some_and(|c| c.morning.max(c.evening) > MAX_SHIFT_COVERAGE)
+        {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "coverage must be at most {MAX_SHIFT_COVERAGE} staff per shift"
+            )));
+        }
+
+        if let Some(url) = &options.callback_url {
+            if !self.callbacks {
+                return Err(SchedulingServiceError::BadRequest(
+                    "Job callbacks are disabled on this server".to_string(),
+                ));
+            }
+            validate_callback_url(url)?;
+        }
+
         let today = shared::time::today_in(self.config.timezone());
         if period_begin_date < today {
             return Err(SchedulingServiceError::BadRequest(
@@ -59,39 +190,184 @@ impl SchedulingService {
             ));
         }
 
+        if !options.force
+            && !options.sandbox
+            && let Some(existing) = self
+                .job_repo
+                .find_duplicate_job(staff_group_id, period_begin_date)
+                .await?
+        {
+            return match self.config.duplicate_submissions {
+                DuplicatePolicy::Reject => Err(SchedulingServiceError::Conflict(format!(
+                    "Job {} ({}) already covers this group from {period_begin_date}; \
+                     set force to submit another",
+                    existing.id,
+                    existing.status.as_str()
+                ))),
+                DuplicatePolicy::ReturnExisting => Ok(SubmittedJob {
+                    job: existing,
+                    warnings: Vec::new(),
+                    existing: true,
+                }),
+            };
+        }
+
+        depends_on.sort_unstable();
+        depends_on.dedup();
+        for &dependency in &depends_on {
+            match self.job_repo.find_by_id(dependency).await? {
+                None => {
+                    return Err(SchedulingServiceError::BadRequest(format!(
+                        "Dependency job {dependency} not found"
+                    )));
+                }
+                // It will be purged, and a real schedule shouldn't rest on a test run anyway
+                Some(job) if job.sandbox && !options.sandbox => {
+                    return Err(SchedulingServiceError::BadRequest(format!(
+                        "Dependency job {dependency} is a sandbox job"
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let warnings = self
+            .preflight(
+                staff_group_id,
+                period_begin_date,
+                options.periods,
+                options.coverage,
+            )
+            .await?;
+
         let job = self
             .job_repo
-            .create_job(staff_group_id, period_begin_date)
+            .create_job(staff_group_id, period_begin_date, depends_on, options)
             .await?;
+        let detail = (!job.depends_on.is_empty())
+            .then(|| format!("waiting on {} dependencies", job.depends_on.len()));
+        record_event(
+            self.job_repo.as_ref(),
+            job.id,
+            JobEventKind::Created,
+            detail,
+            None,
+        )
+        .await;
+
+        if job.depends_on.is_empty() {
+            let pending_job = PendingJob::from_schedule_job(job.clone()).ok_or_else(|| {
+                SchedulingServiceError::Internal(format!(
+                    "Newly created job {} has unexpected status {:?}",
+                    job.id, job.status
+                ))
+            })?;
+            self.spawn_process_job(pending_job);
+        } else {
+            self.start_if_dependencies_completed(job.clone()).await?;
+        }
 
-        let pending_job = PendingJob::from_schedule_job(job.clone()).ok_or_else(|| {
-            SchedulingServiceError::Internal(format!(
-                "Newly created job {} has unexpected status {:?}",
-                job.id, job.status
-            ))
-        })?;
+        Ok(SubmittedJob {
+            job,
+            warnings,
+            existing: false,
+        })
+    }
 
-        self.spawn_process_job(pending_job);
+    /// Start a job with dependencies right away when they have all completed already.
+    /// Otherwise it stays `Pending` until the last of them completes.
+    async fn start_if_dependencies_completed(
+        &self,
+        job: ScheduleJob,
+    ) -> Result<(), SchedulingServiceError> {
+        for &dependency in &job.depends_on {
+            let completed = self
+                .job_repo
+                .find_by_id(dependency)
+                .await?
+                .is_some_and(|d| d.status == JobStatus::Completed);
+            if !completed {
+                tracing::info!(job_id = %job.id, %dependency, "Job waiting on dependency");
+                return Ok(());
+            }
+        }
 
-        Ok(job)
+        let job_id = job.id;
+        if self.runner().claim_and_spawn(job).await? {
+            tracing::info!(%job_id, "Dependencies completed, starting job");
+        }
+        Ok(())
     }
 
-    pub fn spawn_process_job(&self, pending_job: PendingJob) {
-        let job_id = pending_job.id();
-        let staff_group_id = pending_job.inner().staff_group_id;
-        let repo = Arc::clone(&self.job_repo);
-        let client = Arc::clone(&self.data_client);
-        let rules = Arc::clone(&self.rules);
-
-        let span = tracing::info_span!("process_job", %job_id, %staff_group_id);
-        self.task_tracker.spawn(
-            async move {
-                if let Err(e) = process_job(pending_job, repo, client, rules).await {
-                    tracing::error!("Job {job_id} failed: {e}");
+    /// Cheap checks for problems the job would otherwise only hit (or silently produce) later.
+    async fn preflight(
+        &self,
+        staff_group_id: Uuid,
+        period_begin_date: NaiveDate,
+        periods: i16,
+        coverage: Option<ShiftCoverage>,
+    ) -> Result<Vec<SubmissionWarning>, SchedulingServiceError> {
+        let config = group_config(self.job_repo.as_ref(), &self.config, staff_group_id)
+            .await?
+            .with_shift_coverage(coverage);
+        let position_limits = self.config.position_coverage.groups.get(&staff_group_id);
+        let (active_staff, overlapping, position_counts) = tokio::join!(
+            self.data_client
+                .count_resolved_members(staff_group_id, true),
+            self.job_repo.find_overlapping_jobs(
+                staff_group_id,
+                earliest_overlapping_begin(period_begin_date),
+                period_begin_date + TimeDelta::days(horizon_days(periods) - 1)
+            ),
+            async {
+                match position_limits {
+                    Some(_) => Some(
+                        self.data_client
+                            .count_members_by_position(staff_group_id, true)
+                            .await,
+                    ),
+                    None => None,
                 }
-            }
-            .instrument(span),
+            },
         );
+
+        let active_staff = match active_staff {
+            Ok(count) => Some(count.max(0) as usize),
+            Err(e) => {
+                tracing::warn!("Pre-flight member count failed: {e}");
+                None
+            }
+        };
+
+        let mut overlapping = overlapping?;
+        overlapping.retain(|job| overlaps(job, period_begin_date, periods));
+        let mut warnings = preflight_warnings(&config, active_staff, &overlapping);
+        match (position_limits, position_counts) {
+            (Some(limits), Some(Ok(counts))) => {
+                warnings.extend(position_warnings(&config, limits, &counts));
+            }
+            (_, Some(Err(e))) => tracing::warn!("Pre-flight position count failed: {e}"),
+            _ => {}
+        }
+        Ok(warnings)
+    }
+
+    pub fn spawn_process_job(&self, pending_job: PendingJob) {
+        self.runner().spawn(pending_job);
+    }
+
+    fn runner(&self) -> JobRunner {
+        JobRunner {
+            repo: Arc::clone(&self.job_repo),
+            client: Arc::clone(&self.data_client),
+            config: self.config.clone(),
+            notifiers: Arc::clone(&self.notifiers),
+            callbacks: self.callbacks,
+            task_tracker: self.task_tracker.clone(),
+            status_updates: self.status_updates.clone(),
+            worker_id: self.worker_id.clone(),
+            job_slots: Arc::clone(&self.job_slots),
+        }
     }
 
     #[tracing::instrument(skip(self))]
@@ -101,6 +377,21 @@ impl SchedulingService {
         })
     }
 
+    /// Follow a job's status from what it is now until it completes or fails.
+    #[tracing::instrument(skip(self))]
+    pub async fn watch_status(&self, job_id: Uuid) -> Result<StatusWatch, SchedulingServiceError> {
+        let updates = self.status_updates.subscribe();
+        let job = self.get_status(job_id).await?;
+        Ok(StatusWatch::new(Arc::clone(&self.job_repo), &job, updates))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_timeline(&self, job_id: Uuid) -> Result<JobTimeline, SchedulingServiceError> {
+        let job = self.get_status(job_id).await?;
+        let events = self.job_repo.get_job_events(job_id).await?;
+        Ok(JobTimeline::new(&job, events))
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_result(&self, job_id: Uuid) -> Result<ScheduleResult, SchedulingServiceError> {
         let job = self.get_status(job_id).await?;
@@ -112,368 +403,3491 @@ impl SchedulingService {
             )));
         }
 
-        let assignments = self.job_repo.get_assignments(job_id).await?;
-
-        Ok(ScheduleResult {
-            schedule_id: job.id,
-            period_begin_date: job.period_begin_date,
-            staff_group_id: job.staff_group_id,
-            assignments,
-        })
+        build_result(
+            self.job_repo.as_ref(),
+            self.data_client.as_ref(),
+            &self.config,
+            job,
+        )
+        .await
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn recover_stale_jobs(&self) -> Result<(), SchedulingServiceError> {
-        let stale_jobs = self.job_repo.find_by_status(JobStatus::Processing).await?;
-
-        if stale_jobs.is_empty() {
-            tracing::info!("No stale jobs to recover");
-            return Ok(());
+    /// Attach a period-level note to a schedule, written by `author`, the caller. Notes can be
+    /// written before the job completes; they are shown with the result once it does.
+    #[tracing::instrument(skip(self, note))]
+    pub async fn add_note(
+        &self,
+        job_id: Uuid,
+        author: &str,
+        note: CreateScheduleNote,
+    ) -> Result<ScheduleNote, SchedulingServiceError> {
+        let author = author.trim();
+        let body = note.body.trim();
+        if body.is_empty() {
+            return Err(SchedulingServiceError::BadRequest(
+                "body is required".to_string(),
+            ));
         }
-
-        tracing::info!(count = stale_jobs.len(), "Recovering stale jobs");
-
-        for job in stale_jobs {
-            let job_id = job.id;
-            tracing::info!(%job_id, "Recovering stale job");
-
-            self.job_repo.delete_assignments(job_id).await?;
-            self.job_repo
-                .update_status(job_id, JobStatus::Pending)
-                .await?;
-
-            let refreshed = self.job_repo.find_by_id(job_id).await?;
-            if let Some(job) = refreshed {
-                if let Some(pending) = PendingJob::from_schedule_job(job) {
-                    self.spawn_process_job(pending);
-                } else {
-                    tracing::warn!(%job_id, "Job no longer in Pending status after reset");
-                }
-            }
+        if author.is_empty() || author.chars().count() > MAX_AUTHOR_CHARS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "The caller can't sign notes: their subject must be 1 to {MAX_AUTHOR_CHARS} characters"
+            )));
+        }
+        if body.chars().count() > MAX_NOTE_BODY_CHARS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "body must be at most {MAX_NOTE_BODY_CHARS} characters"
+            )));
         }
 
-        Ok(())
-    }
-}
+        self.get_status(job_id).await?;
 
-#[tracing::instrument(skip(pending_job, repo, client, rules), fields(job_id = %pending_job.id()))]
-async fn process_job(
-    pending_job: PendingJob,
-    repo: Arc<dyn JobRepository>,
-    client: Arc<dyn DataServiceClient>,
-    rules: Arc<Vec<Box<dyn SchedulingRule>>>,
-) -> Result<(), SchedulingServiceError> {
-    tracing::info!("Processing job");
+        self.job_repo
+            .add_note(job_id, author.to_string(), body.to_string())
+            .await
+    }
 
-    let (processing_job, job_id, status) = pending_job.start_processing();
-    repo.update_status(job_id, status).await?;
+    /// Manually change one assignment of a completed schedule that isn't published yet. The
+    /// job checksum is recomputed so the result stays verifiable.
+    #[tracing::instrument(skip(self, update))]
+    pub async fn update_assignment(
+        &self,
+        job_id: Uuid,
+        assignment_id: Uuid,
+        update: UpdateAssignment,
+    ) -> Result<ShiftAssignment, SchedulingServiceError> {
+        if update.shift_type.is_none() && update.note.is_none() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Nothing to update, set shift_type and/or note".to_string(),
+            ));
+        }
+        if let Some(note) = &update.note
+            && note.trim().chars().count() > MAX_NOTE_CHARS
+        {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "note must be at most {MAX_NOTE_CHARS} characters"
+            )));
+        }
 
-    let staff_group_id = processing_job.staff_group_id();
-    let period_begin_date = processing_job.period_begin_date();
+        let mut assignments = self.get_result(job_id).await?.assignments;
+        self.ensure_unpublished(job_id).await?;
+        let not_found = || {
+            SchedulingServiceError::NotFound(format!(
+                "Assignment {assignment_id} not found in schedule {job_id}"
+            ))
+        };
+        let target = assignments
+            .iter_mut()
+            .find(|a| a.id == assignment_id)
+            .ok_or_else(not_found)?;
 
-    let members = match client.get_resolved_members(staff_group_id).await {
-        Ok(m) => m,
-        Err(e) => {
-            let (_failed, id, status) = processing_job.fail();
-            repo.update_status(id, status).await.ok();
-            return Err(e);
+        if let Some(shift_type) = update.shift_type {
+            target.shift_type = shift_type;
         }
-    };
+        if let Some(note) = update.note {
+            let note = note.trim();
+            target.note = (!note.is_empty()).then(|| note.to_string());
+        }
+        let (shift_type, note) = (target.shift_type.clone(), target.note.clone());
 
-    let active_ids: Vec<_> = members
-        .into_iter()
-        .filter(|s| s.status == StaffStatus::Active)
-        .map(|s| s.id)
-        .collect();
+        // The checksum is recomputed with the job locked, over edits made meanwhile too
+        self.job_repo
+            .update_assignment(job_id, assignment_id, shift_type, note)
+            .await?
+            .ok_or_else(not_found)
+    }
 
-    match gen_schedule(&active_ids, period_begin_date, &rules) {
-        Ok(assignments) => {
-            repo.save_assignments(job_id, assignments).await?;
-            let (_completed, id, status) = processing_job.complete();
-            repo.update_status(id, status).await?;
-            tracing::info!("Job completed");
+    /// Change the shifts of several assignments of an unpublished schedule at once, all or none.
+    /// The edited schedule is checked against the group's rules; the violations are reported,
+    /// not refused, like a single manual change would be.
+    #[tracing::instrument(skip(self, update), fields(changes = update.changes.len()))]
+    pub async fn update_assignments(
+        &self,
+        job_id: Uuid,
+        update: BulkAssignmentUpdate,
+    ) -> Result<BulkAssignmentReport, SchedulingServiceError> {
+        if update.changes.is_empty() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Nothing to update, changes is empty".to_string(),
+            ));
         }
-        Err(e) => {
-            let (_failed, id, status) = processing_job.fail();
-            repo.update_status(id, status).await.ok();
-            tracing::error!("Scheduling failed: {e}");
-            return Err(SchedulingServiceError::Internal(format!(
-                "Scheduling failed: {e}"
+        if update.changes.len() > MAX_ASSIGNMENT_CHANGES {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "At most {MAX_ASSIGNMENT_CHANGES} changes per update"
+            )));
+        }
+        let mut seen = HashSet::new();
+        if let Some(change) = update
+            .changes
+            .iter()
+            .find(|c| !seen.insert(c.assignment_id))
+        {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Assignment {} is changed more than once",
+                change.assignment_id
             )));
         }
-    }
 
-    Ok(())
-}
+        let result = self.get_result(job_id).await?;
+        self.ensure_unpublished(job_id).await?;
+        let config =
+            group_config(self.job_repo.as_ref(), &self.config, result.staff_group_id).await?;
+        let mut assignments = result.assignments;
+        let violations_of = |assignments: &[ShiftAssignment]| {
+            find_violations(
+                assignments
+                    .iter()
+                    .map(|a| (a.staff_id, a.date, &a.shift_type)),
+                result.period_begin_date,
+                &config,
+            )
+        };
+        let before = violations_of(&assignments);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::client::MockDataServiceClient;
-    use crate::domain::job::{MockJobRepository, NewShiftAssignment};
-    use crate::domain::scheduler::SchedulingConfig;
-    use shared::types::ShiftAssignment;
-    use std::sync::Mutex;
+        let index: HashMap<Uuid, usize> = assignments
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.id, i))
+            .collect();
+        for change in &update.changes {
+            let i = *index.get(&change.assignment_id).ok_or_else(|| {
+                SchedulingServiceError::NotFound(format!(
+                    "Assignment {} not found in schedule {job_id}",
+                    change.assignment_id
+                ))
+            })?;
+            assignments[i].shift_type = change.shift_type.clone();
+        }
 
-    fn make_service(
-        job_repo: MockJobRepository,
-        data_client: MockDataServiceClient,
-    ) -> SchedulingService {
-        SchedulingService::new(
-            Arc::new(job_repo),
-            Arc::new(data_client),
-            SchedulingConfig::default(),
-        )
-    }
+        let violations = violations_of(&assignments);
+        let introduced_violations = violations.iter().filter(|v| !before.contains(v)).count();
 
-    fn make_job(status: JobStatus) -> ScheduleJob {
-        ScheduleJob {
-            id: Uuid::new_v4(),
-            staff_group_id: Uuid::new_v4(),
-            period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
-            status,
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
-        }
+        let changes = update
+            .changes
+            .into_iter()
+            .map(|c| (c.assignment_id, c.shift_type))
+            .collect();
+        let assignments = self
+            .job_repo
+            .update_assignments(job_id, changes)
+            .await?
+            .ok_or_else(|| {
+                SchedulingServiceError::Conflict(format!(
+                    "Assignments of schedule {job_id} changed meanwhile, check it and try again"
+                ))
+            })?;
+
+        Ok(BulkAssignmentReport {
+            assignments,
+            violations,
+            introduced_violations,
+        })
     }
 
-    #[tokio::test]
-    async fn submit_schedule_rejects_non_monday() {
-        let repo = MockJobRepository::new();
-        let client = MockDataServiceClient::new();
-        let svc = make_service(repo, client);
+    /// Regenerate a completed schedule in memory with some staff removed and/or rules
+    /// changed, and compare it with the stored one. Nothing is persisted.
+    #[tracing::instrument(skip(self, request))]
+    pub async fn simulate(
+        &self,
+        job_id: Uuid,
+        request: SimulationRequest,
+    ) -> Result<SimulationReport, SchedulingServiceError> {
+        let result = self.get_result(job_id).await?;
+        let config = request
+            .rules
+            .apply(
+                &group_config(self.job_repo.as_ref(), &self.config, result.staff_group_id).await?,
+            )
+            .map_err(SchedulingServiceError::BadRequest)?;
+        let members = self
+            .data_client
+            .get_resolved_members(result.staff_group_id, true)
+            .await?;
+        let period_begin_date = result.period_begin_date;
+        let periods = result.periods.len().max(1);
+        let period_end_date =
+            period_begin_date + TimeDelta::days((PERIOD_DAYS * periods) as i64 - 1);
+        let blackouts = self
+            .data_client
+            .get_blackouts(result.staff_group_id, period_begin_date, period_end_date)
+            .await?;
+        let time_off = self
+            .data_client
+            .get_time_off(result.staff_group_id, period_begin_date, period_end_date)
+            .await?;
 
-        // 2026-02-17 is Tuesday
-        let tuesday = NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
-        let output = svc.submit_schedule(Uuid::new_v4(), tuesday).await;
+        let baseline_assignments = || {
+            result
+                .assignments
+                .iter()
+                .map(|a| (a.staff_id, a.date, &a.shift_type))
+        };
+        let baseline = ScenarioReport {
+            coverage: coverage(baseline_assignments()),
+            violations: find_violations(baseline_assignments(), period_begin_date, &config),
+            error: None,
+        };
 
-        assert!(output.is_err());
-        assert!(matches!(
-            output.unwrap_err(),
-            SchedulingServiceError::BadRequest(_)
-        ));
-    }
+        let mut roster: Vec<_> = members
+            .iter()
+            .filter(|m| {
+                m.staff.status == StaffStatus::Active && !request.remove_staff.contains(&m.staff.id)
+            })
+            .map(RosterMember::from)
+            .collect();
+        let history = shift_history(
+            self.job_repo.as_ref(),
+            &config,
+            result.staff_group_id,
+            period_begin_date,
+        )
+        .await;
+        apply_history(&mut roster, &history);
+        apply_time_off(&mut roster, &time_off, period_begin_date, period_end_date);
+        let generator_config = config.clone();
+        let generated = tokio::task::spawn_blocking(move || {
+            let rules = generator_config.build_rules_for_group(result.staff_group_id, blackouts);
+            gen_horizon(
+                &roster,
+                period_begin_date,
+                periods,
+                &rules,
+                &generator_config,
+            )
+        })
+        .await
+        .map_err(|e| {
+            SchedulingServiceError::Internal(format!("Schedule generation panicked: {e}"))
+        })?;
 
-    #[tokio::test]
-    async fn get_status_not_found() {
-        let mut repo = MockJobRepository::new();
-        repo.expect_find_by_id().returning(|_| Ok(None));
+        let simulated = match generated {
+            Ok(assignments) => {
+                let simulated_assignments = || {
+                    assignments
+                        .iter()
+                        .map(|a| (a.staff_id, a.date, &a.shift_type))
+                };
+                ScenarioReport {
+                    coverage: coverage(simulated_assignments()),
+                    violations: find_violations(
+                        simulated_assignments(),
+                        period_begin_date,
+                        &config,
+                    ),
+                    error: None,
+                }
+            }
+            Err(e) => ScenarioReport {
+                coverage: Coverage::default(),
+                violations: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        };
 
-        let client = MockDataServiceClient::new();
-        let svc = make_service(repo, client);
+        Ok(SimulationReport::new(baseline, simulated))
+    }
 
-        let output = svc.get_status(Uuid::new_v4()).await;
+    /// Attach name and position of every assigned staff member to `result.staff`.
+    #[tracing::instrument(skip_all, fields(schedule_id = %result.schedule_id))]
+    pub async fn hydrate_staff(
+        &self,
+        result: &mut ScheduleResult,
+    ) -> Result<(), SchedulingServiceError> {
+        let mut ids: Vec<Uuid> = result.assignments.iter().map(|a| a.staff_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let staff = self.data_client.get_staff_by_ids(ids).await?;
+        result.staff = Some(
+            staff
+                .iter()
+                .map(|s| (s.id, StaffSummary::from(s)))
+                .collect(),
+        );
 
-        assert!(output.is_err());
-        assert!(matches!(
-            output.unwrap_err(),
-            SchedulingServiceError::NotFound(_)
-        ));
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn get_result_not_completed() {
-        let mut repo = MockJobRepository::new();
-        let job = make_job(JobStatus::Processing);
-        let job_id = job.id;
-        repo.expect_find_by_id()
-            .returning(move |_| Ok(Some(job.clone())));
-
-        let client = MockDataServiceClient::new();
-        let svc = make_service(repo, client);
+    /// Sort the result's assignments, fetching the staff names a `StaffName` order needs
+    /// unless `hydrate_staff` already did.
+    pub async fn order_assignments(
+        &self,
+        result: &mut ScheduleResult,
+        order: AssignmentOrder,
+    ) -> Result<(), SchedulingServiceError> {
+        let names: HashMap<Uuid, String> = match (order, &result.staff) {
+            (AssignmentOrder::StaffName, Some(staff)) => {
+                staff.iter().map(|(id, s)| (*id, s.name.clone())).collect()
+            }
+            (AssignmentOrder::StaffName, None) => {
+                let mut ids: Vec<Uuid> = result.assignments.iter().map(|a| a.staff_id).collect();
+                ids.sort_unstable();
+                ids.dedup();
+                self.data_client
+                    .get_staff_by_ids(ids)
+                    .await?
+                    .into_iter()
+                    .map(|s| (s.id, s.name))
+                    .collect()
+            }
+            _ => HashMap::new(),
+        };
+        sort_assignments(&mut result.assignments, order, &names);
 
-        let output = svc.get_result(job_id).await;
+        Ok(())
+    }
 
-        assert!(output.is_err());
-        assert!(matches!(
-            output.unwrap_err(),
-            SchedulingServiceError::BadRequest(_)
-        ));
+    #[tracing::instrument(skip(self))]
+    pub async fn payroll_export(
+        &self,
+        job_id: Uuid,
+        locale: Option<Locale>,
+        order: AssignmentOrder,
+    ) -> Result<String, SchedulingServiceError> {
+        let mut result = self.get_result(job_id).await?;
+        self.order_assignments(&mut result, order).await?;
+        let locale = self
+            .config
+            .localization
+            .resolve(locale, result.staff_group_id);
+
+        Ok(render_payroll_csv(
+            &result,
+            &self.config.shift_times,
+            &self.config.payroll_export,
+            locale,
+        ))
     }
 
-    #[tokio::test]
-    async fn get_result_returns_schedule_result_with_metadata() {
+    /// One staff member's assignments in a completed schedule, 404 when they have none.
+    #[tracing::instrument(skip(self))]
+    pub async fn staff_schedule(
+        &self,
+        job_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<StaffSchedule, SchedulingServiceError> {
+        let result = self.get_result(job_id).await?;
+        let assignments: Vec<_> = result
+            .assignments
+            .into_iter()
+            .filter(|a| a.staff_id == staff_id)
+            .collect();
+        if assignments.is_empty() {
+            return Err(SchedulingServiceError::NotFound(format!(
+                "Staff {staff_id} has no assignments in schedule {job_id}"
+            )));
+        }
+
+        Ok(StaffSchedule {
+            schedule_id: job_id,
+            staff_group_id: result.staff_group_id,
+            period_begin_date: result.period_begin_date,
+            staff_id,
+            assignments,
+        })
+    }
+
+    /// A staff member's shifts across every completed schedule, by default within the
+    /// `[calendar]` horizon around today.
+    #[tracing::instrument(skip(self))]
+    pub async fn staff_assignments(
+        &self,
+        staff_id: Uuid,
+        query: StaffAssignmentsQuery,
+    ) -> Result<StaffAssignments, SchedulingServiceError> {
+        let (horizon_from, horizon_to) = self
+            .config
+            .calendar
+            .horizon(shared::time::today_in(self.config.timezone()));
+        let from = query.from.unwrap_or(horizon_from);
+        let to = query.to.unwrap_or(horizon_to);
+        if from > to {
+            return Err(SchedulingServiceError::BadRequest(
+                "from must not be after to".to_string(),
+            ));
+        }
+        if (to - from).num_days() >= MAX_STAFF_ASSIGNMENT_DAYS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "from..=to must cover at most {MAX_STAFF_ASSIGNMENT_DAYS} days"
+            )));
+        }
+
+        let assignments = self
+            .job_repo
+            .get_staff_assignments(staff_id, from, to, true)
+            .await?;
+        Ok(StaffAssignments {
+            staff_id,
+            from,
+            to,
+            assignments,
+        })
+    }
+
+    /// A completed schedule as an iCalendar feed, optionally only one staff member's shifts.
+    #[tracing::instrument(skip(self))]
+    pub async fn result_calendar(
+        &self,
+        job_id: Uuid,
+        staff_id: Option<Uuid>,
+        locale: Option<Locale>,
+    ) -> Result<String, SchedulingServiceError> {
+        let mut result = self.get_result(job_id).await?;
+        if let Some(staff_id) = staff_id {
+            result.assignments.retain(|a| a.staff_id == staff_id);
+            if result.assignments.is_empty() {
+                return Err(SchedulingServiceError::NotFound(format!(
+                    "Staff {staff_id} has no assignments in schedule {job_id}"
+                )));
+            }
+        }
+        let locale = self
+            .config
+            .localization
+            .resolve(locale, result.staff_group_id);
+
+        let mut ids: Vec<Uuid> = result.assignments.iter().map(|a| a.staff_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let names: HashMap<Uuid, String> = match self.data_client.get_staff_by_ids(ids).await {
+            Ok(staff) => staff.into_iter().map(|s| (s.id, s.name)).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to fetch staff names for calendar: {e}");
+                HashMap::new()
+            }
+        };
+
+        let entries: Vec<_> = result
+            .assignments
+            .iter()
+            .map(|assignment| CalendarEntry {
+                assignment,
+                staff_name: names.get(&assignment.staff_id).map(String::as_str),
+            })
+            .collect();
+        let name = match staff_id.and_then(|id| names.get(&id)) {
+            Some(staff_name) => locale.staff_calendar_name(staff_name, result.period_begin_date),
+            None => locale.schedule_calendar_name(result.period_begin_date),
+        };
+
+        Ok(render_calendar(
+            &name,
+            &entries,
+            self.config.timezone(),
+            &self.config.shift_times,
+            locale,
+            chrono::Utc::now(),
+        ))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn create_share_link(
+        &self,
+        job_id: Uuid,
+        expires_in_hours: Option<u32>,
+    ) -> Result<ShareLink, SchedulingServiceError> {
+        let signer = self.share_signer.as_ref().ok_or_else(|| {
+            SchedulingServiceError::Internal("Share links are not configured".to_string())
+        })?;
+
+        let ttl_hours = expires_in_hours.unwrap_or(DEFAULT_SHARE_LINK_TTL_HOURS);
+        if ttl_hours == 0 || ttl_hours > MAX_SHARE_LINK_TTL_HOURS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "expires_in_hours must be between 1 and {MAX_SHARE_LINK_TTL_HOURS}"
+            )));
+        }
+
+        let job = self.get_status(job_id).await?;
+        if job.status != JobStatus::Completed {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Only completed schedules can be shared, current status: {:?}",
+                job.status
+            )));
+        }
+        if job.sandbox {
+            return Err(SchedulingServiceError::BadRequest(
+                "Sandbox schedules can't be shared".to_string(),
+            ));
+        }
+        if self.job_repo.get_published_at(job_id).await?.is_none() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Only published schedules can be shared".to_string(),
+            ));
+        }
+
+        Ok(signer.issue_for(job_id, ttl_hours, chrono::Utc::now()))
+    }
+
+    /// Published schedules are what staff saw and acknowledged; edits go into a new job.
+    async fn ensure_unpublished(&self, job_id: Uuid) -> Result<(), SchedulingServiceError> {
+        if self.job_repo.get_published_at(job_id).await?.is_some() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Published schedules can't be edited".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_approval_status(
+        &self,
+        job_id: Uuid,
+    ) -> Result<ApprovalStatus, SchedulingServiceError> {
+        let job = self.get_status(job_id).await?;
+        self.approval_status(&job).await
+    }
+
+    async fn approval_status(
+        &self,
+        job: &ScheduleJob,
+    ) -> Result<ApprovalStatus, SchedulingServiceError> {
+        let (decisions, published_at) = tokio::try_join!(
+            self.job_repo.get_approvals(job.id),
+            self.job_repo.get_published_at(job.id)
+        )?;
+        Ok(ApprovalStatus::new(
+            job,
+            self.config.approvals.groups.get(&job.staff_group_id),
+            decisions,
+            published_at,
+        ))
+    }
+
+    /// Completed, non-sandbox job: the only kind that goes through sign-off and publishing.
+    async fn publishable_job(&self, job_id: Uuid) -> Result<ScheduleJob, SchedulingServiceError> {
+        let job = self.get_status(job_id).await?;
+        if job.status != JobStatus::Completed {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Only completed schedules can be published, current status: {:?}",
+                job.status
+            )));
+        }
+        if job.sandbox {
+            return Err(SchedulingServiceError::BadRequest(
+                "Sandbox schedules can't be published".to_string(),
+            ));
+        }
+        Ok(job)
+    }
+
+    /// Approve or reject a draft as one of its group's approvers. Deciding again replaces the
+    /// approver's earlier decision.
+    #[tracing::instrument(skip(self, request))]
+    pub async fn decide_approval(
+        &self,
+        job_id: Uuid,
+        approver_id: Uuid,
+        decision: ApprovalDecision,
+        request: ApprovalRequest,
+    ) -> Result<ApprovalStatus, SchedulingServiceError> {
+        let comment = request
+            .comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+        if comment.is_some_and(|c| c.chars().count() > MAX_APPROVAL_COMMENT_CHARS) {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "comment must be at most {MAX_APPROVAL_COMMENT_CHARS} characters"
+            )));
+        }
+        if decision == ApprovalDecision::Rejected && comment.is_none() {
+            return Err(SchedulingServiceError::BadRequest(
+                "A rejection needs a comment".to_string(),
+            ));
+        }
+
+        let job = self.publishable_job(job_id).await?;
+        let approvers = self
+            .config
+            .approvals
+            .groups
+            .get(&job.staff_group_id)
+            .ok_or_else(|| {
+                SchedulingServiceError::BadRequest(format!(
+                    "Schedules of group {} don't need approval",
+                    job.staff_group_id
+                ))
+            })?;
+        if !approvers.approvers.contains(&approver_id) {
+            return Err(SchedulingServiceError::Forbidden(format!(
+                "Staff {approver_id} is not an approver of group {}",
+                job.staff_group_id
+            )));
+        }
+        if self.job_repo.get_published_at(job_id).await?.is_some() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Schedule is already published".to_string(),
+            ));
+        }
+
+        self.job_repo
+            .record_approval(
+                job_id,
+                approver_id,
+                decision,
+                comment.map(str::to_string),
+                job.result_checksum.clone(),
+            )
+            .await?;
+        tracing::info!(?decision, "Approval recorded");
+
+        self.approval_status(&job).await
+    }
+
+    /// Publish a draft once it has the approvals its group requires.
+    #[tracing::instrument(skip(self))]
+    pub async fn publish(&self, job_id: Uuid) -> Result<ApprovalStatus, SchedulingServiceError> {
+        let job = self.publishable_job(job_id).await?;
+        let mut status = self.approval_status(&job).await?;
+        let already_published =
+            || SchedulingServiceError::BadRequest("Schedule is already published".to_string());
+        if status.published_at.is_some() {
+            return Err(already_published());
+        }
+        if !status.can_publish {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Schedule needs {} approvals to publish, has {} and {} rejections",
+                status.required, status.approved, status.rejected
+            )));
+        }
+
+        let published_at = self
+            .job_repo
+            .publish_job(job_id)
+            .await?
+            .ok_or_else(already_published)?;
+        tracing::info!("Schedule published");
+
+        status.published_at = Some(published_at);
+        status.can_publish = false;
+        Ok(status)
+    }
+
+    /// Published, non-sandbox job and when it was published: what staff acknowledge.
+    async fn published_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<(ScheduleJob, DateTime<Utc>), SchedulingServiceError> {
+        let job = self.publishable_job(job_id).await?;
+        let published_at = self
+            .job_repo
+            .get_published_at(job_id)
+            .await?
+            .ok_or_else(|| {
+                SchedulingServiceError::BadRequest("Schedule isn't published yet".to_string())
+            })?;
+        Ok((job, published_at))
+    }
+
+    /// Record that a staff member on the roster has seen the published schedule.
+    #[tracing::instrument(skip(self))]
+    pub async fn acknowledge(
+        &self,
+        job_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<AcknowledgmentStatus, SchedulingServiceError> {
+        let (job, published_at) = self.published_job(job_id).await?;
+        let mut staff = self.job_repo.get_acknowledgments(job.id).await?;
+        let entry = staff
+            .iter_mut()
+            .find(|s| s.staff_id == staff_id)
+            .ok_or_else(|| {
+                SchedulingServiceError::BadRequest(format!(
+                    "Staff {staff_id} has no shifts in this schedule"
+                ))
+            })?;
+        entry.acknowledged_at = Some(
+            self.job_repo
+                .record_acknowledgment(job.id, staff_id)
+                .await?,
+        );
+
+        Ok(AcknowledgmentStatus::new(job.id, published_at, staff))
+    }
+
+    pub async fn get_acknowledgments(
+        &self,
+        job_id: Uuid,
+    ) -> Result<AcknowledgmentStatus, SchedulingServiceError> {
+        let (job, published_at) = self.published_job(job_id).await?;
+        let staff = self.job_repo.get_acknowledgments(job.id).await?;
+        Ok(AcknowledgmentStatus::new(job.id, published_at, staff))
+    }
+
+    pub async fn get_outstanding_acknowledgments(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<Vec<OutstandingAcknowledgments>, SchedulingServiceError> {
+        self.job_repo
+            .find_outstanding_acknowledgments(Some(staff_group_id))
+            .await
+    }
+
+    /// Send one reminder per published schedule with missing acknowledgments that is due
+    /// one. Returns how many were sent.
+    pub async fn send_acknowledgment_reminders(&self) -> Result<usize, SchedulingServiceError> {
+        let interval_hours = self.config.acknowledgments.reminder_interval_hours;
+        if interval_hours == 0 || self.notifiers.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let mut sent = 0;
+        for schedule in self.job_repo.find_outstanding_acknowledgments(None).await? {
+            if !schedule.reminder_due(interval_hours, now)
+                || !self
+                    .job_repo
+                    .mark_acknowledgment_reminder(schedule.schedule_id, schedule.last_reminded_at)
+                    .await?
+            {
+                continue;
+            }
+            let event = JobEvent {
+                job_id: schedule.schedule_id,
+                staff_group_id: schedule.staff_group_id,
+                period_begin_date: schedule.period_begin_date,
+                status: JobStatus::Completed,
+                error: None,
+                unacknowledged: Some(schedule.outstanding),
+                occurred_at: now,
+            };
+            dispatch(&self.notifiers, event).await;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Read-only result behind a share token, once the schedule is published. Every rejection
+    /// looks the same to the caller.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_shared_result(
+        &self,
+        token: &str,
+    ) -> Result<ScheduleResult, SchedulingServiceError> {
+        let not_found =
+            || SchedulingServiceError::NotFound("Share link is invalid or has expired".to_string());
+
+        let signer = self.share_signer.as_ref().ok_or_else(not_found)?;
+        let job_id = signer.verify(token, chrono::Utc::now()).map_err(|e| {
+            tracing::warn!("Rejected share token: {e}");
+            not_found()
+        })?;
+        if self.job_repo.get_published_at(job_id).await?.is_none() {
+            return Err(not_found());
+        }
+
+        self.get_result(job_id).await.map_err(|e| match e {
+            SchedulingServiceError::NotFound(_) | SchedulingServiceError::BadRequest(_) => {
+                not_found()
+            }
+            other => other,
+        })
+    }
+
+    /// The next Monday, in the configured timezone, that can be submitted for the group
+    /// without overlapping one of its non-failed jobs.
+    #[tracing::instrument(skip(self))]
+    pub async fn next_period(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<NextPeriod, SchedulingServiceError> {
+        let timezone = self.config.timezone();
+        let today = shared::time::today_in(timezone);
+        let jobs = self
+            .job_repo
+            .find_overlapping_jobs(
+                staff_group_id,
+                earliest_overlapping_begin(today),
+                NaiveDate::MAX,
+            )
+            .await?;
+
+        let period_begin_date = next_unscheduled_monday(today, &jobs);
+        Ok(NextPeriod {
+            staff_group_id,
+            period_begin_date,
+            period_end_date: period_begin_date + TimeDelta::days(PERIOD_DAYS as i64 - 1),
+            timezone: timezone.name().to_string(),
+        })
+    }
+
+    /// Calendar feed of all published assignments of a group within the configured horizon.
+    #[tracing::instrument(skip(self))]
+    pub async fn group_calendar(
+        &self,
+        staff_group_id: Uuid,
+        locale: Option<Locale>,
+    ) -> Result<String, SchedulingServiceError> {
+        let timezone = self.config.timezone();
+        let locale = self.config.localization.resolve(locale, staff_group_id);
+        let (from, to) = self
+            .config
+            .calendar
+            .horizon(shared::time::today_in(timezone));
+
+        let assignments = self
+            .job_repo
+            .get_group_assignments(staff_group_id, from, to)
+            .await?;
+
+        // Names make the feed readable on a ward display; fall back to ids if unavailable
+        let names: HashMap<Uuid, String> = match self
+            .data_client
+            .get_resolved_members(staff_group_id, false)
+            .await
+        {
+            Ok(members) => members
+                .into_iter()
+                .map(|m| (m.staff.id, m.staff.name))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to fetch staff names for calendar: {e}");
+                HashMap::new()
+            }
+        };
+
+        let entries: Vec<_> = assignments
+            .iter()
+            .map(|assignment| CalendarEntry {
+                assignment,
+                staff_name: names.get(&assignment.staff_id).map(String::as_str),
+            })
+            .collect();
+
+        Ok(render_calendar(
+            &locale.group_calendar_name(staff_group_id),
+            &entries,
+            timezone,
+            &self.config.shift_times,
+            locale,
+            chrono::Utc::now(),
+        ))
+    }
+
+    /// Jobs that ended `Failed`, by creation time. Sandbox jobs are left out.
+    #[tracing::instrument(skip(self))]
+    pub async fn list_failed_jobs(
+        &self,
+        order: SortOrder,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError> {
+        let mut jobs = self.job_repo.find_by_status(JobStatus::Failed).await?;
+        jobs.retain(|job| !job.sandbox);
+        if order == SortOrder::Desc {
+            jobs.reverse();
+        }
+        Ok(jobs)
+    }
+
+    /// Jobs of any status, filtered and paged, for operators keeping an eye on the queue.
+    #[tracing::instrument(skip(self))]
+    pub async fn list_jobs(
+        &self,
+        query: JobQuery,
+        page: PageRequest,
+    ) -> Result<JobPage, SchedulingServiceError> {
+        page.validate(MAX_JOB_PAGE_SIZE)
+            .map_err(SchedulingServiceError::BadRequest)?;
+        if let (Some(from), Some(to)) = (query.from, query.to)
+            && from > to
+        {
+            return Err(SchedulingServiceError::BadRequest(
+                "from must not be after to".to_string(),
+            ));
+        }
+        self.job_repo.find_paginated(query, page).await
+    }
+
+    /// Status counts, processing time and failure rate of the jobs created in the window.
+    #[tracing::instrument(skip(self))]
+    pub async fn job_stats(
+        &self,
+        query: JobStatsQuery,
+    ) -> Result<JobStats, SchedulingServiceError> {
+        let window_hours = query.window_hours.unwrap_or(DEFAULT_STATS_WINDOW_HOURS);
+        if !(1..=MAX_STATS_WINDOW_HOURS).contains(&window_hours) {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "window_hours must be between 1 and {MAX_STATS_WINDOW_HOURS}"
+            )));
+        }
+        let since = Utc::now() - TimeDelta::hours(i64::from(window_hours));
+        let counts = self
+            .job_repo
+            .job_stats(since, query.staff_group_id, query.include_sandbox)
+            .await?;
+        Ok(JobStats::new(since, window_hours, counts))
+    }
+
+    /// Jobs waiting to be picked up and jobs being generated, across all replicas.
+    #[tracing::instrument(skip(self))]
+    pub async fn job_queue(&self) -> Result<JobQueue, SchedulingServiceError> {
+        let pending = self.job_repo.find_by_status(JobStatus::Pending).await?;
+        let mut processing = Vec::new();
+        for job in self.job_repo.find_by_status(JobStatus::Processing).await? {
+            let events = self.job_repo.get_job_events(job.id).await?;
+            processing.push((job, events));
+        }
+        Ok(JobQueue::new(pending, processing, Utc::now()))
+    }
+
+    /// Rule overrides of a group, 404 when it uses `scheduling.toml` as is.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<GroupSchedulingConfig, SchedulingServiceError> {
+        self.job_repo
+            .get_group_config(staff_group_id)
+            .await?
+            .ok_or_else(|| {
+                SchedulingServiceError::NotFound(format!(
+                    "Group {staff_group_id} has no scheduling config overrides"
+                ))
+            })
+    }
+
+    /// Replace a group's rule overrides. They are checked merged over the current
+    /// `scheduling.toml`, so a group can't be left with rules no schedule could meet.
+    #[tracing::instrument(skip(self))]
+    pub async fn set_group_config(
+        &self,
+        staff_group_id: Uuid,
+        overrides: GroupRuleOverrides,
+    ) -> Result<GroupSchedulingConfig, SchedulingServiceError> {
+        overrides
+            .apply(&self.config)
+            .map_err(SchedulingServiceError::BadRequest)?;
+        self.job_repo
+            .upsert_group_config(staff_group_id, overrides)
+            .await
+    }
+
+    /// Drop a group's overrides, putting it back on `scheduling.toml`.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<(), SchedulingServiceError> {
+        if !self.job_repo.delete_group_config(staff_group_id).await? {
+            return Err(SchedulingServiceError::NotFound(format!(
+                "Group {staff_group_id} has no scheduling config overrides"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Re-run a failed job from scratch under the same id.
+    #[tracing::instrument(skip(self))]
+    pub async fn retry_job(&self, job_id: Uuid) -> Result<ScheduleJob, SchedulingServiceError> {
+        let job = self.get_status(job_id).await?;
+        if job.status != JobStatus::Failed {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Only failed jobs can be retried, current status: {:?}",
+                job.status
+            )));
+        }
+
+        let job = self
+            .job_repo
+            .reset_failed_job(job_id)
+            .await?
+            .ok_or_else(|| {
+                SchedulingServiceError::BadRequest(format!("Job {job_id} is already being retried"))
+            })?;
+
+        tracing::info!(%job_id, "Retrying failed job");
+        record_event(
+            self.job_repo.as_ref(),
+            job_id,
+            JobEventKind::Retried,
+            None,
+            None,
+        )
+        .await;
+        self.restart_job(job.clone()).await?;
+
+        Ok(job)
+    }
+
+    /// Start a job just reset to `Pending`, or leave it for its dependencies.
+    async fn restart_job(&self, job: ScheduleJob) -> Result<(), SchedulingServiceError> {
+        if job.depends_on.is_empty() {
+            let job_id = job.id;
+            let pending = PendingJob::from_schedule_job(job).ok_or_else(|| {
+                SchedulingServiceError::Internal(format!("Reset job {job_id} is no longer pending"))
+            })?;
+            self.spawn_process_job(pending);
+            Ok(())
+        } else {
+            self.start_if_dependencies_completed(job).await
+        }
+    }
+
+    /// Force a job's status outside the normal flow, recording who did it and why in its
+    /// timeline.
+    /// `Pending` drops the saved result and runs the job again; `Processing` is left to the
+    /// workers, `Completed` needs a saved result and published schedules can't be changed.
+    #[tracing::instrument(skip(self, request), fields(status = ?request.status))]
+    pub async fn override_status(
+        &self,
+        job_id: Uuid,
+        actor: &str,
+        request: StatusOverride,
+    ) -> Result<ScheduleJob, SchedulingServiceError> {
+        let reason = request.reason.trim();
+        if reason.is_empty() {
+            return Err(SchedulingServiceError::BadRequest(
+                "A reason is required".to_string(),
+            ));
+        }
+        if reason.chars().count() > MAX_OVERRIDE_REASON_CHARS {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Reason is longer than {MAX_OVERRIDE_REASON_CHARS} characters"
+            )));
+        }
+
+        let job = self.get_status(job_id).await?;
+        let (from, to) = (job.status.clone(), request.status);
+        if from == to {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Job is already {}",
+                to.as_str()
+            )));
+        }
+        if to == JobStatus::Processing {
+            return Err(SchedulingServiceError::BadRequest(
+                "Only a worker can move a job to PROCESSING; use PENDING to run it again"
+                    .to_string(),
+            ));
+        }
+        if to == JobStatus::Completed && job.result_checksum.is_none() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Job has no saved result to complete with".to_string(),
+            ));
+        }
+        if self.job_repo.get_published_at(job_id).await?.is_some() {
+            return Err(SchedulingServiceError::BadRequest(
+                "Published schedules can't be overridden".to_string(),
+            ));
+        }
+
+        let job = self
+            .job_repo
+            .override_status(job_id, from.clone(), to.clone())
+            .await?
+            .ok_or_else(|| {
+                SchedulingServiceError::BadRequest(format!(
+                    "Job {job_id} changed status meanwhile, check it and try again"
+                ))
+            })?;
+
+        tracing::warn!(%job_id, from = from.as_str(), to = to.as_str(), actor, reason, "Job status overridden");
+        record_event(
+            self.job_repo.as_ref(),
+            job_id,
+            JobEventKind::Overridden,
+            Some(format!(
+                "{} -> {} by {actor}: {reason}",
+                from.as_str(),
+                to.as_str()
+            )),
+            None,
+        )
+        .await;
+        self.status_updates
+            .publish(JobStatusUpdate::new(job_id, to.clone(), None));
+        if to == JobStatus::Pending {
+            self.restart_job(job.clone()).await?;
+        }
+
+        Ok(job)
+    }
+
+    /// Startup recovery: restart the jobs abandoned by stopped replicas, including this one's
+    /// previous run, and the jobs that were still waiting for a slot or a dependency.
+    #[tracing::instrument(skip(self))]
+    pub async fn recover_stale_jobs(&self) -> Result<(), SchedulingServiceError> {
+        let _guard = match &self.task_lock {
+            Some(lock) => match lock.try_acquire(RECOVER_STALE_JOBS).await? {
+                Some(guard) => Some(guard),
+                None => {
+                    tracing::info!("Another replica is recovering stale jobs, skipping");
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+
+        // Read before the abandoned jobs below are reset to Pending and started
+        let queued = self.job_repo.find_by_status(JobStatus::Pending).await?;
+        // Nothing this process runs can be Processing yet, so this replica's jobs are leftovers
+        let recovered = self.restart_abandoned_jobs(true).await?;
+        if recovered == 0 {
+            tracing::info!("No stale jobs to recover");
+        }
+
+        // Jobs still waiting for a slot when the last process stopped
+        let mut requeued = 0;
+        for job in queued.into_iter().filter(|j| j.depends_on.is_empty()) {
+            if self.runner().claim_and_spawn(job).await? {
+                requeued += 1;
+            }
+        }
+        if requeued > 0 {
+            tracing::info!(count = requeued, "Requeued jobs that never started");
+        }
+
+        // A dependency may have completed while nothing was running to start its dependents
+        let released = self.runner().release_dependents(None).await?;
+        if released > 0 {
+            tracing::info!(
+                count = released,
+                "Started jobs whose dependencies completed"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Restart the `Processing` jobs whose worker stopped sending heartbeats, e.g. because its
+    /// replica was killed mid-run. Jobs still running, on this replica or another, keep their
+    /// heartbeat fresh and are left alone. Returns how many were restarted.
+    #[tracing::instrument(skip(self))]
+    pub async fn recover_abandoned_jobs(&self) -> Result<usize, SchedulingServiceError> {
+        let _guard = match &self.task_lock {
+            Some(lock) => match lock.try_acquire(RECOVER_STALE_JOBS).await? {
+                Some(guard) => Some(guard),
+                None => return Ok(0),
+            },
+            None => None,
+        };
+        self.restart_abandoned_jobs(false).await
+    }
+
+    /// With `include_own`, also the jobs left `Processing` under this replica's worker id,
+    /// whatever their heartbeat.
+    async fn restart_abandoned_jobs(
+        &self,
+        include_own: bool,
+    ) -> Result<usize, SchedulingServiceError> {
+        let worker_id = self
+            .worker_id
+            .as_deref()
+            .filter(|_| include_own)
+            .map(str::to_string);
+        let abandoned = self
+            .job_repo
+            .reset_abandoned_jobs(Utc::now() - JOB_HEARTBEAT_TIMEOUT, worker_id)
+            .await?;
+        if !abandoned.is_empty() {
+            tracing::info!(count = abandoned.len(), "Recovering abandoned jobs");
+        }
+
+        let recovered = abandoned.len();
+        for job in abandoned {
+            let job_id = job.id;
+            tracing::info!(%job_id, "Recovering abandoned job");
+            record_event(
+                self.job_repo.as_ref(),
+                job_id,
+                JobEventKind::Recovered,
+                Some("worker stopped sending heartbeats".to_string()),
+                None,
+            )
+            .await;
+
+            match PendingJob::from_schedule_job(job) {
+                Some(pending) => self.spawn_process_job(pending),
+                None => tracing::warn!(%job_id, "Job no longer in Pending status after reset"),
+            }
+        }
+        Ok(recovered)
+    }
+
+    /// Store a historical roster as completed jobs, one per Monday-based period, so rules that
+    /// look back at past schedules also see the time before this service was in use. Periods
+    /// that already have a non-failed job are refused rather than merged.
+    #[tracing::instrument(skip(self, csv))]
+    pub async fn import_history(
+        &self,
+        staff_group_id: Uuid,
+        csv: &str,
+    ) -> Result<HistoryImport, SchedulingServiceError> {
+        let today = shared::time::today_in(self.config.timezone());
+        let (rows, periods) = parse_history_csv(csv, today)?;
+
+        let (Some(first), Some(last)) = (periods.first(), periods.last()) else {
+            return Err(SchedulingServiceError::BadRequest(
+                "CSV has no rows".to_string(),
+            ));
+        };
+        let existing = self
+            .job_repo
+            .find_overlapping_jobs(
+                staff_group_id,
+                earliest_overlapping_begin(first.period_begin_date),
+                last.period_begin_date + TimeDelta::days(PERIOD_DAYS as i64 - 1),
+            )
+            .await?;
+        let clash = existing.iter().find(|job| {
+            periods
+                .iter()
+                .any(|p| overlaps(job, p.period_begin_date, 1))
+        });
+        if let Some(job) = clash {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "Period starting {} overlaps job {}",
+                job.period_begin_date, job.id
+            )));
+        }
+
+        let jobs = self
+            .job_repo
+            .import_completed_jobs(staff_group_id, periods)
+            .await?;
+        tracing::info!(%staff_group_id, rows, periods = jobs.len(), "Imported roster history");
+        Ok(HistoryImport { rows, jobs })
+    }
+
+    /// Backfill for `[storage] compact_assignments`: pack up to `limit` completed jobs still
+    /// stored as per-day rows, in id order after `after`. Each job is packed on its own, so
+    /// an interrupted run loses nothing and can resume from `next_after`.
+    #[tracing::instrument(skip(self))]
+    pub async fn pack_assignments(
+        &self,
+        query: PackAssignmentsQuery,
+    ) -> Result<PackReport, SchedulingServiceError> {
+        let limit = query.limit.unwrap_or(DEFAULT_PACK_LIMIT);
+        if !(1..=MAX_PACK_LIMIT).contains(&limit) {
+            return Err(SchedulingServiceError::BadRequest(format!(
+                "limit must be between 1 and {MAX_PACK_LIMIT}"
+            )));
+        }
+
+        let jobs = self.job_repo.find_unpacked_jobs(query.after, limit).await?;
+        let mut report = PackReport {
+            scanned: jobs.len(),
+            next_after: (jobs.len() as i64 == limit)
+                .then(|| jobs.last().copied())
+                .flatten(),
+            ..Default::default()
+        };
+        for job_id in jobs {
+            match self.job_repo.pack_job(job_id).await? {
+                Some(rows) => {
+                    report.packed_jobs += 1;
+                    report.packed_rows += rows;
+                }
+                None => report.skipped.push(job_id),
+            }
+        }
+        tracing::info!(
+            scanned = report.scanned,
+            packed_jobs = report.packed_jobs,
+            packed_rows = report.packed_rows,
+            skipped = report.skipped.len(),
+            "Packed stored assignments"
+        );
+        Ok(report)
+    }
+
+    /// Look up every staff id in stored assignments in the data-service and report the ones
+    /// it no longer knows, e.g. after a hard delete. With `annotate`, leave a note on each
+    /// affected schedule.
+    #[tracing::instrument(skip(self))]
+    pub async fn check_staff_references(
+        &self,
+        query: ConsistencyCheckQuery,
+    ) -> Result<ConsistencyReport, SchedulingServiceError> {
+        let references = self.job_repo.find_staff_references().await?;
+        let ids: Vec<Uuid> = references.iter().map(|r| r.staff_id).collect();
+        let known: HashSet<Uuid> = self
+            .data_client
+            .get_staff_by_ids(ids.clone())
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+
+        let mut report = ConsistencyReport {
+            checked_staff: ids.len(),
+            orphaned: find_orphans(references, &known),
+            ..Default::default()
+        };
+        if query.annotate {
+            for (job_id, staff_ids) in orphans_by_job(&report.orphaned) {
+                let body = orphan_note(&staff_ids);
+                let noted = self
+                    .job_repo
+                    .get_notes(job_id)
+                    .await?
+                    .iter()
+                    .any(|note| note.author == CONSISTENCY_NOTE_AUTHOR && note.body == body);
+                if !noted {
+                    self.job_repo
+                        .add_note(job_id, CONSISTENCY_NOTE_AUTHOR.to_string(), body)
+                        .await?;
+                    report.annotated_jobs += 1;
+                }
+            }
+        }
+        tracing::info!(
+            checked_staff = report.checked_staff,
+            orphaned = report.orphaned.len(),
+            annotated_jobs = report.annotated_jobs,
+            "Checked staff references"
+        );
+        Ok(report)
+    }
+
+    /// Delete sandbox jobs older than `sandbox_retention_hours`, unless some replica already
+    /// did within `interval`. Returns how many.
+    #[tracing::instrument(skip(self))]
+    pub async fn purge_sandbox_jobs(
+        &self,
+        interval: Duration,
+    ) -> Result<u64, SchedulingServiceError> {
+        let _guard = match &self.task_lock {
+            Some(lock) => match lock.try_acquire_due(PURGE_SANDBOX_JOBS, interval).await? {
+                Some(guard) => Some(guard),
+                None => return Ok(0),
+            },
+            None => None,
+        };
+
+        let cutoff = chrono::Utc::now() - self.config.sandbox_retention();
+        let purged = self.job_repo.purge_sandbox_jobs(cutoff).await?;
+        if purged > 0 {
+            tracing::info!(count = purged, "Purged expired sandbox jobs");
+        }
+        Ok(purged)
+    }
+
+    /// Refresh the job count gauges from the database on `interval`.
+    pub async fn run_job_metrics(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.job_repo.count_by_status().await {
+                Ok(counts) => {
+                    for status in [
+                        JobStatus::Pending,
+                        JobStatus::Processing,
+                        JobStatus::Completed,
+                        JobStatus::Failed,
+                    ] {
+                        let count = counts
+                            .iter()
+                            .find(|(s, _)| *s == status)
+                            .map_or(0, |(_, count)| *count);
+                        shared::metrics::set_job_count(status.as_str(), count);
+                    }
+                }
+                Err(e) => tracing::warn!("Job count refresh failed: {e}"),
+            }
+        }
+    }
+
+    pub async fn run_acknowledgment_reminders(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.send_acknowledgment_reminders().await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!(sent, "Sent acknowledgment reminders"),
+                Err(e) => tracing::warn!("Acknowledgment reminders failed: {e}"),
+            }
+        }
+    }
+
+    pub async fn run_sandbox_purge(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.purge_sandbox_jobs(interval).await {
+                tracing::warn!("Sandbox job purge failed: {e}");
+            }
+        }
+    }
+
+    /// Restart jobs abandoned by replicas that stopped while this one keeps running. The
+    /// first tick is skipped: startup recovery covers it.
+    pub async fn run_job_recovery(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.recover_abandoned_jobs().await {
+                tracing::warn!("Abandoned job recovery failed: {e}");
+            }
+        }
+    }
+}
+
+/// How often the worker running a job tells other replicas it is still alive.
+const JOB_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// A `Processing` job whose heartbeat is older than this has lost its worker.
+const JOB_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What a spawned job needs, detached from the service so a finished job can start the jobs
+/// that were waiting on it.
+#[derive(Clone)]
+struct JobRunner {
+    repo: Arc<dyn JobRepository>,
+    client: Arc<dyn DataServiceClient>,
+    config: SchedulingConfig,
+    notifiers: Arc<Vec<Arc<dyn Notifier>>>,
+    callbacks: bool,
+    task_tracker: TaskTracker,
+    status_updates: JobStatusUpdates,
+    worker_id: Option<Arc<str>>,
+    job_slots: Arc<Semaphore>,
+}
+
+impl JobRunner {
+    fn spawn(&self, pending_job: PendingJob) {
+        let job_id = pending_job.id();
+        let staff_group_id = pending_job.inner().staff_group_id;
+        let period_begin_date = pending_job.inner().period_begin_date;
+        let sandbox = pending_job.inner().sandbox;
+        let version = pending_job.inner().version;
+        let runner = self.clone();
+        let timeout = self.config.job_timeout();
+
+        let span = tracing::info_span!("process_job", %job_id, %staff_group_id);
+        shared::telemetry::attach_baggage(
+            &span,
+            [(shared::telemetry::JOB_ID_KEY, job_id.to_string())],
+        );
+        self.task_tracker.spawn(
+            async move {
+                // The job stays Pending until a slot frees up; the timeout starts after
+                let _slot = runner.job_slots.acquire().await;
+                // Recovery on another replica may have started it meanwhile
+                let worker_id = runner.worker_id.as_deref().map(str::to_string);
+                match runner
+                    .repo
+                    .start_pending_job(job_id, version, worker_id)
+                    .await
+                {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::info!("Job {job_id} was started elsewhere, skipping");
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::error!("Starting job {job_id} failed: {e}");
+                        return;
+                    }
+                }
+                let started = Instant::now();
+                let job = process_job(
+                    pending_job,
+                    Arc::clone(&runner.repo),
+                    Arc::clone(&runner.client),
+                    runner.config.clone(),
+                    &runner.status_updates,
+                    runner.worker_id.as_deref(),
+                );
+                let worker_id = runner.worker_id.as_deref();
+                let job = with_heartbeat(job_id, runner.repo.as_ref(), worker_id, job);
+                let output =
+                    run_with_watchdog(job_id, timeout, Arc::clone(&runner.repo), worker_id, job)
+                        .await;
+                // Its outcome belongs to the worker that took it over
+                if let Err(SchedulingServiceError::Conflict(e)) = &output {
+                    tracing::warn!("Job {job_id} was taken over by another worker: {e}");
+                    return;
+                }
+                if let Err(e) = &output {
+                    tracing::error!("Job {job_id} failed: {e}");
+                }
+                let completed = output.is_ok();
+                let (kind, detail) = match &output {
+                    Ok(()) => (JobEventKind::Completed, None),
+                    Err(e) => (JobEventKind::Failed, Some(e.to_string())),
+                };
+                record_event(
+                    runner.repo.as_ref(),
+                    job_id,
+                    kind,
+                    detail,
+                    Some(elapsed_ms(started)),
+                )
+                .await;
+
+                let error = output.as_ref().err().map(|e| e.to_string());
+                runner.status_updates.publish(JobStatusUpdate::new(
+                    job_id,
+                    if completed {
+                        JobStatus::Completed
+                    } else {
+                        JobStatus::Failed
+                    },
+                    error.clone(),
+                ));
+                if runner.callbacks
+                    && let Err(e) = runner.enqueue_callback(job_id, error.clone()).await
+                {
+                    tracing::error!("Queueing the callback of {job_id} failed: {e}");
+                }
+
+                if !runner.notifiers.is_empty() && !sandbox {
+                    let event = JobEvent {
+                        job_id,
+                        staff_group_id,
+                        period_begin_date,
+                        status: if completed {
+                            JobStatus::Completed
+                        } else {
+                            JobStatus::Failed
+                        },
+                        error,
+                        unacknowledged: None,
+                        occurred_at: chrono::Utc::now(),
+                    };
+                    dispatch(&runner.notifiers, event).await;
+                }
+
+                // Dependents of a failed job keep waiting: retrying it releases them
+                if completed && let Err(e) = runner.release_dependents(Some(job_id)).await {
+                    tracing::error!("Starting jobs waiting on {job_id} failed: {e}");
+                }
+            }
+            .instrument(span),
+        );
+    }
+
+    /// Queue the outcome of a finished job for its callback URL, when it was given one: the
+    /// result if it completed, otherwise `error`.
+    async fn enqueue_callback(
+        &self,
+        job_id: Uuid,
+        error: Option<String>,
+    ) -> Result<(), SchedulingServiceError> {
+        let Some(url) = self.repo.get_callback_url(job_id).await? else {
+            return Ok(());
+        };
+        let (status, result) = match &error {
+            Some(_) => (JobStatus::Failed, None),
+            None => {
+                let job = self.repo.find_by_id(job_id).await?.ok_or_else(|| {
+                    SchedulingServiceError::NotFound(format!("Schedule job {job_id} not found"))
+                })?;
+                let result =
+                    build_result(self.repo.as_ref(), self.client.as_ref(), &self.config, job)
+                        .await?;
+                (JobStatus::Completed, Some(result))
+            }
+        };
+        let payload = serde_json::to_string(&JobCallback {
+            job_id,
+            status,
+            result,
+            error,
+            occurred_at: chrono::Utc::now(),
+        })
+        .map_err(|e| SchedulingServiceError::Internal(format!("Failed to encode callback: {e}")))?;
+
+        self.repo.enqueue_callback(job_id, url, payload).await
+    }
+
+    /// Start the `Pending` jobs whose dependencies have all completed, only those waiting on
+    /// `dependency` when set. Returns how many were started.
+    async fn release_dependents(
+        &self,
+        dependency: Option<Uuid>,
+    ) -> Result<usize, SchedulingServiceError> {
+        let mut started = 0;
+        for job in self.repo.find_ready_dependents(dependency).await? {
+            let job_id = job.id;
+            if self.claim_and_spawn(job).await? {
+                tracing::info!(%job_id, "Dependencies completed, starting job");
+                started += 1;
+            }
+        }
+        Ok(started)
+    }
+
+    /// Start a `Pending` job as it was read. Two dependencies completing at once (or two
+    /// replicas) can both find it ready, so only the one whose claim lands starts it.
+    async fn claim_and_spawn(&self, job: ScheduleJob) -> Result<bool, SchedulingServiceError> {
+        let claimed = self.repo.claim_pending_job(job.id, job.version).await?;
+        match claimed.and_then(PendingJob::from_schedule_job) {
+            Some(pending) => {
+                self.spawn(pending);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// The result of a completed job, with its metrics.
+async fn build_result(
+    repo: &dyn JobRepository,
+    client: &dyn DataServiceClient,
+    config: &SchedulingConfig,
+    job: ScheduleJob,
+) -> Result<ScheduleResult, SchedulingServiceError> {
+    let assignments = repo.get_assignments(job.id).await?;
+
+    if let Some(stored) = &job.result_checksum {
+        let actual = assignments_checksum(
+            assignments
+                .iter()
+                .map(|a| (a.staff_id, a.date, &a.shift_type)),
+        );
+        if *stored != actual {
+            tracing::error!(job_id = %job.id, %stored, %actual, "Assignment checksum mismatch");
+        }
+    }
+
+    let notes = repo.get_notes(job.id).await?;
+    let borrowed_staff = repo.get_borrowed_staff(job.id).await?;
+    let coverage_shortfalls = repo.get_coverage_shortfalls(job.id).await?;
+    let unfilled = repo.get_unfilled_shifts(job.id).await?;
+    let positions = target_positions(client, config, &assignments).await;
+
+    let metrics = |assignments: &[&ShiftAssignment]| {
+        let mut weekend_days_off: HashMap<Uuid, u32> = HashMap::new();
+        for a in assignments {
+            let days_off = weekend_days_off.entry(a.staff_id).or_default();
+            if a.shift_type == ShiftType::DayOff && is_weekend(a.date) {
+                *days_off += 1;
+            }
+        }
+        ScheduleMetrics {
+            weekend_days_off,
+            weekend_weighting: config.fairness.describe(),
+            shift_targets: config
+                .shift_targets
+                .deviations(assignments.iter().copied(), &positions),
+        }
+    };
+    let periods = if job.periods > 1 {
+        (0..job.periods)
+            .map(|period| {
+                let begin =
+                    job.period_begin_date + TimeDelta::days(PERIOD_DAYS as i64 * i64::from(period));
+                let end = begin + TimeDelta::days(PERIOD_DAYS as i64 - 1);
+                let in_period: Vec<&ShiftAssignment> = assignments
+                    .iter()
+                    .filter(|a| (begin..=end).contains(&a.date))
+                    .collect();
+                SchedulePeriod {
+                    period_begin_date: begin,
+                    period_end_date: end,
+                    assignments: in_period.len(),
+                    metrics: metrics(&in_period),
+                }
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let metrics = metrics(&assignments.iter().collect::<Vec<_>>());
+
+    Ok(ScheduleResult {
+        schema_version: shared::types::RESULT_SCHEMA_VERSION,
+        schedule_id: job.id,
+        period_begin_date: job.period_begin_date,
+        staff_group_id: job.staff_group_id,
+        checksum: job.result_checksum,
+        assignments,
+        notes,
+        metrics,
+        periods,
+        borrowed_staff,
+        coverage_shortfalls,
+        unfilled,
+        assignment_groups: vec![],
+        staff: None,
+    })
+}
+
+/// Current positions of the assigned staff, for position shift targets. Only fetched when
+/// some are configured; if the data-service is down the result still loads, reporting
+/// staff targets only.
+async fn target_positions(
+    client: &dyn DataServiceClient,
+    config: &SchedulingConfig,
+    assignments: &[ShiftAssignment],
+) -> HashMap<Uuid, String> {
+    if config.shift_targets.positions.is_empty() {
+        return HashMap::new();
+    }
+    let mut ids: Vec<Uuid> = assignments.iter().map(|a| a.staff_id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    match client.get_staff_by_ids(ids).await {
+        Ok(staff) => staff.into_iter().map(|s| (s.id, s.position)).collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not fetch positions for shift target metrics");
+            HashMap::new()
+        }
+    }
+}
+
+/// Drive `job` to completion within `timeout`.
+///
+/// When the budget runs out the job future is dropped and the job is marked `Failed`,
+/// so a stuck job can't hold a worker (or stay `Processing`) forever.
+async fn run_with_watchdog(
+    job_id: Uuid,
+    timeout: Duration,
+    repo: Arc<dyn JobRepository>,
+    worker_id: Option<&str>,
+    job: impl Future<Output = Result<(), SchedulingServiceError>>,
+) -> Result<(), SchedulingServiceError> {
+    match tokio::time::timeout(timeout, job).await {
+        Ok(output) => output,
+        Err(_) => {
+            tracing::warn!(%job_id, timeout_secs = timeout.as_secs(), "Job exceeded time budget");
+            let error = SchedulingServiceError::JobTimeout(format!(
+                "Job {job_id} exceeded processing time budget of {}s",
+                timeout.as_secs()
+            ));
+            repo.mark_failed(job_id, error.to_string(), worker_id.map(str::to_string))
+                .await
+                .ok();
+            Err(error)
+        }
+    }
+}
+
+/// Drive `job`, refreshing its heartbeat every `JOB_HEARTBEAT_INTERVAL` so recovery on other
+/// replicas leaves it alone. A missed heartbeat only gets a warning.
+async fn with_heartbeat<T>(
+    job_id: Uuid,
+    repo: &dyn JobRepository,
+    worker_id: Option<&str>,
+    job: impl Future<Output = T>,
+) -> T {
+    let mut job = std::pin::pin!(job);
+    let mut ticker = tokio::time::interval(JOB_HEARTBEAT_INTERVAL);
+    // The first tick is immediate, and starting the job already set the heartbeat
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            output = &mut job => return output,
+            _ = ticker.tick() => {
+                if let Err(e) = repo.heartbeat(job_id, worker_id.map(str::to_string)).await {
+                    tracing::warn!(%job_id, "Job heartbeat failed: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Add a step to the job's timeline. Best effort: the timeline explains a job, it must not
+/// fail one.
+async fn record_event(
+    repo: &dyn JobRepository,
+    job_id: Uuid,
+    kind: JobEventKind,
+    detail: Option<String>,
+    duration_ms: Option<i64>,
+) {
+    if let Err(e) = repo
+        .record_job_event(job_id, kind, detail, duration_ms)
+        .await
+    {
+        tracing::warn!(%job_id, ?kind, "Failed to record job event: {e}");
+    }
+}
+
+fn elapsed_ms(started: Instant) -> i64 {
+    started.elapsed().as_millis().try_into().unwrap_or(i64::MAX)
+}
+
+/// `config` with the group's overrides merged over it, when it has any. Overrides that
+/// stopped making sense after `scheduling.toml` changed are a 400 rather than a job that
+/// can't be generated.
+async fn group_config(
+    repo: &dyn JobRepository,
+    config: &SchedulingConfig,
+    staff_group_id: Uuid,
+) -> Result<SchedulingConfig, SchedulingServiceError> {
+    match repo.get_group_config(staff_group_id).await? {
+        Some(group) => group.overrides.apply(config).map_err(|e| {
+            SchedulingServiceError::BadRequest(format!(
+                "Scheduling config overrides of group {staff_group_id}: {e}"
+            ))
+        }),
+        None => Ok(config.clone()),
+    }
+}
+
+/// Weekend days off and evening shifts of the group's recent schedules before
+/// `period_begin_date`. Best effort: without it the period is balanced on its own.
+async fn shift_history(
+    repo: &dyn JobRepository,
+    config: &SchedulingConfig,
+    staff_group_id: Uuid,
+    period_begin_date: NaiveDate,
+) -> HashMap<Uuid, ShiftHistory> {
+    if config.fairness.history_periods == 0 {
+        return HashMap::new();
+    }
+    match repo
+        .get_shift_history(
+            staff_group_id,
+            period_begin_date,
+            config.fairness.history_periods.into(),
+        )
+        .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|row| {
+                let history = ShiftHistory {
+                    weekend_days_off: row.weekend_days_off.try_into().unwrap_or(u32::MAX),
+                    evening_shifts: row.evening_shifts.try_into().unwrap_or(u32::MAX),
+                };
+                (row.staff_id, history)
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to load fairness history: {e}");
+            HashMap::new()
+        }
+    }
+}
+
+/// Members of the float pool who can be lent to a group with `roster`, in the order to try
+/// them. The pool's time off and the days they work in other schedules stay free; with no
+/// history in the group they count as average, like newcomers.
+async fn float_pool_roster(
+    repo: &dyn JobRepository,
+    client: &dyn DataServiceClient,
+    pool_group_id: Uuid,
+    roster: &[RosterMember],
+    history: &HashMap<Uuid, ShiftHistory>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<RosterMember>, SchedulingServiceError> {
+    let members = client.get_resolved_members(pool_group_id, true).await?;
+    let time_off = client.get_time_off(pool_group_id, from, to).await?;
+    let mut pool: Vec<_> = members
+        .iter()
+        .filter(|m| m.staff.status == StaffStatus::Active)
+        .map(RosterMember::from)
+        .collect();
+    apply_time_off(&mut pool, &time_off, from, to);
+
+    let mut worked_elsewhere = HashMap::new();
+    for member in &pool {
+        // Drafts count: lending someone a draft already has double-books them once it's published
+        let assignments = repo
+            .get_staff_assignments(member.staff_id, from, to, false)
+            .await?;
+        worked_elsewhere.insert(member.staff_id, assignments);
+    }
+    let mut candidates = roster.to_vec();
+    candidates.extend(lendable(pool, roster, &worked_elsewhere));
+    apply_history(&mut candidates, history);
+    Ok(candidates.split_off(roster.len()))
+}
+
+#[tracing::instrument(
+    skip(pending_job, repo, client, config, status_updates),
+    fields(job_id = %pending_job.id())
+)]
+async fn process_job(
+    pending_job: PendingJob,
+    repo: Arc<dyn JobRepository>,
+    client: Arc<dyn DataServiceClient>,
+    config: SchedulingConfig,
+    status_updates: &JobStatusUpdates,
+    worker_id: Option<&str>,
+) -> Result<(), SchedulingServiceError> {
+    tracing::info!("Processing job");
+
+    // Moved to Processing in the database by whoever started it
+    let (processing_job, job_id, status) = pending_job.start_processing();
+    status_updates.publish(JobStatusUpdate::new(job_id, status, None));
+    record_event(
+        repo.as_ref(),
+        job_id,
+        JobEventKind::PickedUp,
+        worker_id.map(str::to_string),
+        None,
+    )
+    .await;
+
+    let staff_group_id = processing_job.staff_group_id();
+    let period_begin_date = processing_job.period_begin_date();
+    let periods = processing_job.periods();
+    let coverage = processing_job.shift_coverage();
+
+    let period_end_date = period_begin_date + TimeDelta::days(horizon_days(periods) - 1);
+    let started = Instant::now();
+    let fetched = async {
+        let config = group_config(repo.as_ref(), &config, staff_group_id)
+            .await?
+            .with_shift_coverage(coverage);
+        let members = client.get_resolved_members(staff_group_id, true).await?;
+        let blackouts = client
+            .get_blackouts(staff_group_id, period_begin_date, period_end_date)
+            .await?;
+        let time_off = client
+            .get_time_off(staff_group_id, period_begin_date, period_end_date)
+            .await?;
+        Ok::<_, SchedulingServiceError>((config, members, blackouts, time_off))
+    }
+    .await;
+    let (config, members, blackouts, time_off) = match fetched {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            let (_failed, id, _) = processing_job.fail();
+            repo.mark_failed(id, e.to_string(), worker_id.map(str::to_string))
+                .await
+                .ok();
+            return Err(e);
+        }
+    };
+    record_event(
+        repo.as_ref(),
+        job_id,
+        JobEventKind::DataFetched,
+        Some(format!(
+            "{} members, {} blackouts, {} time off requests",
+            members.len(),
+            blackouts.len(),
+            time_off.len()
+        )),
+        Some(elapsed_ms(started)),
+    )
+    .await;
+
+    // The data-service already filters by status; re-check in case an older version ignores it
+    let mut roster: Vec<_> = members
+        .iter()
+        .filter(|m| m.staff.status == StaffStatus::Active)
+        .map(RosterMember::from)
+        .collect();
+    let history = shift_history(repo.as_ref(), &config, staff_group_id, period_begin_date).await;
+    apply_history(&mut roster, &history);
+    apply_time_off(&mut roster, &time_off, period_begin_date, period_end_date);
+
+    // Generation is CPU-bound: keep it off the async workers so the watchdog can still fire
+    let started = Instant::now();
+    let rules = Arc::new(config.build_rules_for_group(staff_group_id, blackouts.clone()));
+    let generated = {
+        let (roster, rules, config) = (roster.clone(), Arc::clone(&rules), config.clone());
+        tokio::task::spawn_blocking(move || {
+            gen_horizon(
+                &roster,
+                period_begin_date,
+                periods as usize,
+                &rules,
+                &config,
+            )
+        })
+        .await
+        .map_err(|e| {
+            SchedulingServiceError::Internal(format!("Schedule generation panicked: {e}"))
+        })?
+    };
+
+    let mut borrowed = Vec::new();
+    let generated = match (generated, borrowing_pool(&config, staff_group_id)) {
+        (Err(e), Some(pool_group_id)) => {
+            tracing::info!(%pool_group_id, "Own staff fall short ({e}), borrowing from the float pool");
+            let pool = float_pool_roster(
+                repo.as_ref(),
+                client.as_ref(),
+                pool_group_id,
+                &roster,
+                &history,
+                period_begin_date,
+                period_end_date,
+            )
+            .await
+            .unwrap_or_else(|fetch_error| {
+                tracing::warn!("Failed to load the float pool: {fetch_error}");
+                Vec::new()
+            });
+            let (in_group, rules, pool_config) = (roster.clone(), rules, config.clone());
+            let outcome = tokio::task::spawn_blocking(move || {
+                gen_borrowing(
+                    &in_group,
+                    &pool,
+                    period_begin_date,
+                    periods as usize,
+                    &rules,
+                    &pool_config,
+                )
+            })
+            .await
+            .map_err(|e| {
+                SchedulingServiceError::Internal(format!("Schedule generation panicked: {e}"))
+            })?;
+            match outcome {
+                Some((assignments, staff_ids)) => {
+                    borrowed = staff_ids
+                        .into_iter()
+                        .map(|staff_id| BorrowedStaff {
+                            staff_id,
+                            pool_group_id,
+                        })
+                        .collect();
+                    Ok(assignments)
+                }
+                None => Err(e),
+            }
+        }
+        (generated, _) => generated,
+    };
+
+    let mut shortfalls = Vec::new();
+    let generated = match generated {
+        Err(e)
+            if config.coverage_shortfall != ShortfallBehavior::Fail
+                && has_coverage_minimums(&config, staff_group_id) =>
+        {
+            tracing::warn!("Coverage minimums can't be met ({e}), generating without them");
+            let rules = config.build_relaxed_rules_for_group(staff_group_id, blackouts);
+            let unfilled = config.coverage_shortfall == ShortfallBehavior::Unfilled;
+            let relaxed = tokio::task::spawn_blocking(move || {
+                // The rules still aim for the minimums; left in the config they would turn a
+                // roster that's too small away up front
+                let generator_config = SchedulingConfig {
+                    shift_coverage: ShiftCoverage::default(),
+                    ..config.clone()
+                };
+                let assignments = gen_horizon(
+                    &roster,
+                    period_begin_date,
+                    periods as usize,
+                    &rules,
+                    &generator_config,
+                )?;
+                let shortfalls =
+                    coverage_shortfalls(&assignments, &roster, &config, staff_group_id, unfilled);
+                Ok::<_, SchedulingError>((assignments, shortfalls))
+            })
+            .await
+            .map_err(|e| {
+                SchedulingServiceError::Internal(format!("Schedule generation panicked: {e}"))
+            })?;
+            match relaxed {
+                Ok((assignments, found)) => {
+                    shortfalls = found;
+                    Ok(assignments)
+                }
+                Err(_) => Err(e),
+            }
+        }
+        generated => generated,
+    };
+
+    match generated {
+        Ok(assignments) => {
+            let mut detail = format!("{} assignments", assignments.len());
+            if !borrowed.is_empty() {
+                detail.push_str(&format!(", {} staff borrowed", borrowed.len()));
+            }
+            if !shortfalls.is_empty() {
+                detail.push_str(&format!(", {} coverage shortfalls", shortfalls.len()));
+            }
+            record_event(
+                repo.as_ref(),
+                job_id,
+                JobEventKind::Generated,
+                Some(detail),
+                Some(elapsed_ms(started)),
+            )
+            .await;
+            let checksum = assignments_checksum(
+                assignments
+                    .iter()
+                    .map(|a| (a.staff_id, a.date, &a.shift_type)),
+            );
+            let started = Instant::now();
+            repo.save_assignments(
+                job_id,
+                assignments,
+                checksum,
+                borrowed,
+                shortfalls,
+                worker_id.map(str::to_string),
+            )
+            .await?;
+            record_event(
+                repo.as_ref(),
+                job_id,
+                JobEventKind::Saved,
+                None,
+                Some(elapsed_ms(started)),
+            )
+            .await;
+            let (_completed, id, status) = processing_job.complete();
+            repo.update_status(id, status, worker_id.map(str::to_string))
+                .await?;
+            tracing::info!("Job completed");
+        }
+        Err(e) => {
+            tracing::error!("Scheduling failed: {e}");
+            let error = SchedulingServiceError::Internal(format!("Scheduling failed: {e}"));
+            let (_failed, id, _) = processing_job.fail();
+            repo.mark_failed(id, error.to_string(), worker_id.map(str::to_string))
+                .await
+                .ok();
+            return Err(error);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::client::MockDataServiceClient;
+    use crate::domain::coverage::unfilled_shifts;
+    use crate::domain::float_pool::FloatPoolConfig;
+    use crate::domain::job::{MockJobRepository, NewShiftAssignment};
+    use crate::domain::lock::MockTaskLock;
+    use crate::domain::notify::MockNotifier;
+    use crate::domain::scheduler::SchedulingConfig;
+    use crate::domain::targets::ShiftTarget;
+    use crate::testing::{RosterBuilder, SchedulingConfigBuilder};
+    use shared::types::GroupMember;
+    use shared::types::{ShiftAssignment, ShiftEligibility};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn make_service(
+        job_repo: MockJobRepository,
+        data_client: MockDataServiceClient,
+    ) -> SchedulingService {
+        SchedulingService::new(
+            Arc::new(job_repo),
+            Arc::new(data_client),
+            SchedulingConfig::default(),
+        )
+    }
+
+    fn make_job(status: JobStatus) -> ScheduleJob {
+        ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            status,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_schedule_rejects_non_monday() {
+        let repo = MockJobRepository::new();
+        let client = MockDataServiceClient::new();
+        let svc = make_service(repo, client);
+
+        // 2026-02-17 is Tuesday
+        let tuesday = NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+        let output = svc
+            .submit_schedule(Uuid::new_v4(), tuesday, vec![], JobOptions::default())
+            .await;
+
+        assert!(output.is_err());
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::BadRequest(_)
+        ));
+    }
+
+    fn next_monday() -> NaiveDate {
+        let today = chrono::Utc::now().date_naive();
+        today + TimeDelta::days(7 - today.weekday().num_days_from_monday() as i64)
+    }
+
+    #[tokio::test]
+    async fn submit_schedule_rejects_unknown_dependency() {
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+        repo.expect_find_by_id().returning(|_| Ok(None));
+        repo.expect_create_job().never();
+
+        let svc = make_service(repo, MockDataServiceClient::new());
+        let output = svc
+            .submit_schedule(
+                Uuid::new_v4(),
+                next_monday(),
+                vec![Uuid::new_v4()],
+                JobOptions::default(),
+            )
+            .await;
+
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::BadRequest(msg) if msg.contains("Dependency job")
+        ));
+    }
+
+    #[tokio::test]
+    async fn submit_schedule_rejects_sandbox_dependency_of_real_job() {
+        let dependency = ScheduleJob {
+            sandbox: true,
+            ..make_job(JobStatus::Completed)
+        };
+        let dependency_id = dependency.id;
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(dependency.clone())));
+        repo.expect_create_job().never();
+
+        let svc = make_service(repo, MockDataServiceClient::new());
+        let output = svc
+            .submit_schedule(
+                Uuid::new_v4(),
+                next_monday(),
+                vec![dependency_id],
+                JobOptions::default(),
+            )
+            .await;
+
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::BadRequest(msg) if msg.contains("is a sandbox job")
+        ));
+    }
+
+    #[tokio::test]
+    async fn submit_schedule_waits_for_unfinished_dependency() {
+        let dependency = make_job(JobStatus::Processing);
+        let dependency_id = dependency.id;
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(dependency.clone())));
+        repo.expect_find_overlapping_jobs()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_create_job()
+            .withf(move |_, _, depends_on, _| depends_on == &[dependency_id])
+            .returning(|staff_group_id, period_begin_date, depends_on, _| {
+                Ok(ScheduleJob {
+                    staff_group_id,
+                    period_begin_date,
+                    depends_on,
+                    ..make_job(JobStatus::Pending)
+                })
+            });
+        repo.expect_claim_pending_job().never();
+        repo.expect_update_status().never();
+
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_count_resolved_members()
+            .returning(|_, _| Ok(5));
+
+        let svc = make_service(repo, client);
+        let submitted = svc
+            .submit_schedule(
+                Uuid::new_v4(),
+                next_monday(),
+                vec![dependency_id, dependency_id],
+                JobOptions::default(),
+            )
+            .await
+            .unwrap();
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+
+        assert_eq!(submitted.job.status, JobStatus::Pending);
+        assert_eq!(submitted.job.depends_on, vec![dependency_id]);
+    }
+
+    #[tokio::test]
+    async fn get_status_not_found() {
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_by_id().returning(|_| Ok(None));
+
+        let client = MockDataServiceClient::new();
+        let svc = make_service(repo, client);
+
+        let output = svc.get_status(Uuid::new_v4()).await;
+
+        assert!(output.is_err());
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_result_not_completed() {
+        let mut repo = MockJobRepository::new();
+        let job = make_job(JobStatus::Processing);
+        let job_id = job.id;
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(job.clone())));
+
+        let client = MockDataServiceClient::new();
+        let svc = make_service(repo, client);
+
+        let output = svc.get_result(job_id).await;
+
+        assert!(output.is_err());
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::BadRequest(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_result_returns_schedule_result_with_metadata() {
         let mut repo = MockJobRepository::new();
         let job = make_job(JobStatus::Completed);
         let job_id = job.id;
         let staff_group_id = job.staff_group_id;
         let period_begin_date = job.period_begin_date;
 
-        repo.expect_find_by_id()
-            .returning(move |_| Ok(Some(job.clone())));
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(job.clone())));
+
+        let assignment = ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id,
+            staff_id: Uuid::new_v4(),
+            date: period_begin_date,
+            shift_type: shared::types::ShiftType::Morning,
+            note: None,
+        };
+        let assignments = vec![assignment.clone()];
+        repo.expect_get_assignments()
+            .returning(move |_| Ok(assignments.clone()));
+        repo.expect_get_notes().returning(|_| Ok(vec![]));
+        repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+        repo.expect_get_coverage_shortfalls()
+            .returning(|_| Ok(vec![]));
+
+        repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+        let client = MockDataServiceClient::new();
+        let svc = make_service(repo, client);
+
+        let output = svc.get_result(job_id).await.unwrap();
+
+        assert_eq!(output.schedule_id, job_id);
+        assert_eq!(output.staff_group_id, staff_group_id);
+        assert_eq!(output.period_begin_date, period_begin_date);
+        assert_eq!(output.assignments.len(), 1);
+        assert_eq!(output.assignments[0].id, assignment.id);
+    }
+
+    #[tokio::test]
+    async fn get_result_reports_position_shift_targets() {
+        let mut repo = MockJobRepository::new();
+        let job = make_job(JobStatus::Completed);
+        let job_id = job.id;
+        let date = job.period_begin_date;
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(job.clone())));
+        let nurse = Uuid::new_v4();
+        let assignments: Vec<ShiftAssignment> = [ShiftType::Evening, ShiftType::Morning]
+            .into_iter()
+            .map(|shift_type| ShiftAssignment {
+                id: Uuid::new_v4(),
+                job_id,
+                staff_id: nurse,
+                date,
+                shift_type,
+                note: None,
+            })
+            .collect();
+        repo.expect_get_assignments()
+            .returning(move |_| Ok(assignments.clone()));
+        repo.expect_get_notes().returning(|_| Ok(vec![]));
+        repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+        repo.expect_get_coverage_shortfalls()
+            .returning(|_| Ok(vec![]));
+
+        repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_staff_by_ids()
+            .withf(move |ids| *ids == vec![nurse])
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![shared::types::Staff {
+                    id: nurse,
+                    name: "Nurse".to_string(),
+                    email: "nurse@example.com".to_string(),
+                    position: "Nurse".to_string(),
+                    fairness_weight: 1.0,
+                    shift_eligibility: ShiftEligibility::Any,
+                    status: StaffStatus::Active,
+                    effective_until: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                    version: 1,
+                }])
+            });
+        let mut config = SchedulingConfig::default();
+        config.shift_targets.positions.insert(
+            "Nurse".to_string(),
+            ShiftTarget {
+                morning: 0,
+                evening: 1,
+            },
+        );
+        let svc = SchedulingService::new(Arc::new(repo), Arc::new(client), config);
+
+        let metrics = svc.get_result(job_id).await.unwrap().metrics;
+        let deviation = &metrics.shift_targets[&nurse];
+        assert_eq!((deviation.morning_shifts, deviation.evening_shifts), (1, 1));
+        assert_eq!(deviation.deviation, 0.5);
+    }
+
+    #[tokio::test]
+    async fn process_job_happy_path() {
+        let job = make_job(JobStatus::Pending);
+        let period_begin_date = job.period_begin_date;
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_get_shift_history()
+            .withf(move |_, before, periods| *before == period_begin_date && *periods == 2)
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+
+        // Track status transitions
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let statuses_clone = statuses.clone();
+        repo.expect_update_status().returning(move |_, status, _| {
+            statuses_clone.lock().unwrap().push(status);
+            Ok(())
+        });
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        repo.expect_record_job_event()
+            .returning(move |_, kind, _, _| {
+                events_clone.lock().unwrap().push(kind);
+                Ok(())
+            });
+
+        // Capture saved assignments
+        let saved = Arc::new(Mutex::new(Vec::<NewShiftAssignment>::new()));
+        let saved_clone = saved.clone();
+        let saved_checksum = Arc::new(Mutex::new(String::new()));
+        let saved_checksum_clone = saved_checksum.clone();
+        repo.expect_save_assignments()
+            .returning(move |_, assignments, checksum, _, _, _| {
+                *saved_clone.lock().unwrap() = assignments;
+                *saved_checksum_clone.lock().unwrap() = checksum;
+                Ok(())
+            });
+
+        let mut client = MockDataServiceClient::new();
+        let staff_ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let staff: Vec<_> = staff_ids
+            .iter()
+            .enumerate()
+            .map(|(i, &id)| shared::types::Staff {
+                id,
+                name: format!("Staff {i}"),
+                email: format!("s{i}@example.com"),
+                position: "Nurse".to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                status: StaffStatus::Active,
+                effective_until: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                version: 1,
+            })
+            .collect();
+        client
+            .expect_get_resolved_members()
+            .returning(move |_, _| Ok(staff.iter().cloned().map(GroupMember::from).collect()));
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            SchedulingConfig::default(),
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_ok());
+
+        // Whoever started the job moved it to Processing; it ends Completed here
+        let recorded = statuses.lock().unwrap();
+        assert_eq!(*recorded, vec![JobStatus::Completed]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                JobEventKind::PickedUp,
+                JobEventKind::DataFetched,
+                JobEventKind::Generated,
+                JobEventKind::Saved,
+            ]
+        );
+
+        // Verify assignments were saved (4 staff * 28 days = 112)
+        let assignments = saved.lock().unwrap();
+        assert_eq!(assignments.len(), 4 * 28);
+
+        // Verify all staff have assignments
+        for &sid in &staff_ids {
+            let count = assignments.iter().filter(|a| a.staff_id == sid).count();
+            assert_eq!(count, 28, "Staff {sid} should have 28 assignments");
+        }
+
+        // Verify the checksum covers exactly the saved assignments
+        let expected = assignments_checksum(
+            assignments
+                .iter()
+                .map(|a| (a.staff_id, a.date, &a.shift_type)),
+        );
+        assert_eq!(*saved_checksum.lock().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn process_job_data_service_error_marks_failed() {
+        let job = make_job(JobStatus::Pending);
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let statuses_clone = statuses.clone();
+        repo.expect_update_status().returning(move |_, status, _| {
+            statuses_clone.lock().unwrap().push(status);
+            Ok(())
+        });
+        repo.expect_mark_failed()
+            .withf(|_, error, _| error.contains("Connection refused"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut client = MockDataServiceClient::new();
+        client.expect_get_resolved_members().returning(|_, _| {
+            Err(SchedulingServiceError::DataService(
+                "Connection refused".into(),
+            ))
+        });
+
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            SchedulingConfig::default(),
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_err());
+
+        // Failed with the reason through mark_failed, not a plain status update
+        assert!(statuses.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn process_job_filters_inactive_staff() {
+        let job = make_job(JobStatus::Pending);
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+
+        let saved = Arc::new(Mutex::new(Vec::<NewShiftAssignment>::new()));
+        let saved_clone = saved.clone();
+        repo.expect_save_assignments()
+            .returning(move |_, assignments, _, _, _, _| {
+                *saved_clone.lock().unwrap() = assignments;
+                Ok(())
+            });
+
+        let active_id = Uuid::new_v4();
+        let inactive_id = Uuid::new_v4();
+        let mut client = MockDataServiceClient::new();
+        let staff = [
+            shared::types::Staff {
+                id: active_id,
+                name: "Active".to_string(),
+                email: "a@example.com".to_string(),
+                position: "Nurse".to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                status: StaffStatus::Active,
+                effective_until: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                version: 1,
+            },
+            shared::types::Staff {
+                id: inactive_id,
+                name: "Inactive".to_string(),
+                email: "i@example.com".to_string(),
+                position: "Nurse".to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                status: StaffStatus::Inactive,
+                effective_until: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                version: 1,
+            },
+        ];
+        client
+            .expect_get_resolved_members()
+            .withf(|_, active_only| *active_only)
+            .returning(move |_, _| Ok(staff.iter().cloned().map(GroupMember::from).collect()));
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            SchedulingConfig::default(),
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_ok());
+
+        // Only the active staff member should have assignments
+        let assignments = saved.lock().unwrap();
+        assert_eq!(assignments.len(), 28);
+        assert!(assignments.iter().all(|a| a.staff_id == active_id));
+    }
+
+    #[tokio::test]
+    async fn process_job_uses_the_group_overrides() {
+        let job = make_job(JobStatus::Pending);
+        let staff_group_id = job.staff_group_id;
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config()
+            .withf(move |id| *id == staff_group_id)
+            .returning(move |_| {
+                Ok(Some(GroupSchedulingConfig {
+                    staff_group_id,
+                    overrides: GroupRuleOverrides {
+                        min_day_off_per_week: Some(3),
+                        max_day_off_per_week: Some(3),
+                        ..GroupRuleOverrides::default()
+                    },
+                    updated_at: chrono::Utc::now(),
+                }))
+            });
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+
+        let saved = Arc::new(Mutex::new(Vec::<NewShiftAssignment>::new()));
+        let saved_clone = saved.clone();
+        repo.expect_save_assignments()
+            .returning(move |_, assignments, _, _, _, _| {
+                *saved_clone.lock().unwrap() = assignments;
+                Ok(())
+            });
+
+        let mut client = MockDataServiceClient::new();
+        let staff = shared::types::Staff {
+            id: Uuid::new_v4(),
+            name: "Ward Nurse".to_string(),
+            email: "w@example.com".to_string(),
+            position: "Nurse".to_string(),
+            fairness_weight: 1.0,
+            shift_eligibility: ShiftEligibility::Any,
+            status: StaffStatus::Active,
+            effective_until: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
+        };
+        client
+            .expect_get_resolved_members()
+            .returning(move |_, _| Ok(vec![GroupMember::from(staff.clone())]));
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            SchedulingConfig::default(),
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_ok());
+
+        // Three days off in each of the four weeks, where scheduling.toml allows two at most
+        let assignments = saved.lock().unwrap();
+        let days_off = assignments
+            .iter()
+            .filter(|a| a.shift_type == ShiftType::DayOff)
+            .count();
+        assert_eq!(days_off, 12);
+    }
+
+    #[tokio::test]
+    async fn process_job_borrows_from_the_float_pool_when_short() {
+        let job = make_job(JobStatus::Pending);
+        let pool_group_id = Uuid::new_v4();
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+        let staff = RosterBuilder::new()
+            .with_staff("Nurse", 3)
+            .with_staff("Float", 4);
+        let mut ward = staff.group_members();
+        let pool = ward.split_off(3);
+        let pool_ids: Vec<Uuid> = pool.iter().map(|m| m.staff.id).collect();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_get_staff_assignments()
+            .withf(|_, _, _, published_only| !published_only)
+            .returning(|_, _, _, _| Ok(vec![]));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+
+        let saved = Arc::new(Mutex::new((Vec::new(), Vec::new())));
+        let saved_clone = saved.clone();
+        repo.expect_save_assignments()
+            .returning(move |_, assignments, _, borrowed, _, _| {
+                *saved_clone.lock().unwrap() = (assignments, borrowed);
+                Ok(())
+            });
+
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .returning(move |group_id, _| {
+                Ok(if group_id == pool_group_id {
+                    pool.clone()
+                } else {
+                    ward.clone()
+                })
+            });
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+        let config = SchedulingConfig {
+            float_pool: FloatPoolConfig {
+                staff_group_id: Some(pool_group_id),
+            },
+            ..SchedulingConfigBuilder::new()
+                .with_shift_coverage(2, 2)
+                .build()
+                .unwrap()
+        };
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            config,
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_ok());
+
+        let (assignments, borrowed) = &*saved.lock().unwrap();
+        assert!(!borrowed.is_empty());
+        assert!(
+            borrowed
+                .iter()
+                .all(|b| b.pool_group_id == pool_group_id && pool_ids.contains(&b.staff_id))
+        );
+        assert!(
+            assignments
+                .iter()
+                .any(|a| a.staff_id == borrowed[0].staff_id && a.shift_type != ShiftType::DayOff)
+        );
+    }
+
+    #[tokio::test]
+    async fn process_job_leaves_unfilled_shifts_when_coverage_falls_short() {
+        let pending = PendingJob::from_schedule_job(make_job(JobStatus::Pending)).unwrap();
+        let ward = RosterBuilder::new().with_staff("Nurse", 3).group_members();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+        repo.expect_mark_failed().never();
+
+        let saved = Arc::new(Mutex::new((Vec::new(), Vec::new())));
+        let saved_clone = saved.clone();
+        repo.expect_save_assignments()
+            .returning(move |_, assignments, _, _, shortfalls, _| {
+                *saved_clone.lock().unwrap() = (assignments, shortfalls);
+                Ok(())
+            });
 
-        let assignment = ShiftAssignment {
-            id: Uuid::new_v4(),
-            job_id,
-            staff_id: Uuid::new_v4(),
-            date: period_begin_date,
-            shift_type: shared::types::ShiftType::Morning,
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .returning(move |_, _| Ok(ward.clone()));
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+        let config = SchedulingConfig {
+            coverage_shortfall: ShortfallBehavior::Unfilled,
+            ..SchedulingConfigBuilder::new()
+                .with_shift_coverage(2, 2)
+                .build()
+                .unwrap()
         };
-        let assignments = vec![assignment.clone()];
-        repo.expect_get_assignments()
-            .returning(move |_| Ok(assignments.clone()));
+        let output = process_job(
+            pending,
+            Arc::new(repo),
+            Arc::new(client),
+            config,
+            &JobStatusUpdates::default(),
+            None,
+        )
+        .await;
+        assert!(output.is_ok());
 
-        let client = MockDataServiceClient::new();
-        let svc = make_service(repo, client);
+        // Three nurses can't put two on each shift every day and still get their days off
+        let (assignments, shortfalls) = &*saved.lock().unwrap();
+        assert_eq!(assignments.len(), 3 * PERIOD_DAYS);
+        assert!(!shortfalls.is_empty());
+        assert!(
+            shortfalls
+                .iter()
+                .all(|s| s.unfilled && s.position.is_none() && s.assigned < s.required)
+        );
+        assert!(unfilled_shifts(shortfalls).len() >= shortfalls.len());
+    }
 
-        let output = svc.get_result(job_id).await.unwrap();
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_marks_job_failed_on_timeout() {
+        let mut repo = MockJobRepository::new();
 
-        assert_eq!(output.schedule_id, job_id);
-        assert_eq!(output.staff_group_id, staff_group_id);
-        assert_eq!(output.period_begin_date, period_begin_date);
-        assert_eq!(output.assignments.len(), 1);
-        assert_eq!(output.assignments[0].id, assignment.id);
+        repo.expect_mark_failed()
+            .withf(|_, error, _| error.contains("exceeded processing time budget of 5s"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let stuck_job = std::future::pending::<Result<(), SchedulingServiceError>>();
+        let output = run_with_watchdog(
+            Uuid::new_v4(),
+            Duration::from_secs(5),
+            Arc::new(repo),
+            None,
+            stuck_job,
+        )
+        .await;
+
+        assert!(matches!(
+            output.unwrap_err(),
+            SchedulingServiceError::JobTimeout(_)
+        ));
     }
 
     #[tokio::test]
-    async fn process_job_happy_path() {
-        let job = make_job(JobStatus::Pending);
-        let pending = PendingJob::from_schedule_job(job).unwrap();
+    async fn watchdog_passes_through_job_output() {
+        let repo = MockJobRepository::new();
+
+        let output = run_with_watchdog(
+            Uuid::new_v4(),
+            Duration::from_secs(5),
+            Arc::new(repo),
+            None,
+            async { Ok(()) },
+        )
+        .await;
+
+        assert!(output.is_ok());
+    }
 
+    #[tokio::test]
+    async fn recover_stale_jobs_skips_when_another_replica_holds_the_lock() {
         let mut repo = MockJobRepository::new();
+        repo.expect_find_by_status().never();
 
-        // Track status transitions
-        let statuses = Arc::new(Mutex::new(Vec::new()));
-        let statuses_clone = statuses.clone();
-        repo.expect_update_status().returning(move |_, status| {
-            statuses_clone.lock().unwrap().push(status);
-            Ok(())
-        });
+        let mut lock = MockTaskLock::new();
+        lock.expect_try_acquire()
+            .withf(|name| name == RECOVER_STALE_JOBS)
+            .returning(|_| Ok(None));
 
-        // Capture saved assignments
-        let saved = Arc::new(Mutex::new(Vec::<NewShiftAssignment>::new()));
-        let saved_clone = saved.clone();
+        let svc = make_service(repo, MockDataServiceClient::new()).with_task_lock(Arc::new(lock));
+
+        assert!(svc.recover_stale_jobs().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn sandbox_purge_skips_when_a_replica_ran_it_within_the_interval() {
+        let mut repo = MockJobRepository::new();
+        repo.expect_purge_sandbox_jobs().never();
+
+        let mut lock = MockTaskLock::new();
+        lock.expect_try_acquire_due()
+            .withf(|name, interval| {
+                name == PURGE_SANDBOX_JOBS && *interval == Duration::from_secs(600)
+            })
+            .returning(|_, _| Ok(None));
+
+        let svc = make_service(repo, MockDataServiceClient::new()).with_task_lock(Arc::new(lock));
+
+        assert_eq!(
+            svc.purge_sandbox_jobs(Duration::from_secs(600))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn completed_job_starts_the_jobs_waiting_on_it() {
+        let job = make_job(JobStatus::Pending);
+        let job_id = job.id;
+        let dependent = ScheduleJob {
+            depends_on: vec![job_id],
+            ..make_job(JobStatus::Pending)
+        };
+        let dependent_id = dependent.id;
+
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let started_clone = Arc::clone(&started);
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+        repo.expect_start_pending_job().returning(move |id, _, _| {
+            started_clone.lock().unwrap().push(id);
+            Ok(true)
+        });
         repo.expect_save_assignments()
-            .returning(move |_, assignments| {
-                *saved_clone.lock().unwrap() = assignments;
-                Ok(())
+            .returning(|_, _, _, _, _, _| Ok(()));
+        repo.expect_find_ready_dependents()
+            .returning(move |dependency| {
+                Ok(if dependency == Some(job_id) {
+                    vec![dependent.clone()]
+                } else {
+                    vec![]
+                })
+            });
+        repo.expect_claim_pending_job()
+            .withf(move |id, _| *id == dependent_id)
+            .times(1)
+            .returning(|id, _| {
+                Ok(Some(ScheduleJob {
+                    id,
+                    ..make_job(JobStatus::Pending)
+                }))
             });
 
         let mut client = MockDataServiceClient::new();
-        let staff_ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
-        let staff: Vec<_> = staff_ids
-            .iter()
-            .enumerate()
-            .map(|(i, &id)| shared::types::Staff {
-                id,
-                name: format!("Staff {i}"),
-                email: format!("s{i}@example.com"),
-                position: "Nurse".to_string(),
-                status: StaffStatus::Active,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-            })
-            .collect();
+        client.expect_get_resolved_members().returning(|_, _| {
+            Ok((0..4)
+                .map(|i| shared::types::Staff {
+                    id: Uuid::new_v4(),
+                    name: format!("Staff {i}"),
+                    email: format!("s{i}@example.com"),
+                    position: "Nurse".to_string(),
+                    fairness_weight: 1.0,
+                    shift_eligibility: ShiftEligibility::Any,
+                    status: StaffStatus::Active,
+                    effective_until: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                    version: 1,
+                })
+                .map(GroupMember::from)
+                .collect())
+        });
         client
-            .expect_get_resolved_members()
-            .returning(move |_| Ok(staff.clone()));
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
 
-        let rules = Arc::new(SchedulingConfig::default().build_rules());
+        let svc = make_service(repo, client);
+        svc.spawn_process_job(PendingJob::from_schedule_job(job).unwrap());
+        // The dependent is spawned on the same tracker before the first task ends
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
 
-        let output = process_job(pending, Arc::new(repo), Arc::new(client), rules).await;
-        assert!(output.is_ok());
+        assert_eq!(*started.lock().unwrap(), vec![job_id, dependent_id]);
+    }
 
-        // Verify status transitions: Pending -> Processing -> Completed
-        let recorded = statuses.lock().unwrap();
-        assert_eq!(recorded.len(), 2);
-        assert_eq!(recorded[0], JobStatus::Processing);
-        assert_eq!(recorded[1], JobStatus::Completed);
+    #[tokio::test]
+    async fn recovery_skips_waiting_job_claimed_elsewhere() {
+        let waiting = ScheduleJob {
+            depends_on: vec![Uuid::new_v4()],
+            ..make_job(JobStatus::Pending)
+        };
 
-        // Verify assignments were saved (4 staff * 28 days = 112)
-        let assignments = saved.lock().unwrap();
-        assert_eq!(assignments.len(), 4 * 28);
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_by_status().returning(|_| Ok(vec![]));
+        repo.expect_reset_abandoned_jobs()
+            .returning(|_, _| Ok(vec![]));
+        repo.expect_find_ready_dependents()
+            .withf(|dependency| dependency.is_none())
+            .returning(move |_| Ok(vec![waiting.clone()]));
+        repo.expect_claim_pending_job()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        repo.expect_update_status().never();
+
+        let svc = make_service(repo, MockDataServiceClient::new());
+
+        assert!(svc.recover_stale_jobs().await.is_ok());
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+    }
 
-        // Verify all staff have assignments
-        for &sid in &staff_ids {
-            let count = assignments.iter().filter(|a| a.staff_id == sid).count();
-            assert_eq!(count, 28, "Staff {sid} should have 28 assignments");
+    #[tokio::test]
+    async fn job_stays_pending_until_a_slot_frees_up() {
+        let job = make_job(JobStatus::Pending);
+
+        let started = Arc::new(AtomicBool::new(false));
+        let started_clone = started.clone();
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_start_pending_job().returning(move |_, _, _| {
+            started_clone.store(true, Ordering::SeqCst);
+            Ok(true)
+        });
+        repo.expect_mark_failed().returning(|_, _, _| Ok(()));
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .returning(|_, _| Err(SchedulingServiceError::DataService("down".into())));
+
+        let svc = SchedulingService::new(
+            Arc::new(repo),
+            Arc::new(client),
+            SchedulingConfig {
+                max_concurrent_jobs: 1,
+                ..SchedulingConfig::default()
+            },
+        );
+        let busy = svc.job_slots.acquire().await.unwrap();
+        svc.spawn_process_job(PendingJob::from_schedule_job(job).unwrap());
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
         }
+        assert!(!started.load(Ordering::SeqCst));
+
+        drop(busy);
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+        assert!(started.load(Ordering::SeqCst));
     }
 
     #[tokio::test]
-    async fn process_job_data_service_error_marks_failed() {
+    async fn job_held_by_two_replicas_runs_once() {
         let job = make_job(JobStatus::Pending);
-        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        // The database lets only the first start through
+        let taken = Arc::new(AtomicBool::new(false));
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_start_pending_job()
+            .times(2)
+            .returning(move |_, _, _| Ok(!taken.swap(true, Ordering::SeqCst)));
+        repo.expect_mark_failed()
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .times(1)
+            .returning(|_, _| Err(SchedulingServiceError::DataService("down".into())));
+        let (repo, client): (Arc<dyn JobRepository>, Arc<dyn DataServiceClient>) =
+            (Arc::new(repo), Arc::new(client));
+
+        let replicas = [
+            SchedulingService::new(repo.clone(), client.clone(), SchedulingConfig::default()),
+            SchedulingService::new(repo, client, SchedulingConfig::default()),
+        ];
+        for replica in &replicas {
+            replica.spawn_process_job(PendingJob::from_schedule_job(job.clone()).unwrap());
+        }
+        for replica in &replicas {
+            replica.task_tracker().close();
+            replica.task_tracker().wait().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn recovery_requeues_jobs_that_never_started() {
+        let queued = make_job(JobStatus::Pending);
+        let queued_id = queued.id;
 
         let mut repo = MockJobRepository::new();
+        repo.expect_find_by_status()
+            .withf(|status| *status == JobStatus::Pending)
+            .returning(move |_| Ok(vec![queued.clone()]));
+        repo.expect_reset_abandoned_jobs()
+            .returning(|_, _| Ok(vec![]));
+        repo.expect_find_ready_dependents()
+            .returning(|_| Ok(vec![]));
+        // Claimed elsewhere meanwhile, so nothing is spawned
+        repo.expect_claim_pending_job()
+            .withf(move |id, version| *id == queued_id && *version == 1)
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let svc = make_service(repo, MockDataServiceClient::new());
+
+        assert!(svc.recover_stale_jobs().await.is_ok());
+    }
 
-        let statuses = Arc::new(Mutex::new(Vec::new()));
-        let statuses_clone = statuses.clone();
-        repo.expect_update_status().returning(move |_, status| {
-            statuses_clone.lock().unwrap().push(status);
-            Ok(())
-        });
+    #[tokio::test]
+    async fn recovery_restarts_only_jobs_whose_worker_stopped() {
+        let abandoned = make_job(JobStatus::Pending);
+        let abandoned_id = abandoned.id;
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_by_status().returning(|_| Ok(vec![]));
+        // Heartbeats within the last minute belong to jobs a live replica is running
+        repo.expect_reset_abandoned_jobs()
+            .withf(|stale_before, worker_id| {
+                let age = Utc::now() - *stale_before;
+                age >= TimeDelta::seconds(60)
+                    && age < TimeDelta::seconds(65)
+                    && worker_id.as_deref() == Some("worker-b")
+            })
+            .times(1)
+            .returning(move |_, _| Ok(vec![abandoned.clone()]));
+        repo.expect_record_job_event()
+            .withf(move |id, kind, _, _| *id == abandoned_id && *kind == JobEventKind::Recovered)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_start_pending_job()
+            .withf(move |id, _, worker_id| {
+                *id == abandoned_id && worker_id.as_deref() == Some("worker-b")
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(false));
+        repo.expect_find_ready_dependents()
+            .returning(|_| Ok(vec![]));
+
+        let svc = make_service(repo, MockDataServiceClient::new()).with_worker_id("worker-b");
+
+        assert!(svc.recover_stale_jobs().await.is_ok());
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+    }
+
+    #[tokio::test]
+    async fn periodic_recovery_leaves_this_replicas_running_job_alone() {
+        let running = make_job(JobStatus::Processing);
+        let heartbeat_at = Utc::now();
+
+        let mut repo = MockJobRepository::new();
+        // Resets like the query: a stale heartbeat, or the worker id that was passed in
+        repo.expect_reset_abandoned_jobs()
+            .times(1)
+            .returning(move |stale_before, worker_id| {
+                if heartbeat_at < stale_before || worker_id.as_deref() == Some("worker-b") {
+                    Ok(vec![running.clone()])
+                } else {
+                    Ok(vec![])
+                }
+            });
+        repo.expect_record_job_event().never();
+        repo.expect_start_pending_job().never();
+
+        let svc = make_service(repo, MockDataServiceClient::new()).with_worker_id("worker-b");
+
+        assert_eq!(svc.recover_abandoned_jobs().await.unwrap(), 0);
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+    }
+
+    #[tokio::test]
+    async fn job_taken_over_by_another_worker_reports_nothing() {
+        let job = make_job(JobStatus::Pending);
+        let job_id = job.id;
 
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_get_shift_history()
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_start_pending_job()
+            .returning(|_, _, _| Ok(true));
+        repo.expect_record_job_event()
+            .withf(|_, kind, _, _| !matches!(kind, JobEventKind::Completed | JobEventKind::Failed))
+            .returning(|_, _, _, _| Ok(()));
+        // Recovery reset the job while it was generating
+        repo.expect_save_assignments()
+            .withf(|_, _, _, _, _, worker_id| worker_id.as_deref() == Some("worker-b"))
+            .times(1)
+            .returning(move |_, _, _, _, _, _| {
+                Err(SchedulingServiceError::Conflict(format!(
+                    "Schedule job {job_id} is no longer processed by this worker"
+                )))
+            });
+        repo.expect_update_status().never();
+        repo.expect_mark_failed().never();
+        repo.expect_get_callback_url().never();
+        repo.expect_find_ready_dependents().never();
         let mut client = MockDataServiceClient::new();
-        client.expect_get_resolved_members().returning(|_| {
-            Err(SchedulingServiceError::DataService(
-                "Connection refused".into(),
-            ))
-        });
+        client
+            .expect_get_resolved_members()
+            .returning(|_, _| Ok(RosterBuilder::new().with_staff("Nurse", 6).group_members()));
+        client
+            .expect_get_blackouts()
+            .returning(|_, _, _| Ok(vec![]));
+        client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+        // The outcome is left to the worker running the job now
+        let mut notifier = MockNotifier::new();
+        notifier.expect_send().never();
+
+        let svc = make_service(repo, client)
+            .with_worker_id("worker-b")
+            .with_notifiers(vec![Arc::new(notifier)]);
+        svc.spawn_process_job(PendingJob::from_schedule_job(job).unwrap());
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+    }
 
-        let rules = Arc::new(SchedulingConfig::default().build_rules());
+    #[tokio::test(start_paused = true)]
+    async fn running_job_keeps_sending_heartbeats() {
+        let mut repo = MockJobRepository::new();
+        // At 15s and 30s into a 40s job
+        repo.expect_heartbeat().times(2).returning(|_, _| Ok(()));
 
-        let output = process_job(pending, Arc::new(repo), Arc::new(client), rules).await;
-        assert!(output.is_err());
+        let output = with_heartbeat(Uuid::new_v4(), &repo, None, async {
+            tokio::time::sleep(Duration::from_secs(40)).await;
+            "done"
+        })
+        .await;
 
-        // Verify status transitions: Pending -> Processing -> Failed
-        let recorded = statuses.lock().unwrap();
-        assert_eq!(recorded.len(), 2);
-        assert_eq!(recorded[0], JobStatus::Processing);
-        assert_eq!(recorded[1], JobStatus::Failed);
+        assert_eq!(output, "done");
     }
 
     #[tokio::test]
-    async fn process_job_filters_inactive_staff() {
+    async fn finished_job_is_sent_to_every_notifier() {
         let job = make_job(JobStatus::Pending);
+        let job_id = job.id;
         let pending = PendingJob::from_schedule_job(job).unwrap();
 
         let mut repo = MockJobRepository::new();
-        repo.expect_update_status().returning(|_, _| Ok(()));
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+        repo.expect_start_pending_job()
+            .returning(|_, _, _| Ok(true));
+        repo.expect_mark_failed().returning(|_, _, _| Ok(()));
 
-        let saved = Arc::new(Mutex::new(Vec::<NewShiftAssignment>::new()));
-        let saved_clone = saved.clone();
-        repo.expect_save_assignments()
-            .returning(move |_, assignments| {
-                *saved_clone.lock().unwrap() = assignments;
-                Ok(())
-            });
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .returning(|_, _| Err(SchedulingServiceError::DataService("down".to_string())));
+
+        let notifiers: Vec<Arc<dyn Notifier>> = (0..2)
+            .map(|_| {
+                let mut notifier = MockNotifier::new();
+                notifier.expect_channel().return_const("mock");
+                notifier
+                    .expect_send()
+                    .withf(move |event| {
+                        event.job_id == job_id
+                            && event.status == JobStatus::Failed
+                            && event.error.as_deref().is_some_and(|e| e.contains("down"))
+                    })
+                    .times(1)
+                    .returning(|_| Ok(()));
+                Arc::new(notifier) as Arc<dyn Notifier>
+            })
+            .collect();
+
+        let svc = make_service(repo, client).with_notifiers(notifiers);
+        svc.spawn_process_job(pending);
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+    }
+
+    #[tokio::test]
+    async fn watchers_see_a_job_go_through_processing_to_failed() {
+        let job = make_job(JobStatus::Pending);
+        let job_id = job.id;
+        let pending = PendingJob::from_schedule_job(job.clone()).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(job.clone())));
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+        repo.expect_start_pending_job()
+            .returning(|_, _, _| Ok(true));
+        repo.expect_mark_failed().returning(|_, _, _| Ok(()));
 
-        let active_id = Uuid::new_v4();
-        let inactive_id = Uuid::new_v4();
         let mut client = MockDataServiceClient::new();
-        let staff = vec![
-            shared::types::Staff {
-                id: active_id,
-                name: "Active".to_string(),
-                email: "a@example.com".to_string(),
-                position: "Nurse".to_string(),
-                status: StaffStatus::Active,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-            },
-            shared::types::Staff {
-                id: inactive_id,
-                name: "Inactive".to_string(),
-                email: "i@example.com".to_string(),
-                position: "Nurse".to_string(),
-                status: StaffStatus::Inactive,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-            },
-        ];
         client
             .expect_get_resolved_members()
-            .returning(move |_| Ok(staff.clone()));
+            .returning(|_, _| Err(SchedulingServiceError::DataService("down".to_string())));
 
-        let rules = Arc::new(SchedulingConfig::default().build_rules());
+        let svc = make_service(repo, client);
+        let mut watch = svc.watch_status(job_id).await.unwrap();
+        svc.spawn_process_job(pending);
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
+
+        let statuses = [
+            watch.next().await.unwrap(),
+            watch.next().await.unwrap(),
+            watch.next().await.unwrap(),
+        ];
+        assert_eq!(
+            statuses
+                .iter()
+                .map(|u| u.status.clone())
+                .collect::<Vec<_>>(),
+            [JobStatus::Pending, JobStatus::Processing, JobStatus::Failed]
+        );
+        assert!(
+            statuses[2]
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("down"))
+        );
+        assert!(watch.next().await.is_none());
+    }
 
-        let output = process_job(pending, Arc::new(repo), Arc::new(client), rules).await;
-        assert!(output.is_ok());
+    #[tokio::test]
+    async fn acknowledgment_reminders_go_out_once_per_due_schedule() {
+        use crate::domain::acknowledgment::OutstandingAcknowledgments;
+        let schedule = |hours_ago: i64| OutstandingAcknowledgments {
+            schedule_id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            published_at: Utc::now() - chrono::TimeDelta::hours(hours_ago),
+            roster_size: 4,
+            outstanding: vec![Uuid::from_u128(1)],
+            last_reminded_at: None,
+        };
+        let (due, not_due, claimed) = (schedule(30), schedule(2), schedule(30));
+        let (due_id, claimed_id) = (due.schedule_id, claimed.schedule_id);
 
-        // Only the active staff member should have assignments
-        let assignments = saved.lock().unwrap();
-        assert_eq!(assignments.len(), 28);
-        assert!(assignments.iter().all(|a| a.staff_id == active_id));
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_outstanding_acknowledgments()
+            .withf(|group| group.is_none())
+            .returning(move |_| Ok(vec![due.clone(), not_due.clone(), claimed.clone()]));
+        // Another replica already reminded about `claimed`
+        repo.expect_mark_acknowledgment_reminder()
+            .times(2)
+            .returning(move |id, _| Ok(id == due_id));
+
+        let mut notifier = MockNotifier::new();
+        notifier.expect_channel().return_const("mock");
+        notifier
+            .expect_send()
+            .withf(move |event| {
+                event.job_id == due_id
+                    && event.job_id != claimed_id
+                    && event.unacknowledged == Some(vec![Uuid::from_u128(1)])
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut config = SchedulingConfig::default();
+        config.acknowledgments.reminder_interval_hours = 24;
+        let svc = SchedulingService::new(
+            Arc::new(repo),
+            Arc::new(MockDataServiceClient::new()),
+            config,
+        )
+        .with_notifiers(vec![Arc::new(notifier)]);
+
+        assert_eq!(svc.send_acknowledgment_reminders().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn finished_job_queues_its_callback() {
+        let job = make_job(JobStatus::Pending);
+        let job_id = job.id;
+        let pending = PendingJob::from_schedule_job(job).unwrap();
+
+        let mut repo = MockJobRepository::new();
+        repo.expect_get_group_config().returning(|_| Ok(None));
+        repo.expect_record_job_event()
+            .returning(|_, _, _, _| Ok(()));
+        repo.expect_update_status().returning(|_, _, _| Ok(()));
+        repo.expect_start_pending_job()
+            .returning(|_, _, _| Ok(true));
+        repo.expect_mark_failed().returning(|_, _, _| Ok(()));
+        repo.expect_get_callback_url()
+            .returning(|_| Ok(Some("https://example.com/done".to_string())));
+        repo.expect_enqueue_callback()
+            .withf(move |id, url, payload| {
+                let callback: JobCallback = serde_json::from_str(payload).unwrap();
+                *id == job_id
+                    && url == "https://example.com/done"
+                    && callback.status == JobStatus::Failed
+                    && callback.result.is_none()
+                    && callback.error.is_some_and(|e| e.contains("down"))
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut client = MockDataServiceClient::new();
+        client
+            .expect_get_resolved_members()
+            .returning(|_, _| Err(SchedulingServiceError::DataService("down".to_string())));
+
+        let svc = make_service(repo, client).with_callbacks();
+        svc.spawn_process_job(pending);
+        svc.task_tracker().close();
+        svc.task_tracker().wait().await;
     }
 }
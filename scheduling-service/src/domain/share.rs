@@ -0,0 +1,141 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const DEFAULT_SHARE_LINK_TTL_HOURS: u32 = 24 * 7;
+pub const MAX_SHARE_LINK_TTL_HOURS: u32 = 24 * 90;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateShareLinkRequest {
+    /// Link lifetime, defaults to 7 days (max 90 days)
+    pub expires_in_hours: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLink {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShareTokenError {
+    #[error("malformed share token")]
+    Malformed,
+    #[error("invalid share token signature")]
+    BadSignature,
+    #[error("share token expired")]
+    Expired,
+}
+
+/// Issues and verifies stateless share tokens: `{job_id}.{expires_at_unix}.{hmac_sha256_hex}`.
+///
+/// Nothing is stored server side; rotating the secret revokes every outstanding link.
+pub struct ShareLinkSigner {
+    secret: Vec<u8>,
+}
+
+impl ShareLinkSigner {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    pub fn issue(&self, job_id: Uuid, expires_at: DateTime<Utc>) -> ShareLink {
+        let payload = format!("{}.{}", job_id.simple(), expires_at.timestamp());
+        let signature = hex::encode(self.mac(&payload).finalize().into_bytes());
+
+        ShareLink {
+            token: format!("{payload}.{signature}"),
+            expires_at: DateTime::from_timestamp(expires_at.timestamp(), 0).unwrap_or(expires_at),
+        }
+    }
+
+    pub fn issue_for(&self, job_id: Uuid, ttl_hours: u32, now: DateTime<Utc>) -> ShareLink {
+        self.issue(job_id, now + TimeDelta::hours(ttl_hours.into()))
+    }
+
+    /// Returns the job id the token grants access to.
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Result<Uuid, ShareTokenError> {
+        let (payload, signature) = token.rsplit_once('.').ok_or(ShareTokenError::Malformed)?;
+        let (job_id, expires_at) = payload.split_once('.').ok_or(ShareTokenError::Malformed)?;
+
+        let signature = hex::decode(signature).map_err(|_| ShareTokenError::Malformed)?;
+        self.mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| ShareTokenError::BadSignature)?;
+
+        let job_id = Uuid::parse_str(job_id).map_err(|_| ShareTokenError::Malformed)?;
+        let expires_at: i64 = expires_at.parse().map_err(|_| ShareTokenError::Malformed)?;
+        if now.timestamp() >= expires_at {
+            return Err(ShareTokenError::Expired);
+        }
+
+        Ok(job_id)
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any size");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_token_verifies_to_job_id() {
+        let signer = ShareLinkSigner::new("secret");
+        let job_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        let link = signer.issue_for(job_id, 1, now);
+
+        assert_eq!(signer.verify(&link.token, now), Ok(job_id));
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let signer = ShareLinkSigner::new("secret");
+        let now = Utc::now();
+        let link = signer.issue_for(Uuid::new_v4(), 1, now);
+
+        let later = now + TimeDelta::hours(2);
+        assert_eq!(
+            signer.verify(&link.token, later),
+            Err(ShareTokenError::Expired)
+        );
+    }
+
+    #[test]
+    fn tampered_token_is_rejected() {
+        let signer = ShareLinkSigner::new("secret");
+        let now = Utc::now();
+        let link = signer.issue_for(Uuid::new_v4(), 1, now);
+
+        // Swap in another job id but keep the original signature
+        let (_, rest) = link.token.split_once('.').unwrap();
+        let forged = format!("{}.{rest}", Uuid::new_v4().simple());
+        assert_eq!(
+            signer.verify(&forged, now),
+            Err(ShareTokenError::BadSignature)
+        );
+
+        let other_signer = ShareLinkSigner::new("other-secret");
+        assert_eq!(
+            other_signer.verify(&link.token, now),
+            Err(ShareTokenError::BadSignature)
+        );
+        assert_eq!(
+            signer.verify("not-a-token", now),
+            Err(ShareTokenError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+use shared::types::ShiftType;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::coverage::ShiftCoverage;
+use crate::domain::scheduler::{DayOffPairing, SchedulingConfig, ShiftOrdering};
+
+/// Hypothetical changes to try against a completed schedule.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SimulationRequest {
+    /// Staff to leave out of the simulated roster
+    #[serde(default)]
+    pub remove_staff: Vec<Uuid>,
+    #[serde(default)]
+    pub rules: RuleOverrides,
+}
+
+/// Rule settings to use instead of the configured ones; omitted fields keep their value.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RuleOverrides {
+    pub min_day_off_per_week: Option<u8>,
+    pub max_day_off_per_week: Option<u8>,
+    pub no_morning_after_evening: Option<bool>,
+    pub max_daily_shift_diff: Option<u8>,
+    pub day_off_pairing: Option<DayOffPairing>,
+    /// Try another shift ordering strategy before switching the configured one
+    pub shift_ordering: Option<ShiftOrdering>,
+    /// Staff needed on each shift every day
+    pub shift_coverage: Option<ShiftCoverage>,
+}
+
+impl RuleOverrides {
+    pub fn apply(&self, config: &SchedulingConfig) -> Result<SchedulingConfig, String> {
+        let config = SchedulingConfig {
+            min_day_off_per_week: self
+                .min_day_off_per_week
+                .unwrap_or(config.min_day_off_per_week),
+            max_day_off_per_week: self
+                .max_day_off_per_week
+                .unwrap_or(config.max_day_off_per_week),
+            no_morning_after_evening: self
+                .no_morning_after_evening
+                .unwrap_or(config.no_morning_after_evening),
+            max_daily_shift_diff: self
+                .max_daily_shift_diff
+                .unwrap_or(config.max_daily_shift_diff),
+            day_off_pairing: self.day_off_pairing.unwrap_or(config.day_off_pairing),
+            shift_ordering: self.shift_ordering.unwrap_or(config.shift_ordering),
+            shift_coverage: self.shift_coverage.unwrap_or(config.shift_coverage),
+            ..config.clone()
+        };
+
+        config.validate_rules()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Coverage {
+    pub staff_count: usize,
+    pub morning_shifts: usize,
+    pub evening_shifts: usize,
+    pub night_shifts: usize,
+    /// Days without at least one MORNING and one EVENING shift
+    pub uncovered_days: usize,
+    /// Fewest staff working on any single day
+    pub min_daily_staff: usize,
+}
+
+/// One broken rule. `staff_id` is absent for whole-day rules such as `daily_balance`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct RuleViolation {
+    /// Name of the rule, as in `SchedulingRule::name`
+    pub rule: String,
+    pub date: NaiveDate,
+    pub staff_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScenarioReport {
+    pub coverage: Coverage,
+    /// Checked against the simulated rules
+    pub violations: Vec<RuleViolation>,
+    /// Why no schedule could be generated, the other fields are empty then
+    pub error: Option<String>,
+}
+
+/// Simulated minus baseline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ScenarioDelta {
+    pub staff_count: i64,
+    pub morning_shifts: i64,
+    pub evening_shifts: i64,
+    pub night_shifts: i64,
+    pub uncovered_days: i64,
+    pub min_daily_staff: i64,
+    pub violations: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SimulationReport {
+    /// The stored schedule
+    pub baseline: ScenarioReport,
+    /// A schedule regenerated with the changes applied, never persisted
+    pub simulated: ScenarioReport,
+    pub delta: ScenarioDelta,
+}
+
+impl SimulationReport {
+    pub fn new(baseline: ScenarioReport, simulated: ScenarioReport) -> Self {
+        let diff = |a: usize, b: usize| b as i64 - a as i64;
+        let (before, after) = (&baseline.coverage, &simulated.coverage);
+        let delta = ScenarioDelta {
+            staff_count: diff(before.staff_count, after.staff_count),
+            morning_shifts: diff(before.morning_shifts, after.morning_shifts),
+            evening_shifts: diff(before.evening_shifts, after.evening_shifts),
+            night_shifts: diff(before.night_shifts, after.night_shifts),
+            uncovered_days: diff(before.uncovered_days, after.uncovered_days),
+            min_daily_staff: diff(before.min_daily_staff, after.min_daily_staff),
+            violations: diff(baseline.violations.len(), simulated.violations.len()),
+        };
+        Self {
+            baseline,
+            simulated,
+            delta,
+        }
+    }
+}
+
+pub fn coverage<'a>(
+    assignments: impl IntoIterator<Item = (Uuid, NaiveDate, &'a ShiftType)>,
+) -> Coverage {
+    let mut staff = Vec::new();
+    let mut days: BTreeMap<NaiveDate, ShiftCounts> = BTreeMap::new();
+    for (staff_id, date, shift_type) in assignments {
+        staff.push(staff_id);
+        days.entry(date).or_default().add(shift_type);
+    }
+    staff.sort_unstable();
+    staff.dedup();
+
+    Coverage {
+        staff_count: staff.len(),
+        morning_shifts: days.values().map(|d| d.morning).sum(),
+        evening_shifts: days.values().map(|d| d.evening).sum(),
+        night_shifts: days.values().map(|d| d.night).sum(),
+        uncovered_days: days
+            .values()
+            .filter(|d| d.morning == 0 || d.evening == 0)
+            .count(),
+        min_daily_staff: days
+            .values()
+            .map(|d| d.morning + d.evening + d.night)
+            .min()
+            .unwrap_or(0),
+    }
+}
+
+/// Working shifts on one day.
+#[derive(Default)]
+struct ShiftCounts {
+    morning: usize,
+    evening: usize,
+    night: usize,
+}
+
+impl ShiftCounts {
+    fn add(&mut self, shift_type: &ShiftType) {
+        match shift_type {
+            ShiftType::Morning => self.morning += 1,
+            ShiftType::Evening => self.evening += 1,
+            ShiftType::Night => self.night += 1,
+            ShiftType::DayOff => {}
+        }
+    }
+
+    /// Largest difference between the shifts `daily_balance` compares.
+    fn spread(&self, night_shift: bool) -> usize {
+        let counts: &[usize] = if night_shift {
+            &[self.morning, self.evening, self.night]
+        } else {
+            &[self.morning, self.evening]
+        };
+        counts.iter().max().unwrap_or(&0) - counts.iter().min().unwrap_or(&0)
+    }
+}
+
+/// Check a finished schedule against the rules in `config`, with weeks as set by its
+/// `week_alignment`.
+pub fn find_violations<'a>(
+    assignments: impl IntoIterator<Item = (Uuid, NaiveDate, &'a ShiftType)>,
+    period_begin_date: NaiveDate,
+    config: &SchedulingConfig,
+) -> Vec<RuleViolation> {
+    let mut by_staff: HashMap<Uuid, Vec<(NaiveDate, &ShiftType)>> = HashMap::new();
+    let mut days: BTreeMap<NaiveDate, ShiftCounts> = BTreeMap::new();
+    for (staff_id, date, shift_type) in assignments {
+        by_staff
+            .entry(staff_id)
+            .or_default()
+            .push((date, shift_type));
+        days.entry(date).or_default().add(shift_type);
+    }
+
+    let mut violations = Vec::new();
+    let violation = |rule: &str, date, staff_id| RuleViolation {
+        rule: rule.to_string(),
+        date,
+        staff_id,
+    };
+
+    for (&staff_id, shifts) in &mut by_staff {
+        shifts.sort_by_key(|(date, _)| *date);
+
+        for pair in shifts.windows(2) {
+            let ((prev_date, prev), (date, shift)) = (pair[0], pair[1]);
+            if (date - prev_date).num_days() != 1 || *shift != ShiftType::Morning {
+                continue;
+            }
+            if config.no_morning_after_evening && *prev == ShiftType::Evening {
+                violations.push(violation("no_morning_after_evening", date, Some(staff_id)));
+            }
+            if config.night_shift && config.no_morning_after_night && *prev == ShiftType::Night {
+                violations.push(violation("no_morning_after_night", date, Some(staff_id)));
+            }
+        }
+
+        // A lone day off inside the schedule; the days beyond either end are unknown
+        if config.day_off_pairing == DayOffPairing::Require {
+            let is_day_off = |date| {
+                shifts
+                    .iter()
+                    .find(|(d, _)| *d == date)
+                    .map(|(_, shift)| **shift == ShiftType::DayOff)
+            };
+            for (date, shift) in shifts.iter() {
+                let (before, after) = (*date - TimeDelta::days(1), *date + TimeDelta::days(1));
+                if **shift == ShiftType::DayOff
+                    && is_day_off(before) == Some(false)
+                    && is_day_off(after) == Some(false)
+                {
+                    violations.push(violation("day_off_pairing", *date, Some(staff_id)));
+                }
+            }
+        }
+
+        // Keyed by week start, reported on the week's first scheduled day
+        let mut weeks: BTreeMap<NaiveDate, (NaiveDate, u8)> = BTreeMap::new();
+        for (date, shift) in shifts.iter() {
+            let week_start = config.week_alignment.week_start(*date, period_begin_date);
+            let (_, day_offs) = weeks.entry(week_start).or_insert((*date, 0));
+            if **shift == ShiftType::DayOff {
+                *day_offs += 1;
+            }
+        }
+        for (first_day, day_offs) in weeks.into_values() {
+            if day_offs < config.min_day_off_per_week {
+                violations.push(violation("min_day_off", first_day, Some(staff_id)));
+            }
+            if day_offs > config.max_day_off_per_week {
+                violations.push(violation("max_day_off", first_day, Some(staff_id)));
+            }
+        }
+    }
+
+    let coverage = config.shift_coverage;
+    for (&date, counts) in &days {
+        if counts.spread(config.night_shift) > config.max_daily_shift_diff as usize {
+            violations.push(violation("daily_balance", date, None));
+        }
+        if counts.morning < coverage.morning || counts.evening < coverage.evening {
+            violations.push(violation("shift_coverage", date, None));
+        }
+    }
+
+    violations.sort_by(|a, b| (a.date, &a.rule, a.staff_id).cmp(&(b.date, &b.rule, b.staff_id)));
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, day).unwrap()
+    }
+
+    #[test]
+    fn overrides_keep_unset_fields_and_reject_inverted_bounds() {
+        let config = SchedulingConfig::default();
+        let overrides = RuleOverrides {
+            max_daily_shift_diff: Some(3),
+            ..RuleOverrides::default()
+        };
+
+        let applied = overrides.apply(&config).unwrap();
+        assert_eq!(applied.max_daily_shift_diff, 3);
+        assert_eq!(applied.max_day_off_per_week, config.max_day_off_per_week);
+
+        let inverted = RuleOverrides {
+            min_day_off_per_week: Some(3),
+            ..RuleOverrides::default()
+        };
+        assert!(inverted.apply(&config).is_err());
+    }
+
+    #[test]
+    fn coverage_counts_uncovered_days() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let assignments = [
+            (alice, date(16), ShiftType::Morning),
+            (bob, date(16), ShiftType::Evening),
+            (alice, date(17), ShiftType::Morning),
+            (bob, date(17), ShiftType::DayOff),
+        ];
+
+        let coverage = coverage(assignments.iter().map(|(s, d, t)| (*s, *d, t)));
+
+        assert_eq!(
+            coverage,
+            Coverage {
+                staff_count: 2,
+                morning_shifts: 2,
+                evening_shifts: 1,
+                night_shifts: 0,
+                uncovered_days: 1,
+                min_daily_staff: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn violations_are_reported_per_rule() {
+        let staff_id = Uuid::new_v4();
+        // One week without a day off, with an EVENING -> MORNING turnaround on the 17th
+        let mut assignments = vec![
+            (staff_id, date(16), ShiftType::Evening),
+            (staff_id, date(17), ShiftType::Morning),
+        ];
+        for day in 18..=22 {
+            assignments.push((staff_id, date(day), ShiftType::Morning));
+        }
+        let config = SchedulingConfig::default();
+
+        let violations = find_violations(
+            assignments.iter().map(|(s, d, t)| (*s, *d, t)),
+            date(16),
+            &config,
+        );
+
+        let rules: Vec<_> = violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(rules, ["min_day_off", "no_morning_after_evening"]);
+        assert_eq!(violations[1].date, date(17));
+    }
+
+    #[test]
+    fn lone_days_off_break_required_pairing() {
+        let staff_id = Uuid::new_v4();
+        let shifts = [
+            ShiftType::DayOff,
+            ShiftType::Morning,
+            ShiftType::DayOff,
+            ShiftType::Morning,
+            ShiftType::DayOff,
+            ShiftType::DayOff,
+            ShiftType::Morning,
+        ];
+        let assignments: Vec<_> = (16..)
+            .zip(shifts)
+            .map(|(day, shift)| (staff_id, date(day), shift))
+            .collect();
+        let config = SchedulingConfig {
+            max_day_off_per_week: 3,
+            day_off_pairing: DayOffPairing::Require,
+            ..SchedulingConfig::default()
+        };
+
+        let violations = find_violations(
+            assignments.iter().map(|(s, d, t)| (*s, *d, t)),
+            date(16),
+            &config,
+        );
+
+        // The 16th could pair with the day before the schedule
+        let dates: Vec<_> = violations
+            .iter()
+            .filter(|v| v.rule == "day_off_pairing")
+            .map(|v| v.date)
+            .collect();
+        assert_eq!(dates, [date(18)]);
+    }
+}
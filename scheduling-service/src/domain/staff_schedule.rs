@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use shared::types::ShiftAssignment;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Longest `from..=to` range of a staff member's assignments, about a year.
+pub const MAX_STAFF_ASSIGNMENT_DAYS: i64 = 366;
+
+/// One staff member's part of a completed schedule.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StaffSchedule {
+    pub schedule_id: Uuid,
+    pub staff_group_id: Uuid,
+    pub period_begin_date: NaiveDate,
+    pub staff_id: Uuid,
+    /// Every day of the schedule the staff member has an assignment for, days off included
+    pub assignments: Vec<ShiftAssignment>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StaffAssignmentsQuery {
+    /// First date, inclusive; the `[calendar]` horizon start when omitted
+    pub from: Option<NaiveDate>,
+    /// Last date, inclusive; the `[calendar]` horizon end when omitted
+    pub to: Option<NaiveDate>,
+}
+
+/// A staff member's assignments across the completed schedules of all their groups.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StaffAssignments {
+    pub staff_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// By date; `job_id` tells which schedule each one comes from
+    pub assignments: Vec<ShiftAssignment>,
+}
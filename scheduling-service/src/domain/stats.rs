@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Hours looked back when `window_hours` is omitted.
+pub const DEFAULT_STATS_WINDOW_HOURS: u32 = 24;
+/// Longest window, 90 days.
+pub const MAX_STATS_WINDOW_HOURS: u32 = 24 * 90;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobStatsQuery {
+    /// Jobs created in this many hours up to now, 24 by default and at most 2160
+    pub window_hours: Option<u32>,
+    pub staff_group_id: Option<Uuid>,
+    /// Count sandbox jobs too, left out by default
+    #[serde(default)]
+    pub include_sandbox: bool,
+}
+
+/// Aggregates over the jobs in a window, as the database computes them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, FromRow, ToSchema)]
+pub struct JobStatusCounts {
+    pub pending: i64,
+    pub processing: i64,
+    pub completed: i64,
+    pub failed: i64,
+    /// Mean time from pickup to completion or failure of the finished jobs, from their
+    /// timelines; `None` when none finished
+    pub avg_processing_ms: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobStats {
+    /// Start of the window; it ends now
+    pub since: DateTime<Utc>,
+    pub window_hours: u32,
+    pub total: i64,
+    pub by_status: JobStatusCounts,
+    /// Failed share of the finished jobs, 0 to 1; `None` when none finished
+    pub failure_rate: Option<f64>,
+}
+
+impl JobStats {
+    pub fn new(since: DateTime<Utc>, window_hours: u32, by_status: JobStatusCounts) -> Self {
+        let finished = by_status.completed + by_status.failed;
+        Self {
+            since,
+            window_hours,
+            total: by_status.pending + by_status.processing + finished,
+            failure_rate: (finished > 0).then(|| by_status.failed as f64 / finished as f64),
+            by_status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_rate_covers_finished_jobs_only() {
+        let stats = JobStats::new(
+            Utc::now(),
+            24,
+            JobStatusCounts {
+                pending: 5,
+                processing: 1,
+                completed: 3,
+                failed: 1,
+                avg_processing_ms: Some(1_200.0),
+            },
+        );
+        assert_eq!(stats.total, 10);
+        assert_eq!(stats.failure_rate, Some(0.25));
+
+        let idle = JobStats::new(Utc::now(), 24, JobStatusCounts::default());
+        assert_eq!(idle.total, 0);
+        assert_eq!(idle.failure_rate, None);
+    }
+}
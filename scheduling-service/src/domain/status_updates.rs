@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use shared::types::{JobStatus, ScheduleJob};
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::job::JobRepository;
+
+/// Updates a slow subscriber can fall behind by before it starts missing some.
+pub const STATUS_UPDATE_CAPACITY: usize = 256;
+
+/// A job moving to a new status, as sent on `/schedules/{id}/events`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct JobStatusUpdate {
+    pub job_id: Uuid,
+    pub status: JobStatus,
+    /// Why the job failed, for `FAILED`
+    pub error: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl JobStatusUpdate {
+    pub fn new(job_id: Uuid, status: JobStatus, error: Option<String>) -> Self {
+        Self {
+            job_id,
+            status,
+            error,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// Nothing follows a `COMPLETED` or `FAILED` job unless someone runs it again.
+    pub fn is_final(&self) -> bool {
+        matches!(self.status, JobStatus::Completed | JobStatus::Failed)
+    }
+}
+
+impl From<&ScheduleJob> for JobStatusUpdate {
+    fn from(job: &ScheduleJob) -> Self {
+        Self {
+            job_id: job.id,
+            status: job.status.clone(),
+            error: job.error_message.clone(),
+            occurred_at: job.updated_at,
+        }
+    }
+}
+
+/// Status changes of the jobs this replica runs, fanned out to whoever is listening. Jobs
+/// run by another replica don't show up here.
+#[derive(Debug, Clone)]
+pub struct JobStatusUpdates {
+    sender: broadcast::Sender<JobStatusUpdate>,
+}
+
+impl Default for JobStatusUpdates {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(STATUS_UPDATE_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl JobStatusUpdates {
+    pub fn publish(&self, update: JobStatusUpdate) {
+        // Err only means nobody is listening right now
+        let _ = self.sender.send(update);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<JobStatusUpdate> {
+        self.sender.subscribe()
+    }
+}
+
+/// One job's status, then each change to it until it completes or fails.
+pub struct StatusWatch {
+    repo: Arc<dyn JobRepository>,
+    job_id: Uuid,
+    updates: broadcast::Receiver<JobStatusUpdate>,
+    next: Option<JobStatusUpdate>,
+    finished: bool,
+}
+
+impl StatusWatch {
+    /// `updates` has to be subscribed before `job` was read, or a change in between is lost.
+    pub fn new(
+        repo: Arc<dyn JobRepository>,
+        job: &ScheduleJob,
+        updates: broadcast::Receiver<JobStatusUpdate>,
+    ) -> Self {
+        Self {
+            repo,
+            job_id: job.id,
+            updates,
+            next: Some(job.into()),
+            finished: false,
+        }
+    }
+
+    /// `None` once a final status has been returned, or when the service shuts down.
+    pub async fn next(&mut self) -> Option<JobStatusUpdate> {
+        if self.finished {
+            return None;
+        }
+        let update = match self.next.take() {
+            Some(update) => update,
+            None => self.receive().await?,
+        };
+        self.finished = update.is_final();
+        Some(update)
+    }
+
+    async fn receive(&mut self) -> Option<JobStatusUpdate> {
+        loop {
+            match self.updates.recv().await {
+                Ok(update) if update.job_id == self.job_id => return Some(update),
+                Ok(_) => {}
+                // Fell behind and may have missed this job's changes: the stored job says
+                // where it got to
+                Err(RecvError::Lagged(_)) => match self.repo.find_by_id(self.job_id).await {
+                    Ok(Some(job)) => return Some((&job).into()),
+                    Ok(None) => return None,
+                    Err(e) => {
+                        tracing::warn!(job_id = %self.job_id, "Failed to reload watched job: {e}");
+                        return None;
+                    }
+                },
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use shared::types::{ShiftAssignment, ShiftTargetDeviation, ShiftType};
+use uuid::Uuid;
+
+use crate::domain::scheduler::RosterMember;
+
+/// Morning:Evening ratios staff should work towards, e.g. `{ morning = 0, evening = 1 }` for
+/// someone contracted to evenings only. A staff entry wins over one for their position.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShiftTargetConfig {
+    /// Keyed by the staff `position`, matched exactly
+    pub positions: HashMap<String, ShiftTarget>,
+    pub staff: HashMap<Uuid, ShiftTarget>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ShiftTarget {
+    pub morning: u32,
+    pub evening: u32,
+}
+
+impl ShiftTarget {
+    /// Share of working shifts meant to be MORNING, `None` for a 0:0 ratio.
+    pub fn morning_share(&self) -> Option<f64> {
+        let total = self.morning + self.evening;
+        (total > 0).then(|| f64::from(self.morning) / f64::from(total))
+    }
+
+    /// The working shift that moves the counts so far closest to the ratio, then the other.
+    pub fn working_shifts(&self, morning: u32, evening: u32) -> Option<[ShiftType; 2]> {
+        let share = self.morning_share()?;
+        let worked = f64::from(morning + evening + 1);
+        Some(if f64::from(morning) < share * worked {
+            [ShiftType::Morning, ShiftType::Evening]
+        } else {
+            [ShiftType::Evening, ShiftType::Morning]
+        })
+    }
+}
+
+impl ShiftTargetConfig {
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.staff.is_empty()
+    }
+
+    pub fn target_for(&self, staff_id: Uuid, position: Option<&str>) -> Option<ShiftTarget> {
+        self.staff
+            .get(&staff_id)
+            .or_else(|| position.and_then(|p| self.positions.get(p)))
+            .copied()
+            .filter(|target| target.morning_share().is_some())
+    }
+
+    /// Target of each roster member, in roster order.
+    pub fn for_roster(&self, roster: &[RosterMember]) -> Vec<Option<ShiftTarget>> {
+        roster
+            .iter()
+            .map(|m| self.target_for(m.staff_id, Some(&m.position)))
+            .collect()
+    }
+
+    /// How far each staff member with a target ended up from it. `positions` maps staff ids
+    /// to positions; staff missing from it only match a staff entry.
+    pub fn deviations<'a>(
+        &self,
+        assignments: impl IntoIterator<Item = &'a ShiftAssignment>,
+        positions: &HashMap<Uuid, String>,
+    ) -> HashMap<Uuid, ShiftTargetDeviation> {
+        let mut deviations: HashMap<Uuid, ShiftTargetDeviation> = HashMap::new();
+        for a in assignments {
+            let Some(share) = self
+                .target_for(a.staff_id, positions.get(&a.staff_id).map(String::as_str))
+                .and_then(|target| target.morning_share())
+            else {
+                continue;
+            };
+            let deviation = deviations
+                .entry(a.staff_id)
+                .or_insert_with(|| ShiftTargetDeviation {
+                    target_morning_share: share,
+                    ..Default::default()
+                });
+            match a.shift_type {
+                ShiftType::Morning => deviation.morning_shifts += 1,
+                ShiftType::Evening => deviation.evening_shifts += 1,
+                ShiftType::Night | ShiftType::DayOff => {}
+            }
+        }
+        for deviation in deviations.values_mut() {
+            let worked = deviation.morning_shifts + deviation.evening_shifts;
+            if worked > 0 {
+                deviation.deviation = f64::from(deviation.morning_shifts) / f64::from(worked)
+                    - deviation.target_morning_share;
+            }
+        }
+        deviations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn working_shifts_follow_the_ratio() {
+        let two_to_one = ShiftTarget {
+            morning: 2,
+            evening: 1,
+        };
+        let mut counts = (0, 0);
+        let mut order = String::new();
+        for _ in 0..6 {
+            let [first, _] = two_to_one.working_shifts(counts.0, counts.1).unwrap();
+            match first {
+                ShiftType::Morning => counts.0 += 1,
+                _ => counts.1 += 1,
+            }
+            order.push(if first == ShiftType::Morning {
+                'M'
+            } else {
+                'E'
+            });
+        }
+        assert_eq!(order, "MMEMME");
+
+        let evening_only = ShiftTarget {
+            morning: 0,
+            evening: 1,
+        };
+        assert_eq!(
+            evening_only.working_shifts(0, 5),
+            Some([ShiftType::Evening, ShiftType::Morning])
+        );
+        assert_eq!(ShiftTarget::default().working_shifts(0, 0), None);
+    }
+
+    #[test]
+    fn staff_targets_win_over_positions_and_deviation_is_reported() {
+        let (nurse, lead) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let config = ShiftTargetConfig {
+            positions: HashMap::from([(
+                "Nurse".to_string(),
+                ShiftTarget {
+                    morning: 0,
+                    evening: 1,
+                },
+            )]),
+            staff: HashMap::from([(
+                lead,
+                ShiftTarget {
+                    morning: 1,
+                    evening: 1,
+                },
+            )]),
+        };
+        let positions = HashMap::from([
+            (nurse, "Nurse".to_string()),
+            (lead, "Nurse".to_string()),
+            (Uuid::from_u128(3), "Porter".to_string()),
+        ]);
+        let assignment = |staff_id, shift_type| ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id: Uuid::nil(),
+            staff_id,
+            date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            shift_type,
+            note: None,
+        };
+        let assignments = [
+            assignment(nurse, ShiftType::Evening),
+            assignment(nurse, ShiftType::Evening),
+            assignment(nurse, ShiftType::Evening),
+            assignment(nurse, ShiftType::Morning),
+            assignment(lead, ShiftType::Morning),
+            assignment(lead, ShiftType::DayOff),
+            assignment(Uuid::from_u128(3), ShiftType::Morning),
+        ];
+
+        let deviations = config.deviations(&assignments, &positions);
+        assert_eq!(deviations.len(), 2);
+        assert_eq!(deviations[&nurse].target_morning_share, 0.0);
+        assert_eq!(deviations[&nurse].deviation, 0.25);
+        assert_eq!(deviations[&lead].target_morning_share, 0.5);
+        assert_eq!(deviations[&lead].deviation, 0.5);
+    }
+}
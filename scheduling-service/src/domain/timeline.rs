@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::{JobStatus, ScheduleJob};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "job_event_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobEventKind {
+    Created,
+    PickedUp,
+    DataFetched,
+    Generated,
+    Saved,
+    Completed,
+    Failed,
+    Retried,
+    /// Reset to `Pending` after a restart found it stuck in `Processing`
+    Recovered,
+    /// Status forced by an admin; the detail has the old and new status and the reason
+    Overridden,
+}
+
+/// One step in a job's lifecycle.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct JobTimelineEvent {
+    pub kind: JobEventKind,
+    /// E.g. how much data was fetched, or why the job failed
+    pub detail: Option<String>,
+    /// How long the step itself took, for timed steps
+    pub duration_ms: Option<i64>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub event: JobTimelineEvent,
+    /// Time since the previous event, 0 for the first one
+    pub since_previous_ms: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobTimeline {
+    pub schedule_id: Uuid,
+    pub status: JobStatus,
+    /// From the first event to the last one
+    pub total_ms: i64,
+    /// Oldest first
+    pub events: Vec<TimelineEntry>,
+}
+
+impl JobTimeline {
+    pub fn new(job: &ScheduleJob, events: Vec<JobTimelineEvent>) -> Self {
+        let first = events.first().map(|e| e.occurred_at);
+        let last = events.last().map(|e| e.occurred_at);
+        let mut previous = first;
+        let events = events
+            .into_iter()
+            .map(|event| {
+                let since_previous_ms = previous
+                    .map_or(0, |p| (event.occurred_at - p).num_milliseconds())
+                    .max(0);
+                previous = Some(event.occurred_at);
+                TimelineEntry {
+                    event,
+                    since_previous_ms,
+                }
+            })
+            .collect();
+
+        Self {
+            schedule_id: job.id,
+            status: job.status.clone(),
+            total_ms: first
+                .zip(last)
+                .map_or(0, |(first, last)| (last - first).num_milliseconds()),
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn timeline_measures_gaps_between_events() {
+        let job = ScheduleJob {
+            id: Uuid::new_v4(),
+            staff_group_id: Uuid::new_v4(),
+            period_begin_date: chrono::NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+            status: JobStatus::Completed,
+            result_checksum: None,
+            depends_on: vec![],
+            sandbox: false,
+            periods: 1,
+            min_morning: None,
+            min_evening: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: 1,
+        };
+        let start = Utc::now();
+        let event = |kind, after_ms| JobTimelineEvent {
+            kind,
+            detail: None,
+            duration_ms: None,
+            occurred_at: start + TimeDelta::milliseconds(after_ms),
+        };
+
+        let timeline = JobTimeline::new(
+            &job,
+            vec![
+                event(JobEventKind::Created, 0),
+                event(JobEventKind::PickedUp, 2_400_000),
+                event(JobEventKind::Completed, 2_400_350),
+            ],
+        );
+
+        let gaps: Vec<i64> = timeline
+            .events
+            .iter()
+            .map(|e| e.since_previous_ms)
+            .collect();
+        assert_eq!(gaps, vec![0, 2_400_000, 350]);
+        assert_eq!(timeline.total_ms, 2_400_350);
+        assert_eq!(JobTimeline::new(&job, vec![]).total_ms, 0);
+    }
+}
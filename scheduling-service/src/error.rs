@@ -2,6 +2,7 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::response::Response;
 use shared::responses::ApiResponse;
+use sqlx::error::ErrorKind;
 use thiserror::Error;
 
 // Scheduling Service Error
@@ -13,6 +14,15 @@ pub enum SchedulingServiceError {
     #[error("Bad Request: {0}")]
     BadRequest(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Not Acceptable: {0}")]
+    NotAcceptable(String),
+
     #[error("Internal Server Error: {0}")]
     Internal(String),
 
@@ -21,6 +31,25 @@ pub enum SchedulingServiceError {
 
     #[error("Data Service Error: {0}")]
     DataService(String),
+
+    #[error("Job Timeout: {0}")]
+    JobTimeout(String),
+}
+
+impl SchedulingServiceError {
+    /// Maps a constraint violation by its SQLSTATE class and constraint name. `map` gets both
+    /// and returns the error to report, or `None` to keep it a database error.
+    pub fn from_violation(
+        e: sqlx::Error,
+        map: impl FnOnce(ErrorKind, Option<&str>) -> Option<Self>,
+    ) -> Self {
+        if let sqlx::Error::Database(db) = &e
+            && let Some(mapped) = map(db.kind(), db.constraint())
+        {
+            return mapped;
+        }
+        e.into()
+    }
 }
 
 impl IntoResponse for SchedulingServiceError {
@@ -28,12 +57,16 @@ impl IntoResponse for SchedulingServiceError {
         let (status, message) = match &self {
             Self::NotFound(message) => (StatusCode::NOT_FOUND, message.clone()),
             Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message.clone()),
+            Self::Forbidden(message) => (StatusCode::FORBIDDEN, message.clone()),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message.clone()),
+            Self::NotAcceptable(message) => (StatusCode::NOT_ACCEPTABLE, message.clone()),
             Self::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message.clone()),
             Self::Database(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Oof, Something went wrong while accessing the database.".into(),
             ),
             Self::DataService(message) => (StatusCode::BAD_GATEWAY, message.clone()),
+            Self::JobTimeout(message) => (StatusCode::GATEWAY_TIMEOUT, message.clone()),
         };
 
         if status.is_server_error() {
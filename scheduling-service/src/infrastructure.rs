@@ -1,2 +1,10 @@
+pub mod cache;
+pub mod callback;
 pub mod client;
+pub mod health;
 pub mod job;
+pub mod lock;
+pub mod notify;
+
+/// Migrations embedded at build time: run on boot, then compared by the startup self-check.
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
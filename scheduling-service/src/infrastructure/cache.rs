@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use shared::types::{GroupBlackout, GroupMember, Staff, TimeOffRequest};
+use uuid::Uuid;
+
+use crate::{domain::client::DataServiceClient, error::SchedulingServiceError};
+
+/// Keeps staff looked up by id in process memory, so repeated result hydration doesn't
+/// hit the data-service every time. Member listings and blackout windows are not cached:
+/// schedule generation must see changes to them immediately.
+pub struct CachedDataServiceClient {
+    inner: Arc<dyn DataServiceClient>,
+    ttl: Duration,
+    staff: Mutex<HashMap<Uuid, (Instant, Staff)>>,
+}
+
+impl CachedDataServiceClient {
+    pub fn new(inner: Arc<dyn DataServiceClient>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            staff: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl DataServiceClient for CachedDataServiceClient {
+    async fn get_resolved_members(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<Vec<GroupMember>, SchedulingServiceError> {
+        self.inner
+            .get_resolved_members(staff_group_id, active_only)
+            .await
+    }
+
+    async fn count_resolved_members(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<i64, SchedulingServiceError> {
+        self.inner
+            .count_resolved_members(staff_group_id, active_only)
+            .await
+    }
+
+    async fn count_members_by_position(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<HashMap<String, i64>, SchedulingServiceError> {
+        self.inner
+            .count_members_by_position(staff_group_id, active_only)
+            .await
+    }
+
+    async fn get_blackouts(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<GroupBlackout>, SchedulingServiceError> {
+        self.inner.get_blackouts(staff_group_id, from, to).await
+    }
+
+    async fn get_time_off(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TimeOffRequest>, SchedulingServiceError> {
+        self.inner.get_time_off(staff_group_id, from, to).await
+    }
+
+    async fn get_staff_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, SchedulingServiceError> {
+        let now = Instant::now();
+        let mut found = Vec::with_capacity(ids.len());
+        let mut missing = Vec::new();
+        {
+            let mut cache = self.staff.lock().unwrap();
+            cache.retain(|_, (cached_at, _)| now.duration_since(*cached_at) < self.ttl);
+            for id in ids {
+                match cache.get(&id) {
+                    Some((_, staff)) => found.push(staff.clone()),
+                    None => missing.push(id),
+                }
+            }
+        }
+        shared::metrics::record_cache_lookups("staff", found.len() as u64, missing.len() as u64);
+
+        if !missing.is_empty() {
+            let fetched = self.inner.get_staff_by_ids(missing).await?;
+            let mut cache = self.staff.lock().unwrap();
+            for staff in &fetched {
+                // A lookup that raced this one may have cached a newer copy meanwhile
+                let newer_cached = cache
+                    .get(&staff.id)
+                    .is_some_and(|(_, cached)| cached.version > staff.version);
+                if !newer_cached {
+                    cache.insert(staff.id, (now, staff.clone()));
+                }
+            }
+            found.extend(fetched);
+        }
+
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::client::MockDataServiceClient;
+    use shared::types::{ShiftEligibility, StaffStatus};
+
+    fn make_staff(id: Uuid) -> Staff {
+        Staff {
+            id,
+            name: "Alice".to_string(),
+            email: format!("{id}@example.com"),
+            position: "Nurse".to_string(),
+            fairness_weight: 1.0,
+            shift_eligibility: ShiftEligibility::Any,
+            status: StaffStatus::Active,
+            effective_until: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn staff_lookups_only_fetch_uncached_ids() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        let mut inner = MockDataServiceClient::new();
+        inner
+            .expect_get_staff_by_ids()
+            .withf(move |ids| ids == &[a])
+            .times(1)
+            .returning(|ids| Ok(ids.into_iter().map(make_staff).collect()));
+        inner
+            .expect_get_staff_by_ids()
+            .withf(move |ids| ids == &[b])
+            .times(1)
+            .returning(|ids| Ok(ids.into_iter().map(make_staff).collect()));
+
+        let client = CachedDataServiceClient::new(Arc::new(inner), Duration::from_secs(60));
+
+        assert_eq!(client.get_staff_by_ids(vec![a]).await.unwrap().len(), 1);
+        assert_eq!(client.get_staff_by_ids(vec![a, b]).await.unwrap().len(), 2);
+        assert_eq!(client.get_staff_by_ids(vec![b, a]).await.unwrap().len(), 2);
+    }
+}
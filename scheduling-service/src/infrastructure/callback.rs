@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::{Client, header};
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::error::SchedulingServiceError;
+
+const BATCH_SIZE: i64 = 50;
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a claimed delivery stays hidden from other replicas while it is being sent.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+/// Attempts after the first failed one before a callback is dropped.
+const MAX_RETRIES: i16 = 5;
+const FIRST_RETRY_DELAY_SECS: f64 = 30.0;
+const MAX_RETRY_DELAY_SECS: f64 = 3600.0;
+
+struct DueCallback {
+    id: i64,
+    job_id: Uuid,
+    url: String,
+    payload: String,
+    attempts: i16,
+}
+
+/// Sends queued job callbacks signed with the service secret, retrying failed ones with
+/// exponential backoff.
+pub struct CallbackDispatcher {
+    pool: PgPool,
+    client: Client,
+    secret: Vec<u8>,
+}
+
+impl CallbackDispatcher {
+    pub fn new(pool: PgPool, secret: impl Into<Vec<u8>>) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+        Ok(Self {
+            pool,
+            client,
+            secret: secret.into(),
+        })
+    }
+
+    /// Claim the callbacks that are due and send them concurrently. Returns how many were
+    /// attempted.
+    pub async fn drain(&self) -> Result<usize, SchedulingServiceError> {
+        let due = sqlx::query_as!(
+            DueCallback,
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM callback_deliveries
+                WHERE next_attempt_at <= now()
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE callback_deliveries d
+            SET next_attempt_at = now() + make_interval(secs => $2)
+            FROM due
+            WHERE d.id = due.id
+            RETURNING d.id, d.job_id, d.url, d.payload, d.attempts
+            "#,
+            BATCH_SIZE,
+            CLAIM_LEASE.as_secs_f64()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let count = due.len();
+        let mut sends = JoinSet::new();
+        for callback in due {
+            let pool = self.pool.clone();
+            let client = self.client.clone();
+            let signature = self.sign(&callback.payload);
+            sends.spawn(async move {
+                if let Err(e) = deliver(&pool, &client, callback, signature).await {
+                    tracing::warn!("Recording callback outcome failed: {e}");
+                }
+            });
+        }
+        sends.join_all().await;
+
+        Ok(count)
+    }
+
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.drain().await {
+                tracing::warn!("Callback dispatch failed: {e}");
+            }
+        }
+    }
+
+    /// Hex HMAC-SHA256 of the body.
+    fn sign(&self, payload: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any size");
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+#[tracing::instrument(skip_all, fields(delivery_id = callback.id, job_id = %callback.job_id))]
+async fn deliver(
+    pool: &PgPool,
+    client: &Client,
+    callback: DueCallback,
+    signature: String,
+) -> Result<(), SchedulingServiceError> {
+    let outcome = client
+        .post(&callback.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Callback-Delivery", callback.id.to_string())
+        .header("X-Callback-Signature", format!("sha256={signature}"))
+        .body(callback.payload)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))
+        .and_then(|res| {
+            if res.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("receiver returned {}", res.status()))
+            }
+        });
+
+    match outcome {
+        Ok(()) => remove(pool, callback.id).await,
+        Err(e) if callback.attempts >= MAX_RETRIES => {
+            tracing::warn!(url = %callback.url, "Dropping job callback after retries: {e}");
+            remove(pool, callback.id).await
+        }
+        Err(e) => {
+            tracing::info!(url = %callback.url, "Job callback failed, will retry: {e}");
+            let delay = (FIRST_RETRY_DELAY_SECS * 2f64.powi(callback.attempts.into()))
+                .min(MAX_RETRY_DELAY_SECS);
+            sqlx::query!(
+                r#"
+                UPDATE callback_deliveries
+                SET attempts = attempts + 1,
+                    next_attempt_at = now() + make_interval(secs => $2)
+                WHERE id = $1
+                "#,
+                callback.id,
+                delay
+            )
+            .execute(pool)
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+async fn remove(pool: &PgPool, id: i64) -> Result<(), SchedulingServiceError> {
+    sqlx::query!(
+        r#"
+        DELETE FROM callback_deliveries
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
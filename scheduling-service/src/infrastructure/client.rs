@@ -1,8 +1,17 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use opentelemetry::global;
 use opentelemetry::propagation::Injector;
-use reqwest::{Client, header};
-use shared::{responses::ApiResponse, types::Staff};
+use reqwest::{Client, RequestBuilder, header};
+use serde::{Serialize, de::DeserializeOwned};
+use shared::{
+    auth::API_KEY_HEADER,
+    pagination::Page,
+    responses::ApiResponse,
+    types::{GroupBlackout, GroupMember, PositionBucket, Staff, TimeOffRequest},
+};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use uuid::Uuid;
 
@@ -11,12 +20,32 @@ use crate::{domain::client::DataServiceClient, error::SchedulingServiceError};
 pub struct HttpDataServiceClient {
     client: Client,
     base_url: String,
+    bearer_token: Option<String>,
+    api_key: Option<String>,
 }
 
 impl HttpDataServiceClient {
     pub fn new(base_url: String) -> Self {
         let client = Client::new();
-        Self { client, base_url }
+        Self {
+            client,
+            base_url,
+            bearer_token: None,
+            api_key: None,
+        }
+    }
+
+    /// Token sent with every call, for a data-service that requires authentication. It needs
+    /// the `viewer` role.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Key sent as `X-Api-Key`, one of the data-service's `AUTH_API_KEYS`.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
     }
 }
 
@@ -32,35 +61,35 @@ impl Injector for HeaderMapInjector<'_> {
     }
 }
 
-#[async_trait]
-impl DataServiceClient for HttpDataServiceClient {
-    #[tracing::instrument(skip(self))]
-    async fn get_resolved_members(
-        &self,
-        staff_group_id: Uuid,
-    ) -> Result<Vec<Staff>, SchedulingServiceError> {
-        let url = format!(
-            "{}/api/v1/groups/{staff_group_id}/resolved-members",
-            self.base_url
-        );
+/// Largest page the data-service accepts.
+const MEMBER_PAGE_SIZE: usize = 1000;
+
+/// Most ids the data-service accepts per staff lookup.
+const LOOKUP_BATCH_SIZE: usize = 1000;
 
+impl HttpDataServiceClient {
+    /// Send a request with trace context attached and unwrap the `ApiResponse` payload.
+    async fn send<T: DeserializeOwned + Serialize>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, SchedulingServiceError> {
         let mut headers = header::HeaderMap::new();
         let cx = tracing::Span::current().context();
         global::get_text_map_propagator(|propagator| {
             propagator.inject_context(&cx, &mut HeaderMapInjector(&mut headers));
         });
 
-        tracing::debug!(%url, "Requesting resolved members");
-
-        let res = self
-            .client
-            .get(&url)
-            .headers(headers)
-            .send()
-            .await
-            .map_err(|e| {
-                SchedulingServiceError::DataService(format!("Failed to reach Data Service:{e}"))
-            })?;
+        let request = match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let request = match &self.api_key {
+            Some(key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        };
+        let res = request.headers(headers).send().await.map_err(|e| {
+            SchedulingServiceError::DataService(format!("Failed to reach Data Service:{e}"))
+        })?;
 
         tracing::debug!(status = %res.status(), "Data service responded");
 
@@ -71,7 +100,7 @@ impl DataServiceClient for HttpDataServiceClient {
             )));
         }
 
-        let api_response = res.json::<ApiResponse<Vec<Staff>>>().await.map_err(|e| {
+        let api_response = res.json::<ApiResponse<T>>().await.map_err(|e| {
             SchedulingServiceError::DataService(format!("Failed to deserialize response: {e}"))
         })?;
 
@@ -80,3 +109,107 @@ impl DataServiceClient for HttpDataServiceClient {
             .ok_or_else(|| SchedulingServiceError::DataService("No data in response".to_string()))
     }
 }
+
+#[async_trait]
+impl DataServiceClient for HttpDataServiceClient {
+    #[tracing::instrument(skip(self))]
+    async fn get_resolved_members(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<Vec<GroupMember>, SchedulingServiceError> {
+        let status_filter = if active_only { "status=active&" } else { "" };
+        let mut members = Vec::new();
+
+        loop {
+            let url = format!(
+                "{}/api/v1/groups/{staff_group_id}/resolved-members?{status_filter}include_future=true&limit={MEMBER_PAGE_SIZE}&offset={}",
+                self.base_url,
+                members.len()
+            );
+            tracing::debug!(%url, "Requesting resolved members");
+            let page: Page<GroupMember> = self.send(self.client.get(&url)).await?;
+            let page_len = page.items.len();
+            members.extend(page.items);
+
+            if page_len < MEMBER_PAGE_SIZE || members.len() as i64 >= page.total {
+                break;
+            }
+        }
+
+        Ok(members)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_resolved_members(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<i64, SchedulingServiceError> {
+        let status_filter = if active_only { "status=active&" } else { "" };
+        let url = format!(
+            "{}/api/v1/groups/{staff_group_id}/resolved-members?{status_filter}count_only=true",
+            self.base_url
+        );
+        let page: Page<Staff> = self.send(self.client.get(&url)).await?;
+
+        Ok(page.total)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_members_by_position(
+        &self,
+        staff_group_id: Uuid,
+        active_only: bool,
+    ) -> Result<HashMap<String, i64>, SchedulingServiceError> {
+        let status_filter = if active_only { "status=active&" } else { "" };
+        let url = format!(
+            "{}/api/v1/groups/{staff_group_id}/members/by-position?{status_filter}count_only=true",
+            self.base_url
+        );
+        let buckets: Vec<PositionBucket> = self.send(self.client.get(&url)).await?;
+
+        Ok(buckets.into_iter().map(|b| (b.position, b.count)).collect())
+    }
+
+    #[tracing::instrument(skip(self, ids), fields(count = ids.len()))]
+    async fn get_staff_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Staff>, SchedulingServiceError> {
+        let url = format!("{}/api/v1/staff/lookup", self.base_url);
+        let mut staff = Vec::with_capacity(ids.len());
+
+        for chunk in ids.chunks(LOOKUP_BATCH_SIZE) {
+            let found: Vec<Staff> = self.send(self.client.post(&url).json(chunk)).await?;
+            staff.extend(found);
+        }
+
+        Ok(staff)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_blackouts(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<GroupBlackout>, SchedulingServiceError> {
+        let url = format!(
+            "{}/api/v1/groups/{staff_group_id}/blackouts?from={from}&to={to}",
+            self.base_url
+        );
+        self.send(self.client.get(&url)).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_time_off(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TimeOffRequest>, SchedulingServiceError> {
+        let url = format!(
+            "{}/api/v1/groups/{staff_group_id}/resolved-members/time-off?from={from}&to={to}",
+            self.base_url
+        );
+        self.send(self.client.get(&url)).await
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use shared::health::DependencyChecks;
+use shared::startup::{StartupSelfCheck, check_database, check_migrations};
+use sqlx::PgPool;
+
+use crate::domain::health::{
+    DATA_SERVICE_DEPENDENCY, HealthChecker, HealthDependency, HealthProber, ProbeReport,
+};
+use crate::domain::scheduler::SchedulingConfig;
+use crate::infrastructure::MIGRATOR;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Plain GET against the health URL; any 2xx counts as healthy. Deliberately skips the
+/// data-service client wrappers so caching can't mask an outage.
+pub struct HttpHealthProber {
+    client: Client,
+}
+
+impl HttpHealthProber {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+}
+
+impl Default for HttpHealthProber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HealthProber for HttpHealthProber {
+    #[tracing::instrument(skip(self), fields(name = %dependency.name))]
+    async fn probe(&self, dependency: &HealthDependency) -> ProbeReport {
+        let started = Instant::now();
+        let result = self
+            .client
+            .get(&dependency.url)
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let (healthy, status, error) = match result {
+            Ok(res) => (res.status().is_success(), Some(res.status().as_u16()), None),
+            Err(e) => (false, None, Some(e.to_string())),
+        };
+
+        ProbeReport {
+            name: dependency.name.clone(),
+            target: dependency.url.clone(),
+            required: dependency.required,
+            healthy,
+            status,
+            latency_ms,
+            error,
+            consecutive_failures: 0,
+        }
+    }
+}
+
+/// Boot checks: our database and its schema, the loaded config and the data-service. The
+/// data-service is probed directly, without the readiness failure threshold.
+pub fn startup_self_check(
+    pool: PgPool,
+    config: &SchedulingConfig,
+    health_checker: Arc<HealthChecker>,
+) -> StartupSelfCheck {
+    let config_result = config
+        .validate()
+        .map(|()| format!("timezone {}", config.timezone));
+    let migrations_pool = pool.clone();
+
+    StartupSelfCheck::new()
+        .with_check("database", move || {
+            let pool = pool.clone();
+            async move { check_database(&pool).await }
+        })
+        .with_check("migrations", move || {
+            let pool = migrations_pool.clone();
+            async move { check_migrations(&pool, &MIGRATOR).await }
+        })
+        .with_check("config", move || std::future::ready(config_result.clone()))
+        .with_check(DATA_SERVICE_DEPENDENCY, move || {
+            let checker = health_checker.clone();
+            async move { probe_data_service(&checker).await }
+        })
+}
+
+/// Pinged on every `/readyz`: our database and the data-service, the latter directly like
+/// the startup check.
+pub fn dependency_checks(pool: PgPool, health_checker: Arc<HealthChecker>) -> DependencyChecks {
+    DependencyChecks::new()
+        .with_check("postgres", move || {
+            let pool = pool.clone();
+            async move { check_database(&pool).await }
+        })
+        .with_check(DATA_SERVICE_DEPENDENCY, move || {
+            let checker = health_checker.clone();
+            async move { probe_data_service(&checker).await }
+        })
+}
+
+async fn probe_data_service(checker: &HealthChecker) -> Result<String, String> {
+    let report = checker
+        .probe(DATA_SERVICE_DEPENDENCY)
+        .await
+        .ok_or_else(|| "not configured".to_string())?;
+    let outcome = match (report.status, &report.error) {
+        (_, Some(error)) => error.clone(),
+        (Some(status), None) => format!("HTTP {status}"),
+        (None, None) => "no response".to_string(),
+    };
+    let detail = format!("{}: {outcome}", report.target);
+    if report.healthy {
+        Ok(detail)
+    } else {
+        Err(detail)
+    }
+}
@@ -1,22 +1,271 @@
 use async_trait::async_trait;
-use chrono::NaiveDate;
-use shared::types::{JobStatus, ScheduleJob, ShiftAssignment, ShiftType};
-use sqlx::PgPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use shared::{
+    pagination::PageRequest,
+    types::{
+        BorrowedStaff, CoverageShortfall, JobStatus, ScheduleJob, ScheduleNote, ShiftAssignment,
+        ShiftType, UnfilledShift,
+    },
+};
+use sqlx::{PgConnection, PgPool, error::ErrorKind};
 use uuid::Uuid;
 
 use crate::{
-    domain::job::{JobRepository, NewShiftAssignment},
+    domain::{
+        acknowledgment::{OutstandingAcknowledgments, StaffAcknowledgment},
+        approval::{ApprovalDecision, ScheduleApproval},
+        checksum::assignments_checksum,
+        consistency::StaffReferences,
+        coverage::{ShiftCoverage, unfilled_shifts},
+        group_config::{GroupRuleOverrides, GroupSchedulingConfig},
+        history::ImportedPeriod,
+        job::{
+            JobOptions, JobPage, JobQuery, JobRepository, NewShiftAssignment, StaffShiftHistory,
+            job_page_limit,
+        },
+        packed::{PackedShifts, pack_shifts},
+        scheduler::{DayOffPairing, WeekAlignment},
+        stats::JobStatusCounts,
+        timeline::{JobEventKind, JobTimelineEvent},
+    },
     error::SchedulingServiceError,
 };
 
+/// A second queued or running job for the same group and period is a 409, whether it was
+/// submitted, retried or forced back to `Pending`.
+fn map_in_flight_conflict(e: sqlx::Error) -> SchedulingServiceError {
+    SchedulingServiceError::from_violation(e, |kind, constraint| match (kind, constraint) {
+        (ErrorKind::UniqueViolation, Some("uq_jobs_in_flight_period")) => {
+            Some(SchedulingServiceError::Conflict(
+                "Another job for this group and period is already queued or running".to_string(),
+            ))
+        }
+        _ => None,
+    })
+}
+
+/// The job is gone, or no longer `Processing` under the worker writing to it: recovery reset
+/// it and another worker may be running it now.
+fn taken_over(job_id: Uuid) -> SchedulingServiceError {
+    SchedulingServiceError::Conflict(format!(
+        "Schedule job {job_id} is no longer processed by this worker"
+    ))
+}
+
+/// A `group_scheduling_configs` row, with the columns as stored.
+struct GroupConfigRow {
+    staff_group_id: Uuid,
+    min_day_off_per_week: Option<i16>,
+    max_day_off_per_week: Option<i16>,
+    no_morning_after_evening: Option<bool>,
+    night_shift: Option<bool>,
+    no_morning_after_night: Option<bool>,
+    max_daily_shift_diff: Option<i16>,
+    day_off_pairing: Option<DayOffPairing>,
+    week_alignment: Option<WeekAlignment>,
+    min_morning: Option<i16>,
+    min_evening: Option<i16>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<GroupConfigRow> for GroupSchedulingConfig {
+    fn from(row: GroupConfigRow) -> Self {
+        let small = |value: Option<i16>| value.map(|v| v.clamp(0, u8::MAX.into()) as u8);
+        Self {
+            staff_group_id: row.staff_group_id,
+            overrides: GroupRuleOverrides {
+                min_day_off_per_week: small(row.min_day_off_per_week),
+                max_day_off_per_week: small(row.max_day_off_per_week),
+                no_morning_after_evening: row.no_morning_after_evening,
+                night_shift: row.night_shift,
+                no_morning_after_night: row.no_morning_after_night,
+                max_daily_shift_diff: small(row.max_daily_shift_diff),
+                day_off_pairing: row.day_off_pairing,
+                week_alignment: row.week_alignment,
+                shift_coverage: ShiftCoverage::from_job(row.min_morning, row.min_evening),
+            },
+            updated_at: row.updated_at,
+        }
+    }
+}
+
 pub struct PgJobRepository {
     pool: PgPool,
+    compact_assignments: bool,
 }
 
 impl PgJobRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            compact_assignments: false,
+        }
+    }
+
+    /// Store new schedules as packed rows, one per staff member, see `[storage]`.
+    pub fn with_compact_assignments(mut self, compact: bool) -> Self {
+        self.compact_assignments = compact;
+        self
+    }
+
+    /// Write a job's assignments in the configured layout. Assignments that can't be packed
+    /// are stored as per-day rows either way.
+    async fn insert_assignments(
+        &self,
+        conn: &mut PgConnection,
+        job_id: Uuid,
+        period_begin_date: NaiveDate,
+        assignments: &[NewShiftAssignment],
+    ) -> Result<(), SchedulingServiceError> {
+        if self.compact_assignments {
+            let packed = pack_shifts(
+                period_begin_date,
+                assignments
+                    .iter()
+                    .map(|a| (a.staff_id, a.date, a.shift_type.clone(), None)),
+            );
+            match packed {
+                Some(packed) => return insert_packed(conn, job_id, &packed).await,
+                None => tracing::warn!(%job_id, "Assignments can't be packed, storing rows"),
+            }
+        }
+
+        let job_ids: Vec<Uuid> = vec![job_id; assignments.len()];
+        let staff_ids: Vec<Uuid> = assignments.iter().map(|a| a.staff_id).collect();
+        let dates: Vec<NaiveDate> = assignments.iter().map(|a| a.date).collect();
+        let shift_types: Vec<ShiftType> =
+            assignments.iter().map(|a| a.shift_type.clone()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO shift_assignments (job_id, staff_id, date, shift_type)
+            SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::date[], $4::shift_type[])
+            "#,
+        )
+        .bind(&job_ids)
+        .bind(&staff_ids)
+        .bind(&dates)
+        .bind(&shift_types)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+async fn insert_packed(
+    conn: &mut PgConnection,
+    job_id: Uuid,
+    packed: &[PackedShifts],
+) -> Result<(), SchedulingServiceError> {
+    let staff_ids: Vec<Uuid> = packed.iter().map(|p| p.staff_id).collect();
+    let shifts: Vec<String> = packed.iter().map(|p| p.shifts.clone()).collect();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO packed_assignments (job_id, staff_id, shifts)
+        SELECT $1, * FROM UNNEST($2::uuid[], $3::text[])
+        "#,
+        job_id,
+        &staff_ids,
+        &shifts,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // Ragged note arrays can't go through UNNEST; few staff have notes anyway
+    for p in packed {
+        if let Some(notes) = &p.notes {
+            sqlx::query!(
+                r#"
+                UPDATE packed_assignments
+                SET notes = $3
+                WHERE job_id = $1 AND staff_id = $2
+                "#,
+                job_id,
+                p.staff_id,
+                notes as &[Option<String>],
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
     }
+
+    Ok(())
+}
+
+/// Turn a packed job back into per-day rows, ids included, so one day can be edited. Locks
+/// the job so a concurrent backfill can't pack it again meanwhile.
+async fn unpack_job(conn: &mut PgConnection, job_id: Uuid) -> Result<(), SchedulingServiceError> {
+    sqlx::query!(
+        r#"
+        SELECT id FROM schedule_jobs
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        job_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let unpacked = sqlx::query!(
+        r#"
+        INSERT INTO shift_assignments (id, job_id, staff_id, date, shift_type, note)
+        SELECT id, job_id, staff_id, date, shift_type, note
+        FROM unpacked_assignments
+        WHERE job_id = $1
+        "#,
+        job_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if unpacked.rows_affected() > 0 {
+        sqlx::query!(
+            r#"
+            DELETE FROM packed_assignments
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Recompute the job's checksum from its stored assignments. Run after `unpack_job`, while
+/// the job is still locked, so a concurrent edit can't slip in between.
+async fn refresh_checksum(
+    conn: &mut PgConnection,
+    job_id: Uuid,
+) -> Result<(), SchedulingServiceError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT staff_id, date, shift_type AS "shift_type: ShiftType"
+        FROM shift_assignments
+        WHERE job_id = $1
+        "#,
+        job_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let checksum = assignments_checksum(rows.iter().map(|r| (r.staff_id, r.date, &r.shift_type)));
+
+    sqlx::query!(
+        r#"
+        UPDATE schedule_jobs
+        SET result_checksum = $2, updated_at = now()
+        WHERE id = $1
+        "#,
+        job_id,
+        checksum,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
 }
 
 #[async_trait]
@@ -26,18 +275,30 @@ impl JobRepository for PgJobRepository {
         &self,
         staff_group_id: Uuid,
         period_begin_date: NaiveDate,
+        depends_on: Vec<Uuid>,
+        options: JobOptions,
     ) -> Result<ScheduleJob, SchedulingServiceError> {
+        // Checked to fit on submission
+        let min_morning = options.coverage.map(|c| c.morning as i16);
+        let min_evening = options.coverage.map(|c| c.evening as i16);
         let output = sqlx::query_as!(ScheduleJob,
             r#"
-            INSERT INTO schedule_jobs (staff_group_id, period_begin_date)
-            VALUES ($1, $2)
-            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", created_at, updated_at
+            INSERT INTO schedule_jobs (staff_group_id, period_begin_date, depends_on, sandbox, periods, min_morning, min_evening, callback_url)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
             "#,
             staff_group_id,
-            period_begin_date
+            period_begin_date,
+            &depends_on,
+            options.sandbox,
+            options.periods,
+            min_morning,
+            min_evening,
+            options.callback_url
         )
         .fetch_one(&self.pool)
-        .await?;
+        .await
+        .map_err(map_in_flight_conflict)?;
 
         Ok(output)
     }
@@ -47,7 +308,7 @@ impl JobRepository for PgJobRepository {
         let output = sqlx::query_as!(
             ScheduleJob,
             r#"
-            SELECT id, staff_group_id, period_begin_date, status AS "status: _", created_at, updated_at
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
             FROM schedule_jobs
             WHERE id = $1
             "#,
@@ -59,61 +320,284 @@ impl JobRepository for PgJobRepository {
         Ok(output)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn find_duplicate_job(
+        &self,
+        staff_group_id: Uuid,
+        period_begin_date: NaiveDate,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            FROM schedule_jobs
+            WHERE staff_group_id = $1 AND period_begin_date = $2
+                AND status <> 'FAILED' AND NOT sandbox
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            staff_group_id,
+            period_begin_date
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn update_status(
         &self,
         id: Uuid,
         status: JobStatus,
+        worker_id: Option<String>,
     ) -> Result<(), SchedulingServiceError> {
         let output = sqlx::query!(
             r#"
             UPDATE schedule_jobs
             SET status = $2, updated_at = now()
-            WHERE id = $1
+            WHERE id = $1 AND status = 'PROCESSING' AND worker_id IS NOT DISTINCT FROM $3
             "#,
             id,
             status as _,
+            worker_id,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if output.rows_affected() == 0 {
+            return Err(taken_over(id));
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, error_message))]
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error_message: String,
+        worker_id: Option<String>,
+    ) -> Result<(), SchedulingServiceError> {
+        let output = sqlx::query!(
+            r#"
+            UPDATE schedule_jobs
+            SET status = 'FAILED', error_message = $2, updated_at = now()
+            WHERE id = $1 AND status = 'PROCESSING' AND worker_id IS NOT DISTINCT FROM $3
+            "#,
+            id,
+            error_message,
+            worker_id,
         )
         .execute(&self.pool)
         .await?;
 
         if output.rows_affected() == 0 {
-            return Err(SchedulingServiceError::NotFound(format!(
-                "Schedule job {id} not found"
-            )));
+            return Err(taken_over(id));
         }
 
         Ok(())
     }
 
-    #[tracing::instrument(skip(self, assignments))]
+    #[tracing::instrument(skip(self, assignments, borrowed, shortfalls))]
     async fn save_assignments(
         &self,
         job_id: Uuid,
         assignments: Vec<NewShiftAssignment>,
+        checksum: String,
+        borrowed: Vec<BorrowedStaff>,
+        shortfalls: Vec<CoverageShortfall>,
+        worker_id: Option<String>,
     ) -> Result<(), SchedulingServiceError> {
-        let job_ids: Vec<Uuid> = vec![job_id; assignments.len()];
-        let staff_ids: Vec<Uuid> = assignments.iter().map(|a| a.staff_id).collect();
-        let dates: Vec<NaiveDate> = assignments.iter().map(|a| a.date).collect();
-        let shift_types: Vec<ShiftType> =
-            assignments.iter().map(|a| a.shift_type.clone()).collect();
+        let mut tx = self.pool.begin().await?;
+
+        let period_begin_date = sqlx::query_scalar!(
+            r#"
+            UPDATE schedule_jobs
+            SET result_checksum = $2, updated_at = now()
+            WHERE id = $1 AND status = 'PROCESSING' AND worker_id IS NOT DISTINCT FROM $3
+            RETURNING period_begin_date
+            "#,
+            job_id,
+            checksum,
+            worker_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| taken_over(job_id))?;
+
+        self.insert_assignments(&mut tx, job_id, period_begin_date, &assignments)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM borrowed_staff
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let (staff_ids, pool_group_ids): (Vec<Uuid>, Vec<Uuid>) = borrowed
+            .iter()
+            .map(|b| (b.staff_id, b.pool_group_id))
+            .unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO borrowed_staff (job_id, staff_id, pool_group_id)
+            SELECT $1, * FROM UNNEST($2::uuid[], $3::uuid[])
+            "#,
+            job_id,
+            &staff_ids,
+            &pool_group_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM coverage_shortfalls
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
 
+        let dates: Vec<NaiveDate> = shortfalls.iter().map(|s| s.date).collect();
+        let shift_types: Vec<ShiftType> = shortfalls.iter().map(|s| s.shift_type.clone()).collect();
+        let positions: Vec<Option<String>> =
+            shortfalls.iter().map(|s| s.position.clone()).collect();
+        let required: Vec<i32> = shortfalls.iter().map(|s| s.required as i32).collect();
+        let assigned: Vec<i32> = shortfalls.iter().map(|s| s.assigned as i32).collect();
+        let unfilled: Vec<bool> = shortfalls.iter().map(|s| s.unfilled).collect();
         sqlx::query(
             r#"
-            INSERT INTO shift_assignments (job_id, staff_id, date, shift_type)
-            SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::date[], $4::shift_type[])
+            INSERT INTO coverage_shortfalls
+                (job_id, date, shift_type, position, required, assigned, unfilled)
+            SELECT $1, * FROM UNNEST(
+                $2::date[], $3::shift_type[], $4::text[], $5::int[], $6::int[], $7::boolean[]
+            )
             "#,
         )
-        .bind(&job_ids)
-        .bind(&staff_ids)
+        .bind(job_id)
         .bind(&dates)
         .bind(&shift_types)
-        .execute(&self.pool)
+        .bind(&positions)
+        .bind(&required)
+        .bind(&assigned)
+        .bind(&unfilled)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM unfilled_shifts
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let placeholders = unfilled_shifts(&shortfalls);
+        let dates: Vec<NaiveDate> = placeholders.iter().map(|u| u.date).collect();
+        let shift_types: Vec<ShiftType> =
+            placeholders.iter().map(|u| u.shift_type.clone()).collect();
+        let positions: Vec<Option<String>> =
+            placeholders.iter().map(|u| u.position.clone()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO unfilled_shifts (job_id, date, shift_type, position)
+            SELECT $1, * FROM UNNEST($2::date[], $3::shift_type[], $4::text[])
+            "#,
+        )
+        .bind(job_id)
+        .bind(&dates)
+        .bind(&shift_types)
+        .bind(&positions)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn get_unfilled_shifts(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<UnfilledShift>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            UnfilledShift,
+            r#"
+            SELECT id, job_id, date, shift_type AS "shift_type: ShiftType", position
+            FROM unfilled_shifts
+            WHERE job_id = $1
+            ORDER BY date, shift_type, position NULLS LAST, id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_borrowed_staff(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<BorrowedStaff>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            BorrowedStaff,
+            r#"
+            SELECT staff_id, pool_group_id
+            FROM borrowed_staff
+            WHERE job_id = $1
+            ORDER BY staff_id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_coverage_shortfalls(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<CoverageShortfall>, SchedulingServiceError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT date, shift_type AS "shift_type: ShiftType", position, required, assigned,
+                unfilled
+            FROM coverage_shortfalls
+            WHERE job_id = $1
+            ORDER BY date, position NULLS FIRST, shift_type
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CoverageShortfall {
+                date: row.date,
+                shift_type: row.shift_type,
+                position: row.position,
+                required: row.required as u32,
+                assigned: row.assigned as u32,
+                unfilled: row.unfilled,
+            })
+            .collect())
+    }
+
     #[tracing::instrument(skip(self))]
     async fn get_assignments(
         &self,
@@ -122,8 +606,9 @@ impl JobRepository for PgJobRepository {
         let output = sqlx::query_as!(
             ShiftAssignment,
             r#"
-            SELECT id, job_id, staff_id, date, shift_type AS "shift_type: _"
-            FROM shift_assignments
+            SELECT id AS "id!", job_id AS "job_id!", staff_id AS "staff_id!", date AS "date!",
+                shift_type AS "shift_type!: _", note
+            FROM all_shift_assignments
             WHERE job_id = $1
             ORDER BY staff_id, date
             "#,
@@ -135,6 +620,48 @@ impl JobRepository for PgJobRepository {
         Ok(output)
     }
 
+    #[tracing::instrument(skip(self, body))]
+    async fn add_note(
+        &self,
+        job_id: Uuid,
+        author: String,
+        body: String,
+    ) -> Result<ScheduleNote, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleNote,
+            r#"
+            INSERT INTO schedule_notes (job_id, author, body)
+            VALUES ($1, $2, $3)
+            RETURNING id, job_id, author, body, created_at
+            "#,
+            job_id,
+            author,
+            body
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_notes(&self, job_id: Uuid) -> Result<Vec<ScheduleNote>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleNote,
+            r#"
+            SELECT id, job_id, author, body, created_at
+            FROM schedule_notes
+            WHERE job_id = $1
+            ORDER BY created_at, id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn find_by_status(
         &self,
@@ -143,7 +670,7 @@ impl JobRepository for PgJobRepository {
         let output = sqlx::query_as!(
             ScheduleJob,
             r#"
-            SELECT id, staff_group_id, period_begin_date, status AS "status: _", created_at, updated_at
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
             FROM schedule_jobs
             WHERE status = $1
             ORDER BY created_at ASC
@@ -157,13 +684,1135 @@ impl JobRepository for PgJobRepository {
     }
 
     #[tracing::instrument(skip(self))]
-    async fn delete_assignments(&self, job_id: Uuid) -> Result<(), SchedulingServiceError> {
-        sqlx::query!(
+    async fn count_by_status(&self) -> Result<Vec<(JobStatus, i64)>, SchedulingServiceError> {
+        let rows = sqlx::query!(
             r#"
-            DELETE FROM shift_assignments
-            WHERE job_id = $1
+            SELECT status AS "status: JobStatus", COUNT(*) AS "count!"
+            FROM schedule_jobs
+            GROUP BY status
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn job_stats(
+        &self,
+        since: DateTime<Utc>,
+        staff_group_id: Option<Uuid>,
+        include_sandbox: bool,
+    ) -> Result<JobStatusCounts, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            JobStatusCounts,
+            r#"
+            WITH jobs AS (
+                SELECT id, status
+                FROM schedule_jobs
+                WHERE created_at >= $1
+                    AND ($2::uuid IS NULL OR staff_group_id = $2)
+                    AND ($3 OR NOT sandbox)
+            ),
+            processing_times AS (
+                SELECT max(je.occurred_at) FILTER (WHERE je.kind IN ('COMPLETED', 'FAILED'))
+                    - max(je.occurred_at) FILTER (WHERE je.kind = 'PICKED_UP') AS took
+                FROM jobs
+                JOIN job_events je ON je.job_id = jobs.id
+                WHERE jobs.status IN ('COMPLETED', 'FAILED')
+                GROUP BY jobs.id
+            )
+            SELECT
+                (SELECT count(*) FROM jobs WHERE status = 'PENDING') AS "pending!",
+                (SELECT count(*) FROM jobs WHERE status = 'PROCESSING') AS "processing!",
+                (SELECT count(*) FROM jobs WHERE status = 'COMPLETED') AS "completed!",
+                (SELECT count(*) FROM jobs WHERE status = 'FAILED') AS "failed!",
+                (
+                    SELECT avg(EXTRACT(EPOCH FROM took) * 1000)::float8
+                    FROM processing_times
+                    WHERE took >= interval '0'
+                ) AS avg_processing_ms
             "#,
-            job_id
+            since,
+            staff_group_id,
+            include_sandbox
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_paginated(
+        &self,
+        query: JobQuery,
+        page: PageRequest,
+    ) -> Result<JobPage, SchedulingServiceError> {
+        let limit = job_page_limit(&page);
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM schedule_jobs
+            WHERE ($1::job_status IS NULL OR status = $1)
+                AND ($2::uuid IS NULL OR staff_group_id = $2)
+                AND ($3::date IS NULL OR period_begin_date >= $3)
+                AND ($4::date IS NULL OR period_begin_date <= $4)
+                AND ($5 OR NOT sandbox)
+            "#,
+            query.status.clone() as Option<JobStatus>,
+            query.staff_group_id,
+            query.from,
+            query.to,
+            query.include_sandbox
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let items = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            FROM schedule_jobs
+            WHERE ($1::job_status IS NULL OR status = $1)
+                AND ($2::uuid IS NULL OR staff_group_id = $2)
+                AND ($3::date IS NULL OR period_begin_date >= $3)
+                AND ($4::date IS NULL OR period_begin_date <= $4)
+                AND ($5 OR NOT sandbox)
+            ORDER BY created_at, id
+            LIMIT $6 OFFSET $7
+            "#,
+            query.status as Option<JobStatus>,
+            query.staff_group_id,
+            query.from,
+            query.to,
+            query.include_sandbox,
+            limit,
+            page.offset()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(JobPage {
+            items,
+            total,
+            limit,
+            offset: page.offset(),
+        })
+    }
+
+    #[tracing::instrument(skip(self, note))]
+    async fn update_assignment(
+        &self,
+        job_id: Uuid,
+        assignment_id: Uuid,
+        shift_type: ShiftType,
+        note: Option<String>,
+    ) -> Result<Option<ShiftAssignment>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        unpack_job(&mut tx, job_id).await?;
+
+        let assignment = sqlx::query_as!(
+            ShiftAssignment,
+            r#"
+            UPDATE shift_assignments
+            SET shift_type = $3, note = $4
+            WHERE job_id = $1 AND id = $2
+            RETURNING id, job_id, staff_id, date, shift_type AS "shift_type: _", note
+            "#,
+            job_id,
+            assignment_id,
+            shift_type as _,
+            note,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if assignment.is_some() {
+            refresh_checksum(&mut tx, job_id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(assignment)
+    }
+
+    #[tracing::instrument(skip(self, changes), fields(changes = changes.len()))]
+    async fn update_assignments(
+        &self,
+        job_id: Uuid,
+        changes: Vec<(Uuid, ShiftType)>,
+    ) -> Result<Option<Vec<ShiftAssignment>>, SchedulingServiceError> {
+        let (ids, shift_types): (Vec<Uuid>, Vec<ShiftType>) = changes.into_iter().unzip();
+        let mut tx = self.pool.begin().await?;
+
+        unpack_job(&mut tx, job_id).await?;
+
+        let mut assignments: Vec<ShiftAssignment> = sqlx::query_as(
+            r#"
+            UPDATE shift_assignments AS a
+            SET shift_type = c.shift_type
+            FROM UNNEST($2::uuid[], $3::shift_type[]) AS c(id, shift_type)
+            WHERE a.job_id = $1 AND a.id = c.id
+            RETURNING a.id, a.job_id, a.staff_id, a.date, a.shift_type, a.note
+            "#,
+        )
+        .bind(job_id)
+        .bind(&ids)
+        .bind(&shift_types)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Dropping the transaction rolls back the ones that did match
+        if assignments.len() != ids.len() {
+            return Ok(None);
+        }
+
+        refresh_checksum(&mut tx, job_id).await?;
+
+        tx.commit().await?;
+
+        assignments.sort_by_key(|a| (a.date, a.staff_id));
+        Ok(Some(assignments))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_overlapping_jobs(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            FROM schedule_jobs
+            WHERE staff_group_id = $1
+              AND period_begin_date BETWEEN $2 AND $3
+              AND status <> 'FAILED'
+              AND NOT sandbox
+            ORDER BY period_begin_date, created_at
+            "#,
+            staff_group_id,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_ready_dependents(
+        &self,
+        dependency: Option<Uuid>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            SELECT id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            FROM schedule_jobs j
+            WHERE status = 'PENDING'
+              AND cardinality(depends_on) > 0
+              AND ($1::uuid IS NULL OR $1 = ANY(depends_on))
+              AND NOT EXISTS (
+                  SELECT 1 FROM schedule_jobs d
+                  WHERE d.id = ANY(j.depends_on) AND d.status <> 'COMPLETED'
+              )
+            ORDER BY created_at ASC
+            "#,
+            dependency,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_pending_job(
+        &self,
+        job_id: Uuid,
+        seen_version: i32,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            UPDATE schedule_jobs
+            SET updated_at = now()
+            WHERE id = $1 AND status = 'PENDING' AND version = $2
+            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            "#,
+            job_id,
+            seen_version,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn start_pending_job(
+        &self,
+        job_id: Uuid,
+        seen_version: i32,
+        worker_id: Option<String>,
+    ) -> Result<bool, SchedulingServiceError> {
+        let output = sqlx::query!(
+            r#"
+            UPDATE schedule_jobs
+            SET status = 'PROCESSING', worker_id = $3, heartbeat_at = now(), updated_at = now()
+            WHERE id = $1 AND status = 'PENDING' AND version = $2
+            "#,
+            job_id,
+            seen_version,
+            worker_id,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(output.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn heartbeat(
+        &self,
+        job_id: Uuid,
+        worker_id: Option<String>,
+    ) -> Result<(), SchedulingServiceError> {
+        sqlx::query!(
+            r#"
+            UPDATE schedule_jobs
+            SET heartbeat_at = now()
+            WHERE id = $1 AND status = 'PROCESSING' AND worker_id IS NOT DISTINCT FROM $2
+            "#,
+            job_id,
+            worker_id,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reset_abandoned_jobs(
+        &self,
+        stale_before: DateTime<Utc>,
+        worker_id: Option<String>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let jobs = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            UPDATE schedule_jobs
+            SET status = 'PENDING', result_checksum = NULL, worker_id = NULL, heartbeat_at = NULL,
+                updated_at = now()
+            WHERE status = 'PROCESSING'
+              AND (heartbeat_at IS NULL OR heartbeat_at < $1 OR worker_id = $2)
+            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            "#,
+            stale_before,
+            worker_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let ids: Vec<Uuid> = jobs.iter().map(|job| job.id).collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM shift_assignments
+            WHERE job_id = ANY($1)
+            "#,
+            &ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM packed_assignments
+            WHERE job_id = ANY($1)
+            "#,
+            &ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(jobs)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_assignments(&self, job_id: Uuid) -> Result<(), SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM shift_assignments
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM packed_assignments
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reset_failed_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let job = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            UPDATE schedule_jobs
+            SET status = 'PENDING', result_checksum = NULL, error_message = NULL, updated_at = now()
+            WHERE id = $1 AND status = 'FAILED'
+            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            "#,
+            job_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_in_flight_conflict)?;
+
+        if job.is_some() {
+            sqlx::query!(
+                r#"
+                DELETE FROM shift_assignments
+                WHERE job_id = $1
+                "#,
+                job_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM packed_assignments
+                WHERE job_id = $1
+                "#,
+                job_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(job)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn override_status(
+        &self,
+        job_id: Uuid,
+        from: JobStatus,
+        to: JobStatus,
+    ) -> Result<Option<ScheduleJob>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let reset = to == JobStatus::Pending;
+
+        let job = sqlx::query_as!(
+            ScheduleJob,
+            r#"
+            UPDATE schedule_jobs
+            SET status = $3,
+                result_checksum = CASE WHEN $4 THEN NULL ELSE result_checksum END,
+                error_message = NULL,
+                updated_at = now()
+            WHERE id = $1 AND status = $2
+            RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+            "#,
+            job_id,
+            from as _,
+            to as _,
+            reset
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_in_flight_conflict)?;
+
+        if job.is_some() && reset {
+            sqlx::query!(
+                r#"
+                DELETE FROM shift_assignments
+                WHERE job_id = $1
+                "#,
+                job_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM packed_assignments
+                WHERE job_id = $1
+                "#,
+                job_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(job)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_group_assignments(
+        &self,
+        staff_group_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ShiftAssignment>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ShiftAssignment,
+            r#"
+            WITH latest_jobs AS (
+                SELECT DISTINCT ON (period_begin_date) id
+                FROM schedule_jobs
+                WHERE staff_group_id = $1 AND status = 'COMPLETED' AND NOT sandbox
+                    AND published_at IS NOT NULL
+                ORDER BY period_begin_date, created_at DESC
+            )
+            SELECT sa.id AS "id!", sa.job_id AS "job_id!", sa.staff_id AS "staff_id!",
+                sa.date AS "date!", sa.shift_type AS "shift_type!: _", sa.note
+            FROM all_shift_assignments sa
+            JOIN latest_jobs lj ON sa.job_id = lj.id
+            WHERE sa.date BETWEEN $2 AND $3 AND sa.shift_type <> 'DAY_OFF'
+            ORDER BY sa.date, sa.staff_id
+            "#,
+            staff_group_id,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_staff_assignments(
+        &self,
+        staff_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        published_only: bool,
+    ) -> Result<Vec<ShiftAssignment>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ShiftAssignment,
+            r#"
+            WITH latest_jobs AS (
+                SELECT DISTINCT ON (staff_group_id, period_begin_date) id
+                FROM schedule_jobs
+                WHERE status = 'COMPLETED' AND NOT sandbox
+                    AND (published_at IS NOT NULL OR NOT $4)
+                    AND period_begin_date <= $3
+                    AND period_begin_date + periods * 28 > $2
+                ORDER BY staff_group_id, period_begin_date, created_at DESC
+            )
+            SELECT sa.id AS "id!", sa.job_id AS "job_id!", sa.staff_id AS "staff_id!",
+                sa.date AS "date!", sa.shift_type AS "shift_type!: _", sa.note
+            FROM all_shift_assignments sa
+            JOIN latest_jobs lj ON sa.job_id = lj.id
+            WHERE sa.staff_id = $1 AND sa.date BETWEEN $2 AND $3
+            ORDER BY sa.date, sa.job_id
+            "#,
+            staff_id,
+            from,
+            to,
+            published_only,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn purge_sandbox_jobs(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, SchedulingServiceError> {
+        let output = sqlx::query!(
+            r#"
+            DELETE FROM schedule_jobs
+            WHERE sandbox AND created_at < $1 AND status <> 'PROCESSING'
+            "#,
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(output.rows_affected())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_shift_history(
+        &self,
+        staff_group_id: Uuid,
+        before: NaiveDate,
+        periods: i64,
+    ) -> Result<Vec<StaffShiftHistory>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            StaffShiftHistory,
+            r#"
+            WITH recent_jobs AS (
+                SELECT id
+                FROM (
+                    SELECT DISTINCT ON (period_begin_date) id, period_begin_date
+                    FROM schedule_jobs
+                    WHERE staff_group_id = $1 AND status = 'COMPLETED' AND NOT sandbox
+                        AND period_begin_date < $2
+                    ORDER BY period_begin_date, created_at DESC
+                ) latest
+                ORDER BY period_begin_date DESC
+                LIMIT $3
+            )
+            SELECT sa.staff_id AS "staff_id!",
+                COUNT(*) FILTER (
+                    WHERE sa.shift_type = 'DAY_OFF' AND EXTRACT(ISODOW FROM sa.date) >= 6
+                ) AS "weekend_days_off!",
+                COUNT(*) FILTER (WHERE sa.shift_type = 'EVENING') AS "evening_shifts!"
+            FROM all_shift_assignments sa
+            JOIN recent_jobs rj ON sa.job_id = rj.id
+            WHERE sa.date < $2
+            GROUP BY sa.staff_id
+            "#,
+            staff_group_id,
+            before,
+            periods
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self, periods))]
+    async fn import_completed_jobs(
+        &self,
+        staff_group_id: Uuid,
+        periods: Vec<ImportedPeriod>,
+    ) -> Result<Vec<ScheduleJob>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let mut output = Vec::with_capacity(periods.len());
+
+        for period in periods {
+            let job = sqlx::query_as!(ScheduleJob,
+                r#"
+                INSERT INTO schedule_jobs (staff_group_id, period_begin_date, status, result_checksum)
+                VALUES ($1, $2, 'COMPLETED', $3)
+                RETURNING id, staff_group_id, period_begin_date, status AS "status: _", result_checksum, depends_on, sandbox, periods, min_morning, min_evening, error_message, created_at, updated_at, version
+                "#,
+                staff_group_id,
+                period.period_begin_date,
+                period.checksum
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            self.insert_assignments(
+                &mut tx,
+                job.id,
+                period.period_begin_date,
+                &period.assignments,
+            )
+            .await?;
+
+            output.push(job);
+        }
+
+        tx.commit().await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self, comment))]
+    async fn record_approval(
+        &self,
+        job_id: Uuid,
+        approver_id: Uuid,
+        decision: ApprovalDecision,
+        comment: Option<String>,
+        checksum: Option<String>,
+    ) -> Result<(), SchedulingServiceError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO schedule_approvals (job_id, approver_id, decision, comment, checksum)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (job_id, approver_id) DO UPDATE
+            SET decision = EXCLUDED.decision,
+                comment = EXCLUDED.comment,
+                checksum = EXCLUDED.checksum,
+                decided_at = now()
+            "#,
+            job_id,
+            approver_id,
+            decision as _,
+            comment,
+            checksum
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_approvals(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<ScheduleApproval>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            ScheduleApproval,
+            r#"
+            SELECT job_id, approver_id, decision AS "decision: _", comment, checksum, decided_at
+            FROM schedule_approvals
+            WHERE job_id = $1
+            ORDER BY decided_at DESC, approver_id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_published_at(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, SchedulingServiceError> {
+        let output = sqlx::query_scalar!(
+            "SELECT published_at FROM schedule_jobs WHERE id = $1",
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output.flatten())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn publish_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, SchedulingServiceError> {
+        let output = sqlx::query_scalar!(
+            r#"
+            UPDATE schedule_jobs
+            SET published_at = now(), updated_at = now()
+            WHERE id = $1 AND published_at IS NULL
+            RETURNING published_at AS "published_at!"
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn record_acknowledgment(
+        &self,
+        job_id: Uuid,
+        staff_id: Uuid,
+    ) -> Result<DateTime<Utc>, SchedulingServiceError> {
+        let output = sqlx::query_scalar!(
+            r#"
+            INSERT INTO schedule_acknowledgments (job_id, staff_id)
+            VALUES ($1, $2)
+            ON CONFLICT (job_id, staff_id)
+            DO UPDATE SET acknowledged_at = schedule_acknowledgments.acknowledged_at
+            RETURNING acknowledged_at
+            "#,
+            job_id,
+            staff_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_acknowledgments(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<StaffAcknowledgment>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            StaffAcknowledgment,
+            r#"
+            SELECT roster.staff_id AS "staff_id!", sa.acknowledged_at AS "acknowledged_at?"
+            FROM (
+                SELECT DISTINCT staff_id FROM all_shift_assignments WHERE job_id = $1
+            ) roster
+            LEFT JOIN schedule_acknowledgments sa
+                ON sa.job_id = $1 AND sa.staff_id = roster.staff_id
+            ORDER BY roster.staff_id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_outstanding_acknowledgments(
+        &self,
+        staff_group_id: Option<Uuid>,
+    ) -> Result<Vec<OutstandingAcknowledgments>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            OutstandingAcknowledgments,
+            r#"
+            SELECT sj.id AS schedule_id, sj.staff_group_id, sj.period_begin_date,
+                sj.published_at AS "published_at!",
+                COUNT(*) AS "roster_size!",
+                ARRAY_AGG(roster.staff_id ORDER BY roster.staff_id)
+                    FILTER (WHERE sa.staff_id IS NULL) AS "outstanding!",
+                sj.acknowledgment_reminded_at AS last_reminded_at
+            FROM schedule_jobs sj
+            JOIN LATERAL (
+                SELECT DISTINCT staff_id FROM all_shift_assignments WHERE job_id = sj.id
+            ) roster ON true
+            LEFT JOIN schedule_acknowledgments sa
+                ON sa.job_id = sj.id AND sa.staff_id = roster.staff_id
+            WHERE sj.published_at IS NOT NULL
+                AND NOT sj.sandbox
+                AND ($1::uuid IS NULL OR sj.staff_group_id = $1)
+                AND sj.period_begin_date + 28 * sj.periods > CURRENT_DATE
+            GROUP BY sj.id
+            HAVING COUNT(*) FILTER (WHERE sa.staff_id IS NULL) > 0
+            ORDER BY sj.period_begin_date DESC, sj.id
+            "#,
+            staff_group_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn mark_acknowledgment_reminder(
+        &self,
+        job_id: Uuid,
+        last_reminded_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, SchedulingServiceError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE schedule_jobs
+            SET acknowledgment_reminded_at = now()
+            WHERE id = $1 AND acknowledgment_reminded_at IS NOT DISTINCT FROM $2
+            "#,
+            job_id,
+            last_reminded_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(skip(self, detail))]
+    async fn record_job_event(
+        &self,
+        job_id: Uuid,
+        kind: JobEventKind,
+        detail: Option<String>,
+        duration_ms: Option<i64>,
+    ) -> Result<(), SchedulingServiceError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO job_events (job_id, kind, detail, duration_ms)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            job_id,
+            kind as _,
+            detail,
+            duration_ms
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_job_events(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<JobTimelineEvent>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            JobTimelineEvent,
+            r#"
+            SELECT kind AS "kind: _", detail, duration_ms, occurred_at
+            FROM job_events
+            WHERE job_id = $1
+            ORDER BY occurred_at, id
+            "#,
+            job_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_unpacked_jobs(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, SchedulingServiceError> {
+        let output = sqlx::query_scalar!(
+            r#"
+            SELECT sj.id
+            FROM schedule_jobs sj
+            WHERE sj.status = 'COMPLETED'
+                AND ($1::uuid IS NULL OR sj.id > $1)
+                AND EXISTS (SELECT 1 FROM shift_assignments sa WHERE sa.job_id = sj.id)
+            ORDER BY sj.id
+            LIMIT $2
+            "#,
+            after,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_staff_references(&self) -> Result<Vec<StaffReferences>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            StaffReferences,
+            r#"
+            SELECT staff_id AS "staff_id!", array_agg(DISTINCT job_id ORDER BY job_id) AS "job_ids!"
+            FROM (
+                SELECT staff_id, job_id FROM shift_assignments
+                UNION
+                SELECT staff_id, job_id FROM packed_assignments
+            ) refs
+            GROUP BY staff_id
+            ORDER BY staff_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(output)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn pack_job(&self, job_id: Uuid) -> Result<Option<u64>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(period_begin_date) = sqlx::query_scalar!(
+            r#"
+            SELECT period_begin_date
+            FROM schedule_jobs
+            WHERE id = $1 AND status = 'COMPLETED'
+            FOR UPDATE
+            "#,
+            job_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT staff_id, date, shift_type AS "shift_type: ShiftType", note
+            FROM shift_assignments
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let Some(packed) = pack_shifts(
+            period_begin_date,
+            rows.into_iter()
+                .map(|r| (r.staff_id, r.date, r.shift_type, r.note)),
+        ) else {
+            return Ok(None);
+        };
+        insert_packed(&mut tx, job_id, &packed).await?;
+
+        let replaced = sqlx::query!(
+            r#"
+            DELETE FROM shift_assignments
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(replaced.rows_affected()))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<Option<GroupSchedulingConfig>, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            GroupConfigRow,
+            r#"
+            SELECT staff_group_id, min_day_off_per_week, max_day_off_per_week,
+                   no_morning_after_evening, night_shift, no_morning_after_night,
+                   max_daily_shift_diff, day_off_pairing AS "day_off_pairing: _",
+                   week_alignment AS "week_alignment: _", min_morning, min_evening, updated_at
+            FROM group_scheduling_configs
+            WHERE staff_group_id = $1
+            "#,
+            staff_group_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output.map(GroupSchedulingConfig::from))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn upsert_group_config(
+        &self,
+        staff_group_id: Uuid,
+        overrides: GroupRuleOverrides,
+    ) -> Result<GroupSchedulingConfig, SchedulingServiceError> {
+        let output = sqlx::query_as!(
+            GroupConfigRow,
+            r#"
+            INSERT INTO group_scheduling_configs (
+                staff_group_id, min_day_off_per_week, max_day_off_per_week,
+                no_morning_after_evening, night_shift, no_morning_after_night,
+                max_daily_shift_diff, day_off_pairing, week_alignment, min_morning, min_evening
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (staff_group_id) DO UPDATE
+            SET min_day_off_per_week = EXCLUDED.min_day_off_per_week,
+                max_day_off_per_week = EXCLUDED.max_day_off_per_week,
+                no_morning_after_evening = EXCLUDED.no_morning_after_evening,
+                night_shift = EXCLUDED.night_shift,
+                no_morning_after_night = EXCLUDED.no_morning_after_night,
+                max_daily_shift_diff = EXCLUDED.max_daily_shift_diff,
+                day_off_pairing = EXCLUDED.day_off_pairing,
+                week_alignment = EXCLUDED.week_alignment,
+                min_morning = EXCLUDED.min_morning,
+                min_evening = EXCLUDED.min_evening,
+                updated_at = now()
+            RETURNING staff_group_id, min_day_off_per_week, max_day_off_per_week,
+                      no_morning_after_evening, night_shift, no_morning_after_night,
+                      max_daily_shift_diff, day_off_pairing AS "day_off_pairing: _",
+                      week_alignment AS "week_alignment: _", min_morning, min_evening, updated_at
+            "#,
+            staff_group_id,
+            overrides.min_day_off_per_week.map(i16::from),
+            overrides.max_day_off_per_week.map(i16::from),
+            overrides.no_morning_after_evening,
+            overrides.night_shift,
+            overrides.no_morning_after_night,
+            overrides.max_daily_shift_diff.map(i16::from),
+            overrides.day_off_pairing as _,
+            overrides.week_alignment as _,
+            overrides.shift_coverage.map(|c| c.morning as i16),
+            overrides.shift_coverage.map(|c| c.evening as i16)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(output.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete_group_config(
+        &self,
+        staff_group_id: Uuid,
+    ) -> Result<bool, SchedulingServiceError> {
+        let output = sqlx::query!(
+            "DELETE FROM group_scheduling_configs WHERE staff_group_id = $1",
+            staff_group_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(output.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_callback_url(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<String>, SchedulingServiceError> {
+        let output = sqlx::query_scalar!(
+            r#"
+            SELECT callback_url
+            FROM schedule_jobs
+            WHERE id = $1
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(output.flatten())
+    }
+
+    #[tracing::instrument(skip(self, payload))]
+    async fn enqueue_callback(
+        &self,
+        job_id: Uuid,
+        url: String,
+        payload: String,
+    ) -> Result<(), SchedulingServiceError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO callback_deliveries (job_id, url, payload)
+            VALUES ($1, $2, $3)
+            "#,
+            job_id,
+            url,
+            payload
         )
         .execute(&self.pool)
         .await?;
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    domain::lock::{TaskLock, TaskLockGuard},
+    error::SchedulingServiceError,
+};
+
+/// A run that comes due this much before `interval` is up still counts, so a replica whose
+/// ticker fires a moment early doesn't skip a whole round.
+const DUE_TOLERANCE: Duration = Duration::from_secs(5);
+
+/// Postgres advisory locks keyed by `hashtext(name)`. The lock is transaction scoped, so it
+/// is released when the guard's transaction ends, including when the connection drops. Runs
+/// of interval tasks are recorded in `task_runs`, outside that transaction, so they persist.
+pub struct PgTaskLock {
+    pool: PgPool,
+}
+
+impl PgTaskLock {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct PgTaskLockGuard {
+    _tx: Transaction<'static, Postgres>,
+}
+
+impl TaskLockGuard for PgTaskLockGuard {}
+
+#[async_trait]
+impl TaskLock for PgTaskLock {
+    #[tracing::instrument(skip(self))]
+    async fn try_acquire(
+        &self,
+        name: &str,
+    ) -> Result<Option<Box<dyn TaskLockGuard>>, SchedulingServiceError> {
+        let mut tx = self.pool.begin().await?;
+
+        let acquired = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_xact_lock(hashtext($1)) AS "acquired!""#,
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !acquired {
+            return Ok(None);
+        }
+
+        Ok(Some(Box::new(PgTaskLockGuard { _tx: tx })))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn try_acquire_due(
+        &self,
+        name: &str,
+        interval: Duration,
+    ) -> Result<Option<Box<dyn TaskLockGuard>>, SchedulingServiceError> {
+        let Some(guard) = self.try_acquire(name).await? else {
+            return Ok(None);
+        };
+
+        let due_after = interval.saturating_sub(DUE_TOLERANCE).as_secs_f64();
+        let claimed = sqlx::query!(
+            r#"
+            INSERT INTO task_runs (name, last_run_at)
+            VALUES ($1, now())
+            ON CONFLICT (name) DO UPDATE
+            SET last_run_at = now()
+            WHERE task_runs.last_run_at <= now() - make_interval(secs => $2)
+            "#,
+            name,
+            due_after,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok((claimed.rows_affected() == 1).then_some(guard))
+    }
+}
@@ -0,0 +1,178 @@
+use std::{env, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor, message::Mailbox,
+    transport::smtp::authentication::Credentials,
+};
+use reqwest::Client;
+use serde_json::json;
+
+use crate::domain::notify::{ChannelConfig, JobEvent, NotificationConfig, Notifier, NotifyError};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build one notifier per configured channel. Secrets referenced by the config are read
+/// from the environment here, so a missing one fails startup rather than the first send.
+pub fn build_notifiers(config: &NotificationConfig) -> Result<Vec<Arc<dyn Notifier>>, String> {
+    let client = Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+
+    config
+        .channels
+        .iter()
+        .map(|channel| -> Result<Arc<dyn Notifier>, String> {
+            match channel {
+                ChannelConfig::Webhook { url } => Ok(Arc::new(WebhookNotifier {
+                    client: client.clone(),
+                    url: url.clone(),
+                })),
+                ChannelConfig::Slack { webhook_url_env } => {
+                    let webhook_url = env::var(webhook_url_env)
+                        .map_err(|_| format!("{webhook_url_env} must be set for Slack"))?;
+                    Ok(Arc::new(SlackNotifier {
+                        client: client.clone(),
+                        webhook_url,
+                    }))
+                }
+                ChannelConfig::Email {
+                    smtp_host,
+                    smtp_port,
+                    from,
+                    to,
+                } => Ok(Arc::new(EmailNotifier::new(
+                    smtp_host, *smtp_port, from, to,
+                )?)),
+            }
+        })
+        .collect()
+}
+
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn channel(&self) -> &'static str {
+        "webhook"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(job_id = %event.job_id))]
+    async fn send(&self, event: &JobEvent) -> Result<(), NotifyError> {
+        post_json(&self.client, &self.url, event).await
+    }
+}
+
+pub struct SlackNotifier {
+    client: Client,
+    webhook_url: String,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn channel(&self) -> &'static str {
+        "slack"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(job_id = %event.job_id))]
+    async fn send(&self, event: &JobEvent) -> Result<(), NotifyError> {
+        let payload = json!({ "text": event.summary() });
+        post_json(&self.client, &self.webhook_url, &payload).await
+    }
+}
+
+async fn post_json(
+    client: &Client,
+    url: &str,
+    body: &impl serde::Serialize,
+) -> Result<(), NotifyError> {
+    let res = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| NotifyError(format!("request failed: {e}")))?;
+
+    if !res.status().is_success() {
+        return Err(NotifyError(format!("receiver returned {}", res.status())));
+    }
+
+    Ok(())
+}
+
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn new(
+        smtp_host: &str,
+        smtp_port: Option<u16>,
+        from: &str,
+        to: &[String],
+    ) -> Result<Self, String> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)
+            .map_err(|e| format!("Invalid SMTP host {smtp_host}: {e}"))?
+            .timeout(Some(SEND_TIMEOUT));
+        if let Some(port) = smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Ok(username), Ok(password)) = (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD"))
+        {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| format!("Invalid email address {address}: {e}"))
+        };
+        let to = to
+            .iter()
+            .map(|address| parse(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        if to.is_empty() {
+            return Err("Email channel needs at least one recipient".to_string());
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: parse(from)?,
+            to,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn channel(&self) -> &'static str {
+        "email"
+    }
+
+    #[tracing::instrument(skip(self, event), fields(job_id = %event.job_id))]
+    async fn send(&self, event: &JobEvent) -> Result<(), NotifyError> {
+        let summary = event.summary();
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(summary.clone());
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .body(summary)
+            .map_err(|e| NotifyError(format!("failed to build email: {e}")))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| NotifyError(format!("SMTP send failed: {e}")))?;
+
+        Ok(())
+    }
+}
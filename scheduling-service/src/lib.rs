@@ -2,3 +2,5 @@ pub mod api;
 pub mod domain;
 pub mod error;
 pub mod infrastructure;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
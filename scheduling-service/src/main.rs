@@ -1,29 +1,97 @@
 use axum::{
-    Router,
-    routing::{get, post},
+    Extension, Router,
+    routing::{get, patch, post},
 };
 use scheduling_service::{
-    api::{handler::schedule, state::SchedulingAppState},
-    domain::{scheduler::SchedulingConfig, service::SchedulingService},
-    infrastructure::{client::HttpDataServiceClient, job::PgJobRepository},
+    api::{
+        handler::{admin, health, schedule},
+        state::SchedulingAppState,
+    },
+    domain::{
+        health::HealthChecker, scheduler::SchedulingConfig, service::SchedulingService,
+        share::ShareLinkSigner,
+    },
+    infrastructure::{
+        MIGRATOR,
+        cache::CachedDataServiceClient,
+        callback::CallbackDispatcher,
+        client::HttpDataServiceClient,
+        health::{HttpHealthProber, dependency_checks, startup_self_check},
+        job::PgJobRepository,
+        lock::PgTaskLock,
+        notify::build_notifiers,
+    },
 };
+use shared::auth::{AuthConfig, Authenticator, SecuritySchemes};
+use shared::startup::STARTUP_RETRY_INTERVAL;
 use sqlx::postgres::PgPoolOptions;
-use std::{env, sync::Arc};
+use std::{env, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+const STAFF_CACHE_TTL: Duration = Duration::from_secs(300);
+const SANDBOX_PURGE_INTERVAL: Duration = Duration::from_secs(600);
+const JOB_RECOVERY_INTERVAL: Duration = Duration::from_secs(60);
+const CALLBACK_DISPATCH_INTERVAL: Duration = Duration::from_secs(5);
+const JOB_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+const ACKNOWLEDGMENT_REMINDER_INTERVAL: Duration = Duration::from_secs(300);
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
         schedule::submit_schedule,
+        schedule::list_schedules,
         schedule::get_status,
+        schedule::get_timeline,
+        schedule::stream_events,
+        schedule::list_failed,
+        schedule::get_stats,
+        schedule::retry_schedule,
+        schedule::update_assignment,
+        schedule::update_assignments,
+        schedule::add_note,
+        schedule::get_approvals,
+        schedule::approve_schedule,
+        schedule::reject_schedule,
+        schedule::publish_schedule,
+        schedule::acknowledge_schedule,
+        schedule::get_acknowledgments,
+        schedule::get_outstanding_acknowledgments,
+        schedule::simulate,
         schedule::get_result,
+        schedule::get_payroll_export,
+        schedule::get_result_calendar,
+        schedule::get_staff_schedule,
+        schedule::get_staff_assignments,
+        schedule::get_group_calendar,
+        schedule::get_next_period,
+        schedule::create_share_link,
+        schedule::get_shared_schedule,
+        admin::probe_dependency,
+        admin::job_queue,
+        admin::import_history,
+        admin::pack_assignments,
+        admin::check_staff_references,
+        admin::override_job_status,
+        admin::get_group_config,
+        admin::put_group_config,
+        admin::delete_group_config,
+        health::liveness,
+        health::dependency_readiness,
+        health::readiness,
+        health::startup,
     ),
+    modifiers(&SecuritySchemes),
+    security(("bearer_auth" = [])),
     tags(
         (name = "Schedules", description = "Schedule job management"),
+        (name = "Reports", description = "Payroll and calendar exports of completed schedules"),
+        (name = "Sharing", description = "Signed links for read-only access to a published schedule"),
+        (name = "Health", description = "Dependency readiness"),
+        (name = "Admin", description = "Operator diagnostics and maintenance"),
     )
 )]
 struct ApiDoc;
@@ -31,6 +99,7 @@ struct ApiDoc;
 #[tokio::main]
 async fn main() {
     let _guard = shared::telemetry::init_telemetry("scheduling-service");
+    let metrics = shared::metrics::init_metrics();
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let port = env::var("SERVER_PORT").unwrap_or_else(|_| "8081".to_string());
@@ -43,25 +112,114 @@ async fn main() {
         .await
         .expect("Failed to establish connection into Postgres");
 
-    sqlx::migrate!()
+    MIGRATOR
         .run(&pool)
         .await
         .expect("Failed to run database migrations");
 
-    let job_repo = Arc::new(PgJobRepository::new(pool.clone()));
-    let data_client = Arc::new(HttpDataServiceClient::new(data_service_url));
     let config_path =
         env::var("SCHEDULING_CONFIG_PATH").unwrap_or_else(|_| "scheduling.toml".to_string());
     let config = SchedulingConfig::load(&config_path).expect("Failed to load scheduling config");
+    let job_repo = Arc::new(
+        PgJobRepository::new(pool.clone())
+            .with_compact_assignments(config.storage.compact_assignments),
+    );
+    let mut http_client = HttpDataServiceClient::new(data_service_url.clone());
+    match env::var("DATA_SERVICE_TOKEN") {
+        Ok(token) if !token.is_empty() => http_client = http_client.with_bearer_token(token),
+        _ => tracing::info!("DATA_SERVICE_TOKEN not set, calling the data-service without a token"),
+    }
+    match env::var("DATA_SERVICE_API_KEY") {
+        Ok(key) if !key.is_empty() => http_client = http_client.with_api_key(key),
+        _ => tracing::info!("DATA_SERVICE_API_KEY not set, calling the data-service without a key"),
+    }
+    let data_client = Arc::new(CachedDataServiceClient::new(
+        Arc::new(http_client),
+        STAFF_CACHE_TTL,
+    ));
+
+    let health_checker = Arc::new(
+        HealthChecker::new(
+            Arc::new(HttpHealthProber::new()),
+            config.health.resolve(&data_service_url),
+        )
+        .with_failure_threshold(config.health.failure_threshold),
+    );
+    health_checker.spawn_monitor(&config.health);
 
-    let scheduling_service = Arc::new(SchedulingService::new(job_repo, data_client, config));
+    let startup = Arc::new(startup_self_check(
+        pool.clone(),
+        &config,
+        health_checker.clone(),
+    ));
+    startup.spawn(STARTUP_RETRY_INTERVAL);
+    let dependencies = Arc::new(dependency_checks(pool.clone(), health_checker.clone()));
+
+    let notifiers =
+        build_notifiers(&config.notifications).expect("Failed to set up notification channels");
+
+    let mut scheduling_service = SchedulingService::new(job_repo, data_client, config)
+        .with_task_lock(Arc::new(PgTaskLock::new(pool.clone())))
+        .with_notifiers(notifiers);
+    match env::var("WORKER_ID").or_else(|_| env::var("HOSTNAME")) {
+        Ok(worker_id) if !worker_id.is_empty() => {
+            scheduling_service = scheduling_service.with_worker_id(worker_id);
+        }
+        _ => tracing::info!("WORKER_ID and HOSTNAME not set, picked up jobs won't name a worker"),
+    }
+    match env::var("SHARE_LINK_SECRET") {
+        Ok(secret) if !secret.is_empty() => {
+            scheduling_service = scheduling_service.with_share_links(ShareLinkSigner::new(secret));
+        }
+        _ => tracing::warn!("SHARE_LINK_SECRET not set, public share links are disabled"),
+    }
+    match env::var("CALLBACK_SECRET") {
+        Ok(secret) if !secret.is_empty() => {
+            scheduling_service = scheduling_service.with_callbacks();
+            tokio::spawn(
+                CallbackDispatcher::new(pool.clone(), secret)
+                    .expect("Failed to initialize callback dispatcher")
+                    .run(CALLBACK_DISPATCH_INTERVAL),
+            );
+        }
+        _ => tracing::warn!("CALLBACK_SECRET not set, job callbacks are disabled"),
+    }
+    let scheduling_service = Arc::new(scheduling_service);
 
     if let Err(e) = scheduling_service.recover_stale_jobs().await {
         tracing::warn!("Failed to recover stale jobs: {e}");
     }
+    tokio::spawn(
+        scheduling_service
+            .clone()
+            .run_sandbox_purge(SANDBOX_PURGE_INTERVAL),
+    );
+    tokio::spawn(
+        scheduling_service
+            .clone()
+            .run_job_recovery(JOB_RECOVERY_INTERVAL),
+    );
+    tokio::spawn(
+        scheduling_service
+            .clone()
+            .run_job_metrics(JOB_METRICS_INTERVAL),
+    );
+    tokio::spawn(
+        scheduling_service
+            .clone()
+            .run_acknowledgment_reminders(ACKNOWLEDGMENT_REMINDER_INTERVAL),
+    );
+
+    let authenticator = Arc::new(Authenticator::new(AuthConfig::from_env()));
+    if !authenticator.is_enabled() {
+        tracing::warn!("AUTH_JWKS_URL and AUTH_JWT_SECRET not set, authentication is disabled");
+    }
 
     let state = Arc::new(SchedulingAppState {
         scheduling_service: scheduling_service.clone(),
+        health_checker,
+        startup,
+        dependencies,
     });
 
     let app = Router::new()
@@ -73,20 +231,145 @@ async fn main() {
                 })
             }),
         )
-        .route("/api/v1/schedules", post(schedule::submit_schedule))
+        .route("/healthz", get(health::liveness))
+        .route("/readyz", get(health::dependency_readiness))
+        .route("/ready", get(health::readiness))
+        .route("/healthz/startup", get(health::startup))
+        .route(
+            "/metrics",
+            get(move || std::future::ready(shared::metrics::render(&metrics))),
+        )
+        .route(
+            "/api/v1/schedules",
+            post(schedule::submit_schedule).get(schedule::list_schedules),
+        )
+        .route("/api/v1/schedules/failed", get(schedule::list_failed))
+        .route("/api/v1/schedules/stats", get(schedule::get_stats))
+        .route(
+            "/api/v1/schedules/{schedule_id}/assignments/{assignment_id}",
+            patch(schedule::update_assignment),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/assignments",
+            patch(schedule::update_assignments),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/notes",
+            post(schedule::add_note),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals",
+            get(schedule::get_approvals),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals/approve",
+            post(schedule::approve_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals/reject",
+            post(schedule::reject_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/publish",
+            post(schedule::publish_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/acknowledgments",
+            get(schedule::get_acknowledgments).post(schedule::acknowledge_schedule),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/acknowledgments",
+            get(schedule::get_outstanding_acknowledgments),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/simulate",
+            post(schedule::simulate),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/retry",
+            post(schedule::retry_schedule),
+        )
         .route(
             "/api/v1/schedules/{schedule_id}/status",
             get(schedule::get_status),
         )
+        .route(
+            "/api/v1/schedules/{schedule_id}/timeline",
+            get(schedule::get_timeline),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/events",
+            get(schedule::stream_events),
+        )
         .route(
             "/api/v1/schedules/{schedule_id}/result",
             get(schedule::get_result),
         )
+        .route(
+            "/api/v1/schedules/{schedule_id}/payroll-export",
+            get(schedule::get_payroll_export),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/result.ics",
+            get(schedule::get_result_calendar),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/staff/{staff_id}",
+            get(schedule::get_staff_schedule),
+        )
+        .route(
+            "/api/v1/staff/{staff_id}/assignments",
+            get(schedule::get_staff_assignments),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/calendar.ics",
+            get(schedule::get_group_calendar),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/next-period",
+            get(schedule::get_next_period),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/share",
+            post(schedule::create_share_link),
+        )
+        .route("/api/v1/shared/{token}", get(schedule::get_shared_schedule))
+        // Admin routes
+        .route(
+            "/api/v1/admin/probe/{dependency}",
+            get(admin::probe_dependency),
+        )
+        .route("/api/v1/admin/queue", get(admin::job_queue))
+        .route(
+            "/api/v1/admin/groups/{staff_group_id}/history",
+            post(admin::import_history),
+        )
+        .route(
+            "/api/v1/admin/assignments/pack",
+            post(admin::pack_assignments),
+        )
+        .route(
+            "/api/v1/admin/consistency/staff-references",
+            post(admin::check_staff_references),
+        )
+        .route(
+            "/api/v1/admin/schedules/{schedule_id}/status",
+            post(admin::override_job_status),
+        )
+        .route(
+            "/api/v1/admin/groups/{staff_group_id}/scheduling-config",
+            get(admin::get_group_config)
+                .put(admin::put_group_config)
+                .delete(admin::delete_group_config),
+        )
         // Swagger UI
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .layer(Extension(authenticator))
+        .layer(axum::middleware::from_fn(shared::metrics::track_requests))
         // tracing log (turn request into info level)
         .layer(
             TraceLayer::new_for_http()
+                .make_span_with(shared::telemetry::make_request_span)
                 .on_request(DefaultOnRequest::new().level(Level::INFO))
                 .on_response(
                     DefaultOnResponse::new()
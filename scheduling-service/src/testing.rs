@@ -0,0 +1,292 @@
+//! Builders for scheduling configs and rosters in tests, enabled by the `test-support`
+//! feature. They start from the defaults `scheduling.toml` would give and only change what
+//! a test asks for, so a test reads as the few settings it is about.
+
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate, Utc};
+use shared::types::{
+    GroupMember, ShiftAssignment, ShiftEligibility, ShiftType, Staff, StaffStatus, TimeOffRequest,
+    TimeOffStatus,
+};
+use uuid::Uuid;
+
+use crate::domain::coverage::ShiftCoverage;
+use crate::domain::job::NewShiftAssignment;
+use crate::domain::scheduler::{
+    DayOffPairing, FairnessConfig, RosterMember, SchedulingConfig, ShiftHistory, ShiftOrdering,
+    WeekAlignment,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct SchedulingConfigBuilder {
+    config: SchedulingConfig,
+}
+
+impl SchedulingConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        self.config.timezone = timezone.to_string();
+        self
+    }
+
+    pub fn with_day_off_per_week(mut self, min: u8, max: u8) -> Self {
+        self.config.min_day_off_per_week = min;
+        self.config.max_day_off_per_week = max;
+        self
+    }
+
+    pub fn with_no_morning_after_evening(mut self, enabled: bool) -> Self {
+        self.config.no_morning_after_evening = enabled;
+        self
+    }
+
+    pub fn with_night_shift(mut self, enabled: bool) -> Self {
+        self.config.night_shift = enabled;
+        self
+    }
+
+    pub fn with_max_daily_shift_diff(mut self, max_diff: u8) -> Self {
+        self.config.max_daily_shift_diff = max_diff;
+        self
+    }
+
+    pub fn with_day_off_pairing(mut self, pairing: DayOffPairing) -> Self {
+        self.config.day_off_pairing = pairing;
+        self
+    }
+
+    pub fn with_week_alignment(mut self, alignment: WeekAlignment) -> Self {
+        self.config.week_alignment = alignment;
+        self
+    }
+
+    pub fn with_shift_ordering(mut self, ordering: ShiftOrdering) -> Self {
+        self.config.shift_ordering = ordering;
+        self
+    }
+
+    pub fn with_shift_coverage(mut self, morning: usize, evening: usize) -> Self {
+        self.config.shift_coverage = ShiftCoverage { morning, evening };
+        self
+    }
+
+    pub fn with_fairness(mut self, fairness: FairnessConfig) -> Self {
+        self.config.fairness = fairness;
+        self
+    }
+
+    /// The config, refused like `scheduling.toml` would be when it can't be worked with.
+    pub fn build(self) -> Result<SchedulingConfig, String> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Staff for the generator, and the same people as the data-service would return them.
+/// Members are referred to by the order they were added in; their ids are fixed by that
+/// order too, so two runs of a test see the same roster.
+#[derive(Debug, Clone, Default)]
+pub struct RosterBuilder {
+    members: Vec<RosterMember>,
+}
+
+impl RosterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `count` more members in `position`, eligible for any shift, with a weight of 1.
+    pub fn with_staff(mut self, position: &str, count: usize) -> Self {
+        for _ in 0..count {
+            let staff_id = Uuid::from_u128(self.members.len() as u128 + 1);
+            self.members.push(RosterMember {
+                staff_id,
+                position: position.to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                history: ShiftHistory::default(),
+                last_shift: None,
+                time_off: HashSet::new(),
+                starts_on: None,
+                effective_until: None,
+            });
+        }
+        self
+    }
+
+    pub fn with_eligibility(mut self, member: usize, eligibility: ShiftEligibility) -> Self {
+        self.members[member].shift_eligibility = eligibility;
+        self
+    }
+
+    pub fn with_fairness_weight(mut self, member: usize, weight: f64) -> Self {
+        self.members[member].fairness_weight = weight;
+        self
+    }
+
+    pub fn with_history(mut self, member: usize, history: ShiftHistory) -> Self {
+        self.members[member].history = history;
+        self
+    }
+
+    /// The shift `member` worked the day before the period.
+    pub fn with_last_shift(mut self, member: usize, shift_type: ShiftType) -> Self {
+        self.members[member].last_shift = Some(shift_type);
+        self
+    }
+
+    /// Pin a day off for `member` on `date`, the way approved time off does.
+    pub fn with_day_off(mut self, member: usize, date: NaiveDate) -> Self {
+        self.members[member].time_off.insert(date);
+        self
+    }
+
+    pub fn with_starts_on(mut self, member: usize, date: NaiveDate) -> Self {
+        self.members[member].starts_on = Some(date);
+        self
+    }
+
+    pub fn with_effective_until(mut self, member: usize, date: NaiveDate) -> Self {
+        self.members[member].effective_until = Some(date);
+        self
+    }
+
+    pub fn staff_ids(&self) -> Vec<Uuid> {
+        self.members.iter().map(|m| m.staff_id).collect()
+    }
+
+    pub fn build(self) -> Vec<RosterMember> {
+        self.members
+    }
+
+    /// The roster as `DataServiceClient::get_resolved_members` returns it, for mocks. History,
+    /// the last shift and pinned days off aren't part of it; see `time_off`.
+    pub fn group_members(&self) -> Vec<GroupMember> {
+        let now = Utc::now();
+        self.members
+            .iter()
+            .map(|m| GroupMember {
+                staff: Staff {
+                    id: m.staff_id,
+                    name: format!("Staff {}", m.staff_id.as_u128()),
+                    email: format!("staff-{}@example.com", m.staff_id.as_u128()),
+                    position: m.position.clone(),
+                    fairness_weight: m.fairness_weight,
+                    shift_eligibility: m.shift_eligibility,
+                    status: StaffStatus::Active,
+                    effective_until: m.effective_until,
+                    created_at: now,
+                    updated_at: now,
+                    version: 1,
+                },
+                valid_from: m.starts_on,
+            })
+            .collect()
+    }
+
+    /// Pinned days off as approved one-day requests, as `DataServiceClient::get_time_off`
+    /// returns them.
+    pub fn time_off(&self) -> Vec<TimeOffRequest> {
+        let now = Utc::now();
+        let mut requests: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|m| m.time_off.iter().map(|&date| (m.staff_id, date)))
+            .map(|(staff_id, date)| TimeOffRequest {
+                id: Uuid::from_u128(staff_id.as_u128() << 32 | date.num_days_from_ce() as u128),
+                staff_id,
+                start_date: date,
+                end_date: date,
+                reason: None,
+                status: TimeOffStatus::Approved,
+                created_at: now,
+                updated_at: now,
+            })
+            .collect();
+        requests.sort_by_key(|r| (r.start_date, r.staff_id));
+        requests
+    }
+}
+
+/// Generated assignments as a completed job stores them, for `JobRepository::get_assignments`.
+/// Ids are derived from the job id and the order of the assignments.
+pub fn stored_assignments(
+    job_id: Uuid,
+    generated: Vec<NewShiftAssignment>,
+) -> Vec<ShiftAssignment> {
+    generated
+        .into_iter()
+        .enumerate()
+        .map(|(i, a)| ShiftAssignment {
+            id: Uuid::from_u128(job_id.as_u128() ^ (i as u128 + 1)),
+            job_id,
+            staff_id: a.staff_id,
+            date: a.date,
+            shift_type: a.shift_type,
+            note: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::scheduler::apply_time_off;
+
+    #[test]
+    fn config_builder_refuses_rules_that_cannot_be_met() {
+        assert!(
+            SchedulingConfigBuilder::new()
+                .with_day_off_per_week(3, 2)
+                .build()
+                .is_err()
+        );
+        assert!(
+            SchedulingConfigBuilder::new()
+                .with_timezone("Mars/Olympus")
+                .build()
+                .is_err()
+        );
+        let config = SchedulingConfigBuilder::new()
+            .with_day_off_per_week(2, 2)
+            .with_shift_coverage(2, 1)
+            .build()
+            .unwrap();
+        assert_eq!(config.min_day_off_per_week, 2);
+        assert_eq!(config.shift_coverage.morning, 2);
+    }
+
+    #[test]
+    fn mocked_data_service_view_resolves_to_the_same_roster() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let builder = RosterBuilder::new()
+            .with_staff("Nurse", 2)
+            .with_staff("Doctor", 1)
+            .with_eligibility(1, ShiftEligibility::MorningOnly)
+            .with_fairness_weight(2, 0.5)
+            .with_day_off(0, day)
+            .with_starts_on(2, day);
+
+        let mut resolved: Vec<RosterMember> = builder
+            .group_members()
+            .iter()
+            .map(RosterMember::from)
+            .collect();
+        apply_time_off(&mut resolved, &builder.time_off(), day, day);
+
+        let roster = builder.build();
+        assert_eq!(roster.len(), 3);
+        for (expected, actual) in roster.iter().zip(&resolved) {
+            assert_eq!(expected.staff_id, actual.staff_id);
+            assert_eq!(expected.position, actual.position);
+            assert_eq!(expected.fairness_weight, actual.fairness_weight);
+            assert_eq!(expected.shift_eligibility, actual.shift_eligibility);
+            assert_eq!(expected.time_off, actual.time_off);
+            assert_eq!(expected.starts_on, actual.starts_on);
+        }
+    }
+}
@@ -1,47 +1,248 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     http::{Request, StatusCode},
-    routing::{get, post},
+    routing::{get, patch, post},
 };
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use http_body_util::BodyExt;
 use serde_json::json;
 use tower::ServiceExt;
 use uuid::Uuid;
 
 use scheduling_service::{
-    api::{handler::schedule, state::SchedulingAppState},
+    api::{
+        handler::{admin, health, schedule},
+        state::SchedulingAppState,
+    },
     domain::{
-        client::MockDataServiceClient, job::MockJobRepository, scheduler::SchedulingConfig,
+        acknowledgment::StaffAcknowledgment,
+        approval::{ApprovalDecision, GroupApprovers, ScheduleApproval},
+        client::MockDataServiceClient,
+        consistency::{CONSISTENCY_NOTE_AUTHOR, StaffReferences, orphan_note},
+        coverage::ShiftCoverage,
+        group_config::GroupSchedulingConfig,
+        health::{HealthChecker, HealthConfig, MockHealthProber, ProbeReport},
+        job::MockJobRepository,
+        preflight::DuplicatePolicy,
+        scheduler::{SchedulingConfig, gen_schedule},
         service::SchedulingService,
+        share::ShareLinkSigner,
+        stats::JobStatusCounts,
+        timeline::{JobEventKind, JobTimelineEvent},
     },
+    error::SchedulingServiceError,
+    testing::{RosterBuilder, stored_assignments},
+};
+use shared::auth::{AuthConfig, Authenticator};
+use shared::health::DependencyChecks;
+use shared::pagination::Page;
+use shared::startup::StartupSelfCheck;
+use shared::types::{
+    JobStatus, ScheduleJob, ScheduleNote, ShiftAssignment, ShiftEligibility, ShiftType, Staff,
+    StaffStatus,
 };
-use shared::types::{JobStatus, ScheduleJob, ShiftAssignment, ShiftType};
 
 fn build_test_app(mock_repo: MockJobRepository, mock_client: MockDataServiceClient) -> Router {
-    let svc = Arc::new(SchedulingService::new(
-        Arc::new(mock_repo),
-        Arc::new(mock_client),
+    build_test_app_with_prober(mock_repo, mock_client, MockHealthProber::new())
+}
+
+fn build_test_app_with_prober(
+    mock_repo: MockJobRepository,
+    mock_client: MockDataServiceClient,
+    mock_prober: MockHealthProber,
+) -> Router {
+    build_test_app_with(
+        mock_repo,
+        mock_client,
+        mock_prober,
         SchedulingConfig::default(),
+    )
+}
+
+fn build_test_app_with(
+    mock_repo: MockJobRepository,
+    mock_client: MockDataServiceClient,
+    mock_prober: MockHealthProber,
+    config: SchedulingConfig,
+) -> Router {
+    let svc = Arc::new(
+        SchedulingService::new(Arc::new(mock_repo), Arc::new(mock_client), config)
+            .with_share_links(ShareLinkSigner::new("test-secret")),
+    );
+    let health_checker = Arc::new(HealthChecker::new(
+        Arc::new(mock_prober),
+        HealthConfig::default().resolve("http://data-service"),
     ));
-    let state = Arc::new(SchedulingAppState {
+    test_router(SchedulingAppState {
         scheduling_service: svc,
-    });
+        health_checker,
+        startup: Arc::new(StartupSelfCheck::new()),
+        dependencies: Arc::new(DependencyChecks::new()),
+    })
+}
+
+/// Like `build_test_app_with`, but requiring tokens signed by `token_for`.
+fn build_authenticated_app(mock_repo: MockJobRepository, config: SchedulingConfig) -> Router {
+    let svc = Arc::new(SchedulingService::new(
+        Arc::new(mock_repo),
+        Arc::new(MockDataServiceClient::new()),
+        config,
+    ));
+    test_router_with_auth(
+        SchedulingAppState {
+            scheduling_service: svc,
+            health_checker: Arc::new(HealthChecker::new(
+                Arc::new(MockHealthProber::new()),
+                HealthConfig::default().resolve("http://data-service"),
+            )),
+            startup: Arc::new(StartupSelfCheck::new()),
+            dependencies: Arc::new(DependencyChecks::new()),
+        },
+        Authenticator::new(AuthConfig {
+            secret: Some("test-secret".to_string()),
+            ..AuthConfig::default()
+        }),
+    )
+}
+
+fn test_router(state: SchedulingAppState) -> Router {
+    test_router_with_auth(state, Authenticator::disabled())
+}
 
+fn test_router_with_auth(state: SchedulingAppState, authenticator: Authenticator) -> Router {
     Router::new()
-        .route("/api/v1/schedules", post(schedule::submit_schedule))
+        .route("/healthz", get(health::liveness))
+        .route("/readyz", get(health::dependency_readiness))
+        .route("/ready", get(health::readiness))
+        .route("/healthz/startup", get(health::startup))
+        .route(
+            "/api/v1/schedules",
+            post(schedule::submit_schedule).get(schedule::list_schedules),
+        )
+        .route("/api/v1/schedules/failed", get(schedule::list_failed))
+        .route("/api/v1/schedules/stats", get(schedule::get_stats))
+        .route(
+            "/api/v1/schedules/{schedule_id}/assignments/{assignment_id}",
+            patch(schedule::update_assignment),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/assignments",
+            patch(schedule::update_assignments),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/notes",
+            post(schedule::add_note),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals",
+            get(schedule::get_approvals),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals/approve",
+            post(schedule::approve_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/approvals/reject",
+            post(schedule::reject_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/publish",
+            post(schedule::publish_schedule),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/acknowledgments",
+            get(schedule::get_acknowledgments).post(schedule::acknowledge_schedule),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/acknowledgments",
+            get(schedule::get_outstanding_acknowledgments),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/simulate",
+            post(schedule::simulate),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/retry",
+            post(schedule::retry_schedule),
+        )
         .route(
             "/api/v1/schedules/{schedule_id}/status",
             get(schedule::get_status),
         )
+        .route(
+            "/api/v1/schedules/{schedule_id}/timeline",
+            get(schedule::get_timeline),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/events",
+            get(schedule::stream_events),
+        )
         .route(
             "/api/v1/schedules/{schedule_id}/result",
             get(schedule::get_result),
         )
-        .with_state(state)
+        .route(
+            "/api/v1/schedules/{schedule_id}/payroll-export",
+            get(schedule::get_payroll_export),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/result.ics",
+            get(schedule::get_result_calendar),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/staff/{staff_id}",
+            get(schedule::get_staff_schedule),
+        )
+        .route(
+            "/api/v1/staff/{staff_id}/assignments",
+            get(schedule::get_staff_assignments),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/calendar.ics",
+            get(schedule::get_group_calendar),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/next-period",
+            get(schedule::get_next_period),
+        )
+        .route(
+            "/api/v1/schedules/{schedule_id}/share",
+            post(schedule::create_share_link),
+        )
+        .route("/api/v1/shared/{token}", get(schedule::get_shared_schedule))
+        .route(
+            "/api/v1/admin/probe/{dependency}",
+            get(admin::probe_dependency),
+        )
+        .route("/api/v1/admin/queue", get(admin::job_queue))
+        .route(
+            "/api/v1/admin/groups/{staff_group_id}/history",
+            post(admin::import_history),
+        )
+        .route(
+            "/api/v1/admin/assignments/pack",
+            post(admin::pack_assignments),
+        )
+        .route(
+            "/api/v1/admin/consistency/staff-references",
+            post(admin::check_staff_references),
+        )
+        .route(
+            "/api/v1/admin/schedules/{schedule_id}/status",
+            post(admin::override_job_status),
+        )
+        .route(
+            "/api/v1/admin/groups/{staff_group_id}/scheduling-config",
+            get(admin::get_group_config)
+                .put(admin::put_group_config)
+                .delete(admin::delete_group_config),
+        )
+        .layer(Extension(Arc::new(authenticator)))
+        .with_state(Arc::new(state))
 }
 
 fn make_job(id: Uuid, status: JobStatus) -> ScheduleJob {
@@ -50,33 +251,62 @@ fn make_job(id: Uuid, status: JobStatus) -> ScheduleJob {
         staff_group_id: Uuid::new_v4(),
         period_begin_date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
         status,
+        result_checksum: None,
+        depends_on: vec![],
+        sandbox: false,
+        periods: 1,
+        min_morning: None,
+        min_evening: None,
+        error_message: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
+        version: 1,
     }
 }
 
+fn next_monday() -> NaiveDate {
+    let today = chrono::Utc::now().date_naive();
+    today + chrono::Duration::days(7 - today.weekday().num_days_from_monday() as i64)
+}
+
 #[tokio::test]
 async fn submit_schedule_returns_202() {
     let mut repo = MockJobRepository::new();
+    repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
     let job = make_job(Uuid::new_v4(), JobStatus::Pending);
     let job_clone = job.clone();
 
     repo.expect_create_job()
-        .returning(move |_, _| Ok(job_clone.clone()));
+        .returning(move |_, _, _, _| Ok(job_clone.clone()));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_find_overlapping_jobs()
+        .returning(|_, _, _| Ok(vec![]));
     // Background task will call these -- just allow them
-    repo.expect_update_status().returning(|_, _| Ok(()));
-    repo.expect_save_assignments().returning(|_, _| Ok(()));
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_save_assignments()
+        .returning(|_, _, _, _, _, _| Ok(()));
+    repo.expect_find_ready_dependents()
+        .returning(|_| Ok(vec![]));
 
     let mut client = MockDataServiceClient::new();
+    client
+        .expect_count_resolved_members()
+        .returning(|_, _| Ok(5));
     client
         .expect_get_resolved_members()
-        .returning(|_| Ok(vec![]));
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
 
     let app = build_test_app(repo, client);
 
     let body = json!({
         "staff_group_id": job.staff_group_id,
-        "period_begin_date": "2026-02-16"
+        "period_begin_date": next_monday()
     });
 
     let res = app
@@ -92,113 +322,338 @@ async fn submit_schedule_returns_202() {
         .unwrap();
 
     assert_eq!(res.status(), StatusCode::ACCEPTED);
-}
 
-#[tokio::test]
-async fn get_status_returns_job() {
-    let mut repo = MockJobRepository::new();
-    let job_id = Uuid::new_v4();
-    let job = make_job(job_id, JobStatus::Processing);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["id"], job.id.to_string());
+    assert_eq!(json["data"]["warnings"], json!([]));
+}
 
-    repo.expect_find_by_id()
-        .returning(move |_| Ok(Some(job.clone())));
+fn submit_request(body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/api/v1/schedules")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
 
-    let app = build_test_app(repo, MockDataServiceClient::new());
+#[tokio::test]
+async fn submit_schedule_handles_a_period_that_already_has_a_job() {
+    let existing = make_job(Uuid::new_v4(), JobStatus::Completed);
+    let body = json!({
+        "staff_group_id": existing.staff_group_id,
+        "period_begin_date": next_monday()
+    });
 
-    let res = app
-        .oneshot(
-            Request::builder()
-                .uri(format!("/api/v1/schedules/{job_id}/status"))
-                .body(Body::empty())
-                .unwrap(),
+    let app_with = |policy: DuplicatePolicy| {
+        let existing = existing.clone();
+        let mut repo = MockJobRepository::new();
+        repo.expect_find_duplicate_job()
+            .returning(move |_, _| Ok(Some(existing.clone())));
+        repo.expect_create_job().never();
+        let config = SchedulingConfig {
+            duplicate_submissions: policy,
+            ..SchedulingConfig::default()
+        };
+        build_test_app_with(
+            repo,
+            MockDataServiceClient::new(),
+            MockHealthProber::new(),
+            config,
         )
+    };
+
+    let res = app_with(DuplicatePolicy::Reject)
+        .oneshot(submit_request(body.clone()))
         .await
         .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
 
+    let res = app_with(DuplicatePolicy::ReturnExisting)
+        .oneshot(submit_request(body))
+        .await
+        .unwrap();
     assert_eq!(res.status(), StatusCode::OK);
 
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    assert!(json["success"].as_bool().unwrap());
+    assert_eq!(json["data"]["id"], existing.id.to_string());
+    assert_eq!(json["data"]["existing"], true);
 }
 
 #[tokio::test]
-async fn get_status_not_found_returns_404() {
+async fn submit_schedule_rejects_callback_url_when_callbacks_are_disabled() {
     let mut repo = MockJobRepository::new();
-    repo.expect_find_by_id().returning(|_| Ok(None));
+    repo.expect_create_job().never();
 
     let app = build_test_app(repo, MockDataServiceClient::new());
 
+    let body = json!({
+        "staff_group_id": Uuid::new_v4(),
+        "period_begin_date": next_monday(),
+        "callback_url": "https://example.com/hooks/schedule"
+    });
+
     let res = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/schedules/{}/status", Uuid::new_v4()))
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/v1/schedules")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn get_result_returns_schedule_result() {
+async fn submit_schedule_passes_sandbox_flag() {
     let mut repo = MockJobRepository::new();
-    let job_id = Uuid::new_v4();
-    let job = make_job(job_id, JobStatus::Completed);
-    let staff_group_id = job.staff_group_id;
-    let period_begin_date = job.period_begin_date;
+    let job = ScheduleJob {
+        sandbox: true,
+        ..make_job(Uuid::new_v4(), JobStatus::Pending)
+    };
+    let job_clone = job.clone();
 
-    repo.expect_find_by_id()
-        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_create_job()
+        .withf(|_, _, _, options| options.sandbox)
+        .returning(move |_, _, _, _| Ok(job_clone.clone()));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_find_overlapping_jobs()
+        .returning(|_, _, _| Ok(vec![]));
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_save_assignments()
+        .returning(|_, _, _, _, _, _| Ok(()));
+    repo.expect_find_ready_dependents()
+        .returning(|_| Ok(vec![]));
 
-    let assignment = ShiftAssignment {
-        id: Uuid::new_v4(),
-        job_id,
-        staff_id: Uuid::new_v4(),
-        date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
-        shift_type: ShiftType::Morning,
-    };
-    let assignments = vec![assignment];
-    repo.expect_get_assignments()
-        .returning(move |_| Ok(assignments.clone()));
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_count_resolved_members()
+        .returning(|_, _| Ok(5));
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
 
-    let app = build_test_app(repo, MockDataServiceClient::new());
+    let app = build_test_app(repo, client);
+
+    let body = json!({
+        "staff_group_id": job.staff_group_id,
+        "period_begin_date": next_monday(),
+        "sandbox": true
+    });
 
     let res = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/schedules/{job_id}/result"))
-                .body(Body::empty())
+                .method("POST")
+                .uri("/api/v1/schedules")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
 
     let body = res.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-    assert!(json["success"].as_bool().unwrap());
+    assert_eq!(json["data"]["sandbox"], true);
+}
 
-    let data = &json["data"];
-    assert_eq!(data["schedule_id"], job_id.to_string());
-    assert_eq!(data["staff_group_id"], staff_group_id.to_string());
-    assert_eq!(data["period_begin_date"], period_begin_date.to_string());
-    assert_eq!(data["assignments"].as_array().unwrap().len(), 1);
-    assert_eq!(data["assignments"][0]["shift_type"], "MORNING");
+#[tokio::test]
+async fn submit_schedule_passes_horizon_and_rejects_too_long_ones() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+    let job = ScheduleJob {
+        periods: 3,
+        ..make_job(Uuid::new_v4(), JobStatus::Pending)
+    };
+    let job_clone = job.clone();
+
+    repo.expect_create_job()
+        .withf(|_, _, _, options| options.periods == 3)
+        .times(1)
+        .returning(move |_, _, _, _| Ok(job_clone.clone()));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_find_overlapping_jobs()
+        .returning(|_, _, _| Ok(vec![]));
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_save_assignments()
+        .returning(|_, _, _, _, _, _| Ok(()));
+    repo.expect_get_shift_history()
+        .returning(|_, _, _| Ok(vec![]));
+    repo.expect_find_ready_dependents()
+        .returning(|_| Ok(vec![]));
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_count_resolved_members()
+        .returning(|_, _| Ok(5));
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+    let app = build_test_app(repo, client);
+
+    for (periods, status) in [(3, StatusCode::ACCEPTED), (14, StatusCode::BAD_REQUEST)] {
+        let body = json!({
+            "staff_group_id": job.staff_group_id,
+            "period_begin_date": next_monday(),
+            "periods": periods
+        });
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/schedules")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status, "periods = {periods}");
+    }
 }
 
 #[tokio::test]
-async fn submit_schedule_non_monday_returns_400() {
-    let repo = MockJobRepository::new();
-    let client = MockDataServiceClient::new();
+async fn submit_schedule_stores_requested_coverage_and_warns_when_short() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+    let job = ScheduleJob {
+        min_morning: Some(3),
+        min_evening: Some(3),
+        error_message: None,
+        ..make_job(Uuid::new_v4(), JobStatus::Pending)
+    };
+    let job_clone = job.clone();
+
+    repo.expect_create_job()
+        .withf(|_, _, _, options| {
+            options.coverage
+                == Some(ShiftCoverage {
+                    morning: 3,
+                    evening: 3,
+                })
+        })
+        .times(1)
+        .returning(move |_, _, _, _| Ok(job_clone.clone()));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_find_overlapping_jobs()
+        .returning(|_, _, _| Ok(vec![]));
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_get_shift_history()
+        .returning(|_, _, _| Ok(vec![]));
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_count_resolved_members()
+        .returning(|_, _| Ok(5));
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+    let app = build_test_app(repo, client);
+    let submit = |coverage: serde_json::Value| {
+        let body = json!({
+            "staff_group_id": job.staff_group_id,
+            "period_begin_date": next_monday(),
+            "coverage": coverage
+        });
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/schedules")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(submit(json!({ "morning": 3, "evening": 3 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["min_morning"], 3);
+    // 6 shifts a day over 6 working days each
+    let warnings = json["data"]["warnings"].as_array().unwrap();
+    assert!(warnings.iter().any(|w| w["code"] == "INSUFFICIENT_STAFF"
+        && w["message"].as_str().unwrap().contains("at least 7")));
+
+    let res = app
+        .oneshot(submit(json!({ "morning": 40000 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn submit_schedule_reports_preflight_warnings() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_duplicate_job().returning(|_, _| Ok(None));
+    let job = make_job(Uuid::new_v4(), JobStatus::Pending);
+    let job_clone = job.clone();
+    let existing = ScheduleJob {
+        period_begin_date: next_monday(),
+        ..make_job(Uuid::new_v4(), JobStatus::Completed)
+    };
+
+    repo.expect_create_job()
+        .returning(move |_, _, _, _| Ok(job_clone.clone()));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_find_overlapping_jobs()
+        .returning(move |_, _, _| Ok(vec![existing.clone()]));
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_save_assignments()
+        .returning(|_, _, _, _, _, _| Ok(()));
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_count_resolved_members()
+        .withf(|_, active_only| *active_only)
+        .returning(|_, _| Ok(0));
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
     let app = build_test_app(repo, client);
 
     let body = json!({
-        "staff_group_id": Uuid::new_v4(),
-        "period_begin_date": "2026-02-17"
+        "staff_group_id": job.staff_group_id,
+        "period_begin_date": next_monday()
     });
 
     let res = app
@@ -213,35 +668,1214 @@ async fn submit_schedule_non_monday_returns_400() {
         .await
         .unwrap();
 
-    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let codes: Vec<_> = json["data"]["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["code"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(codes, ["EMPTY_GROUP", "OVERLAPPING_SCHEDULE"]);
 }
 
 #[tokio::test]
-async fn get_result_not_completed_returns_400() {
+async fn retry_resets_failed_job_and_returns_202() {
     let mut repo = MockJobRepository::new();
     let job_id = Uuid::new_v4();
-    let job = make_job(job_id, JobStatus::Processing);
+    let failed = make_job(job_id, JobStatus::Failed);
+    let mut reset = failed.clone();
+    reset.status = JobStatus::Pending;
 
     repo.expect_find_by_id()
-        .returning(move |_| Ok(Some(job.clone())));
+        .returning(move |_| Ok(Some(failed.clone())));
+    repo.expect_reset_failed_job()
+        .withf(move |id| *id == job_id)
+        .times(1)
+        .returning(move |_| Ok(Some(reset.clone())));
+    // Background task will call these -- just allow them
+    repo.expect_update_status().returning(|_, _, _| Ok(()));
+    repo.expect_record_job_event()
+        .returning(|_, _, _, _| Ok(()));
+    repo.expect_save_assignments()
+        .returning(|_, _, _, _, _, _| Ok(()));
+    repo.expect_find_ready_dependents()
+        .returning(|_| Ok(vec![]));
 
-    let app = build_test_app(repo, MockDataServiceClient::new());
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Ok(vec![]));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+    let app = build_test_app(repo, client);
 
     let res = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/schedules/{job_id}/result"))
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/retry"))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["status"], "PENDING");
 }
 
 #[tokio::test]
-async fn get_result_not_found_returns_404() {
+async fn retry_non_failed_job_returns_400() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_reset_failed_job().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/retry"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+fn override_request(job_id: Uuid, body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/admin/schedules/{job_id}/status"))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn override_forces_a_stuck_job_and_records_the_reason() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let stuck = make_job(job_id, JobStatus::Processing);
+    let mut failed = stuck.clone();
+    failed.status = JobStatus::Failed;
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(stuck.clone())));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_override_status()
+        .withf(move |id, from, to| {
+            *id == job_id && *from == JobStatus::Processing && *to == JobStatus::Failed
+        })
+        .times(1)
+        .returning(move |_, _, _| Ok(Some(failed.clone())));
+    // The admin who forced it is taken from the token, next to the reason
+    repo.expect_record_job_event()
+        .withf(|_, kind, detail, _| {
+            *kind == JobEventKind::Overridden
+                && detail.as_deref()
+                    == Some("PROCESSING -> FAILED by ops-lead: worker lost in a deploy")
+        })
+        .times(1)
+        .returning(|_, _, _, _| Ok(()));
+
+    let app = build_authenticated_app(repo, SchedulingConfig::default());
+
+    let mut request = override_request(
+        job_id,
+        json!({ "status": "FAILED", "reason": " worker lost in a deploy " }),
+    );
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", token_for("ops-lead", &["admin"]))
+            .parse()
+            .unwrap(),
+    );
+    let res = app.oneshot(request).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["status"], "FAILED");
+}
+
+#[tokio::test]
+async fn override_refuses_transitions_that_break_invariants() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    // Processing without a saved result
+    let stuck = make_job(job_id, JobStatus::Processing);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(stuck.clone())));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_override_status().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    for body in [
+        json!({ "status": "FAILED", "reason": "  " }),
+        json!({ "status": "PROCESSING", "reason": "stuck" }),
+        json!({ "status": "COMPLETED", "reason": "stuck" }),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(override_request(job_id, body.clone()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{body}");
+    }
+}
+
+fn group_config_request(staff_group_id: Uuid, body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("PUT")
+        .uri(format!(
+            "/api/v1/admin/groups/{staff_group_id}/scheduling-config"
+        ))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn group_scheduling_config_is_stored_when_the_merged_rules_are_valid() {
+    let mut repo = MockJobRepository::new();
+    let staff_group_id = Uuid::new_v4();
+    repo.expect_upsert_group_config()
+        .withf(move |id, overrides| {
+            *id == staff_group_id
+                && overrides.max_day_off_per_week == Some(3)
+                && overrides.min_day_off_per_week.is_none()
+        })
+        .times(1)
+        .returning(|staff_group_id, overrides| {
+            Ok(GroupSchedulingConfig {
+                staff_group_id,
+                overrides,
+                updated_at: chrono::Utc::now(),
+            })
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .clone()
+        .oneshot(group_config_request(
+            staff_group_id,
+            json!({ "max_day_off_per_week": 3, "night_shift": true }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["overrides"]["max_day_off_per_week"], 3);
+    assert_eq!(json["data"]["overrides"]["night_shift"], true);
+
+    // The default max_day_off_per_week of 2 is below this minimum
+    let res = app
+        .oneshot(group_config_request(
+            staff_group_id,
+            json!({ "min_day_off_per_week": 3 }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn missing_group_scheduling_config_is_404() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_delete_group_config().returning(|_| Ok(false));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+    let uri = format!("/api/v1/admin/groups/{}/scheduling-config", Uuid::new_v4());
+
+    for method in ["GET", "DELETE"] {
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{method}");
+    }
+}
+
+#[tokio::test]
+async fn list_schedules_passes_filters_and_page_to_repository() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+    let job = make_job(Uuid::new_v4(), JobStatus::Pending);
+    let job_id = job.id;
+
+    repo.expect_find_paginated()
+        .withf(move |query, page| {
+            query.status == Some(JobStatus::Pending)
+                && query.staff_group_id == Some(group_id)
+                && query.from == NaiveDate::from_ymd_opt(2026, 2, 1)
+                && query.to.is_none()
+                && !query.include_sandbox
+                && page.limit == Some(10)
+                && page.offset() == 20
+        })
+        .returning(move |_, page| {
+            Ok(Page {
+                items: vec![job.clone()],
+                total: 21,
+                limit: page.limit_or(50),
+                offset: page.offset(),
+            })
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/schedules?status=PENDING&staff_group_id={group_id}&from=2026-02-01&limit=10&offset=20"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["items"][0]["id"], job_id.to_string());
+    assert_eq!(json["data"]["total"], 21);
+    assert_eq!(json["data"]["limit"], 10);
+}
+
+#[tokio::test]
+async fn list_schedules_rejects_bad_range_and_page_size() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_paginated().never();
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    for uri in [
+        "/api/v1/schedules?from=2026-03-01&to=2026-02-01",
+        "/api/v1/schedules?limit=501",
+    ] {
+        let res = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn stats_summarise_jobs_in_the_window() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+
+    repo.expect_job_stats()
+        .withf(move |since, group, include_sandbox| {
+            let hours = (chrono::Utc::now() - *since).num_hours();
+            hours == 48 && *group == Some(group_id) && !include_sandbox
+        })
+        .times(1)
+        .returning(|_, _, _| {
+            Ok(JobStatusCounts {
+                pending: 2,
+                processing: 1,
+                completed: 9,
+                failed: 1,
+                avg_processing_ms: Some(850.0),
+            })
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/schedules/stats?window_hours=48&staff_group_id={group_id}"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["total"], 13);
+    assert_eq!(json["data"]["by_status"]["completed"], 9);
+    assert_eq!(json["data"]["by_status"]["avg_processing_ms"], 850.0);
+    assert_eq!(json["data"]["failure_rate"], 0.1);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/schedules/stats?window_hours=0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn list_failed_returns_failed_jobs() {
+    let mut repo = MockJobRepository::new();
+    let job = make_job(Uuid::new_v4(), JobStatus::Failed);
+    let job_id = job.id;
+
+    repo.expect_find_by_status()
+        .withf(|status| *status == JobStatus::Failed)
+        .returning(move |_| Ok(vec![job.clone()]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/schedules/failed")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["id"], job_id.to_string());
+}
+
+#[tokio::test]
+async fn list_failed_orders_newest_first_on_request() {
+    let mut repo = MockJobRepository::new();
+    let older = make_job(Uuid::new_v4(), JobStatus::Failed);
+    let newer = make_job(Uuid::new_v4(), JobStatus::Failed);
+    let newer_id = newer.id;
+
+    repo.expect_find_by_status()
+        .returning(move |_| Ok(vec![older.clone(), newer.clone()]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/schedules/failed?order=desc")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"][0]["id"], newer_id.to_string());
+}
+
+#[tokio::test]
+async fn update_assignment_overrides_shift_and_sets_note() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let assignment = ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id: Uuid::new_v4(),
+        date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+        shift_type: ShiftType::Morning,
+        note: None,
+    };
+    let assignment_id = assignment.id;
+    let mut updated = assignment.clone();
+    updated.shift_type = ShiftType::Evening;
+    updated.note = Some("Swapped with Bob".to_string());
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(vec![assignment.clone()]));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_update_assignment()
+        .withf(move |job, id, shift_type, note| {
+            *job == job_id
+                && *id == assignment_id
+                && *shift_type == ShiftType::Evening
+                && note.as_deref() == Some("Swapped with Bob")
+        })
+        .times(1)
+        .returning(move |_, _, _, _| Ok(Some(updated.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!(
+                    "/api/v1/schedules/{job_id}/assignments/{assignment_id}"
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "shift_type": "EVENING", "note": "  Swapped with Bob " }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["shift_type"], "EVENING");
+    assert_eq!(json["data"]["note"], "Swapped with Bob");
+}
+
+#[tokio::test]
+async fn update_assignment_refuses_published_schedule() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let assignment = ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id: Uuid::new_v4(),
+        date: job.period_begin_date,
+        shift_type: ShiftType::Morning,
+        note: None,
+    };
+    let assignment_id = assignment.id;
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(vec![assignment.clone()]));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_published_at()
+        .returning(|_| Ok(Some(chrono::Utc::now())));
+    repo.expect_update_assignment().never();
+    repo.expect_update_assignments().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+    let patch = |uri: String, body: serde_json::Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+
+    let res = patch(
+        format!("/api/v1/schedules/{job_id}/assignments/{assignment_id}"),
+        json!({ "shift_type": "EVENING" }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = patch(
+        format!("/api/v1/schedules/{job_id}/assignments"),
+        json!({ "changes": [{ "assignment_id": assignment_id, "shift_type": "EVENING" }] }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn update_assignment_rejects_overlong_note() {
+    let job_id = Uuid::new_v4();
+    let assignment_id = Uuid::new_v4();
+    let app = build_test_app(MockJobRepository::new(), MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!(
+                    "/api/v1/schedules/{job_id}/assignments/{assignment_id}"
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "note": "x".repeat(281) }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn update_assignments_saves_all_changes_and_reports_violations() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let period_begin_date = job.period_begin_date;
+    let config = SchedulingConfig::default();
+    let roster = RosterBuilder::new().with_staff("Nurse", 4).build();
+    let generated =
+        gen_schedule(&roster, period_begin_date, &config.build_rules(), &config).unwrap();
+    let stored = stored_assignments(job_id, generated);
+
+    // Work one nurse through every day off of the first week
+    let first_week = period_begin_date + chrono::TimeDelta::days(6);
+    let days_off: Vec<_> = stored
+        .iter()
+        .filter(|a| {
+            a.staff_id == roster[0].staff_id
+                && a.date <= first_week
+                && a.shift_type == ShiftType::DayOff
+        })
+        .map(|a| a.id)
+        .collect();
+    assert!(!days_off.is_empty());
+    let saved: Vec<_> = stored
+        .iter()
+        .filter(|a| days_off.contains(&a.id))
+        .map(|a| ShiftAssignment {
+            shift_type: ShiftType::Morning,
+            ..a.clone()
+        })
+        .collect();
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(stored.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    let expected = days_off.clone();
+    repo.expect_update_assignments()
+        .withf(move |id, changes| {
+            *id == job_id
+                && changes.iter().map(|(id, _)| *id).collect::<Vec<_>>() == expected
+                && changes.iter().all(|(_, s)| *s == ShiftType::Morning)
+        })
+        .times(1)
+        .returning(move |_, _| Ok(Some(saved.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let changes: Vec<_> = days_off
+        .iter()
+        .map(|id| json!({ "assignment_id": id, "shift_type": "MORNING" }))
+        .collect();
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/schedules/{job_id}/assignments"))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "changes": changes }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let data = &json["data"];
+    assert_eq!(
+        data["assignments"].as_array().unwrap().len(),
+        days_off.len()
+    );
+    let violations = data["violations"].as_array().unwrap();
+    assert!(
+        violations
+            .iter()
+            .any(|v| v["staff_id"] == roster[0].staff_id.to_string())
+    );
+    // The generated schedule broke nothing, so every violation comes from the changes
+    assert_eq!(data["introduced_violations"], violations.len());
+}
+
+#[tokio::test]
+async fn update_assignments_saves_nothing_when_one_is_unknown() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let assignment = ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id: Uuid::new_v4(),
+        date: job.period_begin_date,
+        shift_type: ShiftType::Morning,
+        note: None,
+    };
+    let known = assignment.id;
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(vec![assignment.clone()]));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_update_assignments().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/v1/schedules/{job_id}/assignments"))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "changes": [
+                        { "assignment_id": known, "shift_type": "EVENING" },
+                        { "assignment_id": Uuid::new_v4(), "shift_type": "EVENING" },
+                    ] })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn add_note_returns_201_with_trimmed_note() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Pending);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    // Without authentication every caller is anonymous
+    repo.expect_add_note()
+        .withf(move |id, author, body| {
+            *id == job_id && author == "anonymous" && body == "**Handover:** ward B short on Friday"
+        })
+        .times(1)
+        .returning(|job_id, author, body| {
+            Ok(ScheduleNote {
+                id: Uuid::new_v4(),
+                job_id,
+                author,
+                body,
+                created_at: chrono::Utc::now(),
+            })
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/notes"))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "body": "**Handover:** ward B short on Friday\n" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["author"], "anonymous");
+    assert_eq!(json["data"]["job_id"], job_id.to_string());
+}
+
+#[tokio::test]
+async fn add_note_is_signed_by_the_caller_not_the_body() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_add_note()
+        .withf(|_, author, _| author == "carol")
+        .times(1)
+        .returning(|job_id, author, body| {
+            Ok(ScheduleNote {
+                id: Uuid::new_v4(),
+                job_id,
+                author,
+                body,
+                created_at: chrono::Utc::now(),
+            })
+        });
+
+    let app = build_authenticated_app(repo, SchedulingConfig::default());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/notes"))
+                .header("content-type", "application/json")
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", token_for("carol", &["scheduler"])),
+                )
+                .body(Body::from(
+                    json!({ "author": "Head of Nursing", "body": "Ward B is closed" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["author"], "carol");
+}
+
+#[tokio::test]
+async fn add_note_unknown_schedule_returns_404() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_by_id().returning(|_| Ok(None));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{}/notes", Uuid::new_v4()))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "body": "hello" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+fn make_staff(id: Uuid) -> Staff {
+    Staff {
+        id,
+        name: "Alice".to_string(),
+        email: format!("alice-{id}@example.com"),
+        position: "Nurse".to_string(),
+        fairness_weight: 1.0,
+        shift_eligibility: ShiftEligibility::Any,
+        status: StaffStatus::Active,
+        effective_until: None,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        version: 1,
+    }
+}
+
+#[tokio::test]
+async fn simulate_removing_staff_reports_delta_without_saving() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let period_begin_date = job.period_begin_date;
+    repo.expect_get_shift_history()
+        .returning(|_, _, _| Ok(vec![]));
+    let roster = RosterBuilder::new().with_staff("Nurse", 4);
+    let members = roster.group_members();
+    let removed = members[3].staff.id;
+
+    let config = SchedulingConfig::default();
+    let generated = gen_schedule(
+        &roster.build(),
+        period_begin_date,
+        &config.build_rules(),
+        &config,
+    )
+    .unwrap();
+    let stored = stored_assignments(job_id, generated);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(stored.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    // No save_assignments / update_assignment expectations: anything persisted panics
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_get_resolved_members()
+        .returning(move |_, _| Ok(members.clone()));
+    client
+        .expect_get_blackouts()
+        .returning(|_, _, _| Ok(vec![]));
+    client.expect_get_time_off().returning(|_, _, _| Ok(vec![]));
+
+    let app = build_test_app(repo, client);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/simulate"))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "remove_staff": [removed] }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["baseline"]["coverage"]["staff_count"], 4);
+    assert_eq!(data["simulated"]["coverage"]["staff_count"], 3);
+    assert_eq!(data["delta"]["staff_count"], -1);
+    assert_eq!(data["delta"]["violations"], 0);
+}
+
+#[tokio::test]
+async fn simulate_rejects_inverted_day_off_bounds() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments().returning(|_| Ok(vec![]));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_group_config().returning(|_| Ok(None));
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/simulate"))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "rules": { "min_day_off_per_week": 3, "max_day_off_per_week": 2 } })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_status_returns_job() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Processing);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/status"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["success"].as_bool().unwrap());
+}
+
+#[tokio::test]
+async fn get_status_includes_the_failure_reason() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = ScheduleJob {
+        error_message: Some("Data service error: Connection refused".to_string()),
+        ..make_job(job_id, JobStatus::Failed)
+    };
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/status"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["status"], "FAILED");
+    assert_eq!(
+        json["data"]["error_message"],
+        "Data service error: Connection refused"
+    );
+}
+
+#[tokio::test]
+async fn get_status_is_not_modified_while_the_version_matches() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = ScheduleJob {
+        version: 2,
+        ..make_job(job_id, JobStatus::Processing)
+    };
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+    let get = |if_none_match: &str| {
+        Request::builder()
+            .uri(format!("/api/v1/schedules/{job_id}/status"))
+            .header("if-none-match", if_none_match)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app.clone().oneshot(get("\"2\"")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(res.headers()["etag"], "\"2\"");
+
+    let res = app.oneshot(get("\"1\"")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["etag"], "\"2\"");
+}
+
+#[tokio::test]
+async fn get_status_not_found_returns_404() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_by_id().returning(|_| Ok(None));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{}/status", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_timeline_returns_events_with_gaps() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let created = job.created_at;
+    let event = move |kind, after_secs, duration_ms| JobTimelineEvent {
+        kind,
+        detail: None,
+        duration_ms,
+        occurred_at: created + chrono::TimeDelta::seconds(after_secs),
+    };
+    let events = vec![
+        event(JobEventKind::Created, 0, None),
+        event(JobEventKind::PickedUp, 2400, None),
+        event(JobEventKind::Generated, 2401, Some(900)),
+        event(JobEventKind::Completed, 2402, Some(2000)),
+    ];
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_job_events()
+        .withf(move |id| *id == job_id)
+        .returning(move |_| Ok(events.clone()));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/timeline"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["total_ms"], 2_402_000);
+    let events = json["data"]["events"].as_array().unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[1]["kind"], "PICKED_UP");
+    assert_eq!(events[1]["since_previous_ms"], 2_400_000);
+    assert_eq!(events[2]["duration_ms"], 900);
+}
+
+#[tokio::test]
+async fn job_queue_lists_pending_and_running_jobs() {
+    let mut repo = MockJobRepository::new();
+    let (pending_id, running_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let pending = make_job(pending_id, JobStatus::Pending);
+    let running = make_job(running_id, JobStatus::Processing);
+    let picked_up = JobTimelineEvent {
+        kind: JobEventKind::PickedUp,
+        detail: Some("scheduler-7f9c".to_string()),
+        duration_ms: None,
+        occurred_at: chrono::Utc::now() - chrono::TimeDelta::minutes(3),
+    };
+
+    repo.expect_find_by_status()
+        .returning(move |status| match status {
+            JobStatus::Pending => Ok(vec![pending.clone()]),
+            _ => Ok(vec![running.clone()]),
+        });
+    repo.expect_get_job_events()
+        .withf(move |id| *id == running_id)
+        .returning(move |_| Ok(vec![picked_up.clone()]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/admin/queue")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["pending"][0]["id"], pending_id.to_string());
+    let running = &json["data"]["processing"][0];
+    assert_eq!(running["id"], running_id.to_string());
+    assert_eq!(running["worker_id"], "scheduler-7f9c");
+    assert!(running["elapsed_ms"].as_i64().unwrap() >= 180_000);
+}
+
+#[tokio::test]
+async fn events_of_a_finished_job_are_its_status_then_the_end() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/events"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "text/event-stream");
+
+    // Collecting returns only because the stream ends after the final status
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    let data = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .collect::<Vec<_>>();
+    assert!(body.starts_with("event: status\n"));
+    assert_eq!(data.len(), 1);
+    let update: serde_json::Value = serde_json::from_str(data[0]).unwrap();
+    assert_eq!(update["job_id"], job_id.to_string());
+    assert_eq!(update["status"], "COMPLETED");
+}
+
+#[tokio::test]
+async fn events_of_an_unknown_job_are_404() {
     let mut repo = MockJobRepository::new();
     repo.expect_find_by_id().returning(|_| Ok(None));
 
@@ -250,12 +1884,1594 @@ async fn get_result_not_found_returns_404() {
     let res = app
         .oneshot(
             Request::builder()
-                .uri(format!("/api/v1/schedules/{}/result", Uuid::new_v4()))
+                .uri(format!("/api/v1/schedules/{}/events", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_result_returns_schedule_result() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let staff_group_id = job.staff_group_id;
+    let period_begin_date = job.period_begin_date;
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let assignment = ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id: Uuid::new_v4(),
+        date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+        shift_type: ShiftType::Morning,
+        note: None,
+    };
+    let assignments = vec![assignment];
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(move |job_id| {
+        Ok(vec![ScheduleNote {
+            id: Uuid::new_v4(),
+            job_id,
+            author: "Carol".to_string(),
+            body: "Ward B is short on Friday".to_string(),
+            created_at: chrono::Utc::now(),
+        }])
+    });
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["success"].as_bool().unwrap());
+
+    let data = &json["data"];
+    assert_eq!(data["schema_version"], 1);
+    assert_eq!(data["schedule_id"], job_id.to_string());
+    assert_eq!(data["staff_group_id"], staff_group_id.to_string());
+    assert_eq!(data["period_begin_date"], period_begin_date.to_string());
+    assert_eq!(data["assignments"].as_array().unwrap().len(), 1);
+    assert_eq!(data["assignments"][0]["shift_type"], "MORNING");
+    assert_eq!(data["notes"][0]["author"], "Carol");
+}
+
+#[tokio::test]
+async fn get_result_splits_multi_period_schedules_into_sections() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = ScheduleJob {
+        periods: 3,
+        ..make_job(job_id, JobStatus::Completed)
+    };
+    let begin = job.period_begin_date;
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    let staff_id = Uuid::new_v4();
+    // A Saturday off in the first period and in the third
+    let assignments: Vec<ShiftAssignment> = [5, 61, 62]
+        .into_iter()
+        .map(|day| ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id,
+            staff_id,
+            date: begin + chrono::TimeDelta::days(day),
+            shift_type: ShiftType::DayOff,
+            note: None,
+        })
+        .collect();
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["assignments"].as_array().unwrap().len(), 3);
+    assert_eq!(data["metrics"]["weekend_days_off"][staff_id.to_string()], 3);
+
+    let periods = data["periods"].as_array().unwrap();
+    assert_eq!(periods.len(), 3);
+    assert_eq!(periods[1]["period_begin_date"], "2026-03-16");
+    assert_eq!(periods[1]["period_end_date"], "2026-04-12");
+    let counts: Vec<_> = periods.iter().map(|p| p["assignments"].clone()).collect();
+    assert_eq!(counts, vec![json!(1), json!(0), json!(2)]);
+    assert_eq!(
+        periods[2]["metrics"]["weekend_days_off"][staff_id.to_string()],
+        2
+    );
+}
+
+#[tokio::test]
+async fn get_result_include_staff_embeds_staff_details() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let staff_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    let assignments: Vec<_> = (0..2)
+        .map(|day| ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id,
+            staff_id,
+            date: NaiveDate::from_ymd_opt(2026, 2, 16 + day).unwrap(),
+            shift_type: ShiftType::Morning,
+            note: None,
+        })
+        .collect();
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_get_staff_by_ids()
+        .withf(move |ids| ids == &[staff_id])
+        .returning(|ids| {
+            Ok(ids
+                .into_iter()
+                .map(|id| Staff {
+                    id,
+                    name: "Alice".to_string(),
+                    email: "alice@example.com".to_string(),
+                    position: "Nurse".to_string(),
+                    fairness_weight: 1.0,
+                    shift_eligibility: ShiftEligibility::Any,
+                    status: StaffStatus::Active,
+                    effective_until: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                    version: 1,
+                })
+                .collect())
+        });
+
+    let app = build_test_app(repo, client);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result?include=staff"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let staff = &json["data"]["staff"][staff_id.to_string()];
+    assert_eq!(staff["name"], "Alice");
+    assert_eq!(staff["position"], "Nurse");
+}
+
+#[tokio::test]
+async fn get_result_groups_assignments_by_staff_in_name_order() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let (zoe, adam) = (Uuid::new_v4(), Uuid::new_v4());
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    let assignments: Vec<_> = [(zoe, 16), (adam, 17), (zoe, 17), (adam, 16)]
+        .into_iter()
+        .map(|(staff_id, day)| ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id,
+            staff_id,
+            date: NaiveDate::from_ymd_opt(2026, 2, day).unwrap(),
+            shift_type: ShiftType::Morning,
+            note: None,
+        })
+        .collect();
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let mut client = MockDataServiceClient::new();
+    client.expect_get_staff_by_ids().returning(move |ids| {
+        Ok(ids
+            .into_iter()
+            .map(|id| Staff {
+                id,
+                name: if id == zoe { "Zoe" } else { "Adam" }.to_string(),
+                email: format!("{id}@example.com"),
+                position: "Nurse".to_string(),
+                fairness_weight: 1.0,
+                shift_eligibility: ShiftEligibility::Any,
+                status: StaffStatus::Active,
+                effective_until: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                version: 1,
+            })
+            .collect())
+    });
+
+    let app = build_test_app(repo, client);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/schedules/{job_id}/result?sort=staff_name&group_by=staff"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["assignments"], json!([]));
+    assert!(data.get("staff").is_none());
+    let groups = data["assignment_groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0]["staff_id"], adam.to_string());
+    let dates: Vec<_> = groups[0]["assignments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["date"].clone())
+        .collect();
+    assert_eq!(dates, vec![json!("2026-02-16"), json!("2026-02-17")]);
+    assert_eq!(groups[1]["staff_id"], zoe.to_string());
+}
+
+#[tokio::test]
+async fn submit_schedule_non_monday_returns_400() {
+    let repo = MockJobRepository::new();
+    let client = MockDataServiceClient::new();
+    let app = build_test_app(repo, client);
+
+    let body = json!({
+        "staff_group_id": Uuid::new_v4(),
+        "period_begin_date": "2026-02-17"
+    });
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/schedules")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_result_unsupported_version_is_rejected_before_loading() {
+    let app = build_test_app(MockJobRepository::new(), MockDataServiceClient::new());
+    let job_id = Uuid::new_v4();
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result?version=99"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result"))
+                .header(
+                    "accept",
+                    "application/vnd.shift-scheduler.result+json; version=99",
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+}
+
+#[tokio::test]
+async fn get_result_not_completed_returns_400() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Processing);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/result"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn get_result_not_found_returns_404() {
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_by_id().returning(|_| Ok(None));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{}/result", Uuid::new_v4()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_payroll_export_returns_csv() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let staff_id = Uuid::new_v4();
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+
+    let assignments = vec![ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id,
+        date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+        shift_type: ShiftType::Evening,
+        note: None,
+    }];
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/payroll-export"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "text/csv; charset=utf-8");
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[1],
+        format!("{staff_id},2026-02-16,EVENING,14:00,22:00,8.00")
+    );
+}
+
+#[tokio::test]
+async fn result_calendar_covers_the_schedule_or_one_staff_member() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    let assignments: Vec<_> = [
+        (alice, ShiftType::Morning),
+        (bob, ShiftType::Evening),
+        (bob, ShiftType::DayOff),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(day, (staff_id, shift_type))| ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id,
+        staff_id,
+        date: date + chrono::Days::new(day as u64),
+        shift_type,
+        note: None,
+    })
+    .collect();
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let mut client = MockDataServiceClient::new();
+    client.expect_get_staff_by_ids().returning(move |ids| {
+        Ok(ids
+            .into_iter()
+            .map(|id| Staff {
+                name: if id == alice { "Alice" } else { "Bob" }.to_string(),
+                ..make_staff(id)
+            })
+            .collect())
+    });
+
+    let app = build_test_app(repo, client);
+    let calendar = |query: String| {
+        let app = app.clone();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/schedules/{job_id}/result.ics{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let res = calendar(String::new()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["content-type"],
+        "text/calendar; charset=utf-8"
+    );
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let ics = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains("SUMMARY:Alice - Morning shift\r\n"));
+    assert!(ics.contains("SUMMARY:Bob - Evening shift\r\n"));
+
+    let res = calendar(format!("?staff_id={bob}")).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let ics = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("X-WR-CALNAME:Bob's shifts from "));
+    assert!(!ics.contains("Alice"));
+
+    let res = calendar(format!("?staff_id={}", Uuid::new_v4())).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn staff_schedule_returns_only_that_staff_members_days() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    let assignments: Vec<_> = [alice, bob, alice]
+        .into_iter()
+        .enumerate()
+        .map(|(day, staff_id)| ShiftAssignment {
+            id: Uuid::new_v4(),
+            job_id,
+            staff_id,
+            date: date + chrono::Days::new(day as u64),
+            shift_type: ShiftType::DayOff,
+            note: None,
+        })
+        .collect();
+    repo.expect_get_assignments()
+        .returning(move |_| Ok(assignments.clone()));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+    let get = |staff_id: Uuid| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/schedules/{job_id}/staff/{staff_id}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let res = get(alice).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let days: Vec<_> = json["data"]["assignments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["date"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(days, ["2026-02-16", "2026-02-18"]);
+    assert_eq!(json["data"]["staff_id"], alice.to_string());
+
+    let res = get(Uuid::new_v4()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn staff_assignments_span_schedules_within_the_range() {
+    let mut repo = MockJobRepository::new();
+    let staff_id = Uuid::new_v4();
+    let from = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+    repo.expect_get_staff_assignments()
+        .withf(move |id, f, t, published_only| {
+            *id == staff_id && *f == from && *t == to && *published_only
+        })
+        .times(1)
+        .returning(move |staff_id, from, _, _| {
+            Ok([Uuid::new_v4(), Uuid::new_v4()]
+                .into_iter()
+                .map(|job_id| ShiftAssignment {
+                    id: Uuid::new_v4(),
+                    job_id,
+                    staff_id,
+                    date: from,
+                    shift_type: ShiftType::Morning,
+                    note: None,
+                })
+                .collect())
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+    let get = |query: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/staff/{staff_id}/assignments?{query}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+
+    let res = get("from=2026-02-01&to=2026-03-31").await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["assignments"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["from"], "2026-02-01");
+
+    for query in [
+        "from=2026-03-31&to=2026-02-01",
+        "from=2026-01-01&to=2027-06-01",
+    ] {
+        let res = get(query).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+}
+
+#[tokio::test]
+async fn get_group_calendar_returns_ics() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+    let staff_id = Uuid::new_v4();
+
+    let assignments = vec![ShiftAssignment {
+        id: Uuid::new_v4(),
+        job_id: Uuid::new_v4(),
+        staff_id,
+        date: NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+        shift_type: ShiftType::Morning,
+        note: None,
+    }];
+    repo.expect_get_group_assignments()
+        .returning(move |_, _, _| Ok(assignments.clone()));
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_get_resolved_members()
+        .returning(|_, _| Err(SchedulingServiceError::DataService("down".into())));
+
+    let app = build_test_app(repo, client);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/groups/{group_id}/calendar.ics"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["content-type"],
+        "text/calendar; charset=utf-8"
+    );
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let ics = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    // Falls back to staff ids when names can't be fetched
+    assert!(ics.contains(&format!("SUMMARY:{staff_id} - Morning shift")));
+}
+
+#[tokio::test]
+async fn next_period_skips_scheduled_periods() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+    let upcoming = next_monday();
+    let mut scheduled = make_job(Uuid::new_v4(), JobStatus::Completed);
+    scheduled.staff_group_id = group_id;
+    scheduled.period_begin_date = upcoming;
+
+    repo.expect_find_overlapping_jobs()
+        .withf(move |id, _, _| *id == group_id)
+        .returning(move |_, _, _| Ok(vec![scheduled.clone()]));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/groups/{group_id}/next-period"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let expected = upcoming + chrono::Duration::days(28);
+    assert_eq!(json["data"]["period_begin_date"], expected.to_string());
+    assert_eq!(json["data"]["timezone"], "UTC");
+}
+
+#[tokio::test]
+async fn share_link_grants_read_only_access_to_result() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_assignments().returning(|_| Ok(vec![]));
+    repo.expect_get_notes().returning(|_| Ok(vec![]));
+    repo.expect_get_borrowed_staff().returning(|_| Ok(vec![]));
+
+    repo.expect_get_coverage_shortfalls()
+        .returning(|_| Ok(vec![]));
+
+    repo.expect_get_unfilled_shifts().returning(|_| Ok(vec![]));
+    repo.expect_get_published_at()
+        .returning(|_| Ok(Some(chrono::Utc::now())));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/share"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"expires_in_hours": 24}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let token = json["data"]["token"].as_str().unwrap().to_string();
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/shared/{token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["schedule_id"], job_id.to_string());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/shared/{}x", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn share_link_is_refused_for_a_draft() {
+    let mut repo = MockJobRepository::new();
+    let job_id = Uuid::new_v4();
+    let job = make_job(job_id, JobStatus::Completed);
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_published_at()
+        .times(1)
+        .returning(|_| Ok(None));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/share"))
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn probe_reports_data_service_status() {
+    let mut prober = MockHealthProber::new();
+    prober.expect_probe().returning(|dep| ProbeReport {
+        name: dep.name.clone(),
+        target: dep.url.clone(),
+        required: dep.required,
+        healthy: false,
+        status: Some(503),
+        latency_ms: 12,
+        error: None,
+        consecutive_failures: 0,
+    });
+
+    let app = build_test_app_with_prober(
+        MockJobRepository::new(),
+        MockDataServiceClient::new(),
+        prober,
+    );
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/admin/probe/data-service")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["healthy"], false);
+    assert_eq!(json["data"]["status"], 503);
+    assert_eq!(json["data"]["target"], "http://data-service/headpat");
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/ready")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/admin/probe/auth-service")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn readiness_waits_for_startup_self_check() {
+    let mut prober = MockHealthProber::new();
+    prober.expect_probe().returning(|dep| ProbeReport {
+        name: dep.name.clone(),
+        target: dep.url.clone(),
+        required: dep.required,
+        healthy: true,
+        status: Some(200),
+        latency_ms: 3,
+        error: None,
+        consecutive_failures: 0,
+    });
+    let migrated = Arc::new(AtomicBool::new(false));
+    let startup = Arc::new(StartupSelfCheck::new().with_check("migrations", {
+        let migrated = migrated.clone();
+        move || {
+            let result = if migrated.load(Ordering::SeqCst) {
+                Ok("8 applied, latest 8".to_string())
+            } else {
+                Err("migrations 8 is pending".to_string())
+            };
+            async move { result }
+        }
+    }));
+    let app = test_router(SchedulingAppState {
+        scheduling_service: Arc::new(SchedulingService::new(
+            Arc::new(MockJobRepository::new()),
+            Arc::new(MockDataServiceClient::new()),
+            SchedulingConfig::default(),
+        )),
+        health_checker: Arc::new(HealthChecker::new(
+            Arc::new(prober),
+            HealthConfig::default().resolve("http://data-service"),
+        )),
+        startup: startup.clone(),
+        dependencies: Arc::new(
+            DependencyChecks::new()
+                .with_check("postgres", || async { Ok("PostgreSQL 17.2".to_string()) }),
+        ),
+    });
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    // Not run yet
+    let res = app.clone().oneshot(get("/healthz/startup")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    startup.run().await;
+    let res = app.clone().oneshot(get("/healthz/startup")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["attempt"], 1);
+    assert_eq!(json["data"]["checks"][0]["name"], "migrations");
+    assert_eq!(
+        json["data"]["checks"][0]["detail"],
+        "migrations 8 is pending"
+    );
+
+    let res = app.clone().oneshot(get("/ready")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"], "Startup self-check has not passed");
+    let res = app.clone().oneshot(get("/readyz")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let res = app.clone().oneshot(get("/healthz")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    migrated.store(true, Ordering::SeqCst);
+    startup.run().await;
+    let res = app.clone().oneshot(get("/healthz/startup")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(get("/ready")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.oneshot(get("/readyz")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["dependencies"][0]["name"], "postgres");
+}
+
+#[tokio::test]
+async fn import_history_stores_one_completed_job_per_period() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+    let staff_id = Uuid::new_v4();
+
+    repo.expect_find_overlapping_jobs()
+        .returning(|_, _, _| Ok(vec![]));
+    repo.expect_import_completed_jobs()
+        .withf(|_, periods| {
+            periods.len() == 2
+                && periods[0].period_begin_date == NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()
+                && periods[1].assignments.len() == 1
+        })
+        .returning(move |group_id, periods| {
+            Ok(periods
+                .iter()
+                .map(|p| ScheduleJob {
+                    staff_group_id: group_id,
+                    period_begin_date: p.period_begin_date,
+                    result_checksum: Some(p.checksum.clone()),
+                    ..make_job(Uuid::new_v4(), JobStatus::Completed)
+                })
+                .collect())
+        });
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let csv = format!(
+        "staff_id,date,shift_type\n\
+         {staff_id},2025-01-06,MORNING\n\
+         {staff_id},2025-01-07,DAY_OFF\n\
+         {staff_id},2025-01-13,EVENING\n"
+    );
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/admin/groups/{group_id}/history"))
+                .header("content-type", "text/csv")
+                .body(Body::from(csv))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["rows"], 3);
+    assert_eq!(json["data"]["jobs"][0]["status"], "COMPLETED");
+    assert_eq!(json["data"]["jobs"][1]["period_begin_date"], "2025-01-13");
+}
+
+#[tokio::test]
+async fn import_history_refuses_periods_that_already_have_a_job() {
+    let mut repo = MockJobRepository::new();
+    let group_id = Uuid::new_v4();
+    let existing = ScheduleJob {
+        period_begin_date: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+        ..make_job(Uuid::new_v4(), JobStatus::Completed)
+    };
+
+    repo.expect_find_overlapping_jobs()
+        .returning(move |_, _, _| Ok(vec![existing.clone()]));
+    repo.expect_import_completed_jobs().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/admin/groups/{group_id}/history"))
+                .body(Body::from(format!(
+                    "staff_id,date,shift_type\n{},2025-01-08,MORNING\n",
+                    Uuid::new_v4()
+                )))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn pack_assignments_reports_packed_and_skipped_jobs() {
+    let mut repo = MockJobRepository::new();
+    let after = Uuid::from_u128(1);
+    let jobs = [Uuid::from_u128(2), Uuid::from_u128(3)];
+
+    repo.expect_find_unpacked_jobs()
+        .withf(move |a, limit| *a == Some(after) && *limit == 2)
+        .returning(move |_, _| Ok(jobs.to_vec()));
+    repo.expect_pack_job()
+        .returning(move |id| Ok((id == jobs[0]).then_some(112)));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/v1/admin/assignments/pack?after={after}&limit=2"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["scanned"], 2);
+    assert_eq!(json["data"]["packed_jobs"], 1);
+    assert_eq!(json["data"]["packed_rows"], 112);
+    assert_eq!(json["data"]["skipped"][0], jobs[1].to_string());
+    assert_eq!(json["data"]["next_after"], jobs[1].to_string());
+}
+
+#[tokio::test]
+async fn staff_reference_check_reports_and_notes_orphaned_assignments() {
+    let mut repo = MockJobRepository::new();
+    let (kept, deleted) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let (noted_job, fresh_job) = (Uuid::from_u128(10), Uuid::from_u128(11));
+
+    repo.expect_find_staff_references().returning(move || {
+        Ok(vec![
+            StaffReferences {
+                staff_id: kept,
+                job_ids: vec![noted_job, fresh_job],
+            },
+            StaffReferences {
+                staff_id: deleted,
+                job_ids: vec![noted_job, fresh_job],
+            },
+        ])
+    });
+    // An earlier run already left its note on `noted_job`
+    repo.expect_get_notes().returning(move |job_id| {
+        Ok((job_id == noted_job)
+            .then(|| ScheduleNote {
+                id: Uuid::new_v4(),
+                job_id,
+                author: CONSISTENCY_NOTE_AUTHOR.to_string(),
+                body: orphan_note(&[deleted]),
+                created_at: chrono::Utc::now(),
+            })
+            .into_iter()
+            .collect())
+    });
+    repo.expect_add_note()
+        .withf(move |id, author, body| {
+            *id == fresh_job
+                && author == CONSISTENCY_NOTE_AUTHOR
+                && body.contains(&deleted.to_string())
+        })
+        .times(1)
+        .returning(|job_id, author, body| {
+            Ok(ScheduleNote {
+                id: Uuid::new_v4(),
+                job_id,
+                author,
+                body,
+                created_at: chrono::Utc::now(),
+            })
+        });
+
+    let mut client = MockDataServiceClient::new();
+    client
+        .expect_get_staff_by_ids()
+        .withf(move |ids| ids == &[kept, deleted])
+        .returning(move |_| Ok(vec![make_staff(kept)]));
+
+    let app = build_test_app(repo, client);
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/admin/consistency/staff-references?annotate=true")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["checked_staff"], 2);
+    assert_eq!(json["data"]["orphaned"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"]["orphaned"][0]["staff_id"], deleted.to_string());
+    assert_eq!(json["data"]["annotated_jobs"], 1);
+}
+
+/// Completed job of a group that needs two of its three leads to sign off.
+fn approval_fixture() -> (ScheduleJob, Vec<Uuid>, SchedulingConfig) {
+    let job = ScheduleJob {
+        result_checksum: Some("abc".to_string()),
+        ..make_job(Uuid::new_v4(), JobStatus::Completed)
+    };
+    let leads: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    let mut config = SchedulingConfig::default();
+    config.approvals.groups.insert(
+        job.staff_group_id,
+        GroupApprovers {
+            approvers: leads.clone(),
+            required: Some(2),
+        },
+    );
+    (job, leads, config)
+}
+
+fn approval(job_id: Uuid, approver_id: Uuid, checksum: &str) -> ScheduleApproval {
+    ScheduleApproval {
+        job_id,
+        approver_id,
+        decision: ApprovalDecision::Approved,
+        comment: None,
+        checksum: Some(checksum.to_string()),
+        decided_at: chrono::Utc::now(),
+    }
+}
+
+fn publish_request(job_id: Uuid) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/schedules/{job_id}/publish"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn publish_after_required_approvals() {
+    let mut repo = MockJobRepository::new();
+    let (job, leads, config) = approval_fixture();
+    let job_id = job.id;
+    let approvals = vec![
+        approval(job_id, leads[0], "abc"),
+        approval(job_id, leads[2], "abc"),
+    ];
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_approvals()
+        .returning(move |_| Ok(approvals.clone()));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_publish_job()
+        .withf(move |id| *id == job_id)
+        .times(1)
+        .returning(|_| Ok(Some(chrono::Utc::now())));
+
+    let app = build_test_app_with(
+        repo,
+        MockDataServiceClient::new(),
+        MockHealthProber::new(),
+        config,
+    );
+
+    let res = app.oneshot(publish_request(job_id)).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["approved"], 2);
+    assert_eq!(json["data"]["required"], 2);
+    assert!(json["data"]["published_at"].is_string());
+}
+
+#[tokio::test]
+async fn publish_refuses_approvals_given_before_an_edit() {
+    let mut repo = MockJobRepository::new();
+    let (job, leads, config) = approval_fixture();
+    let job_id = job.id;
+    let approvals = vec![
+        approval(job_id, leads[0], "abc"),
+        approval(job_id, leads[1], "old"),
+    ];
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_approvals()
+        .returning(move |_| Ok(approvals.clone()));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_publish_job().never();
+
+    let app = build_test_app_with(
+        repo,
+        MockDataServiceClient::new(),
+        MockHealthProber::new(),
+        config,
+    );
+
+    let res = app.oneshot(publish_request(job_id)).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+fn acknowledge_request(job_id: Uuid, staff_id: Uuid) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/schedules/{job_id}/acknowledgments"))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "staff_id": staff_id }).to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn staff_acknowledge_a_published_schedule() {
+    let mut repo = MockJobRepository::new();
+    let job = make_job(Uuid::new_v4(), JobStatus::Completed);
+    let job_id = job.id;
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_published_at()
+        .returning(|_| Ok(Some(chrono::Utc::now())));
+    repo.expect_get_acknowledgments().returning(move |_| {
+        Ok(vec![
+            StaffAcknowledgment {
+                staff_id: alice,
+                acknowledged_at: None,
+            },
+            StaffAcknowledgment {
+                staff_id: bob,
+                acknowledged_at: None,
+            },
+        ])
+    });
+    repo.expect_record_acknowledgment()
+        .withf(move |id, staff| *id == job_id && *staff == alice)
+        .times(1)
+        .returning(|_, _| Ok(chrono::Utc::now()));
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .oneshot(acknowledge_request(job_id, alice))
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["roster_size"], 2);
+    assert_eq!(json["data"]["acknowledged"], 1);
+    assert_eq!(json["data"]["outstanding"], json!([bob]));
+}
+
+#[tokio::test]
+async fn acknowledge_refuses_unpublished_schedules_and_staff_off_the_roster() {
+    let mut repo = MockJobRepository::new();
+    let unpublished = make_job(Uuid::new_v4(), JobStatus::Completed);
+    let published = make_job(Uuid::new_v4(), JobStatus::Completed);
+    let (unpublished_id, published_id) = (unpublished.id, published.id);
+    let rostered = Uuid::new_v4();
+
+    repo.expect_find_by_id().returning(move |id| {
+        Ok(Some(if id == unpublished_id {
+            unpublished.clone()
+        } else {
+            published.clone()
+        }))
+    });
+    repo.expect_get_published_at()
+        .returning(move |id| Ok((id == published_id).then(chrono::Utc::now)));
+    repo.expect_get_acknowledgments().returning(move |_| {
+        Ok(vec![StaffAcknowledgment {
+            staff_id: rostered,
+            acknowledged_at: None,
+        }])
+    });
+    repo.expect_record_acknowledgment().never();
+
+    let app = build_test_app(repo, MockDataServiceClient::new());
+
+    let res = app
+        .clone()
+        .oneshot(acknowledge_request(unpublished_id, rostered))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(acknowledge_request(published_id, Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn staff_acknowledge_as_themselves_unless_they_schedule() {
+    let mut repo = MockJobRepository::new();
+    let job = make_job(Uuid::new_v4(), JobStatus::Completed);
+    let job_id = job.id;
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_published_at()
+        .returning(|_| Ok(Some(chrono::Utc::now())));
+    repo.expect_get_acknowledgments().returning(move |_| {
+        Ok([alice, bob]
+            .map(|staff_id| StaffAcknowledgment {
+                staff_id,
+                acknowledged_at: None,
+            })
+            .to_vec())
+    });
+    repo.expect_record_acknowledgment()
+        .withf(move |_, staff| *staff == alice)
+        .times(1)
+        .returning(|_, _| Ok(chrono::Utc::now()));
+    repo.expect_record_acknowledgment()
+        .withf(move |_, staff| *staff == bob)
+        .times(1)
+        .returning(|_, _| Ok(chrono::Utc::now()));
+
+    let app = build_authenticated_app(repo, SchedulingConfig::default());
+    let acknowledge = |token: String, body: serde_json::Value| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/schedules/{job_id}/acknowledgments"))
+                .header("content-type", "application/json")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let alice_token = token_for(&alice.to_string(), &["viewer"]);
+
+    let res = acknowledge(alice_token.clone(), json!({})).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // A viewer can't acknowledge for someone else, nor without being staff
+    let res = acknowledge(alice_token, json!({ "staff_id": bob }))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = acknowledge(token(&["viewer"]), json!({})).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = acknowledge(token(&["scheduler"]), json!({ "staff_id": bob }))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn reject_records_comment_of_a_designated_approver() {
+    let mut repo = MockJobRepository::new();
+    let (job, leads, config) = approval_fixture();
+    let job_id = job.id;
+    let lead = leads[1];
+
+    repo.expect_find_by_id()
+        .returning(move |_| Ok(Some(job.clone())));
+    repo.expect_get_published_at().returning(|_| Ok(None));
+    repo.expect_record_approval()
+        .withf(move |id, approver, decision, comment, checksum| {
+            *id == job_id
+                && *approver == lead
+                && *decision == ApprovalDecision::Rejected
+                && comment.as_deref() == Some("Ward B has no evening cover on the 20th")
+                && checksum.as_deref() == Some("abc")
+        })
+        .times(1)
+        .returning(|_, _, _, _, _| Ok(()));
+    repo.expect_get_approvals().returning(move |_| {
+        Ok(vec![ScheduleApproval {
+            decision: ApprovalDecision::Rejected,
+            ..approval(job_id, lead, "abc")
+        }])
+    });
+
+    let app = build_authenticated_app(repo, config);
+
+    let reject = |approver: &str, comment: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/schedules/{job_id}/approvals/reject"))
+            .header("content-type", "application/json")
+            .header(
+                "Authorization",
+                format!("Bearer {}", token_for(approver, &["scheduler"])),
+            )
+            .body(Body::from(json!({ "comment": comment }).to_string()))
+            .unwrap()
+    };
+    let lead = lead.to_string();
+
+    let res = app
+        .clone()
+        .oneshot(reject(&lead, " Ward B has no evening cover on the 20th "))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["data"]["rejected"], 1);
+    assert_eq!(json["data"]["can_publish"], false);
+
+    // Rejections need a reason, and only the group's leads can decide, as themselves
+    let res = app.clone().oneshot(reject(&lead, "  ")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = app
+        .clone()
+        .oneshot(reject(&Uuid::new_v4().to_string(), "Looks wrong"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = app
+        .oneshot(reject("planner@example.com", "Looks wrong"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+fn token(roles: &[&str]) -> String {
+    token_for("user-1", roles)
+}
+
+fn token_for(subject: &str, roles: &[&str]) -> String {
+    let claims = json!({
+        "sub": subject,
+        "roles": roles,
+        "exp": chrono::Utc::now().timestamp() + 3600,
+    });
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(b"test-secret"),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn schedule_routes_require_a_token_with_the_right_role() {
+    let job_id = Uuid::new_v4();
+    let mut repo = MockJobRepository::new();
+    repo.expect_find_by_id()
+        .returning(|id| Ok(Some(make_job(id, JobStatus::Completed))));
+    let svc = Arc::new(
+        SchedulingService::new(
+            Arc::new(repo),
+            Arc::new(MockDataServiceClient::new()),
+            SchedulingConfig::default(),
+        )
+        .with_share_links(ShareLinkSigner::new("test-secret")),
+    );
+    let app = test_router_with_auth(
+        SchedulingAppState {
+            scheduling_service: svc,
+            health_checker: Arc::new(HealthChecker::new(
+                Arc::new(MockHealthProber::new()),
+                HealthConfig::default().resolve("http://data-service"),
+            )),
+            startup: Arc::new(StartupSelfCheck::new()),
+            dependencies: Arc::new(DependencyChecks::new()),
+        },
+        Authenticator::new(AuthConfig {
+            secret: Some("test-secret".to_string()),
+            ..AuthConfig::default()
+        }),
+    );
+    let call = |method: &str, uri: String, token: Option<String>| {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let status_uri = format!("/api/v1/schedules/{job_id}/status");
+
+    let res = call("GET", status_uri.clone(), None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = call("GET", status_uri, Some(token(&["viewer"])))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Roles outside the known set grant nothing
+    let res = call(
+        "POST",
+        format!("/api/v1/schedules/{job_id}/retry"),
+        Some(token(&["viewer", "owner"])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = call(
+        "GET",
+        "/api/v1/admin/probe/data-service".to_string(),
+        Some(token(&["scheduler"])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    // Share links carry their own signature and need no token
+    let res = call("GET", "/api/v1/shared/not-a-link".to_string(), None)
+        .await
+        .unwrap();
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
 }
@@ -0,0 +1,175 @@
+//! Query plan benchmark for the assignment indexes on a ~1M row table.
+//!
+//! Needs a Postgres server the test can create databases on:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://user@localhost/postgres \
+//!     cargo test -p scheduling-service --test assignment_index_bench -- --ignored --nocapture
+//! ```
+
+use serde_json::Value;
+use sqlx::PgPool;
+
+const JOBS: i32 = 1000;
+const STAFF_PER_JOB: i32 = 36;
+const STAFF_POOL: i32 = 5000;
+
+/// 1000 completed jobs x 36 staff x 28 days = 1,008,000 assignments.
+async fn seed(pool: &PgPool) {
+    sqlx::query(
+        r#"
+        INSERT INTO schedule_jobs (id, staff_group_id, period_begin_date, status)
+        SELECT md5('job' || n)::uuid,
+               md5('group' || (n % 50))::uuid,
+               DATE '2024-01-01' + ((n / 50) * 28),
+               'COMPLETED'
+        FROM generate_series(1, $1) AS n
+        "#,
+    )
+    .bind(JOBS)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO shift_assignments (job_id, staff_id, date, shift_type)
+        SELECT md5('job' || n)::uuid,
+               md5('staff' || ((n * $2 + s) % $3))::uuid,
+               DATE '2024-01-01' + ((n / 50) * 28) + d,
+               (ARRAY['MORNING', 'EVENING', 'DAY_OFF']::shift_type[])[1 + (s + d) % 3]
+        FROM generate_series(1, $1) AS n,
+             generate_series(1, $2) AS s,
+             generate_series(0, 27) AS d
+        "#,
+    )
+    .bind(JOBS)
+    .bind(STAFF_PER_JOB)
+    .bind(STAFF_POOL)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    sqlx::query("ANALYZE").execute(pool).await.unwrap();
+}
+
+struct Plan {
+    execution_ms: f64,
+    indexes: Vec<String>,
+}
+
+async fn explain(pool: &PgPool, sql: &str) -> Plan {
+    let (plan,): (Value,) =
+        sqlx::query_as(&format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {sql}"))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+
+    fn collect(node: &Value, indexes: &mut Vec<String>) {
+        if let Some(name) = node["Index Name"].as_str() {
+            indexes.push(name.to_string());
+        }
+        for child in node["Plans"].as_array().into_iter().flatten() {
+            collect(child, indexes);
+        }
+    }
+
+    let mut indexes = Vec::new();
+    collect(&plan[0]["Plan"], &mut indexes);
+    Plan {
+        execution_ms: plan[0]["Execution Time"].as_f64().unwrap(),
+        indexes,
+    }
+}
+
+/// Best of a few runs, so the first (cold cache) run doesn't dominate.
+async fn best_of(pool: &PgPool, sql: &str, runs: usize) -> Plan {
+    let mut best = explain(pool, sql).await;
+    for _ in 1..runs {
+        let plan = explain(pool, sql).await;
+        if plan.execution_ms < best.execution_ms {
+            best = plan;
+        }
+    }
+    best
+}
+
+fn queries() -> [(&'static str, String); 3] {
+    [
+        (
+            "staff within date range",
+            "SELECT id, job_id, staff_id, date, shift_type FROM shift_assignments \
+             WHERE staff_id = md5('staff42')::uuid AND date BETWEEN '2024-06-01' AND '2024-08-31' \
+             ORDER BY date"
+                .to_string(),
+        ),
+        (
+            "job within date range",
+            "SELECT id, job_id, staff_id, date, shift_type FROM shift_assignments \
+             WHERE job_id = md5('job500')::uuid AND date BETWEEN '2024-10-07' AND '2024-10-13' \
+             ORDER BY date"
+                .to_string(),
+        ),
+        (
+            "group calendar",
+            "WITH latest_jobs AS ( \
+                 SELECT DISTINCT ON (period_begin_date) id FROM schedule_jobs \
+                 WHERE staff_group_id = md5('group7')::uuid AND status = 'COMPLETED' \
+                 ORDER BY period_begin_date, created_at DESC) \
+             SELECT sa.id, sa.job_id, sa.staff_id, sa.date, sa.shift_type \
+             FROM shift_assignments sa JOIN latest_jobs lj ON sa.job_id = lj.id \
+             WHERE sa.date BETWEEN '2025-01-01' AND '2025-03-31' AND sa.shift_type <> 'DAY_OFF' \
+             ORDER BY sa.date, sa.staff_id"
+                .to_string(),
+        ),
+    ]
+}
+
+#[sqlx::test]
+#[ignore = "seeds 1M rows; needs DATABASE_URL"]
+async fn composite_assignment_indexes_beat_single_column(pool: PgPool) {
+    seed(&pool).await;
+
+    let mut after = Vec::new();
+    for (name, sql) in queries() {
+        after.push((name, best_of(&pool, &sql, 5).await));
+    }
+
+    // Back to the indexes from the initial migration
+    sqlx::raw_sql(
+        r#"
+        DROP INDEX idx_sa_job_date;
+        DROP INDEX idx_sa_staff_date;
+        DROP INDEX idx_jobs_group_period;
+        CREATE INDEX idx_sa_job ON shift_assignments(job_id);
+        CREATE INDEX idx_sa_staff ON shift_assignments(staff_id);
+        CREATE INDEX idx_jobs_group ON schedule_jobs(staff_group_id);
+        ANALYZE;
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    println!("{:<26} {:>12} {:>12}", "query", "before (ms)", "after (ms)");
+    for ((name, sql), (_, new_plan)) in queries().into_iter().zip(&after) {
+        let old_plan = best_of(&pool, &sql, 5).await;
+        println!(
+            "{name:<26} {:>12.3} {:>12.3}   {:?} -> {:?}",
+            old_plan.execution_ms, new_plan.execution_ms, old_plan.indexes, new_plan.indexes
+        );
+    }
+
+    let (_, staff_plan) = &after[0];
+    assert!(
+        staff_plan.indexes.iter().any(|i| i == "idx_sa_staff_date"),
+        "staff range query should use idx_sa_staff_date, used {:?}",
+        staff_plan.indexes
+    );
+    let (_, job_plan) = &after[1];
+    assert!(
+        job_plan.indexes.iter().any(|i| i == "idx_sa_job_date"),
+        "job range query should use idx_sa_job_date, used {:?}",
+        job_plan.indexes
+    );
+}
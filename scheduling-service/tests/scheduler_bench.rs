@@ -0,0 +1,175 @@
+//! Schedule generation cost for large groups.
+//!
+//! The default suite counts rule evaluations, which is deterministic and the same in debug
+//! and release builds. The wall-clock timings are ignored by default:
+//!
+//! ```sh
+//! cargo test --release -p scheduling-service --test scheduler_bench -- --ignored --nocapture
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, NaiveDate};
+use shared::types::{ShiftEligibility, ShiftType};
+use uuid::Uuid;
+
+use scheduling_service::domain::{
+    coverage::{PositionLimit, ShiftPositionLimits},
+    scheduler::{
+        AssignmentContext, PERIOD_DAYS, RosterMember, SchedulingConfig, SchedulingRule,
+        ShiftHistory, gen_schedule,
+    },
+};
+
+/// Rule evaluations the previous generator made for a 1,000-staff period, measured with
+/// [`CountingRule`] on the same rosters. It re-evaluated every rule for every staff member
+/// still waiting on each pick, so the count was quadratic in the roster.
+const BASELINE_EVALUATIONS: [(&str, usize); 2] = [("default", 12_218_500), ("coverage", 4_395_205)];
+/// The generator has to do at most half the baseline's work.
+const MIN_SPEEDUP: usize = 2;
+/// How many times the work may grow for ten times the staff. A linear generator does 10x.
+const MAX_GROWTH: usize = 11;
+const RUNS: usize = 3;
+const POSITIONS: [&str; 4] = ["Nurse", "Doctor", "Porter", "Clerk"];
+
+/// Counts every call the generator makes into a rule, allowing everything.
+struct CountingRule(Arc<AtomicUsize>);
+
+impl SchedulingRule for CountingRule {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    fn is_valid(&self, _ctx: &AssignmentContext, _candidate: &ShiftType) -> bool {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    fn preferred_shift(&self, _ctx: &AssignmentContext) -> Option<ShiftType> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+}
+
+fn roster(size: usize) -> Vec<RosterMember> {
+    (0..size)
+        .map(|i| RosterMember {
+            staff_id: Uuid::from_u128(i as u128 + 1),
+            position: POSITIONS[i % POSITIONS.len()].to_string(),
+            fairness_weight: 1.0 + (i % 3) as f64 * 0.5,
+            shift_eligibility: ShiftEligibility::Any,
+            history: ShiftHistory::default(),
+            last_shift: None,
+            time_off: HashSet::new(),
+            starts_on: None,
+            effective_until: None,
+        })
+        .collect()
+}
+
+/// Each position needs a fifth of its staff on mornings and on evenings.
+fn coverage_config(group_id: Uuid, size: usize) -> SchedulingConfig {
+    let min = size / POSITIONS.len() / 5;
+    let limits: HashMap<String, PositionLimit> = POSITIONS
+        .iter()
+        .map(|p| (p.to_string(), PositionLimit { min, max: None }))
+        .collect();
+    let mut config = SchedulingConfig::default();
+    config.position_coverage.groups.insert(
+        group_id,
+        ShiftPositionLimits {
+            morning: limits.clone(),
+            evening: limits,
+        },
+    );
+    config
+}
+
+fn config_for(case: &str, group_id: Uuid, size: usize) -> SchedulingConfig {
+    match case {
+        "coverage" => coverage_config(group_id, size),
+        _ => SchedulingConfig::default(),
+    }
+}
+
+fn monday() -> NaiveDate {
+    let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    assert_eq!(monday.weekday(), chrono::Weekday::Mon);
+    monday
+}
+
+fn rule_evaluations(case: &str, size: usize) -> usize {
+    let group_id = Uuid::from_u128(7);
+    let members = roster(size);
+    let config = config_for(case, group_id, size);
+    let evaluations = Arc::new(AtomicUsize::new(0));
+    let mut rules = config.build_rules_for_group(group_id, vec![]);
+    rules.push(Box::new(CountingRule(evaluations.clone())));
+
+    let assignments = gen_schedule(&members, monday(), &rules, &config).unwrap();
+    assert_eq!(assignments.len(), size * PERIOD_DAYS);
+    evaluations.load(Ordering::Relaxed)
+}
+
+fn best_of(runs: usize, mut f: impl FnMut()) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..runs {
+        let started = Instant::now();
+        f();
+        best = best.min(started.elapsed());
+    }
+    best
+}
+
+#[test]
+fn generator_does_at_most_half_the_baseline_rule_work() {
+    for (case, baseline) in BASELINE_EVALUATIONS {
+        let evaluations = rule_evaluations(case, 1000);
+        assert!(
+            evaluations * MIN_SPEEDUP <= baseline,
+            "{case}: {evaluations} rule evaluations, at most {} allowed",
+            baseline / MIN_SPEEDUP
+        );
+    }
+}
+
+#[test]
+fn generator_work_grows_linearly_with_the_roster() {
+    for (case, _) in BASELINE_EVALUATIONS {
+        let small = rule_evaluations(case, 100);
+        let large = rule_evaluations(case, 1000);
+        assert!(
+            large <= small * MAX_GROWTH,
+            "{case}: 10x the staff took {large} rule evaluations against {small}"
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn generator_timings() {
+    let group_id = Uuid::new_v4();
+    println!(
+        "{:<16} {:>14} {:>14} {:>8}",
+        "case", "100 (ms)", "1,000 (ms)", "growth"
+    );
+    for (case, _) in BASELINE_EVALUATIONS {
+        let [small, large] = [100, 1000].map(|size| {
+            let members = roster(size);
+            let config = config_for(case, group_id, size);
+            let rules = config.build_rules_for_group(group_id, vec![]);
+            best_of(RUNS, || {
+                gen_schedule(&members, monday(), &rules, &config).unwrap();
+            })
+        });
+        println!(
+            "{case:<16} {:>14.2} {:>14.2} {:>7.1}x",
+            small.as_secs_f64() * 1000.0,
+            large.as_secs_f64() * 1000.0,
+            large.as_secs_f64() / small.as_secs_f64()
+        );
+    }
+}
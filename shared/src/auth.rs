@@ -0,0 +1,453 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::extract::FromRequestParts;
+use axum::http::{StatusCode, header, request::Parts};
+use axum::response::{IntoResponse, Response};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use utoipa::Modify;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+
+use crate::responses::ApiResponse;
+
+/// A cached key set older than this is fetched again before it is used.
+const JWKS_MAX_AGE: Duration = Duration::from_secs(3600);
+/// A token signed with a key we don't know triggers a refetch, at most this often, so a
+/// flood of forged `kid`s can't hammer the identity provider.
+const JWKS_MIN_REFRESH: Duration = Duration::from_secs(30);
+/// Signature algorithms accepted from a JWKS. HMAC is left out: a public key set must never
+/// be usable as a shared secret.
+const JWKS_ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// What a caller may do, each role including the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Read staff, groups and schedules
+    Viewer,
+    /// Also create, change and delete them, and submit schedules
+    Scheduler,
+    /// Also manage webhooks, onboarding rules and the admin endpoints
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Scheduler => "scheduler",
+            Self::Admin => "admin",
+        }
+    }
+
+    fn parse(role: &str) -> Option<Self> {
+        match role {
+            "viewer" => Some(Self::Viewer),
+            "scheduler" => Some(Self::Scheduler),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// The caller a validated token was issued to.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    /// The token's `sub`
+    pub subject: String,
+    /// Roles from the token's `roles` claim; unknown ones are dropped
+    pub roles: Vec<Role>,
+}
+
+impl Principal {
+    pub fn has(&self, role: Role) -> bool {
+        self.roles.iter().any(|r| *r >= role)
+    }
+
+    /// The staff member the caller is, when the identity provider issues tokens with the
+    /// staff id as `sub`.
+    pub fn staff_id(&self) -> Option<uuid::Uuid> {
+        self.subject.parse().ok()
+    }
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Header other services authenticate with on internal routes.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Where tokens are validated against, read from the environment:
+/// `AUTH_JWKS_URL` or `AUTH_JWT_SECRET` (HS256, for development), plus optional
+/// `AUTH_ISSUER` and `AUTH_AUDIENCE` the tokens must carry, and `AUTH_API_KEYS`, the
+/// comma-separated keys accepted from other services.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    pub jwks_url: Option<String>,
+    pub secret: Option<String>,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    /// Several keys can be valid at once, so one can be rotated without downtime
+    pub api_keys: Vec<String>,
+}
+
+impl AuthConfig {
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        Self {
+            jwks_url: var("AUTH_JWKS_URL"),
+            secret: var("AUTH_JWT_SECRET"),
+            issuer: var("AUTH_ISSUER"),
+            audience: var("AUTH_AUDIENCE"),
+            api_keys: var("AUTH_API_KEYS")
+                .map(|keys| {
+                    keys.split(',')
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+enum Keys {
+    Secret(DecodingKey),
+    Jwks(JwksKeys),
+}
+
+struct JwksKeys {
+    url: String,
+    client: reqwest::Client,
+    cached: RwLock<Option<(JwkSet, Instant)>>,
+}
+
+impl JwksKeys {
+    /// The key for `kid`, refetching the set when it is stale or doesn't have it. Without a
+    /// `kid` the set must hold exactly one key.
+    async fn key(&self, kid: Option<&str>) -> Result<DecodingKey, AuthError> {
+        let (found, fetched_at) = {
+            let cached = self.cached.read().expect("JWKS cache lock poisoned");
+            match cached.as_ref() {
+                Some((set, fetched_at)) => (find_key(set, kid), Some(*fetched_at)),
+                None => (None, None),
+            }
+        };
+        let stale = fetched_at.is_none_or(|at| at.elapsed() >= JWKS_MAX_AGE);
+        let may_refetch = fetched_at.is_none_or(|at| at.elapsed() >= JWKS_MIN_REFRESH);
+        match found {
+            Some(key) if !stale => return Ok(key),
+            _ if !may_refetch => {
+                return Err(AuthError::Invalid("unknown signing key".to_string()));
+            }
+            _ => {}
+        }
+
+        let set = self.fetch().await?;
+        let key = find_key(&set, kid);
+        *self.cached.write().expect("JWKS cache lock poisoned") = Some((set, Instant::now()));
+        key.ok_or(AuthError::Invalid("unknown signing key".to_string()))
+    }
+
+    async fn fetch(&self) -> Result<JwkSet, AuthError> {
+        let unavailable = |e: reqwest::Error| {
+            tracing::error!(url = %self.url, error = %e, "Failed to fetch JWKS");
+            AuthError::Unavailable
+        };
+        self.client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(unavailable)?
+            .json::<JwkSet>()
+            .await
+            .map_err(unavailable)
+    }
+}
+
+fn find_key(set: &JwkSet, kid: Option<&str>) -> Option<DecodingKey> {
+    let jwk = match kid {
+        Some(kid) => set.find(kid)?,
+        None if set.keys.len() == 1 => &set.keys[0],
+        None => return None,
+    };
+    DecodingKey::from_jwk(jwk).ok()
+}
+
+/// Validates bearer tokens for the [`ReadAccess`], [`WriteAccess`] and [`AdminAccess`]
+/// extractors, which find it as an `Arc<Authenticator>` request extension:
+/// `router.layer(Extension(Arc::new(authenticator)))`.
+pub struct Authenticator {
+    keys: Option<Keys>,
+    validation: Validation,
+    api_keys: Vec<String>,
+}
+
+impl Authenticator {
+    /// Tokens are checked against the JWKS when one is configured, else the secret. With
+    /// neither, authentication is off.
+    pub fn new(config: AuthConfig) -> Self {
+        let (keys, mut validation) = match (config.jwks_url, config.secret) {
+            (Some(url), _) => {
+                let mut validation = Validation::new(Algorithm::RS256);
+                validation.algorithms = JWKS_ALGORITHMS.to_vec();
+                let keys = Keys::Jwks(JwksKeys {
+                    url,
+                    client: reqwest::Client::new(),
+                    cached: RwLock::new(None),
+                });
+                (Some(keys), validation)
+            }
+            (None, Some(secret)) => (
+                Some(Keys::Secret(DecodingKey::from_secret(secret.as_bytes()))),
+                Validation::new(Algorithm::HS256),
+            ),
+            (None, None) => (None, Validation::default()),
+        };
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        if let Some(issuer) = config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        Self {
+            keys,
+            validation,
+            api_keys: config.api_keys,
+        }
+    }
+
+    /// Every request is let through as an admin.
+    pub fn disabled() -> Self {
+        Self::new(AuthConfig::default())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// A caller on an internal route: another service with a configured API key, or else a
+    /// user whose token has the `viewer` role. Once API keys are configured, a call without a
+    /// key or token is refused even when tokens aren't.
+    pub async fn authenticate_service(
+        &self,
+        api_key: Option<&str>,
+        authorization: Option<&str>,
+    ) -> Result<Principal, AuthError> {
+        if let Some(api_key) = api_key.filter(|_| !self.api_keys.is_empty()) {
+            return self
+                .api_keys
+                .iter()
+                .position(|key| constant_time_eq(key.as_bytes(), api_key.as_bytes()))
+                .map(|index| Principal {
+                    subject: format!("api-key:{index}"),
+                    roles: vec![Role::Viewer],
+                })
+                .ok_or(AuthError::Invalid("unknown API key".to_string()));
+        }
+        if !self.api_keys.is_empty() && !self.is_enabled() {
+            return Err(AuthError::Missing);
+        }
+        let principal = self.authenticate(authorization).await?;
+        if principal.has(Role::Viewer) {
+            Ok(principal)
+        } else {
+            Err(AuthError::Forbidden(Role::Viewer))
+        }
+    }
+
+    /// The caller behind an `Authorization: Bearer` header.
+    pub async fn authenticate(&self, authorization: Option<&str>) -> Result<Principal, AuthError> {
+        let Some(keys) = &self.keys else {
+            return Ok(Principal {
+                subject: "anonymous".to_string(),
+                roles: vec![Role::Admin],
+            });
+        };
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or(AuthError::Missing)?;
+
+        let key = match keys {
+            Keys::Secret(key) => key.clone(),
+            Keys::Jwks(jwks) => {
+                let header = jsonwebtoken::decode_header(token)
+                    .map_err(|e| AuthError::Invalid(e.to_string()))?;
+                jwks.key(header.kid.as_deref()).await?
+            }
+        };
+        let claims = jsonwebtoken::decode::<Claims>(token, &key, &self.validation)
+            .map_err(|e| AuthError::Invalid(e.to_string()))?
+            .claims;
+
+        Ok(Principal {
+            subject: claims.sub,
+            roles: claims.roles.iter().filter_map(|r| Role::parse(r)).collect(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    /// No bearer token
+    Missing,
+    /// Bad signature, expired, wrong issuer or audience, ...
+    Invalid(String),
+    /// Valid token without the role the route needs
+    Forbidden(Role),
+    /// The key set couldn't be fetched, or no authenticator was added to the router
+    Unavailable,
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::Missing => (StatusCode::UNAUTHORIZED, "Missing bearer token".to_string()),
+            Self::Invalid(reason) => (StatusCode::UNAUTHORIZED, format!("Invalid token: {reason}")),
+            Self::Forbidden(role) => (
+                StatusCode::FORBIDDEN,
+                format!("Requires the {} role", role.as_str()),
+            ),
+            Self::Unavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Tokens can't be validated right now".to_string(),
+            ),
+        };
+        tracing::warn!(%status, error = %message, "Request not authorized");
+
+        let body = axum::Json(ApiResponse::<()>::err(message));
+        if status == StatusCode::UNAUTHORIZED {
+            (status, [(header::WWW_AUTHENTICATE, "Bearer")], body).into_response()
+        } else {
+            (status, body).into_response()
+        }
+    }
+}
+
+/// Compares without returning early, so response times don't reveal how much of a key matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn authenticator(parts: &Parts) -> Result<&Authenticator, AuthError> {
+    parts
+        .extensions
+        .get::<Arc<Authenticator>>()
+        .map(Arc::as_ref)
+        .ok_or(AuthError::Unavailable)
+}
+
+fn header_value<'a>(parts: &'a Parts, name: &str) -> Option<&'a str> {
+    parts
+        .headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+async fn authorize(parts: &Parts, role: Role) -> Result<Principal, AuthError> {
+    let authorization = header_value(parts, header::AUTHORIZATION.as_str());
+    let principal = authenticator(parts)?.authenticate(authorization).await?;
+    if principal.has(role) {
+        Ok(principal)
+    } else {
+        Err(AuthError::Forbidden(role))
+    }
+}
+
+/// Extractor for routes that only read: any of the roles.
+#[derive(Debug)]
+pub struct ReadAccess(pub Principal);
+
+/// Extractor for routes that change data: `scheduler` or `admin`.
+#[derive(Debug)]
+pub struct WriteAccess(pub Principal);
+
+/// Extractor for internal routes other services read: an `X-Api-Key` from `AUTH_API_KEYS`, or
+/// a token with any of the roles.
+#[derive(Debug)]
+pub struct ServiceAccess(pub Principal);
+
+/// Extractor for configuration and maintenance routes: `admin` only.
+#[derive(Debug)]
+pub struct AdminAccess(pub Principal);
+
+impl<S: Send + Sync> FromRequestParts<S> for ReadAccess {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        authorize(parts, Role::Viewer).await.map(Self)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for WriteAccess {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        authorize(parts, Role::Scheduler).await.map(Self)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminAccess {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        authorize(parts, Role::Admin).await.map(Self)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ServiceAccess {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let api_key = header_value(parts, API_KEY_HEADER);
+        let authorization = header_value(parts, header::AUTHORIZATION.as_str());
+        authenticator(parts)?
+            .authenticate_service(api_key, authorization)
+            .await
+            .map(Self)
+    }
+}
+
+/// Adds the `bearer_auth` and `api_key` schemes the API docs refer to.
+pub struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
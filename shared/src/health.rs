@@ -0,0 +1,93 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::startup::CheckFn;
+
+/// A dependency that hasn't answered by then counts as down. Kept well under the usual
+/// probe timeout of orchestrators so `/readyz` itself answers in time.
+const DEPENDENCY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub healthy: bool,
+    /// What answered, or why the dependency counts as down
+    pub detail: String,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DependencyReport {
+    /// True when every dependency answered
+    pub ready: bool,
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+/// Named dependency pings behind `/readyz`. Unlike the startup self-check they run on every
+/// call, concurrently, and keep no state, so the answer reflects the dependencies right now.
+#[derive(Default)]
+pub struct DependencyChecks {
+    checks: Vec<(String, CheckFn)>,
+}
+
+impl DependencyChecks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a dependency. `Ok` carries a short description of what answered, `Err` the reason
+    /// it is down.
+    pub fn with_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        self.checks
+            .push((name.into(), Box::new(move || Box::pin(check()))));
+        self
+    }
+
+    /// Ping every dependency once. With none configured the service is ready.
+    pub async fn check(&self) -> DependencyReport {
+        let handles: Vec<_> = self
+            .checks
+            .iter()
+            .map(|(_, check)| {
+                let check = check();
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    let result = tokio::time::timeout(DEPENDENCY_TIMEOUT, check).await;
+                    (result, started.elapsed().as_millis() as u64)
+                })
+            })
+            .collect();
+
+        let mut dependencies = Vec::with_capacity(handles.len());
+        for (handle, (name, _)) in handles.into_iter().zip(&self.checks) {
+            let (healthy, detail, latency_ms) = match handle.await {
+                Ok((Ok(Ok(detail)), latency_ms)) => (true, detail, latency_ms),
+                Ok((Ok(Err(reason)), latency_ms)) => (false, reason, latency_ms),
+                Ok((Err(_), latency_ms)) => (
+                    false,
+                    format!("no answer within {DEPENDENCY_TIMEOUT:?}"),
+                    latency_ms,
+                ),
+                Err(e) => (false, format!("check task failed: {e}"), 0),
+            };
+            dependencies.push(DependencyStatus {
+                name: name.clone(),
+                healthy,
+                detail,
+                latency_ms,
+            });
+        }
+
+        DependencyReport {
+            ready: dependencies.iter().all(|d| d.healthy),
+            dependencies,
+        }
+    }
+}
@@ -1,5 +1,10 @@
+pub mod auth;
+pub mod health;
+pub mod metrics;
+pub mod pagination;
 pub mod responses;
 pub mod shutdown;
+pub mod startup;
 pub mod telemetry;
 pub mod time;
 pub mod types;
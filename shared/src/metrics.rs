@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+pub const CACHE_LOOKUPS_TOTAL: &str = "cache_lookups_total";
+pub const SCHEDULE_JOBS: &str = "schedule_jobs";
+pub const DEPENDENCY_HEALTH_FAILING: &str = "dependency_health_failing";
+
+/// Latency buckets in seconds, from a cache hit to a slow schedule generation.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+/// Histograms are compacted on this interval, which the exporter leaves to the caller.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Install the Prometheus recorder for the process. Metrics recorded before this, or in tests
+/// that never call it, are dropped. Must be called from within a Tokio runtime.
+pub fn init_metrics() -> PrometheusHandle {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(HTTP_REQUEST_DURATION_SECONDS.to_string()),
+            &LATENCY_BUCKETS,
+        )
+        .expect("latency buckets are not empty")
+        .install_recorder()
+        .expect("Failed to install the metrics recorder");
+
+    let upkeep = handle.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            upkeep.run_upkeep();
+        }
+    });
+    handle
+}
+
+/// Handler body for `GET /metrics`, in the Prometheus text format.
+pub fn render(handle: &PrometheusHandle) -> Response {
+    (
+        [(
+            http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        handle.render(),
+    )
+        .into_response()
+}
+
+/// Middleware counting requests and their latency by method, route template and status. Add
+/// it with `Router::layer` so the matched route is known; unmatched requests share one label
+/// instead of one per path.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |p| p.as_str().to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels)
+        .record(started.elapsed().as_secs_f64());
+    response
+}
+
+/// Count cache lookups by what was looked up, e.g. `staff`, and whether it was cached.
+pub fn record_cache_lookups(cache: &str, hits: u64, misses: u64) {
+    for (result, count) in [("hit", hits), ("miss", misses)] {
+        if count > 0 {
+            metrics::counter!(CACHE_LOOKUPS_TOTAL, "cache" => cache.to_string(), "result" => result)
+                .increment(count);
+        }
+    }
+}
+
+/// Jobs currently in `status` (`PENDING`, `PROCESSING`, ...).
+pub fn set_job_count(status: &str, count: i64) {
+    metrics::gauge!(SCHEDULE_JOBS, "status" => status.to_string()).set(count as f64);
+}
+
+/// 1 while `dependency` keeps failing its health checks, 0 once one passes.
+pub fn set_dependency_health_failing(dependency: &str, failing: bool) {
+    metrics::gauge!(DEPENDENCY_HEALTH_FAILING, "dependency" => dependency.to_string())
+        .set(if failing { 1.0 } else { 0.0 });
+}
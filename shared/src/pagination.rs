@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// One page of a listing. `total` counts every match, not just `items`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// `?limit=&offset=` of a paginated endpoint. Each endpoint picks its own default and
+/// maximum page size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageRequest {
+    /// Page size
+    pub limit: Option<i64>,
+    /// Number of items to skip
+    pub offset: Option<i64>,
+}
+
+impl PageRequest {
+    pub fn limit_or(&self, default: i64) -> i64 {
+        self.limit.unwrap_or(default)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0)
+    }
+
+    /// Check the request against the endpoint's largest page, returning the message for a 400.
+    ///
+    /// # Example
+    ///```
+    /// use shared::pagination::PageRequest;
+    /// let page = PageRequest { limit: Some(2000), offset: None };
+    /// assert!(page.validate(1000).is_err());
+    /// assert!(PageRequest::default().validate(1000).is_ok());
+    /// ```
+    pub fn validate(&self, max_limit: i64) -> Result<(), String> {
+        if let Some(limit) = self.limit
+            && !(1..=max_limit).contains(&limit)
+        {
+            return Err(format!("limit must be between 1 and {max_limit}"));
+        }
+        if self.offset() < 0 {
+            return Err("offset must not be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
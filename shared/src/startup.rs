@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use sqlx::migrate::Migrator;
+use utoipa::ToSchema;
+
+/// Delay between self-check runs while any check is still failing.
+pub const STARTUP_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// A check that hasn't answered by then counts as failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StartupCheck {
+    pub name: String,
+    pub passed: bool,
+    /// What was found, or why the check failed
+    pub detail: String,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StartupReport {
+    pub passed: bool,
+    /// Number of runs so far, 0 before the first one finished
+    pub attempt: u32,
+    pub checked_at: Option<DateTime<Utc>>,
+    pub checks: Vec<StartupCheck>,
+}
+
+pub type CheckFuture = Pin<Box<dyn Future<Output = Result<String, String>> + Send>>;
+pub type CheckFn = Box<dyn Fn() -> CheckFuture + Send + Sync>;
+
+/// Named checks run once the service is up, e.g. database reachable and migrations current.
+/// Every run logs one structured line per check; runs repeat until all checks pass, and the
+/// latest report backs `/healthz/startup`.
+#[derive(Default)]
+pub struct StartupSelfCheck {
+    checks: Vec<(String, CheckFn)>,
+    latest: Mutex<Option<StartupReport>>,
+}
+
+impl StartupSelfCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a check. `Ok` carries a short description of what was found, `Err` the reason it
+    /// failed.
+    pub fn with_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        self.checks
+            .push((name.into(), Box::new(move || Box::pin(check()))));
+        self
+    }
+
+    /// Whether the latest run passed. With no checks configured there is nothing to fail.
+    pub fn passed(&self) -> bool {
+        self.report().passed
+    }
+
+    pub fn report(&self) -> StartupReport {
+        self.latest
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| StartupReport {
+                passed: self.checks.is_empty(),
+                attempt: 0,
+                checked_at: None,
+                checks: Vec::new(),
+            })
+    }
+
+    /// Run every check once, in order, and keep the result as the latest report.
+    pub async fn run(&self) -> StartupReport {
+        let mut checks = Vec::with_capacity(self.checks.len());
+        for (name, check) in &self.checks {
+            let started = Instant::now();
+            let (passed, detail) = match tokio::time::timeout(CHECK_TIMEOUT, check()).await {
+                Ok(Ok(detail)) => (true, detail),
+                Ok(Err(reason)) => (false, reason),
+                Err(_) => (false, format!("no answer within {CHECK_TIMEOUT:?}")),
+            };
+            let latency_ms = started.elapsed().as_millis() as u64;
+            if passed {
+                tracing::info!(check = %name, passed, latency_ms, %detail, "Startup check");
+            } else {
+                tracing::warn!(check = %name, passed, latency_ms, %detail, "Startup check");
+            }
+            checks.push(StartupCheck {
+                name: name.clone(),
+                passed,
+                detail,
+                latency_ms,
+            });
+        }
+
+        let mut latest = self.latest.lock().unwrap();
+        let report = StartupReport {
+            passed: checks.iter().all(|c| c.passed),
+            attempt: latest.as_ref().map_or(0, |r| r.attempt) + 1,
+            checked_at: Some(Utc::now()),
+            checks,
+        };
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name.as_str())
+            .collect();
+        tracing::info!(
+            passed = report.passed,
+            attempt = report.attempt,
+            ?failed,
+            "Startup self-check finished"
+        );
+        *latest = Some(report.clone());
+        report
+    }
+
+    /// Run in the background, `retry` apart, until a run passes.
+    pub fn spawn(self: &Arc<Self>, retry: Duration) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            while !this.run().await.passed {
+                tokio::time::sleep(retry).await;
+            }
+        });
+    }
+}
+
+pub async fn check_database(pool: &PgPool) -> Result<String, String> {
+    let (version,): (String,) = sqlx::query_as("SELECT current_setting('server_version')")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(format!("PostgreSQL {version}"))
+}
+
+/// Every migration of this build is applied with the same checksum, and the database has
+/// none this build doesn't know about (e.g. from a newer release during a rolling deploy).
+pub async fn check_migrations(pool: &PgPool, migrator: &Migrator) -> Result<String, String> {
+    let applied: Vec<(i64, Vec<u8>, bool)> =
+        sqlx::query_as("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let applied: HashMap<i64, (Vec<u8>, bool)> = applied
+        .into_iter()
+        .map(|(version, checksum, success)| (version, (checksum, success)))
+        .collect();
+
+    let mut problems = Vec::new();
+    let known: Vec<_> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .collect();
+    for migration in &known {
+        match applied.get(&migration.version) {
+            None => problems.push(format!("{} is pending", migration.version)),
+            Some((_, false)) => problems.push(format!("{} failed", migration.version)),
+            Some((checksum, _)) if *checksum != *migration.checksum => {
+                problems.push(format!("{} was modified after it ran", migration.version));
+            }
+            Some(_) => {}
+        }
+    }
+    let mut unknown: Vec<i64> = applied
+        .keys()
+        .filter(|v| !known.iter().any(|m| m.version == **v))
+        .copied()
+        .collect();
+    unknown.sort_unstable();
+    if !unknown.is_empty() {
+        problems.push(format!("{unknown:?} are not part of this build"));
+    }
+
+    if !problems.is_empty() {
+        return Err(format!("migrations {}", problems.join(", ")));
+    }
+    let latest = known.iter().map(|m| m.version).max().unwrap_or_default();
+    Ok(format!("{} applied, latest {latest}", known.len()))
+}
@@ -1,8 +1,22 @@
+use opentelemetry::Context;
+use opentelemetry::baggage::{BaggageExt, KeyValueMetadata};
+use opentelemetry::propagation::{Extractor, TextMapCompositePropagator};
 use opentelemetry::trace::TracerProvider;
 use opentelemetry_otlp::WithExportConfig;
-use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Request header a caller can name its tenant with, when it doesn't send OTel baggage.
+pub const TENANT_ID_HEADER: &str = "x-tenant-id";
+/// Baggage key, and span attribute, of the tenant a request or job runs for.
+pub const TENANT_ID_KEY: &str = "tenant.id";
+/// Baggage key, and span attribute, of the schedule job being processed.
+pub const JOB_ID_KEY: &str = "job.id";
+
+/// Baggage entries copied onto spans, so the collector can filter on them.
+const SPAN_BAGGAGE_KEYS: [&str; 2] = [TENANT_ID_KEY, JOB_ID_KEY];
+
 pub struct TelemetryGuard {
     provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
 }
@@ -18,7 +32,10 @@ impl Drop for TelemetryGuard {
 }
 
 pub fn init_telemetry(service_name: &str) -> TelemetryGuard {
-    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(BaggagePropagator::new()),
+    ]));
 
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
@@ -57,6 +74,69 @@ pub fn init_telemetry(service_name: &str) -> TelemetryGuard {
     }
 }
 
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(http::HeaderName::as_str).collect()
+    }
+}
+
+/// Span for an incoming request, for `TraceLayer::make_span_with`. It continues the caller's
+/// trace and baggage; `x-tenant-id` fills in the tenant when the baggage has none.
+pub fn make_request_span<B>(request: &http::Request<B>) -> tracing::Span {
+    let headers = request.headers();
+    let mut cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    });
+    if cx.baggage().get(TENANT_ID_KEY).is_none()
+        && let Some(tenant) = headers.get(TENANT_ID_HEADER).and_then(|v| v.to_str().ok())
+    {
+        cx = with_entries(&cx, [(TENANT_ID_KEY, tenant.to_string())]);
+    }
+
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+    );
+    attach(&span, cx);
+    span
+}
+
+/// Give a span that hasn't been entered yet the current context plus `entries` as baggage.
+/// Its children, and requests made while it is entered, carry them on.
+pub fn attach_baggage<const N: usize>(span: &tracing::Span, entries: [(&'static str, String); N]) {
+    let cx = with_entries(&tracing::Span::current().context(), entries);
+    attach(span, cx);
+}
+
+fn with_entries<const N: usize>(cx: &Context, entries: [(&'static str, String); N]) -> Context {
+    let existing = cx.baggage().iter().map(|(key, (value, metadata))| {
+        KeyValueMetadata::new(key.clone(), value.clone(), metadata.clone())
+    });
+    // Later entries win, so `entries` replace existing ones with the same key
+    let added = entries
+        .into_iter()
+        .map(|(key, value)| KeyValueMetadata::new(key, value, ""));
+    cx.with_baggage(existing.chain(added).collect::<Vec<_>>())
+}
+
+fn attach(span: &tracing::Span, cx: Context) {
+    for key in SPAN_BAGGAGE_KEYS {
+        if let Some(value) = cx.baggage().get(key) {
+            span.set_attribute(key, value.to_string());
+        }
+    }
+    // Fails when no OTel layer is installed, and then there is nothing to propagate anyway
+    let _ = span.set_parent(cx);
+}
+
 fn build_otel_layer<S>(
     service_name: &str,
     endpoint: &str,
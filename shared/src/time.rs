@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, Utc};
+use chrono::{Datelike, Days, NaiveDate, Utc};
 use chrono_tz::Tz;
 
 /// Return the date in given timezone
@@ -14,3 +14,17 @@ use chrono_tz::Tz;
 pub fn today_in(timezone: Tz) -> NaiveDate {
     Utc::now().with_timezone(&timezone).date_naive()
 }
+
+/// The first Monday on or after `date`.
+///
+/// # Example
+///```
+/// use chrono::NaiveDate;
+/// use shared::time::monday_on_or_after;
+/// let saturday = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+/// assert_eq!(monday_on_or_after(saturday), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+/// ```
+pub fn monday_on_or_after(date: NaiveDate) -> NaiveDate {
+    let days_ahead = (7 - date.weekday().num_days_from_monday()) % 7;
+    date + Days::new(days_ahead.into())
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::{FromRow, Type};
@@ -14,15 +16,94 @@ pub enum StaffStatus {
     Inactive,
 }
 
+/// Working shifts a staff member can be scheduled on. Days off are always allowed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "shift_eligibility", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ShiftEligibility {
+    #[default]
+    Any,
+    MorningOnly,
+    EveningOnly,
+}
+
+impl ShiftEligibility {
+    pub fn allows(&self, shift: &ShiftType) -> bool {
+        !matches!(
+            (self, shift),
+            (Self::MorningOnly, ShiftType::Evening | ShiftType::Night)
+                | (Self::EveningOnly, ShiftType::Morning | ShiftType::Night)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Staff {
     pub id: Uuid,
     pub name: String,
     pub email: String,
     pub position: String,
+    /// Seniority / FTE weight used by the scheduler's fairness preferences, 1.0 is the
+    /// baseline.
+    pub fairness_weight: f64,
+    /// Shifts the staff member can be given at all, e.g. because of a medical restriction
+    #[serde(default)]
+    pub shift_eligibility: ShiftEligibility,
     pub status: StaffStatus,
+    /// Last day the staff member works, e.g. a notice period's end. They aren't scheduled
+    /// after it and are set `Inactive` once it has passed.
+    #[serde(default)]
+    pub effective_until: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Bumped on every update, and sent as the ETag of single-staff responses
+    #[serde(default)]
+    pub version: i32,
+}
+
+/// A staff member as listed among a group's members.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GroupMember {
+    #[serde(flatten)]
+    pub staff: Staff,
+    /// First day in the group when the membership was added ahead of it. For resolved
+    /// members, the earliest of their memberships in the subtree.
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+}
+
+impl From<Staff> for GroupMember {
+    fn from(staff: Staff) -> Self {
+        Self {
+            staff,
+            valid_from: None,
+        }
+    }
+}
+
+/// Resolved members of a group sharing a position.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PositionBucket {
+    pub position: String,
+    pub count: i64,
+    /// Ordered by name then id, empty with `count_only`
+    pub members: Vec<Staff>,
+}
+
+/// Display details of a staff member, for embedding in other services' responses.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct StaffSummary {
+    pub name: String,
+    pub position: String,
+}
+
+impl From<&Staff> for StaffSummary {
+    fn from(staff: &Staff) -> Self {
+        Self {
+            name: staff.name.clone(),
+            position: staff.position.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -32,12 +113,73 @@ pub struct StaffGroup {
     pub parent_group_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Bumped on every update, and sent as the ETag of single-group responses
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct GroupMembership {
     pub staff_id: Uuid,
     pub group_id: Uuid,
+    /// First day of the membership, `None` when it applied from the start
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+}
+
+/// A date range in which at most `max_off_percent` of a group's staff may have a day off on
+/// any single day, e.g. around public holidays.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct GroupBlackout {
+    pub id: Uuid,
+    pub group_id: Uuid,
+    /// First day of the window, inclusive
+    pub start_date: NaiveDate,
+    /// Last day of the window, inclusive
+    pub end_date: NaiveDate,
+    pub max_off_percent: i16,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GroupBlackout {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.start_date..=self.end_date).contains(&date)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "time_off_status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeOffStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Days a staff member asked to have off. Approved requests are days off in every schedule
+/// generated for them.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TimeOffRequest {
+    pub id: Uuid,
+    pub staff_id: Uuid,
+    /// First day off, inclusive
+    pub start_date: NaiveDate,
+    /// Last day off, inclusive
+    pub end_date: NaiveDate,
+    pub reason: Option<String>,
+    pub status: TimeOffStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TimeOffRequest {
+    /// Days of the request within `from..=to`.
+    pub fn days_within(&self, from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+        let start = self.start_date.max(from);
+        let end = self.end_date.min(to);
+        start.iter_days().take_while(move |day| *day <= end)
+    }
 }
 
 // endregion: Data Service Types
@@ -54,23 +196,66 @@ pub enum JobStatus {
     Failed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type, ToSchema)]
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Processing => "PROCESSING",
+            Self::Completed => "COMPLETED",
+            Self::Failed => "FAILED",
+        }
+    }
+}
+
+/// Ordered as a day runs, with `DayOff` last.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Type, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[sqlx(type_name = "shift_type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ShiftType {
     Morning,
     Evening,
+    /// Only generated when the scheduler's `night_shift` is on
+    Night,
     DayOff,
 }
 
+impl ShiftType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Morning => "MORNING",
+            Self::Evening => "EVENING",
+            Self::Night => "NIGHT",
+            Self::DayOff => "DAY_OFF",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ScheduleJob {
     pub id: Uuid,
     pub staff_group_id: Uuid,
     pub period_begin_date: NaiveDate,
     pub status: JobStatus,
+    /// SHA-256 of the assignment set, set when the job completes.
+    pub result_checksum: Option<String>,
+    /// Jobs that must complete before this one is started.
+    pub depends_on: Vec<Uuid>,
+    /// Test run: kept out of calendars, period planning and the failed-job list, can't be
+    /// shared, sends no notifications and is purged after `sandbox_retention_hours`.
+    pub sandbox: bool,
+    /// Consecutive 28-day periods generated from `period_begin_date`, 1 for a single period.
+    pub periods: i16,
+    /// Staff needed on MORNING every day, when the job asked for its own coverage.
+    pub min_morning: Option<i16>,
+    /// Staff needed on EVENING every day, set together with `min_morning`.
+    pub min_evening: Option<i16>,
+    /// Why the job failed, while it is `FAILED`.
+    pub error_message: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Bumped on every update, e.g. each status change.
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -80,14 +265,141 @@ pub struct ShiftAssignment {
     pub staff_id: Uuid,
     pub date: NaiveDate,
     pub shift_type: ShiftType,
+    pub note: Option<String>,
+}
+
+/// Period-level note on a schedule, such as a handover note for the group.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScheduleNote {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub author: String,
+    /// Markdown, stored and returned as written
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How evenly a schedule shares out the less popular days.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleMetrics {
+    /// Saturdays and Sundays off per staff member
+    pub weekend_days_off: HashMap<Uuid, u32>,
+    /// The weighting formula the generator applies to weekend days off
+    pub weekend_weighting: String,
+    /// Staff with a Morning:Evening target, and how close they came to it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub shift_targets: HashMap<Uuid, ShiftTargetDeviation>,
+}
+
+/// A staff member's working shifts against their Morning:Evening target.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ShiftTargetDeviation {
+    /// Share of working shifts meant to be MORNING, 0 to 1
+    pub target_morning_share: f64,
+    pub morning_shifts: u32,
+    pub evening_shifts: u32,
+    /// Actual MORNING share minus the target, 0 without working shifts
+    pub deviation: f64,
+}
+
+/// One 28-day section of a schedule covering several periods.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SchedulePeriod {
+    pub period_begin_date: NaiveDate,
+    /// Last day of the period, inclusive
+    pub period_end_date: NaiveDate,
+    /// Number of assignments dated within the period
+    pub assignments: usize,
+    /// Metrics over this period alone
+    pub metrics: ScheduleMetrics,
+}
+
+/// A staff member from the float pool group who was scheduled with a group that couldn't
+/// meet its coverage with its own staff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BorrowedStaff {
+    pub staff_id: Uuid,
+    pub pool_group_id: Uuid,
+}
+
+/// A shift short of a coverage minimum on one day, in a schedule generated after the
+/// minimums couldn't all be met.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CoverageShortfall {
+    pub date: NaiveDate,
+    pub shift_type: ShiftType,
+    /// The position with the minimum, `None` for the group-wide shift coverage
+    pub position: Option<String>,
+    pub required: u32,
+    pub assigned: u32,
+    /// The missing staff are listed in `ScheduleResult::unfilled` as well
+    pub unfilled: bool,
+}
+
+/// A placeholder for one person a shift is missing, to be filled by hand (e.g. with agency
+/// staff). Stored apart from the assignments, since nobody is assigned yet.
+/// The assignments of one staff member or one date.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentGroup {
+    /// Set when grouped by staff
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staff_id: Option<Uuid>,
+    /// Set when grouped by date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    pub assignments: Vec<ShiftAssignment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct UnfilledShift {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub date: NaiveDate,
+    pub shift_type: ShiftType,
+    /// Set when the slot needs a particular position
+    pub position: Option<String>,
 }
 
+/// Layout of `ScheduleResult` produced by default. Bumped whenever a field is removed,
+/// renamed or changes meaning; adding a field doesn't need a new version.
+pub const RESULT_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ScheduleResult {
+    /// Layout of this payload, see `RESULT_SCHEMA_VERSION`
+    pub schema_version: u32,
     pub schedule_id: Uuid,
     pub period_begin_date: NaiveDate,
     pub staff_group_id: Uuid,
+    /// SHA-256 (hex) of the assignment set, see `checksum` in the scheduling-service.
+    /// `None` for results generated before checksums were recorded.
+    pub checksum: Option<String>,
     pub assignments: Vec<ShiftAssignment>,
+    /// Schedule notes, oldest first.
+    #[serde(default)]
+    pub notes: Vec<ScheduleNote>,
+    #[serde(default)]
+    pub metrics: ScheduleMetrics,
+    /// Per-period sections, oldest first, when the schedule covers more than one period
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub periods: Vec<SchedulePeriod>,
+    /// Staff borrowed from the float pool; their assignments are in `assignments` like
+    /// everyone else's.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub borrowed_staff: Vec<BorrowedStaff>,
+    /// Coverage minimums the schedule falls short of, by date
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coverage_shortfalls: Vec<CoverageShortfall>,
+    /// One placeholder per missing person, by date, when shortfalls are left unfilled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unfilled: Vec<UnfilledShift>,
+    /// The assignments split by staff member or date, only present with `?group_by=`;
+    /// `assignments` is empty then.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignment_groups: Vec<AssignmentGroup>,
+    /// Staff details keyed by staff id, only present with `?include=staff`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staff: Option<HashMap<Uuid, StaffSummary>>,
 }
 
 // endregion: Scheduling Service Types
@@ -0,0 +1,49 @@
+//! Workspace tasks, run with `cargo xtask <task>`.
+
+mod typescript;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cargo xtask <task>
+
+Tasks:
+  ts-types           Write the TypeScript definitions to clients/typescript/index.d.ts
+  ts-types --check   Fail if the committed definitions are out of date";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["ts-types"] => ts_types(false),
+        ["ts-types", "--check"] => ts_types(true),
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn ts_types(check: bool) -> ExitCode {
+    let path = typescript::output_path();
+    let rendered = typescript::render(&typescript::declarations());
+
+    if check {
+        let committed = std::fs::read_to_string(&path).unwrap_or_default();
+        if committed != rendered {
+            eprintln!(
+                "{} is out of date, run `cargo xtask ts-types`",
+                path.display()
+            );
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let Err(e) = std::fs::write(&path, rendered) {
+        eprintln!("Failed to write {}: {e}", path.display());
+        return ExitCode::FAILURE;
+    }
+    println!("Wrote {}", path.display());
+    ExitCode::SUCCESS
+}
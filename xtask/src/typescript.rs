@@ -0,0 +1,315 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use shared::{health, pagination, responses, startup, types};
+use utoipa::openapi::{Ref, RefOr, schema::Schema};
+use utoipa::{PartialSchema, ToSchema};
+
+/// The generated file of the `clients/typescript` package.
+pub fn output_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the workspace root")
+        .join("clients/typescript/index.d.ts")
+}
+
+/// Stands in for the type parameter of generic envelopes, so their schemas reference `T`
+/// instead of a concrete type.
+struct TypeParam;
+
+impl PartialSchema for TypeParam {
+    fn schema() -> RefOr<Schema> {
+        RefOr::Ref(Ref::from_schema_name(Self::name()))
+    }
+}
+
+impl ToSchema for TypeParam {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("T")
+    }
+}
+
+impl serde::Serialize for TypeParam {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+/// A named schema to emit, with the type parameters its name takes.
+pub struct Declaration {
+    pub name: String,
+    pub params: &'static [&'static str],
+    pub schema: Value,
+}
+
+fn declare<T: ToSchema>() -> Declaration {
+    declare_generic::<T>(&[])
+}
+
+fn declare_generic<T: ToSchema>(params: &'static [&'static str]) -> Declaration {
+    Declaration {
+        name: T::name().into_owned(),
+        params,
+        schema: serde_json::to_value(T::schema()).expect("schemas serialize to JSON"),
+    }
+}
+
+/// Every API model in `shared`, in output order. Types added there need a line here; the
+/// tests fail on references to anything missing.
+pub fn declarations() -> Vec<Declaration> {
+    vec![
+        declare_generic::<responses::ApiResponse<TypeParam>>(&["T"]),
+        declare::<responses::EmptyApiResponse>(),
+        declare::<responses::HeadpatResponse>(),
+        declare_generic::<pagination::Page<TypeParam>>(&["T"]),
+        declare::<pagination::SortOrder>(),
+        declare::<types::StaffStatus>(),
+        declare::<types::ShiftEligibility>(),
+        declare::<types::Staff>(),
+        declare::<types::GroupMember>(),
+        declare::<types::PositionBucket>(),
+        declare::<types::StaffSummary>(),
+        declare::<types::StaffGroup>(),
+        declare::<types::GroupMembership>(),
+        declare::<types::GroupBlackout>(),
+        declare::<types::TimeOffStatus>(),
+        declare::<types::TimeOffRequest>(),
+        declare::<types::JobStatus>(),
+        declare::<types::ShiftType>(),
+        declare::<types::ScheduleJob>(),
+        declare::<types::ShiftAssignment>(),
+        declare::<types::ScheduleNote>(),
+        declare::<types::ScheduleMetrics>(),
+        declare::<types::ShiftTargetDeviation>(),
+        declare::<types::SchedulePeriod>(),
+        declare::<types::BorrowedStaff>(),
+        declare::<types::CoverageShortfall>(),
+        declare::<types::UnfilledShift>(),
+        declare::<types::AssignmentGroup>(),
+        declare::<types::ScheduleResult>(),
+        declare::<startup::StartupCheck>(),
+        declare::<startup::StartupReport>(),
+        declare::<health::DependencyStatus>(),
+        declare::<health::DependencyReport>(),
+    ]
+}
+
+/// The whole `index.d.ts`.
+pub fn render(declarations: &[Declaration]) -> String {
+    let mut out = String::from(
+        "// Generated by `cargo xtask ts-types` from the OpenAPI schemas of the `shared` crate.\n\
+         // Do not edit by hand.\n",
+    );
+    for declaration in declarations {
+        out.push('\n');
+        render_declaration(&mut out, declaration);
+    }
+    out
+}
+
+fn render_declaration(out: &mut String, declaration: &Declaration) {
+    let schema = &declaration.schema;
+    write_doc(out, schema, "");
+    let name = if declaration.params.is_empty() {
+        declaration.name.clone()
+    } else {
+        format!("{}<{}>", declaration.name, declaration.params.join(", "))
+    };
+    match schema.get("properties").and_then(Value::as_object) {
+        Some(properties) => {
+            let required = required_fields(schema);
+            let _ = writeln!(out, "export interface {name} {{");
+            for (field, property) in properties {
+                write_doc(out, property, "  ");
+                let optional = if required.contains(&field.as_str()) {
+                    ""
+                } else {
+                    "?"
+                };
+                let _ = writeln!(
+                    out,
+                    "  {}{optional}: {};",
+                    property_name(field),
+                    ts_type(property)
+                );
+            }
+            out.push_str("}\n");
+        }
+        None => {
+            let _ = writeln!(out, "export type {name} = {};", ts_type(schema));
+        }
+    }
+}
+
+fn required_fields(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn write_doc(out: &mut String, schema: &Value, indent: &str) {
+    let Some(description) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let lines: Vec<&str> = description.lines().collect();
+    if let [line] = lines.as_slice() {
+        let _ = writeln!(out, "{indent}/** {line} */");
+        return;
+    }
+    let _ = writeln!(out, "{indent}/**");
+    for line in lines {
+        let _ = writeln!(out, "{indent} * {line}");
+    }
+    let _ = writeln!(out, "{indent} */");
+}
+
+fn property_name(name: &str) -> Cow<'_, str> {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if identifier {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(Value::from(name).to_string())
+    }
+}
+
+/// TypeScript for a schema, in the subset of JSON Schema utoipa produces.
+pub fn ts_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    for (keyword, separator) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+            let mut members: Vec<String> =
+                schemas.iter().map(|s| parenthesize(ts_type(s))).collect();
+            // `T | null` reads better than utoipa's `null` first
+            members.sort_by_key(|member| member == "null");
+            return members.join(separator);
+        }
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string(),
+    };
+    types
+        .into_iter()
+        .map(|t| match t {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let items = schema.get("items").map_or("unknown".to_string(), ts_type);
+                format!("{}[]", parenthesize(items))
+            }
+            "object" => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => format!("Record<string, {}>", ts_type(values)),
+                _ => "Record<string, unknown>".to_string(),
+            },
+            _ => "unknown".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn parenthesize(ts: String) -> String {
+    if ts.contains(" | ") || ts.contains(" & ") {
+        format!("({ts})")
+    } else {
+        ts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn schemas_map_to_typescript() {
+        assert_eq!(
+            ts_type(&json!({ "type": ["string", "null"] })),
+            "string | null"
+        );
+        assert_eq!(
+            ts_type(
+                &json!({ "oneOf": [{ "type": "null" }, { "$ref": "#/components/schemas/T" }] })
+            ),
+            "T | null"
+        );
+        assert_eq!(
+            ts_type(&json!({ "type": "array", "items": { "type": ["integer", "null"] } })),
+            "(number | null)[]"
+        );
+        assert_eq!(
+            ts_type(&json!({
+                "type": ["object", "null"],
+                "additionalProperties": { "$ref": "#/components/schemas/StaffSummary" }
+            })),
+            "Record<string, StaffSummary> | null"
+        );
+        assert_eq!(
+            ts_type(&json!({ "type": "string", "enum": ["ACTIVE", "INACTIVE"] })),
+            r#""ACTIVE" | "INACTIVE""#
+        );
+    }
+
+    fn references<'a>(schema: &'a Value, found: &mut Vec<&'a str>) {
+        match schema {
+            Value::Object(fields) => {
+                if let Some(reference) = fields.get("$ref").and_then(Value::as_str) {
+                    found.push(reference.rsplit('/').next().unwrap_or(reference));
+                }
+                fields.values().for_each(|v| references(v, found));
+            }
+            Value::Array(values) => values.iter().for_each(|v| references(v, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn generated_package_is_up_to_date() {
+        let declarations = declarations();
+        for declaration in &declarations {
+            let mut found = Vec::new();
+            references(&declaration.schema, &mut found);
+            for name in found {
+                assert!(
+                    declaration.params.contains(&name)
+                        || declarations.iter().any(|d| d.name == name),
+                    "{} references {name}, which needs adding to declarations()",
+                    declaration.name
+                );
+            }
+        }
+
+        let path = output_path();
+        let committed = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            committed == render(&declarations),
+            "{} is out of date, run `cargo xtask ts-types`",
+            path.display()
+        );
+    }
+}